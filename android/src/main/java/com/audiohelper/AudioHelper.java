@@ -0,0 +1,30 @@
+package com.audiohelper;
+
+/**
+ * audio_helper 的 JNI 入口，需要以 --features jni 编译原生库。
+ * 失败时抛出 {@link AudioHelperException}，错误码与 C 接口的 CErrorCode 相同。
+ */
+public final class AudioHelper {
+    static {
+        System.loadLibrary("audio_helper");
+    }
+
+    private AudioHelper() {
+    }
+
+    /** PCM 转 WAV */
+    public static native void pcmToWav(String inputPath, String outputPath, int sampleRate, int channels, int bitsPerSample);
+
+    /**
+     * PCM 转 MP3
+     * @param bitrateKbps 64、128、192、256 或 320
+     * @param quality 0=低, 1=中, 2=高, 3=最佳
+     */
+    public static native void pcmToMp3(String inputPath, String outputPath, int sampleRate, int channels, int bitrateKbps, int quality);
+
+    /** 读取文件信息，原始 PCM 的参数从文件名推断 */
+    public static native AudioInfo probe(String path);
+
+    /** 库版本 */
+    public static native String getVersion();
+}
@@ -0,0 +1,28 @@
+package com.audiohelper;
+
+/**
+ * 原生转换失败时抛出的异常
+ */
+public class AudioHelperException extends RuntimeException {
+    public static final int UNKNOWN = -1;
+    public static final int INVALID_ARGUMENT = -2;
+    public static final int INVALID_PATH = -3;
+    public static final int FILE_NOT_FOUND = -4;
+    public static final int PERMISSION_DENIED = -5;
+    public static final int IO_ERROR = -6;
+    public static final int UNSUPPORTED_CONFIG = -7;
+    public static final int ENCODER_UNAVAILABLE = -8;
+    public static final int CANCELLED = -9;
+
+    private final int code;
+
+    public AudioHelperException(int code, String message) {
+        super(message);
+        this.code = code;
+    }
+
+    /** 错误码，与 C 接口的 CErrorCode 相同 */
+    public int getCode() {
+        return code;
+    }
+}
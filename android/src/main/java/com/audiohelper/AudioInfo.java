@@ -0,0 +1,31 @@
+package com.audiohelper;
+
+/**
+ * 音频文件信息，无法得到的字段为 0
+ */
+public final class AudioInfo {
+    /** 按内容识别出的格式：pcm、wav、mp3、flac 或 ogg */
+    public final String format;
+    public final int sampleRate;
+    public final int channels;
+    public final int bitsPerSample;
+    public final long durationMs;
+    /** 音频数据字节数：WAV 为 data 块大小，其余格式为文件大小 */
+    public final long dataSize;
+    public final long fileSize;
+
+    public AudioInfo(String format, int sampleRate, int channels, int bitsPerSample, long durationMs, long dataSize, long fileSize) {
+        this.format = format;
+        this.sampleRate = sampleRate;
+        this.channels = channels;
+        this.bitsPerSample = bitsPerSample;
+        this.durationMs = durationMs;
+        this.dataSize = dataSize;
+        this.fileSize = fileSize;
+    }
+
+    @Override
+    public String toString() {
+        return format + " " + sampleRate + "Hz " + channels + "ch " + bitsPerSample + "bit " + durationMs + "ms";
+    }
+}
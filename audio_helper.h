@@ -1,6 +1,7 @@
 #ifndef AUDIO_HELPER_H
 #define AUDIO_HELPER_H
 
+#include <stddef.h>
 #include <stdint.h>
 
 #ifdef __cplusplus
@@ -36,6 +37,72 @@ typedef enum {
     AUDIO_FORMAT_MP3 = 1       // MP3 格式
 } CAudioFormat;
 
+/**
+ * 转换所处的阶段
+ */
+typedef enum {
+    AH_STAGE_READING = 0,      // 读取输入文件
+    AH_STAGE_PROCESSING = 1,   // 样本处理
+    AH_STAGE_ENCODING = 2,     // 编码为输出格式
+    AH_STAGE_WRITING = 3       // 写入输出文件
+} CConvertStage;
+
+/**
+ * 按内容识别出的文件格式
+ */
+typedef enum {
+    AH_FORMAT_PCM = 0,         // 没有可识别的文件头，按原始 PCM 处理
+    AH_FORMAT_WAV = 1,
+    AH_FORMAT_MP3 = 2,
+    AH_FORMAT_FLAC = 3,
+    AH_FORMAT_OGG = 4,
+    AH_FORMAT_REGISTERED = 5   // 由 Rust 端 FormatRegistry 中注册的处理器识别
+} CDetectedFormat;
+
+/**
+ * 音频文件信息，无法得到的字段为 0
+ */
+typedef struct {
+    CDetectedFormat format;    // 文件格式
+    uint32_t sample_rate;      // 采样率 (Hz)
+    uint16_t channels;         // 声道数
+    uint16_t bits_per_sample;  // 位深度
+    uint64_t duration_ms;      // 时长（毫秒）
+    uint64_t data_size;        // 音频数据字节数（WAV 为 data 块大小，其余格式为文件大小）
+    uint64_t file_size;        // 文件字节数
+} CAudioInfo;
+
+/**
+ * 函数返回码：0 表示成功，负数表示失败的类别，详细信息通过 get_last_error 获取
+ */
+typedef enum {
+    AH_OK = 0,                     // 成功
+    AH_ERROR_UNKNOWN = -1,         // 未归类的错误
+    AH_ERROR_INVALID_ARGUMENT = -2, // 参数为空指针或取值无效（如不存在的预设名称、输入不是 PCM 文件）
+    AH_ERROR_INVALID_PATH = -3,    // 路径或字符串参数不是有效的 UTF-8
+    AH_ERROR_FILE_NOT_FOUND = -4,  // 输入文件或输出目录不存在
+    AH_ERROR_PERMISSION_DENIED = -5, // 没有读写权限
+    AH_ERROR_IO = -6,              // 其他读写错误（如磁盘已满）
+    AH_ERROR_UNSUPPORTED_CONFIG = -7, // 音频参数不受支持或组合无效，或输入格式不受支持
+    AH_ERROR_ENCODER_UNAVAILABLE = -8, // 无法创建或配置 MP3 编码器
+    AH_ERROR_CANCELLED = -9        // 转换被取消
+} CErrorCode;
+
+/**
+ * 流式 MP3 编码句柄（不透明类型，只能通过指针使用）
+ */
+typedef struct AH_EncoderHandle AH_EncoderHandle;
+
+/**
+ * 进度回调，在发起转换的线程中同步执行
+ * @param stage 当前阶段
+ * @param bytes_processed 当前阶段已处理的字节数
+ * @param total_bytes 当前阶段的总字节数
+ * @param user_data 调用转换函数时传入的指针
+ * @return 0 继续转换，非 0 取消转换（转换函数返回 AH_ERROR_CANCELLED）
+ */
+typedef int (*CProgressCallback)(CConvertStage stage, uint64_t bytes_processed, uint64_t total_bytes, void* user_data);
+
 // ==================== 核心转换函数 ====================
 
 /**
@@ -43,27 +110,181 @@ typedef enum {
  * @param input_path 输入 PCM 文件路径
  * @param output_path 输出 WAV 文件路径
  * @param config PCM 配置，可以为 NULL 使用默认配置
- * @return 0 成功，-1 失败
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
  */
-int pcm_to_wav(const char* input_path, const char* output_path, const CPcmConfig* config);
+CErrorCode pcm_to_wav(const char* input_path, const char* output_path, const CPcmConfig* config);
 
 /**
  * PCM 转 MP3
  * @param input_path 输入 PCM 文件路径
  * @param output_path 输出 MP3 文件路径
  * @param config MP3 配置，可以为 NULL 使用默认配置
- * @return 0 成功，-1 失败
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode pcm_to_mp3(const char* input_path, const char* output_path, const CMp3Config* config);
+
+/**
+ * PCM 转 WAV，并通过回调报告进度
+ * @param callback 进度回调，可以为 NULL
+ * @param user_data 原样传给回调的指针
+ * @return AH_OK 成功，AH_ERROR_CANCELLED 被回调取消（已有的输出文件保持不变），其他负数为错误码
+ */
+CErrorCode pcm_to_wav_with_progress(const char* input_path, const char* output_path, const CPcmConfig* config,
+                                    CProgressCallback callback, void* user_data);
+
+/**
+ * PCM 转 MP3，并通过回调报告进度
+ * @param callback 进度回调，可以为 NULL
+ * @param user_data 原样传给回调的指针
+ * @return AH_OK 成功，AH_ERROR_CANCELLED 被回调取消（已有的输出文件保持不变），其他负数为错误码
+ */
+CErrorCode pcm_to_mp3_with_progress(const char* input_path, const char* output_path, const CMp3Config* config,
+                                    CProgressCallback callback, void* user_data);
+
+/**
+ * 按预设将 PCM 转为 MP3（从文件名推断输入参数）
+ * @param input_path 输入 PCM 文件路径
+ * @param output_path 输出 MP3 文件路径
+ * @param preset_name 预设名称：voice、music、podcast、archive 或已注册的自定义预设
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
  */
-int pcm_to_mp3(const char* input_path, const char* output_path, const CMp3Config* config);
+CErrorCode pcm_to_mp3_preset(const char* input_path, const char* output_path, const char* preset_name);
 
 /**
  * 智能自动转换 PCM 到指定格式（从文件名推断配置）
  * @param input_path 输入 PCM 文件路径
  * @param output_path 输出文件路径
  * @param format 输出格式 (AUDIO_FORMAT_WAV 或 AUDIO_FORMAT_MP3)
- * @return 0 成功，-1 失败
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode auto_convert_audio(const char* input_path, const char* output_path, CAudioFormat format);
+
+// ==================== 宽字符路径 ====================
+// 其他函数的 char* 路径一律按 UTF-8 解释；Windows 宿主可改用以下 _w 版本直接传入 wchar_t 路径，
+// 避免经本地代码页转换后损坏中文等非 ASCII 目录名（wchar_t 在 Windows 上为 UTF-16，其他平台为 UTF-32）
+
+/**
+ * PCM 转 WAV（宽字符路径）
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode pcm_to_wav_w(const wchar_t* input_path, const wchar_t* output_path, const CPcmConfig* config);
+
+/**
+ * PCM 转 MP3（宽字符路径）
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode pcm_to_mp3_w(const wchar_t* input_path, const wchar_t* output_path, const CMp3Config* config);
+
+/**
+ * 智能自动转换（宽字符路径，从文件名推断配置）
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
  */
-int auto_convert_audio(const char* input_path, const char* output_path, CAudioFormat format);
+CErrorCode auto_convert_audio_w(const wchar_t* input_path, const wchar_t* output_path, CAudioFormat format);
+
+// ==================== 内存缓冲区转换 ====================
+
+/**
+ * 将内存中的 PCM 数据转换为 WAV（不写入临时文件）
+ * @param pcm_data PCM 数据，pcm_len 为 0 时可以为 NULL
+ * @param pcm_len PCM 数据的字节数
+ * @param config PCM 配置，可以为 NULL 使用默认配置
+ * @param out 接收 WAV 数据，成功后需要调用 audio_helper_free_buffer 释放；失败时为 NULL
+ * @param out_len 接收 WAV 数据的字节数，失败时为 0
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode pcm_buffer_to_wav_buffer(const uint8_t* pcm_data, size_t pcm_len, const CPcmConfig* config,
+                                    uint8_t** out, size_t* out_len);
+
+/**
+ * 将内存中的 PCM 数据编码为 MP3（不写入临时文件）
+ * @param pcm_data PCM 数据，pcm_len 为 0 时可以为 NULL
+ * @param pcm_len PCM 数据的字节数
+ * @param config MP3 配置，可以为 NULL 使用默认配置
+ * @param out 接收 MP3 数据，成功后需要调用 audio_helper_free_buffer 释放；失败时为 NULL
+ * @param out_len 接收 MP3 数据的字节数，失败时为 0
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode pcm_buffer_to_mp3_buffer(const uint8_t* pcm_data, size_t pcm_len, const CMp3Config* config,
+                                    uint8_t** out, size_t* out_len);
+
+/**
+ * 将内存中的交错 16 位样本转换为 WAV，直接读取样本数组（如 AudioRecord 读到的 short[]），不需要先转换为字节
+ * @param samples 交错的 int16_t 样本（本机字节序），count 为 0 时可以为 NULL
+ * @param count 样本总数（所有声道合计，不是帧数）
+ * @param config PCM 配置，可以为 NULL 使用默认配置；bits_per_sample 被忽略，总是 16 位
+ * @param out 接收 WAV 数据，成功后需要调用 audio_helper_free_buffer 释放；失败时为 NULL
+ * @param out_len 接收 WAV 数据的字节数，失败时为 0
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode pcm_samples_to_wav_buffer(const int16_t* samples, size_t count, const CPcmConfig* config,
+                                     uint8_t** out, size_t* out_len);
+
+/**
+ * 将内存中的交错 16 位样本编码为 MP3，不需要样本处理时直接编码样本数组
+ * @param samples 交错的 int16_t 样本（本机字节序），count 为 0 时可以为 NULL
+ * @param count 样本总数（所有声道合计，不是帧数）
+ * @param config MP3 配置，可以为 NULL 使用默认配置
+ * @param out 接收 MP3 数据，成功后需要调用 audio_helper_free_buffer 释放；失败时为 NULL
+ * @param out_len 接收 MP3 数据的字节数，失败时为 0
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode pcm_samples_to_mp3_buffer(const int16_t* samples, size_t count, const CMp3Config* config,
+                                     uint8_t** out, size_t* out_len);
+
+/**
+ * 释放缓冲区转换函数返回的数据
+ * @param buffer 数据指针，可以为 NULL
+ * @param len 同一次调用返回的字节数
+ */
+void audio_helper_free_buffer(uint8_t* buffer, size_t len);
+
+// ==================== 流式编码 ====================
+
+/**
+ * 创建流式 MP3 编码句柄
+ * 输入为 16 位小端交错 PCM，采样率必须是 MP3 标准采样率；码流开头不写 Xing/LAME 标签帧
+ * @param config MP3 配置，可以为 NULL 使用默认配置
+ * @return 编码句柄，使用完毕后调用 ah_encoder_close 释放；失败时返回 NULL（调用 get_last_error 获取错误信息）
+ */
+AH_EncoderHandle* ah_encoder_open(const CMp3Config* config);
+
+/**
+ * 送入一块 PCM 数据并取出编码结果
+ * 放不下的数据保留在句柄中，下次调用（len 可以为 0）时继续输出；out_cap 不小于 len * 5 / 8 + 7200 时一次即可取完
+ * @param handle 编码句柄（同一句柄不能被多个线程同时使用）
+ * @param pcm PCM 数据，len 为 0 时可以为 NULL；不足一帧的尾部会与下一块拼接
+ * @param len PCM 数据的字节数
+ * @param out 接收 MP3 数据的缓冲区
+ * @param out_cap out 的容量
+ * @return 写入 out 的字节数（可能为 0），失败时返回负数错误码（CErrorCode 的值）
+ */
+ptrdiff_t ah_encoder_feed(AH_EncoderHandle* handle, const uint8_t* pcm, size_t len, uint8_t* out, size_t out_cap);
+
+/**
+ * 送入一块交错的 16 位样本并取出编码结果，直接编码样本数组；可以与 ah_encoder_feed 交替使用（后者送入的字节数需为偶数）
+ * @param handle 编码句柄（同一句柄不能被多个线程同时使用）
+ * @param samples 交错的 int16_t 样本（本机字节序），count 为 0 时可以为 NULL；不足一帧的尾部会与下一块拼接
+ * @param count 样本总数（所有声道合计，不是帧数）
+ * @param out 接收 MP3 数据的缓冲区
+ * @param out_cap out 的容量，不小于 count * 5 / 4 / 声道数 + 7200 时一次即可取完
+ * @return 写入 out 的字节数（可能为 0），失败时返回负数错误码（CErrorCode 的值）
+ */
+ptrdiff_t ah_encoder_feed_samples(AH_EncoderHandle* handle, const int16_t* samples, size_t count, uint8_t* out, size_t out_cap);
+
+/**
+ * 结束编码并取出剩余数据，out 放不下时重复调用直到返回 0
+ * @param handle 编码句柄
+ * @param out 接收 MP3 数据的缓冲区
+ * @param out_cap out 的容量，不小于 7200 时一次即可取完
+ * @return 写入 out 的字节数，失败时返回负数错误码（CErrorCode 的值）
+ */
+ptrdiff_t ah_encoder_finish(AH_EncoderHandle* handle, uint8_t* out, size_t out_cap);
+
+/**
+ * 释放编码句柄，未取走的数据被丢弃
+ * @param handle 编码句柄，可以为 NULL
+ */
+void ah_encoder_close(AH_EncoderHandle* handle);
 
 // ==================== 辅助功能 ====================
 
@@ -71,16 +292,36 @@ int auto_convert_audio(const char* input_path, const char* output_path, CAudioFo
  * 从文件名推断音频配置
  * @param filename 文件名（支持中文和各种格式）
  * @param config 输出配置结构体指针
- * @return 0 成功，-1 失败
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
+ */
+CErrorCode infer_config_from_filename(const char* filename, CPcmConfig* config);
+
+/**
+ * 读取音频文件信息（格式、采样率、声道、位深度、时长和数据大小），用于转换前展示
+ * 原始 PCM 的参数从文件名推断；MP3、FLAC、Ogg 的采样率、声道和位深度为 0
+ * @param path 文件路径
+ * @param info 输出信息结构体指针
+ * @return AH_OK 成功，失败时返回负数错误码（调用 get_last_error 获取错误信息）
  */
-int infer_config_from_filename(const char* filename, CPcmConfig* config);
+CErrorCode probe_audio(const char* path, CAudioInfo* info);
 
 /**
- * 获取最后一次错误信息
- * @return 错误信息字符串，需要调用 free_string 释放内存
+ * 获取当前线程最近一次失败调用的错误信息（按线程保存，成功的调用不会清除）
+ * @return 错误信息字符串，需要调用 free_string 释放内存；当前线程没有失败过的调用时返回 NULL
  */
 char* get_last_error(void);
 
+/**
+ * 获取当前线程最近一次失败调用的错误码
+ * @return 与失败调用的返回值相同；没有失败过的调用时返回 AH_OK
+ */
+CErrorCode get_last_error_code(void);
+
+/**
+ * 清除当前线程保存的错误信息
+ */
+void clear_last_error(void);
+
 /**
  * 释放由库分配的字符串内存
  * @param str_ptr 要释放的字符串指针
@@ -146,25 +387,56 @@ static inline CMp3Config create_compressed_mp3_config() {
 // ==================== 使用示例 (注释) ====================
 
 /*
-// 简单的 PCM 到 WAV 转换
-if (pcm_to_wav("input.pcm", "output.wav", NULL) == 0) {
+// 简单的 PCM 到 WAV 转换，失败时获取错误信息
+if (pcm_to_wav("input.pcm", "output.wav", NULL) == AH_OK) {
     printf("转换成功!\n");
+} else {
+    char* error = get_last_error();
+    printf("转换失败 (%d): %s\n", get_last_error_code(), error ? error : "未知错误");
+    free_string(error);
 }
 
 // 使用自定义配置的 PCM 到 MP3 转换
 CMp3Config mp3_config = create_standard_mp3_config();
-if (pcm_to_mp3("input.pcm", "output.mp3", &mp3_config) == 0) {
+if (pcm_to_mp3("input.pcm", "output.mp3", &mp3_config) == AH_OK) {
     printf("MP3 转换成功!\n");
 }
 
+// 带进度的转换，user_data 用于把进度交给界面对象
+static int on_progress(CConvertStage stage, uint64_t done, uint64_t total, void* user_data) {
+    update_progress_bar((ProgressBar*)user_data, stage, total ? (double)done / total : 1.0);
+    return user_cancelled() ? 1 : 0;
+}
+pcm_to_mp3_with_progress("input.pcm", "output.mp3", &mp3_config, on_progress, progress_bar);
+
+// 内存中转换，不需要在沙盒存储中写临时文件
+uint8_t* mp3_data = NULL;
+size_t mp3_len = 0;
+if (pcm_buffer_to_mp3_buffer(pcm, pcm_len, &mp3_config, &mp3_data, &mp3_len) == AH_OK) {
+    upload(mp3_data, mp3_len);
+    audio_helper_free_buffer(mp3_data, mp3_len);
+}
+
+// 边录音边编码
+AH_EncoderHandle* encoder = ah_encoder_open(&mp3_config);
+uint8_t mp3_chunk[16384];
+while (read_microphone(pcm, &pcm_len)) {
+    ptrdiff_t written = ah_encoder_feed(encoder, pcm, pcm_len, mp3_chunk, sizeof(mp3_chunk));
+    if (written < 0) break;
+    send(mp3_chunk, written);
+}
+ptrdiff_t written = ah_encoder_finish(encoder, mp3_chunk, sizeof(mp3_chunk));
+if (written > 0) send(mp3_chunk, written);
+ah_encoder_close(encoder);
+
 // 智能自动转换（从文件名推断配置）
-if (auto_convert_audio("audio_8k16bit单声道.pcm", "output.wav", AUDIO_FORMAT_WAV) == 0) {
+if (auto_convert_audio("audio_8k16bit单声道.pcm", "output.wav", AUDIO_FORMAT_WAV) == AH_OK) {
     printf("自动转换成功!\n");
 }
 
 // 推断配置信息
 CPcmConfig inferred_config;
-if (infer_config_from_filename("test_48k16bit双声道.pcm", &inferred_config) == 0) {
+if (infer_config_from_filename("test_48k16bit双声道.pcm", &inferred_config) == AH_OK) {
     printf("检测到: %dHz, %d声道, %d位\n", 
            inferred_config.sample_rate, 
            inferred_config.channels, 
@@ -0,0 +1,95 @@
+// 样本转换基准：对比优化之前逐样本的标量实现与库中的实现（SIMD 或可自动向量化的循环）
+//
+// 运行：cargo bench --bench sample_conversion
+// 数据量默认 256 MB，可通过环境变量 AUDIO_HELPER_BENCH_MB 调整
+
+use audio_helper::{decode_samples_f32, decode_samples_i16, deinterleave_stereo, SampleFormat};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// 逐样本按格式解码为 i16（优化之前的实现，格式判断在循环内）
+fn scalar_i16(data: &[u8], format: SampleFormat) -> Vec<i16> {
+    data.chunks_exact(format.bytes_per_sample())
+        .map(|b| match format {
+            SampleFormat::S16 => i16::from_le_bytes([b[0], b[1]]),
+            SampleFormat::U8 => ((b[0] as i16) - 128) << 8,
+            _ => (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 16) as i16,
+        })
+        .collect()
+}
+
+/// 逐样本按格式解码为 f32（优化之前的实现）
+fn scalar_f32(data: &[u8], format: SampleFormat) -> Vec<f32> {
+    data.chunks_exact(format.bytes_per_sample())
+        .map(|b| match format {
+            SampleFormat::S16 => i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            SampleFormat::U8 => (b[0] as f32 - 128.0) / 128.0,
+            _ => (i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8) as f32 / 8_388_608.0,
+        })
+        .collect()
+}
+
+/// 逐帧拆分左右声道
+fn scalar_deinterleave(samples: &[i16]) -> (Vec<i16>, Vec<i16>) {
+    samples.chunks_exact(2).map(|frame| (frame[0], frame[1])).unzip()
+}
+
+/// 取 3 次运行中最快的一次
+fn measure<T>(mut run: impl FnMut() -> T) -> Duration {
+    (0..3)
+        .map(|_| {
+            let started = Instant::now();
+            black_box(run());
+            started.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, bytes: usize, scalar: Duration, simd: Duration) {
+    let throughput = |elapsed: Duration| bytes as f64 / (1024.0 * 1024.0) / elapsed.as_secs_f64();
+    println!(
+        "{:<24} 优化前 {:>8.0} MB/s   优化后 {:>8.0} MB/s   加速 {:.2}x",
+        name,
+        throughput(scalar),
+        throughput(simd),
+        scalar.as_secs_f64() / simd.as_secs_f64()
+    );
+}
+
+fn main() {
+    let megabytes: usize = std::env::var("AUDIO_HELPER_BENCH_MB").ok().and_then(|mb| mb.parse().ok()).unwrap_or(256);
+    let bytes = megabytes * 1024 * 1024;
+    // 伪随机数据，避免编译器针对常量优化
+    let mut seed = 0x1234_5678u32;
+    let data: Vec<u8> = (0..bytes)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+            (seed >> 24) as u8
+        })
+        .collect();
+    println!("📊 样本转换基准：{} MB 16 位立体声 PCM", megabytes);
+
+    assert_eq!(decode_samples_i16(&data, SampleFormat::S16), scalar_i16(&data, SampleFormat::S16));
+    report(
+        "S16 字节 → i16",
+        bytes,
+        measure(|| scalar_i16(black_box(&data), black_box(SampleFormat::S16))),
+        measure(|| decode_samples_i16(black_box(&data), SampleFormat::S16)),
+    );
+    report(
+        "S16 字节 → f32",
+        bytes,
+        measure(|| scalar_f32(black_box(&data), black_box(SampleFormat::S16))),
+        measure(|| decode_samples_f32(black_box(&data), SampleFormat::S16)),
+    );
+
+    let samples = decode_samples_i16(&data, SampleFormat::S16);
+    assert_eq!(deinterleave_stereo(&samples), scalar_deinterleave(&samples));
+    report(
+        "立体声拆分左右声道",
+        bytes,
+        measure(|| scalar_deinterleave(black_box(&samples))),
+        measure(|| deinterleave_stereo(black_box(&samples))),
+    );
+}
@@ -15,8 +15,8 @@ use audio_helper::{
     
     // FFI 模块（移动端集成）
     ffi::{
-        CPcmConfig, CMp3Config,
-        pcm_to_wav, pcm_to_mp3, auto_convert_audio,
+        CErrorCode, CPcmConfig,
+        pcm_to_wav, pcm_to_mp3,
         infer_config_from_filename, get_version
     }
 };
@@ -58,12 +58,9 @@ fn demo_wav_conversion() {
         let config = PcmToWavConfig::new(8000, 1, 16);
         
         match trans_pcm_file_to_wav(pcm_file, wav_file, Some(config)) {
-            Ok(()) => {
+            Ok(report) => {
                 println!("   ✅ WAV 转换成功: {}", wav_file);
-                
-                if let Ok(metadata) = std::fs::metadata(wav_file) {
-                    println!("   📁 输出文件大小: {} bytes", metadata.len());
-                }
+                println!("   📁 输出文件大小: {} bytes，时长 {:.2}s", report.output_bytes, report.duration.as_secs_f64());
             }
             Err(e) => println!("   ❌ WAV 转换失败: {}", e),
         }
@@ -83,7 +80,7 @@ fn demo_mp3_conversion() {
         let config = Mp3Config::new(32000, 1, Mp3Bitrate::Kbps256, AudioQuality::Best);
         
         match trans_pcm_file_to_mp3(pcm_file, mp3_file, Some(config)) {
-            Ok(()) => {
+            Ok(_) => {
                 println!("   ✅ MP3 转换成功: {}", mp3_file);
                 
                 if let Ok(input_meta) = std::fs::metadata(pcm_file) {
@@ -117,6 +114,9 @@ fn demo_auto_conversion() {
                     std::path::Path::new(pcm_file).file_stem().unwrap().to_string_lossy()),
                 AudioFormat::Mp3 => format!("output_wav/auto_{}.mp3", 
                     std::path::Path::new(pcm_file).file_stem().unwrap().to_string_lossy()),
+                AudioFormat::Pcm => format!("output_wav/auto_{}.pcm", 
+                    std::path::Path::new(pcm_file).file_stem().unwrap().to_string_lossy()),
+                _ => continue,
             };
             
             match auto_convert_pcm(pcm_file, &output_file, format) {
@@ -174,7 +174,7 @@ fn demo_ffi_interface() {
     };
     
     let result = infer_config_from_filename(filename.as_ptr(), &mut ffi_config);
-    if result == 0 {
+    if result == CErrorCode::Ok {
         println!("   📊 FFI 推断结果: {}Hz, {}ch, {}bit", 
                  ffi_config.sample_rate, ffi_config.channels, ffi_config.bits_per_sample);
     }
@@ -186,19 +186,19 @@ fn demo_ffi_interface() {
         let output_path = CString::new("output_wav/ffi_test_output.wav").unwrap();
         
         let ffi_result = pcm_to_wav(input_path.as_ptr(), output_path.as_ptr(), ptr::null());
-        if ffi_result == 0 {
+        if ffi_result == CErrorCode::Ok {
             println!("   ✅ FFI WAV 转换成功");
         } else {
-            println!("   ❌ FFI WAV 转换失败");
+            println!("   ❌ FFI WAV 转换失败: {:?}", ffi_result);
         }
         
         // 测试 FFI MP3 转换
         let mp3_output = CString::new("output_wav/ffi_test_output.mp3").unwrap();
         let ffi_mp3_result = pcm_to_mp3(input_path.as_ptr(), mp3_output.as_ptr(), ptr::null());
-        if ffi_mp3_result == 0 {
+        if ffi_mp3_result == CErrorCode::Ok {
             println!("   ✅ FFI MP3 转换成功");
         } else {
-            println!("   ❌ FFI MP3 转换失败");
+            println!("   ❌ FFI MP3 转换失败: {:?}", ffi_mp3_result);
         }
     }
     
@@ -64,12 +64,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     for entry in pcm_files {
         let path = entry.path();
         let filename = path.file_name().unwrap().to_string_lossy();
-        let input_path = path.to_string_lossy();
         let output_path = format!("batch_{}.wav", filename.replace(".pcm", ""));
         
         println!("   🔄 处理: {}", filename);
         
-        match auto_trans_pcm_to_wav(&input_path, &output_path) {
+        match auto_trans_pcm_to_wav(&path, &output_path) {
             Ok(config) => {
                 let file_size = std::fs::metadata(&output_path)
                     .map(|m| m.len())
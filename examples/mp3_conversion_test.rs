@@ -28,7 +28,7 @@ fn main() {
     
     // 转换 PCM 到 MP3
     match trans_pcm_file_to_mp3(pcm_file, mp3_file, Some(mp3_config)) {
-        Ok(()) => {
+        Ok(_) => {
             println!("✅ MP3 conversion successful!");
             
             // 检查输出文件大小
@@ -0,0 +1,231 @@
+// IMA ADPCM 编解码（WAV 格式标签 0x0011，按块存储）
+
+use crate::AudioError;
+
+/// 量化步长表
+const STEP_TABLE: [i32; 89] = [
+    7, 8, 9, 10, 11, 12, 13, 14, 16, 17, 19, 21, 23, 25, 28, 31,
+    34, 37, 41, 45, 50, 55, 60, 66, 73, 80, 88, 97, 107, 118, 130, 143,
+    157, 173, 190, 209, 230, 253, 279, 307, 337, 371, 408, 449, 494, 544, 598, 658,
+    724, 796, 876, 963, 1060, 1166, 1282, 1411, 1552, 1707, 1878, 2066, 2272, 2499, 2749, 3024,
+    3327, 3660, 4026, 4428, 4871, 5358, 5894, 6484, 7132, 7845, 8630, 9493, 10442, 11487, 12635, 13899,
+    15289, 16818, 18500, 20350, 22385, 24623, 27086, 29794, 32767,
+];
+
+/// 步长索引调整表
+const INDEX_TABLE: [i32; 8] = [-1, -1, -1, -1, 2, 4, 6, 8];
+
+/// 每个声道块头的字节数（预测值 i16 + 步长索引 u8 + 保留字节）
+const BLOCK_HEADER_SIZE: usize = 4;
+
+/// IMA ADPCM 的块布局
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct AdpcmFormat {
+    /// 每块字节数（所有声道）
+    pub block_align: u16,
+    /// 实际采样帧数（写入 fact 块）
+    pub frames: u64,
+}
+
+impl AdpcmFormat {
+    /// 每块包含的采样帧数
+    pub fn samples_per_block(&self, channels: u8) -> usize {
+        samples_per_block(self.block_align, channels)
+    }
+}
+
+/// 按采样率选择常用的块大小（与 Windows 编码器一致）
+pub(crate) fn default_block_align(sample_rate: u32, channels: u8) -> u16 {
+    let scale = (sample_rate / 11025).max(1) as u16;
+    256 * channels as u16 * scale
+}
+
+/// 每块包含的采样帧数：块头中的 1 个样本加上数据区的 4 位样本
+pub(crate) fn samples_per_block(block_align: u16, channels: u8) -> usize {
+    let channels = channels as usize;
+    (block_align as usize - BLOCK_HEADER_SIZE * channels) * 2 / channels + 1
+}
+
+/// 单个声道的编解码状态
+#[derive(Debug, Clone, Copy, Default)]
+struct ChannelState {
+    predictor: i32,
+    index: i32,
+}
+
+impl ChannelState {
+    /// 根据 4 位编码更新预测值和步长索引，返回解码后的样本
+    fn decode(&mut self, nibble: u8) -> i16 {
+        let step = STEP_TABLE[self.index as usize];
+        let mut diff = step >> 3;
+        if nibble & 1 != 0 {
+            diff += step >> 2;
+        }
+        if nibble & 2 != 0 {
+            diff += step >> 1;
+        }
+        if nibble & 4 != 0 {
+            diff += step;
+        }
+        if nibble & 8 != 0 {
+            self.predictor -= diff;
+        } else {
+            self.predictor += diff;
+        }
+        self.predictor = self.predictor.clamp(i16::MIN as i32, i16::MAX as i32);
+        self.index = (self.index + INDEX_TABLE[(nibble & 7) as usize]).clamp(0, 88);
+        self.predictor as i16
+    }
+
+    /// 将样本编码为 4 位，并同步更新状态
+    fn encode(&mut self, sample: i16) -> u8 {
+        let mut diff = sample as i32 - self.predictor;
+        let mut nibble = 0u8;
+        if diff < 0 {
+            nibble = 8;
+            diff = -diff;
+        }
+        let mut step = STEP_TABLE[self.index as usize];
+        for bit in [4u8, 2, 1] {
+            if diff >= step {
+                nibble |= bit;
+                diff -= step;
+            }
+            step >>= 1;
+        }
+        self.decode(nibble);
+        nibble
+    }
+}
+
+/// 将交错的 i16 样本编码为 IMA ADPCM 块，最后一块不足时用最后一帧补齐
+pub(crate) fn encode_ima_adpcm(samples: &[i16], channels: u8, block_align: u16) -> Vec<u8> {
+    let ch = channels as usize;
+    let frames_per_block = samples_per_block(block_align, channels);
+    let frames = samples.len() / ch;
+    let blocks = frames.div_ceil(frames_per_block);
+    let mut states = vec![ChannelState::default(); ch];
+    let mut output = Vec::with_capacity(blocks * block_align as usize);
+
+    let frame_at = |i: usize| -> &[i16] {
+        let i = i.min(frames.saturating_sub(1));
+        &samples[i * ch..(i + 1) * ch]
+    };
+
+    for block in 0..blocks {
+        let start = block * frames_per_block;
+
+        // 块头：第一帧样本直接作为预测值
+        for (c, state) in states.iter_mut().enumerate() {
+            state.predictor = frame_at(start)[c] as i32;
+            output.extend_from_slice(&(state.predictor as i16).to_le_bytes());
+            output.push(state.index as u8);
+            output.push(0);
+        }
+
+        // 数据区：每个声道每次 8 个样本（4 字节），低 4 位在前
+        for group in (1..frames_per_block).step_by(8) {
+            for (c, state) in states.iter_mut().enumerate() {
+                for pair in 0..4 {
+                    let low = state.encode(frame_at(start + group + pair * 2)[c]);
+                    let high = state.encode(frame_at(start + group + pair * 2 + 1)[c]);
+                    output.push(low | (high << 4));
+                }
+            }
+        }
+    }
+
+    output
+}
+
+/// 将 IMA ADPCM 块解码为交错的 i16 样本，末尾不完整的块按实际长度解码
+pub(crate) fn decode_ima_adpcm(data: &[u8], channels: u8, block_align: u16) -> Result<Vec<i16>, Box<dyn std::error::Error>> {
+    let ch = channels as usize;
+    if ch == 0
+        || (block_align as usize) <= BLOCK_HEADER_SIZE * ch
+        || !(block_align as usize - BLOCK_HEADER_SIZE * ch).is_multiple_of(4 * ch)
+    {
+        return Err(AudioError::InvalidInput(format!("Invalid IMA ADPCM block align {} for {} channels", block_align, channels)).into());
+    }
+
+    let frames_per_block = samples_per_block(block_align, channels);
+    let mut output = Vec::with_capacity(data.len().div_ceil(block_align as usize) * frames_per_block * ch);
+    let mut states = vec![ChannelState::default(); ch];
+
+    for block in data.chunks(block_align as usize) {
+        if block.len() < BLOCK_HEADER_SIZE * ch {
+            break;
+        }
+        let mut frames = vec![0i16; frames_per_block * ch];
+
+        for (c, state) in states.iter_mut().enumerate() {
+            let header = &block[c * BLOCK_HEADER_SIZE..(c + 1) * BLOCK_HEADER_SIZE];
+            state.predictor = i16::from_le_bytes([header[0], header[1]]) as i32;
+            state.index = (header[2] as i32).clamp(0, 88);
+            frames[c] = state.predictor as i16;
+        }
+
+        // 每组为所有声道各 4 字节
+        let body = &block[BLOCK_HEADER_SIZE * ch..];
+        let mut decoded_frames = 1;
+        for (g, group) in body.chunks_exact(4 * ch).enumerate() {
+            for (c, state) in states.iter_mut().enumerate() {
+                for (i, &byte) in group[c * 4..(c + 1) * 4].iter().enumerate() {
+                    let frame = 1 + g * 8 + i * 2;
+                    frames[frame * ch + c] = state.decode(byte & 0x0F);
+                    frames[(frame + 1) * ch + c] = state.decode(byte >> 4);
+                }
+            }
+            decoded_frames = 1 + (g + 1) * 8;
+        }
+
+        output.extend_from_slice(&frames[..decoded_frames * ch]);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(frames: usize, channels: u8) -> Vec<i16> {
+        (0..frames)
+            .flat_map(|i| {
+                let s = ((i as f32 * 0.05).sin() * 10000.0) as i16;
+                (0..channels).map(move |c| if c == 0 { s } else { s / 2 })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_block_layout() {
+        assert_eq!(samples_per_block(256, 1), 505);
+        assert_eq!(samples_per_block(1024, 2), 1017);
+        assert_eq!(default_block_align(8000, 1), 256);
+        assert_eq!(default_block_align(44100, 2), 2048);
+    }
+
+    #[test]
+    fn test_round_trip_mono_and_stereo() {
+        for channels in [1u8, 2] {
+            let block_align = 256 * channels as u16;
+            let samples = sine(2000, channels);
+            let encoded = encode_ima_adpcm(&samples, channels, block_align);
+            assert_eq!(encoded.len() % block_align as usize, 0);
+
+            let decoded = decode_ima_adpcm(&encoded, channels, block_align).unwrap();
+            assert!(decoded.len() >= samples.len());
+            // 起始阶段步长需要几个样本自适应，跳过后再比较
+            let warmup = 64 * channels as usize;
+            let max_error = samples.iter().zip(&decoded).skip(warmup).map(|(a, b)| (*a as i32 - *b as i32).abs()).max().unwrap();
+            assert!(max_error < 600, "{} 声道最大误差过大: {}", channels, max_error);
+        }
+    }
+
+    #[test]
+    fn test_rejects_invalid_block_align() {
+        assert!(decode_ima_adpcm(&[0; 16], 2, 8).is_err());
+        assert!(decode_ima_adpcm(&[0; 16], 1, 7).is_err());
+    }
+}
@@ -0,0 +1,409 @@
+// 音频分析：电平测量、频谱图等只读统计
+
+use crate::{dsp, AudioError};
+
+/// 单个声道的电平
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelLevels {
+    /// 峰值电平（dBFS），静音时为负无穷
+    pub peak_dbfs: f32,
+    /// 均方根电平（dBFS），静音时为负无穷
+    pub rms_dbfs: f32,
+}
+
+/// 电平分析结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct LevelReport {
+    /// 所有声道中的最大峰值电平（dBFS）
+    pub peak_dbfs: f32,
+    /// 所有声道合并计算的均方根电平（dBFS）
+    pub rms_dbfs: f32,
+    /// 每个声道的电平
+    pub per_channel: Vec<ChannelLevels>,
+}
+
+/// 计算交错样本的峰值和均方根电平
+/// # Arguments
+/// * `samples` - 交错的 f32 样本（满量程为 1.0）
+/// * `channels` - 声道数
+/// # Returns
+/// * 整体及每个声道的电平
+pub fn measure_levels(samples: &[f32], channels: u8) -> LevelReport {
+    let channels = channels.max(1) as usize;
+    let mut peaks = vec![0.0f32; channels];
+    let mut squares = vec![0.0f64; channels];
+    for frame in samples.chunks_exact(channels) {
+        for (c, &s) in frame.iter().enumerate() {
+            peaks[c] = peaks[c].max(s.abs());
+            squares[c] += s as f64 * s as f64;
+        }
+    }
+
+    let frames = (samples.len() / channels).max(1) as f64;
+    let rms = |sum: f64, count: f64| dsp::linear_to_db((sum / count).sqrt() as f32);
+    let per_channel = peaks
+        .iter()
+        .zip(&squares)
+        .map(|(&peak, &sum)| ChannelLevels {
+            peak_dbfs: dsp::linear_to_db(peak),
+            rms_dbfs: rms(sum, frames),
+        })
+        .collect();
+
+    LevelReport {
+        peak_dbfs: dsp::linear_to_db(peaks.iter().copied().fold(0.0, f32::max)),
+        rms_dbfs: rms(squares.iter().sum(), frames * channels as f64),
+        per_channel,
+    }
+}
+
+/// 两段音频的比较结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct ComparisonReport {
+    /// 长度相同且所有样本完全一致
+    pub bit_exact: bool,
+    /// 重叠部分样本的最大绝对差（满量程为 1.0）
+    pub max_diff: f32,
+    /// 重叠部分的峰值信噪比（dB，峰值为满量程），完全一致时为正无穷
+    pub psnr: f64,
+    /// 长度差（B 的帧数减去 A 的帧数）
+    pub length_diff: i64,
+}
+
+/// 比较两段声道数相同的交错样本
+pub fn compare_samples(a: &[f32], b: &[f32], channels: u8) -> ComparisonReport {
+    let channels = channels.max(1) as usize;
+    let overlap = a.len().min(b.len());
+    let mut max_diff = 0.0f32;
+    let mut squared_error = 0.0f64;
+    for (x, y) in a[..overlap].iter().zip(&b[..overlap]) {
+        let diff = (x - y).abs();
+        max_diff = max_diff.max(diff);
+        squared_error += diff as f64 * diff as f64;
+    }
+
+    let mse = squared_error / overlap.max(1) as f64;
+    ComparisonReport {
+        bit_exact: a == b,
+        max_diff,
+        psnr: if mse == 0.0 { f64::INFINITY } else { 10.0 * (1.0 / mse).log10() },
+        length_diff: (b.len() / channels) as i64 - (a.len() / channels) as i64,
+    }
+}
+
+/// 频谱图参数
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpectrogramOptions {
+    /// FFT 长度（必须为 2 的幂），决定频率分辨率
+    pub fft_size: usize,
+    /// 相邻分析帧之间的帧数，决定时间分辨率
+    pub hop_size: usize,
+}
+
+impl Default for SpectrogramOptions {
+    fn default() -> Self {
+        SpectrogramOptions {
+            fft_size: 1024,
+            hop_size: 512,
+        }
+    }
+}
+
+impl SpectrogramOptions {
+    /// 设置 FFT 长度
+    pub fn with_fft_size(mut self, fft_size: usize) -> Self {
+        self.fft_size = fft_size;
+        self
+    }
+
+    /// 设置帧移
+    pub fn with_hop_size(mut self, hop_size: usize) -> Self {
+        self.hop_size = hop_size;
+        self
+    }
+}
+
+/// 频谱图（短时傅里叶变换的幅度矩阵）
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spectrogram {
+    /// 按时间排列的分析帧，每帧包含 `fft_size / 2 + 1` 个频点的幅度（满量程正弦波的幅度约为 1.0）
+    pub magnitudes: Vec<Vec<f32>>,
+    /// 采样率
+    pub sample_rate: u32,
+    /// FFT 长度
+    pub fft_size: usize,
+    /// 帧移
+    pub hop_size: usize,
+}
+
+impl Spectrogram {
+    /// 频点对应的频率（Hz）
+    pub fn bin_frequency(&self, bin: usize) -> f32 {
+        bin as f32 * self.sample_rate as f32 / self.fft_size as f32
+    }
+
+    /// 分析帧起始位置对应的时间（毫秒）
+    pub fn frame_time_ms(&self, frame: usize) -> f64 {
+        (frame * self.hop_size) as f64 * 1000.0 / self.sample_rate as f64
+    }
+
+    /// 导出为灰度 PNG：横轴为时间，纵轴为频率（低频在下），亮度按 -100dB 到 0dB 线性映射
+    #[cfg(feature = "image")]
+    pub fn save_png(&self, output_path: impl AsRef<std::path::Path>) -> Result<(), Box<dyn std::error::Error>> {
+        let width = self.magnitudes.len() as u32;
+        let height = (self.fft_size / 2 + 1) as u32;
+        let image = image::GrayImage::from_fn(width, height, |x, y| {
+            let db = dsp::linear_to_db(self.magnitudes[x as usize][(height - 1 - y) as usize]);
+            image::Luma([((db + 100.0) / 100.0 * 255.0).clamp(0.0, 255.0) as u8])
+        });
+        image.save_with_format(output_path, image::ImageFormat::Png)?;
+        Ok(())
+    }
+}
+
+/// 计算交错样本的频谱图（各声道混缩为单声道后加 Hann 窗做 FFT）
+/// # Arguments
+/// * `samples` - 交错的 f32 样本
+/// * `channels` - 声道数
+/// * `sample_rate` - 采样率
+/// * `options` - FFT 长度和帧移
+/// # Returns
+/// * 频谱图，音频短于一个 FFT 长度时末尾补零
+pub fn spectrogram(samples: &[f32], channels: u8, sample_rate: u32, options: &SpectrogramOptions) -> Result<Spectrogram, Box<dyn std::error::Error>> {
+    let fft_size = options.fft_size;
+    if fft_size < 2 || !fft_size.is_power_of_two() {
+        return Err(AudioError::InvalidInput("FFT size must be a power of two".into()).into());
+    }
+    if options.hop_size == 0 {
+        return Err(AudioError::InvalidInput("Hop size must be greater than zero".into()).into());
+    }
+
+    let mono = dsp::downmix_to_mono(samples, channels);
+    let window: Vec<f32> = (0..fft_size)
+        .map(|n| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / fft_size as f32).cos())
+        .collect();
+    // Hann 窗的相干增益为 0.5，幅度按 fft_size / 4 归一化后满量程正弦波约为 1.0
+    let scale = 4.0 / fft_size as f32;
+
+    let frame_count = mono.len().saturating_sub(fft_size).div_ceil(options.hop_size) + 1;
+    let magnitudes = (0..frame_count)
+        .map(|frame| {
+            let start = frame * options.hop_size;
+            let mut re: Vec<f32> = window
+                .iter()
+                .enumerate()
+                .map(|(n, w)| mono.get(start + n).copied().unwrap_or(0.0) * w)
+                .collect();
+            let mut im = vec![0.0f32; fft_size];
+            fft(&mut re, &mut im);
+            (0..=fft_size / 2).map(|k| (re[k] * re[k] + im[k] * im[k]).sqrt() * scale).collect()
+        })
+        .collect();
+
+    Ok(Spectrogram {
+        magnitudes,
+        sample_rate,
+        fft_size,
+        hop_size: options.hop_size,
+    })
+}
+
+/// 速度检测的 BPM 范围
+const MIN_BPM: f32 = 60.0;
+const MAX_BPM: f32 = 200.0;
+
+/// 速度先验的中心（BPM），用于在候选周期的倍数之间选择
+const PREFERRED_BPM: f32 = 120.0;
+
+/// 起始点强度包络的采样率（每秒帧数）
+const ONSET_FRAME_RATE: u32 = 200;
+
+/// 起始点强度包络：相邻频谱帧之间对数幅度的正向增量（spectral flux）之和
+///
+/// 鼓点、拨弦等起音在包络中表现为尖峰，持续的音符不产生响应
+fn onset_envelope(samples: &[f32], channels: u8, sample_rate: u32) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    // FFT 约 20ms，帧移 5ms
+    let options = SpectrogramOptions::default()
+        .with_fft_size((sample_rate as usize / 50).next_power_of_two().max(64))
+        .with_hop_size((sample_rate / ONSET_FRAME_RATE).max(1) as usize);
+    let spectrogram = spectrogram(samples, channels, sample_rate, &options)?;
+    let compress = |magnitude: f32| (1.0 + 1000.0 * magnitude).ln();
+    Ok(spectrogram
+        .magnitudes
+        .windows(2)
+        .map(|pair| pair[1].iter().zip(&pair[0]).map(|(&current, &previous)| (compress(current) - compress(previous)).max(0.0)).sum())
+        .collect())
+}
+
+/// 通过起始点检测和自相关估计交错样本的速度（BPM）
+///
+/// 起始点强度包络的自相关在节拍周期处出现峰值；候选周期按以 120 BPM 为中心的先验加权，
+/// 以减少检测为一半或两倍速度的情况
+/// # Arguments
+/// * `samples` - 交错的 f32 样本
+/// * `channels` - 声道数
+/// * `sample_rate` - 采样率
+/// # Returns
+/// * 60 - 200 之间的 BPM；音频过短（不足两个最长节拍周期）或没有起音时为 None
+pub fn estimate_bpm(samples: &[f32], channels: u8, sample_rate: u32) -> Option<f32> {
+    if sample_rate == 0 {
+        return None;
+    }
+    let envelope = onset_envelope(samples, channels, sample_rate).ok()?;
+    let frame_rate = sample_rate as f32 / (sample_rate / ONSET_FRAME_RATE).max(1) as f32;
+    let min_lag = (60.0 * frame_rate / MAX_BPM).floor() as usize;
+    let max_lag = (60.0 * frame_rate / MIN_BPM).ceil() as usize;
+    if envelope.len() < 2 * max_lag {
+        return None;
+    }
+
+    let mean = envelope.iter().sum::<f32>() / envelope.len() as f32;
+    let centered: Vec<f32> = envelope.iter().map(|&e| e - mean).collect();
+    // 加权自相关，多算两侧各一个延迟用于抛物线插值
+    let scores: Vec<f32> = (min_lag - 1..=max_lag + 1)
+        .map(|lag| {
+            let correlation: f32 = centered.iter().zip(&centered[lag..]).map(|(a, b)| a * b).sum();
+            let octaves = (60.0 * frame_rate / lag as f32 / PREFERRED_BPM).log2();
+            correlation * (-0.5 * octaves * octaves).exp()
+        })
+        .collect();
+
+    let (best, &peak) = scores[1..scores.len() - 1].iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))?;
+    if peak <= 0.0 {
+        return None;
+    }
+    // 在相邻延迟之间做抛物线插值，得到小于一帧的周期
+    let (left, right) = (scores[best], scores[best + 2]);
+    let denominator = left - 2.0 * peak + right;
+    let offset = if denominator.abs() > f32::EPSILON { (0.5 * (left - right) / denominator).clamp(-0.5, 0.5) } else { 0.0 };
+    let lag = (min_lag + best) as f32 + offset;
+    Some(60.0 * frame_rate / lag)
+}
+
+/// 原地基 2 迭代 FFT，长度必须为 2 的幂
+pub(crate) fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+
+    // 位反转重排
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f64::consts::PI / len as f64;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f64).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let t_re = re[b] * cos as f32 - im[b] * sin as f32;
+                let t_im = re[b] * sin as f32 + im[b] * cos as f32;
+                re[b] = re[a] - t_re;
+                im[b] = im[a] - t_im;
+                re[a] += t_re;
+                im[a] += t_im;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare_samples() {
+        let a = [0.5f32, -0.5, 0.25, 0.0];
+        let report = compare_samples(&a, &a, 2);
+        assert!(report.bit_exact);
+        assert_eq!((report.max_diff, report.psnr, report.length_diff), (0.0, f64::INFINITY, 0));
+
+        // 每个样本相差 0.01：MSE 为 1e-4，PSNR 为 40dB
+        let b = [0.51f32, -0.49, 0.26, 0.01, 0.0, 0.0];
+        let report = compare_samples(&a, &b, 2);
+        assert!(!report.bit_exact);
+        assert!((report.max_diff - 0.01).abs() < 1e-6);
+        assert!((report.psnr - 40.0).abs() < 0.01, "{}", report.psnr);
+        assert_eq!(report.length_diff, 1);
+    }
+
+    #[test]
+    fn test_spectrogram_peak_bin() {
+        // 8kHz 采样率下 1kHz 正弦波落在 256 点 FFT 的第 32 个频点
+        let samples: Vec<f32> = (0..2048).map(|i| (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 8000.0).sin()).collect();
+        let options = SpectrogramOptions::default().with_fft_size(256).with_hop_size(128);
+        let result = spectrogram(&samples, 1, 8000, &options).unwrap();
+        assert_eq!(result.magnitudes.len(), 15);
+        assert_eq!(result.bin_frequency(32), 1000.0);
+        for frame in &result.magnitudes {
+            assert_eq!(frame.len(), 129);
+            let peak = frame.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1)).unwrap();
+            assert_eq!(peak.0, 32);
+            assert!((peak.1 - 1.0).abs() < 0.01, "{}", peak.1);
+        }
+        assert!(spectrogram(&samples, 1, 8000, &options.with_fft_size(100)).is_err());
+    }
+
+    #[test]
+    fn test_levels_of_full_scale_square_wave() {
+        // 左声道为满量程方波（峰值和 RMS 均为 0dBFS），右声道为静音
+        let samples: Vec<f32> = (0..100).flat_map(|i| [if i % 2 == 0 { 1.0 } else { -1.0 }, 0.0]).collect();
+        let report = measure_levels(&samples, 2);
+        assert_eq!(report.per_channel[0], ChannelLevels { peak_dbfs: 0.0, rms_dbfs: 0.0 });
+        assert_eq!(report.per_channel[1].peak_dbfs, f32::NEG_INFINITY);
+        assert_eq!(report.peak_dbfs, 0.0);
+        assert!((report.rms_dbfs + 3.0103).abs() < 1e-3, "{}", report.rms_dbfs);
+    }
+
+    #[test]
+    fn test_sine_rms_is_3db_below_peak() {
+        let samples: Vec<f32> = (0..8000).map(|i| (2.0 * std::f32::consts::PI * i as f32 / 80.0).sin() * 0.5).collect();
+        let report = measure_levels(&samples, 1);
+        assert!((report.peak_dbfs + 6.0206).abs() < 1e-2);
+        assert!((report.rms_dbfs + 9.0309).abs() < 1e-2);
+    }
+
+    /// 合成鼓点：每拍一个底鼓（衰减的 60Hz 正弦），反拍一个踩镲（衰减的伪随机噪声）
+    fn drum_pattern(bpm: f32, sample_rate: u32, seconds: f32) -> Vec<f32> {
+        let beat = 60.0 / bpm * sample_rate as f32;
+        let mut seed = 1u32;
+        (0..(sample_rate as f32 * seconds) as usize)
+            .map(|i| {
+                seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+                let since_beat = i as f32 % beat;
+                let since_offbeat = (i as f32 + beat / 2.0) % beat;
+                let t = since_beat / sample_rate as f32;
+                let kick = (2.0 * std::f32::consts::PI * 60.0 * t).sin() * (-t * 30.0).exp() * 0.6;
+                let hat = (seed as f32 / u32::MAX as f32 - 0.5) * (-since_offbeat / sample_rate as f32 * 80.0).exp() * 0.3;
+                kick + hat
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimate_bpm_of_drum_pattern() {
+        for bpm in [90.0, 128.0, 150.0, 174.0] {
+            let samples = drum_pattern(bpm, 22050, 10.0);
+            let detected = estimate_bpm(&samples, 1, 22050).unwrap();
+            assert!((detected - bpm).abs() < 1.0, "{} BPM 检测为 {}", bpm, detected);
+        }
+    }
+
+    #[test]
+    fn test_estimate_bpm_without_beats() {
+        assert_eq!(estimate_bpm(&vec![0.0; 22050 * 5], 1, 22050), None);
+        // 短于两个最长节拍周期
+        assert_eq!(estimate_bpm(&drum_pattern(120.0, 22050, 1.5), 1, 22050), None);
+    }
+}
@@ -0,0 +1,186 @@
+// 异步文件转换：基于 tokio 的文件 IO，编码在阻塞线程池中执行，避免阻塞异步运行时
+
+use std::error::Error;
+use std::path::Path;
+use std::time::Instant;
+
+use crate::atomic::temp_path_for;
+use crate::{
+    audio_config_to_mp3_config, audio_config_to_wav_config, encode_pcm_to_mp3, frames_duration, infer_audio_config_from_filename,
+    is_pcm_file, log_conversion_done, mp3, probe, wav, wav_conversion_report, write_pcm_as_wav, AudioConfig, AudioError, AudioFormat,
+    AudioQuality, ConversionReport, ConvertOptions, Mp3Config, PcmToWavConfig, WavParseMode,
+};
+
+/// 异步接口的错误类型，需要能够跨 `.await` 在线程间传递
+pub type AsyncError = Box<dyn Error + Send + Sync>;
+
+/// 在阻塞线程池中执行 CPU 密集的编解码任务
+async fn run_blocking<T, F>(task: F) -> Result<T, AsyncError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, Box<dyn Error>> + Send + 'static,
+{
+    // 同步函数的错误类型不是 Send，需要在阻塞线程内转换为字符串
+    tokio::task::spawn_blocking(move || task().map_err(|e| e.to_string()))
+        .await?
+        .map_err(Into::into)
+}
+
+/// 异步原子写入：先写入目标目录中的临时文件，成功后重命名，失败时删除临时文件
+async fn write_atomic_async(output_path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let temp_path = temp_path_for(output_path);
+    let written = match tokio::fs::write(&temp_path, contents).await {
+        Ok(()) => tokio::fs::rename(&temp_path, output_path).await,
+        Err(e) => Err(e),
+    };
+    if written.is_err() {
+        let _ = tokio::fs::remove_file(&temp_path).await;
+    }
+    written
+}
+
+/// 异步读取原始 PCM 文件，并做与同步接口相同的输入检查
+async fn read_pcm_input(input_path: &Path) -> Result<Vec<u8>, AsyncError> {
+    if !is_pcm_file(input_path) {
+        return Err(AudioError::InvalidInput("Input file is not a PCM file".into()).into());
+    }
+    if !tokio::fs::try_exists(input_path).await? {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Input file does not exist").into());
+    }
+    let pcm_data = tokio::fs::read(input_path).await?;
+    let detected = probe::detect_format(&pcm_data);
+    if detected.is_container() {
+        return Err(AudioError::InvalidInput(format!("Input file is a {:?} file, not raw PCM", detected)).into());
+    }
+    Ok(pcm_data)
+}
+
+/// 异步将 PCM 文件转换为 WAV 文件
+///
+/// 与 [`crate::trans_pcm_file_to_wav`] 不同，输入数据会整体读入内存
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出 WAV 文件路径
+/// * `config` - PCM 转 WAV 的配置参数
+/// # Returns
+/// * `Result<ConversionReport, AsyncError>` - 转换统计
+pub async fn trans_pcm_file_to_wav_async(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, config: Option<PcmToWavConfig>) -> Result<ConversionReport, AsyncError> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    let pcm_data = read_pcm_input(input_path).await?;
+    let input_bytes = pcm_data.len() as u64;
+    let config = config.unwrap_or_default();
+    let (header, wav_data) = run_blocking(move || {
+        let mut wav_data = Vec::new();
+        let header = write_pcm_as_wav(&mut pcm_data.as_slice(), input_bytes, &config, &mut wav_data, &mut ConvertOptions::default())?;
+        Ok((header, wav_data))
+    })
+    .await?;
+    let output_bytes = wav_data.len() as u64;
+    write_atomic_async(output_path, &wav_data).await?;
+
+    log_conversion_done("WAV", input_path, output_path, input_bytes, started);
+    Ok(wav_conversion_report(&header, input_bytes, output_path, output_bytes, started))
+}
+
+/// 异步将 PCM 文件转换为 MP3 文件
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出 MP3 文件路径
+/// * `config` - MP3 配置，如果为 None 则使用默认配置
+/// # Returns
+/// * `Result<ConversionReport, AsyncError>` - 转换统计，`config_used` 为实际编码参数（重采样后采样率可能变化）
+pub async fn trans_pcm_file_to_mp3_async(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, config: Option<Mp3Config>) -> Result<ConversionReport, AsyncError> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    let pcm_data = read_pcm_input(input_path).await?;
+    let input_bytes = pcm_data.len() as u64;
+    let mp3_config = config.unwrap_or_default();
+    let (mp3_data, mp3_config, duration) = run_blocking(move || encode_pcm_to_mp3(&pcm_data, mp3_config, &mut ConvertOptions::default())).await?;
+    let output_bytes = mp3_data.len() as u64;
+    write_atomic_async(output_path, &mp3_data).await?;
+
+    log_conversion_done("MP3", input_path, output_path, input_bytes, started);
+    Ok(ConversionReport {
+        input_bytes,
+        output_bytes,
+        duration,
+        elapsed: started.elapsed(),
+        config_used: AudioConfig::mp3_output(mp3_config.sample_rate, mp3_config.channels),
+        format: AudioFormat::Mp3,
+        output_path: output_path.to_path_buf(),
+        skipped: false,
+    })
+}
+
+/// 异步将 WAV 文件解码为原始 PCM 文件
+/// # Arguments
+/// * `input_path` - 输入 WAV 文件路径
+/// * `output_path` - 输出 PCM 文件路径
+/// # Returns
+/// * `Result<ConversionReport, AsyncError>` - 转换统计，`config_used` 为输出 PCM 数据的音频参数
+pub async fn trans_wav_file_to_pcm_async(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> Result<ConversionReport, AsyncError> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    let bytes = tokio::fs::read(input_path).await?;
+    let input_bytes = bytes.len() as u64;
+    let audio = run_blocking(move || wav::parse_wav(&bytes, WavParseMode::Lenient)).await?;
+    write_atomic_async(output_path, &audio.data).await?;
+
+    log_conversion_done("PCM", input_path, output_path, audio.data.len() as u64, started);
+    Ok(ConversionReport {
+        input_bytes,
+        output_bytes: audio.data.len() as u64,
+        duration: frames_duration(audio.frames() as u64, audio.config.sample_rate),
+        elapsed: started.elapsed(),
+        config_used: audio.config,
+        format: AudioFormat::Pcm,
+        output_path: output_path.to_path_buf(),
+        skipped: false,
+    })
+}
+
+/// 异步自动转换 PCM 到指定格式，从文件名推断配置
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出文件路径
+/// * `format` - 输出格式
+/// # Returns
+/// * `Result<AudioConfig, AsyncError>` - 从文件名推断出的音频配置
+pub async fn auto_convert_pcm_async(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, format: AudioFormat) -> Result<AudioConfig, AsyncError> {
+    let input_path = input_path.as_ref();
+    let filename = input_path
+        .file_name()
+        .ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?
+        .to_string_lossy();
+
+    let audio_config = infer_audio_config_from_filename(&filename);
+
+    match format {
+        AudioFormat::Wav => {
+            let wav_config = audio_config_to_wav_config(&audio_config);
+            trans_pcm_file_to_wav_async(input_path, output_path, Some(wav_config)).await?;
+        }
+        AudioFormat::Mp3 => {
+            let mp3_config = audio_config_to_mp3_config(&audio_config, mp3::default_bitrate(audio_config.sample_rate), AudioQuality::High);
+            trans_pcm_file_to_mp3_async(input_path, output_path, Some(mp3_config)).await?;
+        }
+        AudioFormat::Pcm => {
+            // 原始 PCM 无需转换，直接复制（输出与输入为同一文件时复制会清空文件）
+            let output_path = output_path.as_ref();
+            if tokio::fs::try_exists(output_path).await? && tokio::fs::canonicalize(output_path).await? == tokio::fs::canonicalize(input_path).await? {
+                return Err(AudioError::InvalidInput("Output file is the same as the input file".into()).into());
+            }
+            let data = tokio::fs::read(input_path).await?;
+            write_atomic_async(output_path, &data).await?;
+        }
+    }
+
+    Ok(audio_config)
+}
@@ -0,0 +1,137 @@
+// 原子写入：输出先写入目标目录中的临时文件，成功后再重命名为最终文件名，
+// 进程崩溃、转换出错或被取消时不会留下写了一半、却会被下游工具当作有效文件的输出
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::OverwritePolicy;
+
+/// 同一进程内区分临时文件名的计数器
+static TEMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// 目标文件对应的临时文件路径：与目标位于同一目录（保证重命名不跨文件系统），以 `.` 开头并以 `.tmp` 结尾
+pub(crate) fn temp_path_for(target: &Path) -> PathBuf {
+    let file_name = target.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let unique = TEMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    target.with_file_name(format!(".{}.{}-{}.tmp", file_name, std::process::id(), unique))
+}
+
+/// 按覆盖策略确定实际的输出路径，返回 None 表示输出已存在且应跳过转换
+pub(crate) fn resolve_output(output: &Path, policy: OverwritePolicy) -> Result<Option<PathBuf>, Box<dyn std::error::Error>> {
+    match policy {
+        OverwritePolicy::Overwrite => Ok(Some(output.to_path_buf())),
+        _ if !output.exists() => Ok(Some(output.to_path_buf())),
+        OverwritePolicy::Error => Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("Output file already exists: {}", output.display())).into()),
+        OverwritePolicy::Skip => Ok(None),
+        OverwritePolicy::RenameWithSuffix => {
+            let stem = output.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+            let extension = output.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+            let renamed = (1u32..)
+                .map(|n| output.with_file_name(format!("{}_{}{}", stem, n, extension)))
+                .find(|candidate| !candidate.exists())
+                .ok_or_else(|| io::Error::new(io::ErrorKind::AlreadyExists, "No free output file name"))?;
+            log::info!("{} already exists, writing to {}", output.display(), renamed.display());
+            Ok(Some(renamed))
+        }
+    }
+}
+
+/// 写入临时文件、提交时重命名为目标文件；未提交就被丢弃时删除临时文件
+pub(crate) struct AtomicFile {
+    file: Option<File>,
+    temp_path: PathBuf,
+    target: PathBuf,
+}
+
+impl AtomicFile {
+    /// 在目标文件所在目录创建临时文件，目标文件在提交前保持不变
+    pub(crate) fn create(target: &Path) -> io::Result<Self> {
+        let temp_path = temp_path_for(target);
+        let file = OpenOptions::new().write(true).create_new(true).open(&temp_path)?;
+        Ok(AtomicFile {
+            file: Some(file),
+            temp_path,
+            target: target.to_path_buf(),
+        })
+    }
+
+    /// 将数据刷入磁盘并重命名为目标文件（已存在的目标文件被替换）
+    pub(crate) fn commit(mut self) -> io::Result<()> {
+        if let Some(file) = self.file.take() {
+            file.sync_all()?;
+        }
+        std::fs::rename(&self.temp_path, &self.target)
+    }
+}
+
+impl Write for AtomicFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self.file.as_mut() {
+            Some(file) => file.write(buf),
+            None => Err(io::Error::other("Atomic file already committed")),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.as_mut().map_or(Ok(()), Write::flush)
+    }
+}
+
+impl Seek for AtomicFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self.file.as_mut() {
+            Some(file) => file.seek(pos),
+            None => Err(io::Error::other("Atomic file already committed")),
+        }
+    }
+}
+
+impl Drop for AtomicFile {
+    fn drop(&mut self) {
+        // 提交成功后临时文件已被重命名，删除会失败且无影响
+        self.file.take();
+        let _ = std::fs::remove_file(&self.temp_path);
+    }
+}
+
+/// 原子地写入整个文件
+pub(crate) fn write_atomic(target: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut file = AtomicFile::create(target)?;
+    file.write_all(contents)?;
+    file.commit()
+}
+
+/// 原子地复制文件
+pub(crate) fn copy_atomic(source: &Path, target: &Path) -> io::Result<u64> {
+    let mut input = File::open(source)?;
+    let mut file = AtomicFile::create(target)?;
+    let copied = io::copy(&mut input, &mut file)?;
+    file.commit()?;
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_atomic_file_commit_and_discard() {
+        let target = Path::new("atomic_unit_test.bin");
+        std::fs::write(target, b"old").unwrap();
+
+        // 未提交时目标文件保持原样，临时文件被删除
+        let mut file = AtomicFile::create(target).unwrap();
+        file.write_all(b"partial").unwrap();
+        let temp_path = file.temp_path.clone();
+        assert!(temp_path.exists());
+        drop(file);
+        assert!(!temp_path.exists());
+        assert_eq!(std::fs::read(target).unwrap(), b"old");
+
+        write_atomic(target, b"new").unwrap();
+        assert_eq!(std::fs::read(target).unwrap(), b"new");
+        let _ = std::fs::remove_file(target);
+    }
+}
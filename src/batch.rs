@@ -0,0 +1,497 @@
+// 批量转换：将目录中或指定的 PCM 文件并行转换为 WAV / MP3
+
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{atomic, auto_convert_pcm, infer_audio_config_from_filename, manifest, preset, AudioConfig, AudioError, AudioFormat, OverwritePolicy, Presets};
+
+/// 批量转换选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchOptions {
+    /// 输出格式
+    pub format: AudioFormat,
+    /// 并行转换的线程数，0 表示使用全部 CPU 核心
+    pub parallelism: usize,
+    /// 是否递归处理子目录（输出保持相同的目录结构）
+    pub recursive: bool,
+    /// 转换结束后写入 JSON 清单的路径，None 表示不写清单
+    pub manifest: Option<PathBuf>,
+    /// 是否断点续传：在输出目录中记录已完成的文件，重新运行时跳过输出未变化的文件
+    pub resume: bool,
+    /// 转换策略，`IfChanged` 时只转换新增或修改过的文件
+    pub policy: ConvertPolicy,
+    /// 输出文件已存在时的处理方式，默认覆盖
+    pub overwrite: OverwritePolicy,
+    /// MP3 编码使用的预设名称（内置或通过 `Presets::register` 注册），为 None 时按采样率选择默认码率
+    pub preset: Option<String>,
+}
+
+/// 批量转换策略
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ConvertPolicy {
+    /// 总是转换（开启断点续传时跳过上次已完成且输出未变化的文件）
+    #[default]
+    Always,
+    /// 只转换新增或修改过的文件：输入的大小和修改时间与上次记录一致，
+    /// 或修改时间变化但 SHA-256 一致，且输出未变化时跳过
+    IfChanged,
+}
+
+impl Default for BatchOptions {
+    fn default() -> Self {
+        BatchOptions {
+            format: AudioFormat::Wav,
+            parallelism: 0,
+            recursive: false,
+            manifest: None,
+            resume: false,
+            policy: ConvertPolicy::Always,
+            overwrite: OverwritePolicy::Overwrite,
+            preset: None,
+        }
+    }
+}
+
+impl BatchOptions {
+    /// 创建指定输出格式的批量转换选项
+    pub fn new(format: AudioFormat) -> Self {
+        BatchOptions {
+            format,
+            ..Default::default()
+        }
+    }
+
+    /// 设置并行线程数，0 表示使用全部 CPU 核心
+    pub fn with_parallelism(mut self, parallelism: usize) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// 设置是否递归处理子目录
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// 设置 JSON 清单的输出路径
+    pub fn with_manifest(mut self, path: impl Into<PathBuf>) -> Self {
+        self.manifest = Some(path.into());
+        self
+    }
+
+    /// 设置是否断点续传，首次运行时也需要开启才会记录进度
+    pub fn with_resume(mut self, resume: bool) -> Self {
+        self.resume = resume;
+        self
+    }
+
+    /// 设置转换策略
+    pub fn with_policy(mut self, policy: ConvertPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 设置输出文件已存在时的处理方式（断点续传或增量转换跳过的文件不受影响）
+    pub fn with_overwrite(mut self, overwrite: OverwritePolicy) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// 设置 MP3 编码使用的预设名称，仅适用于 MP3 输出
+    pub fn with_preset(mut self, name: impl Into<String>) -> Self {
+        self.preset = Some(name.into());
+        self
+    }
+
+    /// 开始转换前检查预设名称，避免每个文件都因同一原因失败
+    fn check_preset(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let Some(name) = &self.preset else { return Ok(()) };
+        if self.format != AudioFormat::Mp3 {
+            return Err(AudioError::InvalidConfig(format!("Preset {} applies only to MP3 output", name)).into());
+        }
+        if !Presets::contains(name) {
+            return Err(AudioError::InvalidInput(format!("Unknown preset: {} (available: {})", name, Presets::names().join(", "))).into());
+        }
+        Ok(())
+    }
+
+    /// 实际使用的线程数
+    fn worker_count(&self, jobs: usize) -> usize {
+        let parallelism = match self.parallelism {
+            0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+            n => n,
+        };
+        parallelism.min(jobs).max(1)
+    }
+}
+
+/// 单个文件的转换结果
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchItem {
+    /// 输入文件路径
+    pub input: PathBuf,
+    /// 输出文件路径（覆盖策略为 `RenameWithSuffix` 时为实际写入的路径）
+    pub output: PathBuf,
+    /// 成功时为从文件名推断出的音频配置，失败时为错误信息
+    pub result: Result<AudioConfig, String>,
+    /// 输出已存在且未变化（断点续传或输入未修改），或输出已存在且覆盖策略为 `Skip`，本次没有重新转换
+    pub skipped: bool,
+}
+
+/// 批量转换结果，按输入路径排序
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BatchReport {
+    pub items: Vec<BatchItem>,
+}
+
+impl BatchReport {
+    /// 转换成功的文件数
+    pub fn succeeded(&self) -> usize {
+        self.items.iter().filter(|item| item.result.is_ok()).count()
+    }
+
+    /// 跳过的文件数
+    pub fn skipped(&self) -> usize {
+        self.items.iter().filter(|item| item.skipped).count()
+    }
+
+    /// 转换失败的文件
+    pub fn failures(&self) -> impl Iterator<Item = &BatchItem> {
+        self.items.iter().filter(|item| item.result.is_err())
+    }
+
+    /// 将转换结果写为 JSON 清单
+    ///
+    /// 每个条目包含输入输出路径、输入大小和状态；成功的条目还包含音频参数、
+    /// 输出时长、输出大小和输出文件的 SHA-256，失败的条目包含错误信息
+    pub fn write_manifest(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+        manifest::write_manifest(self, path.as_ref())
+    }
+}
+
+/// 查找目录中的 PCM 文件（按路径排序）
+fn collect_pcm_files(dir: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if recursive {
+                collect_pcm_files(&path, recursive, files)?;
+            }
+        } else if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pcm")) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 在固定数量的工作线程上执行任务，结果按任务顺序返回
+pub(crate) fn run_parallel<T: Sync, R: Send>(jobs: &[T], workers: usize, work: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(jobs.len()));
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(job) = jobs.get(index) else { break };
+                    let result = work(job);
+                    results.lock().unwrap_or_else(|e| e.into_inner()).push((index, result));
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap_or_else(|e| e.into_inner());
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// 输出格式对应的文件扩展名
+pub(crate) fn output_extension(format: &AudioFormat) -> &'static str {
+    match format {
+        AudioFormat::Wav => "wav",
+        AudioFormat::Mp3 => "mp3",
+        AudioFormat::Pcm => "pcm",
+    }
+}
+
+/// 转换单个文件，必要时创建输出目录，按覆盖策略处理已存在的输出，失败时记录警告
+///
+/// 指定了预设名称时按预设编码为 MP3，否则按文件名推断的参数转换为 `format`
+pub(crate) fn convert_item(input: &Path, output: &Path, format: &AudioFormat, overwrite: OverwritePolicy, preset: Option<&str>) -> BatchItem {
+    let resolved = match atomic::resolve_output(output, overwrite) {
+        Ok(Some(resolved)) => resolved,
+        Ok(None) => {
+            log::debug!("Skipping {}: {} already exists", input.display(), output.display());
+            let filename = input.file_name().unwrap_or_default().to_string_lossy();
+            return BatchItem {
+                input: input.to_path_buf(),
+                output: output.to_path_buf(),
+                result: Ok(infer_audio_config_from_filename(&filename)),
+                skipped: true,
+            };
+        }
+        Err(e) => {
+            log::warn!("Conversion of {} failed: {}", input.display(), e);
+            return BatchItem {
+                input: input.to_path_buf(),
+                output: output.to_path_buf(),
+                result: Err(e.to_string()),
+                skipped: false,
+            };
+        }
+    };
+    let result = resolved
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .map_err(|e| e.into())
+        .and_then(|_| match preset {
+            Some(name) => preset::convert_with_named_preset(input, &resolved, name),
+            None => auto_convert_pcm(input, &resolved, format.clone()),
+        })
+        .map_err(|e| e.to_string());
+    if let Err(e) = &result {
+        log::warn!("Conversion of {} failed: {}", input.display(), e);
+    }
+    BatchItem {
+        input: input.to_path_buf(),
+        output: resolved,
+        result,
+        skipped: false,
+    }
+}
+
+/// 批量转换状态文件名，位于输出目录中
+const STATE_FILE_NAME: &str = ".audio-helper-batch-state";
+
+/// 已完成文件的记录
+#[derive(Debug, Clone, PartialEq)]
+struct StateEntry {
+    output_size: u64,
+    output_hash: String,
+    input_size: u64,
+    /// 输入文件修改时间（自 UNIX 纪元起的纳秒数）
+    input_modified: u128,
+    input_hash: String,
+}
+
+impl StateEntry {
+    /// 读取输入和输出文件的当前状态
+    fn read(input: &Path, output: &Path) -> std::io::Result<Self> {
+        let input_metadata = std::fs::metadata(input)?;
+        Ok(StateEntry {
+            output_size: std::fs::metadata(output)?.len(),
+            output_hash: manifest::sha256_file(output)?,
+            input_size: input_metadata.len(),
+            input_modified: modified_nanos(&input_metadata),
+            input_hash: manifest::sha256_file(input)?,
+        })
+    }
+
+    /// 解析状态文件中的一行
+    fn parse(line: &str) -> Option<(PathBuf, Self)> {
+        let mut fields = line.splitn(6, '\t');
+        let entry = StateEntry {
+            output_size: fields.next()?.parse().ok()?,
+            output_hash: fields.next()?.to_string(),
+            input_size: fields.next()?.parse().ok()?,
+            input_modified: fields.next()?.parse().ok()?,
+            input_hash: fields.next()?.to_string(),
+        };
+        Some((PathBuf::from(fields.next()?), entry))
+    }
+
+    /// 格式化为状态文件中的一行
+    fn to_line(&self, relative: &Path) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            self.output_size, self.output_hash, self.input_size, self.input_modified, self.input_hash, relative.to_string_lossy()
+        )
+    }
+}
+
+/// 文件修改时间（自 UNIX 纪元起的纳秒数），无法获取时为 0
+fn modified_nanos(metadata: &std::fs::Metadata) -> u128 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos())
+}
+
+/// 批量转换状态：记录已完成文件的输入输出大小、修改时间和 SHA-256
+///
+/// 状态文件每行为 `输出大小\t输出 SHA-256\t输入大小\t输入修改时间\t输入 SHA-256\t输入相对路径`，
+/// 每完成一个文件追加一行，中断时已完成的记录不会丢失
+struct BatchState {
+    completed: HashMap<PathBuf, StateEntry>,
+    file: Mutex<File>,
+}
+
+impl BatchState {
+    /// 打开输出目录中的状态文件，不存在时创建；重复的记录只保留最后一条
+    fn open(output_dir: &Path) -> std::io::Result<Self> {
+        std::fs::create_dir_all(output_dir)?;
+        let path = output_dir.join(STATE_FILE_NAME);
+        let completed: HashMap<PathBuf, StateEntry> = match std::fs::read_to_string(&path) {
+            Ok(text) => text.lines().filter_map(StateEntry::parse).collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e),
+        };
+        let compacted: String = completed.iter().map(|(relative, entry)| entry.to_line(relative)).collect();
+        atomic::write_atomic(&path, compacted.as_bytes())?;
+        let file = OpenOptions::new().append(true).open(&path)?;
+        Ok(BatchState { completed, file: Mutex::new(file) })
+    }
+
+    /// 输出文件与记录的大小和校验和一致时返回该记录
+    fn intact_entry(&self, relative: &Path, output: &Path) -> Option<&StateEntry> {
+        let entry = self.completed.get(relative)?;
+        let intact = std::fs::metadata(output).is_ok_and(|m| m.len() == entry.output_size)
+            && manifest::sha256_file(output).is_ok_and(|h| h == entry.output_hash);
+        intact.then_some(entry)
+    }
+
+    /// 记录一个已完成的文件
+    fn record(&self, relative: &Path, entry: &StateEntry) -> std::io::Result<()> {
+        // 整行一次写入，多个线程追加时不会交错
+        self.file.lock().unwrap_or_else(|e| e.into_inner()).write_all(entry.to_line(relative).as_bytes())
+    }
+
+    /// 判断已记录的文件是否可以跳过
+    ///
+    /// 修改时间变化但内容未变时（例如文件被 touch），会更新记录中的修改时间
+    fn can_skip(&self, relative: &Path, input: &Path, output: &Path, policy: ConvertPolicy) -> bool {
+        let Some(entry) = self.intact_entry(relative, output) else { return false };
+        if policy == ConvertPolicy::Always {
+            return true;
+        }
+        let Ok(metadata) = std::fs::metadata(input) else { return false };
+        if metadata.len() != entry.input_size {
+            return false;
+        }
+        let modified = modified_nanos(&metadata);
+        if modified == entry.input_modified {
+            return true;
+        }
+        if !manifest::sha256_file(input).is_ok_and(|h| h == entry.input_hash) {
+            return false;
+        }
+        let refreshed = StateEntry { input_modified: modified, ..entry.clone() };
+        if let Err(e) = self.record(relative, &refreshed) {
+            log::warn!("Failed to record batch state for {}: {}", input.display(), e);
+        }
+        true
+    }
+}
+
+/// 批量转换目录中的 PCM 文件
+/// # Arguments
+/// * `input_dir` - 输入目录，其中的 .pcm 文件按文件名推断音频参数
+/// * `output_dir` - 输出目录，不存在时自动创建
+/// * `options` - 输出格式、并行度、是否递归、清单路径、是否断点续传、转换策略、覆盖策略和 MP3 预设
+/// # Returns
+/// * `Result<BatchReport, Box<dyn std::error::Error>>` - 每个文件的转换结果；单个文件失败不会中断其余文件
+pub fn batch_convert_directory(input_dir: impl AsRef<Path>, output_dir: impl AsRef<Path>, options: &BatchOptions) -> Result<BatchReport, Box<dyn std::error::Error>> {
+    let input_dir = input_dir.as_ref();
+    let output_dir = output_dir.as_ref();
+    if !input_dir.is_dir() {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Input directory does not exist").into());
+    }
+    options.check_preset()?;
+
+    let mut inputs = Vec::new();
+    collect_pcm_files(input_dir, options.recursive, &mut inputs)?;
+    inputs.sort();
+
+    let extension = output_extension(&options.format);
+    let jobs: Vec<(PathBuf, PathBuf)> = inputs
+        .into_iter()
+        .map(|input| {
+            let relative = input.strip_prefix(input_dir).unwrap_or(&input);
+            let output = output_dir.join(relative).with_extension(extension);
+            (input, output)
+        })
+        .collect();
+
+    let workers = options.worker_count(jobs.len());
+    log::info!("Batch converting {} files from {} with {} workers", jobs.len(), input_dir.display(), workers);
+
+    let use_state = options.resume || options.policy == ConvertPolicy::IfChanged;
+    let state = if use_state { Some(BatchState::open(output_dir)?) } else { None };
+    let items = run_parallel(&jobs, workers, |(input, output)| {
+        let Some(state) = &state else { return convert_item(input, output, &options.format, options.overwrite, options.preset.as_deref()) };
+        let relative = input.strip_prefix(input_dir).unwrap_or(input);
+        if state.can_skip(relative, input, output, options.policy) {
+            log::debug!("Skipping {}: output is up to date", input.display());
+            let filename = input.file_name().unwrap_or_default().to_string_lossy();
+            return BatchItem {
+                input: input.clone(),
+                output: output.clone(),
+                result: Ok(infer_audio_config_from_filename(&filename)),
+                skipped: true,
+            };
+        }
+        let item = convert_item(input, output, &options.format, options.overwrite, options.preset.as_deref());
+        if item.result.is_ok() && !item.skipped {
+            let recorded = StateEntry::read(input, &item.output).and_then(|entry| state.record(relative, &entry));
+            if let Err(e) = recorded {
+                log::warn!("Failed to record batch state for {}: {}", input.display(), e);
+            }
+        }
+        item
+    });
+
+    let report = BatchReport { items };
+    if let Some(path) = &options.manifest {
+        report.write_manifest(path)?;
+    }
+    Ok(report)
+}
+
+/// 批量转换指定的文件
+/// # Arguments
+/// * `jobs` - (输入 PCM 文件, 输出文件) 列表，输入的音频参数从文件名推断，输出目录不存在时自动创建
+/// * `options` - 输出格式、并行度、清单路径和覆盖策略；断点续传和增量转换的状态保存在输出目录中，这里不支持
+/// # Returns
+/// * `Result<BatchReport, Box<dyn std::error::Error>>` - 每个文件的转换结果，顺序与 `jobs` 一致；单个文件失败不会中断其余文件
+pub fn batch_convert_files(jobs: &[(PathBuf, PathBuf)], options: &BatchOptions) -> Result<BatchReport, Box<dyn std::error::Error>> {
+    if options.resume || options.policy != ConvertPolicy::Always {
+        return Err(AudioError::InvalidInput("Resume and incremental conversion require batch_convert_directory".into()).into());
+    }
+    options.check_preset()?;
+    let mut outputs = HashMap::new();
+    for (input, output) in jobs {
+        if let Some(previous) = outputs.insert(output, input) {
+            return Err(AudioError::InvalidInput(format!("{} and {} would both be written to {}", previous.display(), input.display(), output.display())).into());
+        }
+    }
+
+    let workers = options.worker_count(jobs.len());
+    log::info!("Batch converting {} files with {} workers", jobs.len(), workers);
+    let items = run_parallel(jobs, workers, |(input, output)| convert_item(input, output, &options.format, options.overwrite, options.preset.as_deref()));
+
+    let report = BatchReport { items };
+    if let Some(path) = &options.manifest {
+        report.write_manifest(path)?;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run_parallel_keeps_job_order() {
+        let jobs: Vec<u64> = (0..100).collect();
+        for workers in [1, 3, 8] {
+            let results = run_parallel(&jobs, workers, |n| n * n);
+            assert_eq!(results, jobs.iter().map(|n| n * n).collect::<Vec<_>>());
+        }
+    }
+}
@@ -0,0 +1,462 @@
+// audio-helper 命令行工具：无需编写 Rust 代码即可转换、探测和批量处理音频文件
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use audio_helper::{
+    batch_convert_directory, batch_convert_files, infer_audio_config_from_filename, infer_audio_config_with_confidence, probe_audio_with_config, AudioConfig,
+    AudioFormat, BatchOptions, BatchReport, ConversionProfile, ConvertOptions, ConvertPolicy, Mp3Bitrate, Mp3RateMode,
+    OverwritePolicy, Preset, SampleFormat,
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+/// PCM / WAV / MP3 音频转换工具
+#[derive(Parser)]
+#[command(name = "audio-helper", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 转换单个文件，输入输出格式由扩展名决定（pcm→wav、pcm→mp3、wav→pcm）
+    Convert(ConvertArgs),
+    /// 检测文件格式并显示音频参数、时长和块布局
+    Probe(ProbeArgs),
+    /// 批量转换目录中或匹配 glob 模式的 PCM 文件
+    Batch(BatchArgs),
+    /// 从文件名推断音频参数
+    Infer(InferArgs),
+    /// 在默认输出设备上试听文件，确认 PCM 参数后再批量转换
+    #[cfg(feature = "playback")]
+    Play(PlayArgs),
+}
+
+/// 原始 PCM 的音频参数，未指定时从文件名推断
+#[derive(Args)]
+struct PcmArgs {
+    /// 采样率（Hz）
+    #[arg(long)]
+    sample_rate: Option<u32>,
+    /// 声道数
+    #[arg(long)]
+    channels: Option<u8>,
+    /// 位深度（8 / 16 / 24 / 32）
+    #[arg(long)]
+    bits: Option<u16>,
+}
+
+impl PcmArgs {
+    /// 以从文件名推断的参数为基础，应用命令行中指定的参数
+    fn config_for(&self, path: &Path) -> Result<AudioConfig, Box<dyn std::error::Error>> {
+        let filename = path.file_name().ok_or("无效的文件路径")?.to_string_lossy();
+        let mut config = infer_audio_config_from_filename(&filename);
+        if let Some(sample_rate) = self.sample_rate {
+            config.sample_rate = sample_rate;
+        }
+        if let Some(channels) = self.channels {
+            config.channels = channels;
+        }
+        if let Some(bits) = self.bits {
+            config.sample_format = SampleFormat::from_bits(bits).ok_or_else(|| format!("Unsupported bit depth: {}", bits))?;
+            config.bits_per_sample = bits;
+        }
+        Ok(config)
+    }
+
+    /// 将命令行中指定的参数写入转换配置
+    fn apply_to(&self, profile: &mut ConversionProfile) {
+        profile.sample_rate = self.sample_rate.or(profile.sample_rate);
+        profile.channels = self.channels.or(profile.channels);
+        if let Some(bits) = self.bits {
+            profile.bits_per_sample = Some(bits);
+            profile.sample_format = None;
+        }
+    }
+}
+
+/// 输出文件已存在时的处理方式
+#[derive(Clone, Copy, ValueEnum)]
+enum OverwriteArg {
+    /// 报错
+    Error,
+    /// 覆盖
+    Overwrite,
+    /// 跳过
+    Skip,
+    /// 添加 _1、_2 等后缀
+    Rename,
+}
+
+impl From<OverwriteArg> for OverwritePolicy {
+    fn from(overwrite: OverwriteArg) -> Self {
+        match overwrite {
+            OverwriteArg::Error => OverwritePolicy::Error,
+            OverwriteArg::Overwrite => OverwritePolicy::Overwrite,
+            OverwriteArg::Skip => OverwritePolicy::Skip,
+            OverwriteArg::Rename => OverwritePolicy::RenameWithSuffix,
+        }
+    }
+}
+
+/// 输出格式
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    Wav,
+    Mp3,
+    Pcm,
+}
+
+impl From<Format> for AudioFormat {
+    fn from(format: Format) -> Self {
+        match format {
+            Format::Wav => AudioFormat::Wav,
+            Format::Mp3 => AudioFormat::Mp3,
+            Format::Pcm => AudioFormat::Pcm,
+        }
+    }
+}
+
+#[derive(Args)]
+struct ConvertArgs {
+    /// 输入文件
+    input: PathBuf,
+    /// 输出文件
+    output: PathBuf,
+    #[command(flatten)]
+    pcm: PcmArgs,
+    /// MP3 比特率（kbps），64/128/192/256/320 使用固定码率，其余值使用平均码率；未指定时使用预设或配置文件中的设置（默认按采样率选择）
+    #[arg(long)]
+    bitrate: Option<u32>,
+    /// MP3 编码线程数，0 为使用全部可用核心；长音频分段并行编码
+    #[arg(long)]
+    threads: Option<usize>,
+    /// MP3 无缝编码：编码全部样本并在 LAME 标签中记录编码器延迟和末尾填充
+    #[arg(long)]
+    gapless: bool,
+    /// 转换配置文件（.toml / .json），包含编码和处理选项；命令行参数优先于配置文件
+    #[arg(long)]
+    profile: Option<PathBuf>,
+    /// 编码预设：voice（语音）、music（音乐）、podcast（播客）、archive（归档）
+    #[arg(long, value_parser = parse_preset)]
+    preset: Option<Preset>,
+    /// 输出文件已存在时的处理方式
+    #[arg(long, value_enum, default_value = "overwrite")]
+    overwrite: OverwriteArg,
+}
+
+#[derive(Args)]
+struct ProbeArgs {
+    /// 输入文件
+    input: PathBuf,
+    #[command(flatten)]
+    pcm: PcmArgs,
+    /// 以 JSON 输出格式、参数、时长和块布局
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Args)]
+struct BatchArgs {
+    /// 输入目录，或匹配 PCM 文件的 glob 模式（如 "in/**/*.pcm"，需加引号避免被 shell 展开）
+    input: String,
+    /// 输出目录；使用 glob 模式时输出路径为该目录下的 --out 模板（默认 "{dir}/{stem}.{ext}"）
+    output_dir: Option<PathBuf>,
+    /// 使用 glob 模式时的输出路径模板，支持 {stem}（文件名主干）、{name}（文件名）、{ext}（输出扩展名）、{dir}（相对于模式中固定前缀的子目录）
+    #[arg(long)]
+    out: Option<String>,
+    /// 输出格式
+    #[arg(long, value_enum, default_value = "wav")]
+    to: Format,
+    /// 并行线程数，0 表示使用全部 CPU 核心
+    #[arg(long, short, default_value_t = 0)]
+    jobs: usize,
+    /// 递归处理子目录（输入为目录时）
+    #[arg(long, short)]
+    recursive: bool,
+    /// 写入 JSON 清单的路径
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+    /// 断点续传，跳过上次已完成的文件（输入为目录时）
+    #[arg(long)]
+    resume: bool,
+    /// 只转换新增或修改过的文件（输入为目录时）
+    #[arg(long)]
+    if_changed: bool,
+    /// 输出文件已存在时的处理方式
+    #[arg(long, value_enum, default_value = "overwrite")]
+    overwrite: OverwriteArg,
+    /// MP3 编码预设：voice（语音）、music（音乐）、podcast（播客）、archive（归档），需配合 --to mp3
+    #[arg(long)]
+    preset: Option<String>,
+}
+
+#[derive(Args)]
+struct InferArgs {
+    /// 文件名（不需要存在）
+    filenames: Vec<String>,
+}
+
+#[cfg(feature = "playback")]
+#[derive(Args)]
+struct PlayArgs {
+    /// 输入文件（WAV / MP3 / PCM）
+    input: PathBuf,
+    #[command(flatten)]
+    pcm: PcmArgs,
+}
+
+/// MP3 比特率：标准值使用固定码率，其余使用平均码率
+fn mp3_rate_mode(kbps: u32) -> Result<Mp3RateMode, Box<dyn std::error::Error>> {
+    let bitrate = match kbps {
+        64 => Mp3Bitrate::Kbps64,
+        128 => Mp3Bitrate::Kbps128,
+        192 => Mp3Bitrate::Kbps192,
+        256 => Mp3Bitrate::Kbps256,
+        320 => Mp3Bitrate::Kbps320,
+        8..=320 => return Ok(Mp3RateMode::Abr(kbps)),
+        _ => return Err(format!("Unsupported MP3 bitrate: {} kbps", kbps).into()),
+    };
+    Ok(Mp3RateMode::Cbr(bitrate))
+}
+
+/// 按名称解析编码预设
+fn parse_preset(name: &str) -> Result<Preset, String> {
+    Preset::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = Preset::ALL.iter().map(Preset::name).collect();
+        format!("未知的预设 {}（可选: {}）", name, names.join(", "))
+    })
+}
+
+/// 小写的文件扩展名
+fn extension(path: &Path) -> String {
+    path.extension().map(|ext| ext.to_string_lossy().to_ascii_lowercase()).unwrap_or_default()
+}
+
+/// 音频参数的简短描述
+fn describe_config(config: &AudioConfig) -> String {
+    format!("{}Hz, {} 声道, {} 位 ({:?})", config.sample_rate, config.channels, config.bits_per_sample, config.sample_format)
+}
+
+/// 由扩展名确定文件格式
+fn format_of(path: &Path) -> Option<AudioFormat> {
+    match extension(path).as_str() {
+        "pcm" => Some(AudioFormat::Pcm),
+        "wav" => Some(AudioFormat::Wav),
+        "mp3" => Some(AudioFormat::Mp3),
+        _ => None,
+    }
+}
+
+fn convert(args: ConvertArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let (Some(input_format), Some(output_format)) = (format_of(&args.input), format_of(&args.output)) else {
+        return Err(format!("Unsupported conversion: .{} -> .{}", extension(&args.input), extension(&args.output)).into());
+    };
+
+    // 输入输出格式由扩展名决定，编码和处理选项来自配置文件，命令行参数优先
+    let mut profile = match &args.profile {
+        Some(path) => ConversionProfile::from_file(path)?,
+        None => ConversionProfile::default(),
+    };
+    profile.input_format = input_format;
+    profile.output_format = output_format;
+    args.pcm.apply_to(&mut profile);
+    if let Some(preset) = args.preset {
+        profile.preset = Some(preset);
+    }
+    if let Some(bitrate) = args.bitrate {
+        profile.mp3.rate_mode = Some(mp3_rate_mode(bitrate)?);
+    }
+    if let Some(threads) = args.threads {
+        profile.mp3.threads = threads;
+    }
+    if args.gapless {
+        profile.mp3.gapless = true;
+    }
+
+    let options = ConvertOptions::new().with_overwrite(args.overwrite.into());
+    let report = profile.convert_with_options(&args.input, &args.output, options)?;
+
+    if report.skipped {
+        println!("跳过: {} 已存在", report.output_path.display());
+    } else {
+        println!(
+            "{} -> {}: {} 字节, 时长 {:.3}s, {}, 耗时 {:.3}s",
+            args.input.display(),
+            report.output_path.display(),
+            report.output_bytes,
+            report.duration.as_secs_f64(),
+            describe_config(&report.config_used),
+            report.elapsed.as_secs_f64()
+        );
+    }
+    Ok(())
+}
+
+fn probe(args: ProbeArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let info = probe_audio_with_config(&args.input, &args.pcm.config_for(&args.input)?)?;
+    if args.json {
+        println!("{}", info.to_json());
+        return Ok(());
+    }
+
+    println!("文件: {}", args.input.display());
+    println!("格式: {:?}", info.format);
+    if let Some(config) = &info.config {
+        println!("参数: {}", describe_config(config));
+    }
+    if let Some(duration) = info.duration {
+        println!("时长: {:.3}s", duration.as_secs_f64());
+    }
+    println!("大小: {} 字节（音频数据 {} 字节）", info.file_size, info.data_size);
+    for chunk in &info.chunks {
+        println!("  块 '{}': 偏移 {}, {} 字节", chunk.id, chunk.offset, chunk.size);
+    }
+    Ok(())
+}
+
+/// glob 模式中不含通配符的目录前缀，用于计算 {dir}
+fn glob_base(pattern: &str) -> PathBuf {
+    Path::new(pattern)
+        .components()
+        .take_while(|component| !component.as_os_str().to_string_lossy().contains(['*', '?', '[']))
+        .collect()
+}
+
+/// 按模板生成输出路径
+fn expand_template(template: &str, input: &Path, base: &Path, extension: &str) -> PathBuf {
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    let name = input.file_name().unwrap_or_default().to_string_lossy();
+    let dir = input.parent().and_then(|parent| parent.strip_prefix(base).ok()).unwrap_or(Path::new(""));
+    let dir = dir.to_string_lossy();
+    // 文件位于模式的固定前缀中时 {dir} 为空，去掉多余的路径分隔符
+    let template = if dir.is_empty() { template.replace("{dir}/", "").replace("{dir}\\", "") } else { template.to_string() };
+    PathBuf::from(template.replace("{stem}", &stem).replace("{name}", &name).replace("{ext}", extension).replace("{dir}", &dir))
+}
+
+/// 按 glob 模式和输出模板生成 (输入, 输出) 列表
+fn glob_jobs(pattern: &str, output_dir: Option<&Path>, template: Option<&str>, extension: &str) -> Result<Vec<(PathBuf, PathBuf)>, Box<dyn std::error::Error>> {
+    let template = match (output_dir, template) {
+        (None, None) => return Err("An output directory or --out template is required".into()),
+        (output_dir, template) => {
+            let template = template.unwrap_or("{dir}/{stem}.{ext}");
+            output_dir.map_or(template.to_string(), |dir| dir.join(template).to_string_lossy().into_owned())
+        }
+    };
+    let base = glob_base(pattern);
+    let mut jobs = Vec::new();
+    for entry in glob::glob(pattern)? {
+        let input = entry?;
+        if input.is_file() {
+            let output = expand_template(&template, &input, &base, extension);
+            jobs.push((input, output));
+        }
+    }
+    if jobs.is_empty() {
+        return Err(format!("No files match {}", pattern).into());
+    }
+    Ok(jobs)
+}
+
+/// 打印批量转换结果表格
+fn print_batch_table(report: &BatchReport) {
+    let width = report.items.iter().map(|item| item.input.display().to_string().chars().count()).max().unwrap_or(0).max(4);
+    // 中文表头每个字符占两列
+    println!("状态  {:<width$}  输出 / 错误", "输入", width = width - 2);
+    for item in &report.items {
+        let (status, detail) = match &item.result {
+            Ok(_) if item.skipped => ("跳过", item.output.display().to_string()),
+            Ok(config) => ("成功", format!("{}  ({})", item.output.display(), describe_config(config))),
+            Err(e) => ("失败", e.clone()),
+        };
+        println!("{}  {:<width$}  {}", status, item.input.display().to_string(), detail, width = width);
+    }
+}
+
+fn batch(args: BatchArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let format: AudioFormat = args.to.into();
+    let extension = match args.to {
+        Format::Wav => "wav",
+        Format::Mp3 => "mp3",
+        Format::Pcm => "pcm",
+    };
+    let mut options = BatchOptions::new(format)
+        .with_parallelism(args.jobs)
+        .with_recursive(args.recursive)
+        .with_resume(args.resume)
+        .with_overwrite(args.overwrite.into());
+    if args.if_changed {
+        options = options.with_policy(ConvertPolicy::IfChanged);
+    }
+    if let Some(manifest) = args.manifest {
+        options = options.with_manifest(manifest);
+    }
+    if let Some(preset) = args.preset {
+        options = options.with_preset(preset);
+    }
+
+    let input_dir = Path::new(&args.input);
+    let report = match &args.output_dir {
+        Some(output_dir) if input_dir.is_dir() && args.out.is_none() => batch_convert_directory(input_dir, output_dir, &options)?,
+        output_dir => {
+            let jobs = glob_jobs(&args.input, output_dir.as_deref(), args.out.as_deref(), extension)?;
+            batch_convert_files(&jobs, &options)?
+        }
+    };
+
+    print_batch_table(&report);
+    println!(
+        "共 {} 个文件: 成功 {} 个（跳过 {} 个），失败 {} 个",
+        report.items.len(),
+        report.succeeded(),
+        report.skipped(),
+        report.items.len() - report.succeeded()
+    );
+    if report.succeeded() < report.items.len() {
+        return Err("Some files failed to convert".into());
+    }
+    Ok(())
+}
+
+fn infer(args: InferArgs) -> Result<(), Box<dyn std::error::Error>> {
+    for filename in &args.filenames {
+        let inferred = infer_audio_config_with_confidence(filename);
+        println!("{}: {}（置信度 {:.0}%）", filename, describe_config(&inferred.config), inferred.confidence * 100.0);
+        if !inferred.matched_tokens.is_empty() {
+            println!("  识别依据: {}", inferred.matched_tokens.join(", "));
+        }
+        // 置信度低时列出其他可能的参数，提示用户用 --sample-rate / --channels 指定
+        if inferred.confidence < 0.5 {
+            for (config, confidence) in inferred.alternatives.iter().take(3) {
+                println!("  也可能是: {}（置信度 {:.0}%）", describe_config(config), confidence * 100.0);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "playback")]
+fn play(args: PlayArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let config = args.pcm.config_for(&args.input)?;
+    println!("播放 {}（PCM 参数: {}）", args.input.display(), describe_config(&config));
+    audio_helper::play_file_with_config(&args.input, &config)
+}
+
+fn main() -> ExitCode {
+    let result = match Cli::parse().command {
+        Command::Convert(args) => convert(args),
+        Command::Probe(args) => probe(args),
+        Command::Batch(args) => batch(args),
+        Command::Infer(args) => infer(args),
+        #[cfg(feature = "playback")]
+        Command::Play(args) => play(args),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
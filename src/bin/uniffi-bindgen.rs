@@ -0,0 +1,5 @@
+// 生成 UniFFI 绑定：cargo run --features uniffi --bin uniffi-bindgen -- generate --library <库文件> --language kotlin --out-dir <目录>
+
+fn main() {
+    uniffi::uniffi_bindgen_main()
+}
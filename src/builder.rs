@@ -0,0 +1,318 @@
+// 配置构建器：按字段名链式设置参数，`build` 时统一校验，避免位置参数的 `new()` 调用传错顺序
+
+use crate::{
+    mp3, AudioConfig, AudioError, AudioQuality, ChannelMap, Endianness, FadeConfig, LimiterConfig, Mp3Bitrate, Mp3ChannelMode, Mp3Config, Mp3RateMode, SampleFormat, VbrQuality,
+};
+
+/// 记录构建器中第一个出错的设置，`build` 时作为 `AudioError::InvalidConfig` 返回
+///
+/// 只保存错误信息，构建器因此仍可 `Clone` 和比较
+fn first_error(error: &mut Option<String>, message: String) {
+    error.get_or_insert(message);
+}
+
+/// `Mp3Config` 的构建器，通过 `Mp3Config::builder()` 创建
+///
+/// ```
+/// use audio_helper::{Mp3Bitrate, Mp3Config};
+///
+/// let config = Mp3Config::builder().sample_rate(48000).channels(1).bitrate(Mp3Bitrate::Kbps192).build().unwrap();
+/// assert_eq!(config.sample_rate, 48000);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mp3ConfigBuilder {
+    config: Mp3Config,
+    /// 是否显式设置过码率模式
+    rate_mode_set: bool,
+    error: Option<String>,
+}
+
+impl Mp3ConfigBuilder {
+    /// 以默认配置（44.1kHz 立体声）为基础创建构建器
+    ///
+    /// 未设置码率时按采样率选择固定码率：MPEG-1 采样率为 192kbps，更低的采样率为 128kbps
+    pub fn new() -> Self {
+        Mp3ConfigBuilder {
+            config: Mp3Config::default(),
+            rate_mode_set: false,
+            error: None,
+        }
+    }
+
+    /// 采样率（Hz）
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.config.sample_rate = sample_rate;
+        self
+    }
+
+    /// 输入声道数
+    pub fn channels(mut self, channels: u8) -> Self {
+        self.config.channels = channels;
+        self
+    }
+
+    /// 固定码率
+    pub fn bitrate(mut self, bitrate: Mp3Bitrate) -> Self {
+        self.config.rate_mode = Mp3RateMode::Cbr(bitrate);
+        self.rate_mode_set = true;
+        self
+    }
+
+    /// 平均码率（kbps，8-320）
+    pub fn abr(mut self, kbps: u32) -> Self {
+        self.config.rate_mode = Mp3RateMode::Abr(kbps);
+        self.rate_mode_set = true;
+        self
+    }
+
+    /// 可变码率，质量等级 0（最好）到 9（最小）
+    pub fn vbr(mut self, level: u8) -> Self {
+        match VbrQuality::from_level(level) {
+            Some(quality) => {
+                self.config.rate_mode = Mp3RateMode::Vbr(quality);
+                self.rate_mode_set = true;
+            }
+            None => first_error(&mut self.error, format!("Invalid vbr: quality level {} (expected 0-9)", level)),
+        }
+        self
+    }
+
+    /// 码率模式
+    pub fn rate_mode(mut self, rate_mode: Mp3RateMode) -> Self {
+        self.config.rate_mode = rate_mode;
+        self.rate_mode_set = true;
+        self
+    }
+
+    /// 编码质量
+    pub fn quality(mut self, quality: AudioQuality) -> Self {
+        self.config.quality = quality;
+        self
+    }
+
+    /// 声道模式
+    pub fn channel_mode(mut self, channel_mode: Mp3ChannelMode) -> Self {
+        self.config.channel_mode = Some(channel_mode);
+        self
+    }
+
+    /// 采样率不被 MP3 支持时是否自动重采样
+    pub fn resample(mut self, resample: bool) -> Self {
+        self.config.resample = resample;
+        self
+    }
+
+    /// 编码输出的采样率（Hz）
+    pub fn target_sample_rate(mut self, target_sample_rate: u32) -> Self {
+        self.config.target_sample_rate = Some(target_sample_rate);
+        self
+    }
+
+    /// 输入 PCM 的样本格式
+    pub fn sample_format(mut self, sample_format: SampleFormat) -> Self {
+        self.config.sample_format = sample_format;
+        self
+    }
+
+    /// 输入 PCM 的位深度，按整数样本格式解释
+    pub fn bits(mut self, bits_per_sample: u16) -> Self {
+        match SampleFormat::from_bits(bits_per_sample) {
+            Some(sample_format) => self.config.sample_format = sample_format,
+            None => first_error(&mut self.error, format!("Invalid bits: {} (expected 8, 16, 24 or 32)", bits_per_sample)),
+        }
+        self
+    }
+
+    /// 输入 PCM 的字节序
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.config.endianness = endianness;
+        self
+    }
+
+    /// 声道映射，在其他样本处理之前应用
+    pub fn channel_map(mut self, channel_map: ChannelMap) -> Self {
+        self.config.channel_map = Some(channel_map);
+        self
+    }
+
+    /// 编码前转换到的声道数
+    pub fn target_channels(mut self, target_channels: u8) -> Self {
+        self.config.target_channels = Some(target_channels);
+        self
+    }
+
+    /// 增益（dB）
+    pub fn gain_db(mut self, gain_db: f32) -> Self {
+        self.config.gain_db = Some(gain_db);
+        self
+    }
+
+    /// 峰值归一化的目标电平（dBFS）
+    pub fn normalize_db(mut self, normalize_db: f32) -> Self {
+        self.config.normalize_db = Some(normalize_db);
+        self
+    }
+
+    /// 淡入淡出
+    pub fn fade(mut self, fade: FadeConfig) -> Self {
+        self.config.fade = Some(fade);
+        self
+    }
+
+    /// 是否去除直流偏移
+    pub fn dc_removal(mut self, remove_dc: bool) -> Self {
+        self.config.remove_dc = remove_dc;
+        self
+    }
+
+    /// 峰值限制器
+    pub fn limiter(mut self, limiter: LimiterConfig) -> Self {
+        self.config.limiter = Some(limiter);
+        self
+    }
+
+    /// 编码线程数，0 为使用全部可用核心
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.config.threads = threads;
+        self
+    }
+
+    /// 是否无缝编码（在 LAME 标签中记录编码器延迟和末尾填充）
+    pub fn gapless(mut self, gapless: bool) -> Self {
+        self.config.gapless = gapless;
+        self
+    }
+
+    /// 校验参数并创建配置，错误信息中包含出错的字段名
+    pub fn build(self) -> Result<Mp3Config, Box<dyn std::error::Error>> {
+        if let Some(error) = self.error {
+            return Err(AudioError::InvalidConfig(error).into());
+        }
+        let mut config = self.config;
+        if !self.rate_mode_set {
+            config.rate_mode = Mp3RateMode::Cbr(mp3::default_bitrate(config.output_sample_rate()));
+        }
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+impl Default for Mp3ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// `AudioConfig` 的构建器，通过 `AudioConfig::builder()` 创建
+///
+/// ```
+/// use audio_helper::AudioConfig;
+///
+/// let config = AudioConfig::builder().sample_rate(16000).channels(1).bits(24).build().unwrap();
+/// assert_eq!(config.bits_per_sample, 24);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioConfigBuilder {
+    config: AudioConfig,
+    error: Option<String>,
+}
+
+impl AudioConfigBuilder {
+    /// 以默认配置（44.1kHz 立体声 16 位）为基础创建构建器
+    pub fn new() -> Self {
+        AudioConfigBuilder {
+            config: AudioConfig::default(),
+            error: None,
+        }
+    }
+
+    /// 采样率（Hz）
+    pub fn sample_rate(mut self, sample_rate: u32) -> Self {
+        self.config.sample_rate = sample_rate;
+        self
+    }
+
+    /// 声道数
+    pub fn channels(mut self, channels: u8) -> Self {
+        self.config.channels = channels;
+        self
+    }
+
+    /// 位深度，按整数样本格式解释
+    pub fn bits(mut self, bits_per_sample: u16) -> Self {
+        match SampleFormat::from_bits(bits_per_sample) {
+            Some(sample_format) => self.config = self.config.with_sample_format(sample_format),
+            None => first_error(&mut self.error, format!("Invalid bits: {} (expected 8, 16, 24 or 32)", bits_per_sample)),
+        }
+        self
+    }
+
+    /// 样本格式（同时更新位深度）
+    pub fn sample_format(mut self, sample_format: SampleFormat) -> Self {
+        self.config = self.config.with_sample_format(sample_format);
+        self
+    }
+
+    /// 原始 PCM 数据的字节序
+    pub fn endianness(mut self, endianness: Endianness) -> Self {
+        self.config.endianness = endianness;
+        self
+    }
+
+    /// 声道映射，在其他样本处理之前应用
+    pub fn channel_map(mut self, channel_map: ChannelMap) -> Self {
+        self.config.channel_map = Some(channel_map);
+        self
+    }
+
+    /// 转换时输出的声道数
+    pub fn target_channels(mut self, target_channels: u8) -> Self {
+        self.config.target_channels = Some(target_channels);
+        self
+    }
+
+    /// 增益（dB）
+    pub fn gain_db(mut self, gain_db: f32) -> Self {
+        self.config.gain_db = Some(gain_db);
+        self
+    }
+
+    /// 峰值归一化的目标电平（dBFS）
+    pub fn normalize_db(mut self, normalize_db: f32) -> Self {
+        self.config.normalize_db = Some(normalize_db);
+        self
+    }
+
+    /// 淡入淡出
+    pub fn fade(mut self, fade: FadeConfig) -> Self {
+        self.config.fade = Some(fade);
+        self
+    }
+
+    /// 是否去除直流偏移
+    pub fn dc_removal(mut self, remove_dc: bool) -> Self {
+        self.config.remove_dc = remove_dc;
+        self
+    }
+
+    /// 峰值限制器
+    pub fn limiter(mut self, limiter: LimiterConfig) -> Self {
+        self.config.limiter = Some(limiter);
+        self
+    }
+
+    /// 校验参数并创建配置，错误信息中包含出错的字段名
+    pub fn build(self) -> Result<AudioConfig, Box<dyn std::error::Error>> {
+        if let Some(error) = self.error {
+            return Err(AudioError::InvalidConfig(error).into());
+        }
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+impl Default for AudioConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
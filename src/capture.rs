@@ -0,0 +1,377 @@
+// 录音：从默认输入设备采集音频，边录边写入 WAV（停止时回填文件头）或实时编码为 MP3
+
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{SizedSample, StreamConfig};
+
+use crate::atomic::AtomicFile;
+use crate::{
+    dsp, frames_duration, mp3, sample, AudioConfig, AudioEncoder, AudioError, AudioFormat, AudioQuality, ConversionReport,
+    Mp3Bitrate, Mp3Config, Mp3RateMode, Mp3StreamEncoder, PcmToWavConfig, WavStreamEncoder,
+};
+
+/// 录音输出：WAV 先写入数据大小为 0 的文件头，停止时回填；MP3 采集到数据即编码写入
+enum RecordingSink {
+    Wav(WavStreamEncoder<BufWriter<AtomicFile>>),
+    Mp3(Mp3StreamEncoder<BufWriter<AtomicFile>>),
+}
+
+impl RecordingSink {
+    fn encoder(&mut self) -> &mut dyn AudioEncoder {
+        match self {
+            RecordingSink::Wav(encoder) => encoder,
+            RecordingSink::Mp3(encoder) => encoder,
+        }
+    }
+
+    fn into_inner(self) -> BufWriter<AtomicFile> {
+        match self {
+            RecordingSink::Wav(encoder) => encoder.into_inner(),
+            RecordingSink::Mp3(encoder) => encoder.into_inner(),
+        }
+    }
+}
+
+/// 在写入线程中运行：接收采集到的样本，转换声道、截断到时长上限后写入输出
+struct RecordingWriter {
+    sink: RecordingSink,
+    output_path: PathBuf,
+    format: AudioFormat,
+    /// 输出音频的参数
+    config: AudioConfig,
+    input_channels: u8,
+    frames: u64,
+    max_frames: Option<u64>,
+    started: Instant,
+}
+
+impl RecordingWriter {
+    /// 创建输出文件并写入文件头（MP3 创建编码器）
+    fn create(recorder: &Recorder, sample_rate: u32, input_channels: u8) -> Result<Self, Box<dyn std::error::Error>> {
+        if input_channels == 0 {
+            return Err(AudioError::UnsupportedFormat("Input device reports zero channels".into()).into());
+        }
+        // MP3 最多支持双声道，多声道设备默认混缩为单声道
+        let channels = match (&recorder.format, recorder.target_channels) {
+            (_, Some(target)) => target,
+            (AudioFormat::Mp3, None) if input_channels > 2 => 1,
+            (_, None) => input_channels,
+        };
+        let config = AudioConfig::new(sample_rate, channels, 16);
+
+        let file = AtomicFile::create(&recorder.output_path)?;
+        let writer = BufWriter::new(file);
+        let sink = match recorder.format {
+            AudioFormat::Wav => RecordingSink::Wav(WavStreamEncoder::new(writer, &PcmToWavConfig::new(sample_rate, channels, 16))?),
+            AudioFormat::Mp3 => {
+                if !mp3::is_mp3_sample_rate(sample_rate) {
+                    return Err(AudioError::UnsupportedFormat(format!("Input device sample rate {}Hz is not supported by MP3", sample_rate)).into());
+                }
+                let mp3_config = Mp3Config::new(sample_rate, channels, Mp3Bitrate::Kbps128, recorder.quality.clone())
+                    .with_rate_mode(recorder.rate_mode.clone());
+                RecordingSink::Mp3(Mp3StreamEncoder::new(writer, &mp3_config)?)
+            }
+            AudioFormat::Pcm => return Err(AudioError::UnsupportedFormat("Recording supports WAV and MP3 output only".into()).into()),
+        };
+
+        Ok(RecordingWriter {
+            sink,
+            output_path: recorder.output_path.clone(),
+            format: recorder.format.clone(),
+            config,
+            input_channels,
+            frames: 0,
+            max_frames: recorder.max_duration.map(|duration| (duration.as_secs_f64() * sample_rate as f64).round() as u64),
+            started: Instant::now(),
+        })
+    }
+
+    /// 写入一块交错样本
+    ///
+    /// # Returns
+    /// 达到时长上限后返回 false，之后的数据不再写入
+    fn write(&mut self, samples: &[f32]) -> Result<bool, Box<dyn std::error::Error>> {
+        let input_channels = self.input_channels as usize;
+        let mut frames = (samples.len() / input_channels) as u64;
+        if let Some(max_frames) = self.max_frames {
+            frames = frames.min(max_frames - self.frames);
+        }
+        let samples = &samples[..frames as usize * input_channels];
+        let converted = dsp::convert_channels(samples, self.input_channels, self.config.channels)?;
+
+        let converted: Vec<i16> = converted.into_iter().map(sample::f32_to_i16).collect();
+        self.sink.encoder().encode(&converted)?;
+
+        self.frames += frames;
+        Ok(self.max_frames.is_none_or(|max_frames| self.frames < max_frames))
+    }
+
+    /// 接收采集线程送来的数据直到录音停止或达到时长上限
+    fn run(mut self, receiver: Receiver<Vec<f32>>, finished: Arc<AtomicBool>) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        let result = (|| {
+            for samples in receiver {
+                if !self.write(&samples)? {
+                    break;
+                }
+            }
+            self.finish()
+        })();
+        finished.store(true, Ordering::SeqCst);
+        result
+    }
+
+    /// 回填 WAV 文件头或 MP3 标签帧，提交输出文件
+    fn finish(mut self) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        self.sink.encoder().finish()?;
+        self.sink.into_inner().into_inner().map_err(|e| e.into_error())?.commit()?;
+
+        Ok(ConversionReport {
+            input_bytes: self.frames * self.input_channels as u64 * 2,
+            output_bytes: std::fs::metadata(&self.output_path)?.len(),
+            duration: frames_duration(self.frames, self.config.sample_rate),
+            elapsed: self.started.elapsed(),
+            config_used: self.config,
+            format: self.format,
+            output_path: self.output_path,
+            skipped: false,
+        })
+    }
+}
+
+/// 正在进行的录音
+struct ActiveRecording {
+    stream: cpal::Stream,
+    writer: JoinHandle<Result<ConversionReport, Box<dyn std::error::Error + Send + Sync>>>,
+    finished: Arc<AtomicBool>,
+}
+
+/// 从默认输入设备录音到 WAV 或 MP3 文件
+///
+/// 使用设备的默认采样率和声道数，样本以 16 位写入。输出先写入临时文件，
+/// 停止后才重命名为目标文件。
+pub struct Recorder {
+    output_path: PathBuf,
+    format: AudioFormat,
+    max_duration: Option<Duration>,
+    target_channels: Option<u8>,
+    rate_mode: Mp3RateMode,
+    quality: AudioQuality,
+    active: Option<ActiveRecording>,
+}
+
+impl Recorder {
+    /// 创建录音器
+    ///
+    /// # Arguments
+    /// * `output_path` - 输出文件路径
+    /// * `format` - 输出格式（`AudioFormat::Wav` 或 `AudioFormat::Mp3`）
+    pub fn new(output_path: impl AsRef<Path>, format: AudioFormat) -> Self {
+        Recorder {
+            output_path: output_path.as_ref().to_path_buf(),
+            format,
+            max_duration: None,
+            target_channels: None,
+            rate_mode: Mp3RateMode::Cbr(Mp3Bitrate::Kbps128),
+            quality: AudioQuality::High,
+            active: None,
+        }
+    }
+
+    /// 设置录音时长上限，达到后自动停止写入
+    pub fn with_max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// 设置输出声道数，为 None 时保持设备声道数（MP3 输出在设备多于两个声道时混缩为单声道）
+    pub fn with_target_channels(mut self, target_channels: u8) -> Self {
+        self.target_channels = Some(target_channels);
+        self
+    }
+
+    /// 设置 MP3 码率模式（默认 128kbps CBR）
+    pub fn with_rate_mode(mut self, rate_mode: Mp3RateMode) -> Self {
+        self.rate_mode = rate_mode;
+        self
+    }
+
+    /// 设置 MP3 编码质量
+    pub fn with_quality(mut self, quality: AudioQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// 打开默认输入设备开始录音，立即返回
+    pub fn start(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.active.is_some() {
+            return Err(AudioError::InvalidInput("Recording already in progress".into()).into());
+        }
+
+        let input = InputDevice::open_default()?;
+        let writer = RecordingWriter::create(self, input.sample_rate, input.channels)?;
+        log::info!(
+            "Recording {}Hz {}ch from default input device to {}",
+            input.sample_rate,
+            input.channels,
+            self.output_path.display()
+        );
+
+        // 无界通道：音频回调只做格式转换和发送，不会因为写入线程而阻塞
+        let (sender, receiver) = mpsc::channel();
+        let stream = input.build_stream(move |samples| {
+            // 写入线程达到时长上限后已结束，之后的数据直接丢弃
+            let _ = sender.send(samples);
+        })?;
+
+        let finished = Arc::new(AtomicBool::new(false));
+        let writer_finished = Arc::clone(&finished);
+        let writer = std::thread::spawn(move || writer.run(receiver, writer_finished).map_err(crate::error::into_send));
+        stream.play()?;
+
+        self.active = Some(ActiveRecording { stream, writer, finished });
+        Ok(())
+    }
+
+    /// 是否正在录音（已开始且尚未停止）
+    pub fn is_recording(&self) -> bool {
+        self.active.is_some()
+    }
+
+    /// 是否已达到时长上限（或写入出错），此时应调用 `stop` 取得结果
+    pub fn is_finished(&self) -> bool {
+        self.active.as_ref().is_some_and(|active| active.finished.load(Ordering::SeqCst))
+    }
+
+    /// 阻塞直到达到时长上限，然后停止录音
+    ///
+    /// # Returns
+    /// 录音结果统计，未设置时长上限时返回错误
+    pub fn wait(&mut self) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        if self.max_duration.is_none() {
+            return Err(AudioError::InvalidInput("Recorder::wait requires a duration limit".into()).into());
+        }
+        let active = self.active.take().ok_or_else(|| AudioError::InvalidInput("Recording has not been started".into()))?;
+        let report = active.writer.join().map_err(|_| AudioError::Encoder("Recording thread panicked".into()))?;
+        drop(active.stream);
+        report.map_err(|e| e as Box<dyn std::error::Error>)
+    }
+
+    /// 停止录音，完成输出文件
+    ///
+    /// # Returns
+    /// 录音结果统计（时长、输出大小和实际使用的参数）
+    pub fn stop(&mut self) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        let active = self.active.take().ok_or_else(|| AudioError::InvalidInput("Recording has not been started".into()))?;
+        // 关闭输入流后发送端随回调一起释放，写入线程收完剩余数据后结束
+        drop(active.stream);
+        let report = active.writer.join().map_err(|_| AudioError::Encoder("Recording thread panicked".into()))?;
+        report.map_err(|e| e as Box<dyn std::error::Error>)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        if self.active.is_some()
+            && let Err(e) = self.stop()
+        {
+            log::error!("Failed to finish recording {}: {}", self.output_path.display(), e);
+        }
+    }
+}
+
+/// 默认输入设备及其默认采集参数
+pub(crate) struct InputDevice {
+    device: cpal::Device,
+    supported: cpal::SupportedStreamConfig,
+    pub sample_rate: u32,
+    pub channels: u8,
+}
+
+impl InputDevice {
+    /// 打开默认输入设备，使用设备的默认采样率和声道数
+    pub fn open_default() -> Result<Self, Box<dyn std::error::Error>> {
+        let host = cpal::default_host();
+        let device = host.default_input_device().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "No input device available"))?;
+        let supported = device.default_input_config()?;
+        let channels = u8::try_from(supported.channels())
+            .map_err(|_| AudioError::UnsupportedFormat(format!("Unsupported channel count: {}", supported.channels())))?;
+        Ok(InputDevice {
+            device,
+            sample_rate: supported.sample_rate(),
+            channels,
+            supported,
+        })
+    }
+
+    /// 创建输入流（尚未开始采集），回调收到的是转换为 f32 的交错样本
+    pub fn build_stream(&self, on_data: impl FnMut(Vec<f32>) + Send + 'static) -> Result<cpal::Stream, Box<dyn std::error::Error>> {
+        let config = self.supported.config();
+        match self.supported.sample_format() {
+            cpal::SampleFormat::F32 => build_input_stream::<f32>(&self.device, config, on_data),
+            cpal::SampleFormat::I16 => build_input_stream::<i16>(&self.device, config, on_data),
+            cpal::SampleFormat::I32 => build_input_stream::<i32>(&self.device, config, on_data),
+            cpal::SampleFormat::U16 => build_input_stream::<u16>(&self.device, config, on_data),
+            cpal::SampleFormat::I8 => build_input_stream::<i8>(&self.device, config, on_data),
+            cpal::SampleFormat::U8 => build_input_stream::<u8>(&self.device, config, on_data),
+            other => Err(AudioError::UnsupportedFormat(format!("Unsupported input sample format: {}", other)).into()),
+        }
+    }
+}
+
+/// 创建输入流，回调中将样本转换为 f32 后交给 `on_data`
+fn build_input_stream<T>(device: &cpal::Device, config: StreamConfig, mut on_data: impl FnMut(Vec<f32>) + Send + 'static) -> Result<cpal::Stream, Box<dyn std::error::Error>>
+where
+    T: SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    let stream = device.build_input_stream(
+        config,
+        move |data: &[T], _: &cpal::InputCallbackInfo| on_data(data.iter().map(|&sample| sample.to_sample::<f32>()).collect()),
+        |e| log::error!("Input stream error: {}", e),
+        None,
+    )?;
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_writer_wav_and_mp3() {
+        let samples: Vec<f32> = (0..48000 * 2).map(|i| ((i / 2) as f32 * 0.05).sin() * 0.5).collect();
+
+        // WAV：达到时长上限后截断，停止时回填数据大小
+        let recorder = Recorder::new("capture_unit_test.wav", AudioFormat::Wav).with_max_duration(Duration::from_millis(500));
+        let mut writer = RecordingWriter::create(&recorder, 48000, 2).unwrap();
+        assert!(!writer.write(&samples).unwrap());
+        let report = writer.finish().unwrap();
+        assert_eq!(report.duration, Duration::from_millis(500));
+        let audio = crate::read_wav_file("capture_unit_test.wav").unwrap();
+        assert_eq!(audio.frames(), 24000);
+        assert_eq!(audio.config.channels, 2);
+        assert_eq!(report.output_bytes, 44 + 24000 * 4);
+
+        // MP3：多块数据逐块编码，声道按设置混缩
+        let recorder = Recorder::new("capture_unit_test.mp3", AudioFormat::Mp3).with_target_channels(1);
+        let mut writer = RecordingWriter::create(&recorder, 48000, 2).unwrap();
+        for chunk in samples.chunks(960) {
+            assert!(writer.write(chunk).unwrap());
+        }
+        let report = writer.finish().unwrap();
+        assert_eq!(report.config_used.channels, 1);
+        let duration = crate::audio_duration("capture_unit_test.mp3").unwrap();
+        assert!((duration.as_secs_f64() - 1.0).abs() < 0.05, "MP3 时长: {:?}", duration);
+
+        assert!(RecordingWriter::create(&Recorder::new("capture_unit_test.pcm", AudioFormat::Pcm), 48000, 2).is_err());
+        let _ = std::fs::remove_file("capture_unit_test.wav");
+        let _ = std::fs::remove_file("capture_unit_test.mp3");
+    }
+}
@@ -0,0 +1,535 @@
+// 编解码器抽象：编码器接收交错的 i16 样本写入输出，解码器从输入中读出交错的 i16 样本
+//
+// WAV 和 MP3 是内置实现；其他格式（FLAC、Opus、AAC 等）实现同样的 trait 即可与现有的转换流程组合，
+// 下游 crate 也可以用它接入自己的编解码器。
+
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::{adpcm, mp3, sample, wav, AudioConfig, AudioError, Endianness, Mp3Config, PcmToWavConfig, Sample, SampleFormat, WavParseMode};
+
+/// 解码器每次读取的采样帧数
+const DECODE_CHUNK_FRAMES: usize = 4096;
+
+/// `encode_iter` 合并小块输入时每次送入编码器的最少样本数
+const ENCODE_ITER_BLOCK_SAMPLES: usize = 8192;
+
+/// 音频编码器：按顺序送入交错的 16 位样本，最后调用 `finish` 完成输出
+pub trait AudioEncoder {
+    /// 编码一块交错样本，样本数应为声道数的整数倍
+    fn encode(&mut self, samples: &[i16]) -> Result<(), Box<dyn std::error::Error>>;
+
+    /// 输出剩余数据并回填文件头或标签，返回写入的总字节数；之后不能再调用 `encode`，重复调用直接返回
+    fn finish(&mut self) -> Result<u64, Box<dyn std::error::Error>>;
+
+    /// 编码一块任意样本类型（i16 / i32 / f32）的交错样本
+    ///
+    /// 默认转换为 i16 后调用 `encode`；能保存更高精度的编码器（如 32 位或浮点 WAV）直接写入，不经过 i16
+    fn encode_as<S: Sample>(&mut self, samples: &[S]) -> Result<(), Box<dyn std::error::Error>>
+    where
+        Self: Sized,
+    {
+        self.encode(&sample::convert_samples(samples))
+    }
+
+    /// 从迭代器逐块取出样本编码并完成输出，返回写入的总字节数
+    ///
+    /// 每项可以是一个采样帧（如 `[i16; 2]`）或一块交错样本（`&[i16]`、`Vec<i16>`），样本按需生成或解码，
+    /// 不需要先得到完整的信号；较小的项会合并后再送入编码器
+    /// # Arguments
+    /// * `frames` - 交错样本块的迭代器，每项的样本数应为声道数的整数倍
+    fn encode_iter<I>(&mut self, frames: I) -> Result<u64, Box<dyn std::error::Error>>
+    where
+        Self: Sized,
+        I: IntoIterator,
+        I::Item: AsRef<[i16]>,
+    {
+        let mut block = Vec::with_capacity(ENCODE_ITER_BLOCK_SAMPLES);
+        for frame in frames {
+            let samples = frame.as_ref();
+            if block.is_empty() && samples.len() >= ENCODE_ITER_BLOCK_SAMPLES {
+                self.encode(samples)?;
+                continue;
+            }
+            block.extend_from_slice(samples);
+            if block.len() >= ENCODE_ITER_BLOCK_SAMPLES {
+                self.encode(&block)?;
+                block.clear();
+            }
+        }
+        if !block.is_empty() {
+            self.encode(&block)?;
+        }
+        self.finish()
+    }
+}
+
+/// 音频解码器：逐块读出交错的 16 位样本
+pub trait AudioDecoder {
+    /// 解码输出的音频参数（采样率和声道数，样本总是 16 位）
+    fn config(&self) -> AudioConfig;
+
+    /// 解码下一块样本并追加到 `samples`，返回追加的样本数；返回 0 表示已经到达末尾
+    fn decode(&mut self, samples: &mut Vec<i16>) -> Result<usize, Box<dyn std::error::Error>>;
+
+    /// 解码剩余的全部样本
+    fn decode_to_end(&mut self) -> Result<Vec<i16>, Box<dyn std::error::Error>> {
+        let mut samples = Vec::new();
+        while self.decode(&mut samples)? > 0 {}
+        Ok(samples)
+    }
+}
+
+impl<E: AudioEncoder + ?Sized> AudioEncoder for Box<E> {
+    fn encode(&mut self, samples: &[i16]) -> Result<(), Box<dyn std::error::Error>> {
+        (**self).encode(samples)
+    }
+
+    fn finish(&mut self) -> Result<u64, Box<dyn std::error::Error>> {
+        (**self).finish()
+    }
+}
+
+impl<D: AudioDecoder + ?Sized> AudioDecoder for Box<D> {
+    fn config(&self) -> AudioConfig {
+        (**self).config()
+    }
+
+    fn decode(&mut self, samples: &mut Vec<i16>) -> Result<usize, Box<dyn std::error::Error>> {
+        (**self).decode(samples)
+    }
+}
+
+// ==================== WAV ====================
+
+/// 边写边编码的 WAV 编码器：先写入数据大小为 0 的文件头，`finish` 时回填
+///
+/// 样本按配置的样本格式写入（默认 16 位），`encode_as` 送入的 i32 / f32 样本直接转换为该格式而不经过 i16；不支持 IMA ADPCM 和声道转换、增益等样本处理
+pub struct WavStreamEncoder<W: Write + Seek> {
+    writer: W,
+    header: wav::WavHeader,
+    /// 文件头在输出中的起始位置
+    start: u64,
+    /// 未指定容器：RIFF 头中预留 ds64 的位置，数据超过 4 GB 时在 `finish` 中升级为 RF64
+    promotable: bool,
+    finished: bool,
+}
+
+impl<W: Write + Seek> WavStreamEncoder<W> {
+    /// 在 `writer` 的当前位置写入文件头，创建编码器
+    /// # Arguments
+    /// * `writer` - 输出，需要支持回到文件头
+    /// * `config` - 采样率、声道数、样本格式和容器格式，容器为 None 时使用 RIFF，数据超过 4 GB 时升级为 RF64
+    pub fn new(writer: W, config: &PcmToWavConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let header = Self::header_for(config)?;
+        Self::with_header(writer, header, config.container.is_none())
+    }
+
+    /// 校验配置并生成数据大小为 0 的文件头
+    fn header_for(config: &PcmToWavConfig) -> Result<wav::WavHeader, Box<dyn std::error::Error>> {
+        config.validate()?;
+        if config.ima_adpcm {
+            return Err(AudioError::InvalidConfig("WavStreamEncoder does not support IMA ADPCM output".into()).into());
+        }
+        let channels = config.channels.unwrap_or(2);
+        if !config.processing().is_identity(channels) {
+            return Err(AudioError::InvalidConfig("WavStreamEncoder does not apply sample processing, process the samples before encoding".into()).into());
+        }
+        let sample_format = config.input_sample_format()?;
+        Ok(wav::WavHeader {
+            sample_rate: config.sample_rate.unwrap_or(44100),
+            channels,
+            bits_per_sample: sample_format.bits_per_sample(),
+            data_size: 0,
+            container: config.container.unwrap_or(wav::WavContainer::Riff),
+            force_extensible: config.force_extensible,
+            sample_format,
+            adpcm: None,
+            channel_mask: config.channel_layout.map(|layout| layout.channel_mask()),
+        })
+    }
+
+    fn with_header(mut writer: W, header: wav::WavHeader, promotable: bool) -> Result<Self, Box<dyn std::error::Error>> {
+        let start = writer.stream_position()?;
+        let mut encoder = WavStreamEncoder { writer, header, start, promotable, finished: false };
+        encoder.write_header()?;
+        Ok(encoder)
+    }
+
+    fn write_header(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if self.promotable {
+            wav::write_promotable_header(&mut self.writer, &self.header)
+        } else {
+            wav::write_wav_header(&mut self.writer, &self.header)
+        }
+    }
+
+    /// 取回输出
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+
+    fn write_data(&mut self, data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        if self.finished {
+            return Err(AudioError::InvalidInput("Encoder has already been finished".into()).into());
+        }
+        self.writer.write_all(data)?;
+        self.header.data_size += data.len() as u64;
+        Ok(())
+    }
+}
+
+impl<W: Write + Seek> AudioEncoder for WavStreamEncoder<W> {
+    fn encode(&mut self, samples: &[i16]) -> Result<(), Box<dyn std::error::Error>> {
+        let data = match self.header.sample_format {
+            SampleFormat::S16 => sample::i16_as_le_bytes(samples),
+            format => sample::encode_samples(samples, format).into(),
+        };
+        self.write_data(&data)
+    }
+
+    fn encode_as<S: Sample>(&mut self, samples: &[S]) -> Result<(), Box<dyn std::error::Error>> {
+        self.write_data(&sample::encode_samples(samples, self.header.sample_format))
+    }
+
+    fn finish(&mut self) -> Result<u64, Box<dyn std::error::Error>> {
+        if !self.finished {
+            self.writer.write_all(&vec![0u8; self.header.padding() as usize])?;
+            let end = self.writer.stream_position()?;
+            self.writer.seek(SeekFrom::Start(self.start))?;
+            self.write_header()?;
+            self.writer.seek(SeekFrom::Start(end))?;
+            self.writer.flush()?;
+            self.finished = true;
+        }
+        Ok(self.writer.stream_position()? - self.start)
+    }
+}
+
+/// 增量写入 WAV 文件：`create` 后多次 `write_frames`，最后 `finalize` 回填文件头中的大小
+///
+/// 没有调用 `finalize` 就被丢弃时（如提前返回或 panic 展开），在 `Drop` 中回填文件头，已写入的样本仍然可以读取
+pub struct WavWriter {
+    encoder: Option<WavStreamEncoder<BufWriter<File>>>,
+    channels: u8,
+    frames: u64,
+}
+
+impl WavWriter {
+    /// 创建 WAV 文件（已存在时覆盖）并写入文件头
+    /// # Arguments
+    /// * `path` - 输出 WAV 文件路径
+    /// * `config` - 采样率、声道数、样本格式和容器格式，不支持 IMA ADPCM 和样本处理
+    pub fn create(path: impl AsRef<Path>, config: &PcmToWavConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        // 先校验配置，配置无效时不动已有的文件
+        let header = WavStreamEncoder::<BufWriter<File>>::header_for(config)?;
+        let existed = path.exists();
+        let file = BufWriter::new(File::create(path)?);
+        // 写文件头失败时只删除本次新建的文件
+        let encoder = WavStreamEncoder::with_header(file, header, config.container.is_none()).inspect_err(|_| {
+            if !existed {
+                let _ = std::fs::remove_file(path);
+            }
+        })?;
+        Ok(WavWriter { encoder: Some(encoder), channels: config.channels.unwrap_or(2), frames: 0 })
+    }
+
+    /// 写入一块交错样本，样本数须为声道数的整数倍
+    pub fn write_frames(&mut self, samples: &[i16]) -> Result<(), Box<dyn std::error::Error>> {
+        if !samples.len().is_multiple_of(self.channels as usize) {
+            return Err(AudioError::InvalidInput(format!("{} samples is not a whole number of {}-channel frames", samples.len(), self.channels)).into());
+        }
+        self.encoder.as_mut().ok_or_else(|| AudioError::InvalidInput("WavWriter has already been finalized".into()))?.encode(samples)?;
+        self.frames += (samples.len() / self.channels as usize) as u64;
+        Ok(())
+    }
+
+    /// 已写入的采样帧数
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// 回填文件头中的 RIFF / data 大小并关闭文件
+    /// # Returns
+    /// * `Result<u64, Box<dyn std::error::Error>>` - 文件的总字节数
+    pub fn finalize(mut self) -> Result<u64, Box<dyn std::error::Error>> {
+        let mut encoder = self.encoder.take().ok_or_else(|| AudioError::InvalidInput("WavWriter has already been finalized".into()))?;
+        let size = encoder.finish()?;
+        encoder.into_inner().into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        Ok(size)
+    }
+}
+
+impl Drop for WavWriter {
+    fn drop(&mut self) {
+        if let Some(mut encoder) = self.encoder.take() {
+            log::warn!("WavWriter dropped without finalize(), patching the header with {} frames", self.frames);
+            if let Err(e) = encoder.finish() {
+                log::error!("Failed to finalize WAV header: {}", e);
+            }
+        }
+    }
+}
+
+/// 逐块读取 WAV 文件的解码器，支持整数 PCM、32 位浮点、A-law / μ-law 和 IMA ADPCM
+pub struct WavDecoder<R: Read + Seek> {
+    reader: R,
+    info: wav::WavInfo,
+    config: AudioConfig,
+    /// data 块中还没有读取的字节数
+    remaining: u64,
+    /// 还没有输出的采样帧数（ADPCM 的最后一块可能被补齐）
+    frames_left: u64,
+    buffer: Vec<u8>,
+}
+
+impl<R: Read + Seek> WavDecoder<R> {
+    /// 解析文件头，定位到 data 块开头；不规范的文件按宽松模式尽量恢复
+    pub fn new(mut reader: R) -> Result<Self, Box<dyn std::error::Error>> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(0))?;
+        let info = wav::read_wav_info(&mut reader, len, WavParseMode::Lenient)?;
+        let config = info.config()?;
+        reader.seek(SeekFrom::Start(info.data_offset))?;
+        Ok(WavDecoder { remaining: info.data_size, frames_left: info.frames(), reader, info, config, buffer: Vec::new() })
+    }
+}
+
+impl<R: Read + Seek> AudioDecoder for WavDecoder<R> {
+    fn config(&self) -> AudioConfig {
+        AudioConfig::new(self.config.sample_rate, self.config.channels, 16)
+    }
+
+    fn decode(&mut self, samples: &mut Vec<i16>) -> Result<usize, Box<dyn std::error::Error>> {
+        let channels = self.config.channels as usize;
+        let block_align = self.info.block_align().max(1) as usize;
+        // ADPCM 按整块读取，其余格式按整帧读取
+        let chunk = if self.info.is_adpcm() { block_align * (DECODE_CHUNK_FRAMES / 256).max(1) } else { block_align * DECODE_CHUNK_FRAMES };
+        let len = (chunk as u64).min(self.remaining) as usize;
+        self.buffer.resize(len, 0);
+        let filled = sample::read_full(&mut self.reader, &mut self.buffer)?;
+        self.remaining = if filled < len { 0 } else { self.remaining - len as u64 };
+        let data = &self.buffer[..filled];
+
+        let before = samples.len();
+        if self.info.is_adpcm() {
+            samples.extend(adpcm::decode_ima_adpcm(data, self.config.channels, self.info.block_align())?);
+        } else {
+            let whole = filled / block_align * block_align;
+            samples.extend(sample::decode_samples_i16(&data[..whole], self.config.sample_format));
+        }
+        let frames = ((samples.len() - before) / channels) as u64;
+        let kept = frames.min(self.frames_left);
+        samples.truncate(before + kept as usize * channels);
+        self.frames_left -= kept;
+        Ok(samples.len() - before)
+    }
+}
+
+/// 逐块读取原始 PCM 数据的解码器，末尾不完整的帧被忽略
+pub struct PcmDecoder<R: Read> {
+    reader: R,
+    config: AudioConfig,
+    buffer: Vec<u8>,
+}
+
+impl<R: Read> PcmDecoder<R> {
+    /// 按配置中的采样率、声道数、样本格式和字节序读取 PCM 数据
+    pub fn new(reader: R, config: AudioConfig) -> Result<Self, Box<dyn std::error::Error>> {
+        config.validate()?;
+        Ok(PcmDecoder { reader, config, buffer: Vec::new() })
+    }
+}
+
+impl<R: Read> AudioDecoder for PcmDecoder<R> {
+    fn config(&self) -> AudioConfig {
+        AudioConfig::new(self.config.sample_rate, self.config.channels, 16)
+    }
+
+    fn decode(&mut self, samples: &mut Vec<i16>) -> Result<usize, Box<dyn std::error::Error>> {
+        let format = self.config.sample_format;
+        let frame_bytes = format.bytes_per_sample() * self.config.channels.max(1) as usize;
+        self.buffer.resize(frame_bytes * DECODE_CHUNK_FRAMES, 0);
+        let filled = sample::read_full(&mut self.reader, &mut self.buffer)?;
+        let data = &mut self.buffer[..filled / frame_bytes * frame_bytes];
+        if self.config.endianness == Endianness::Big {
+            sample::swap_sample_bytes(data, format);
+        }
+        let before = samples.len();
+        samples.extend(sample::decode_samples_i16(data, format));
+        Ok(samples.len() - before)
+    }
+}
+
+// ==================== PCM ====================
+
+/// 输出 16 位小端原始 PCM 的编码器
+pub struct PcmStreamEncoder<W: Write> {
+    writer: W,
+    written: u64,
+}
+
+impl<W: Write> PcmStreamEncoder<W> {
+    /// 创建编码器，样本直接写入 `writer`
+    pub fn new(writer: W) -> Self {
+        PcmStreamEncoder { writer, written: 0 }
+    }
+
+    /// 取回输出
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> AudioEncoder for PcmStreamEncoder<W> {
+    fn encode(&mut self, samples: &[i16]) -> Result<(), Box<dyn std::error::Error>> {
+        let data = sample::i16_as_le_bytes(samples);
+        self.writer.write_all(&data)?;
+        self.written += data.len() as u64;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<u64, Box<dyn std::error::Error>> {
+        self.writer.flush()?;
+        Ok(self.written)
+    }
+}
+
+// ==================== MP3 ====================
+
+/// 边写边编码的 MP3 编码器：码流开头预留 Xing/LAME 标签帧，`finish` 时回填
+///
+/// 采样率必须是 MP3 标准采样率，不做重采样和样本处理
+pub struct Mp3StreamEncoder<W: Write + Seek> {
+    writer: W,
+    encoder: mp3::StreamEncoder,
+    buffer: Vec<u8>,
+    /// 码流在输出中的起始位置
+    start: u64,
+    finished: bool,
+}
+
+impl<W: Write + Seek> Mp3StreamEncoder<W> {
+    /// 在 `writer` 的当前位置开始写入码流，创建编码器
+    /// # Arguments
+    /// * `writer` - 输出，需要支持回到码流开头写入标签帧
+    /// * `config` - MP3 配置，样本格式和字节序被忽略（输入总是 i16 样本）
+    pub fn new(mut writer: W, config: &Mp3Config) -> Result<Self, Box<dyn std::error::Error>> {
+        config.validate()?;
+        if config.output_sample_rate() != config.sample_rate {
+            return Err(AudioError::InvalidConfig(format!("Mp3StreamEncoder does not resample, {}Hz must be resampled before encoding", config.sample_rate)).into());
+        }
+        if !config.processing().is_identity(config.channels) {
+            return Err(AudioError::InvalidConfig("Mp3StreamEncoder does not apply sample processing, process the samples before encoding".into()).into());
+        }
+        let start = writer.stream_position()?;
+        Ok(Mp3StreamEncoder { writer, encoder: mp3::StreamEncoder::new(config, true)?, buffer: Vec::new(), start, finished: false })
+    }
+
+    /// 取回输出
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write + Seek> AudioEncoder for Mp3StreamEncoder<W> {
+    fn encode(&mut self, samples: &[i16]) -> Result<(), Box<dyn std::error::Error>> {
+        if self.finished {
+            return Err(AudioError::InvalidInput("Encoder has already been finished".into()).into());
+        }
+        self.buffer.clear();
+        self.encoder.encode(samples, &mut self.buffer)?;
+        self.writer.write_all(&self.buffer)?;
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<u64, Box<dyn std::error::Error>> {
+        if !self.finished {
+            self.buffer.clear();
+            self.encoder.flush(&mut self.buffer)?;
+            self.writer.write_all(&self.buffer)?;
+            // 回填 Xing/LAME 标签，保证播放器能得到准确的时长
+            if let Some(tag) = self.encoder.lame_tag()? {
+                let end = self.writer.stream_position()?;
+                self.writer.seek(SeekFrom::Start(self.start))?;
+                self.writer.write_all(&tag)?;
+                self.writer.seek(SeekFrom::Start(end))?;
+            }
+            self.writer.flush()?;
+            self.finished = true;
+        }
+        Ok(self.writer.stream_position()? - self.start)
+    }
+}
+
+/// 基于 symphonia 的 MP3 解码器，按 LAME 标签去除编码器延迟和末尾填充
+#[cfg(feature = "mp3-decode")]
+pub struct Mp3Decoder {
+    format: Box<dyn symphonia::core::formats::FormatReader>,
+    decoder: Box<dyn symphonia::core::codecs::Decoder>,
+    track_id: u32,
+    config: AudioConfig,
+}
+
+#[cfg(feature = "mp3-decode")]
+impl Mp3Decoder {
+    /// 读取码流开头（ID3 标签和 Xing/LAME 标签帧），创建解码器
+    pub fn new<R: Read + Send + Sync + 'static>(reader: R) -> Result<Self, Box<dyn std::error::Error>> {
+        use symphonia::core::formats::FormatOptions;
+        use symphonia::core::io::{MediaSourceStream, ReadOnlySource};
+        use symphonia::core::probe::Hint;
+
+        let source = MediaSourceStream::new(Box::new(ReadOnlySource::new(reader)), Default::default());
+        let mut hint = Hint::new();
+        hint.with_extension("mp3");
+        let options = FormatOptions { enable_gapless: true, ..Default::default() };
+        let format = symphonia::default::get_probe().format(&hint, source, &options, &Default::default()).map_err(AudioError::from)?.format;
+        let track = format.default_track().ok_or_else(|| AudioError::UnsupportedFormat("MP3 stream has no audio track".into()))?;
+        let params = &track.codec_params;
+        let sample_rate = params.sample_rate.ok_or_else(|| AudioError::UnsupportedFormat("MP3 stream has no sample rate".into()))?;
+        let channels = params.channels.ok_or_else(|| AudioError::UnsupportedFormat("MP3 stream has no channel layout".into()))?.count() as u8;
+        let decoder = symphonia::default::get_codecs().make(params, &Default::default()).map_err(AudioError::from)?;
+        Ok(Mp3Decoder { track_id: track.id, format, decoder, config: AudioConfig::new(sample_rate, channels, 16) })
+    }
+}
+
+#[cfg(feature = "mp3-decode")]
+impl AudioDecoder for Mp3Decoder {
+    fn config(&self) -> AudioConfig {
+        self.config.clone()
+    }
+
+    fn decode(&mut self, samples: &mut Vec<i16>) -> Result<usize, Box<dyn std::error::Error>> {
+        use symphonia::core::audio::SampleBuffer;
+        use symphonia::core::errors::Error;
+
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(Error::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(0),
+                Err(e) => return Err(AudioError::from(e).into()),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // 损坏的帧跳过，与播放器的行为一致
+                Err(Error::DecodeError(e)) => {
+                    log::warn!("Skipping corrupt MP3 frame: {}", e);
+                    continue;
+                }
+                Err(e) => return Err(AudioError::from(e).into()),
+            };
+            if decoded.frames() == 0 {
+                continue;
+            }
+            let mut buffer = SampleBuffer::<i16>::new(decoded.capacity() as u64, *decoded.spec());
+            buffer.copy_interleaved_ref(decoded);
+            samples.extend_from_slice(buffer.samples());
+            return Ok(buffer.samples().len());
+        }
+    }
+}
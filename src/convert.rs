@@ -0,0 +1,251 @@
+// 统一转换入口：按文件内容识别输入格式、按扩展名确定输出格式，选择对应的解码和编码路径
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::atomic::{self, AtomicFile};
+use crate::probe::{self, DetectedFormat};
+use crate::registry;
+use crate::{
+    frames_duration, infer_audio_config_from_filename, mp3, AudioConfig, AudioDecoder, AudioError, AudioEncoder, AudioFormat,
+    AudioQuality, ConversionReport, ConvertOptions, ConvertStage, Mp3Config, Mp3StreamEncoder, PcmDecoder,
+    PcmStreamEncoder, PcmToWavConfig, WavDecoder, WavStreamEncoder,
+};
+
+/// 转换音频文件，输入格式按文件内容识别，输出格式按扩展名确定
+///
+/// * 原始 PCM 输入的参数从文件名推断（与 `auto_convert_pcm` 相同），走 PCM 转换路径
+/// * WAV 输入逐块解码后编码，输出 16 位样本；输出 PCM 时直接取出 data 块
+/// * MP3 输入需要启用 `mp3-decode` feature，其他格式可以通过 `FormatRegistry` 注册解码器
+/// * MP3 输出使用采样率对应的默认比特率，非 MP3 采样率自动重采样，多声道输入按扬声器布局混缩为立体声（没有默认布局的混缩为单声道）
+/// # Arguments
+/// * `input_path` - 输入文件路径（PCM / WAV / MP3 / 已注册的格式）
+/// * `output_path` - 输出文件路径，扩展名为 `wav`、`mp3`、`pcm` 或 `raw`
+/// * `options` - 转换选项（进度回调、取消令牌、覆盖策略），为 None 时使用默认选项
+/// # Returns
+/// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计
+pub fn convert(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, options: Option<ConvertOptions>) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+    let mut options = options.unwrap_or_default();
+    if !input_path.exists() {
+        return Err(crate::input_not_found());
+    }
+    let output_format = output_format(output_path)?;
+    let input_format = probe::probe_file(input_path)?;
+    log::debug!("Converting {} ({:?}) to {:?}", input_path.display(), input_format, output_format);
+
+    match (input_format, output_format) {
+        (DetectedFormat::Unknown, output_format) => convert_pcm(input_path, output_path, output_format, options),
+        (DetectedFormat::Wav(_), AudioFormat::Pcm) => crate::trans_wav_file_to_pcm_with_options(input_path, output_path, options),
+        (_, output_format) => {
+            let started = Instant::now();
+            let (mut decoder, position) = open_decoder(input_path, input_format, None)?;
+            transcode_file(decoder.as_mut(), &position, input_path, output_path, output_format, &mut options, started)
+        }
+    }
+}
+
+/// 解码器和输入已读取字节数的计数
+pub(crate) type CountedDecoder = (Box<dyn AudioDecoder>, Arc<AtomicU64>);
+
+/// 按识别出的格式打开输入文件的解码器，同时返回已读取字节数的计数（用于报告进度）
+/// # Arguments
+/// * `pcm_config` - 原始 PCM 输入的参数，为 None 时从文件名推断
+pub(crate) fn open_decoder(input_path: &Path, format: DetectedFormat, pcm_config: Option<&AudioConfig>) -> Result<CountedDecoder, Box<dyn std::error::Error>> {
+    let (reader, position) = open_counted(input_path)?;
+    let decoder: Box<dyn AudioDecoder> = match format {
+        DetectedFormat::Unknown => {
+            let config = match pcm_config {
+                Some(config) => config.clone(),
+                None => infer_audio_config_from_filename(&input_path.file_name().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy()),
+            };
+            Box::new(PcmDecoder::new(reader, config)?)
+        }
+        DetectedFormat::Wav(_) => Box::new(WavDecoder::new(reader)?),
+        DetectedFormat::Registered(name) => registry::open_decoder(name, Box::new(reader))?,
+        #[cfg(feature = "mp3-decode")]
+        DetectedFormat::Mp3 => Box::new(crate::Mp3Decoder::new(reader)?),
+        #[cfg(not(feature = "mp3-decode"))]
+        DetectedFormat::Mp3 => return Err(AudioError::UnsupportedFormat("Decoding MP3 input requires the mp3-decode feature".into()).into()),
+        format => return Err(AudioError::UnsupportedFormat(format!("Unsupported input format: {:?}", format)).into()),
+    };
+    Ok((decoder, position))
+}
+
+/// 按扩展名确定输出格式
+pub(crate) fn output_format(output_path: &Path) -> Result<AudioFormat, Box<dyn std::error::Error>> {
+    let extension = output_path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("wav") => Ok(AudioFormat::Wav),
+        Some("mp3") => Ok(AudioFormat::Mp3),
+        Some("pcm") | Some("raw") => Ok(AudioFormat::Pcm),
+        _ => Err(AudioError::UnsupportedFormat(format!("Unsupported output format (expected .wav, .mp3, .pcm or .raw): {}", output_path.display())).into()),
+    }
+}
+
+/// 原始 PCM 输入：参数从文件名推断，使用 PCM 转换函数
+fn convert_pcm(input_path: &Path, output_path: &Path, output_format: AudioFormat, mut options: ConvertOptions) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    let filename = input_path.file_name().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy();
+    let config = infer_audio_config_from_filename(&filename);
+    config.validate()?;
+    match output_format {
+        AudioFormat::Wav => crate::trans_pcm_file_to_wav_with_options(input_path, output_path, Some(crate::audio_config_to_wav_config(&config)), options),
+        AudioFormat::Mp3 => {
+            let mp3_config = crate::audio_config_to_mp3_config(&config, mp3::default_bitrate(config.sample_rate), AudioQuality::High);
+            crate::trans_pcm_file_to_mp3_with_options(input_path, output_path, Some(mp3_config), options)
+        }
+        AudioFormat::Pcm => {
+            let started = Instant::now();
+            let Some(resolved) = atomic::resolve_output(output_path, options.overwrite)? else {
+                return crate::skipped_report(input_path, output_path, output_path, AudioFormat::Pcm, started);
+            };
+            // 输出与输入为同一文件时复制会清空文件
+            if resolved.exists() && resolved.canonicalize()? == input_path.canonicalize()? {
+                return Err(AudioError::InvalidInput("Output file is the same as the input file".into()).into());
+            }
+            let bytes = atomic::copy_atomic(input_path, &resolved)?;
+            options.report(ConvertStage::Writing, bytes, bytes);
+            let frame_size = (config.sample_format.bytes_per_sample() * config.channels as usize) as u64;
+            Ok(ConversionReport {
+                input_bytes: bytes,
+                output_bytes: bytes,
+                duration: frames_duration(bytes / frame_size, config.sample_rate),
+                elapsed: started.elapsed(),
+                config_used: config,
+                format: AudioFormat::Pcm,
+                output_path: resolved,
+                skipped: false,
+            })
+        }
+    }
+}
+
+/// 记录已读取字节数的输入，用于在解码过程中报告进度
+struct CountingReader<R> {
+    inner: R,
+    position: Arc<AtomicU64>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.position.fetch_add(n as u64, Ordering::Relaxed);
+        Ok(n)
+    }
+}
+
+impl<R: Seek> Seek for CountingReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let position = self.inner.seek(pos)?;
+        self.position.store(position, Ordering::Relaxed);
+        Ok(position)
+    }
+}
+
+fn open_counted(path: &Path) -> std::io::Result<(CountingReader<BufReader<File>>, Arc<AtomicU64>)> {
+    let position = Arc::new(AtomicU64::new(0));
+    let reader = CountingReader { inner: BufReader::new(File::open(path)?), position: position.clone() };
+    Ok((reader, position))
+}
+
+/// 逐块解码并编码到输出文件；MP3 输出需要重采样或混缩时先解码全部样本再整体编码
+fn transcode_file(decoder: &mut dyn AudioDecoder, position: &AtomicU64, input_path: &Path, output_path: &Path, output_format: AudioFormat, options: &mut ConvertOptions, started: Instant) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    let Some(resolved) = atomic::resolve_output(output_path, options.overwrite)? else {
+        return crate::skipped_report(input_path, output_path, output_path, output_format, started);
+    };
+    let output_path = resolved.as_path();
+    let input_bytes = std::fs::metadata(input_path)?.len();
+    let config = decoder.config();
+    let create_writer = || AtomicFile::create(output_path).map(BufWriter::new);
+
+    let (output_bytes, config_used, duration) = match output_format {
+        AudioFormat::Wav => {
+            let mut encoder = WavStreamEncoder::new(create_writer()?, &PcmToWavConfig::new(config.sample_rate, config.channels, 16))?;
+            let (output_bytes, frames) = transcode(decoder, &mut encoder, position, input_bytes, options)?;
+            encoder.into_inner().into_inner().map_err(|e| e.into_error())?.commit()?;
+            (output_bytes, AudioConfig::new(config.sample_rate, config.channels, 16), frames_duration(frames, config.sample_rate))
+        }
+        AudioFormat::Pcm => {
+            let mut encoder = PcmStreamEncoder::new(create_writer()?);
+            let (output_bytes, frames) = transcode(decoder, &mut encoder, position, input_bytes, options)?;
+            encoder.into_inner().into_inner().map_err(|e| e.into_error())?.commit()?;
+            (output_bytes, AudioConfig::new(config.sample_rate, config.channels, 16), frames_duration(frames, config.sample_rate))
+        }
+        AudioFormat::Mp3 => {
+            let mut mp3_config = Mp3Config::new(config.sample_rate, config.channels, mp3::default_bitrate(config.sample_rate), AudioQuality::High);
+            mp3_config.target_channels = mp3::downmix_channels(config.channels);
+            if mp3_config.output_sample_rate() == config.sample_rate && mp3_config.target_channels.is_none() {
+                let mut encoder = Mp3StreamEncoder::new(create_writer()?, &mp3_config)?;
+                let (output_bytes, frames) = transcode(decoder, &mut encoder, position, input_bytes, options)?;
+                encoder.into_inner().into_inner().map_err(|e| e.into_error())?.commit()?;
+                (output_bytes, AudioConfig::mp3_output(config.sample_rate, config.channels), frames_duration(frames, config.sample_rate))
+            } else {
+                let samples = decode_all(decoder, position, input_bytes, options)?;
+                let (mp3_data, mp3_config, duration) = crate::encode_samples_to_mp3(&samples, mp3_config, options)?;
+                let output_bytes = mp3_data.len() as u64;
+                options.report(ConvertStage::Writing, 0, output_bytes);
+                atomic::write_atomic(output_path, &mp3_data)?;
+                options.report(ConvertStage::Writing, output_bytes, output_bytes);
+                (output_bytes, AudioConfig::mp3_output(mp3_config.sample_rate, mp3_config.channels), duration)
+            }
+        }
+    };
+
+    crate::log_conversion_done("Audio", input_path, output_path, input_bytes, started);
+    Ok(ConversionReport {
+        input_bytes,
+        output_bytes,
+        duration,
+        elapsed: started.elapsed(),
+        config_used,
+        format: output_format,
+        output_path: output_path.to_path_buf(),
+        skipped: false,
+    })
+}
+
+/// 将解码器的输出全部送入编码器
+/// # Returns
+/// * 写入的字节数和编码的采样帧数
+fn transcode(decoder: &mut dyn AudioDecoder, encoder: &mut dyn AudioEncoder, position: &AtomicU64, input_bytes: u64, options: &mut ConvertOptions) -> Result<(u64, u64), Box<dyn std::error::Error>> {
+    let channels = decoder.config().channels.max(1) as u64;
+    let mut samples = Vec::new();
+    let mut total_samples = 0u64;
+    options.report(ConvertStage::Encoding, 0, input_bytes);
+    loop {
+        options.check_cancelled()?;
+        samples.clear();
+        if decoder.decode(&mut samples)? == 0 {
+            break;
+        }
+        encoder.encode(&samples)?;
+        total_samples += samples.len() as u64;
+        options.report(ConvertStage::Encoding, position.load(Ordering::Relaxed).min(input_bytes), input_bytes);
+    }
+    options.report(ConvertStage::Encoding, input_bytes, input_bytes);
+
+    options.report(ConvertStage::Writing, 0, 0);
+    let output_bytes = encoder.finish()?;
+    options.report(ConvertStage::Writing, output_bytes, output_bytes);
+    Ok((output_bytes, total_samples / channels))
+}
+
+/// 解码全部样本，按读取的字节数报告进度
+fn decode_all(decoder: &mut dyn AudioDecoder, position: &AtomicU64, input_bytes: u64, options: &mut ConvertOptions) -> Result<Vec<i16>, Box<dyn std::error::Error>> {
+    let mut samples = Vec::new();
+    options.report(ConvertStage::Reading, 0, input_bytes);
+    loop {
+        options.check_cancelled()?;
+        if decoder.decode(&mut samples)? == 0 {
+            break;
+        }
+        options.report(ConvertStage::Reading, position.load(Ordering::Relaxed).min(input_bytes), input_bytes);
+    }
+    options.report(ConvertStage::Reading, input_bytes, input_bytes);
+    Ok(samples)
+}
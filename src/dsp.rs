@@ -0,0 +1,1378 @@
+// 音频信号处理（DSP）：重采样、声道转换等基于样本的运算
+
+use std::collections::VecDeque;
+use std::f64::consts::PI;
+
+use crate::{wav, AudioError, Sample};
+
+/// 重采样滤波器每侧的过零点数量
+const RESAMPLE_ZERO_CROSSINGS: usize = 16;
+
+/// 对交错的 i16 样本进行重采样（带限 sinc 插值，Blackman 窗）
+/// # Arguments
+/// * `samples` - 交错的 PCM 样本
+/// * `channels` - 声道数
+/// * `from_rate` - 原始采样率
+/// * `to_rate` - 目标采样率
+/// # Returns
+/// * 重采样后的交错样本
+pub fn resample_i16(samples: &[i16], channels: u8, from_rate: u32, to_rate: u32) -> Vec<i16> {
+    resample(samples, channels, from_rate, to_rate)
+}
+
+/// 对交错的 f32 样本进行重采样，算法与 [`resample_i16`] 相同
+pub fn resample_f32(samples: &[f32], channels: u8, from_rate: u32, to_rate: u32) -> Vec<f32> {
+    resample(samples, channels, from_rate, to_rate)
+}
+
+/// 对任意样本类型的交错样本进行重采样，算法与 [`resample_i16`] 相同，插值在 f64 中进行
+pub fn resample<S: Sample>(samples: &[S], channels: u8, from_rate: u32, to_rate: u32) -> Vec<S> {
+    if from_rate == to_rate || channels == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let channels = channels as usize;
+    let in_frames = samples.len() / channels;
+    let ratio = to_rate as f64 / from_rate as f64;
+    let out_frames = (in_frames as f64 * ratio).round() as usize;
+
+    // 降采样时降低截止频率以避免混叠
+    let cutoff = ratio.min(1.0);
+    let half_width = RESAMPLE_ZERO_CROSSINGS as f64 / cutoff;
+
+    let mut output = Vec::with_capacity(out_frames * channels);
+    let mut acc = vec![0.0f64; channels];
+
+    for out_index in 0..out_frames {
+        let center = out_index as f64 / ratio;
+        let first = (center - half_width).ceil().max(0.0) as usize;
+        let last = ((center + half_width).floor() as usize).min(in_frames.saturating_sub(1));
+
+        acc.iter_mut().for_each(|a| *a = 0.0);
+        let mut weight_sum = 0.0;
+
+        for in_index in first..=last {
+            let weight = resample_weight(in_index as f64 - center, cutoff, half_width);
+            weight_sum += weight;
+            let frame = &samples[in_index * channels..(in_index + 1) * channels];
+            for (a, &s) in acc.iter_mut().zip(frame) {
+                *a += s.to_f64() * weight;
+            }
+        }
+
+        // 归一化，避免边界处增益变化
+        let norm = if weight_sum.abs() > 1e-9 { 1.0 / weight_sum } else { 0.0 };
+        for a in &acc {
+            output.push(S::from_f64(a * norm));
+        }
+    }
+
+    output
+}
+
+/// 距离输出位置 `distance` 个输入帧的样本的插值权重
+fn resample_weight(distance: f64, cutoff: f64, half_width: f64) -> f64 {
+    cutoff * sinc(distance * cutoff) * blackman(distance / half_width)
+}
+
+/// 分块输入的重采样器，输出与对完整信号调用 [`resample_f32`] 相同
+///
+/// 只保留插值窗口需要的输入帧，内存占用与输入长度无关
+pub(crate) struct StreamResampler {
+    channels: usize,
+    ratio: f64,
+    cutoff: f64,
+    half_width: f64,
+    /// 尚未丢弃的输入帧（交错）
+    buffer: Vec<f32>,
+    /// `buffer` 第一帧的输入帧序号
+    buffer_start: usize,
+    /// 已收到的输入帧数
+    in_frames: usize,
+    /// 下一个输出帧的序号
+    next_out: usize,
+    acc: Vec<f64>,
+}
+
+impl StreamResampler {
+    pub fn new(channels: u8, from_rate: u32, to_rate: u32) -> Self {
+        let ratio = to_rate as f64 / from_rate as f64;
+        let cutoff = ratio.min(1.0);
+        StreamResampler {
+            channels: channels.max(1) as usize,
+            ratio,
+            cutoff,
+            half_width: RESAMPLE_ZERO_CROSSINGS as f64 / cutoff,
+            buffer: Vec::new(),
+            buffer_start: 0,
+            in_frames: 0,
+            next_out: 0,
+            acc: vec![0.0; channels.max(1) as usize],
+        }
+    }
+
+    /// 送入一块交错样本，输出插值窗口已经完整的帧
+    pub fn process(&mut self, samples: &[f32], output: &mut Vec<f32>) {
+        self.buffer.extend_from_slice(samples);
+        self.in_frames += samples.len() / self.channels;
+        loop {
+            let center = self.next_out as f64 / self.ratio;
+            if (center + self.half_width).floor() as usize >= self.in_frames {
+                break;
+            }
+            self.emit(center, output);
+        }
+        // 丢弃之后的输出不再需要的输入帧
+        let center = self.next_out as f64 / self.ratio;
+        let first = ((center - self.half_width).ceil().max(0.0) as usize).min(self.in_frames);
+        if first > self.buffer_start {
+            self.buffer.drain(..(first - self.buffer_start) * self.channels);
+            self.buffer_start = first;
+        }
+    }
+
+    /// 输入结束，输出剩余的帧（末尾的插值窗口按信号边界截断）
+    pub fn finish(&mut self, output: &mut Vec<f32>) {
+        let out_frames = (self.in_frames as f64 * self.ratio).round() as usize;
+        while self.next_out < out_frames {
+            let center = self.next_out as f64 / self.ratio;
+            self.emit(center, output);
+        }
+    }
+
+    fn emit(&mut self, center: f64, output: &mut Vec<f32>) {
+        let first = (center - self.half_width).ceil().max(0.0) as usize;
+        let last = ((center + self.half_width).floor() as usize).min(self.in_frames.saturating_sub(1));
+        self.acc.iter_mut().for_each(|a| *a = 0.0);
+        let mut weight_sum = 0.0;
+        for in_index in first.max(self.buffer_start)..=last {
+            let weight = resample_weight(in_index as f64 - center, self.cutoff, self.half_width);
+            weight_sum += weight;
+            let offset = (in_index - self.buffer_start) * self.channels;
+            for (a, &s) in self.acc.iter_mut().zip(&self.buffer[offset..offset + self.channels]) {
+                *a += s as f64 * weight;
+            }
+        }
+        let norm = if weight_sum.abs() > 1e-9 { 1.0 / weight_sum } else { 0.0 };
+        output.extend(self.acc.iter().map(|a| (a * norm) as f32));
+        self.next_out += 1;
+    }
+}
+
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-12 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Blackman 窗，`x` 取值范围为 [-1, 1]
+fn blackman(x: f64) -> f64 {
+    if x.abs() > 1.0 {
+        return 0.0;
+    }
+    let t = (x + 1.0) / 2.0;
+    0.42 - 0.5 * (2.0 * PI * t).cos() + 0.08 * (4.0 * PI * t).cos()
+}
+
+/// 将交错的多声道样本混缩为单声道（各声道取平均）
+pub fn downmix_to_mono<S: Sample>(samples: &[S], channels: u8) -> Vec<S> {
+    if channels <= 1 {
+        return samples.to_vec();
+    }
+    samples
+        .chunks_exact(channels as usize)
+        .map(|frame| S::from_f64(frame.iter().map(|s| s.to_f64()).sum::<f64>() / channels as f64))
+        .collect()
+}
+
+/// 按声道数对应的默认扬声器布局（四声道、5.1、7.1 等）将多声道样本混缩为立体声
+///
+/// 中置和环绕声道按 ITU-R BS.775 以 -3 dB 分配到左右两侧，LFE 声道被舍弃；
+/// 结果按每侧系数之和缩放，满幅输入不会削波。单声道复制为立体声，立体声保持不变
+/// # Returns
+/// * 交错的立体声样本，声道数没有默认布局（超过 8 个）时返回错误
+pub fn downmix_to_stereo<S: Sample>(samples: &[S], channels: u8) -> Result<Vec<S>, Box<dyn std::error::Error>> {
+    match channels {
+        0 => return Err(AudioError::InvalidConfig("Channel count must be greater than zero".into()).into()),
+        1 => return Ok(duplicate_to_stereo(samples)),
+        2 => return Ok(samples.to_vec()),
+        _ => {}
+    }
+    let gains = stereo_downmix_gains(channels).ok_or_else(|| AudioError::InvalidConfig(format!("No default speaker layout for {} channels", channels)))?;
+    let scale = 1.0 / gains.iter().map(|g| g.0).sum::<f64>().max(gains.iter().map(|g| g.1).sum::<f64>());
+    Ok(samples
+        .chunks_exact(channels as usize)
+        .flat_map(|frame| {
+            let (left, right) = frame.iter().zip(&gains).fold((0.0, 0.0), |(left, right), (s, (gain_l, gain_r))| {
+                (left + s.to_f64() * gain_l, right + s.to_f64() * gain_r)
+            });
+            [S::from_f64(left * scale), S::from_f64(right * scale)]
+        })
+        .collect())
+}
+
+/// 默认布局中每个声道分配到左右两侧的增益，按扬声器位置掩码的位序与交错顺序对应
+fn stereo_downmix_gains(channels: u8) -> Option<Vec<(f64, f64)>> {
+    const SIDE: f64 = std::f64::consts::FRAC_1_SQRT_2;
+    let mask = wav::default_channel_mask(channels);
+    if mask == 0 {
+        return None;
+    }
+    let gains = (0..32)
+        .map(|bit| 1u32 << bit)
+        .filter(|speaker| mask & speaker != 0)
+        .map(|speaker| match speaker {
+            0x1 | 0x40 => (1.0, 0.0),     // FL、FLC
+            0x2 | 0x80 => (0.0, 1.0),     // FR、FRC
+            0x8 => (0.0, 0.0),            // LFE
+            0x10 | 0x200 => (SIDE, 0.0),  // BL、SL
+            0x20 | 0x400 => (0.0, SIDE),  // BR、SR
+            _ => (SIDE, SIDE),            // FC、BC 等居中的声道
+        })
+        .collect();
+    Some(gains)
+}
+
+/// 将单声道样本复制为左右相同的立体声
+pub fn duplicate_to_stereo<S: Sample>(samples: &[S]) -> Vec<S> {
+    samples.iter().flat_map(|&s| [s, s]).collect()
+}
+
+/// 转换声道数：多声道混缩为单声道或立体声（按默认扬声器布局），单声道复制为立体声
+/// # Arguments
+/// * `samples` - 交错的样本
+/// * `from_channels` - 原始声道数
+/// * `to_channels` - 目标声道数
+/// # Returns
+/// * 转换后的交错样本，不支持的转换组合返回错误
+pub fn convert_channels<S: Sample>(samples: &[S], from_channels: u8, to_channels: u8) -> Result<Vec<S>, Box<dyn std::error::Error>> {
+    match (from_channels, to_channels) {
+        (0, _) | (_, 0) => Err(AudioError::InvalidConfig("Channel count must be greater than zero".into()).into()),
+        (from, to) if from == to => Ok(samples.to_vec()),
+        (from, 1) => Ok(downmix_to_mono(samples, from)),
+        (1, 2) => Ok(duplicate_to_stereo(samples)),
+        (from, 2) => downmix_to_stereo(samples, from),
+        (from, to) => Err(AudioError::InvalidConfig(format!("Unsupported channel conversion: {} -> {} channels", from, to)).into()),
+    }
+}
+
+/// 声道映射：按顺序列出每个输出声道取自的输入声道（从 0 开始），用于交换、选取或重排声道
+///
+/// 同一个输入声道可以出现多次（如把左声道复制到两侧），修正接线错误的录音接口无需另外的工具
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChannelMap {
+    sources: Vec<u8>,
+}
+
+impl ChannelMap {
+    /// 按输出声道顺序列出输入声道，如 `[1, 0]` 交换左右声道
+    pub fn new(sources: impl Into<Vec<u8>>) -> Self {
+        ChannelMap { sources: sources.into() }
+    }
+
+    /// 交换立体声的左右声道
+    pub fn swap_stereo() -> Self {
+        ChannelMap::new([1, 0])
+    }
+
+    /// 只保留一个输入声道，输出单声道
+    pub fn select(channel: u8) -> Self {
+        ChannelMap::new([channel])
+    }
+
+    /// 每个输出声道取自的输入声道
+    pub fn sources(&self) -> &[u8] {
+        &self.sources
+    }
+
+    /// 输出声道数
+    pub fn output_channels(&self) -> u8 {
+        self.sources.len() as u8
+    }
+
+    /// 对给定声道数的输入是否不做任何修改
+    pub fn is_identity(&self, channels: u8) -> bool {
+        self.sources.iter().copied().eq(0..channels)
+    }
+
+    /// 检查映射能否用于给定声道数的输入
+    pub fn validate(&self, channels: u8) -> Result<(), AudioError> {
+        if self.sources.is_empty() || self.sources.len() > u8::MAX as usize {
+            return Err(AudioError::InvalidConfig("Channel map must list between 1 and 255 output channels".into()));
+        }
+        match self.sources.iter().find(|&&source| source >= channels) {
+            Some(source) => Err(AudioError::InvalidConfig(format!("Channel map refers to channel {} but the input has {} channel(s)", source, channels))),
+            None => Ok(()),
+        }
+    }
+
+    /// 对交错样本应用映射，末尾不完整的帧被忽略
+    /// # Arguments
+    /// * `samples` - 交错样本
+    /// * `channels` - 输入声道数
+    /// # Returns
+    /// * 按映射重排后的交错样本，映射引用了不存在的声道时返回错误
+    pub fn apply<S: Sample>(&self, samples: &[S], channels: u8) -> Result<Vec<S>, Box<dyn std::error::Error>> {
+        self.validate(channels)?;
+        Ok(samples
+            .chunks_exact(channels as usize)
+            .flat_map(|frame| self.sources.iter().map(move |&source| frame[source as usize]))
+            .collect())
+    }
+}
+
+impl std::str::FromStr for ChannelMap {
+    type Err = AudioError;
+
+    /// 解析逗号分隔的输入声道列表（如 `1,0`），`swap` 表示交换左右声道
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim().eq_ignore_ascii_case("swap") {
+            return Ok(ChannelMap::swap_stereo());
+        }
+        let sources = s
+            .split(',')
+            .map(|source| source.trim().parse::<u8>().map_err(|_| AudioError::InvalidConfig(format!("Invalid channel in channel map: {:?}", source.trim()))))
+            .collect::<Result<Vec<u8>, _>>()?;
+        Ok(ChannelMap::new(sources))
+    }
+}
+
+/// 分贝转换为线性增益
+pub fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// 线性幅度转换为分贝，幅度为 0 时返回负无穷
+pub fn linear_to_db(linear: f32) -> f32 {
+    20.0 * linear.log10()
+}
+
+/// 按分贝调整样本增益，超出 [-1.0, 1.0] 的样本会被削波
+/// # Returns
+/// * 被削波的样本数
+pub fn apply_gain<S: Sample>(samples: &mut [S], gain_db: f32) -> usize {
+    let gain = db_to_linear(gain_db) as f64;
+    let mut clipped = 0;
+    for s in samples.iter_mut() {
+        let scaled = s.to_f64() * gain;
+        if scaled.abs() > 1.0 {
+            clipped += 1;
+        }
+        *s = S::from_f64(scaled.clamp(-1.0, 1.0));
+    }
+    clipped
+}
+
+/// 按峰值归一化：缩放样本使最大绝对值达到目标电平，全静音的数据保持不变
+/// # Arguments
+/// * `samples` - 样本数据
+/// * `target_db` - 目标峰值电平（dBFS，通常为 -1.0 左右）
+/// # Returns
+/// * 实际应用的增益（dB）
+pub fn normalize_peak<S: Sample>(samples: &mut [S], target_db: f32) -> f32 {
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.to_f32().abs()));
+    if peak <= 0.0 {
+        return 0.0;
+    }
+    let gain = db_to_linear(target_db) / peak;
+    for s in samples.iter_mut() {
+        *s = S::from_f64((s.to_f64() * gain as f64).clamp(-1.0, 1.0));
+    }
+    linear_to_db(gain)
+}
+
+/// 混音时两条音轨的对齐方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum MixAlign {
+    /// 两条音轨同时开始
+    #[default]
+    Start,
+    /// 两条音轨同时结束
+    End,
+    /// 两条音轨中心对齐
+    Center,
+    /// 音轨 B 相对音轨 A 延迟指定毫秒后开始
+    Offset(u32),
+}
+
+/// 混音参数
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MixOptions {
+    /// 音轨 A 的增益（dB）
+    pub gain_a: f32,
+    /// 音轨 B 的增益（dB）
+    pub gain_b: f32,
+    /// 对齐方式
+    pub align: MixAlign,
+}
+
+impl MixOptions {
+    /// 设置音轨 A 的增益（dB）
+    pub fn with_gain_a(mut self, gain_db: f32) -> Self {
+        self.gain_a = gain_db;
+        self
+    }
+
+    /// 设置音轨 B 的增益（dB）
+    pub fn with_gain_b(mut self, gain_db: f32) -> Self {
+        self.gain_b = gain_db;
+        self
+    }
+
+    /// 设置对齐方式
+    pub fn with_align(mut self, align: MixAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+/// 将两条声道数和采样率相同的音轨相加，超出范围的样本会被削波
+/// # Returns
+/// * 混音后的交错样本，长度覆盖两条音轨
+pub fn mix_samples(a: &[f32], b: &[f32], channels: u8, sample_rate: u32, options: &MixOptions) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let (frames_a, frames_b) = (a.len() / channels, b.len() / channels);
+
+    // 计算两条音轨在输出中的起始帧
+    let (start_a, start_b) = match options.align {
+        MixAlign::Start => (0, 0),
+        MixAlign::End => (frames_b.saturating_sub(frames_a), frames_a.saturating_sub(frames_b)),
+        MixAlign::Center => ((frames_b.saturating_sub(frames_a)) / 2, (frames_a.saturating_sub(frames_b)) / 2),
+        MixAlign::Offset(ms) => (0, (ms as u64 * sample_rate as u64 / 1000) as usize),
+    };
+    let frames = (start_a + frames_a).max(start_b + frames_b);
+
+    let mut output = vec![0.0f32; frames * channels];
+    let (gain_a, gain_b) = (db_to_linear(options.gain_a), db_to_linear(options.gain_b));
+    for (out, &s) in output[start_a * channels..].iter_mut().zip(&a[..frames_a * channels]) {
+        *out += s * gain_a;
+    }
+    for (out, &s) in output[start_b * channels..].iter_mut().zip(&b[..frames_b * channels]) {
+        *out += s * gain_b;
+    }
+
+    let clipped = output.iter().filter(|s| s.abs() > 1.0).count();
+    if clipped > 0 {
+        log::warn!("Mixing clipped {} samples", clipped);
+        output.iter_mut().for_each(|s| *s = s.clamp(-1.0, 1.0));
+    }
+    output
+}
+
+/// 将片段以交叉淡化方式追加到已有样本之后
+///
+/// 重叠部分使用等功率曲线（前一段余弦淡出、后一段正弦淡入），重叠长度不超过任一片段的长度
+pub fn crossfade_append(output: &mut Vec<f32>, clip: &[f32], channels: u8, sample_rate: u32, crossfade_ms: u32) {
+    let channels = channels.max(1) as usize;
+    let overlap = ((crossfade_ms as u64 * sample_rate as u64 / 1000) as usize)
+        .min(output.len() / channels)
+        .min(clip.len() / channels);
+
+    let start = output.len() - overlap * channels;
+    for i in 0..overlap {
+        let t = (i as f32 + 0.5) / overlap as f32 * std::f32::consts::FRAC_PI_2;
+        let (fade_out, fade_in) = (t.cos(), t.sin());
+        for c in 0..channels {
+            let out = &mut output[start + i * channels + c];
+            *out = *out * fade_out + clip[i * channels + c] * fade_in;
+        }
+    }
+    output.extend_from_slice(&clip[overlap * channels..]);
+}
+
+/// 淡入淡出曲线
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FadeCurve {
+    /// 线性
+    #[default]
+    Linear,
+    /// 指数（开始缓慢，结束陡峭）
+    Exponential,
+    /// 对数（开始陡峭，结束缓慢）
+    Logarithmic,
+    /// S 形（两端平缓，余弦曲线）
+    SCurve,
+}
+
+impl FadeCurve {
+    /// 淡入进度 `t`（0.0 - 1.0）对应的增益
+    pub fn gain(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            FadeCurve::Linear => t,
+            FadeCurve::Exponential => t * t,
+            FadeCurve::Logarithmic => 1.0 - (1.0 - t) * (1.0 - t),
+            FadeCurve::SCurve => (1.0 - (std::f32::consts::PI * t).cos()) / 2.0,
+        }
+    }
+}
+
+/// 淡入淡出配置
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct FadeConfig {
+    /// 淡入时长（毫秒）
+    pub fade_in_ms: u32,
+    /// 淡出时长（毫秒）
+    pub fade_out_ms: u32,
+    /// 淡入淡出曲线
+    pub curve: FadeCurve,
+}
+
+impl FadeConfig {
+    /// 创建线性淡入淡出配置
+    pub fn new(fade_in_ms: u32, fade_out_ms: u32) -> Self {
+        FadeConfig {
+            fade_in_ms,
+            fade_out_ms,
+            curve: FadeCurve::Linear,
+        }
+    }
+
+    /// 设置淡入淡出曲线
+    pub fn with_curve(mut self, curve: FadeCurve) -> Self {
+        self.curve = curve;
+        self
+    }
+}
+
+/// 对交错样本应用淡入淡出，时长超过音频长度时按音频长度处理
+pub fn apply_fade(samples: &mut [f32], channels: u8, sample_rate: u32, fade: &FadeConfig) {
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    let to_frames = |ms: u32| ((ms as u64 * sample_rate as u64 / 1000) as usize).min(frames);
+    let fade_in = to_frames(fade.fade_in_ms);
+    let fade_out = to_frames(fade.fade_out_ms);
+
+    for (i, frame) in samples.chunks_exact_mut(channels).enumerate() {
+        let gain = fade_gain(&fade.curve, i, frames, fade_in, fade_out);
+        if gain < 1.0 {
+            frame.iter_mut().for_each(|s| *s *= gain);
+        }
+    }
+}
+
+/// 共 `frames` 帧的音频中第 `i` 帧的淡入淡出增益，`fade_in` / `fade_out` 为已按音频长度截断的帧数
+pub(crate) fn fade_gain(curve: &FadeCurve, i: usize, frames: usize, fade_in: usize, fade_out: usize) -> f32 {
+    let mut gain = 1.0;
+    if i < fade_in {
+        gain *= curve.gain(i as f32 / fade_in as f32);
+    }
+    if i >= frames - fade_out {
+        gain *= curve.gain((frames - 1 - i) as f32 / fade_out as f32);
+    }
+    gain
+}
+
+/// WSOLA 分析帧长度（毫秒）
+const WSOLA_FRAME_MS: u32 = 30;
+
+/// 变速后的输出最多为输入的多少倍长，限制极小的倍率分配的缓冲区大小
+const MAX_STRETCH_RATIO: f32 = 16.0;
+
+/// 检查变速倍率：必须为有限的正数，且输出不超过输入的 `MAX_STRETCH_RATIO` 倍长
+pub(crate) fn check_tempo_factor(factor: f32) -> Result<(), AudioError> {
+    if !factor.is_finite() || factor < 1.0 / MAX_STRETCH_RATIO {
+        return Err(AudioError::InvalidInput(format!(
+            "Tempo factor {} must be a finite number of at least {} (output at most {}x longer)",
+            factor, 1.0 / MAX_STRETCH_RATIO, MAX_STRETCH_RATIO
+        )));
+    }
+    Ok(())
+}
+
+/// 变速不变调（WSOLA：波形相似重叠相加）
+///
+/// 按 `factor` 改变播放速度而保持音高不变：大于 1 加快（输出变短），小于 1 放慢（输出变长）。
+/// 每个合成帧在名义位置附近搜索与上一帧自然延续最相似的波形，再以 Hann 窗 50% 重叠相加，避免相位跳变
+/// # Arguments
+/// * `samples` - 交错的 f32 样本
+/// * `channels` - 声道数（各声道使用相同的帧偏移，保持声道间同步）
+/// * `sample_rate` - 采样率
+/// * `factor` - 速度倍率，不小于 1/16（输出最多为输入的 16 倍长）的有限正数
+/// # Returns
+/// * `Result<Vec<f32>, Box<dyn std::error::Error>>` - 变速后的交错样本，帧数约为原帧数 / `factor`；倍率无效时返回 `AudioError::InvalidInput`
+pub fn time_stretch(samples: &[f32], channels: u8, sample_rate: u32, factor: f32) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    check_tempo_factor(factor)?;
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    if factor == 1.0 || frames == 0 {
+        return Ok(samples.to_vec());
+    }
+
+    let frame_len = ((sample_rate * WSOLA_FRAME_MS / 1000) as usize).max(16) & !1;
+    let hop = frame_len / 2;
+    let tolerance = hop / 2;
+    let out_frames = (frames as f64 / factor as f64).round() as usize;
+    let window: Vec<f32> = (0..frame_len)
+        .map(|n| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / frame_len as f32).cos())
+        .collect();
+
+    // 相似度搜索只在单声道混缩信号上进行
+    let mono = downmix_to_mono(&samples[..frames * channels], channels as u8);
+    let at = |i: usize| mono.get(i).copied().unwrap_or(0.0);
+
+    let mut output = vec![0.0f32; (out_frames + frame_len) * channels];
+    let mut weights = vec![0.0f32; out_frames + frame_len];
+    let mut previous: Option<usize> = None;
+    for out_pos in (0..out_frames).step_by(hop) {
+        let nominal = (out_pos as f64 * factor as f64).round() as usize;
+        let pos = match previous {
+            None => 0,
+            Some(prev) => {
+                let natural = prev + hop;
+                let lo = nominal.saturating_sub(tolerance).min(frames - 1);
+                let hi = (nominal + tolerance).min(frames - 1);
+                (lo..=hi)
+                    .map(|candidate| {
+                        let score: f32 = (0..hop).map(|i| at(natural + i) * at(candidate + i)).sum();
+                        (candidate, score)
+                    })
+                    .fold((lo, f32::NEG_INFINITY), |best, c| if c.1 > best.1 { c } else { best })
+                    .0
+            }
+        };
+
+        for (n, &w) in window.iter().enumerate().take(frames - pos) {
+            let (src, dst) = ((pos + n) * channels, (out_pos + n) * channels);
+            for c in 0..channels {
+                output[dst + c] += samples[src + c] * w;
+            }
+            weights[out_pos + n] += w;
+        }
+        previous = Some(pos);
+    }
+
+    // 按窗函数叠加的权重归一化，首尾只被单个帧覆盖的区域也能保持原幅度
+    output.truncate(out_frames * channels);
+    for (frame, &weight) in output.chunks_exact_mut(channels).zip(&weights) {
+        if weight > 1e-6 {
+            frame.iter_mut().for_each(|s| *s /= weight);
+        }
+    }
+    Ok(output)
+}
+
+/// 去除直流偏移的高通滤波器截止频率（Hz）
+const DC_CUTOFF_HZ: f32 = 10.0;
+
+/// 测量每个声道的直流偏移（样本均值，满量程为 1.0）
+pub fn measure_dc_offset(samples: &[f32], channels: u8) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    let mut sums = vec![0.0f64; channels];
+    for frame in samples.chunks_exact(channels) {
+        for (sum, &s) in sums.iter_mut().zip(frame) {
+            *sum += s as f64;
+        }
+    }
+    sums.into_iter().map(|sum| (sum / frames.max(1) as f64) as f32).collect()
+}
+
+/// 使用一阶高通滤波器（截止频率 10Hz）去除每个声道的直流偏移
+///
+/// 滤波器状态以首帧样本初始化，恒定的偏移从第一个样本起即被去除，不会在开头产生冲击
+pub fn remove_dc_offset(samples: &mut [f32], channels: u8, sample_rate: u32) {
+    DcFilter::new(channels, sample_rate).process(samples);
+}
+
+/// [`remove_dc_offset`] 使用的高通滤波器，保留状态以便分块处理
+pub(crate) struct DcFilter {
+    channels: usize,
+    r: f32,
+    /// 上一帧的输入，收到第一帧前为空
+    previous_input: Vec<f32>,
+    previous_output: Vec<f32>,
+}
+
+impl DcFilter {
+    pub fn new(channels: u8, sample_rate: u32) -> Self {
+        let channels = channels.max(1) as usize;
+        DcFilter {
+            channels,
+            r: (-2.0 * std::f32::consts::PI * DC_CUTOFF_HZ / sample_rate.max(1) as f32).exp(),
+            previous_input: Vec::new(),
+            previous_output: vec![0.0f32; channels],
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if self.previous_input.is_empty() {
+            self.previous_input = samples.iter().take(self.channels).copied().collect();
+        }
+        for frame in samples.chunks_exact_mut(self.channels) {
+            for (c, s) in frame.iter_mut().enumerate() {
+                let output = *s - self.previous_input[c] + self.r * self.previous_output[c];
+                self.previous_input[c] = *s;
+                self.previous_output[c] = output;
+                *s = output;
+            }
+        }
+    }
+}
+
+/// 噪声门打开时增益从 0 升到 1 的时长（毫秒），避免打开时产生爆音
+const NOISE_GATE_ATTACK_MS: f32 = 1.0;
+
+/// 噪声门配置
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct NoiseGateConfig {
+    /// 阈值（dBFS），电平低于阈值的部分被静音
+    pub threshold_db: f32,
+    /// 保持时长（毫秒），电平降到阈值以下后保持打开的时间，避免在音节之间频繁开关
+    pub hold_ms: f32,
+    /// 释放时长（毫秒），保持结束后增益线性降到 0 的时间
+    pub release_ms: f32,
+}
+
+impl Default for NoiseGateConfig {
+    fn default() -> Self {
+        NoiseGateConfig::new(-50.0)
+    }
+}
+
+impl NoiseGateConfig {
+    /// 创建指定阈值的噪声门配置，保持 100ms，释放 200ms
+    pub fn new(threshold_db: f32) -> Self {
+        NoiseGateConfig {
+            threshold_db,
+            hold_ms: 100.0,
+            release_ms: 200.0,
+        }
+    }
+
+    /// 设置保持时长（毫秒）
+    pub fn with_hold_ms(mut self, hold_ms: f32) -> Self {
+        self.hold_ms = hold_ms;
+        self
+    }
+
+    /// 设置释放时长（毫秒）
+    pub fn with_release_ms(mut self, release_ms: f32) -> Self {
+        self.release_ms = release_ms;
+        self
+    }
+}
+
+/// 对交错样本应用噪声门：任一声道的电平超过阈值时打开，低于阈值并经过保持时长后在释放时长内逐渐静音
+///
+/// 适合去除长时间录音中语句之间的底噪，各声道使用相同的增益
+/// # Returns
+/// * 被完全静音的帧数
+pub fn apply_noise_gate(samples: &mut [f32], channels: u8, sample_rate: u32, gate: &NoiseGateConfig) -> usize {
+    let mut noise_gate = NoiseGate::new(channels, sample_rate, gate);
+    noise_gate.process(samples);
+    noise_gate.muted_frames
+}
+
+/// [`apply_noise_gate`] 使用的噪声门，保留状态以便分块处理
+pub(crate) struct NoiseGate {
+    channels: usize,
+    threshold: f32,
+    hold_frames: usize,
+    /// 每帧增益的升高和降低量
+    attack_step: f32,
+    release_step: f32,
+    /// 保持阶段剩余的帧数
+    hold_remaining: usize,
+    gain: f32,
+    muted_frames: usize,
+}
+
+impl NoiseGate {
+    pub fn new(channels: u8, sample_rate: u32, gate: &NoiseGateConfig) -> Self {
+        let ms_to_frames = |ms: f32| ms.max(0.0) * sample_rate as f32 / 1000.0;
+        NoiseGate {
+            channels: channels.max(1) as usize,
+            threshold: db_to_linear(gate.threshold_db),
+            hold_frames: ms_to_frames(gate.hold_ms).round() as usize,
+            attack_step: 1.0 / ms_to_frames(NOISE_GATE_ATTACK_MS).max(1.0),
+            release_step: 1.0 / ms_to_frames(gate.release_ms).max(1.0),
+            hold_remaining: 0,
+            // 开头按关闭处理，静音的开头不会漏出底噪
+            gain: 0.0,
+            muted_frames: 0,
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_exact_mut(self.channels) {
+            let level = frame.iter().fold(0.0f32, |level, s| level.max(s.abs()));
+            if level >= self.threshold {
+                self.hold_remaining = self.hold_frames;
+                self.gain = (self.gain + self.attack_step).min(1.0);
+            } else if self.hold_remaining > 0 {
+                self.hold_remaining -= 1;
+                self.gain = (self.gain + self.attack_step).min(1.0);
+            } else {
+                self.gain = (self.gain - self.release_step).max(0.0);
+            }
+
+            if self.gain <= 0.0 {
+                self.muted_frames += 1;
+            }
+            if self.gain < 1.0 {
+                frame.iter_mut().for_each(|s| *s *= self.gain);
+            }
+        }
+    }
+}
+
+/// 真峰值检测的过采样倍数（ITU-R BS.1770）
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+
+/// 真峰值插值滤波器每侧的抽头数
+const TRUE_PEAK_HALF_TAPS: usize = 8;
+
+/// 真峰值插值滤波器的系数，每个插值相位一组
+type TruePeakTaps = [f64; 2 * TRUE_PEAK_HALF_TAPS];
+
+/// 相邻两帧之间各插值点（第 1..4 个四分之一处）的滤波器系数
+///
+/// 第 `tap` 个系数对应相对左侧帧偏移 `tap + 1 - TRUE_PEAK_HALF_TAPS` 的输入帧，每组系数归一化为直流增益 1
+fn true_peak_taps() -> Vec<TruePeakTaps> {
+    (1..TRUE_PEAK_OVERSAMPLING)
+        .map(|phase| {
+            let position = phase as f64 / TRUE_PEAK_OVERSAMPLING as f64;
+            let mut taps = [0.0; 2 * TRUE_PEAK_HALF_TAPS];
+            for (tap, weight) in taps.iter_mut().enumerate() {
+                let t = position - (tap as f64 + 1.0 - TRUE_PEAK_HALF_TAPS as f64);
+                *weight = sinc(t) * blackman(t / TRUE_PEAK_HALF_TAPS as f64);
+            }
+            let sum: f64 = taps.iter().sum();
+            taps.iter_mut().for_each(|weight| *weight /= sum);
+            taps
+        })
+        .collect()
+}
+
+/// `window` 为连续 `2 * TRUE_PEAK_HALF_TAPS` 帧交错样本，返回中间两帧之间各插值点的最大绝对值
+fn interpolated_peak(window: &[f32], channels: usize, taps: &[TruePeakTaps]) -> f64 {
+    let mut peak = 0.0f64;
+    for c in 0..channels {
+        for phase in taps {
+            let value: f64 = phase.iter().enumerate().map(|(tap, weight)| window[tap * channels + c] as f64 * weight).sum();
+            peak = peak.max(value.abs());
+        }
+    }
+    peak
+}
+
+/// 测量交错样本的真峰值（线性幅度，4 倍过采样），可用 [`linear_to_db`] 转换为 dBTP
+///
+/// 真峰值包含样本之间的插值峰值，重建为模拟信号或有损编码后解码时可能超过样本峰值
+pub fn true_peak(samples: &[f32], channels: u8) -> f32 {
+    let channels = channels.max(1) as usize;
+    let padding = vec![0.0f32; TRUE_PEAK_HALF_TAPS * channels];
+    let padded: Vec<f32> = padding.iter().chain(samples).chain(&padding).copied().collect();
+    let taps = true_peak_taps();
+    let sample_peak = samples.iter().fold(0.0f64, |peak, s| peak.max(s.abs() as f64));
+    let interpolated = padded
+        .windows(2 * TRUE_PEAK_HALF_TAPS * channels)
+        .step_by(channels)
+        .fold(0.0f64, |peak, window| peak.max(interpolated_peak(window, channels, &taps)));
+    sample_peak.max(interpolated) as f32
+}
+
+/// 峰值限制器配置
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct LimiterConfig {
+    /// 真峰值上限（dBTP），播客平台通常要求 -1.0
+    pub ceiling_db: f32,
+    /// 前瞻时长（毫秒），增益在超限的峰值到来之前的这段时间内逐渐降低
+    pub lookahead_ms: f32,
+    /// 释放时间常数（毫秒），峰值过后增益恢复的快慢
+    pub release_ms: f32,
+}
+
+impl Default for LimiterConfig {
+    fn default() -> Self {
+        LimiterConfig::new(-1.0)
+    }
+}
+
+impl LimiterConfig {
+    /// 创建指定真峰值上限的限制器配置，前瞻 5ms，释放 50ms
+    pub fn new(ceiling_db: f32) -> Self {
+        LimiterConfig {
+            ceiling_db,
+            lookahead_ms: 5.0,
+            release_ms: 50.0,
+        }
+    }
+
+    /// 设置前瞻时长（毫秒）
+    pub fn with_lookahead_ms(mut self, lookahead_ms: f32) -> Self {
+        self.lookahead_ms = lookahead_ms;
+        self
+    }
+
+    /// 设置释放时间常数（毫秒）
+    pub fn with_release_ms(mut self, release_ms: f32) -> Self {
+        self.release_ms = release_ms;
+        self
+    }
+}
+
+/// 对交错样本应用前瞻峰值限制器，使真峰值不超过 `limiter.ceiling_db`
+///
+/// 增益在前瞻时间内线性降低到峰值所需的值，之后按释放时间常数恢复；样本数和时间位置保持不变
+/// # Returns
+/// * 最大增益衰减（dB，不大于 0），未触发限制时为 0
+pub fn apply_limiter(samples: &mut [f32], channels: u8, sample_rate: u32, limiter: &LimiterConfig) -> f32 {
+    let mut stream = StreamLimiter::new(channels, sample_rate, limiter);
+    let mut output = Vec::with_capacity(samples.len());
+    stream.process(samples, &mut output);
+    stream.finish(&mut output);
+    samples.copy_from_slice(&output);
+    linear_to_db(stream.min_gain as f32)
+}
+
+/// 分块输入的峰值限制器，输出与对完整信号调用 [`apply_limiter`] 相同
+///
+/// 输出比输入延迟前瞻时长加插值窗口的帧数，[`StreamLimiter::finish`] 输出剩余的帧
+pub(crate) struct StreamLimiter {
+    channels: usize,
+    /// 线性上限
+    ceiling: f64,
+    /// 前瞻帧数
+    lookahead: i64,
+    /// 每帧增益向 1 恢复的比例
+    release: f64,
+    taps: Vec<TruePeakTaps>,
+    /// 尚未输出的帧及其之前 `TRUE_PEAK_HALF_TAPS` 帧（交错），开头以静音填充
+    buffer: Vec<f32>,
+    /// `buffer` 第一帧的输入帧序号
+    buffer_start: i64,
+    /// 已收到的输入帧数
+    in_frames: i64,
+    /// 下一个要测量峰值的插值区间（该帧与下一帧之间）
+    next_interval: i64,
+    /// 上一个插值区间的峰值
+    previous_interval: f64,
+    /// 滑动窗口最小值的单调队列：（帧序号，该帧所需增益 + 帧序号 / 前瞻帧数）
+    window: VecDeque<(i64, f64)>,
+    /// 下一个输出帧的序号
+    next_out: i64,
+    gain: f64,
+    min_gain: f64,
+}
+
+impl StreamLimiter {
+    pub fn new(channels: u8, sample_rate: u32, limiter: &LimiterConfig) -> Self {
+        let channels = channels.max(1) as usize;
+        let ms_to_frames = |ms: f32| ms.max(0.0) as f64 * sample_rate as f64 / 1000.0;
+        let release_frames = ms_to_frames(limiter.release_ms);
+        StreamLimiter {
+            channels,
+            ceiling: db_to_linear(limiter.ceiling_db) as f64,
+            lookahead: (ms_to_frames(limiter.lookahead_ms).round() as i64).max(1),
+            release: if release_frames > 1.0 { 1.0 - (-1.0 / release_frames).exp() } else { 1.0 },
+            taps: true_peak_taps(),
+            buffer: vec![0.0; TRUE_PEAK_HALF_TAPS * channels],
+            buffer_start: -(TRUE_PEAK_HALF_TAPS as i64),
+            in_frames: 0,
+            next_interval: -1,
+            previous_interval: 0.0,
+            window: VecDeque::new(),
+            next_out: 0,
+            gain: 1.0,
+            min_gain: 1.0,
+        }
+    }
+
+    /// 送入一块交错样本，输出前瞻窗口已经完整的帧
+    pub fn process(&mut self, samples: &[f32], output: &mut Vec<f32>) {
+        self.buffer.extend_from_slice(samples);
+        self.in_frames += (samples.len() / self.channels) as i64;
+        self.analyze();
+        // 前瞻窗口内各帧所需的增益都已知时才能输出
+        while self.next_out + self.lookahead < self.next_interval {
+            self.emit(output);
+        }
+        // 丢弃之后的插值不再需要的帧
+        let first = self.next_out - TRUE_PEAK_HALF_TAPS as i64;
+        if first > self.buffer_start {
+            self.buffer.drain(..(first - self.buffer_start) as usize * self.channels);
+            self.buffer_start = first;
+        }
+    }
+
+    /// 输入结束，输出剩余的帧（信号之后按静音处理）
+    pub fn finish(&mut self, output: &mut Vec<f32>) {
+        self.buffer.extend(std::iter::repeat_n(0.0, TRUE_PEAK_HALF_TAPS * self.channels));
+        self.analyze();
+        while self.next_out < self.in_frames {
+            self.emit(output);
+        }
+    }
+
+    /// 计算插值窗口已经完整的各帧所需的增益
+    fn analyze(&mut self) {
+        let half_taps = TRUE_PEAK_HALF_TAPS as i64;
+        let buffered = (self.buffer.len() / self.channels) as i64;
+        while self.next_interval < self.in_frames && self.next_interval + half_taps < self.buffer_start + buffered {
+            let first = (self.next_interval + 1 - half_taps - self.buffer_start) as usize * self.channels;
+            let window = &self.buffer[first..first + 2 * TRUE_PEAK_HALF_TAPS * self.channels];
+            let interval = interpolated_peak(window, self.channels, &self.taps);
+            let frame = self.next_interval;
+            if frame >= 0 {
+                // 该帧的峰值取样本本身及其两侧插值区间的最大值
+                let offset = (frame - self.buffer_start) as usize * self.channels;
+                let sample_peak = self.buffer[offset..offset + self.channels].iter().fold(0.0f64, |peak, s| peak.max(s.abs() as f64));
+                let peak = sample_peak.max(self.previous_interval).max(interval);
+                let required = if peak > self.ceiling { self.ceiling / peak } else { 1.0 };
+                let value = required + frame as f64 / self.lookahead as f64;
+                while self.window.back().is_some_and(|&(_, back)| back >= value) {
+                    self.window.pop_back();
+                }
+                self.window.push_back((frame, value));
+            }
+            self.previous_interval = interval;
+            self.next_interval += 1;
+        }
+    }
+
+    fn emit(&mut self, output: &mut Vec<f32>) {
+        let frame = self.next_out;
+        while self.window.front().is_some_and(|&(index, _)| index < frame) {
+            self.window.pop_front();
+        }
+        // 之后 lookahead 帧内的峰值使增益提前线性降低
+        let target = self.window.front().map_or(1.0, |&(_, value)| value - frame as f64 / self.lookahead as f64).min(1.0);
+        self.gain = if target < self.gain { target } else { (self.gain + (1.0 - self.gain) * self.release).min(target) };
+        self.min_gain = self.min_gain.min(self.gain);
+
+        let offset = (frame - self.buffer_start) as usize * self.channels;
+        let ceiling = self.ceiling as f32;
+        output.extend(self.buffer[offset..offset + self.channels].iter().map(|&s| (s * self.gain as f32).clamp(-ceiling, ceiling)));
+        self.next_out += 1;
+    }
+}
+
+/// 转换过程中对样本应用的处理步骤（由各转换配置中的选项汇总而来）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct Processing {
+    pub channel_map: Option<ChannelMap>,
+    pub target_channels: Option<u8>,
+    pub gain_db: Option<f32>,
+    pub normalize_db: Option<f32>,
+    pub fade: Option<FadeConfig>,
+    pub remove_dc: bool,
+    pub limiter: Option<LimiterConfig>,
+}
+
+impl Processing {
+    /// 对给定声道数的输入是否不做任何修改
+    pub fn is_identity(&self, channels: u8) -> bool {
+        self.channel_map.as_ref().is_none_or(|map| map.is_identity(channels))
+            && self.target_channels.is_none_or(|target| target == channels)
+            && self.gain_db.is_none()
+            && self.normalize_db.is_none()
+            && self.fade.is_none()
+            && !self.remove_dc
+            && self.limiter.is_none()
+    }
+
+    /// 声道映射后、声道转换前的声道数
+    pub fn mapped_channels(&self, channels: u8) -> u8 {
+        self.channel_map.as_ref().map_or(channels, ChannelMap::output_channels)
+    }
+
+    /// 处理后输出的声道数
+    pub fn output_channels(&self, channels: u8) -> u8 {
+        self.target_channels.unwrap_or(self.mapped_channels(channels))
+    }
+
+    /// 依次应用各处理步骤，返回处理后的样本和声道数
+    pub fn apply(&self, samples: Vec<f32>, channels: u8, sample_rate: u32) -> Result<(Vec<f32>, u8), Box<dyn std::error::Error>> {
+        let mut samples = samples;
+        let mut channels = channels;
+
+        // 先修正声道顺序，之后的步骤按映射后的声道处理
+        if let Some(map) = self.channel_map.as_ref().filter(|map| !map.is_identity(channels)) {
+            samples = map.apply(&samples, channels)?;
+            channels = map.output_channels();
+        }
+
+        if self.remove_dc {
+            remove_dc_offset(&mut samples, channels, sample_rate);
+        }
+
+        if let Some(target) = self.target_channels.filter(|&target| target != channels) {
+            samples = convert_channels(&samples, channels, target)?;
+            channels = target;
+        }
+
+        if let Some(gain_db) = self.gain_db {
+            let clipped = apply_gain(&mut samples, gain_db);
+            if clipped > 0 {
+                log::warn!("Gain of {:+.1} dB clipped {} samples", gain_db, clipped);
+            }
+        }
+
+        if let Some(target_db) = self.normalize_db {
+            normalize_peak(&mut samples, target_db);
+        }
+
+        if let Some(fade) = &self.fade {
+            apply_fade(&mut samples, channels, sample_rate, fade);
+        }
+
+        // 限制器放在最后，保证编码前的真峰值不超过上限
+        if let Some(limiter) = &self.limiter {
+            apply_limiter(&mut samples, channels, sample_rate, limiter);
+        }
+
+        Ok((samples, channels))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_channel_conversion() {
+        assert_eq!(downmix_to_mono(&[1.0, 0.0, 0.5, 0.5], 2), vec![0.5, 0.5]);
+        assert_eq!(duplicate_to_stereo(&[0.25, -0.5]), vec![0.25, 0.25, -0.5, -0.5]);
+        assert_eq!(convert_channels(&[0.3, 0.3, 0.3], 3, 1).unwrap().len(), 1);
+        assert!(convert_channels(&[0.0; 4], 2, 6).is_err());
+        assert!(convert_channels(&[0.0; 9], 9, 2).is_err());
+        assert!(convert_channels(&[0.0; 4], 0, 1).is_err());
+    }
+
+    #[test]
+    fn test_surround_downmix_to_stereo() {
+        // 5.1：FL FR FC LFE BL BR
+        let scale = 1.0 / (1.0 + 2.0 * std::f64::consts::FRAC_1_SQRT_2);
+        let front_left = downmix_to_stereo(&[1.0f32, 0.0, 0.0, 0.0, 0.0, 0.0], 6).unwrap();
+        assert!((front_left[0] as f64 - scale).abs() < 1e-6 && front_left[1] == 0.0, "{:?}", front_left);
+        let lfe = downmix_to_stereo(&[0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0], 6).unwrap();
+        assert_eq!(lfe, vec![0.0, 0.0], "LFE 声道应该被舍弃");
+        let full = downmix_to_stereo(&[i16::MAX; 8 * 10], 8).unwrap();
+        assert!(full.iter().all(|&s| s >= i16::MAX - 1), "满幅输入不应该削波也不应该明显衰减");
+
+        // 四声道的后置声道只进入同侧
+        let quad = convert_channels(&[0.0f32, 0.0, 0.0, 0.5], 4, 2).unwrap();
+        assert!(quad[0] == 0.0 && quad[1] > 0.0);
+        assert_eq!(downmix_to_stereo(&[0.25f32, -0.5], 2).unwrap(), vec![0.25, -0.5]);
+    }
+
+    #[test]
+    fn test_channel_map() {
+        let stereo = [1i16, 2, 3, 4, 5];
+        assert_eq!(ChannelMap::swap_stereo().apply(&stereo, 2).unwrap(), vec![2, 1, 4, 3]);
+        assert_eq!(ChannelMap::select(1).apply(&stereo, 2).unwrap(), vec![2, 4]);
+        assert_eq!(ChannelMap::new([0, 0, 1]).apply(&stereo, 2).unwrap(), vec![1, 1, 2, 3, 3, 4]);
+        assert!(ChannelMap::select(2).apply(&stereo, 2).is_err());
+        assert!(ChannelMap::new([]).validate(2).is_err());
+        assert!(ChannelMap::new([0, 1]).is_identity(2));
+        assert!(!ChannelMap::new([0]).is_identity(2));
+
+        assert_eq!("1, 0".parse::<ChannelMap>().unwrap(), ChannelMap::swap_stereo());
+        assert_eq!("SWAP".parse::<ChannelMap>().unwrap(), ChannelMap::swap_stereo());
+        assert!("0,x".parse::<ChannelMap>().is_err());
+
+        let processing = Processing { channel_map: Some(ChannelMap::swap_stereo()), target_channels: Some(1), ..Default::default() };
+        assert_eq!(processing.output_channels(2), 1);
+        let (samples, channels) = processing.apply(vec![0.5, 0.25], 2, 8000).unwrap();
+        assert_eq!((samples, channels), (vec![0.375], 1));
+    }
+
+    #[test]
+    fn test_processing_identity() {
+        let processing = Processing { target_channels: Some(2), ..Default::default() };
+        assert!(processing.is_identity(2));
+        assert!(!processing.is_identity(1));
+        let (samples, channels) = processing.apply(vec![0.5, -0.5], 1, 8000).unwrap();
+        assert_eq!((samples, channels), (vec![0.5, 0.5, -0.5, -0.5], 2));
+    }
+
+    #[test]
+    fn test_fade_in_and_out() {
+        // 1kHz 采样率下 4ms 为 4 帧
+        let mut samples = vec![1.0f32; 20];
+        apply_fade(&mut samples, 2, 1000, &FadeConfig::new(4, 2));
+        assert_eq!(&samples[..8], &[0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.75, 0.75]);
+        assert_eq!(&samples[8..16], &[1.0; 8]);
+        assert_eq!(&samples[16..], &[0.5, 0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_fade_curves_are_monotonic() {
+        for curve in [FadeCurve::Linear, FadeCurve::Exponential, FadeCurve::Logarithmic, FadeCurve::SCurve] {
+            assert_eq!(curve.gain(0.0), 0.0);
+            assert!((curve.gain(1.0) - 1.0).abs() < 1e-6);
+            let values: Vec<f32> = (0..=10).map(|i| curve.gain(i as f32 / 10.0)).collect();
+            assert!(values.windows(2).all(|w| w[0] <= w[1]), "{:?} 应该单调递增", curve);
+        }
+    }
+
+    #[test]
+    fn test_time_stretch_keeps_pitch() {
+        // 1 秒 8kHz 的 440Hz 正弦波，变速后长度按倍率变化，过零率（音高）保持不变
+        let sine: Vec<f32> = (0..8000).map(|i| (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 8000.0).sin() * 0.5).collect();
+        let crossings_per_second = |s: &[f32]| {
+            let middle = &s[s.len() / 4..s.len() * 3 / 4];
+            let crossings = middle.windows(2).filter(|w| (w[0] < 0.0) != (w[1] < 0.0)).count();
+            crossings as f32 * 8000.0 / middle.len() as f32
+        };
+        for factor in [0.75f32, 1.25, 2.0] {
+            let stretched = time_stretch(&sine, 1, 8000, factor).unwrap();
+            assert_eq!(stretched.len(), (8000.0 / factor).round() as usize);
+            let rate = crossings_per_second(&stretched);
+            assert!((rate - 880.0).abs() < 20.0, "倍率 {} 的过零率 {} 应该接近 880", factor, rate);
+        }
+        assert_eq!(time_stretch(&sine, 1, 8000, 1.0).unwrap(), sine);
+        // 无效的倍率在分配输出之前报错
+        for factor in [0.0f32, -1.0, f32::NAN, f32::INFINITY, 1e-30] {
+            assert!(time_stretch(&sine, 1, 8000, factor).is_err(), "倍率 {} 应该报错", factor);
+            assert!(time_stretch(&[], 1, 8000, factor).is_err(), "空输入同样检查倍率 {}", factor);
+        }
+    }
+
+    #[test]
+    fn test_dc_offset_removal() {
+        // 立体声：左声道带 0.2 的直流偏移，右声道为纯交流信号
+        let mut samples: Vec<f32> = (0..8000)
+            .flat_map(|i| {
+                let ac = (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 8000.0).sin() * 0.3;
+                [ac + 0.2, ac]
+            })
+            .collect();
+        let offsets = measure_dc_offset(&samples, 2);
+        assert!((offsets[0] - 0.2).abs() < 1e-3 && offsets[1].abs() < 1e-3, "{:?}", offsets);
+
+        remove_dc_offset(&mut samples, 2, 8000);
+        let offsets = measure_dc_offset(&samples, 2);
+        assert!(offsets.iter().all(|o| o.abs() < 2e-3), "{:?}", offsets);
+        assert!(samples[0].abs() < 1e-6, "开头不应该产生冲击");
+    }
+
+    #[test]
+    fn test_mix_alignment() {
+        let a = [0.5f32; 4];
+        let b = [0.25f32; 2];
+        let mix = |align| mix_samples(&a, &b, 1, 1000, &MixOptions::default().with_align(align));
+        assert_eq!(mix(MixAlign::Start), vec![0.75, 0.75, 0.5, 0.5]);
+        assert_eq!(mix(MixAlign::End), vec![0.5, 0.5, 0.75, 0.75]);
+        assert_eq!(mix(MixAlign::Center), vec![0.5, 0.75, 0.75, 0.5]);
+        // 1kHz 采样率下延迟 3ms，输出延长到 5 帧
+        assert_eq!(mix(MixAlign::Offset(3)), vec![0.5, 0.5, 0.5, 0.75, 0.25]);
+    }
+
+    #[test]
+    fn test_mix_clipping_protection() {
+        let mixed = mix_samples(&[0.8, -0.8], &[0.8, -0.8], 2, 1000, &MixOptions::default());
+        assert_eq!(mixed, vec![1.0, -1.0]);
+    }
+
+    #[test]
+    fn test_stream_resampler_matches_batch() {
+        let input: Vec<f32> = (0..6000).map(|i| (i as f32 * 0.037).sin() * 0.5).collect();
+        for (from, to) in [(48000, 16000), (22050, 44100), (44100, 48000)] {
+            let expected = resample_f32(&input, 2, from, to);
+            let mut resampler = StreamResampler::new(2, from, to);
+            let mut output = Vec::new();
+            for chunk in input.chunks(2 * 333) {
+                resampler.process(chunk, &mut output);
+            }
+            resampler.finish(&mut output);
+            assert_eq!(output, expected, "{} -> {}", from, to);
+        }
+    }
+
+    #[test]
+    fn test_noise_gate_in_chunks() {
+        let input: Vec<f32> = (0..4000).map(|i| (i as f32 * 0.3).sin() * if (i / 1000) % 2 == 0 { 0.5 } else { 0.001 }).collect();
+        let gate = NoiseGateConfig::new(-40.0).with_hold_ms(10.0).with_release_ms(20.0);
+        let mut expected = input.clone();
+        let muted = apply_noise_gate(&mut expected, 1, 8000, &gate);
+        // 两段底噪各 1000 帧，减去保持 80 帧和释放 160 帧
+        assert!((1520..1530).contains(&muted), "静音 {} 帧", muted);
+        assert!(expected[1240..2000].iter().chain(&expected[3240..]).all(|&s| s == 0.0));
+        assert_eq!(&expected[2100..3000], &input[2100..3000]);
+
+        let mut output = input.clone();
+        let mut noise_gate = NoiseGate::new(1, 8000, &gate);
+        for chunk in output.chunks_mut(123) {
+            noise_gate.process(chunk);
+        }
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_stream_limiter_matches_batch() {
+        // 中间一段超过上限的突发
+        let input: Vec<f32> = (0..8000).map(|i| (i as f32 * 0.05).sin() * if (3000..4000).contains(&(i / 2)) { 0.99 } else { 0.3 }).collect();
+        let limiter = LimiterConfig::new(-3.0);
+        let mut expected = input.clone();
+        let reduction = apply_limiter(&mut expected, 2, 8000, &limiter);
+        assert!(reduction < -2.0, "应该衰减约 3dB，实际 {}", reduction);
+
+        let mut stream = StreamLimiter::new(2, 8000, &limiter);
+        let mut output = Vec::new();
+        for chunk in input.chunks(2 * 97) {
+            stream.process(chunk, &mut output);
+        }
+        stream.finish(&mut output);
+        assert_eq!(output, expected);
+        // 突发之外的部分不受影响
+        assert_eq!(&output[..4000], &input[..4000]);
+    }
+
+    #[test]
+    fn test_true_peak_exceeds_sample_peak() {
+        // fs/4 的正弦以 45° 相位采样时，样本峰值只有实际峰值的 0.707
+        let samples: Vec<f32> = (0..400).map(|i| (std::f32::consts::FRAC_PI_2 * i as f32 + std::f32::consts::FRAC_PI_4).sin()).collect();
+        let sample_peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((sample_peak - std::f32::consts::FRAC_1_SQRT_2).abs() < 1e-3);
+        let peak = true_peak(&samples, 1);
+        assert!((peak - 1.0).abs() < 0.02, "真峰值 {}", peak);
+    }
+
+    #[test]
+    fn test_resample_f32_matches_i16() {
+        let samples: Vec<i16> = (0..400).map(|i| ((i as f32 * 0.1).sin() * 10000.0) as i16).collect();
+        let floats: Vec<f32> = samples.iter().map(|&s| s as f32).collect();
+        let expected = resample_i16(&samples, 1, 8000, 11025);
+        let actual = resample_f32(&floats, 1, 8000, 11025);
+        assert_eq!(expected.len(), actual.len());
+        assert!(expected.iter().zip(&actual).all(|(&e, &a)| (e as f32 - a).abs() <= 0.5));
+    }
+
+    #[test]
+    fn test_crossfade_append() {
+        let mut output = vec![1.0f32; 6];
+        crossfade_append(&mut output, &[1.0; 6], 2, 1000, 2);
+        // 重叠 2 帧：6 + 6 - 4 个样本
+        assert_eq!(output.len(), 8);
+        // 等功率曲线在两段相关时略有抬升，但不会出现静音缺口
+        assert!(output.iter().all(|&s| (0.99..=1.42).contains(&s)));
+
+        // 重叠长度不超过片段长度
+        let mut output = vec![0.5f32; 2];
+        crossfade_append(&mut output, &[0.5; 4], 1, 1000, 100);
+        assert_eq!(output.len(), 4);
+    }
+
+    #[test]
+    fn test_gain_with_clipping() {
+        let mut samples = vec![0.25, -0.25, 0.75];
+        let clipped = apply_gain(&mut samples, 6.0206);
+        assert!((samples[0] - 0.5).abs() < 1e-4);
+        assert!((samples[1] + 0.5).abs() < 1e-4);
+        assert_eq!(samples[2], 1.0);
+        assert_eq!(clipped, 1);
+    }
+}
@@ -0,0 +1,114 @@
+// 错误类型：转换函数返回 `Box<dyn Error>`，其中本库产生的错误为 `AudioError`，可通过 `downcast_ref` 按类别处理
+
+use std::fmt;
+use std::io;
+
+/// 本库产生的错误
+///
+/// 转换函数的错误可通过 `err.downcast_ref::<AudioError>()` 取得；读写文件时的错误保持 `std::io::Error` 类型，
+/// `AudioError::kind` 把两者归入同一组类别
+#[derive(Debug)]
+pub enum AudioError {
+    /// 输入数据或参数无效（如扩展名不是 .pcm、数据不是整数个采样帧、时间范围超出文件）
+    InvalidInput(String),
+    /// 输入格式无法识别或不受支持（如未知的 WAV 格式标签、找不到 MPEG 音频帧）
+    UnsupportedFormat(String),
+    /// 音频参数不受支持或组合无效，信息中包含出错的字段名
+    InvalidConfig(String),
+    /// 无法创建、配置或运行编码器
+    Encoder(String),
+    /// 读写错误
+    Io(io::Error),
+    /// 转换被取消
+    Cancelled,
+}
+
+/// 错误类别，由 `AudioError::kind` 得出
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    InvalidInput,
+    UnsupportedFormat,
+    InvalidConfig,
+    Encoder,
+    /// 读写错误及其 `std::io::ErrorKind`
+    Io(io::ErrorKind),
+    Cancelled,
+}
+
+impl AudioError {
+    /// 取得 `Box<dyn Error>` 中错误的类别，不是本库或 IO 产生的错误返回 None
+    /// # Arguments
+    /// * `error` - 转换函数返回的错误
+    /// # Returns
+    /// * `Option<ErrorKind>` - 错误类别
+    pub fn kind(error: &(dyn std::error::Error + 'static)) -> Option<ErrorKind> {
+        if let Some(error) = error.downcast_ref::<AudioError>() {
+            return Some(match error {
+                AudioError::InvalidInput(_) => ErrorKind::InvalidInput,
+                AudioError::UnsupportedFormat(_) => ErrorKind::UnsupportedFormat,
+                AudioError::InvalidConfig(_) => ErrorKind::InvalidConfig,
+                AudioError::Encoder(_) => ErrorKind::Encoder,
+                AudioError::Io(e) => ErrorKind::Io(e.kind()),
+                AudioError::Cancelled => ErrorKind::Cancelled,
+            });
+        }
+        let e = error.downcast_ref::<io::Error>()?;
+        // 经 `Read` / `Write` 传出的错误包装在 io::Error 中
+        match e.get_ref().and_then(|inner| inner.downcast_ref::<AudioError>()) {
+            Some(inner) => AudioError::kind(inner),
+            None => Some(ErrorKind::Io(e.kind())),
+        }
+    }
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::InvalidInput(message)
+            | AudioError::UnsupportedFormat(message)
+            | AudioError::InvalidConfig(message)
+            | AudioError::Encoder(message) => write!(f, "{}", message),
+            AudioError::Io(e) => write!(f, "{}", e),
+            AudioError::Cancelled => write!(f, "Conversion cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AudioError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AudioError {
+    fn from(e: io::Error) -> Self {
+        AudioError::Io(e)
+    }
+}
+
+#[cfg(feature = "mp3-decode")]
+impl From<symphonia::core::errors::Error> for AudioError {
+    fn from(e: symphonia::core::errors::Error) -> Self {
+        use symphonia::core::errors::Error;
+        match e {
+            Error::IoError(e) => AudioError::Io(e),
+            Error::Unsupported(feature) => AudioError::UnsupportedFormat(format!("Unsupported MP3 stream: {}", feature)),
+            e => AudioError::InvalidInput(format!("Failed to decode MP3 stream: {}", e)),
+        }
+    }
+}
+
+/// 把错误转换为可以在线程间传递的类型：`AudioError` 和 `io::Error` 保持原类型，其余错误只保留错误信息
+#[cfg(feature = "capture")]
+pub(crate) fn into_send(error: Box<dyn std::error::Error>) -> Box<dyn std::error::Error + Send + Sync> {
+    match error.downcast::<AudioError>() {
+        Ok(e) => e,
+        Err(error) => match error.downcast::<io::Error>() {
+            Ok(e) => e,
+            Err(error) => error.to_string().into(),
+        },
+    }
+}
@@ -1,11 +1,17 @@
 // FFI (Foreign Function Interface) 绑定，用于移动端调用
 
+use std::cell::RefCell;
 use std::ffi::{CStr, CString};
-use std::os::raw::{c_char, c_int};
+use std::os::raw::{c_char, c_int, c_void};
+use std::path::{Path, PathBuf};
+use crate::mp3;
 use crate::{
-    trans_pcm_file_to_wav, trans_pcm_file_to_mp3, auto_convert_pcm,
+    auto_convert_pcm,
+    trans_pcm_bytes_to_wav, trans_pcm_bytes_to_mp3,
     PcmToWavConfig, Mp3Config, AudioFormat,
-    Mp3Bitrate, AudioQuality
+    Mp3Bitrate, AudioQuality, AudioError, ErrorKind, Presets,
+    CancelToken, ConvertOptions, ConvertStage, DetectedFormat,
+    trans_pcm_file_to_wav_with_options, trans_pcm_file_to_mp3_with_options,
 };
 
 // ==================== C 结构体定义 ====================
@@ -36,27 +42,273 @@ pub enum CAudioFormat {
     Mp3 = 1,
 }
 
+/// C 兼容的文件格式枚举（按内容识别）
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CDetectedFormat {
+    /// 没有可识别的文件头，按原始 PCM 处理
+    Pcm = 0,
+    Wav = 1,
+    Mp3 = 2,
+    Flac = 3,
+    Ogg = 4,
+    /// 由 `FormatRegistry` 中注册的处理器识别
+    Registered = 5,
+}
+
+impl From<DetectedFormat> for CDetectedFormat {
+    fn from(format: DetectedFormat) -> Self {
+        match format {
+            DetectedFormat::Wav(_) => CDetectedFormat::Wav,
+            DetectedFormat::Mp3 => CDetectedFormat::Mp3,
+            DetectedFormat::Flac => CDetectedFormat::Flac,
+            DetectedFormat::Ogg => CDetectedFormat::Ogg,
+            DetectedFormat::Registered(_) => CDetectedFormat::Registered,
+            DetectedFormat::Unknown => CDetectedFormat::Pcm,
+        }
+    }
+}
+
+/// C 兼容的音频文件信息结构体，无法得到的字段为 0
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub struct CAudioInfo {
+    pub format: CDetectedFormat,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bits_per_sample: u16,
+    pub duration_ms: u64,
+    pub data_size: u64,     // WAV 为 data 块大小，其余格式为文件大小
+    pub file_size: u64,
+}
+
+impl From<CAudioFormat> for AudioFormat {
+    fn from(format: CAudioFormat) -> Self {
+        match format {
+            CAudioFormat::Wav => AudioFormat::Wav,
+            CAudioFormat::Mp3 => AudioFormat::Mp3,
+        }
+    }
+}
+
+/// FFI 函数的返回码：0 表示成功，负数表示失败的类别，详细信息通过 `get_last_error` 获取
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CErrorCode {
+    /// 成功
+    Ok = 0,
+    /// 未归类的错误（与早期版本统一返回的 -1 相同）
+    Unknown = -1,
+    /// 参数为空指针或取值无效（如不存在的预设名称、输入不是 PCM 文件）
+    InvalidArgument = -2,
+    /// 路径或字符串参数不是有效的 UTF-8
+    InvalidPath = -3,
+    /// 输入文件或输出目录不存在
+    FileNotFound = -4,
+    /// 没有读写权限
+    PermissionDenied = -5,
+    /// 其他读写错误（如磁盘已满）
+    IoError = -6,
+    /// 音频参数不受支持或组合无效，或输入格式不受支持
+    UnsupportedConfig = -7,
+    /// 无法创建或配置 MP3 编码器
+    EncoderUnavailable = -8,
+    /// 转换被取消
+    Cancelled = -9,
+}
+
+/// C 兼容的转换阶段枚举
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CConvertStage {
+    Reading = 0,
+    Processing = 1,
+    Encoding = 2,
+    Writing = 3,
+}
+
+impl From<ConvertStage> for CConvertStage {
+    fn from(stage: ConvertStage) -> Self {
+        match stage {
+            ConvertStage::Reading => CConvertStage::Reading,
+            ConvertStage::Processing => CConvertStage::Processing,
+            ConvertStage::Encoding => CConvertStage::Encoding,
+            ConvertStage::Writing => CConvertStage::Writing,
+        }
+    }
+}
+
+/// C 进度回调：参数为当前阶段、该阶段已处理的字节数、该阶段的总字节数和调用者传入的 `user_data`，
+/// 返回 0 继续转换，返回非 0 取消转换
+pub type CProgressCallback = extern "C" fn(stage: CConvertStage, bytes_processed: u64, total_bytes: u64, user_data: *mut c_void) -> c_int;
+
 // ==================== 辅助函数 ====================
 
+thread_local! {
+    /// 当前线程最近一次失败的 FFI 调用的错误码和错误信息
+    static LAST_ERROR: RefCell<Option<(CErrorCode, String)>> = const { RefCell::new(None) };
+}
+
+/// FFI 层自身产生的错误（参数检查），直接携带错误码；JNI 绑定也使用它
+#[derive(Debug)]
+pub(crate) struct FfiError(pub CErrorCode, pub String);
+
+impl std::fmt::Display for FfiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.1)
+    }
+}
+
+impl std::error::Error for FfiError {}
+
+/// 按错误类型确定错误码
+pub(crate) fn error_code(error: &(dyn std::error::Error + 'static)) -> CErrorCode {
+    if let Some(FfiError(code, _)) = error.downcast_ref::<FfiError>() {
+        return *code;
+    }
+    if error.is::<std::str::Utf8Error>() {
+        return CErrorCode::InvalidPath;
+    }
+    match AudioError::kind(error) {
+        Some(ErrorKind::InvalidInput) => CErrorCode::InvalidArgument,
+        Some(ErrorKind::UnsupportedFormat | ErrorKind::InvalidConfig) => CErrorCode::UnsupportedConfig,
+        Some(ErrorKind::Encoder) => CErrorCode::EncoderUnavailable,
+        Some(ErrorKind::Io(std::io::ErrorKind::NotFound)) => CErrorCode::FileNotFound,
+        Some(ErrorKind::Io(std::io::ErrorKind::PermissionDenied)) => CErrorCode::PermissionDenied,
+        Some(ErrorKind::Io(_)) => CErrorCode::IoError,
+        Some(ErrorKind::Cancelled) => CErrorCode::Cancelled,
+        None => CErrorCode::Unknown,
+    }
+}
+
+/// 记录失败调用的错误码和错误信息
+fn record_error(e: Box<dyn std::error::Error>) -> CErrorCode {
+    let code = error_code(e.as_ref());
+    log::debug!("FFI call failed ({:?}): {}", code, e);
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some((code, e.to_string())));
+    code
+}
+
+/// 记录失败调用的错误码和错误信息，转换为 C 返回码
+fn finish(result: Result<(), Box<dyn std::error::Error>>) -> CErrorCode {
+    match result {
+        Ok(()) => CErrorCode::Ok,
+        Err(e) => record_error(e),
+    }
+}
+
+/// 成功时返回写入的字节数，失败时返回负数错误码
+fn finish_len(result: Result<usize, Box<dyn std::error::Error>>) -> isize {
+    match result {
+        Ok(len) => len as isize,
+        Err(e) => record_error(e) as isize,
+    }
+}
+
 /// 将 C 字符串转换为 Rust 字符串
 unsafe fn c_str_to_string(c_str: *const c_char) -> Result<String, Box<dyn std::error::Error>> {
     if c_str.is_null() {
-        return Err("Null pointer provided".into());
+        return Err(FfiError(CErrorCode::InvalidArgument, "Null pointer provided".into()).into());
     }
     
     let c_str = unsafe { CStr::from_ptr(c_str) };
     Ok(c_str.to_str()?.to_owned())
 }
 
+/// C 的 `wchar_t`：Windows 上为 UTF-16 码元，其他平台为 UTF-32 码点
+#[cfg(windows)]
+pub type WChar = u16;
+/// C 的 `wchar_t`：Windows 上为 UTF-16 码元，其他平台为 UTF-32 码点
+#[cfg(not(windows))]
+pub type WChar = u32;
+
+/// 将以 0 结尾的宽字符串转换为路径
+///
+/// Windows 上按原样转换（包括不成对的代理项），不会因非 ASCII 用户目录而改变路径
+unsafe fn wide_str_to_path(w_str: *const WChar) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    if w_str.is_null() {
+        return Err(FfiError(CErrorCode::InvalidArgument, "Null pointer provided".into()).into());
+    }
+    let mut len = 0;
+    while unsafe { *w_str.add(len) } != 0 {
+        len += 1;
+    }
+    let units = unsafe { std::slice::from_raw_parts(w_str, len) };
+    
+    #[cfg(windows)]
+    {
+        use std::os::windows::ffi::OsStringExt;
+        Ok(PathBuf::from(std::ffi::OsString::from_wide(units)))
+    }
+    #[cfg(not(windows))]
+    {
+        let path: Option<String> = units.iter().map(|&unit| char::from_u32(unit)).collect();
+        path.map(PathBuf::from)
+            .ok_or_else(|| FfiError(CErrorCode::InvalidPath, "Path is not a valid wide string".into()).into())
+    }
+}
+
+/// 将 C 缓冲区转换为切片，长度为 0 时允许传入空指针
+unsafe fn c_buffer_to_slice<'a>(data: *const u8, len: usize) -> Result<&'a [u8], Box<dyn std::error::Error>> {
+    if len == 0 {
+        return Ok(&[]);
+    }
+    if data.is_null() {
+        return Err(FfiError(CErrorCode::InvalidArgument, "Null buffer provided".into()).into());
+    }
+    Ok(unsafe { std::slice::from_raw_parts(data, len) })
+}
+
+/// 将 C 的 int16_t 数组转换为切片，`count` 为 0 时允许空指针；指针必须按 int16_t 对齐
+unsafe fn c_samples_to_slice<'a>(samples: *const i16, count: usize) -> Result<&'a [i16], Box<dyn std::error::Error>> {
+    if count == 0 {
+        return Ok(&[]);
+    }
+    if samples.is_null() {
+        return Err(FfiError(CErrorCode::InvalidArgument, "Null sample buffer provided".into()).into());
+    }
+    if !samples.is_aligned() {
+        return Err(FfiError(CErrorCode::InvalidArgument, "Sample buffer is not aligned to int16_t".into()).into());
+    }
+    Ok(unsafe { std::slice::from_raw_parts(samples, count) })
+}
+
+/// 将输出数据交给调用者，调用者需要用 `audio_helper_free_buffer` 释放
+unsafe fn hand_over_buffer(data: Vec<u8>, out: *mut *mut u8, out_len: *mut usize) {
+    let len = data.len();
+    let ptr = Box::into_raw(data.into_boxed_slice()) as *mut u8;
+    unsafe {
+        *out = ptr;
+        *out_len = len;
+    }
+}
+
+/// 检查输出指针，并在转换前将其重置，失败时调用者拿到的是 NULL 和 0
+unsafe fn reset_output(out: *mut *mut u8, out_len: *mut usize) -> Result<(), Box<dyn std::error::Error>> {
+    if out.is_null() || out_len.is_null() {
+        return Err(FfiError(CErrorCode::InvalidArgument, "Null output pointer provided".into()).into());
+    }
+    unsafe {
+        *out = std::ptr::null_mut();
+        *out_len = 0;
+    }
+    Ok(())
+}
+
+/// 将 CPcmConfig 转换为 PcmToWavConfig
+fn c_pcm_config_to_rust(c_config: CPcmConfig) -> PcmToWavConfig {
+    PcmToWavConfig::new(c_config.sample_rate, c_config.channels as u8, c_config.bits_per_sample)
+}
+
 /// 将 CMp3Config 转换为 Mp3Config
-fn c_mp3_config_to_rust(c_config: CMp3Config) -> Result<Mp3Config, Box<dyn std::error::Error>> {
+pub(crate) fn c_mp3_config_to_rust(c_config: CMp3Config) -> Result<Mp3Config, Box<dyn std::error::Error>> {
     let bitrate = match c_config.bitrate {
         64 => Mp3Bitrate::Kbps64,
         128 => Mp3Bitrate::Kbps128,
         192 => Mp3Bitrate::Kbps192,
         256 => Mp3Bitrate::Kbps256,
         320 => Mp3Bitrate::Kbps320,
-        _ => return Err(format!("Unsupported bitrate: {}", c_config.bitrate).into()),
+        _ => return Err(FfiError(CErrorCode::UnsupportedConfig, format!("Unsupported bitrate: {}", c_config.bitrate)).into()),
     };
     
     let quality = match c_config.quality {
@@ -64,12 +316,61 @@ fn c_mp3_config_to_rust(c_config: CMp3Config) -> Result<Mp3Config, Box<dyn std::
         1 => AudioQuality::Medium,
         2 => AudioQuality::High,
         3 => AudioQuality::Best,
-        _ => return Err(format!("Unsupported quality: {}", c_config.quality).into()),
+        _ => return Err(FfiError(CErrorCode::UnsupportedConfig, format!("Unsupported quality: {}", c_config.quality)).into()),
     };
     
     Ok(Mp3Config::new(c_config.sample_rate, c_config.channels, bitrate, quality))
 }
 
+/// 调用者传入的 `user_data`，回调在发起转换的线程中同步执行
+struct UserData(*mut c_void);
+
+// 指针只原样交还给 C 回调，库本身不访问其指向的数据
+unsafe impl Send for UserData {}
+
+/// 按 C 回调创建转换选项，回调为 NULL 时不报告进度
+fn progress_options(callback: Option<CProgressCallback>, user_data: *mut c_void) -> ConvertOptions {
+    let Some(callback) = callback else {
+        return ConvertOptions::default();
+    };
+    let cancel = CancelToken::new();
+    let token = cancel.clone();
+    let user_data = UserData(user_data);
+    ConvertOptions::new()
+        .with_cancel(cancel)
+        .with_progress(move |event| {
+            // 整体捕获 UserData，只捕获其中的裸指针会使闭包不满足 Send
+            let user_data = &user_data;
+            if callback(event.stage.into(), event.bytes_processed, event.total_bytes, user_data.0) != 0 {
+                token.cancel();
+            }
+        })
+}
+
+/// PCM 文件转 WAV，供带进度、不带进度和宽字符路径的 C 接口共用
+fn convert_to_wav(input_path: &Path, output_path: &Path, config: *const CPcmConfig, options: ConvertOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let wav_config = if config.is_null() {
+        None
+    } else {
+        Some(c_pcm_config_to_rust(unsafe { *config }))
+    };
+    
+    trans_pcm_file_to_wav_with_options(input_path, output_path, wav_config, options)?;
+    Ok(())
+}
+
+/// PCM 文件转 MP3，供带进度、不带进度和宽字符路径的 C 接口共用
+fn convert_to_mp3(input_path: &Path, output_path: &Path, config: *const CMp3Config, options: ConvertOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let mp3_config = if config.is_null() {
+        None
+    } else {
+        Some(c_mp3_config_to_rust(unsafe { *config })?)
+    };
+    
+    trans_pcm_file_to_mp3_with_options(input_path, output_path, mp3_config, options)?;
+    Ok(())
+}
+
 // ==================== PCM 到 WAV 转换 ====================
 
 /// PCM 转 WAV (C FFI)
@@ -78,37 +379,51 @@ fn c_mp3_config_to_rust(c_config: CMp3Config) -> Result<Mp3Config, Box<dyn std::
 /// * `output_path` - 输出 WAV 文件路径 (C 字符串)
 /// * `config` - PCM 配置，可以为 NULL 使用默认配置
 /// # 返回值
-/// * 0 - 成功
-/// * -1 - 失败
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[unsafe(no_mangle)]
 pub extern "C" fn pcm_to_wav(
     input_path: *const c_char,
     output_path: *const c_char,
     config: *const CPcmConfig,
-) -> c_int {
+) -> CErrorCode {
     let result = || -> Result<(), Box<dyn std::error::Error>> {
         let input_str = unsafe { c_str_to_string(input_path)? };
         let output_str = unsafe { c_str_to_string(output_path)? };
-        
-        let wav_config = if config.is_null() {
-            None
-        } else {
-            let c_cfg = unsafe { *config };
-            Some(PcmToWavConfig::new(
-                c_cfg.sample_rate,
-                c_cfg.channels as u8,
-                c_cfg.bits_per_sample,
-            ))
-        };
-        
-        trans_pcm_file_to_wav(&input_str, &output_str, wav_config)?;
-        Ok(())
+        convert_to_wav(Path::new(&input_str), Path::new(&output_str), config, ConvertOptions::default())
     };
     
-    match result() {
-        Ok(()) => 0,
-        Err(_) => -1,
-    }
+    finish(result())
+}
+
+/// PCM 转 WAV，并通过回调报告进度 (C FFI)
+/// # 参数
+/// * `input_path` - 输入 PCM 文件路径 (C 字符串)
+/// * `output_path` - 输出 WAV 文件路径 (C 字符串)
+/// * `config` - PCM 配置，可以为 NULL 使用默认配置
+/// * `callback` - 进度回调，在调用线程中同步执行，返回非 0 取消转换；可以为 NULL
+/// * `user_data` - 原样传给回调的指针
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * `CErrorCode::Cancelled` - 回调要求取消，已有的输出文件保持不变
+/// * 其他负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_to_wav_with_progress(
+    input_path: *const c_char,
+    output_path: *const c_char,
+    config: *const CPcmConfig,
+    callback: Option<CProgressCallback>,
+    user_data: *mut c_void,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        let input_str = unsafe { c_str_to_string(input_path)? };
+        let output_str = unsafe { c_str_to_string(output_path)? };
+        convert_to_wav(Path::new(&input_str), Path::new(&output_str), config, progress_options(callback, user_data))
+    };
+    
+    finish(result())
 }
 
 // ==================== PCM 到 MP3 转换 ====================
@@ -119,32 +434,540 @@ pub extern "C" fn pcm_to_wav(
 /// * `output_path` - 输出 MP3 文件路径 (C 字符串)
 /// * `config` - MP3 配置，可以为 NULL 使用默认配置
 /// # 返回值
-/// * 0 - 成功
-/// * -1 - 失败
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[unsafe(no_mangle)]
 pub extern "C" fn pcm_to_mp3(
     input_path: *const c_char,
     output_path: *const c_char,
     config: *const CMp3Config,
-) -> c_int {
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        let input_str = unsafe { c_str_to_string(input_path)? };
+        let output_str = unsafe { c_str_to_string(output_path)? };
+        convert_to_mp3(Path::new(&input_str), Path::new(&output_str), config, ConvertOptions::default())
+    };
+    
+    finish(result())
+}
+
+/// PCM 转 MP3，并通过回调报告进度 (C FFI)
+/// # 参数
+/// * `input_path` - 输入 PCM 文件路径 (C 字符串)
+/// * `output_path` - 输出 MP3 文件路径 (C 字符串)
+/// * `config` - MP3 配置，可以为 NULL 使用默认配置
+/// * `callback` - 进度回调，在调用线程中同步执行，返回非 0 取消转换；可以为 NULL
+/// * `user_data` - 原样传给回调的指针
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * `CErrorCode::Cancelled` - 回调要求取消，已有的输出文件保持不变
+/// * 其他负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_to_mp3_with_progress(
+    input_path: *const c_char,
+    output_path: *const c_char,
+    config: *const CMp3Config,
+    callback: Option<CProgressCallback>,
+    user_data: *mut c_void,
+) -> CErrorCode {
     let result = || -> Result<(), Box<dyn std::error::Error>> {
         let input_str = unsafe { c_str_to_string(input_path)? };
         let output_str = unsafe { c_str_to_string(output_path)? };
+        convert_to_mp3(Path::new(&input_str), Path::new(&output_str), config, progress_options(callback, user_data))
+    };
+    
+    finish(result())
+}
+
+/// 按预设将 PCM 转为 MP3 (C FFI)
+/// # 参数
+/// * `input_path` - 输入 PCM 文件路径 (C 字符串)，音频参数从文件名推断
+/// * `output_path` - 输出 MP3 文件路径 (C 字符串)
+/// * `preset_name` - 预设名称 (C 字符串)，内置预设（voice、music、podcast、archive）或通过 `Presets::register` 注册的预设
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息（包括预设名称不存在）
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_to_mp3_preset(
+    input_path: *const c_char,
+    output_path: *const c_char,
+    preset_name: *const c_char,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        let input_str = unsafe { c_str_to_string(input_path)? };
+        let output_str = unsafe { c_str_to_string(output_path)? };
+        let preset_str = unsafe { c_str_to_string(preset_name)? };
+        if !Presets::contains(&preset_str) {
+            return Err(FfiError(CErrorCode::InvalidArgument, format!("Unknown preset: {}", preset_str)).into());
+        }
+        
+        crate::preset::convert_with_named_preset(Path::new(&input_str), Path::new(&output_str), &preset_str)?;
+        Ok(())
+    };
+    
+    finish(result())
+}
+
+// ==================== 宽字符路径 ====================
+
+/// PCM 转 WAV，路径为宽字符串 (C FFI)
+///
+/// 供 Windows 宿主直接传入 `wchar_t` 路径，避免转换为本地代码页时损坏非 ASCII 字符
+/// # 参数
+/// * `input_path` - 输入 PCM 文件路径 (`wchar_t` 字符串)
+/// * `output_path` - 输出 WAV 文件路径 (`wchar_t` 字符串)
+/// * `config` - PCM 配置，可以为 NULL 使用默认配置
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_to_wav_w(
+    input_path: *const WChar,
+    output_path: *const WChar,
+    config: *const CPcmConfig,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        let input = unsafe { wide_str_to_path(input_path)? };
+        let output = unsafe { wide_str_to_path(output_path)? };
+        convert_to_wav(&input, &output, config, ConvertOptions::default())
+    };
+    
+    finish(result())
+}
+
+/// PCM 转 MP3，路径为宽字符串 (C FFI)
+/// # 参数
+/// * `input_path` - 输入 PCM 文件路径 (`wchar_t` 字符串)
+/// * `output_path` - 输出 MP3 文件路径 (`wchar_t` 字符串)
+/// * `config` - MP3 配置，可以为 NULL 使用默认配置
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_to_mp3_w(
+    input_path: *const WChar,
+    output_path: *const WChar,
+    config: *const CMp3Config,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        let input = unsafe { wide_str_to_path(input_path)? };
+        let output = unsafe { wide_str_to_path(output_path)? };
+        convert_to_mp3(&input, &output, config, ConvertOptions::default())
+    };
+    
+    finish(result())
+}
+
+/// 智能自动转换，路径为宽字符串 (C FFI)
+/// # 参数
+/// * `input_path` - 输入 PCM 文件路径 (`wchar_t` 字符串)，音频参数从文件名推断
+/// * `output_path` - 输出文件路径 (`wchar_t` 字符串)
+/// * `format` - 输出格式 (0=WAV, 1=MP3)
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn auto_convert_audio_w(
+    input_path: *const WChar,
+    output_path: *const WChar,
+    format: CAudioFormat,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        let input = unsafe { wide_str_to_path(input_path)? };
+        let output = unsafe { wide_str_to_path(output_path)? };
+        auto_convert_pcm(&input, &output, format.into())?;
+        Ok(())
+    };
+    
+    finish(result())
+}
+
+// ==================== 内存缓冲区转换 ====================
+
+/// 将内存中的 PCM 数据转换为 WAV (C FFI)，不写入临时文件
+/// # 参数
+/// * `pcm_data` - PCM 数据指针，`pcm_len` 为 0 时可以为 NULL
+/// * `pcm_len` - PCM 数据的字节数
+/// * `config` - PCM 配置，可以为 NULL 使用默认配置
+/// * `out` - 接收 WAV 数据指针，成功后需要用 `audio_helper_free_buffer` 释放；失败时为 NULL
+/// * `out_len` - 接收 WAV 数据的字节数，失败时为 0
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_buffer_to_wav_buffer(
+    pcm_data: *const u8,
+    pcm_len: usize,
+    config: *const CPcmConfig,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        unsafe { reset_output(out, out_len)? };
+        let pcm = unsafe { c_buffer_to_slice(pcm_data, pcm_len)? };
+        
+        let wav_config = if config.is_null() {
+            None
+        } else {
+            Some(c_pcm_config_to_rust(unsafe { *config }))
+        };
+        
+        let wav_data = trans_pcm_bytes_to_wav(pcm, wav_config)?;
+        unsafe { hand_over_buffer(wav_data, out, out_len) };
+        Ok(())
+    };
+    
+    finish(result())
+}
+
+/// 将内存中的 PCM 数据编码为 MP3 (C FFI)，不写入临时文件
+/// # 参数
+/// * `pcm_data` - PCM 数据指针，`pcm_len` 为 0 时可以为 NULL
+/// * `pcm_len` - PCM 数据的字节数
+/// * `config` - MP3 配置，可以为 NULL 使用默认配置
+/// * `out` - 接收 MP3 数据指针，成功后需要用 `audio_helper_free_buffer` 释放；失败时为 NULL
+/// * `out_len` - 接收 MP3 数据的字节数，失败时为 0
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_buffer_to_mp3_buffer(
+    pcm_data: *const u8,
+    pcm_len: usize,
+    config: *const CMp3Config,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        unsafe { reset_output(out, out_len)? };
+        let pcm = unsafe { c_buffer_to_slice(pcm_data, pcm_len)? };
         
         let mp3_config = if config.is_null() {
             None
         } else {
-            let c_cfg = unsafe { *config };
-            Some(c_mp3_config_to_rust(c_cfg)?)
+            Some(c_mp3_config_to_rust(unsafe { *config })?)
+        };
+        
+        let mp3_data = trans_pcm_bytes_to_mp3(pcm, mp3_config)?;
+        unsafe { hand_over_buffer(mp3_data, out, out_len) };
+        Ok(())
+    };
+    
+    finish(result())
+}
+
+/// 将内存中的交错 16 位样本转换为 WAV (C FFI)，直接读取调用者的样本数组，不需要先转换为字节
+/// # 参数
+/// * `samples` - 交错的 int16_t 样本（本机字节序），`count` 为 0 时可以为 NULL
+/// * `count` - 样本总数（所有声道合计，不是帧数）
+/// * `config` - PCM 配置，可以为 NULL 使用默认配置；`bits_per_sample` 被忽略，总是 16 位
+/// * `out` - 接收 WAV 数据指针，成功后需要用 `audio_helper_free_buffer` 释放；失败时为 NULL
+/// * `out_len` - 接收 WAV 数据的字节数，失败时为 0
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_samples_to_wav_buffer(
+    samples: *const i16,
+    count: usize,
+    config: *const CPcmConfig,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        unsafe { reset_output(out, out_len)? };
+        let samples = unsafe { c_samples_to_slice(samples, count)? };
+        
+        let wav_config = if config.is_null() {
+            PcmToWavConfig::default()
+        } else {
+            c_pcm_config_to_rust(unsafe { *config })
+        };
+        
+        let wav_data = crate::encode_samples_to_wav(samples, wav_config)?;
+        unsafe { hand_over_buffer(wav_data, out, out_len) };
+        Ok(())
+    };
+    
+    finish(result())
+}
+
+/// 将内存中的交错 16 位样本编码为 MP3 (C FFI)，不需要样本处理时直接编码调用者的样本数组
+/// # 参数
+/// * `samples` - 交错的 int16_t 样本（本机字节序），`count` 为 0 时可以为 NULL
+/// * `count` - 样本总数（所有声道合计，不是帧数）
+/// * `config` - MP3 配置，可以为 NULL 使用默认配置
+/// * `out` - 接收 MP3 数据指针，成功后需要用 `audio_helper_free_buffer` 释放；失败时为 NULL
+/// * `out_len` - 接收 MP3 数据的字节数，失败时为 0
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn pcm_samples_to_mp3_buffer(
+    samples: *const i16,
+    count: usize,
+    config: *const CMp3Config,
+    out: *mut *mut u8,
+    out_len: *mut usize,
+) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        unsafe { reset_output(out, out_len)? };
+        let samples = unsafe { c_samples_to_slice(samples, count)? };
+        
+        let mp3_config = if config.is_null() {
+            Mp3Config::default()
+        } else {
+            c_mp3_config_to_rust(unsafe { *config })?
         };
         
-        trans_pcm_file_to_mp3(&input_str, &output_str, mp3_config)?;
+        let (mp3_data, _, _) = crate::encode_samples_to_mp3(samples, mp3_config, &mut crate::ConvertOptions::default())?;
+        unsafe { hand_over_buffer(mp3_data, out, out_len) };
         Ok(())
     };
     
+    finish(result())
+}
+
+/// 释放由库分配的数据缓冲区
+/// # 参数
+/// * `buffer` - 缓冲区转换函数返回的数据指针，可以为 NULL
+/// * `len` - 同一次调用返回的字节数
+#[unsafe(no_mangle)]
+pub extern "C" fn audio_helper_free_buffer(buffer: *mut u8, len: usize) {
+    if !buffer.is_null() {
+        unsafe {
+            let _ = Box::from_raw(std::ptr::slice_from_raw_parts_mut(buffer, len));
+        }
+    }
+}
+
+// ==================== 流式编码句柄 ====================
+
+/// 流式 MP3 编码句柄，C 侧只持有指针（`AH_EncoderHandle*`）
+///
+/// 同一个句柄不能被多个线程同时使用；不同句柄之间互不影响
+pub struct EncoderHandle {
+    encoder: mp3::StreamEncoder,
+    /// 每帧的字节数（16 位交错样本）
+    frame_bytes: usize,
+    /// 上次送入的数据中不足一帧的尾部字节
+    remainder: Vec<u8>,
+    /// 已编码但还没放进调用者缓冲区的数据
+    pending: Vec<u8>,
+    finished: bool,
+}
+
+impl EncoderHandle {
+    /// 将待输出的数据尽量复制到调用者的缓冲区，返回复制的字节数
+    fn drain_into(&mut self, out: *mut u8, out_cap: usize) -> Result<usize, Box<dyn std::error::Error>> {
+        let len = self.pending.len().min(out_cap);
+        if len == 0 {
+            return Ok(0);
+        }
+        if out.is_null() {
+            return Err(FfiError(CErrorCode::InvalidArgument, "Null output buffer provided".into()).into());
+        }
+        unsafe { std::ptr::copy_nonoverlapping(self.pending.as_ptr(), out, len) };
+        self.pending.drain(..len);
+        Ok(len)
+    }
+}
+
+/// 取得句柄的可变引用
+unsafe fn handle_mut<'a>(handle: *mut EncoderHandle) -> Result<&'a mut EncoderHandle, Box<dyn std::error::Error>> {
+    if handle.is_null() {
+        return Err(FfiError(CErrorCode::InvalidArgument, "Null encoder handle provided".into()).into());
+    }
+    Ok(unsafe { &mut *handle })
+}
+
+/// 创建流式 MP3 编码句柄 (C FFI)
+///
+/// 输入为 16 位小端交错 PCM，采样率必须是 MP3 标准采样率（不做重采样）。
+/// 码流开头不写 Xing/LAME 标签帧，适合边编码边发送的实时场景
+/// # 参数
+/// * `config` - MP3 配置，可以为 NULL 使用默认配置
+/// # 返回值
+/// * 编码句柄，使用完毕后需要用 `ah_encoder_close` 释放
+/// * NULL - 创建失败，调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn ah_encoder_open(config: *const CMp3Config) -> *mut EncoderHandle {
+    let result = || -> Result<EncoderHandle, Box<dyn std::error::Error>> {
+        let mp3_config = if config.is_null() {
+            Mp3Config::default()
+        } else {
+            c_mp3_config_to_rust(unsafe { *config })?
+        };
+        // 分块送入的数据无法整体重采样
+        let mp3_config = mp3_config.with_resample(false);
+        mp3_config.validate()?;
+        
+        Ok(EncoderHandle {
+            encoder: mp3::StreamEncoder::new(&mp3_config, false)?,
+            frame_bytes: mp3_config.channels as usize * 2,
+            remainder: Vec::new(),
+            pending: Vec::new(),
+            finished: false,
+        })
+    };
+    
     match result() {
-        Ok(()) => 0,
-        Err(_) => -1,
+        Ok(handle) => Box::into_raw(Box::new(handle)),
+        Err(e) => {
+            record_error(e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// 向编码句柄送入一块 PCM 数据 (C FFI)
+///
+/// 编码结果写入 `out`，放不下的部分保留在句柄中，下次调用（可以传入 `len` 为 0）时继续输出。
+/// `out_cap` 不小于 `len * 5 / 8 + 7200` 时一次即可取完
+/// # 参数
+/// * `handle` - `ah_encoder_open` 返回的句柄
+/// * `pcm` - 16 位小端交错 PCM 数据，`len` 为 0 时可以为 NULL；不足一帧的尾部会与下一块拼接
+/// * `len` - PCM 数据的字节数
+/// * `out` - 接收 MP3 数据的缓冲区
+/// * `out_cap` - `out` 的容量
+/// # 返回值
+/// * 非负数 - 写入 `out` 的字节数（LAME 内部有缓冲，可能为 0）
+/// * 负数 - 失败的类别（`CErrorCode` 的值），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn ah_encoder_feed(
+    handle: *mut EncoderHandle,
+    pcm: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_cap: usize,
+) -> isize {
+    let result = || -> Result<usize, Box<dyn std::error::Error>> {
+        let handle = unsafe { handle_mut(handle)? };
+        let pcm = unsafe { c_buffer_to_slice(pcm, len)? };
+        if handle.finished && !pcm.is_empty() {
+            return Err(FfiError(CErrorCode::InvalidArgument, "Encoder has already been finished".into()).into());
+        }
+        
+        handle.remainder.extend_from_slice(pcm);
+        let whole = handle.remainder.len() / handle.frame_bytes * handle.frame_bytes;
+        if whole > 0 {
+            let samples = crate::decode_samples_i16(&handle.remainder[..whole], crate::SampleFormat::S16);
+            handle.encoder.encode(&samples, &mut handle.pending)?;
+            handle.remainder.drain(..whole);
+        }
+        handle.drain_into(out, out_cap)
+    };
+    
+    finish_len(result())
+}
+
+/// 向编码句柄送入一块交错的 16 位样本 (C FFI)
+///
+/// 与 `ah_encoder_feed` 相同，但直接编码调用者的样本数组，不经过字节转换；两者可以交替使用（`ah_encoder_feed` 送入的字节数需为偶数）
+/// # 参数
+/// * `handle` - `ah_encoder_open` 返回的句柄
+/// * `samples` - 交错的 int16_t 样本（本机字节序），`count` 为 0 时可以为 NULL；不足一帧的尾部会与下一块拼接
+/// * `count` - 样本总数（所有声道合计，不是帧数）
+/// * `out` - 接收 MP3 数据的缓冲区
+/// * `out_cap` - `out` 的容量，不小于 `count * 5 / 4 / 声道数 + 7200` 时一次即可取完
+/// # 返回值
+/// * 非负数 - 写入 `out` 的字节数（LAME 内部有缓冲，可能为 0）
+/// * 负数 - 失败的类别（`CErrorCode` 的值），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn ah_encoder_feed_samples(
+    handle: *mut EncoderHandle,
+    samples: *const i16,
+    count: usize,
+    out: *mut u8,
+    out_cap: usize,
+) -> isize {
+    let result = || -> Result<usize, Box<dyn std::error::Error>> {
+        let handle = unsafe { handle_mut(handle)? };
+        let samples = unsafe { c_samples_to_slice(samples, count)? };
+        if handle.finished && !samples.is_empty() {
+            return Err(FfiError(CErrorCode::InvalidArgument, "Encoder has already been finished".into()).into());
+        }
+        
+        if handle.remainder.len() % 2 != 0 {
+            return Err(FfiError(CErrorCode::InvalidArgument, "Previous ah_encoder_feed call ended in the middle of a sample".into()).into());
+        }
+        
+        let channels = handle.frame_bytes / 2;
+        let mut samples = samples;
+        if !handle.remainder.is_empty() {
+            // 先用新样本补齐上次剩下的不完整帧
+            let missing = ((handle.frame_bytes - handle.remainder.len()) / 2).min(samples.len());
+            handle.remainder.extend(samples[..missing].iter().flat_map(|s| s.to_le_bytes()));
+            samples = &samples[missing..];
+            if handle.remainder.len() == handle.frame_bytes {
+                let frame = crate::decode_samples_i16(&handle.remainder, crate::SampleFormat::S16);
+                handle.encoder.encode(&frame, &mut handle.pending)?;
+                handle.remainder.clear();
+            }
+        }
+        let whole = samples.len() / channels * channels;
+        if whole > 0 {
+            handle.encoder.encode(&samples[..whole], &mut handle.pending)?;
+        }
+        handle.remainder.extend(samples[whole..].iter().flat_map(|s| s.to_le_bytes()));
+        handle.drain_into(out, out_cap)
+    };
+    
+    finish_len(result())
+}
+
+/// 结束编码，输出编码器中剩余的数据 (C FFI)
+///
+/// 不足一帧的尾部数据被丢弃。`out` 放不下时可以重复调用，直到返回 0
+/// # 参数
+/// * `handle` - `ah_encoder_open` 返回的句柄
+/// * `out` - 接收 MP3 数据的缓冲区
+/// * `out_cap` - `out` 的容量，不小于 7200 字节时一次即可取完
+/// # 返回值
+/// * 非负数 - 写入 `out` 的字节数
+/// * 负数 - 失败的类别（`CErrorCode` 的值），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn ah_encoder_finish(handle: *mut EncoderHandle, out: *mut u8, out_cap: usize) -> isize {
+    let result = || -> Result<usize, Box<dyn std::error::Error>> {
+        let handle = unsafe { handle_mut(handle)? };
+        if !handle.finished {
+            if !handle.remainder.is_empty() {
+                log::debug!("Dropping {} bytes of incomplete PCM frame", handle.remainder.len());
+                handle.remainder.clear();
+            }
+            handle.encoder.flush(&mut handle.pending)?;
+            handle.finished = true;
+        }
+        handle.drain_into(out, out_cap)
+    };
+    
+    finish_len(result())
+}
+
+/// 释放编码句柄 (C FFI)，未取走的数据被丢弃
+/// # 参数
+/// * `handle` - `ah_encoder_open` 返回的句柄，可以为 NULL
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn ah_encoder_close(handle: *mut EncoderHandle) {
+    if !handle.is_null() {
+        unsafe {
+            let _ = Box::from_raw(handle);
+        }
     }
 }
 
@@ -156,31 +979,24 @@ pub extern "C" fn pcm_to_mp3(
 /// * `output_path` - 输出文件路径 (C 字符串)
 /// * `format` - 输出格式 (0=WAV, 1=MP3)
 /// # 返回值
-/// * 0 - 成功
-/// * -1 - 失败
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[unsafe(no_mangle)]
 pub extern "C" fn auto_convert_audio(
     input_path: *const c_char,
     output_path: *const c_char,
     format: CAudioFormat,
-) -> c_int {
+) -> CErrorCode {
     let result = || -> Result<(), Box<dyn std::error::Error>> {
         let input_str = unsafe { c_str_to_string(input_path)? };
         let output_str = unsafe { c_str_to_string(output_path)? };
         
-        let audio_format = match format {
-            CAudioFormat::Wav => AudioFormat::Wav,
-            CAudioFormat::Mp3 => AudioFormat::Mp3,
-        };
-        
-        auto_convert_pcm(&input_str, &output_str, audio_format)?;
+        auto_convert_pcm(&input_str, &output_str, format.into())?;
         Ok(())
     };
     
-    match result() {
-        Ok(()) => 0,
-        Err(_) => -1,
-    }
+    finish(result())
 }
 
 // ==================== 配置推断 ====================
@@ -190,15 +1006,19 @@ pub extern "C" fn auto_convert_audio(
 /// * `filename` - 文件名 (C 字符串)
 /// * `config` - 输出配置结构体指针
 /// # 返回值
-/// * 0 - 成功
-/// * -1 - 失败
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[unsafe(no_mangle)]
 pub extern "C" fn infer_config_from_filename(
     filename: *const c_char,
     config: *mut CPcmConfig,
-) -> c_int {
+) -> CErrorCode {
     let result = || -> Result<(), Box<dyn std::error::Error>> {
         let filename_str = unsafe { c_str_to_string(filename)? };
+        if config.is_null() {
+            return Err(FfiError(CErrorCode::InvalidArgument, "Null config pointer provided".into()).into());
+        }
         let audio_config = crate::infer_audio_config_from_filename(&filename_str);
         
         unsafe {
@@ -210,27 +1030,84 @@ pub extern "C" fn infer_config_from_filename(
         Ok(())
     };
     
-    match result() {
-        Ok(()) => 0,
-        Err(_) => -1,
-    }
+    finish(result())
+}
+
+// ==================== 文件信息 ====================
+
+/// 读取音频文件信息 (C FFI)
+///
+/// 格式按内容识别；原始 PCM 的参数从文件名推断，MP3、FLAC 和 Ogg 只填写时长（MP3）和大小
+/// # 参数
+/// * `path` - 文件路径 (C 字符串)
+/// * `info` - 输出信息结构体指针，无法得到的字段为 0
+/// # 返回值
+/// * `CErrorCode::Ok` (0) - 成功
+/// * 负数 - 失败的类别（见 `CErrorCode`），调用 `get_last_error` 获取错误信息
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+#[unsafe(no_mangle)]
+pub extern "C" fn probe_audio(path: *const c_char, info: *mut CAudioInfo) -> CErrorCode {
+    let result = || -> Result<(), Box<dyn std::error::Error>> {
+        let path_str = unsafe { c_str_to_string(path)? };
+        if info.is_null() {
+            return Err(FfiError(CErrorCode::InvalidArgument, "Null info pointer provided".into()).into());
+        }
+        let audio_info = crate::probe_audio(&path_str)?;
+        let config = audio_info.config.as_ref();
+        
+        unsafe {
+            *info = CAudioInfo {
+                format: audio_info.format.into(),
+                sample_rate: config.map_or(0, |config| config.sample_rate),
+                channels: config.map_or(0, |config| config.channels as u16),
+                bits_per_sample: config.map_or(0, |config| config.bits_per_sample),
+                duration_ms: audio_info.duration.map_or(0, |duration| duration.as_millis() as u64),
+                data_size: audio_info.data_size,
+                file_size: audio_info.file_size,
+            };
+        }
+        
+        Ok(())
+    };
+    
+    finish(result())
 }
 
 // ==================== 错误处理 ====================
 
-/// 获取最后一次错误信息 (C FFI)
+/// 获取当前线程最近一次失败调用的错误信息 (C FFI)
+///
+/// 错误信息按线程保存，成功的调用不会清除；多线程调用时各线程只能看到自己的错误
 /// # 返回值
-/// * 错误信息的 C 字符串指针，调用者需要释放内存
+/// * 错误信息的 C 字符串指针，调用者需要用 `free_string` 释放内存
+/// * NULL - 当前线程还没有失败过的调用
 #[unsafe(no_mangle)]
 pub extern "C" fn get_last_error() -> *mut c_char {
-    // TODO: 实现全局错误状态管理
-    let error_msg = CString::new("Error details not implemented yet").unwrap();
-    error_msg.into_raw()
+    LAST_ERROR.with(|last| match last.borrow().as_ref() {
+        // 错误信息中的 NUL 字符会截断 C 字符串，替换为空格
+        Some((_, message)) => CString::new(message.replace('\0', " ")).map_or(std::ptr::null_mut(), CString::into_raw),
+        None => std::ptr::null_mut(),
+    })
+}
+
+/// 获取当前线程最近一次失败调用的错误码 (C FFI)
+/// # 返回值
+/// * 与失败调用的返回值相同；当前线程还没有失败过的调用时为 `Ok`
+#[unsafe(no_mangle)]
+pub extern "C" fn get_last_error_code() -> CErrorCode {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(CErrorCode::Ok, |(code, _)| *code))
+}
+
+/// 清除当前线程保存的错误信息 (C FFI)
+#[unsafe(no_mangle)]
+pub extern "C" fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
 }
 
 /// 释放 C 字符串内存
 /// # 参数
 /// * `str_ptr` - 要释放的 C 字符串指针
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 #[unsafe(no_mangle)]
 pub extern "C" fn free_string(str_ptr: *mut c_char) {
     if !str_ptr.is_null() {
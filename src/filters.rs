@@ -0,0 +1,327 @@
+// 双二阶（biquad）滤波器：低通、高通、带通和搁架滤波，系数按 RBJ Audio EQ Cookbook 计算
+
+use std::f64::consts::PI;
+
+use crate::AudioError;
+
+/// 未指定 Q 值时使用的 Butterworth Q（通带最平坦，搁架滤波器对应斜率 S = 1）
+const DEFAULT_Q: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// 滤波器类型
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FilterType {
+    /// 低通：衰减截止频率以上的成分
+    LowPass,
+    /// 高通：衰减截止频率以下的成分（如去除低频隆隆声）
+    HighPass,
+    /// 带通：保留中心频率附近的成分，带宽由 Q 决定，中心频率处增益为 0 dB
+    BandPass,
+    /// 低频搁架：按增益（dB）提升或衰减转折频率以下的成分
+    LowShelf { gain_db: f32 },
+    /// 高频搁架：按增益（dB）提升或衰减转折频率以上的成分
+    HighShelf { gain_db: f32 },
+}
+
+/// 单个双二阶滤波器
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BiquadFilter {
+    /// 滤波器类型
+    pub filter_type: FilterType,
+    /// 截止频率、中心频率或转折频率（Hz）
+    pub frequency: f32,
+    /// 品质因数，越大过渡越陡（带通越窄）
+    pub q: f32,
+}
+
+impl BiquadFilter {
+    /// 创建指定类型和频率的滤波器，Q 为 0.707
+    pub fn new(filter_type: FilterType, frequency: f32) -> Self {
+        BiquadFilter {
+            filter_type,
+            frequency,
+            q: DEFAULT_Q,
+        }
+    }
+
+    /// 低通滤波器
+    pub fn low_pass(frequency: f32) -> Self {
+        Self::new(FilterType::LowPass, frequency)
+    }
+
+    /// 高通滤波器
+    pub fn high_pass(frequency: f32) -> Self {
+        Self::new(FilterType::HighPass, frequency)
+    }
+
+    /// 带通滤波器，带宽约为 `frequency / q`
+    pub fn band_pass(frequency: f32, q: f32) -> Self {
+        Self::new(FilterType::BandPass, frequency).with_q(q)
+    }
+
+    /// 低频搁架滤波器
+    pub fn low_shelf(frequency: f32, gain_db: f32) -> Self {
+        Self::new(FilterType::LowShelf { gain_db }, frequency)
+    }
+
+    /// 高频搁架滤波器
+    pub fn high_shelf(frequency: f32, gain_db: f32) -> Self {
+        Self::new(FilterType::HighShelf { gain_db }, frequency)
+    }
+
+    /// 设置品质因数
+    pub fn with_q(mut self, q: f32) -> Self {
+        self.q = q;
+        self
+    }
+
+    /// 检查参数在给定采样率下是否有效
+    pub fn validate(&self, sample_rate: u32) -> Result<(), Box<dyn std::error::Error>> {
+        let nyquist = sample_rate as f32 / 2.0;
+        if !(self.frequency > 0.0 && self.frequency < nyquist) {
+            return Err(AudioError::InvalidConfig(format!("Invalid filter frequency: {} Hz (must be between 0 and {} Hz at {} Hz)", self.frequency, nyquist, sample_rate)).into());
+        }
+        if !(self.q.is_finite() && self.q > 0.0) {
+            return Err(AudioError::InvalidConfig(format!("Invalid filter q: {} (must be greater than zero)", self.q)).into());
+        }
+        if let FilterType::LowShelf { gain_db } | FilterType::HighShelf { gain_db } = self.filter_type
+            && !gain_db.is_finite()
+        {
+            return Err(AudioError::InvalidConfig("Invalid filter gain_db: must be a finite number".into()).into());
+        }
+        Ok(())
+    }
+
+    /// 给定采样率下的归一化系数 `[b0, b1, b2, a1, a2]`（a0 = 1）
+    fn coefficients(&self, sample_rate: u32) -> [f64; 5] {
+        let w0 = 2.0 * PI * self.frequency as f64 / sample_rate as f64;
+        let (sin, cos) = w0.sin_cos();
+        let alpha = sin / (2.0 * self.q as f64);
+        let [b0, b1, b2, a0, a1, a2] = match self.filter_type {
+            FilterType::LowPass => [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            FilterType::HighPass => [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            FilterType::BandPass => [alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha],
+            FilterType::LowShelf { gain_db } => {
+                let a = 10f64.powf(gain_db as f64 / 40.0);
+                let k = 2.0 * a.sqrt() * alpha;
+                [
+                    a * ((a + 1.0) - (a - 1.0) * cos + k),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - k),
+                    (a + 1.0) + (a - 1.0) * cos + k,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - k,
+                ]
+            }
+            FilterType::HighShelf { gain_db } => {
+                let a = 10f64.powf(gain_db as f64 / 40.0);
+                let k = 2.0 * a.sqrt() * alpha;
+                [
+                    a * ((a + 1.0) + (a - 1.0) * cos + k),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - k),
+                    (a + 1.0) - (a - 1.0) * cos + k,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - k,
+                ]
+            }
+        };
+        [b0 / a0, b1 / a0, b2 / a0, a1 / a0, a2 / a0]
+    }
+
+    /// 给定频率处的幅度响应（dB）
+    pub fn response_db(&self, frequency: f32, sample_rate: u32) -> f32 {
+        let [b0, b1, b2, a1, a2] = self.coefficients(sample_rate);
+        let w = 2.0 * PI * frequency as f64 / sample_rate as f64;
+        // |H(e^jw)|，分子分母分别按实部和虚部求模
+        let magnitude = |c0: f64, c1: f64, c2: f64| {
+            let re = c0 + c1 * w.cos() + c2 * (2.0 * w).cos();
+            let im = c1 * w.sin() + c2 * (2.0 * w).sin();
+            (re * re + im * im).sqrt()
+        };
+        (20.0 * (magnitude(b0, b1, b2) / magnitude(1.0, a1, a2)).log10()) as f32
+    }
+}
+
+/// 按顺序串联的滤波器
+///
+/// ```
+/// use audio_helper::{BiquadFilter, FilterChain};
+///
+/// // 语音编码前去除 80Hz 以下的隆隆声，并略微提升清晰度
+/// let chain = FilterChain::new()
+///     .with_filter(BiquadFilter::high_pass(80.0))
+///     .with_filter(BiquadFilter::high_shelf(4000.0, 3.0));
+/// let mut samples = vec![0.0f32; 16000];
+/// chain.apply(&mut samples, 1, 16000)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FilterChain {
+    filters: Vec<BiquadFilter>,
+}
+
+impl From<BiquadFilter> for FilterChain {
+    fn from(filter: BiquadFilter) -> Self {
+        FilterChain::new().with_filter(filter)
+    }
+}
+
+impl FilterChain {
+    /// 创建空的滤波器链
+    pub fn new() -> Self {
+        FilterChain::default()
+    }
+
+    /// 在末尾追加一个滤波器
+    pub fn with_filter(mut self, filter: BiquadFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// 链中的滤波器
+    pub fn filters(&self) -> &[BiquadFilter] {
+        &self.filters
+    }
+
+    /// 是否不包含任何滤波器
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+    }
+
+    /// 检查各滤波器在给定采样率下是否有效
+    pub fn validate(&self, sample_rate: u32) -> Result<(), Box<dyn std::error::Error>> {
+        self.filters.iter().try_for_each(|filter| filter.validate(sample_rate))
+    }
+
+    /// 给定频率处整条链的幅度响应（dB）
+    pub fn response_db(&self, frequency: f32, sample_rate: u32) -> f32 {
+        self.filters.iter().map(|filter| filter.response_db(frequency, sample_rate)).sum()
+    }
+
+    /// 对交错样本依次应用各滤波器，每个声道独立滤波
+    /// # Arguments
+    /// * `samples` - 交错的 f32 样本，原地修改
+    /// * `channels` - 声道数
+    /// * `sample_rate` - 采样率，决定滤波器系数
+    pub fn apply(&self, samples: &mut [f32], channels: u8, sample_rate: u32) -> Result<(), Box<dyn std::error::Error>> {
+        FilterProcessor::new(self, channels, sample_rate)?.process(samples);
+        Ok(())
+    }
+}
+
+/// 一个滤波器的系数和各声道的状态（转置直接 II 型）
+struct Section {
+    coefficients: [f64; 5],
+    state: Vec<[f64; 2]>,
+}
+
+/// [`FilterChain::apply`] 使用的滤波器，保留状态以便分块处理
+pub(crate) struct FilterProcessor {
+    channels: usize,
+    sections: Vec<Section>,
+}
+
+impl FilterProcessor {
+    pub fn new(chain: &FilterChain, channels: u8, sample_rate: u32) -> Result<Self, Box<dyn std::error::Error>> {
+        chain.validate(sample_rate)?;
+        let channels = channels.max(1) as usize;
+        let sections = chain
+            .filters
+            .iter()
+            .map(|filter| Section {
+                coefficients: filter.coefficients(sample_rate),
+                state: vec![[0.0; 2]; channels],
+            })
+            .collect();
+        Ok(FilterProcessor { channels, sections })
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for section in &mut self.sections {
+            let [b0, b1, b2, a1, a2] = section.coefficients;
+            for frame in samples.chunks_exact_mut(self.channels) {
+                for (s, state) in frame.iter_mut().zip(section.state.iter_mut()) {
+                    let input = *s as f64;
+                    let output = b0 * input + state[0];
+                    state[0] = b1 * input - a1 * output + state[1];
+                    state[1] = b2 * input - a2 * output;
+                    *s = output as f32;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 滤波后正弦波后半段（跳过暂态）的 RMS 增益（dB）
+    fn measured_gain_db(chain: &FilterChain, frequency: f32, sample_rate: u32) -> f32 {
+        let input: Vec<f32> = (0..sample_rate as usize)
+            .map(|i| (2.0 * std::f32::consts::PI * frequency * i as f32 / sample_rate as f32).sin() * 0.5)
+            .collect();
+        let mut output = input.clone();
+        chain.apply(&mut output, 1, sample_rate).unwrap();
+        let rms = |samples: &[f32]| (samples.iter().map(|&s| (s * s) as f64).sum::<f64>() / samples.len() as f64).sqrt();
+        let half = input.len() / 2;
+        (20.0 * (rms(&output[half..]) / rms(&input[half..])).log10()) as f32
+    }
+
+    #[test]
+    fn test_filter_responses() {
+        let rate = 48000;
+        let high_pass = FilterChain::from(BiquadFilter::high_pass(80.0));
+        assert!((high_pass.response_db(80.0, rate) + 3.01).abs() < 0.05);
+        assert!(high_pass.response_db(20.0, rate) < -20.0);
+        assert!(high_pass.response_db(1000.0, rate).abs() < 0.1);
+
+        let low_pass = FilterChain::from(BiquadFilter::low_pass(1000.0));
+        assert!((low_pass.response_db(1000.0, rate) + 3.01).abs() < 0.05);
+        assert!(low_pass.response_db(8000.0, rate) < -30.0);
+
+        let band_pass = FilterChain::from(BiquadFilter::band_pass(1000.0, 2.0));
+        assert!(band_pass.response_db(1000.0, rate).abs() < 0.01);
+        assert!(band_pass.response_db(200.0, rate) < -15.0);
+        assert!(band_pass.response_db(5000.0, rate) < -15.0);
+
+        let shelves = FilterChain::new().with_filter(BiquadFilter::low_shelf(200.0, 6.0)).with_filter(BiquadFilter::high_shelf(5000.0, -6.0));
+        assert!((shelves.response_db(20.0, rate) - 6.0).abs() < 0.1);
+        assert!((shelves.response_db(20000.0, rate) + 6.0).abs() < 0.2);
+        assert!(shelves.response_db(1000.0, rate).abs() < 0.5);
+
+        // 实际滤波的增益与计算的响应一致
+        for (chain, frequency) in [(&high_pass, 50.0), (&low_pass, 2000.0), (&band_pass, 1000.0), (&shelves, 100.0)] {
+            let measured = measured_gain_db(chain, frequency, rate);
+            let expected = chain.response_db(frequency, rate);
+            assert!((measured - expected).abs() < 0.1, "{:?} @ {}Hz: {} vs {}", chain, frequency, measured, expected);
+        }
+    }
+
+    #[test]
+    fn test_filter_processor_in_chunks() {
+        let chain = FilterChain::new().with_filter(BiquadFilter::high_pass(100.0)).with_filter(BiquadFilter::low_pass(3000.0));
+        let input: Vec<f32> = (0..4000).map(|i| (i as f32 * 0.013).sin() * 0.4 + (i as f32 * 0.9).sin() * 0.2).collect();
+        let mut expected = input.clone();
+        chain.apply(&mut expected, 2, 16000).unwrap();
+
+        let mut output = input;
+        let mut processor = FilterProcessor::new(&chain, 2, 16000).unwrap();
+        for chunk in output.chunks_mut(2 * 77) {
+            processor.process(chunk);
+        }
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn test_invalid_filters() {
+        assert!(BiquadFilter::low_pass(8000.0).validate(16000).is_err());
+        assert!(BiquadFilter::high_pass(0.0).validate(16000).is_err());
+        assert!(BiquadFilter::band_pass(1000.0, 0.0).validate(16000).is_err());
+        assert!(BiquadFilter::low_shelf(100.0, f32::NAN).validate(16000).is_err());
+        assert!(FilterChain::from(BiquadFilter::low_pass(7999.0)).apply(&mut [0.0; 4], 1, 16000).is_ok());
+    }
+}
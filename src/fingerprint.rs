@@ -0,0 +1,222 @@
+// 声学指纹：按时间排列的 32 位子指纹，对编码格式、采样率和音量的变化保持稳定，用于查找重复的录音
+//
+// 每个子指纹对应约 11.6ms 的一帧：33 个对数间隔频带（300 - 2000Hz）的能量在频率和时间两个方向上的差分符号
+
+use crate::analysis;
+use crate::dsp::{self, StreamResampler};
+
+/// 计算指纹前统一重采样到的采样率
+const FINGERPRINT_SAMPLE_RATE: u32 = 11025;
+
+/// 分析帧长度（约 186ms）
+const FRAME_SIZE: usize = 2048;
+
+/// 相邻分析帧之间的样本数（约 11.6ms）
+const HOP_SIZE: usize = 128;
+
+/// 频带范围（Hz）
+const MIN_FREQUENCY: f32 = 300.0;
+const MAX_FREQUENCY: f32 = 2000.0;
+
+/// 频带数，相邻频带的差分得到 32 位
+const BANDS: usize = 33;
+
+/// 比较时允许的最大时间偏移（子指纹数，约 2 秒）
+const MAX_OFFSET: usize = 172;
+
+/// 音频文件的声学指纹
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AudioFingerprint {
+    /// 按时间排列的子指纹，每个约 11.6ms
+    pub values: Vec<u32>,
+    /// 音频时长（毫秒）
+    pub duration_ms: u64,
+}
+
+impl AudioFingerprint {
+    /// 计算交错样本的指纹
+    /// # Arguments
+    /// * `samples` - 交错的 f32 样本
+    /// * `channels` - 声道数
+    /// * `sample_rate` - 采样率
+    pub fn from_samples(samples: &[f32], channels: u8, sample_rate: u32) -> Self {
+        let mut builder = FingerprintBuilder::new(channels, sample_rate);
+        builder.push(samples);
+        builder.finish()
+    }
+
+    /// 与另一个指纹的相似度（0.0 - 1.0）
+    ///
+    /// 在 ±2 秒的范围内寻找最佳对齐，按重叠部分的误码率计算（误码率 0 为 1.0，达到随机水平的 0.5 时为 0.0），
+    /// 再乘以重叠部分占较长指纹的比例，因此只截取了一部分的录音相似度较低。
+    /// 同一录音经过有损编码或重采样后通常在 0.7 以上，不同录音接近 0
+    pub fn similarity(&self, other: &AudioFingerprint) -> f32 {
+        let (a, b) = (&self.values, &other.values);
+        let longest = a.len().max(b.len());
+        if a.is_empty() || b.is_empty() {
+            return if longest == 0 { 1.0 } else { 0.0 };
+        }
+
+        let mut best = 0.0f32;
+        for offset in -(MAX_OFFSET.min(b.len() - 1) as isize)..=MAX_OFFSET.min(a.len() - 1) as isize {
+            // offset 为正时 a 从第 offset 个子指纹开始与 b 对齐
+            let (a_part, b_part) = if offset >= 0 { (&a[offset as usize..], &b[..]) } else { (&a[..], &b[(-offset) as usize..]) };
+            let overlap = a_part.len().min(b_part.len());
+            let errors: u32 = a_part.iter().zip(b_part).map(|(x, y)| (x ^ y).count_ones()).sum();
+            let bit_error_rate = errors as f32 / (overlap * 32) as f32;
+            let score = (1.0 - 2.0 * bit_error_rate).max(0.0) * overlap as f32 / longest as f32;
+            best = best.max(score);
+        }
+        best
+    }
+}
+
+/// 分块计算指纹：混缩为单声道并重采样到 11025Hz 后逐帧计算
+pub(crate) struct FingerprintBuilder {
+    channels: u8,
+    sample_rate: u32,
+    resampler: Option<StreamResampler>,
+    /// 重采样后尚未分析完的单声道样本
+    buffer: Vec<f32>,
+    window: Vec<f32>,
+    /// 各频带的 FFT 频点范围
+    band_bins: Vec<(usize, usize)>,
+    previous: Option<[f32; BANDS]>,
+    values: Vec<u32>,
+    input_frames: u64,
+}
+
+impl FingerprintBuilder {
+    pub fn new(channels: u8, sample_rate: u32) -> Self {
+        let bin_of = |frequency: f32| (frequency * FRAME_SIZE as f32 / FINGERPRINT_SAMPLE_RATE as f32).round() as usize;
+        let edges: Vec<usize> = (0..=BANDS)
+            .map(|band| bin_of(MIN_FREQUENCY * (MAX_FREQUENCY / MIN_FREQUENCY).powf(band as f32 / BANDS as f32)))
+            .collect();
+        FingerprintBuilder {
+            channels: channels.max(1),
+            sample_rate,
+            resampler: (sample_rate != FINGERPRINT_SAMPLE_RATE).then(|| StreamResampler::new(1, sample_rate, FINGERPRINT_SAMPLE_RATE)),
+            buffer: Vec::new(),
+            window: (0..FRAME_SIZE).map(|n| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * n as f32 / FRAME_SIZE as f32).cos()).collect(),
+            band_bins: edges.windows(2).map(|pair| (pair[0], pair[1].max(pair[0] + 1))).collect(),
+            previous: None,
+            values: Vec::new(),
+            input_frames: 0,
+        }
+    }
+
+    /// 送入一块交错样本
+    pub fn push(&mut self, samples: &[f32]) {
+        self.input_frames += (samples.len() / self.channels as usize) as u64;
+        let mono = dsp::downmix_to_mono(samples, self.channels);
+        match &mut self.resampler {
+            Some(resampler) => resampler.process(&mono, &mut self.buffer),
+            None => self.buffer.extend_from_slice(&mono),
+        }
+        self.analyze();
+    }
+
+    /// 输入结束，返回指纹
+    pub fn finish(mut self) -> AudioFingerprint {
+        if let Some(resampler) = &mut self.resampler {
+            resampler.finish(&mut self.buffer);
+        }
+        self.analyze();
+        AudioFingerprint {
+            values: self.values,
+            duration_ms: self.input_frames * 1000 / self.sample_rate.max(1) as u64,
+        }
+    }
+
+    /// 分析缓冲区中所有完整的帧，丢弃之后不再需要的样本
+    fn analyze(&mut self) {
+        let mut start = 0;
+        while start + FRAME_SIZE <= self.buffer.len() {
+            let energies = self.band_energies(start);
+            if let Some(previous) = &self.previous {
+                let value = (0..BANDS - 1).fold(0u32, |value, band| {
+                    let difference = (energies[band] - energies[band + 1]) - (previous[band] - previous[band + 1]);
+                    value | (((difference > 0.0) as u32) << band)
+                });
+                self.values.push(value);
+            }
+            self.previous = Some(energies);
+            start += HOP_SIZE;
+        }
+        self.buffer.drain(..start);
+    }
+
+    fn band_energies(&self, start: usize) -> [f32; BANDS] {
+        let mut re: Vec<f32> = self.buffer[start..start + FRAME_SIZE].iter().zip(&self.window).map(|(s, w)| s * w).collect();
+        let mut im = vec![0.0f32; FRAME_SIZE];
+        analysis::fft(&mut re, &mut im);
+        let mut energies = [0.0f32; BANDS];
+        for (energy, &(first, last)) in energies.iter_mut().zip(&self.band_bins) {
+            *energy = (first..last).map(|k| re[k] * re[k] + im[k] * im[k]).sum();
+        }
+        energies
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 每 150ms 换一个音高的伪随机旋律，叠加少量噪声
+    fn melody(seed: u32, sample_rate: u32, seconds: f32) -> Vec<f32> {
+        let mut state = seed;
+        let mut next = move || {
+            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+            state as f32 / u32::MAX as f32
+        };
+        let note_frames = (sample_rate as f32 * 0.15) as usize;
+        let total = (sample_rate as f32 * seconds) as usize;
+        let mut samples = Vec::with_capacity(total);
+        let mut phase = 0.0f32;
+        while samples.len() < total {
+            let frequency = 300.0 + next() * 1500.0;
+            for _ in 0..note_frames.min(total - samples.len()) {
+                phase += 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+                samples.push(phase.sin() * 0.4 + (next() - 0.5) * 0.02);
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn test_fingerprint_similarity() {
+        let original = melody(7, 22050, 8.0);
+        let fingerprint = AudioFingerprint::from_samples(&original, 1, 22050);
+        assert_eq!(fingerprint.duration_ms, 8000);
+        // 重采样为 88200 个样本，共 (88200 - 2048) / 128 + 1 帧，第一帧只作为差分的参考
+        assert_eq!(fingerprint.values.len(), 673);
+        assert_eq!(fingerprint.similarity(&fingerprint), 1.0);
+
+        // 音量降低、开头多出 0.3 秒静音
+        let mut shifted = vec![0.0f32; 6615];
+        shifted.extend(original.iter().map(|s| s * 0.5));
+        let similarity = fingerprint.similarity(&AudioFingerprint::from_samples(&shifted, 1, 22050));
+        assert!(similarity > 0.8, "同一录音的相似度 {}", similarity);
+
+        // 不同的录音
+        let other = AudioFingerprint::from_samples(&melody(8, 22050, 8.0), 1, 22050);
+        let similarity = fingerprint.similarity(&other);
+        assert!(similarity < 0.2, "不同录音的相似度 {}", similarity);
+    }
+
+    #[test]
+    fn test_chunked_fingerprint_matches() {
+        let stereo: Vec<f32> = melody(3, 16000, 3.0).iter().flat_map(|&s| [s, s * 0.8]).collect();
+        let expected = AudioFingerprint::from_samples(&stereo, 2, 16000);
+        let mut builder = FingerprintBuilder::new(2, 16000);
+        for chunk in stereo.chunks(2 * 1001) {
+            builder.push(chunk);
+        }
+        assert_eq!(builder.finish(), expected);
+
+        let empty = AudioFingerprint::from_samples(&[], 1, 16000);
+        assert!(empty.values.is_empty());
+        assert_eq!(empty.similarity(&expected), 0.0);
+    }
+}
@@ -0,0 +1,116 @@
+// G.711 µ-law / A-law 编解码（ITU-T G.711）
+
+/// µ-law 编码偏置
+const ULAW_BIAS: i32 = 0x84;
+
+/// µ-law 编码前的最大幅度
+const ULAW_CLIP: i32 = 32635;
+
+/// A-law 各段的上限（13 位幅度）
+const ALAW_SEGMENT_END: [i32; 8] = [0x1F, 0x3F, 0x7F, 0xFF, 0x1FF, 0x3FF, 0x7FF, 0xFFF];
+
+/// 16 位线性 PCM 编码为 µ-law
+pub fn linear_to_ulaw(sample: i16) -> u8 {
+    let mut magnitude = sample as i32;
+    let sign = if magnitude < 0 {
+        magnitude = -magnitude;
+        0x80
+    } else {
+        0
+    };
+    magnitude = magnitude.min(ULAW_CLIP) + ULAW_BIAS;
+
+    // 加偏置后最高位在第 7-14 位之间，对应指数 0-7
+    let exponent = (31 - (magnitude as u32).leading_zeros()) as i32 - 7;
+    let mantissa = (magnitude >> (exponent + 3)) & 0x0F;
+    !(sign | (exponent << 4) | mantissa) as u8
+}
+
+/// µ-law 解码为 16 位线性 PCM
+pub fn ulaw_to_linear(value: u8) -> i16 {
+    let value = !value as i32;
+    let exponent = (value >> 4) & 0x07;
+    let mantissa = value & 0x0F;
+    let magnitude = (((mantissa << 3) + ULAW_BIAS) << exponent) - ULAW_BIAS;
+    if value & 0x80 != 0 { -magnitude as i16 } else { magnitude as i16 }
+}
+
+/// 16 位线性 PCM 编码为 A-law
+pub fn linear_to_alaw(sample: i16) -> u8 {
+    // A-law 使用 13 位幅度
+    let mut magnitude = (sample as i32) >> 3;
+    let mask = if magnitude >= 0 {
+        0xD5
+    } else {
+        magnitude = -magnitude - 1;
+        0x55
+    };
+
+    let segment = ALAW_SEGMENT_END.iter().position(|&end| magnitude <= end);
+    let value = match segment {
+        None => 0x7F,
+        Some(segment) => {
+            let shift = if segment < 2 { 1 } else { segment };
+            ((segment as i32) << 4) | ((magnitude >> shift) & 0x0F)
+        }
+    };
+    (value ^ mask) as u8
+}
+
+/// A-law 解码为 16 位线性 PCM
+pub fn alaw_to_linear(value: u8) -> i16 {
+    let value = (value ^ 0x55) as i32;
+    let segment = (value & 0x70) >> 4;
+    let mut magnitude = (value & 0x0F) << 4;
+    match segment {
+        0 => magnitude += 8,
+        1 => magnitude += 0x108,
+        _ => magnitude = (magnitude + 0x108) << (segment - 1),
+    }
+    if value & 0x80 != 0 { magnitude as i16 } else { -magnitude as i16 }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_silence_codes() {
+        assert_eq!(linear_to_ulaw(0), 0xFF);
+        assert_eq!(ulaw_to_linear(0xFF), 0);
+        assert_eq!(linear_to_alaw(0), 0xD5);
+        assert_eq!(alaw_to_linear(0xD5), 8);
+    }
+
+    #[test]
+    fn test_full_scale() {
+        assert_eq!(ulaw_to_linear(linear_to_ulaw(i16::MAX)), 32124);
+        assert_eq!(ulaw_to_linear(linear_to_ulaw(i16::MIN)), -32124);
+        assert_eq!(alaw_to_linear(linear_to_alaw(i16::MAX)), 32256);
+        assert_eq!(alaw_to_linear(linear_to_alaw(i16::MIN)), -32256);
+    }
+
+    #[test]
+    fn test_round_trip_error_is_bounded() {
+        // G.711 为对数量化，相对误差约在 1/16 以内
+        for sample in (-32000i32..32000).step_by(97) {
+            let sample = sample as i16;
+            let tolerance = (sample as i32).abs() / 16 + 16;
+            let ulaw = ulaw_to_linear(linear_to_ulaw(sample)) as i32;
+            let alaw = alaw_to_linear(linear_to_alaw(sample)) as i32;
+            assert!((ulaw - sample as i32).abs() <= tolerance, "µ-law {} -> {}", sample, ulaw);
+            assert!((alaw - sample as i32).abs() <= tolerance, "A-law {} -> {}", sample, alaw);
+        }
+    }
+
+    #[test]
+    fn test_every_code_round_trips() {
+        for code in 0..=255u8 {
+            // µ-law 中 0x7F 与 0xFF 都表示 0
+            if code != 0x7F {
+                assert_eq!(linear_to_ulaw(ulaw_to_linear(code)), code, "µ-law 0x{:02X}", code);
+            }
+            assert_eq!(linear_to_alaw(alaw_to_linear(code)), code, "A-law 0x{:02X}", code);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+// 信号生成：校准音、测试用的样本数据，按 AudioConfig 的样本格式和字节序输出原始 PCM
+
+use std::path::Path;
+use std::time::Duration;
+
+use crate::atomic;
+use crate::convert;
+use crate::sample::{encode_samples_f32, swap_sample_bytes};
+use crate::{mp3, AudioConfig, AudioError, AudioFormat, AudioQuality, Endianness};
+
+/// 生成信号的默认幅度（-6 dBFS），为叠加和后续处理留出余量
+const DEFAULT_AMPLITUDE: f32 = 0.5;
+
+/// 时长对应的帧数（四舍五入）
+fn duration_frames(duration: Duration, sample_rate: u32) -> usize {
+    (duration.as_secs_f64() * sample_rate as f64).round() as usize
+}
+
+/// 将交错的 f32 样本编码为配置指定的样本格式和字节序
+fn encode(samples: &[f32], config: &AudioConfig) -> Vec<u8> {
+    let mut data = encode_samples_f32(samples, config.sample_format);
+    if config.endianness == Endianness::Big {
+        swap_sample_bytes(&mut data, config.sample_format);
+    }
+    data
+}
+
+/// 每帧所有声道写入相同的样本
+fn interleave(mono: impl Iterator<Item = f32>, channels: u8) -> Vec<f32> {
+    mono.flat_map(|s| std::iter::repeat_n(s, channels as usize)).collect()
+}
+
+/// 将生成的 PCM 写入文件，格式按扩展名确定：`.wav` 写入文件头，`.mp3` 使用采样率对应的默认比特率编码，
+/// `.pcm` / `.raw` 直接写入
+fn write_generated(output_path: &Path, pcm: &[u8], config: &AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let data = match convert::output_format(output_path)? {
+        AudioFormat::Wav => crate::trans_pcm_bytes_to_wav(pcm, Some(crate::audio_config_to_wav_config(config)))?,
+        AudioFormat::Mp3 => {
+            let mp3_config = crate::audio_config_to_mp3_config(config, mp3::default_bitrate(config.sample_rate), AudioQuality::High);
+            crate::trans_pcm_bytes_to_mp3(pcm, Some(mp3_config))?
+        }
+        AudioFormat::Pcm => pcm.to_vec(),
+    };
+    atomic::write_atomic(output_path, &data)?;
+    log::debug!("Generated {} ({} bytes)", output_path.display(), data.len());
+    Ok(())
+}
+
+/// 生成正弦波，所有声道相同，幅度为 -6 dBFS
+/// # Arguments
+/// * `freq_hz` - 频率（Hz），应低于采样率的一半
+/// * `duration` - 时长，按采样率取整到帧
+/// * `config` - 输出的采样率、声道数、样本格式和字节序
+/// # Returns
+/// * `Vec<u8>` - 交错的原始 PCM 数据
+pub fn generate_sine(freq_hz: f32, duration: Duration, config: &AudioConfig) -> Vec<u8> {
+    let step = freq_hz as f64 / config.sample_rate.max(1) as f64;
+    let mono = (0..duration_frames(duration, config.sample_rate))
+        // 相位取模后再计算，长时间生成时不损失精度
+        .map(|i| ((i as f64 * step).fract() * std::f64::consts::TAU).sin() as f32 * DEFAULT_AMPLITUDE);
+    encode(&interleave(mono, config.channels), config)
+}
+
+/// 生成正弦波并写入文件
+/// # Arguments
+/// * `output_path` - 输出文件路径，扩展名为 `wav`、`mp3`、`pcm` 或 `raw`
+/// * `freq_hz` - 频率（Hz）
+/// * `duration` - 时长
+/// * `config` - 音频参数，输出 MP3 时采样率必须是 MP3 支持的采样率
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - 成功或错误信息
+pub fn generate_sine_file(output_path: impl AsRef<Path>, freq_hz: f32, duration: Duration, config: &AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    config.validate()?;
+    if !(freq_hz > 0.0 && freq_hz < config.sample_rate as f32 / 2.0) {
+        return Err(AudioError::InvalidConfig(format!("Invalid tone frequency {}Hz for {}Hz sample rate", freq_hz, config.sample_rate)).into());
+    }
+    write_generated(output_path.as_ref(), &generate_sine(freq_hz, duration, config), config)
+}
+
+/// 噪声使用的伪随机数发生器（xorshift64*），固定种子使生成结果可以复现
+struct NoiseRng(u64);
+
+impl NoiseRng {
+    fn new() -> Self {
+        NoiseRng(0x9E37_79B9_7F4A_7C15)
+    }
+
+    /// [-1.0, 1.0) 内均匀分布的随机数
+    fn next_f32(&mut self) -> f32 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        let bits = self.0.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 40;
+        bits as f32 / (1u64 << 23) as f32 - 1.0
+    }
+}
+
+/// 生成白噪声，各声道独立，峰值不超过 -6 dBFS；使用固定的种子，相同参数的结果相同
+/// # Arguments
+/// * `duration` - 时长，按采样率取整到帧
+/// * `config` - 输出的采样率、声道数、样本格式和字节序
+/// # Returns
+/// * `Vec<u8>` - 交错的原始 PCM 数据
+pub fn generate_white_noise(duration: Duration, config: &AudioConfig) -> Vec<u8> {
+    let mut rng = NoiseRng::new();
+    let count = duration_frames(duration, config.sample_rate) * config.channels as usize;
+    let samples: Vec<f32> = (0..count).map(|_| rng.next_f32() * DEFAULT_AMPLITUDE).collect();
+    encode(&samples, config)
+}
+
+/// 生成粉红噪声（每倍频程能量相同，-3 dB/oct），各声道独立，峰值不超过 -6 dBFS；使用固定的种子
+/// # Arguments
+/// * `duration` - 时长，按采样率取整到帧
+/// * `config` - 输出的采样率、声道数、样本格式和字节序
+/// # Returns
+/// * `Vec<u8>` - 交错的原始 PCM 数据
+pub fn generate_pink_noise(duration: Duration, config: &AudioConfig) -> Vec<u8> {
+    let mut rng = NoiseRng::new();
+    let channels = config.channels as usize;
+    let count = duration_frames(duration, config.sample_rate) * channels;
+    // Paul Kellet 的滤波器：七个一阶低通叠加近似 1/f 频谱
+    let mut state = vec![[0.0f32; 7]; channels];
+    let mut samples: Vec<f32> = (0..count)
+        .map(|i| {
+            let white = rng.next_f32();
+            let b = &mut state[i % channels];
+            b[0] = 0.99886 * b[0] + white * 0.0555179;
+            b[1] = 0.99332 * b[1] + white * 0.0750759;
+            b[2] = 0.96900 * b[2] + white * 0.153852;
+            b[3] = 0.86650 * b[3] + white * 0.3104856;
+            b[4] = 0.55000 * b[4] + white * 0.5329522;
+            b[5] = -0.7616 * b[5] - white * 0.0168980;
+            let pink = b[0] + b[1] + b[2] + b[3] + b[4] + b[5] + b[6] + white * 0.5362;
+            b[6] = white * 0.115926;
+            pink
+        })
+        .collect();
+    // 滤波器的增益随输入变化，按实际峰值缩放
+    let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+    if peak > 0.0 {
+        samples.iter_mut().for_each(|s| *s *= DEFAULT_AMPLITUDE / peak);
+    }
+    encode(&samples, config)
+}
+
+/// 生成静音（无符号 8 位为 128，µ-law / A-law 为对应的零电平编码）
+/// # Arguments
+/// * `duration` - 时长，按采样率取整到帧
+/// * `config` - 输出的采样率、声道数、样本格式和字节序
+/// # Returns
+/// * `Vec<u8>` - 交错的原始 PCM 数据
+pub fn generate_silence(duration: Duration, config: &AudioConfig) -> Vec<u8> {
+    let count = duration_frames(duration, config.sample_rate) * config.channels as usize;
+    encode(&vec![0.0; count], config)
+}
+
+/// DTMF 按键对应的低频组和高频组频率（ITU-T Q.23）
+fn dtmf_frequencies(key: char) -> Option<(f32, f32)> {
+    const ROWS: [f32; 4] = [697.0, 770.0, 852.0, 941.0];
+    const COLUMNS: [f32; 4] = [1209.0, 1336.0, 1477.0, 1633.0];
+    const KEYS: [[char; 4]; 4] = [['1', '2', '3', 'A'], ['4', '5', '6', 'B'], ['7', '8', '9', 'C'], ['*', '0', '#', 'D']];
+    let key = key.to_ascii_uppercase();
+    KEYS.iter().enumerate().find_map(|(row, keys)| keys.iter().position(|&k| k == key).map(|column| (ROWS[row], COLUMNS[column])))
+}
+
+/// 生成 DTMF 拨号音序列，每个按键为低频组和高频组两个等幅正弦波的叠加（合成峰值 -6 dBFS），按键之间插入静音
+/// # Arguments
+/// * `keys` - 按键序列：`0`-`9`、`*`、`#` 和 `A`-`D`（不区分大小写）
+/// * `config` - 输出的采样率、声道数、样本格式和字节序，采样率至少为 4000Hz
+/// * `tone_ms` - 每个按键的时长（毫秒），Q.24 要求不少于 40ms
+/// * `gap_ms` - 按键之间的静音时长（毫秒）
+/// # Returns
+/// * `Result<Vec<u8>, Box<dyn std::error::Error>>` - 交错的原始 PCM 数据
+pub fn generate_dtmf(keys: &str, config: &AudioConfig, tone_ms: u32, gap_ms: u32) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    // 最高的 1633Hz 需要低于奈奎斯特频率
+    if config.sample_rate < 4000 {
+        return Err(AudioError::InvalidConfig(format!("Sample rate {}Hz is too low for DTMF tones", config.sample_rate)).into());
+    }
+    let frequencies = keys
+        .chars()
+        .map(|key| dtmf_frequencies(key).ok_or_else(|| AudioError::InvalidInput(format!("Invalid DTMF key: {:?}", key))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let tone_frames = duration_frames(Duration::from_millis(tone_ms as u64), config.sample_rate);
+    let gap_frames = duration_frames(Duration::from_millis(gap_ms as u64), config.sample_rate);
+    let rate = config.sample_rate as f64;
+
+    let mut mono = Vec::with_capacity(frequencies.len() * (tone_frames + gap_frames));
+    for (index, &(low, high)) in frequencies.iter().enumerate() {
+        if index > 0 {
+            mono.resize(mono.len() + gap_frames, 0.0);
+        }
+        mono.extend((0..tone_frames).map(|i| {
+            let t = i as f64 / rate;
+            let tone = (std::f64::consts::TAU * low as f64 * t).sin() + (std::f64::consts::TAU * high as f64 * t).sin();
+            (tone * 0.5) as f32 * DEFAULT_AMPLITUDE
+        }));
+    }
+    log::debug!("Generated DTMF sequence {:?}: {} frames", keys, mono.len());
+    Ok(encode(&interleave(mono.into_iter(), config.channels), config))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SampleFormat;
+
+    #[test]
+    fn test_sine_length_and_level() {
+        let config = AudioConfig::new(8000, 2, 16);
+        let data = generate_sine(1000.0, Duration::from_millis(250), &config);
+        assert_eq!(data.len(), 2000 * 2 * 2);
+        let samples: Vec<i16> = data.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect();
+        // 1kHz / 8kHz：每 8 帧一个周期，第 2 帧为波峰
+        assert_eq!(samples[0], 0);
+        assert_eq!(samples[4], samples[5]);
+        assert!((samples[4] as i32 - 16384).abs() <= 1);
+    }
+
+    #[test]
+    fn test_sine_sample_format() {
+        let config = AudioConfig::new(8000, 1, 32).with_sample_format(SampleFormat::F32).with_endianness(Endianness::Big);
+        let data = generate_sine(1000.0, Duration::from_millis(1), &config);
+        assert_eq!(data.len(), 8 * 4);
+        let peak = f32::from_be_bytes(data[8..12].try_into().unwrap());
+        assert!((peak - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_noise_is_reproducible_and_bounded() {
+        let config = AudioConfig::new(16000, 2, 16);
+        let white = generate_white_noise(Duration::from_secs(1), &config);
+        assert_eq!(white, generate_white_noise(Duration::from_secs(1), &config));
+        let samples: Vec<i16> = white.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect();
+        assert_eq!(samples.len(), 32000);
+        assert!(samples.iter().all(|s| s.abs() <= 16384));
+        // 均匀分布的均值接近 0，左右声道不相同
+        let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / samples.len() as f64;
+        assert!(mean.abs() < 200.0, "均值 {}", mean);
+        assert!(samples.chunks_exact(2).any(|frame| frame[0] != frame[1]));
+    }
+
+    #[test]
+    fn test_pink_noise_spectrum_tilt() {
+        let config = AudioConfig::new(16000, 1, 32).with_sample_format(SampleFormat::F32);
+        let data = generate_pink_noise(Duration::from_secs(2), &config);
+        let samples: Vec<f32> = data.chunks_exact(4).map(|b| f32::from_le_bytes(b.try_into().unwrap())).collect();
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.5).abs() < 1e-6);
+        // 粉红噪声的低频能量更多：相邻样本的差分能量明显低于白噪声（白噪声约为信号能量的 2 倍）
+        let energy: f32 = samples.iter().map(|s| s * s).sum();
+        let diff: f32 = samples.windows(2).map(|w| (w[1] - w[0]).powi(2)).sum();
+        assert!(diff < energy, "差分能量 {} / 信号能量 {}", diff, energy);
+    }
+
+    #[test]
+    fn test_silence_encoding() {
+        let duration = Duration::from_millis(10);
+        assert!(generate_silence(duration, &AudioConfig::new(8000, 2, 16)).iter().all(|&b| b == 0));
+        let u8_silence = generate_silence(duration, &AudioConfig::new(8000, 1, 8).with_sample_format(SampleFormat::U8));
+        assert_eq!(u8_silence, vec![128; 80]);
+        let ulaw = generate_silence(duration, &AudioConfig::new(8000, 1, 8).with_sample_format(SampleFormat::Ulaw));
+        assert_eq!(ulaw, vec![crate::linear_to_ulaw(0); 80]);
+    }
+
+    /// Goertzel 算法计算单个频率的能量
+    fn goertzel(samples: &[f32], frequency: f32, sample_rate: u32) -> f32 {
+        let coefficient = 2.0 * (std::f32::consts::TAU * frequency / sample_rate as f32).cos();
+        let (mut s1, mut s2) = (0.0f32, 0.0f32);
+        for &x in samples {
+            let s0 = x + coefficient * s1 - s2;
+            s2 = s1;
+            s1 = s0;
+        }
+        s1 * s1 + s2 * s2 - coefficient * s1 * s2
+    }
+
+    #[test]
+    fn test_dtmf_tones() {
+        let config = AudioConfig::new(8000, 1, 16);
+        let data = generate_dtmf("5#d", &config, 100, 50).unwrap();
+        let samples = crate::decode_samples_f32(&data, SampleFormat::S16);
+        assert_eq!(samples.len(), 800 * 3 + 400 * 2);
+        assert!(samples.iter().all(|s| s.abs() <= 0.5 + 1e-4));
+        assert!(samples[800..1200].iter().all(|&s| s == 0.0), "按键之间应该是静音");
+
+        let all = [697.0, 770.0, 852.0, 941.0, 1209.0, 1336.0, 1477.0, 1633.0];
+        for (tone, expected) in [(0, [770.0, 1336.0]), (1, [941.0, 1477.0]), (2, [941.0, 1633.0])] {
+            let start = tone * 1200;
+            let segment = &samples[start..start + 800];
+            let mut energies: Vec<(f32, f32)> = all.iter().map(|&f| (f, goertzel(segment, f, 8000))).collect();
+            energies.sort_by(|a, b| b.1.total_cmp(&a.1));
+            let mut strongest = [energies[0].0, energies[1].0];
+            strongest.sort_by(f32::total_cmp);
+            assert_eq!(strongest, expected);
+        }
+    }
+
+    #[test]
+    fn test_dtmf_rejects_invalid_input() {
+        let config = AudioConfig::new(8000, 1, 16);
+        assert!(generate_dtmf("12E", &config, 100, 50).is_err());
+        assert!(generate_dtmf("1", &AudioConfig::new(3000, 1, 16), 100, 50).is_err());
+        assert!(generate_dtmf("", &config, 100, 50).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+// 内容推断：文件名没有线索时，从原始字节的统计特征猜测 PCM 参数
+//
+// 三项参数分别估计后组合：
+// * 样本格式：多字节样本的最高有效字节取值集中、熵低，按字节位置统计取值分布即可确定位宽和字节序；
+//   浮点数据另外检查数值是否落在 [-1.0, 1.0] 内
+// * 声道数：交错的多声道数据中，同一声道相隔 N 个样本的差值最小；左右声道完全相同时视为双声道
+// * 采样率：录音在奈奎斯特频率附近会被抗混叠滤波截止，频谱的截止位置结合常见内容带宽（电话语音、宽带语音、音乐）推断采样率
+
+use std::io::Read;
+use std::path::Path;
+
+use crate::{analysis, sample, AudioConfig, Endianness, SampleFormat};
+
+/// 最多分析文件开头的字节数
+const ANALYSIS_BYTES: u64 = 1 << 20;
+/// 参与比较的声道数
+const CHANNEL_CANDIDATES: [u8; 5] = [1, 2, 4, 6, 8];
+/// 候选采样率及其先验权重（越常见权重越高）
+const RATE_PRIORS: [(u32, f32); 6] = [(8000, 0.15), (16000, 0.25), (22050, 0.1), (32000, 0.1), (44100, 0.2), (48000, 0.2)];
+/// 常见内容的带宽（Hz）：电话语音、宽带语音、FM 广播、超宽带语音、CD 音乐
+const CONTENT_BANDWIDTHS: [f32; 5] = [3400.0, 7000.0, 11000.0, 15000.0, 20000.0];
+/// 返回的候选数上限
+const MAX_CANDIDATES: usize = 8;
+
+/// 参与比较的样本格式及其先验权重
+const FORMAT_PRIORS: [(SampleFormat, Endianness, f32); 7] = [
+    (SampleFormat::S16, Endianness::Little, 1.0),
+    (SampleFormat::S16, Endianness::Big, 0.6),
+    (SampleFormat::S24, Endianness::Little, 0.8),
+    (SampleFormat::S24, Endianness::Big, 0.5),
+    (SampleFormat::S32, Endianness::Little, 0.7),
+    (SampleFormat::F32, Endianness::Little, 0.9),
+    (SampleFormat::U8, Endianness::Little, 0.6),
+];
+
+/// 将字节按给定格式解码为 f32 样本
+fn decode(data: &[u8], format: SampleFormat, endianness: Endianness) -> Vec<f32> {
+    if endianness == Endianness::Big {
+        let mut swapped = data.to_vec();
+        sample::swap_sample_bytes(&mut swapped, format);
+        return sample::decode_samples_f32(&swapped, format);
+    }
+    sample::decode_samples_f32(data, format)
+}
+
+/// 粗糙度：相隔 `gap` 个样本的平均差值与去除直流后平均幅度之比；平滑的音频远小于 1，噪声约为 1.1
+fn roughness(samples: &[f32], gap: usize) -> f32 {
+    if samples.len() <= gap {
+        return f32::INFINITY;
+    }
+    let mean = samples.iter().map(|&s| s as f64).sum::<f64>() / samples.len() as f64;
+    let level = samples.iter().map(|&s| (s as f64 - mean).abs()).sum::<f64>() / samples.len() as f64;
+    if level == 0.0 {
+        return f32::INFINITY;
+    }
+    let diff = samples.windows(gap + 1).map(|w| (w[gap] as f64 - w[0] as f64).abs()).sum::<f64>() / (samples.len() - gap) as f64;
+    (diff / level) as f32
+}
+
+/// 浮点解释的可信度：有限、不超过满量程且不是非正规小数的样本所占比例
+fn float_plausibility(data: &[u8]) -> f32 {
+    let values: Vec<f32> = data.chunks_exact(4).map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])).collect();
+    if values.is_empty() {
+        return 0.0;
+    }
+    let plausible = values.iter().filter(|v| v.is_finite() && v.abs() <= 1.0 && (**v == 0.0 || v.abs() >= 1e-7)).count();
+    plausible as f32 / values.len() as f32
+}
+
+/// 按字节在样本中的位置（偏移对 `width` 取模）分别统计取值的熵（比特）
+fn byte_entropies(data: &[u8], width: usize) -> Vec<f32> {
+    let mut histograms = vec![[0usize; 256]; width];
+    for (index, &byte) in data.iter().enumerate() {
+        histograms[index % width][byte as usize] += 1;
+    }
+    histograms
+        .iter()
+        .map(|histogram| {
+            let total: usize = histogram.iter().sum();
+            histogram
+                .iter()
+                .filter(|&&count| count > 0)
+                .map(|&count| {
+                    let p = count as f32 / total as f32;
+                    -p * p.log2()
+                })
+                .sum()
+        })
+        .collect()
+}
+
+/// 各候选格式按字节取值分布打分，返回归一化后的 (格式, 字节序, 置信度)
+///
+/// 多字节样本的最高有效字节集中在 0x00 / 0xFF 附近，熵明显低于其余字节；
+/// 只有位宽和字节序都正确时，这个低熵字节才恰好落在每个样本的最高位上
+fn score_formats(data: &[u8]) -> Vec<(SampleFormat, Endianness, f32)> {
+    let float_plausible = float_plausibility(data) >= 0.99;
+    let entropies: Vec<Vec<f32>> = (0..=4).map(|width| if width < 2 { Vec::new() } else { byte_entropies(data, width) }).collect();
+    // 最高有效字节的熵比其余字节中最低的还低多少
+    let contrast = |format: SampleFormat, endianness: Endianness| {
+        let width = format.bytes_per_sample();
+        let msb = if endianness == Endianness::Little { width - 1 } else { 0 };
+        let others = entropies[width].iter().enumerate().filter(|(position, _)| *position != msb).map(|(_, h)| *h).fold(f32::INFINITY, f32::min);
+        (others - entropies[width][msb]).max(0.0)
+    };
+    let best_contrast = FORMAT_PRIORS
+        .iter()
+        .filter(|(format, _, _)| format.bytes_per_sample() > 1)
+        .map(|&(format, endianness, _)| contrast(format, endianness))
+        .fold(0.0f32, f32::max);
+
+    let mut scores: Vec<(SampleFormat, Endianness, f32)> = FORMAT_PRIORS
+        .iter()
+        .filter(|(format, _, _)| *format != SampleFormat::F32 || float_plausible)
+        .map(|&(format, endianness, prior)| {
+            let score = match format {
+                // 8 位样本的各字节分布相同，没有任何多字节格式表现出位置差异时才可能是 U8
+                SampleFormat::U8 => (-best_contrast / 0.1).exp(),
+                // 浮点数据的指数字节同样是低熵，能解释为浮点时优先于 32 位整数
+                SampleFormat::S32 if float_plausible => contrast(format, endianness) * 0.1,
+                _ => contrast(format, endianness),
+            };
+            (format, endianness, score * prior)
+        })
+        .collect();
+    normalize(&mut scores);
+    scores
+}
+
+/// 各候选声道数的置信度
+fn score_channels(samples: &[f32]) -> Vec<(u8, f32)> {
+    // 左右声道完全相同（单声道复制为双声道）时，相邻样本两两相等
+    let pairs = samples.len() / 2;
+    if pairs > 0 {
+        let identical = samples.chunks_exact(2).filter(|pair| pair[0] == pair[1]).count();
+        let moving = samples.chunks_exact(2).zip(samples.chunks_exact(2).skip(1)).filter(|(a, b)| a[0] != b[0]).count();
+        if identical as f32 / pairs as f32 > 0.95 && moving > pairs / 10 {
+            return vec![(2, 0.9), (1, 0.1)];
+        }
+    }
+
+    let roughness: Vec<(u8, f32)> = CHANNEL_CANDIDATES.iter().map(|&c| (c, roughness(samples, c as usize))).collect();
+    let min = roughness.iter().map(|(_, r)| *r).fold(f32::INFINITY, f32::min);
+    if !min.is_finite() || min == 0.0 {
+        return vec![(1, 1.0)];
+    }
+    // 任何间隔下都接近噪声（内容充满整个频带）时无法区分声道数，按常见程度给出单声道和立体声
+    if min > 0.9 {
+        return vec![(1, 0.55), (2, 0.45)];
+    }
+    // 交错数据中真实声道数的倍数同样平滑，但间隔更大，粗糙度略高；越接近最小值权重越高
+    let mut scores: Vec<(u8, f32)> = roughness.iter().map(|&(c, r)| (c, (-(r / min - 1.0) * 8.0).exp())).collect();
+    let total: f32 = scores.iter().map(|(_, s)| s).sum();
+    scores.iter_mut().for_each(|(_, s)| *s /= total);
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scores
+}
+
+/// 频谱的截止频率占奈奎斯特频率的比例：平均功率谱中高于峰值 -50dB 的最高频点
+fn spectral_bandwidth(samples: &[f32], channels: u8) -> Option<f32> {
+    let options = analysis::SpectrogramOptions::default();
+    let spectrogram = analysis::spectrogram(samples, channels, 48000, &options).ok()?;
+    let bins = options.fft_size / 2 + 1;
+    let mut power = vec![0.0f64; bins];
+    for frame in &spectrogram.magnitudes {
+        for (bin, magnitude) in frame.iter().enumerate() {
+            power[bin] += (*magnitude as f64).powi(2);
+        }
+    }
+    let peak = power.iter().skip(1).copied().fold(0.0, f64::max);
+    if peak <= 0.0 {
+        return None;
+    }
+    let edge = power.iter().rposition(|&p| p > peak * 1e-5)?;
+    Some(edge as f32 / (bins - 1) as f32)
+}
+
+/// 各候选采样率的置信度：将截止比例与"采样率 × 内容带宽"的预测值比较，结合先验
+fn score_sample_rates(bandwidth: Option<f32>) -> Vec<(u32, f32)> {
+    let mut scores: Vec<(u32, f32)> = RATE_PRIORS
+        .iter()
+        .map(|&(rate, prior)| {
+            let Some(measured) = bandwidth else { return (rate, prior) };
+            let nyquist = rate as f32 / 2.0;
+            let likelihood = CONTENT_BANDWIDTHS
+                .iter()
+                .map(|&content| {
+                    // 内容带宽超过奈奎斯特频率时被抗混叠滤波截止在约 0.95 处
+                    let predicted = content.min(nyquist * 0.95) / nyquist;
+                    (-((measured - predicted) / 0.08).powi(2)).exp()
+                })
+                .fold(0.0f32, f32::max);
+            (rate, likelihood.max(1e-3) * prior)
+        })
+        .collect();
+    let total: f32 = scores.iter().map(|(_, s)| s).sum();
+    scores.iter_mut().for_each(|(_, s)| *s /= total);
+    scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+    scores
+}
+
+/// 按置信度归一化并从高到低排序，去掉得分为 0 的项
+fn normalize(scores: &mut Vec<(SampleFormat, Endianness, f32)>) {
+    scores.retain(|(_, _, score)| *score > 0.0);
+    let total: f32 = scores.iter().map(|(_, _, score)| score).sum();
+    scores.iter_mut().for_each(|(_, _, score)| *score /= total);
+    scores.sort_by(|a, b| b.2.total_cmp(&a.2));
+}
+
+/// 分析原始 PCM 字节，返回按置信度从高到低排序的候选配置
+pub(crate) fn guess_config_from_bytes(data: &[u8]) -> Vec<(AudioConfig, f32)> {
+    // 空数据和数字静音没有任何可用的统计特征
+    if data.iter().all(|&byte| byte == data.first().copied().unwrap_or(0)) {
+        return Vec::new();
+    }
+    let formats = score_formats(data);
+    let mut candidates = Vec::new();
+    for &(format, endianness, format_score) in formats.iter().take(3) {
+        let samples = decode(data, format, endianness);
+        for &(channels, channel_score) in score_channels(&samples).iter().take(2) {
+            let rates = score_sample_rates(spectral_bandwidth(&samples, channels));
+            for &(rate, rate_score) in rates.iter().take(3) {
+                let config = AudioConfig::new(rate, channels, 16).with_sample_format(format).with_endianness(endianness);
+                candidates.push((config, format_score * channel_score * rate_score));
+            }
+        }
+    }
+    candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+    candidates.truncate(MAX_CANDIDATES);
+    candidates
+}
+
+/// 从文件内容猜测原始 PCM 的参数，用于文件名没有任何线索的情况
+///
+/// 分析文件开头最多 1MB 的数据：用各字节位置的取值分布判断样本格式和字节序，
+/// 用相隔不同样本数的相关性判断声道数，用频谱截止位置估计采样率。
+/// 样本格式和声道数通常比较可靠；采样率只能根据内容带宽估计，置信度较低，应优先使用文件名或元数据。
+///
+/// # Arguments
+/// * `path` - 原始 PCM 文件路径
+///
+/// # Returns
+/// * 按置信度从高到低排序的 (配置, 置信度)，置信度在 0 到 1 之间；文件为空或全部是静音时为空列表
+pub fn guess_config_from_content(path: impl AsRef<Path>) -> Result<Vec<(AudioConfig, f32)>, Box<dyn std::error::Error>> {
+    let mut data = Vec::new();
+    std::fs::File::open(path.as_ref())?.take(ANALYSIS_BYTES).read_to_end(&mut data)?;
+    // 截取到所有候选格式和声道数都能整除的长度
+    data.truncate(data.len() - data.len() % (3 * 4 * 8));
+    Ok(guess_config_from_bytes(&data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roughness_separates_tone_from_noise() {
+        let tone: Vec<f32> = (0..4800).map(|i| (i as f32 * 0.05).sin()).collect();
+        assert!(roughness(&tone, 1) < 0.1);
+        // 线性同余发生器产生的伪随机序列
+        let mut state = 12345u32;
+        let noise: Vec<f32> = (0..4800)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                (state >> 16) as f32 / 32768.0 - 1.0
+            })
+            .collect();
+        assert!(roughness(&noise, 1) > 0.9);
+        assert_eq!(roughness(&[0.0; 16], 1), f32::INFINITY);
+    }
+}
@@ -0,0 +1,520 @@
+// 文件名参数推断：用正则识别采样率、声道数和样本格式的常见写法（48k、44100hz、_sr16000_、f32le、s24、6ch 等）
+
+use std::sync::LazyLock;
+
+use regex::{Captures, Regex, RegexBuilder};
+
+use crate::{AudioConfig, AudioError, Endianness, SampleFormat};
+
+/// MPEG / 设备常用的标准采样率，没有单位的数字只有等于其中之一时才视为采样率
+const STANDARD_SAMPLE_RATES: [u32; 13] = [8000, 11025, 12000, 16000, 22050, 24000, 32000, 44100, 48000, 88200, 96000, 176400, 192000];
+
+/// 编译文件名匹配规则；规则都是常量，编译失败属于程序错误
+fn pattern(pattern: &str) -> Regex {
+    Regex::new(pattern).expect("invalid filename pattern")
+}
+
+/// 带 Hz 单位或 sr / rate / fs 前缀的采样率：`44100hz`、`16000_hz`、`_sr16000_`、`rate-8000`
+static RATE_EXPLICIT: LazyLock<Regex> =
+    LazyLock::new(|| pattern(r"(?:^|[^0-9])(?P<t1>(?P<hz>[1-9][0-9]{3,5})[ _-]?hz)|(?:^|[^a-z])(?P<t2>(?:sr|rate|fs)[ =_-]?(?P<prefixed>[1-9][0-9]{3,5}))(?:[^0-9]|$)"));
+/// 以 k 为单位的采样率：`48k`、`44.1khz`、`22k`（不匹配 `128kbps` 这样的码率）
+static RATE_KILO: LazyLock<Regex> = LazyLock::new(|| pattern(r"(?:^|[^0-9.])(?P<t1>(?P<kilo>[0-9]{1,3}(?:\.[0-9]{1,3})?)[ _-]?k(?:hz)?)(?:[^a-z]|$)"));
+/// 连续的数字，其中等于标准采样率的视为没有单位的采样率：`rec_16000_mono`
+static DIGITS: LazyLock<Regex> = LazyLock::new(|| pattern(r"[0-9]+"));
+/// 声道数：`6ch`、`2_ch`、`8channels`、`ch1`、`5.1`、`7.1`
+static CHANNELS: LazyLock<Regex> = LazyLock::new(|| {
+    pattern(r"(?:^|[^0-9.])(?P<t1>(?P<count>[0-9]{1,2})[ _-]?(?:ch|chan|channels?))(?:[^a-z]|$)|(?:^|[^a-z])(?P<t2>ch[ _-]?(?P<index>[0-9]{1,2}))(?:[^0-9]|$)|(?:^|[^0-9.])(?P<t3>(?P<layout>[57])\.1)(?:[^0-9]|$)")
+});
+/// ffmpeg / ALSA 风格的样本格式：`s16le`、`f32le`、`s24`、`u8`、`S16_LE`、`S24_3LE`、`pcm_mulaw`
+static SAMPLE_FORMAT: LazyLock<Regex> = LazyLock::new(|| {
+    pattern(r"(?:^|[^a-z0-9])(?P<t1>(?P<kind>[suf])(?P<bits>8|16|24|32)(?:[_-]?3)?[_-]?(?P<order>le|be)?|(?P<law>mu?law|ulaw|alaw)|float(?:32)?[_-]?(?P<float_order>le|be)?)(?:[^a-z0-9]|$)")
+});
+/// 位深度：`16bit`、`24-bit`、`32bits`、`16位`、`24ビット`、`16비트`
+static BITS: LazyLock<Regex> = LazyLock::new(|| pattern(r"(?:^|[^0-9])(?P<t1>(?P<bits>8|16|24|32)[ _-]?(?:bits?|位|ビット|비트))(?:[^a-z]|$)"));
+/// 表示声道数的关键词（简体 / 繁体中文、英文、日文、韩文），单声道在前：同时出现时优先识别为单声道
+const CHANNEL_KEYWORDS: [(&str, u8); 12] = [
+    ("单声道", 1),
+    ("單聲道", 1),
+    ("mono", 1),
+    ("モノラル", 1),
+    ("모노", 1),
+    ("立体声", 2),
+    ("立體聲", 2),
+    ("双声道", 2),
+    ("雙聲道", 2),
+    ("stereo", 2),
+    ("ステレオ", 2),
+    ("스테레오", 2),
+];
+/// 显式的字节序：`big-endian`、`littleendian`
+static ENDIANNESS: LazyLock<Regex> = LazyLock::new(|| pattern(r"(?P<t1>(?P<order>big|little)[ _-]?endian)"));
+
+/// 从文件名中识别出的参数，未识别的项为 None
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct FilenameHints {
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u8>,
+    pub sample_format: Option<SampleFormat>,
+    pub endianness: Option<Endianness>,
+    /// 采样率来自没有单位的数字（如 `_16000_`），可能是编号或其他数值
+    pub bare_sample_rate: bool,
+    /// 文件名中被识别的片段（小写），按采样率、声道、样本格式、字节序的顺序
+    pub matched_tokens: Vec<String>,
+}
+
+impl FilenameHints {
+    /// 解析文件名（不区分大小写），同一项有多种写法时优先使用更明确的写法
+    pub fn parse(filename: &str) -> Self {
+        Self::parse_with_keywords(filename, &[])
+    }
+
+    /// 解析文件名，`channel_keywords` 中的自定义关键词优先于内置关键词
+    pub fn parse_with_keywords(filename: &str, channel_keywords: &[(String, u8)]) -> Self {
+        let name = filename.to_lowercase();
+        let mut hints = FilenameHints::default();
+        if let Some((sample_rate, token, bare)) = parse_sample_rate(&name) {
+            hints.sample_rate = Some(sample_rate);
+            hints.bare_sample_rate = bare;
+            hints.matched_tokens.push(token);
+        }
+        if let Some((channels, token)) = parse_channels(&name, channel_keywords) {
+            hints.channels = Some(channels);
+            hints.matched_tokens.push(token);
+        }
+        if let Some((sample_format, endianness, token)) = parse_sample_format(&name) {
+            hints.sample_format = Some(sample_format);
+            hints.endianness = endianness;
+            hints.matched_tokens.push(token);
+        } else if let Some(caps) = BITS.captures(&name) {
+            hints.sample_format = caps["bits"].parse().ok().and_then(SampleFormat::from_bits);
+            hints.matched_tokens.push(token(&caps));
+        }
+        if let Some(caps) = ENDIANNESS.captures(&name) {
+            hints.endianness = Some(if &caps["order"] == "big" { Endianness::Big } else { Endianness::Little });
+            hints.matched_tokens.push(token(&caps));
+        }
+        hints
+    }
+
+    /// 未识别的项使用默认值（44.1kHz、立体声、16 位小端）
+    pub fn to_config(&self) -> AudioConfig {
+        let defaults = AudioConfig::default();
+        AudioConfig::new(self.sample_rate.unwrap_or(defaults.sample_rate), self.channels.unwrap_or(defaults.channels), 16)
+            .with_sample_format(self.sample_format.unwrap_or(defaults.sample_format))
+            .with_endianness(self.endianness.unwrap_or(defaults.endianness))
+    }
+}
+
+/// 文件名中被识别的片段：规则中的 `t1` / `t2` / `t3` 组（不含两侧用于界定边界的字符）
+fn token(caps: &Captures) -> String {
+    ["t1", "t2", "t3"].iter().find_map(|name| caps.name(name)).map(|m| m.as_str().to_string()).unwrap_or_default()
+}
+
+/// 以 k 为单位的采样率换算为 Hz，11k / 22k / 44k / 88k / 176k 对应 44.1kHz 系列
+fn kilo_to_hz(value: &str) -> Option<u32> {
+    let hz = match value {
+        "11" => 11025,
+        "22" => 22050,
+        "44" => 44100,
+        "88" => 88200,
+        "176" => 176400,
+        _ => (value.parse::<f64>().ok()? * 1000.0).round() as u32,
+    };
+    (4000..=384000).contains(&hz).then_some(hz)
+}
+
+/// 返回 (采样率, 匹配片段, 是否为没有单位的数字)
+fn parse_sample_rate(name: &str) -> Option<(u32, String, bool)> {
+    if let Some(caps) = RATE_EXPLICIT.captures(name) {
+        let value = caps.name("hz").or_else(|| caps.name("prefixed"))?;
+        return Some((value.as_str().parse().ok()?, token(&caps), false));
+    }
+    if let Some((hz, matched)) = RATE_KILO.captures_iter(name).find_map(|caps| Some((kilo_to_hz(&caps["kilo"])?, token(&caps)))) {
+        return Some((hz, matched, false));
+    }
+    DIGITS
+        .find_iter(name)
+        .filter_map(|digits| Some((digits.as_str().parse::<u32>().ok()?, digits.as_str().to_string())))
+        .find(|(rate, _)| STANDARD_SAMPLE_RATES.contains(rate))
+        .map(|(rate, matched)| (rate, matched, true))
+}
+
+/// 在文件名中查找第一个出现的关键词
+fn find_keyword<'a>(name: &str, keywords: impl IntoIterator<Item = (&'a str, u8)>) -> Option<(u8, String)> {
+    keywords.into_iter().find(|(keyword, _)| name.contains(keyword)).map(|(keyword, channels)| (channels, keyword.to_string()))
+}
+
+fn parse_channels(name: &str, custom_keywords: &[(String, u8)]) -> Option<(u8, String)> {
+    let custom = custom_keywords.iter().map(|(keyword, channels)| (keyword.as_str(), *channels));
+    if let Some(found) = find_keyword(name, custom.chain(CHANNEL_KEYWORDS)) {
+        return Some(found);
+    }
+    CHANNELS.captures_iter(name).find_map(|caps| {
+        let channels = match (caps.name("count").or_else(|| caps.name("index")), caps.name("layout")) {
+            (Some(count), _) => count.as_str().parse::<u8>().ok()?,
+            // 5.1 / 7.1 环绕声包含一个低频声道
+            (None, Some(layout)) => layout.as_str().parse::<u8>().ok()? + 1,
+            (None, None) => return None,
+        };
+        (channels > 0).then(|| (channels, token(&caps)))
+    })
+}
+
+fn parse_sample_format(name: &str) -> Option<(SampleFormat, Option<Endianness>, String)> {
+    let caps = SAMPLE_FORMAT.captures(name)?;
+    let matched = token(&caps);
+    let endianness = |group: &str| {
+        caps.name(group).map(|m| if m.as_str() == "be" { Endianness::Big } else { Endianness::Little })
+    };
+    if let Some(law) = caps.name("law") {
+        let format = if law.as_str() == "alaw" { SampleFormat::Alaw } else { SampleFormat::Ulaw };
+        return Some((format, None, matched));
+    }
+    let Some(kind) = caps.name("kind") else {
+        return Some((SampleFormat::F32, endianness("float_order"), matched));
+    };
+    let format = match (kind.as_str(), &caps["bits"]) {
+        ("u", "8") => SampleFormat::U8,
+        ("s", "16") => SampleFormat::S16,
+        ("s", "24") => SampleFormat::S24,
+        ("s", "32") => SampleFormat::S32,
+        ("f", "32") => SampleFormat::F32,
+        _ => return None,
+    };
+    Some((format, endianness("order"), matched))
+}
+
+/// 从文件名智能推断音频配置
+///
+/// 识别的写法包括：
+/// * 采样率：`48k`、`44.1k`、`22khz`、`44100hz`、`_sr16000_`、`rate-8000`，以及没有单位的标准采样率（如 `_16000_`）
+/// * 声道：`mono` / `stereo`、`单声道` / `立体声` / `双声道`（含繁体）、`モノラル` / `ステレオ`、`모노` / `스테레오`，
+///   以及 `6ch`、`2_channels`、`ch1`、`5.1`
+/// * 样本格式：`16bit`、`24-bit`、`16位`、`24ビット`、`16비트`、`s16le`、`S16_LE`、`s24`、`f32le`、`u8`、`s32be`、`float`、`mulaw` / `alaw`
+///
+/// 未识别的项使用默认值：44.1kHz、立体声、16 位小端
+pub fn infer_audio_config_from_filename(filename: &str) -> AudioConfig {
+    FilenameHints::parse(filename).to_config()
+}
+
+/// 文件名没有采样率线索时各候选采样率的先验权重，第一项为默认值
+const RATE_FALLBACKS: [(u32, f32); 4] = [(44100, 0.35), (48000, 0.3), (16000, 0.2), (8000, 0.15)];
+/// 文件名没有声道线索时各候选声道数的先验权重，第一项为默认值
+const CHANNEL_FALLBACKS: [(u8, f32); 2] = [(2, 0.55), (1, 0.45)];
+
+/// 带置信度的推断结果
+///
+/// 置信度是采样率、声道数和样本格式三项置信度的乘积：明确写出的项为 1.0，
+/// 没有单位的采样率数字为 0.8，只有位深度没有字节序为 0.9，使用默认值的项取该默认值的先验权重。
+/// 置信度较低（如低于 0.5）时，调用方可以提示用户确认参数，或改用 `guess_config_from_content` 分析文件内容。
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InferredConfig {
+    /// 最可能的配置，与 `infer_audio_config_from_filename` 的结果相同
+    pub config: AudioConfig,
+    /// 置信度（0 到 1）
+    pub confidence: f32,
+    /// 文件名中被识别的片段（小写），如 `["48k", "2ch", "s16le"]`
+    pub matched_tokens: Vec<String>,
+    /// 其他可能的配置及其置信度，按置信度从高到低排列；只包含未识别项取其他常见值的组合
+    pub alternatives: Vec<(AudioConfig, f32)>,
+}
+
+/// 从文件名推断音频配置，同时给出置信度、识别依据和备选配置
+///
+/// # Arguments
+/// * `filename` - 文件名（不需要存在）
+///
+/// # Returns
+/// * 推断结果；`config` 与 `infer_audio_config_from_filename` 相同
+pub fn infer_audio_config_with_confidence(filename: &str) -> InferredConfig {
+    FilenameHints::parse(filename).into_inferred()
+}
+
+impl FilenameHints {
+    /// 计算置信度并列出备选配置
+    fn into_inferred(self) -> InferredConfig {
+        let config = self.to_config();
+
+        let rate_confidence = match (self.sample_rate, self.bare_sample_rate) {
+            (Some(_), false) => 1.0,
+            (Some(_), true) => 0.8,
+            (None, _) => RATE_FALLBACKS[0].1,
+        };
+        let channel_confidence = if self.channels.is_some() { 1.0 } else { CHANNEL_FALLBACKS[0].1 };
+        let format_confidence = match (self.sample_format, self.endianness) {
+            (Some(format), None) if format.bytes_per_sample() > 1 => 0.9,
+            (Some(_), _) => 1.0,
+            (None, _) => 0.8,
+        };
+        let confidence = rate_confidence * channel_confidence * format_confidence;
+
+        // 只替换未识别的项：其他常见采样率、另一种声道数，以及两者的组合
+        let rates: Vec<(u32, f32)> = match self.sample_rate {
+            Some(rate) => vec![(rate, rate_confidence)],
+            None => RATE_FALLBACKS.to_vec(),
+        };
+        let channels: Vec<(u8, f32)> = match self.channels {
+            Some(channels) => vec![(channels, channel_confidence)],
+            None => CHANNEL_FALLBACKS.to_vec(),
+        };
+        let mut alternatives: Vec<(AudioConfig, f32)> = rates
+            .iter()
+            .flat_map(|&(rate, rate_score)| channels.iter().map(move |&(channels, channel_score)| (rate, channels, rate_score * channel_score)))
+            .filter(|&(rate, channels, _)| (rate, channels) != (config.sample_rate, config.channels))
+            .map(|(rate, channels, score)| {
+                let mut alternative = config.clone();
+                alternative.sample_rate = rate;
+                alternative.channels = channels;
+                (alternative, score * format_confidence)
+            })
+            .collect();
+        alternatives.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        InferredConfig {
+            config,
+            confidence,
+            matched_tokens: self.matched_tokens,
+            alternatives,
+        }
+    }
+}
+
+/// 自定义推断规则：文件名匹配时给出其中指定的参数，未指定的项交给其他规则
+///
+/// ```
+/// use audio_helper::{ConfigInferencer, InferenceRule};
+///
+/// // DEVA_ 开头的文件固定为 8kHz 单声道 16 位
+/// let rule = InferenceRule::prefix("DEVA_").with_sample_rate(8000).with_channels(1).with_bits(16).unwrap();
+/// let config = ConfigInferencer::new().with_rule(rule).infer("DEVA_0001.pcm");
+/// assert_eq!((config.sample_rate, config.channels), (8000, 1));
+/// ```
+#[derive(Debug, Clone)]
+pub struct InferenceRule {
+    pattern: Regex,
+    sample_rate: Option<u32>,
+    channels: Option<u8>,
+    sample_format: Option<SampleFormat>,
+    endianness: Option<Endianness>,
+}
+
+impl InferenceRule {
+    /// 按正则表达式匹配文件名（不区分大小写）
+    ///
+    /// # Arguments
+    /// * `pattern` - 正则表达式，匹配到的片段记入 `InferredConfig::matched_tokens`
+    pub fn new(pattern: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        let pattern = RegexBuilder::new(pattern).case_insensitive(true).build()?;
+        Ok(InferenceRule {
+            pattern,
+            sample_rate: None,
+            channels: None,
+            sample_format: None,
+            endianness: None,
+        })
+    }
+
+    /// 匹配以指定文本开头的文件名（不区分大小写）
+    pub fn prefix(prefix: &str) -> Self {
+        InferenceRule::new(&format!("^{}", regex::escape(prefix))).expect("escaped prefix is a valid pattern")
+    }
+
+    /// 匹配包含指定文本的文件名（不区分大小写）
+    pub fn contains(text: &str) -> Self {
+        InferenceRule::new(&regex::escape(text)).expect("escaped text is a valid pattern")
+    }
+
+    /// 匹配时的采样率
+    pub fn with_sample_rate(mut self, sample_rate: u32) -> Self {
+        self.sample_rate = Some(sample_rate);
+        self
+    }
+
+    /// 匹配时的声道数
+    pub fn with_channels(mut self, channels: u8) -> Self {
+        self.channels = Some(channels);
+        self
+    }
+
+    /// 匹配时的样本格式
+    pub fn with_sample_format(mut self, sample_format: SampleFormat) -> Self {
+        self.sample_format = Some(sample_format);
+        self
+    }
+
+    /// 匹配时的位深度（8 / 16 / 24 / 32，对应整数样本格式）
+    pub fn with_bits(self, bits: u16) -> Result<Self, Box<dyn std::error::Error>> {
+        let sample_format = SampleFormat::from_bits(bits).ok_or_else(|| AudioError::InvalidConfig(format!("Unsupported bit depth: {}", bits)))?;
+        Ok(self.with_sample_format(sample_format))
+    }
+
+    /// 匹配时的字节序
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = Some(endianness);
+        self
+    }
+
+    /// 文件名匹配时，用规则中的参数填充 `hints` 中尚未确定的项；有任何一项被采用时记录匹配片段
+    fn fill(&self, name: &str, hints: &mut FilenameHints) {
+        let Some(matched) = self.pattern.find(name) else { return };
+        let mut used = false;
+        if hints.sample_rate.is_none() && self.sample_rate.is_some() {
+            hints.sample_rate = self.sample_rate;
+            hints.bare_sample_rate = false;
+            used = true;
+        }
+        if hints.channels.is_none() && self.channels.is_some() {
+            hints.channels = self.channels;
+            used = true;
+        }
+        if hints.sample_format.is_none() && self.sample_format.is_some() {
+            hints.sample_format = self.sample_format;
+            used = true;
+        }
+        if hints.endianness.is_none() && self.endianness.is_some() {
+            hints.endianness = self.endianness;
+            used = true;
+        }
+        if used {
+            hints.matched_tokens.push(matched.as_str().to_string());
+        }
+    }
+}
+
+/// 可扩展的文件名推断器：在内置规则之外追加自定义规则，并控制两者的优先级
+///
+/// 每项参数按以下顺序取第一个给出该项的规则：
+/// 1. `with_rule` 添加的规则（按添加顺序），优先于内置规则
+/// 2. 内置规则（`infer_audio_config_from_filename` 识别的写法），可用 `without_builtin_rules` 关闭；
+///    `with_channel_keyword` 添加的声道关键词属于这一级，先于内置关键词查找，关闭内置规则后仍然有效
+/// 3. `with_fallback_rule` 添加的规则（按添加顺序），只在内置规则没有识别出该项时使用
+/// 4. 默认值：44.1kHz、立体声、16 位小端
+#[derive(Debug, Clone)]
+pub struct ConfigInferencer {
+    rules: Vec<InferenceRule>,
+    fallback_rules: Vec<InferenceRule>,
+    channel_keywords: Vec<(String, u8)>,
+    builtin_rules: bool,
+}
+
+impl Default for ConfigInferencer {
+    fn default() -> Self {
+        ConfigInferencer {
+            rules: Vec::new(),
+            fallback_rules: Vec::new(),
+            channel_keywords: Vec::new(),
+            builtin_rules: true,
+        }
+    }
+}
+
+impl ConfigInferencer {
+    /// 只包含内置规则的推断器
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加优先于内置规则的自定义规则
+    pub fn with_rule(mut self, rule: InferenceRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// 添加只在内置规则没有识别出对应参数时使用的自定义规则
+    pub fn with_fallback_rule(mut self, rule: InferenceRule) -> Self {
+        self.fallback_rules.push(rule);
+        self
+    }
+
+    /// 添加表示声道数的自定义关键词（不区分大小写），如 `("mono_mic", 1)`、`("zweikanal", 2)`
+    ///
+    /// # Arguments
+    /// * `keyword` - 文件名中出现的词，不能为空
+    /// * `channels` - 对应的声道数
+    pub fn with_channel_keyword(mut self, keyword: &str, channels: u8) -> Result<Self, Box<dyn std::error::Error>> {
+        let keyword = keyword.trim().to_lowercase();
+        if keyword.is_empty() {
+            return Err(AudioError::InvalidConfig("Channel keyword must not be empty".into()).into());
+        }
+        if channels == 0 {
+            return Err(AudioError::InvalidConfig(format!("Invalid channels for keyword {}: 0", keyword)).into());
+        }
+        self.channel_keywords.push((keyword, channels));
+        Ok(self)
+    }
+
+    /// 不使用内置规则，只按自定义规则推断
+    pub fn without_builtin_rules(mut self) -> Self {
+        self.builtin_rules = false;
+        self
+    }
+
+    /// 从文件名推断音频配置
+    pub fn infer(&self, filename: &str) -> AudioConfig {
+        self.hints(filename).to_config()
+    }
+
+    /// 从文件名推断音频配置，同时给出置信度、识别依据和备选配置
+    pub fn infer_with_confidence(&self, filename: &str) -> InferredConfig {
+        self.hints(filename).into_inferred()
+    }
+
+    fn hints(&self, filename: &str) -> FilenameHints {
+        let name = filename.to_lowercase();
+        let mut hints = FilenameHints::default();
+        for rule in &self.rules {
+            rule.fill(&name, &mut hints);
+        }
+        if !self.builtin_rules && hints.channels.is_none() {
+            let keywords = self.channel_keywords.iter().map(|(keyword, channels)| (keyword.as_str(), *channels));
+            if let Some((channels, keyword)) = find_keyword(&name, keywords) {
+                hints.channels = Some(channels);
+                hints.matched_tokens.push(keyword);
+            }
+        }
+        if self.builtin_rules {
+            let builtin = FilenameHints::parse_with_keywords(filename, &self.channel_keywords);
+            let mut used = false;
+            if hints.sample_rate.is_none() && builtin.sample_rate.is_some() {
+                hints.sample_rate = builtin.sample_rate;
+                hints.bare_sample_rate = builtin.bare_sample_rate;
+                used = true;
+            }
+            if hints.channels.is_none() && builtin.channels.is_some() {
+                hints.channels = builtin.channels;
+                used = true;
+            }
+            if hints.sample_format.is_none() && builtin.sample_format.is_some() {
+                hints.sample_format = builtin.sample_format;
+                used = true;
+            }
+            if hints.endianness.is_none() && builtin.endianness.is_some() {
+                hints.endianness = builtin.endianness;
+                used = true;
+            }
+            if used {
+                hints.matched_tokens.extend(builtin.matched_tokens);
+            }
+        }
+        for rule in &self.fallback_rules {
+            rule.fill(&name, &mut hints);
+        }
+        hints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kilo_rates() {
+        assert_eq!(kilo_to_hz("44.1"), Some(44100));
+        assert_eq!(kilo_to_hz("22"), Some(22050));
+        assert_eq!(kilo_to_hz("48"), Some(48000));
+        assert_eq!(kilo_to_hz("1"), None);
+        // 码率不是采样率
+        assert_eq!(FilenameHints::parse("song_128kbps.pcm").sample_rate, None);
+    }
+}
@@ -0,0 +1,103 @@
+// 输入文件的只读视图：启用 mmap 特性时把文件映射到内存，否则整体读入 Vec
+
+use std::fs::File;
+use std::io::{self, Read};
+use std::ops::Deref;
+use std::path::Path;
+
+use crate::progress::{ConvertOptions, ConvertStage, ProgressReader};
+
+/// 输入文件的全部字节
+pub(crate) enum InputData {
+    /// 读入内存的副本
+    Owned(Vec<u8>),
+    /// 内存映射，数据由操作系统按需换入，不占用堆内存
+    #[cfg(feature = "mmap")]
+    Mapped(memmap2::Mmap),
+}
+
+impl AsRef<[u8]> for InputData {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl Deref for InputData {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            InputData::Owned(data) => data,
+            #[cfg(feature = "mmap")]
+            InputData::Mapped(map) => map,
+        }
+    }
+}
+
+/// 读取整个输入文件
+pub(crate) fn read_file(path: &Path) -> io::Result<InputData> {
+    let file = File::open(path)?;
+    #[cfg(feature = "mmap")]
+    if let Some(map) = map_file(&file)? {
+        return Ok(map);
+    }
+    let mut data = Vec::new();
+    (&file).read_to_end(&mut data)?;
+    Ok(InputData::Owned(data))
+}
+
+/// 读取整个输入文件并报告 Reading 阶段的进度，映射文件时该阶段立即完成
+pub(crate) fn read_file_with_progress(file: File, total: u64, options: &mut ConvertOptions) -> Result<InputData, Box<dyn std::error::Error>> {
+    #[cfg(feature = "mmap")]
+    {
+        options.check_cancelled()?;
+        if let Some(map) = map_file(&file)? {
+            options.report(ConvertStage::Reading, 0, total);
+            options.report(ConvertStage::Reading, total, total);
+            return Ok(map);
+        }
+    }
+    let mut data = Vec::with_capacity(total as usize);
+    let read = ProgressReader::new(file, options, ConvertStage::Reading, total).read_to_end(&mut data);
+    read.map_err(|e| options.cancellation_aware(e))?;
+    Ok(InputData::Owned(data))
+}
+
+/// 映射普通文件；管道、设备等无法映射的输入返回 None，由调用方按流读取
+#[cfg(feature = "mmap")]
+fn map_file(file: &File) -> io::Result<Option<InputData>> {
+    if !file.metadata()?.is_file() {
+        return Ok(None);
+    }
+    // 映射期间文件被其他进程截断会导致 SIGBUS，这是换取低内存占用的代价
+    let map = unsafe { memmap2::Mmap::map(file)? };
+    Ok(Some(InputData::Mapped(map)))
+}
+
+/// 按流读取的输入文件，启用 mmap 特性时直接从映射中拷贝，不经过 read 系统调用
+pub(crate) enum InputStream {
+    File(File),
+    #[cfg(feature = "mmap")]
+    Mapped(io::Cursor<InputData>),
+}
+
+impl Read for InputStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            InputStream::File(file) => file.read(buf),
+            #[cfg(feature = "mmap")]
+            InputStream::Mapped(cursor) => cursor.read(buf),
+        }
+    }
+}
+
+/// 打开输入文件用于按流读取，返回数据流和文件字节数
+pub(crate) fn open_stream(path: &Path) -> io::Result<(InputStream, u64)> {
+    let file = File::open(path)?;
+    let size = file.metadata()?.len();
+    #[cfg(feature = "mmap")]
+    if let Some(map) = map_file(&file)? {
+        return Ok((InputStream::Mapped(io::Cursor::new(map)), size));
+    }
+    Ok((InputStream::File(file), size))
+}
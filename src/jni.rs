@@ -0,0 +1,169 @@
+// Android JNI 绑定：对应 Java 类 com.audiohelper.AudioHelper（源码见 android/ 目录）
+//
+// 失败时抛出 com.audiohelper.AudioHelperException，其错误码与 C 接口的 CErrorCode 相同
+
+use ::jni::JNIEnv;
+use ::jni::objects::{JClass, JObject, JString, JThrowable, JValue};
+use ::jni::sys::{jint, jobject, jstring};
+
+use crate::ffi::{self, CErrorCode, CMp3Config, CDetectedFormat, FfiError};
+use crate::PcmToWavConfig;
+
+/// 转换失败时抛出的异常类
+const EXCEPTION_CLASS: &str = "com/audiohelper/AudioHelperException";
+
+/// `probe` 返回的信息类
+const AUDIO_INFO_CLASS: &str = "com/audiohelper/AudioInfo";
+
+/// 读取 Java 字符串，null 视为参数错误
+fn java_string(env: &mut JNIEnv, value: &JString, name: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if value.is_null() {
+        return Err(FfiError(CErrorCode::InvalidArgument, format!("{} must not be null", name)).into());
+    }
+    Ok(env.get_string(value)?.into())
+}
+
+/// 将 Java 的 int 参数转换为无符号整数，负数或超出范围视为参数错误
+fn java_uint<T: TryFrom<jint>>(value: jint, name: &str) -> Result<T, Box<dyn std::error::Error>> {
+    T::try_from(value).map_err(|_| FfiError(CErrorCode::InvalidArgument, format!("Invalid {}: {}", name, value)).into())
+}
+
+/// 抛出 AudioHelperException；已有挂起的 Java 异常时（如读取字符串失败）保留原异常
+fn throw(env: &mut JNIEnv, error: Box<dyn std::error::Error>) {
+    if env.exception_check().unwrap_or(true) {
+        return;
+    }
+    let code = ffi::error_code(error.as_ref()) as jint;
+    let message = error.to_string();
+    log::debug!("JNI call failed ({}): {}", code, message);
+    let thrown = (|| -> ::jni::errors::Result<()> {
+        let java_message = env.new_string(&message)?;
+        let exception = env.new_object(EXCEPTION_CLASS, "(ILjava/lang/String;)V", &[JValue::Int(code), JValue::Object(&java_message)])?;
+        env.throw(JThrowable::from(exception))
+    })();
+    // 异常类没有打包进应用时退回到 RuntimeException，不让错误被静默忽略
+    if thrown.is_err() {
+        let _ = env.exception_clear();
+        let _ = env.throw_new("java/lang/RuntimeException", message);
+    }
+}
+
+/// PCM 转 WAV：`static native void pcmToWav(String inputPath, String outputPath, int sampleRate, int channels, int bitsPerSample)`
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_audiohelper_AudioHelper_pcmToWav<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    input_path: JString<'local>,
+    output_path: JString<'local>,
+    sample_rate: jint,
+    channels: jint,
+    bits_per_sample: jint,
+) {
+    let mut result = || -> Result<(), Box<dyn std::error::Error>> {
+        let input = java_string(&mut env, &input_path, "inputPath")?;
+        let output = java_string(&mut env, &output_path, "outputPath")?;
+        let config = PcmToWavConfig::new(
+            java_uint(sample_rate, "sampleRate")?,
+            java_uint(channels, "channels")?,
+            java_uint(bits_per_sample, "bitsPerSample")?,
+        );
+        crate::trans_pcm_file_to_wav(input, output, Some(config))?;
+        Ok(())
+    };
+
+    if let Err(e) = result() {
+        throw(&mut env, e);
+    }
+}
+
+/// PCM 转 MP3：`static native void pcmToMp3(String inputPath, String outputPath, int sampleRate, int channels, int bitrateKbps, int quality)`
+///
+/// `bitrateKbps` 为 64、128、192、256 或 320，`quality` 为 0（低）到 3（最佳），与 C 接口的 `CMp3Config` 相同
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_audiohelper_AudioHelper_pcmToMp3<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    input_path: JString<'local>,
+    output_path: JString<'local>,
+    sample_rate: jint,
+    channels: jint,
+    bitrate_kbps: jint,
+    quality: jint,
+) {
+    let mut result = || -> Result<(), Box<dyn std::error::Error>> {
+        let input = java_string(&mut env, &input_path, "inputPath")?;
+        let output = java_string(&mut env, &output_path, "outputPath")?;
+        let config = ffi::c_mp3_config_to_rust(CMp3Config {
+            sample_rate: java_uint(sample_rate, "sampleRate")?,
+            channels: java_uint(channels, "channels")?,
+            bitrate: java_uint(bitrate_kbps, "bitrateKbps")?,
+            quality: java_uint(quality, "quality")?,
+        })?;
+        crate::trans_pcm_file_to_mp3(input, output, Some(config))?;
+        Ok(())
+    };
+
+    if let Err(e) = result() {
+        throw(&mut env, e);
+    }
+}
+
+/// 读取文件信息：`static native AudioInfo probe(String path)`，无法得到的字段为 0
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_audiohelper_AudioHelper_probe<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    path: JString<'local>,
+) -> jobject {
+    let mut result = || -> Result<JObject<'local>, Box<dyn std::error::Error>> {
+        let path = java_string(&mut env, &path, "path")?;
+        let info = crate::probe_audio(path)?;
+        let config = info.config.as_ref();
+        let format = match CDetectedFormat::from(info.format) {
+            CDetectedFormat::Pcm => "pcm",
+            CDetectedFormat::Wav => "wav",
+            CDetectedFormat::Mp3 => "mp3",
+            CDetectedFormat::Flac => "flac",
+            CDetectedFormat::Ogg => "ogg",
+            CDetectedFormat::Registered => match info.format {
+                crate::DetectedFormat::Registered(name) => name,
+                _ => "pcm",
+            },
+        };
+        let format = env.new_string(format)?;
+        let object = env.new_object(
+            AUDIO_INFO_CLASS,
+            "(Ljava/lang/String;IIIJJJ)V",
+            &[
+                JValue::Object(&format),
+                JValue::Int(config.map_or(0, |config| config.sample_rate as jint)),
+                JValue::Int(config.map_or(0, |config| config.channels as jint)),
+                JValue::Int(config.map_or(0, |config| config.bits_per_sample as jint)),
+                JValue::Long(info.duration.map_or(0, |duration| duration.as_millis() as i64)),
+                JValue::Long(info.data_size as i64),
+                JValue::Long(info.file_size as i64),
+            ],
+        )?;
+        Ok(object)
+    };
+
+    match result() {
+        Ok(object) => object.into_raw(),
+        Err(e) => {
+            throw(&mut env, e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+/// 库版本：`static native String getVersion()`
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_audiohelper_AudioHelper_getVersion<'local>(mut env: JNIEnv<'local>, _class: JClass<'local>) -> jstring {
+    match env.new_string(env!("CARGO_PKG_VERSION")) {
+        Ok(version) => version.into_raw(),
+        Err(e) => {
+            throw(&mut env, e.into());
+            std::ptr::null_mut()
+        }
+    }
+}
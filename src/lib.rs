@@ -1,18 +1,59 @@
+use std::borrow::Cow;
 use std::fs::File;
-use std::io::{Read, Write, BufWriter};
-use byteorder::{LittleEndian, WriteBytesExt};
+use std::io::{Read, Write, Seek, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 // ==================== 公共结构体和枚举 ====================
 
 /// 音频格式枚举
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[non_exhaustive]
 pub enum AudioFormat {
     Wav,
     Mp3,
+    Pcm,
+}
+
+/// 单次转换的结果统计
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionReport {
+    /// 输入文件字节数
+    pub input_bytes: u64,
+    /// 输出文件字节数
+    pub output_bytes: u64,
+    /// 输出音频时长
+    pub duration: Duration,
+    /// 转换耗时
+    pub elapsed: Duration,
+    /// 输出音频的实际参数（声道转换或重采样后可能与输入不同）；MP3 没有位深度，记为送入编码器的 16 位样本
+    pub config_used: AudioConfig,
+    /// 输出格式
+    pub format: AudioFormat,
+    /// 实际写入的输出文件路径（覆盖策略为 `RenameWithSuffix` 时可能与请求的路径不同）
+    pub output_path: PathBuf,
+    /// 输出文件已存在且覆盖策略为 `Skip`，本次没有转换
+    pub skipped: bool,
+}
+
+/// 输出文件已存在时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverwritePolicy {
+    /// 返回错误，不修改已有文件
+    Error,
+    /// 替换已有文件
+    #[default]
+    Overwrite,
+    /// 跳过转换，保留已有文件
+    Skip,
+    /// 在文件名后添加 `_1`、`_2` 等后缀，写入第一个不存在的路径
+    RenameWithSuffix,
 }
 
 /// 音频质量设置
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AudioQuality {
     Low,     // 低质量
     Medium,  // 中等质量
@@ -22,6 +63,7 @@ pub enum AudioQuality {
 
 /// MP3 比特率枚举
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Mp3Bitrate {
     Kbps64,
     Kbps128,
@@ -30,40 +72,487 @@ pub enum Mp3Bitrate {
     Kbps320,
 }
 
+impl Mp3Bitrate {
+    /// 比特率数值（kbps）
+    pub fn kbps(&self) -> u32 {
+        match self {
+            Mp3Bitrate::Kbps64 => 64,
+            Mp3Bitrate::Kbps128 => 128,
+            Mp3Bitrate::Kbps192 => 192,
+            Mp3Bitrate::Kbps256 => 256,
+            Mp3Bitrate::Kbps320 => 320,
+        }
+    }
+}
+
+/// VBR 质量等级，对应 LAME 的 -V0（最好）到 -V9（最小）
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum VbrQuality {
+    V0,
+    V1,
+    V2,
+    V3,
+    V4,
+    V5,
+    V6,
+    V7,
+    V8,
+    V9,
+}
+
+impl VbrQuality {
+    /// 从 0-9 的数值创建 VBR 质量等级
+    pub fn from_level(level: u8) -> Option<Self> {
+        let quality = match level {
+            0 => VbrQuality::V0,
+            1 => VbrQuality::V1,
+            2 => VbrQuality::V2,
+            3 => VbrQuality::V3,
+            4 => VbrQuality::V4,
+            5 => VbrQuality::V5,
+            6 => VbrQuality::V6,
+            7 => VbrQuality::V7,
+            8 => VbrQuality::V8,
+            9 => VbrQuality::V9,
+            _ => return None,
+        };
+        Some(quality)
+    }
+
+    /// 质量等级数值（0-9）
+    pub fn level(&self) -> u8 {
+        *self as u8
+    }
+}
+
+/// MP3 码率模式
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mp3RateMode {
+    /// 固定码率
+    Cbr(Mp3Bitrate),
+    /// 平均码率，目标比特率（kbps，8-320）
+    Abr(u32),
+    /// 可变码率
+    Vbr(VbrQuality),
+}
+
+/// MP3 声道模式
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mp3ChannelMode {
+    /// 联合立体声（利用左右声道相关性，压缩效率最高）
+    JointStereo,
+    /// 简单立体声（左右声道独立编码）
+    SimpleStereo,
+    /// 双声道（两个完全独立的单声道，如双语音轨）；LAME 目前按简单立体声编码
+    DualChannel,
+    /// 强制单声道，立体声输入会被混缩
+    Mono,
+}
+
+impl std::fmt::Display for Mp3RateMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mp3RateMode::Cbr(bitrate) => write!(f, "CBR {}kbps", bitrate.kbps()),
+            Mp3RateMode::Abr(kbps) => write!(f, "ABR {}kbps", kbps),
+            Mp3RateMode::Vbr(quality) => write!(f, "VBR V{}", quality.level()),
+        }
+    }
+}
+
 // ==================== 工具函数 ====================
 
 /// 检查文件是否为 PCM 文件
-fn is_pcm_file(file_path: &str) -> bool {
-    file_path.ends_with(".pcm")
+fn is_pcm_file(file_path: impl AsRef<Path>) -> bool {
+    file_path.as_ref().extension().is_some_and(|ext| ext == "pcm")
+}
+
+/// 输入文件不存在时的错误，类型为 `io::ErrorKind::NotFound` 的 `io::Error`，便于调用方按类型区分
+pub(crate) fn input_not_found() -> Box<dyn std::error::Error> {
+    std::io::Error::new(std::io::ErrorKind::NotFound, "Input file does not exist").into()
+}
+
+/// 记录转换完成日志（耗时与吞吐量）
+fn log_conversion_done(kind: &str, input_path: &Path, output_path: &Path, input_bytes: u64, started: Instant) {
+    let elapsed = started.elapsed();
+    let throughput = input_bytes as f64 / elapsed.as_secs_f64().max(1e-9) / 1_000_000.0;
+    log::info!(
+        "{} conversion finished: {} -> {} ({} bytes in {:.3}s, {:.2} MB/s)",
+        kind, input_path.display(), output_path.display(), input_bytes, elapsed.as_secs_f64(), throughput
+    );
+}
+
+/// 按帧数和采样率计算时长，采样率为 0 时返回 0
+fn frames_duration(frames: u64, sample_rate: u32) -> Duration {
+    if sample_rate == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_secs_f64(frames as f64 / sample_rate as f64)
 }
 
+/// 覆盖策略为 `Skip` 且输出已存在时的转换统计，音频参数从 PCM 一侧的文件名推断
+fn skipped_report(input_path: &Path, output_path: &Path, pcm_path: &Path, format: AudioFormat, started: Instant) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    let filename = pcm_path.file_name().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy();
+    let config_used = infer_audio_config_from_filename(&filename);
+    log::info!("Skipping conversion of {}: {} already exists", input_path.display(), output_path.display());
+    Ok(ConversionReport {
+        input_bytes: std::fs::metadata(input_path)?.len(),
+        output_bytes: std::fs::metadata(output_path)?.len(),
+        duration: audio_duration_with_config(output_path, &config_used).unwrap_or_default(),
+        elapsed: started.elapsed(),
+        config_used,
+        format,
+        output_path: output_path.to_path_buf(),
+        skipped: true,
+    })
+}
+
+// 错误类型
+mod error;
+pub use error::{AudioError, ErrorKind};
+
 // FFI 模块（用于移动端集成）
 pub mod ffi;
+#[cfg(feature = "jni")]
+mod jni;
+#[cfg(feature = "uniffi")]
+pub mod uniffi_api;
+#[cfg(feature = "uniffi")]
+uniffi::setup_scaffolding!();
+#[cfg(feature = "wasm")]
+mod wasm;
 
-// 检查文件是否存在
-fn file_exists(file_path: &str) -> bool {
-    std::path::Path::new(file_path).exists()
-}
+// WAV 容器（RIFF / RF64 / Wave64）
+mod wav;
+pub use wav::{WavContainer, WavAudio, WavParseMode, WavChunk, ChannelLayout};
+
+// MP3 编码辅助（LAME 底层接口）
+mod mp3;
+pub use mp3::{is_mp3_sample_rate, nearest_mp3_sample_rate, parse_mp3_info, Mp3Info, Mp3BitrateMode};
+mod parallel;
+mod codec;
+pub use codec::{AudioEncoder, AudioDecoder, WavStreamEncoder, WavWriter, WavDecoder, PcmStreamEncoder, PcmDecoder, Mp3StreamEncoder};
+#[cfg(feature = "mp3-decode")]
+pub use codec::Mp3Decoder;
+mod convert;
+pub use convert::convert;
+mod registry;
+pub use registry::{FormatHandler, FormatReader, FormatRegistry};
+mod pipeline;
+pub use pipeline::{AudioPipeline, AudioEffect, PipelineOutput};
+mod pool;
+pub use pool::{EncoderPool, Mp3Encoder, PooledEncoder};
+
+// 音频信号处理
+mod dsp;
+pub use dsp::{resample, ChannelMap, resample_i16, resample_f32, mix_samples, MixOptions, MixAlign, crossfade_append, time_stretch, downmix_to_mono, downmix_to_stereo, duplicate_to_stereo, convert_channels, apply_gain, normalize_peak, db_to_linear, linear_to_db, apply_fade, FadeConfig, FadeCurve, measure_dc_offset, remove_dc_offset, apply_limiter, true_peak, LimiterConfig, apply_noise_gate, NoiseGateConfig};
+
+// 双二阶滤波器
+mod filters;
+pub use filters::{BiquadFilter, FilterChain, FilterType};
+
+// 静音检测
+mod silence;
+pub use silence::{find_trim_range, find_silence_regions, find_split_ranges, SilenceRegion, SplitOptions};
+
+// 信号生成
+mod generate;
+pub use generate::{generate_sine, generate_sine_file, generate_white_noise, generate_pink_noise, generate_silence, generate_dtmf};
+mod probe;
+pub use probe::{detect_format, DetectedFormat, AudioInfo};
+mod infer;
+pub use infer::{infer_audio_config_from_filename, infer_audio_config_with_confidence, ConfigInferencer, InferenceRule, InferredConfig};
+mod guess;
+pub use guess::guess_config_from_content;
+mod batch;
+pub use batch::{batch_convert_directory, batch_convert_files, BatchOptions, BatchItem, BatchReport, ConvertPolicy};
+mod manifest;
+mod atomic;
+use atomic::AtomicFile;
+mod progress;
+pub use progress::{ConvertOptions, ConvertStage, ProgressEvent, ProgressCallback, CancelToken};
+use progress::ProgressReader;
+mod input;
+#[cfg(feature = "watch")]
+mod watch;
+#[cfg(feature = "watch")]
+pub use watch::{watch_directory, WatchOptions, DirectoryWatcher};
+mod analysis;
+pub use analysis::{measure_levels, spectrogram, compare_samples, estimate_bpm, LevelReport, ChannelLevels, Spectrogram, SpectrogramOptions, ComparisonReport};
+mod fingerprint;
+pub use fingerprint::AudioFingerprint;
+#[cfg(feature = "async")]
+mod async_convert;
+#[cfg(feature = "async")]
+pub use async_convert::{trans_pcm_file_to_wav_async, trans_pcm_file_to_mp3_async, trans_wav_file_to_pcm_async, auto_convert_pcm_async, AsyncError};
+#[cfg(feature = "profile")]
+mod profile;
+#[cfg(feature = "profile")]
+pub use profile::{ConversionProfile, Mp3ProfileOptions, WavProfileOptions};
+#[cfg(feature = "playback")]
+mod playback;
+#[cfg(feature = "playback")]
+pub use playback::{play_file, play_file_with_config, play_pcm};
+#[cfg(feature = "capture")]
+mod capture;
+#[cfg(feature = "capture")]
+pub use capture::Recorder;
+#[cfg(feature = "capture")]
+mod live;
+#[cfg(feature = "capture")]
+pub use live::{start_live_mp3, LiveMp3Options, LiveMp3Stats, LiveMp3Capture};
+
+// IMA ADPCM 编解码
+mod adpcm;
+
+// G.711 µ-law / A-law 编解码
+mod g711;
+pub use g711::{linear_to_ulaw, ulaw_to_linear, linear_to_alaw, alaw_to_linear};
+
+// 配置构建器
+mod builder;
+pub use builder::{Mp3ConfigBuilder, AudioConfigBuilder};
+mod preset;
+pub use preset::{trans_pcm_file_to_mp3_preset, Preset, Presets};
+
+// 原始样本格式
+mod sample;
+pub use sample::{SampleFormat, Endianness, Sample, swap_sample_bytes, convert_samples, decode_samples, encode_samples, decode_samples_f32, decode_samples_i16, deinterleave_stereo, encode_samples_f32};
+mod simd;
 
 // ==================== 配置结构体 ====================
 
 /// MP3 转换配置
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Mp3Config {
     pub sample_rate: u32,
     pub channels: u8,
-    pub bitrate: Mp3Bitrate,
+    /// 码率模式（CBR / ABR / VBR）
+    pub rate_mode: Mp3RateMode,
     pub quality: AudioQuality,
+    /// 声道模式，为 None 时由编码器自动选择
+    pub channel_mode: Option<Mp3ChannelMode>,
+    /// 采样率不被 MP3 支持时是否自动重采样到最接近的标准采样率
+    pub resample: bool,
+    /// 编码输出的采样率，必须是 MP3 标准采样率；为 None 时保持输入采样率
+    pub target_sample_rate: Option<u32>,
+    /// 输入 PCM 的样本格式
+    pub sample_format: SampleFormat,
+    /// 输入 PCM 的字节序
+    pub endianness: Endianness,
+    /// 声道映射（交换、选取或重排声道），在其他样本处理之前应用
+    pub channel_map: Option<ChannelMap>,
+    /// 编码前转换到的声道数，为 None 时保持输入声道数
+    pub target_channels: Option<u8>,
+    /// 增益（dB），超出范围的样本会被削波
+    pub gain_db: Option<f32>,
+    /// 峰值归一化的目标电平（dBFS），在增益之后应用
+    pub normalize_db: Option<f32>,
+    /// 淡入淡出，避免片段开头和结尾出现爆音
+    pub fade: Option<FadeConfig>,
+    /// 是否通过高通滤波去除直流偏移
+    pub remove_dc: bool,
+    /// 峰值限制器，在其他处理之后、编码之前应用，保证真峰值不超过上限
+    pub limiter: Option<LimiterConfig>,
+    /// 编码线程数：1 为单线程（默认），0 为使用全部可用核心；大于 1 时长音频分段并行编码
+    pub threads: usize,
+    /// 无缝编码：编码全部样本并在 LAME 标签中记录编码器延迟和末尾填充，支持无缝播放的播放器据此裁剪，
+    /// 同一段录音切分后分别编码的片段可以首尾相接地播放；关闭时末尾不足一帧的样本被丢弃（默认）
+    pub gapless: bool,
 }
 
 impl Mp3Config {
-    /// 创建新的 MP3 配置
+    /// 创建构建器，按字段名设置参数并在 `build` 时校验
+    pub fn builder() -> Mp3ConfigBuilder {
+        Mp3ConfigBuilder::new()
+    }
+
+    /// 创建新的 MP3 配置（固定码率）
     pub fn new(sample_rate: u32, channels: u8, bitrate: Mp3Bitrate, quality: AudioQuality) -> Self {
         Mp3Config {
             sample_rate,
             channels,
-            bitrate,
+            rate_mode: Mp3RateMode::Cbr(bitrate),
             quality,
+            channel_mode: None,
+            resample: true,
+            target_sample_rate: None,
+            sample_format: SampleFormat::S16,
+            endianness: Endianness::Little,
+            channel_map: None,
+            target_channels: None,
+            gain_db: None,
+            normalize_db: None,
+            fade: None,
+            remove_dc: false,
+            limiter: None,
+            threads: 1,
+            gapless: false,
+        }
+    }
+
+    /// 设置码率模式
+    pub fn with_rate_mode(mut self, rate_mode: Mp3RateMode) -> Self {
+        self.rate_mode = rate_mode;
+        self
+    }
+
+    /// 设置声道模式
+    pub fn with_channel_mode(mut self, channel_mode: Mp3ChannelMode) -> Self {
+        self.channel_mode = Some(channel_mode);
+        self
+    }
+
+    /// 设置是否自动重采样
+    pub fn with_resample(mut self, resample: bool) -> Self {
+        self.resample = resample;
+        self
+    }
+
+    /// 设置编码输出的采样率，与输入不同时编码前重采样
+    pub fn with_target_sample_rate(mut self, target_sample_rate: u32) -> Self {
+        self.target_sample_rate = Some(target_sample_rate);
+        self
+    }
+
+    /// 编码输出的采样率：指定的目标采样率，或需要时重采样到的最接近的标准采样率
+    pub(crate) fn output_sample_rate(&self) -> u32 {
+        self.target_sample_rate.unwrap_or_else(|| mp3::nearest_mp3_sample_rate(self.sample_rate))
+    }
+
+    /// 设置输入 PCM 的样本格式
+    pub fn with_sample_format(mut self, sample_format: SampleFormat) -> Self {
+        self.sample_format = sample_format;
+        self
+    }
+
+    /// 设置输入 PCM 的字节序
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    /// 设置声道映射（如 `ChannelMap::swap_stereo()` 交换左右声道），在其他样本处理之前应用
+    pub fn with_channel_map(mut self, channel_map: ChannelMap) -> Self {
+        self.channel_map = Some(channel_map);
+        self
+    }
+
+    /// 设置编码前转换到的声道数（立体声混缩为单声道或单声道复制为立体声）
+    pub fn with_target_channels(mut self, target_channels: u8) -> Self {
+        self.target_channels = Some(target_channels);
+        self
+    }
+
+    /// 设置增益（dB）
+    pub fn with_gain_db(mut self, gain_db: f32) -> Self {
+        self.gain_db = Some(gain_db);
+        self
+    }
+
+    /// 设置峰值归一化的目标电平（dBFS）
+    pub fn with_normalize_db(mut self, normalize_db: f32) -> Self {
+        self.normalize_db = Some(normalize_db);
+        self
+    }
+
+    /// 设置淡入淡出
+    pub fn with_fade(mut self, fade: FadeConfig) -> Self {
+        self.fade = Some(fade);
+        self
+    }
+
+    /// 设置是否去除直流偏移
+    pub fn with_dc_removal(mut self, remove_dc: bool) -> Self {
+        self.remove_dc = remove_dc;
+        self
+    }
+
+    /// 设置峰值限制器
+    pub fn with_limiter(mut self, limiter: LimiterConfig) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// 设置编码线程数，0 为使用全部可用核心
+    ///
+    /// 多线程编码时各段关闭比特池（bit reservoir），同等码率下音质略低于单线程编码；
+    /// 分段需要完整的样本，输入文件会整体读入内存
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    /// 设置是否无缝编码：编码全部样本并在 LAME 标签中记录真实的编码器延迟和末尾填充
+    pub fn with_gapless(mut self, gapless: bool) -> Self {
+        self.gapless = gapless;
+        self
+    }
+
+    /// 汇总编码前需要应用的样本处理
+    pub(crate) fn processing(&self) -> dsp::Processing {
+        dsp::Processing {
+            channel_map: self.channel_map.clone(),
+            target_channels: self.target_channels,
+            gain_db: self.gain_db,
+            normalize_db: self.normalize_db,
+            fade: self.fade.clone(),
+            remove_dc: self.remove_dc,
+            limiter: self.limiter.clone(),
+        }
+    }
+
+    /// 检查参数组合是否可以编码，错误信息中包含出错的字段名
+    ///
+    /// 校验失败时返回 `AudioError::InvalidConfig`
+    pub fn validate(&self) -> Result<(), AudioError> {
+        if self.sample_rate == 0 {
+            return Err(AudioError::InvalidConfig("Invalid sample_rate: must be greater than zero".into()));
+        }
+        if let Some(target_sample_rate) = self.target_sample_rate
+            && !mp3::is_mp3_sample_rate(target_sample_rate)
+        {
+            return Err(AudioError::InvalidConfig(format!("Invalid target_sample_rate: {}Hz is not an MP3 sample rate", target_sample_rate)));
+        }
+        if self.target_sample_rate.is_none() && !self.resample && !mp3::is_mp3_sample_rate(self.sample_rate) {
+            return Err(AudioError::InvalidConfig(format!("Invalid sample_rate: {}Hz is not supported by MP3 and resampling is disabled", self.sample_rate)));
+        }
+        if self.channels == 0 {
+            return Err(AudioError::InvalidConfig("Invalid channels: must be greater than zero".into()));
+        }
+        let processing = self.processing();
+        validate_processing(&processing, self.channels)?;
+        let encoded_channels = processing.output_channels(self.channels);
+        if !(1..=2).contains(&encoded_channels) {
+            return Err(AudioError::InvalidConfig(format!("Invalid channels: MP3 supports 1 or 2 channels, got {} (set target_channels to downmix)", encoded_channels)));
+        }
+        if let Some(channel_mode) = self.channel_mode
+            && channel_mode != Mp3ChannelMode::Mono
+            && encoded_channels != 2
+        {
+            return Err(AudioError::InvalidConfig(format!("Invalid channel_mode: {:?} requires stereo input, got {} channel(s)", channel_mode, encoded_channels)));
+        }
+
+        // 编码使用的采样率决定可用的比特率
+        let encoded_rate = self.output_sample_rate();
+        let range = mp3::bitrate_range_kbps(encoded_rate);
+        match &self.rate_mode {
+            Mp3RateMode::Cbr(bitrate) if !range.contains(&bitrate.kbps()) => Err(AudioError::InvalidConfig(format!(
+                "Invalid rate_mode: {}kbps is not available at {}Hz (expected {}-{}kbps)",
+                bitrate.kbps(), encoded_rate, range.start(), range.end()
+            ))),
+            Mp3RateMode::Abr(kbps) if !(8..=320).contains(kbps) => {
+                Err(AudioError::InvalidConfig(format!("Invalid rate_mode: abr {}kbps (expected 8-320)", kbps)))
+            }
+            Mp3RateMode::Abr(kbps) if !range.contains(kbps) => Err(AudioError::InvalidConfig(format!(
+                "Invalid rate_mode: {}kbps is not available at {}Hz (expected {}-{}kbps)",
+                kbps, encoded_rate, range.start(), range.end()
+            ))),
+            _ => Ok(()),
         }
     }
 
@@ -72,10 +561,57 @@ impl Mp3Config {
         Mp3Config {
             sample_rate: 44100,
             channels: 2,
-            bitrate: Mp3Bitrate::Kbps192,
+            rate_mode: Mp3RateMode::Cbr(Mp3Bitrate::Kbps192),
             quality: AudioQuality::High,
+            channel_mode: None,
+            resample: true,
+            target_sample_rate: None,
+            sample_format: SampleFormat::S16,
+            endianness: Endianness::Little,
+            channel_map: None,
+            target_channels: None,
+            gain_db: None,
+            normalize_db: None,
+            fade: None,
+            remove_dc: false,
+            limiter: None,
+            threads: 1,
+            gapless: false,
+        }
+    }
+}
+
+/// 校验各配置共有的处理选项
+fn validate_processing(processing: &dsp::Processing, channels: u8) -> Result<(), AudioError> {
+    if let Some(map) = &processing.channel_map {
+        map.validate(channels).map_err(|e| AudioError::InvalidConfig(format!("Invalid channel_map: {}", e)))?;
+    }
+    if processing.target_channels == Some(0) {
+        return Err(AudioError::InvalidConfig("Invalid target_channels: must be greater than zero".into()));
+    }
+    if processing.gain_db.is_some_and(|gain| !gain.is_finite()) {
+        return Err(AudioError::InvalidConfig("Invalid gain_db: must be a finite number".into()));
+    }
+    if processing.normalize_db.is_some_and(|level| !level.is_finite() || level > 0.0) {
+        return Err(AudioError::InvalidConfig("Invalid normalize_db: must be a finite level at or below 0 dBFS".into()));
+    }
+    if let Some(limiter) = &processing.limiter {
+        if !limiter.ceiling_db.is_finite() || limiter.ceiling_db > 0.0 {
+            return Err(AudioError::InvalidConfig("Invalid limiter: ceiling_db must be a finite level at or below 0 dBTP".into()));
+        }
+        if !limiter.lookahead_ms.is_finite() || limiter.lookahead_ms < 0.0 || !limiter.release_ms.is_finite() || limiter.release_ms < 0.0 {
+            return Err(AudioError::InvalidConfig("Invalid limiter: lookahead_ms and release_ms must be non-negative".into()));
         }
     }
+    Ok(())
+}
+
+/// 校验整数位深度
+fn validate_bits(bits_per_sample: u16) -> Result<(), AudioError> {
+    if SampleFormat::from_bits(bits_per_sample).is_none() {
+        return Err(AudioError::InvalidConfig(format!("Invalid bits_per_sample: {} (expected 8, 16, 24 or 32)", bits_per_sample)));
+    }
+    Ok(())
 }
 
 impl Default for Mp3Config {
@@ -86,19 +622,147 @@ impl Default for Mp3Config {
 
 /// 通用音频转换配置
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct AudioConfig {
     pub sample_rate: u32,
     pub channels: u8,
     pub bits_per_sample: u16,
+    /// 样本格式，整数格式与 `bits_per_sample` 保持一致
+    pub sample_format: SampleFormat,
+    /// 原始 PCM 数据的字节序
+    pub endianness: Endianness,
+    /// 声道映射（交换、选取或重排声道），在其他样本处理之前应用
+    pub channel_map: Option<ChannelMap>,
+    /// 转换时输出的声道数，为 None 时保持输入声道数
+    pub target_channels: Option<u8>,
+    /// 增益（dB），超出范围的样本会被削波
+    pub gain_db: Option<f32>,
+    /// 峰值归一化的目标电平（dBFS），在增益之后应用
+    pub normalize_db: Option<f32>,
+    /// 淡入淡出，避免片段开头和结尾出现爆音
+    pub fade: Option<FadeConfig>,
+    /// 是否通过高通滤波去除直流偏移
+    pub remove_dc: bool,
+    /// 峰值限制器，在其他处理之后、编码之前应用，保证真峰值不超过上限
+    pub limiter: Option<LimiterConfig>,
 }
 
 impl AudioConfig {
+    /// 创建构建器，按字段名设置参数并在 `build` 时校验
+    pub fn builder() -> AudioConfigBuilder {
+        AudioConfigBuilder::new()
+    }
+
+    /// 创建配置，样本格式根据位深度推断为整数格式（无法识别时为 16 位）
     pub fn new(sample_rate: u32, channels: u8, bits_per_sample: u16) -> Self {
         AudioConfig {
             sample_rate,
             channels,
             bits_per_sample,
+            sample_format: SampleFormat::from_bits(bits_per_sample).unwrap_or(SampleFormat::S16),
+            endianness: Endianness::Little,
+            channel_map: None,
+            target_channels: None,
+            gain_db: None,
+            normalize_db: None,
+            fade: None,
+            remove_dc: false,
+            limiter: None,
+        }
+    }
+
+    /// MP3 输出在 `ConversionReport::config_used` 中的参数：样本以 16 位整数送入 LAME
+    pub(crate) fn mp3_output(sample_rate: u32, channels: u8) -> Self {
+        AudioConfig::new(sample_rate, channels, 16)
+    }
+
+    /// 设置样本格式（同时更新位深度）
+    pub fn with_sample_format(mut self, sample_format: SampleFormat) -> Self {
+        self.sample_format = sample_format;
+        self.bits_per_sample = sample_format.bits_per_sample();
+        self
+    }
+
+    /// 设置原始 PCM 数据的字节序
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
+
+    /// 设置声道映射（如 `ChannelMap::swap_stereo()` 交换左右声道），在其他样本处理之前应用
+    pub fn with_channel_map(mut self, channel_map: ChannelMap) -> Self {
+        self.channel_map = Some(channel_map);
+        self
+    }
+
+    /// 设置转换时输出的声道数
+    pub fn with_target_channels(mut self, target_channels: u8) -> Self {
+        self.target_channels = Some(target_channels);
+        self
+    }
+
+    /// 设置增益（dB）
+    pub fn with_gain_db(mut self, gain_db: f32) -> Self {
+        self.gain_db = Some(gain_db);
+        self
+    }
+
+    /// 设置峰值归一化的目标电平（dBFS）
+    pub fn with_normalize_db(mut self, normalize_db: f32) -> Self {
+        self.normalize_db = Some(normalize_db);
+        self
+    }
+
+    /// 设置淡入淡出
+    pub fn with_fade(mut self, fade: FadeConfig) -> Self {
+        self.fade = Some(fade);
+        self
+    }
+
+    /// 设置是否去除直流偏移
+    pub fn with_dc_removal(mut self, remove_dc: bool) -> Self {
+        self.remove_dc = remove_dc;
+        self
+    }
+
+    /// 设置峰值限制器
+    pub fn with_limiter(mut self, limiter: LimiterConfig) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// 汇总需要应用的样本处理
+    pub(crate) fn processing(&self) -> dsp::Processing {
+        dsp::Processing {
+            channel_map: self.channel_map.clone(),
+            target_channels: self.target_channels,
+            gain_db: self.gain_db,
+            normalize_db: self.normalize_db,
+            fade: self.fade.clone(),
+            remove_dc: self.remove_dc,
+            limiter: self.limiter.clone(),
+        }
+    }
+
+    /// 检查参数是否有效，错误信息中包含出错的字段名
+    ///
+    /// 校验失败时返回 `AudioError::InvalidConfig`
+    pub fn validate(&self) -> Result<(), AudioError> {
+        if self.sample_rate == 0 {
+            return Err(AudioError::InvalidConfig("Invalid sample_rate: must be greater than zero".into()));
+        }
+        if self.channels == 0 {
+            return Err(AudioError::InvalidConfig("Invalid channels: must be greater than zero".into()));
         }
+        validate_bits(self.bits_per_sample)?;
+        if self.bits_per_sample != self.sample_format.bits_per_sample() {
+            return Err(AudioError::InvalidConfig(format!(
+                "Invalid bits_per_sample: {} does not match sample_format {:?} ({} bits)",
+                self.bits_per_sample, self.sample_format, self.sample_format.bits_per_sample()
+            )));
+        }
+        validate_processing(&self.processing(), self.channels)
     }
 
     pub fn default() -> Self {
@@ -106,87 +770,64 @@ impl AudioConfig {
             sample_rate: 44100,
             channels: 2,
             bits_per_sample: 16,
+            sample_format: SampleFormat::S16,
+            endianness: Endianness::Little,
+            channel_map: None,
+            target_channels: None,
+            gain_db: None,
+            normalize_db: None,
+            fade: None,
+            remove_dc: false,
+            limiter: None,
         }
     }
 }
 
-// ==================== 配置推断函数 ====================
-
-/// 从文件名智能推断音频配置
-pub fn infer_audio_config_from_filename(filename: &str) -> AudioConfig {
-    let filename_lower = filename.to_lowercase();
-    
-    // 推断采样率 - 按照更精确的顺序匹配，避免子串匹配问题
-    let sample_rate = if filename_lower.contains("96k") {
-        96000
-    } else if filename_lower.contains("48k") {
-        48000
-    } else if filename_lower.contains("44.1k") || filename_lower.contains("44k") {
-        44100
-    } else if filename_lower.contains("32k") {
-        32000
-    } else if filename_lower.contains("22k") {
-        22050
-    } else if filename_lower.contains("16k") {
-        16000
-    } else if filename_lower.contains("8k") {
-        8000
-    } else {
-        // 默认采样率
-        44100
-    };
-    
-    // 推断声道数
-    let channels = if filename_lower.contains("单声道") || filename_lower.contains("mono") {
-        1
-    } else if filename_lower.contains("立体声") || filename_lower.contains("stereo") || filename_lower.contains("双声道") {
-        2
-    } else {
-        // 根据其他线索推断
-        if filename_lower.contains("1ch") {
-            1
-        } else if filename_lower.contains("2ch") {
-            2
-        } else {
-            // 默认立体声
-            2
-        }
-    };
-    
-    // 推断位深度
-    let bits_per_sample = if filename_lower.contains("8bit") {
-        8
-    } else if filename_lower.contains("16bit") {
-        16
-    } else if filename_lower.contains("24bit") {
-        24
-    } else if filename_lower.contains("32bit") {
-        32
-    } else {
-        // 默认 16 位
-        16
-    };
-    
-    AudioConfig::new(sample_rate, channels, bits_per_sample)
+impl Default for AudioConfig {
+    fn default() -> Self {
+        AudioConfig::default()
+    }
 }
 
+// ==================== 配置推断函数 ====================
+
 /// 从音频配置创建 WAV 配置（保持兼容性）
 pub fn audio_config_to_wav_config(audio_config: &AudioConfig) -> PcmToWavConfig {
-    PcmToWavConfig::new(
+    let mut wav_config = PcmToWavConfig::new(
         audio_config.sample_rate,
         audio_config.channels,
         audio_config.bits_per_sample,
     )
+    .with_sample_format(audio_config.sample_format)
+    .with_endianness(audio_config.endianness);
+    wav_config.channel_map = audio_config.channel_map.clone();
+    wav_config.target_channels = audio_config.target_channels;
+    wav_config.gain_db = audio_config.gain_db;
+    wav_config.normalize_db = audio_config.normalize_db;
+    wav_config.fade = audio_config.fade.clone();
+    wav_config.remove_dc = audio_config.remove_dc;
+    wav_config.limiter = audio_config.limiter.clone();
+    wav_config
 }
 
 /// 从音频配置创建 MP3 配置
 pub fn audio_config_to_mp3_config(audio_config: &AudioConfig, bitrate: Mp3Bitrate, quality: AudioQuality) -> Mp3Config {
-    Mp3Config::new(
+    let mut mp3_config = Mp3Config::new(
         audio_config.sample_rate,
         audio_config.channels,
         bitrate,
         quality,
     )
+    .with_sample_format(audio_config.sample_format)
+    .with_endianness(audio_config.endianness);
+    mp3_config.channel_map = audio_config.channel_map.clone();
+    mp3_config.target_channels = audio_config.target_channels;
+    mp3_config.gain_db = audio_config.gain_db;
+    mp3_config.normalize_db = audio_config.normalize_db;
+    mp3_config.fade = audio_config.fade.clone();
+    mp3_config.remove_dc = audio_config.remove_dc;
+    mp3_config.limiter = audio_config.limiter.clone();
+    mp3_config
 }
 
 // ==================== 兼容性函数 ====================
@@ -203,10 +844,11 @@ pub fn infer_pcm_config_from_filename(filename: &str) -> PcmToWavConfig {
 /// * `output_path` - 输出 WAV 文件路径
 /// # Returns
 /// * `Result<PcmToWavConfig, Box<dyn std::error::Error>>` - 转换结果和使用的配置
-pub fn auto_trans_pcm_to_wav(input_path: &str, output_path: &str) -> Result<PcmToWavConfig, Box<dyn std::error::Error>> {
-    let filename = std::path::Path::new(input_path)
+pub fn auto_trans_pcm_to_wav(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> Result<PcmToWavConfig, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let filename = input_path
         .file_name()
-        .ok_or("无效的文件路径")?
+        .ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?
         .to_string_lossy();
         
     let config = infer_pcm_config_from_filename(&filename);
@@ -219,13 +861,15 @@ pub fn auto_trans_pcm_to_wav(input_path: &str, output_path: &str) -> Result<PcmT
 // ==================== 新的通用转换函数 ====================
 
 /// 自动转换 PCM 到指定格式
-pub fn auto_convert_pcm(input_path: &str, output_path: &str, format: AudioFormat) -> Result<AudioConfig, Box<dyn std::error::Error>> {
-    let filename = std::path::Path::new(input_path)
+pub fn auto_convert_pcm(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, format: AudioFormat) -> Result<AudioConfig, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let filename = input_path
         .file_name()
-        .ok_or("无效的文件路径")?
+        .ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?
         .to_string_lossy();
         
     let audio_config = infer_audio_config_from_filename(&filename);
+    audio_config.validate()?;
     
     match format {
         AudioFormat::Wav => {
@@ -233,9 +877,17 @@ pub fn auto_convert_pcm(input_path: &str, output_path: &str, format: AudioFormat
             trans_pcm_file_to_wav(input_path, output_path, Some(wav_config))?;
         }
         AudioFormat::Mp3 => {
-            let mp3_config = audio_config_to_mp3_config(&audio_config, Mp3Bitrate::Kbps192, AudioQuality::High);
+            let mp3_config = audio_config_to_mp3_config(&audio_config, mp3::default_bitrate(audio_config.sample_rate), AudioQuality::High);
             trans_pcm_file_to_mp3(input_path, output_path, Some(mp3_config))?;
         }
+        AudioFormat::Pcm => {
+            // 原始 PCM 无需转换，直接复制（输出与输入为同一文件时复制会清空文件）
+            let output_path = output_path.as_ref();
+            if output_path.exists() && output_path.canonicalize()? == input_path.canonicalize()? {
+                return Err(AudioError::InvalidInput("Output file is the same as the input file".into()).into());
+            }
+            atomic::copy_atomic(input_path, output_path)?;
+        }
     }
     
     Ok(audio_config)
@@ -249,128 +901,275 @@ pub fn auto_convert_pcm(input_path: &str, output_path: &str, format: AudioFormat
 /// * `output_path` - 输出 MP3 文件路径
 /// * `config` - MP3 配置，如果为 None 则使用默认配置
 /// # Returns
-/// * `Result<(), Box<dyn std::error::Error>>` - 转换结果
-pub fn trans_pcm_file_to_mp3(input_path: &str, output_path: &str, config: Option<Mp3Config>) -> Result<(), Box<dyn std::error::Error>> {
-    use mp3lame_encoder::{Builder, InterleavedPcm, DualPcm, FlushNoGap};
-    use std::mem::MaybeUninit;
-    
+/// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计，`config_used` 为实际编码参数（重采样后采样率可能变化）
+pub fn trans_pcm_file_to_mp3(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, config: Option<Mp3Config>) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    trans_pcm_file_to_mp3_with_options(input_path, output_path, config, ConvertOptions::default())
+}
+
+/// PCM 转 MP3，并按选项报告进度
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出 MP3 文件路径
+/// * `config` - MP3 配置，如果为 None 则使用默认配置
+/// * `options` - 转换选项（进度回调、取消令牌、覆盖策略）
+/// # Returns
+/// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计，`config_used` 为实际编码参数（重采样后采样率可能变化）
+pub fn trans_pcm_file_to_mp3_with_options(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, config: Option<Mp3Config>, mut options: ConvertOptions) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
     let mp3_config = config.unwrap_or_default();
+    let started = Instant::now();
+    log::debug!("MP3 conversion started: {} -> {} ({:?})", input_path.display(), output_path.display(), mp3_config);
+    let Some(resolved) = atomic::resolve_output(output_path, options.overwrite)? else {
+        return skipped_report(input_path, output_path, input_path, AudioFormat::Mp3, started);
+    };
+    let output_path = resolved.as_path();
     
-    // 读取 PCM 数据
-    let pcm_data = std::fs::read(input_path)?;
+    // 带有已知文件头的文件不能按原始 PCM 编码
+    let detected = probe::probe_file(input_path)?;
+    if detected.is_container() {
+        return Err(AudioError::InvalidInput(format!("Input file is a {:?} file, not raw PCM", detected)).into());
+    }
+    mp3_config.validate()?;
+    let mut input_file = File::open(input_path)?;
+    let input_bytes = input_file.metadata()?.len();
     
-    // 创建 MP3 编码器
-    let mut builder = Builder::new()
-        .ok_or("Failed to create MP3 encoder builder (mp3lame library not available)")?;
+    let input_frames = input_bytes / (mp3_config.sample_format.bytes_per_sample() * mp3_config.channels.max(1) as usize) as u64;
+    let streaming = mp3_config.processing().is_identity(mp3_config.channels)
+        && mp3_config.output_sample_rate() == mp3_config.sample_rate
+        && parallel::segment_count(input_frames, mp3_config.sample_rate, mp3_config.threads) == 1;
+    let (output_bytes, mp3_config, duration) = if streaming {
+        // 不需要样本处理和重采样时边读边编码，内存占用与文件大小无关
+        let mut writer = BufWriter::new(AtomicFile::create(output_path)?);
+        let (output_bytes, duration) = stream_pcm_to_mp3(&mut input_file, input_bytes, &mp3_config, &mut writer, &mut options)?;
+        writer.into_inner().map_err(|e| e.into_error())?.commit()?;
+        (output_bytes, mp3_config, duration)
+    } else {
+        // 归一化、重采样和多线程分段需要完整的信号，整体读入后编码
+        let pcm_data = input::read_file_with_progress(input_file, input_bytes, &mut options)?;
+        let (mp3_data, mp3_config, duration) = encode_pcm_to_mp3(&pcm_data, mp3_config, &mut options)?;
+        
+        // 写入文件
+        let output_bytes = mp3_data.len() as u64;
+        options.report(ConvertStage::Writing, 0, output_bytes);
+        atomic::write_atomic(output_path, &mp3_data)?;
+        options.report(ConvertStage::Writing, output_bytes, output_bytes);
+        (output_bytes, mp3_config, duration)
+    };
     
-    builder.set_num_channels(mp3_config.channels)
-        .map_err(|e| format!("Failed to set channels: {:?}", e))?;
+    log::debug!("MP3 output: {}, {}Hz, {} channels", mp3_config.rate_mode, mp3_config.sample_rate, mp3_config.channels);
+    log_conversion_done("MP3", input_path, output_path, input_bytes, started);
     
-    builder.set_sample_rate(mp3_config.sample_rate)
-        .map_err(|e| format!("Failed to set sample rate: {:?}", e))?;
+    Ok(ConversionReport {
+        input_bytes,
+        output_bytes,
+        duration,
+        elapsed: started.elapsed(),
+        config_used: AudioConfig::mp3_output(mp3_config.sample_rate, mp3_config.channels),
+        format: AudioFormat::Mp3,
+        output_path: output_path.to_path_buf(),
+        skipped: false,
+    })
+}
+
+/// 将内存中的 PCM 数据编码为 MP3，不读写任何文件
+/// # Arguments
+/// * `pcm_data` - 原始 PCM 数据
+/// * `config` - MP3 配置，如果为 None 则使用默认配置
+/// # Returns
+/// * `Result<Vec<u8>, Box<dyn std::error::Error>>` - 完整的 MP3 数据（含 Xing/LAME 标签）
+pub fn trans_pcm_bytes_to_mp3(pcm_data: &[u8], config: Option<Mp3Config>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let detected = probe::detect_format(pcm_data);
+    if detected.is_container() {
+        return Err(AudioError::InvalidInput(format!("Input data is a {:?} file, not raw PCM", detected)).into());
+    }
+    let (mp3_data, _, _) = encode_pcm_to_mp3(pcm_data, config.unwrap_or_default(), &mut ConvertOptions::default())?;
+    Ok(mp3_data)
+}
+
+/// 将交错的 i16 样本直接编码为 MP3 并写入输出，省去序列化为字节再解析的过程
+/// # Arguments
+/// * `samples` - 交错样本，采样率和声道数与 `config` 一致（忽略配置中的样本格式和字节序）
+/// * `config` - MP3 配置，其中的样本处理和重采样同样生效
+/// * `writer` - MP3 数据的输出目标
+/// # Returns
+/// * `Result<u64, Box<dyn std::error::Error>>` - 写入的字节数（含 Xing/LAME 标签）
+pub fn trans_pcm_samples_to_mp3(samples: &[i16], config: &Mp3Config, writer: &mut impl Write) -> Result<u64, Box<dyn std::error::Error>> {
+    let (mp3_data, _, _) = encode_samples_to_mp3(samples, config.clone(), &mut ConvertOptions::default())?;
+    writer.write_all(&mp3_data)?;
+    Ok(mp3_data.len() as u64)
+}
+
+/// MP3 分块编码时每块的帧数
+const MP3_ENCODE_CHUNK_FRAMES: usize = 64 * 1152;
+
+/// MP3 流式编码时每次读取的字节数（向下取整到完整的采样帧）
+const MP3_STREAM_CHUNK_BYTES: usize = 64 * 1024;
+
+/// 按块读取 PCM 数据流并编码为 MP3，只用于不需要样本处理和重采样的配置
+///
+/// 每次只保留一块输入和对应的编码输出，结束后回到开头回填 Xing/LAME 标签
+/// # Returns
+/// * 写入的 MP3 字节数和编码的音频时长
+fn stream_pcm_to_mp3<R: Read, W: Write + Seek>(input: &mut R, data_size: u64, mp3_config: &Mp3Config, writer: &mut W, options: &mut ConvertOptions) -> Result<(u64, Duration), Box<dyn std::error::Error>> {
+    let mut encoder = Mp3StreamEncoder::new(writer, mp3_config)?;
+    let channels = mp3_config.channels.max(1) as usize;
+    let frame_bytes = mp3_config.sample_format.bytes_per_sample() * channels;
+    let mut chunk = vec![0u8; (MP3_STREAM_CHUNK_BYTES / frame_bytes).max(1) * frame_bytes];
+    let mut bytes_read = 0u64;
+    let mut frames = 0u64;
+    options.report(ConvertStage::Encoding, 0, data_size);
     
-    // 转换 bitrate 枚举到实际值
-    let bitrate_value = match mp3_config.bitrate {
-        Mp3Bitrate::Kbps64 => mp3lame_encoder::Bitrate::Kbps64,
-        Mp3Bitrate::Kbps128 => mp3lame_encoder::Bitrate::Kbps128,
-        Mp3Bitrate::Kbps192 => mp3lame_encoder::Bitrate::Kbps192,
-        Mp3Bitrate::Kbps256 => mp3lame_encoder::Bitrate::Kbps256,
-        Mp3Bitrate::Kbps320 => mp3lame_encoder::Bitrate::Kbps320,
-    };
+    loop {
+        options.check_cancelled()?;
+        let filled = sample::read_full(input, &mut chunk)?;
+        if filled == 0 {
+            break;
+        }
+        let pcm = &mut chunk[..filled];
+        if mp3_config.endianness == Endianness::Big {
+            sample::swap_sample_bytes(pcm, mp3_config.sample_format);
+        }
+        // 只有最后一块可能不足一帧，与整体编码时一样忽略末尾不完整的帧
+        let samples = sample::decode_samples_i16(pcm, mp3_config.sample_format);
+        let samples = &samples[..samples.len() / channels * channels];
+        encoder.encode(samples)?;
+        frames += (samples.len() / channels) as u64;
+        bytes_read += filled as u64;
+        options.report(ConvertStage::Encoding, bytes_read, data_size.max(bytes_read));
+        if filled < chunk.len() {
+            break;
+        }
+    }
     
-    builder.set_brate(bitrate_value)
-        .map_err(|e| format!("Failed to set bitrate: {:?}", e))?;
+    // 完成编码 - flush 剩余数据并回填 Xing/LAME 标签
+    options.report(ConvertStage::Writing, 0, 0);
+    let output_bytes = encoder.finish()?;
+    options.report(ConvertStage::Writing, output_bytes, output_bytes);
     
-    // 转换质量枚举
-    let quality_value = match mp3_config.quality {
-        AudioQuality::Low => mp3lame_encoder::Quality::Worst,
-        AudioQuality::Medium => mp3lame_encoder::Quality::Good,
-        AudioQuality::High => mp3lame_encoder::Quality::Best,
-        AudioQuality::Best => mp3lame_encoder::Quality::Best, // 最高质量
+    Ok((output_bytes, frames_duration(frames, mp3_config.sample_rate)))
+}
+
+/// 将内存中的 PCM 数据编码为 MP3
+/// # Returns
+/// * MP3 数据、实际使用的配置（声道转换或重采样后可能变化）和编码的音频时长
+fn encode_pcm_to_mp3(pcm_data: &[u8], mp3_config: Mp3Config, options: &mut ConvertOptions) -> Result<(Vec<u8>, Mp3Config, Duration), Box<dyn std::error::Error>> {
+    encode_pcm_to_mp3_with(pcm_data, mp3_config, options, |config| mp3::StreamEncoder::new(config, true))
+}
+
+/// 将内存中的 PCM 数据编码为 MP3，单线程编码时由 `new_encoder` 按最终的编码参数提供编码器（编码器池用它复用已初始化的编码器）
+fn encode_pcm_to_mp3_with(
+    pcm_data: &[u8],
+    mut mp3_config: Mp3Config,
+    options: &mut ConvertOptions,
+    new_encoder: impl FnOnce(&Mp3Config) -> Result<mp3::StreamEncoder, AudioError>,
+) -> Result<(Vec<u8>, Mp3Config, Duration), Box<dyn std::error::Error>> {
+    mp3_config.validate()?;
+    
+    // 按输入样本格式将 PCM 数据转换为 i16 样本，只有大端数据需要复制一份翻转字节序
+    let input_bytes = pcm_data.len() as u64;
+    options.report(ConvertStage::Processing, 0, input_bytes);
+    let swapped;
+    let pcm_data = if mp3_config.endianness == Endianness::Big {
+        swapped = sample::to_little_endian(pcm_data, mp3_config.sample_format);
+        &swapped[..]
+    } else {
+        pcm_data
+    };
+    let processing = mp3_config.processing();
+    let samples = if processing.is_identity(mp3_config.channels) {
+        sample::decode_samples_i16(pcm_data, mp3_config.sample_format)
+    } else {
+        let decoded = sample::decode_samples_f32(pcm_data, mp3_config.sample_format);
+        let (processed, channels) = processing.apply(decoded, mp3_config.channels, mp3_config.sample_rate)?;
+        mp3_config.channels = channels;
+        processed.into_iter().map(sample::f32_to_i16).collect()
     };
+    encode_i16_to_mp3(Cow::Owned(samples), mp3_config, input_bytes, options, new_encoder)
+}
+
+/// 将内存中的交错 i16 样本编码为 MP3，忽略配置中的样本格式和字节序；不需要样本处理和重采样时直接编码传入的切片，不复制
+pub(crate) fn encode_samples_to_mp3(samples: &[i16], mut mp3_config: Mp3Config, options: &mut ConvertOptions) -> Result<(Vec<u8>, Mp3Config, Duration), Box<dyn std::error::Error>> {
+    mp3_config.validate()?;
     
-    builder.set_quality(quality_value)
-        .map_err(|e| format!("Failed to set quality: {:?}", e))?;
+    let input_bytes = (samples.len() * 2) as u64;
+    options.report(ConvertStage::Processing, 0, input_bytes);
+    let processing = mp3_config.processing();
+    let samples = if processing.is_identity(mp3_config.channels) {
+        Cow::Borrowed(samples)
+    } else {
+        let decoded = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+        let (processed, channels) = processing.apply(decoded, mp3_config.channels, mp3_config.sample_rate)?;
+        mp3_config.channels = channels;
+        Cow::Owned(processed.into_iter().map(sample::f32_to_i16).collect())
+    };
+    encode_i16_to_mp3(samples, mp3_config, input_bytes, options, |config| mp3::StreamEncoder::new(config, true))
+}
+
+/// 重采样（需要时）并编码已经过样本处理的 i16 样本，进度按输入字节数报告
+fn encode_i16_to_mp3(
+    mut samples: Cow<'_, [i16]>,
+    mut mp3_config: Mp3Config,
+    input_bytes: u64,
+    options: &mut ConvertOptions,
+    new_encoder: impl FnOnce(&Mp3Config) -> Result<mp3::StreamEncoder, AudioError>,
+) -> Result<(Vec<u8>, Mp3Config, Duration), Box<dyn std::error::Error>> {
+    // MP3 只支持 MPEG 标准采样率，其余采样率需要先重采样；指定了目标采样率时总是重采样到目标采样率
+    let target_rate = mp3_config.output_sample_rate();
+    if target_rate != mp3_config.sample_rate {
+        log::info!("Resampling from {}Hz to {}Hz for MP3 encoding", mp3_config.sample_rate, target_rate);
+        samples = Cow::Owned(dsp::resample_i16(&samples, mp3_config.channels, mp3_config.sample_rate, target_rate));
+        mp3_config.sample_rate = target_rate;
+        mp3_config.target_sample_rate = None;
+    }
+    options.report(ConvertStage::Processing, input_bytes, input_bytes);
+    options.check_cancelled()?;
     
-    let mut encoder = builder.build()
-        .map_err(|e| format!("Failed to build encoder: {:?}", e))?;
+    let channels = mp3_config.channels.max(1) as usize;
+    let duration = frames_duration((samples.len() / channels) as u64, mp3_config.sample_rate);
     
-    // 转换 PCM 数据为 i16 样本
-    let mut samples: Vec<i16> = Vec::new();
-    for chunk in pcm_data.chunks_exact(2) {
-        let sample = i16::from_le_bytes([chunk[0], chunk[1]]);
-        samples.push(sample);
+    // 长音频按线程数分段并行编码
+    let segments = parallel::segment_count((samples.len() / channels) as u64, mp3_config.sample_rate, mp3_config.threads);
+    if segments > 1 {
+        log::debug!("Encoding MP3 in {} segments", segments);
+        let cancel = options.cancel.clone();
+        options.report(ConvertStage::Encoding, 0, input_bytes);
+        let mp3_data = parallel::encode(&samples, &mp3_config, segments, cancel.as_ref(), |encoded, total| {
+            options.report(ConvertStage::Encoding, input_bytes * encoded / total.max(1), input_bytes);
+        })?;
+        return Ok((mp3_data, mp3_config, duration));
     }
     
-    // 创建输出缓冲区
-    let mut mp3_output = vec![MaybeUninit::uninit(); pcm_data.len()]; // 预留足够空间
+    // 创建 MP3 编码器
+    let mut encoder = new_encoder(&mp3_config)?;
+    
+    // 分块编码以便报告进度
     let mut total_mp3_data = Vec::new();
+    let mut encoded_samples = 0;
+    options.report(ConvertStage::Encoding, 0, input_bytes);
     
-    // 编码为 MP3
-    if mp3_config.channels == 1 {
-        // 单声道
-        let interleaved = InterleavedPcm(&samples);
-        let bytes_written = encoder.encode(interleaved, &mut mp3_output)
-            .map_err(|e| format!("Failed to encode mono audio: {:?}", e))?;
-        
-        // 将编码的数据复制到最终输出
-        for i in 0..bytes_written {
-            unsafe {
-                total_mp3_data.push(mp3_output[i].assume_init());
-            }
-        }
-    } else {
-        // 双声道 - 需要分离左右声道
-        let mut left_samples = Vec::new();
-        let mut right_samples = Vec::new();
-        
-        for chunk in samples.chunks_exact(2) {
-            left_samples.push(chunk[0]);
-            right_samples.push(chunk[1]);
-        }
+    for chunk in samples.chunks(MP3_ENCODE_CHUNK_FRAMES * channels) {
+        options.check_cancelled()?;
+        encoder.encode(chunk, &mut total_mp3_data)?;
         
-        let dual = DualPcm { left: &left_samples, right: &right_samples };
-        let bytes_written = encoder.encode(dual, &mut mp3_output)
-            .map_err(|e| format!("Failed to encode stereo audio: {:?}", e))?;
-        
-        // 将编码的数据复制到最终输出
-        for i in 0..bytes_written {
-            unsafe {
-                total_mp3_data.push(mp3_output[i].assume_init());
-            }
-        }
+        // 按已编码的样本比例折算为输入字节数
+        encoded_samples += chunk.len();
+        options.report(ConvertStage::Encoding, input_bytes * encoded_samples as u64 / samples.len() as u64, input_bytes);
     }
     
     // 完成编码 - flush 剩余数据
-    let flush_bytes = encoder.flush::<FlushNoGap>(&mut mp3_output)
-        .map_err(|e| format!("Failed to flush encoder: {:?}", e))?;
-    
-    // 将 flush 的数据添加到最终输出
-    for i in 0..flush_bytes {
-        unsafe {
-            total_mp3_data.push(mp3_output[i].assume_init());
-        }
-    }
+    encoder.flush(&mut total_mp3_data)?;
     
-    // 写入文件
-    std::fs::write(output_path, total_mp3_data)?;
+    // 回填 Xing/LAME 标签，保证播放器能得到准确的时长
+    encoder.write_lame_tag(&mut total_mp3_data)?;
     
-    println!("Successfully converted {} to {} (MP3, {}kbps, {} channels)", 
-             input_path, output_path, 
-             match mp3_config.bitrate {
-                 Mp3Bitrate::Kbps64 => 64,
-                 Mp3Bitrate::Kbps128 => 128,
-                 Mp3Bitrate::Kbps192 => 192,
-                 Mp3Bitrate::Kbps256 => 256,
-                 Mp3Bitrate::Kbps320 => 320,
-             },
-             mp3_config.channels);
-    
-    Ok(())
+    Ok((total_mp3_data, mp3_config, duration))
 }
 
 /// PCM 转 WAV 的配置参数
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct PcmToWavConfig {
     /// 采样率，单位为 Hz
     pub sample_rate: Option<u32>,
@@ -378,107 +1177,1299 @@ pub struct PcmToWavConfig {
     pub channels: Option<u8>,
     /// 每个样本的位数
     pub bits_per_sample: Option<u16>,
+    /// 强制使用的容器格式，为 None 时自动选择（超过 4 GB 升级为 RF64）
+    pub container: Option<WavContainer>,
+    /// 强制写入 WAVE_FORMAT_EXTENSIBLE 格式的 fmt 块（多声道或高于 16 位时会自动启用）
+    pub force_extensible: bool,
+    /// 输出的扬声器布局，写入 WAVE_FORMAT_EXTENSIBLE 的声道掩码；为 None 时按声道数使用默认布局
+    pub channel_layout: Option<ChannelLayout>,
+    /// 样本格式，为 None 时根据位深度推断为整数格式
+    pub sample_format: Option<SampleFormat>,
+    /// 原始 PCM 数据的字节序，大端数据会在写入 WAV 时转换为小端
+    pub endianness: Endianness,
+    /// 将数据编码为 IMA ADPCM（4 位，仅支持单声道和立体声）
+    pub ima_adpcm: bool,
+    /// 声道映射（交换、选取或重排声道），在其他样本处理之前应用
+    pub channel_map: Option<ChannelMap>,
+    /// 输出的声道数，为 None 时保持输入声道数
+    pub target_channels: Option<u8>,
+    /// 增益（dB），超出范围的样本会被削波
+    pub gain_db: Option<f32>,
+    /// 峰值归一化的目标电平（dBFS），在增益之后应用
+    pub normalize_db: Option<f32>,
+    /// 淡入淡出，避免片段开头和结尾出现爆音
+    pub fade: Option<FadeConfig>,
+    /// 是否通过高通滤波去除直流偏移
+    pub remove_dc: bool,
+    /// 峰值限制器，在其他处理之后、编码之前应用，保证真峰值不超过上限
+    pub limiter: Option<LimiterConfig>,
 }
 
-impl PcmToWavConfig {
+impl Default for PcmToWavConfig {
     /// 创建默认配置
-    pub fn default() -> Self {
+    fn default() -> Self {
         PcmToWavConfig {
             sample_rate: Some(44100),
             channels: Some(2),
             bits_per_sample: Some(16),
+            container: None,
+            force_extensible: false,
+            channel_layout: None,
+            sample_format: Some(SampleFormat::S16),
+            endianness: Endianness::Little,
+            ima_adpcm: false,
+            channel_map: None,
+            target_channels: None,
+            gain_db: None,
+            normalize_db: None,
+            fade: None,
+            remove_dc: false,
+            limiter: None,
         }
     }
-    
+}
+
+impl PcmToWavConfig {
     /// 创建自定义配置
     pub fn new(sample_rate: u32, channels: u8, bits_per_sample: u16) -> Self {
         PcmToWavConfig {
             sample_rate: Some(sample_rate),
             channels: Some(channels),
             bits_per_sample: Some(bits_per_sample),
+            container: None,
+            force_extensible: false,
+            channel_layout: None,
+            sample_format: SampleFormat::from_bits(bits_per_sample),
+            endianness: Endianness::Little,
+            ima_adpcm: false,
+            channel_map: None,
+            target_channels: None,
+            gain_db: None,
+            normalize_db: None,
+            fade: None,
+            remove_dc: false,
+            limiter: None,
         }
     }
-}
 
-/// 将 PCM 文件转换为 WAV 文件
-/// # Arguments
-/// * `input_path` - 输入 PCM 文件路径
-/// * `output_path` - 输出 WAV 文件路径
-/// * `config` - PCM 转 WAV 的配置参数
-/// # Returns
-/// * `Result<(), Box<dyn std::error::Error>>` - 转换结果
-pub fn trans_pcm_file_to_wav(input_path: &str, output_path: &str, config: Option<PcmToWavConfig>) -> Result<(), Box<dyn std::error::Error>> {
-    // 1. 读取 pcm 文件
-    // 检查输入文件是否为 pcm 文件
-    if !is_pcm_file(input_path) {
-        return Err("Input file is not a PCM file".into());
-    } 
-    // 判断文件是否存在
-    if !std::path::Path::new(input_path).exists() {
-        return Err("Input file does not exist".into());
+    /// 强制使用指定的容器格式
+    pub fn with_container(mut self, container: WavContainer) -> Self {
+        self.container = Some(container);
+        self
     }
-    // 读取 pcm 文件内容
-    let mut input_file = File::open(input_path)?;
-    let mut pcm_data = Vec::new();
-    input_file.read_to_end(&mut pcm_data)?;
 
-    // 2. 获取配置参数
-    let config = config.unwrap_or_else(PcmToWavConfig::default);
-    let sample_rate = config.sample_rate.unwrap_or(44100);
-    let channels = config.channels.unwrap_or(2);
-    let bits_per_sample = config.bits_per_sample.unwrap_or(16);
+    /// 强制使用 WAVE_FORMAT_EXTENSIBLE 格式
+    pub fn with_force_extensible(mut self, force_extensible: bool) -> Self {
+        self.force_extensible = force_extensible;
+        self
+    }
 
-    // 计算音频参数
-    let byte_rate = sample_rate * channels as u32 * (bits_per_sample / 8) as u32;
-    let block_align = channels as u16 * (bits_per_sample / 8);
-    let data_size = pcm_data.len() as u32;
+    /// 设置输出的扬声器布局（如 `ChannelLayout::Surround51`），声道数须与输出声道数一致
+    pub fn with_channel_layout(mut self, channel_layout: ChannelLayout) -> Self {
+        self.channel_layout = Some(channel_layout);
+        self
+    }
 
-    // 3. 创建输出文件并写入 WAV 头
-    let output_file = File::create(output_path)?;
-    let mut writer = BufWriter::new(output_file);
+    /// 设置样本格式（同时更新位深度）
+    pub fn with_sample_format(mut self, sample_format: SampleFormat) -> Self {
+        self.sample_format = Some(sample_format);
+        self.bits_per_sample = Some(sample_format.bits_per_sample());
+        self
+    }
 
-    // 写入 WAV 文件头
-    write_wav_header(&mut writer, sample_rate, channels, bits_per_sample, byte_rate, block_align, data_size)?;
+    /// 设置原始 PCM 数据的字节序
+    pub fn with_endianness(mut self, endianness: Endianness) -> Self {
+        self.endianness = endianness;
+        self
+    }
 
-    // 4. 写入 PCM 数据
-    writer.write_all(&pcm_data)?;
+    /// 设置是否编码为 IMA ADPCM
+    pub fn with_ima_adpcm(mut self, ima_adpcm: bool) -> Self {
+        self.ima_adpcm = ima_adpcm;
+        self
+    }
 
-    println!("Successfully converted PCM file from {} to WAV file at {}", input_path, output_path);
-    Ok(())
-}
+    /// 设置声道映射（如 `ChannelMap::swap_stereo()` 交换左右声道），在其他样本处理之前应用
+    pub fn with_channel_map(mut self, channel_map: ChannelMap) -> Self {
+        self.channel_map = Some(channel_map);
+        self
+    }
 
-/// 写入 WAV 文件头
-fn write_wav_header<W: Write>(
-    writer: &mut W,
-    sample_rate: u32,
-    channels: u8,
-    bits_per_sample: u16,
-    byte_rate: u32,
-    block_align: u16,
-    data_size: u32,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // RIFF 头
-    writer.write_all(b"RIFF")?;
-    writer.write_u32::<LittleEndian>(36 + data_size)?; // 文件大小 - 8
-    writer.write_all(b"WAVE")?;
-
-    // fmt 块
-    writer.write_all(b"fmt ")?;
-    writer.write_u32::<LittleEndian>(16)?; // fmt 块大小
-    writer.write_u16::<LittleEndian>(1)?;  // PCM 格式
-    writer.write_u16::<LittleEndian>(channels as u16)?;
-    writer.write_u32::<LittleEndian>(sample_rate)?;
-    writer.write_u32::<LittleEndian>(byte_rate)?;
-    writer.write_u16::<LittleEndian>(block_align)?;
-    writer.write_u16::<LittleEndian>(bits_per_sample)?;
-
-    // data 块
-    writer.write_all(b"data")?;
-    writer.write_u32::<LittleEndian>(data_size)?;
+    /// 设置输出的声道数（立体声混缩为单声道或单声道复制为立体声）
+    pub fn with_target_channels(mut self, target_channels: u8) -> Self {
+        self.target_channels = Some(target_channels);
+        self
+    }
+
+    /// 设置增益（dB）
+    pub fn with_gain_db(mut self, gain_db: f32) -> Self {
+        self.gain_db = Some(gain_db);
+        self
+    }
+
+    /// 设置峰值归一化的目标电平（dBFS）
+    pub fn with_normalize_db(mut self, normalize_db: f32) -> Self {
+        self.normalize_db = Some(normalize_db);
+        self
+    }
+
+    /// 设置淡入淡出
+    pub fn with_fade(mut self, fade: FadeConfig) -> Self {
+        self.fade = Some(fade);
+        self
+    }
+
+    /// 设置是否去除直流偏移
+    pub fn with_dc_removal(mut self, remove_dc: bool) -> Self {
+        self.remove_dc = remove_dc;
+        self
+    }
+
+    /// 设置峰值限制器
+    pub fn with_limiter(mut self, limiter: LimiterConfig) -> Self {
+        self.limiter = Some(limiter);
+        self
+    }
+
+    /// 输入 PCM 的样本格式：优先使用显式设置的格式，否则按位深度推断
+    fn input_sample_format(&self) -> Result<SampleFormat, Box<dyn std::error::Error>> {
+        self.sample_format
+            .or_else(|| SampleFormat::from_bits(self.bits_per_sample.unwrap_or(16)))
+            .ok_or_else(|| AudioError::InvalidConfig("Unsupported bits per sample".into()).into())
+    }
+
+    /// 汇总写入前需要应用的样本处理
+    pub(crate) fn processing(&self) -> dsp::Processing {
+        dsp::Processing {
+            channel_map: self.channel_map.clone(),
+            target_channels: self.target_channels,
+            gain_db: self.gain_db,
+            normalize_db: self.normalize_db,
+            fade: self.fade.clone(),
+            remove_dc: self.remove_dc,
+            limiter: self.limiter.clone(),
+        }
+    }
+
+    /// 检查参数是否有效，错误信息中包含出错的字段名
+    ///
+    /// 校验失败时返回 `AudioError::InvalidConfig`
+    pub fn validate(&self) -> Result<(), AudioError> {
+        if self.sample_rate == Some(0) {
+            return Err(AudioError::InvalidConfig("Invalid sample_rate: must be greater than zero".into()));
+        }
+        if self.channels == Some(0) {
+            return Err(AudioError::InvalidConfig("Invalid channels: must be greater than zero".into()));
+        }
+        if self.sample_format.is_none() {
+            validate_bits(self.bits_per_sample.unwrap_or(16))?;
+        }
+        let processing = self.processing();
+        let channels = self.channels.unwrap_or(2);
+        validate_processing(&processing, channels)?;
+        let output_channels = processing.output_channels(channels);
+        if let Some(layout) = self.channel_layout
+            && layout.channels() != output_channels
+        {
+            return Err(AudioError::InvalidConfig(format!("Invalid channel_layout: {:?} has {} channels, output has {}", layout, layout.channels(), output_channels)));
+        }
+        if self.ima_adpcm && !(1..=2).contains(&output_channels) {
+            return Err(AudioError::InvalidConfig(format!("Invalid ima_adpcm: IMA ADPCM supports only mono or stereo audio, got {} channels", output_channels)));
+        }
+        Ok(())
+    }
+}
+
+/// 将 PCM 文件转换为 WAV 文件
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出 WAV 文件路径
+/// * `config` - PCM 转 WAV 的配置参数
+/// # Returns
+/// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计
+pub fn trans_pcm_file_to_wav(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, config: Option<PcmToWavConfig>) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    trans_pcm_file_to_wav_with_options(input_path, output_path, config, ConvertOptions::default())
+}
+
+/// 将 PCM 文件转换为 WAV 文件，并按选项报告进度
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出 WAV 文件路径
+/// * `config` - PCM 转 WAV 的配置参数
+/// * `options` - 转换选项（进度回调、取消令牌、覆盖策略）
+/// # Returns
+/// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计
+pub fn trans_pcm_file_to_wav_with_options(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, config: Option<PcmToWavConfig>, mut options: ConvertOptions) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    // 1. 读取 pcm 文件
+    // 检查输入文件是否为 pcm 文件
+    if !is_pcm_file(input_path) {
+        return Err(AudioError::InvalidInput("Input file is not a PCM file".into()).into());
+    } 
+    // 判断文件是否存在
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+    // 扩展名为 .pcm 但内容带有已知文件头时，按 PCM 处理会把文件头当作样本
+    let detected = probe::probe_file(input_path)?;
+    if detected.is_container() {
+        return Err(AudioError::InvalidInput(format!("Input file is a {:?} file, not raw PCM", detected)).into());
+    }
+    let Some(resolved) = atomic::resolve_output(output_path, options.overwrite)? else {
+        return skipped_report(input_path, output_path, input_path, AudioFormat::Wav, started);
+    };
+    let output_path = resolved.as_path();
+    // 打开 pcm 文件，数据在写入时按流拷贝，避免大文件整体读入内存
+    let (mut input, data_size) = input::open_stream(input_path)?;
+
+    // 写入临时文件，出错或取消时临时文件被删除，已有的输出文件保持不变
+    let mut writer = BufWriter::new(AtomicFile::create(output_path)?);
+    let header = write_pcm_as_wav(&mut input, data_size, &config.unwrap_or_default(), &mut writer, &mut options)?;
+    writer.into_inner().map_err(|e| e.into_error())?.commit()?;
+    let output_bytes = std::fs::metadata(output_path)?.len();
+
+    log_conversion_done("WAV", input_path, output_path, data_size, started);
+    Ok(wav_conversion_report(&header, data_size, output_path, output_bytes, started))
+}
+
+/// 将内存中的 PCM 数据转换为 WAV，不读写任何文件
+/// # Arguments
+/// * `pcm_data` - 原始 PCM 数据
+/// * `config` - PCM 转 WAV 的配置参数，如果为 None 则使用默认配置
+/// # Returns
+/// * `Result<Vec<u8>, Box<dyn std::error::Error>>` - 完整的 WAV 数据（含文件头）
+pub fn trans_pcm_bytes_to_wav(pcm_data: &[u8], config: Option<PcmToWavConfig>) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let detected = probe::detect_format(pcm_data);
+    if detected.is_container() {
+        return Err(AudioError::InvalidInput(format!("Input data is a {:?} file, not raw PCM", detected)).into());
+    }
+    let mut wav_data = Vec::with_capacity(pcm_data.len() + 80);
+    let mut input = pcm_data;
+    write_pcm_as_wav(&mut input, pcm_data.len() as u64, &config.unwrap_or_default(), &mut wav_data, &mut ConvertOptions::default())?;
+    Ok(wav_data)
+}
+
+/// 将交错的 i16 样本直接封装为 16 位 WAV 并写入输出，省去序列化为字节再解析的过程
+/// # Arguments
+/// * `samples` - 交错样本，采样率和声道数与 `config` 一致
+/// * `config` - PCM 转 WAV 的配置参数，忽略其中的位深度、样本格式和字节序；样本处理、容器格式和 IMA ADPCM 同样生效
+/// * `writer` - WAV 数据的输出目标
+/// # Returns
+/// * `Result<u64, Box<dyn std::error::Error>>` - 写入的字节数（含文件头）
+pub fn trans_pcm_samples_to_wav(samples: &[i16], config: &PcmToWavConfig, writer: &mut impl Write) -> Result<u64, Box<dyn std::error::Error>> {
+    let wav_data = encode_samples_to_wav(samples, config.clone())?;
+    writer.write_all(&wav_data)?;
+    Ok(wav_data.len() as u64)
+}
 
+/// 将内存中的交错 i16 样本封装为 WAV，忽略配置中的位深度、样本格式和字节序；小端平台上直接读取传入的切片
+pub(crate) fn encode_samples_to_wav(samples: &[i16], mut config: PcmToWavConfig) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    config.bits_per_sample = Some(16);
+    config.sample_format = Some(SampleFormat::S16);
+    config.endianness = Endianness::Little;
+    let pcm_data = sample::i16_as_le_bytes(samples);
+    let mut wav_data = Vec::with_capacity(pcm_data.len() + 80);
+    let mut input = &pcm_data[..];
+    write_pcm_as_wav(&mut input, pcm_data.len() as u64, &config, &mut wav_data, &mut ConvertOptions::default())?;
+    Ok(wav_data)
+}
+
+/// 根据写入的 WAV 文件头生成转换统计
+fn wav_conversion_report(header: &wav::WavHeader, input_bytes: u64, output_path: &Path, output_bytes: u64, started: Instant) -> ConversionReport {
+    let mut config_used = AudioConfig::new(header.sample_rate, header.channels, header.bits_per_sample).with_sample_format(header.sample_format);
+    // IMA ADPCM 的位深度为 4
+    config_used.bits_per_sample = header.bits_per_sample;
+    ConversionReport {
+        input_bytes,
+        output_bytes,
+        duration: frames_duration(header.frames(), header.sample_rate),
+        elapsed: started.elapsed(),
+        config_used,
+        format: AudioFormat::Wav,
+        output_path: output_path.to_path_buf(),
+        skipped: false,
+    }
+}
+
+/// 将 PCM 数据流按配置写为 WAV 文件
+/// # Arguments
+/// * `input` - PCM 数据流
+/// * `data_size` - 数据流的字节数
+/// * `config` - PCM 转 WAV 的配置参数
+/// * `writer` - WAV 数据的输出目标
+/// * `options` - 转换选项（进度回调、取消令牌、覆盖策略）
+/// # Returns
+/// * 写入的 WAV 文件头
+fn write_pcm_as_wav<R: Read, W: Write>(input: &mut R, data_size: u64, config: &PcmToWavConfig, writer: &mut W, options: &mut ConvertOptions) -> Result<wav::WavHeader, Box<dyn std::error::Error>> {
+    // 2. 获取配置参数
+    config.validate()?;
+    let sample_rate = config.sample_rate.unwrap_or(44100);
+    let channels = config.channels.unwrap_or(2);
+    let sample_format = config.input_sample_format()?;
+    let bits_per_sample = sample_format.bits_per_sample();
+
+    // 需要处理样本或编码为 IMA ADPCM 时，整体解码后重新编码，数据大小在编码后确定
+    let processing = config.processing();
+    if config.ima_adpcm || !processing.is_identity(channels) {
+        let mut pcm_data = Vec::with_capacity(data_size as usize);
+        let read = ProgressReader::new(input, options, ConvertStage::Reading, data_size).read_to_end(&mut pcm_data);
+        read.map_err(|e| options.cancellation_aware(e))?;
+        options.report(ConvertStage::Processing, 0, data_size);
+        if config.endianness == Endianness::Big {
+            sample::swap_sample_bytes(&mut pcm_data, sample_format);
+        }
+        let (samples, channels) = if processing.is_identity(channels) {
+            (sample::decode_samples_f32(&pcm_data, sample_format), channels)
+        } else {
+            processing.apply(sample::decode_samples_f32(&pcm_data, sample_format), channels, sample_rate)?
+        };
+
+        let (data, adpcm, bits_per_sample) = if config.ima_adpcm {
+            if !(1..=2).contains(&channels) {
+                return Err(AudioError::InvalidConfig("IMA ADPCM supports only mono or stereo audio".into()).into());
+            }
+            let samples: Vec<i16> = samples.into_iter().map(sample::f32_to_i16).collect();
+            let block_align = adpcm::default_block_align(sample_rate, channels);
+            let adpcm = adpcm::AdpcmFormat {
+                block_align,
+                frames: (samples.len() / channels as usize) as u64,
+            };
+            (adpcm::encode_ima_adpcm(&samples, channels, block_align), Some(adpcm), 4)
+        } else {
+            (sample::encode_samples_f32(&samples, sample_format), None, bits_per_sample)
+        };
+
+        let header = wav::WavHeader {
+            sample_rate,
+            channels,
+            bits_per_sample,
+            data_size: data.len() as u64,
+            container: config.container.unwrap_or_else(|| WavContainer::auto_for(data.len() as u64)),
+            force_extensible: config.force_extensible,
+            sample_format,
+            adpcm,
+            channel_mask: config.channel_layout.map(|layout| layout.channel_mask()),
+        };
+        log::debug!("Writing WAV ({:?}, {:?})", header, processing);
+        options.report(ConvertStage::Processing, data_size, data_size);
+        options.check_cancelled()?;
+
+        options.report(ConvertStage::Writing, 0, header.data_size);
+        wav::write_wav_header(writer, &header)?;
+        writer.write_all(&data)?;
+        writer.write_all(&vec![0u8; header.padding() as usize])?;
+        options.report(ConvertStage::Writing, header.data_size, header.data_size);
+
+        return Ok(header);
+    }
+
+    let container = config.container.unwrap_or_else(|| WavContainer::auto_for(data_size));
+
+    let header = wav::WavHeader {
+        sample_rate,
+        channels,
+        bits_per_sample,
+        data_size,
+        container,
+        force_extensible: config.force_extensible,
+        sample_format,
+        adpcm: None,
+        channel_mask: config.channel_layout.map(|layout| layout.channel_mask()),
+    };
+    log::debug!("Writing WAV ({:?})", header);
+
+    // 3. 写入 WAV 文件头
+    wav::write_wav_header(writer, &header)?;
+
+    // 4. 写入 PCM 数据
+    let mut input = ProgressReader::new(input, options, ConvertStage::Writing, data_size);
+    let copied = sample::copy_as_little_endian(&mut input, writer, sample_format, config.endianness);
+    let copied = copied.map_err(|e| options.cancellation_aware(e))?;
+    if copied != data_size {
+        return Err(AudioError::InvalidInput("Input file changed size during conversion".into()).into());
+    }
+    writer.write_all(&vec![0u8; header.padding() as usize])?;
+
+    Ok(header)
+}
+
+/// 在已有 WAV 文件的末尾追加原始 PCM 数据，并更新文件头中的 RIFF / data 大小
+///
+/// 用于定期落盘的分段录音：文件被原地修改，每次追加后都是完整有效的 WAV。
+/// 先写入数据再更新文件头，中途出错时文件头仍描述追加前的数据
+/// # Arguments
+/// * `wav_path` - 已有的 WAV 文件路径（RIFF 或 RF64，data 块须位于文件末尾，不支持 IMA ADPCM）
+/// * `pcm_data` - 要追加的原始 PCM 数据，须为整数个采样帧
+/// * `config` - PCM 数据的参数，采样率、声道数和样本格式须与 WAV 文件一致；大端数据会转换为小端，样本处理设置不生效
+/// # Returns
+/// * `Result<u64, Box<dyn std::error::Error>>` - 追加后文件中的采样帧数
+pub fn append_pcm_to_wav(wav_path: impl AsRef<Path>, pcm_data: &[u8], config: &AudioConfig) -> Result<u64, Box<dyn std::error::Error>> {
+    let wav_path = wav_path.as_ref();
+    if !wav_path.exists() {
+        return Err(input_not_found());
+    }
+    config.validate()?;
+    let mut file = std::fs::OpenOptions::new().read(true).write(true).open(wav_path)?;
+    let len = file.metadata()?.len();
+    let info = wav::read_wav_info(&mut file, len, WavParseMode::Lenient)?;
+    if info.is_adpcm() {
+        return Err(AudioError::UnsupportedFormat("Appending to IMA ADPCM WAV files is not supported".into()).into());
+    }
+    let existing = info.config()?;
+    if (existing.sample_rate, existing.channels, existing.sample_format) != (config.sample_rate, config.channels, config.sample_format) {
+        return Err(AudioError::InvalidInput(format!(
+            "PCM format ({}Hz, {} channels, {:?}) does not match {} ({}Hz, {} channels, {:?})",
+            config.sample_rate, config.channels, config.sample_format,
+            wav_path.display(), existing.sample_rate, existing.channels, existing.sample_format
+        )).into());
+    }
+    let frame_size = config.sample_format.bytes_per_sample() * config.channels as usize;
+    if !pcm_data.len().is_multiple_of(frame_size) {
+        return Err(AudioError::InvalidInput(format!("PCM data length {} is not a whole number of {}-byte frames", pcm_data.len(), frame_size)).into());
+    }
+
+    let data = if config.endianness == Endianness::Big {
+        Cow::Owned(sample::to_little_endian(pcm_data, config.sample_format))
+    } else {
+        Cow::Borrowed(pcm_data)
+    };
+    let frames = wav::append_data(&mut file, len, &info, &data)?;
+    log::debug!("Appended {} bytes to {} ({} frames in total)", data.len(), wav_path.display(), frames);
+    Ok(frames)
+}
+
+/// 读取 WAV 文件（支持 PCM、IEEE 浮点、G.711 和 IMA ADPCM 编码）
+///
+/// 使用宽松模式解析：跳过未知块，容忍被截断的 data 块和缺失的填充字节
+/// # Arguments
+/// * `input_path` - 输入 WAV 文件路径
+/// # Returns
+/// * `Result<WavAudio, Box<dyn std::error::Error>>` - 音频参数和样本数据，ADPCM 会被解码为 16 位 PCM
+pub fn read_wav_file(input_path: impl AsRef<Path>) -> Result<WavAudio, Box<dyn std::error::Error>> {
+    read_wav_file_with_mode(input_path, WavParseMode::Lenient)
+}
+
+/// 按指定的解析模式读取 WAV 文件
+/// # Arguments
+/// * `input_path` - 输入 WAV 文件路径
+/// * `mode` - 宽松模式尽量恢复不规范的文件，严格模式遇到未知块或结构错误时报错
+/// # Returns
+/// * `Result<WavAudio, Box<dyn std::error::Error>>` - 音频参数和样本数据，ADPCM 会被解码为 16 位 PCM
+pub fn read_wav_file_with_mode(input_path: impl AsRef<Path>, mode: WavParseMode) -> Result<WavAudio, Box<dyn std::error::Error>> {
+    let bytes = input::read_file(input_path.as_ref())?;
+    wav::parse_wav(&bytes, mode)
+}
+
+/// 将 WAV 文件解码为原始 PCM 文件
+/// # Arguments
+/// * `input_path` - 输入 WAV 文件路径
+/// * `output_path` - 输出 PCM 文件路径
+/// # Returns
+/// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计，`config_used` 为输出 PCM 数据的音频参数
+pub fn trans_wav_file_to_pcm(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    trans_wav_file_to_pcm_with_options(input_path, output_path, ConvertOptions::default())
+}
+
+/// 将 WAV 文件解码为原始 PCM 文件，并按选项报告进度
+/// # Arguments
+/// * `input_path` - 输入 WAV 文件路径
+/// * `output_path` - 输出 PCM 文件路径
+/// * `options` - 转换选项（进度回调、取消令牌、覆盖策略）
+/// # Returns
+/// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计，`config_used` 为输出 PCM 数据的音频参数
+pub fn trans_wav_file_to_pcm_with_options(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, mut options: ConvertOptions) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+    let Some(resolved) = atomic::resolve_output(output_path, options.overwrite)? else {
+        return skipped_report(input_path, output_path, output_path, AudioFormat::Pcm, started);
+    };
+    let output_path = resolved.as_path();
+
+    let input_file = File::open(input_path)?;
+    let input_bytes = input_file.metadata()?.len();
+    let bytes = input::read_file_with_progress(input_file, input_bytes, &mut options)?;
+    let audio = wav::parse_wav(&bytes, WavParseMode::Lenient)?;
+
+    let output_bytes = audio.data.len() as u64;
+    options.report(ConvertStage::Writing, 0, output_bytes);
+    atomic::write_atomic(output_path, &audio.data)?;
+    options.report(ConvertStage::Writing, output_bytes, output_bytes);
+
+    log_conversion_done("PCM", input_path, output_path, audio.data.len() as u64, started);
+    Ok(ConversionReport {
+        input_bytes,
+        output_bytes,
+        duration: frames_duration(audio.frames() as u64, audio.config.sample_rate),
+        elapsed: started.elapsed(),
+        config_used: audio.config,
+        format: AudioFormat::Pcm,
+        output_path: output_path.to_path_buf(),
+        skipped: false,
+    })
+}
+
+// ==================== 输出大小估算 ====================
+
+/// 按转换配置估算输出文件大小（用于转换前检查剩余空间或显示进度）
+pub trait OutputSizeEstimate {
+    /// 估算转换 `input_len` 字节的原始 PCM 后输出文件的字节数
+    fn estimate_output_size(&self, input_len: u64) -> u64;
+}
+
+/// 估算转换后的输出文件大小
+/// # Arguments
+/// * `input_len` - 输入 PCM 数据的字节数
+/// * `config` - 转换配置（`Mp3Config` 或 `PcmToWavConfig`）
+/// # Returns
+/// * 输出文件的估算字节数：WAV 为精确值，CBR MP3 误差在一帧以内，ABR / VBR 按平均码率估算
+pub fn estimate_output_size(input_len: u64, config: &impl OutputSizeEstimate) -> u64 {
+    config.estimate_output_size(input_len)
+}
+
+impl OutputSizeEstimate for PcmToWavConfig {
+    fn estimate_output_size(&self, input_len: u64) -> u64 {
+        let Ok(sample_format) = self.input_sample_format() else {
+            return 0;
+        };
+        let sample_rate = self.sample_rate.unwrap_or(44100);
+        let channels = self.channels.unwrap_or(2).max(1);
+        let output_channels = self.processing().output_channels(channels).max(1);
+        let frames = input_len / (sample_format.bytes_per_sample() * channels as usize) as u64;
+
+        let (data_size, adpcm, bits_per_sample) = if self.ima_adpcm {
+            let block_align = adpcm::default_block_align(sample_rate, output_channels);
+            let per_block = adpcm::samples_per_block(block_align, output_channels) as u64;
+            let adpcm = adpcm::AdpcmFormat { block_align, frames };
+            (frames.div_ceil(per_block) * block_align as u64, Some(adpcm), 4)
+        } else {
+            (frames * (sample_format.bytes_per_sample() * output_channels as usize) as u64, None, sample_format.bits_per_sample())
+        };
+        let header = wav::WavHeader {
+            sample_rate,
+            channels: output_channels,
+            bits_per_sample,
+            data_size,
+            container: self.container.unwrap_or_else(|| WavContainer::auto_for(data_size)),
+            force_extensible: self.force_extensible,
+            sample_format,
+            adpcm,
+            channel_mask: self.channel_layout.map(|layout| layout.channel_mask()),
+        };
+
+        let mut header_bytes = Vec::new();
+        match wav::write_wav_header(&mut header_bytes, &header) {
+            Ok(()) => header_bytes.len() as u64 + data_size + header.padding(),
+            Err(_) => 0,
+        }
+    }
+}
+
+impl OutputSizeEstimate for Mp3Config {
+    fn estimate_output_size(&self, input_len: u64) -> u64 {
+        let channels = self.channels.max(1);
+        let frames = input_len / (self.sample_format.bytes_per_sample() * channels as usize) as u64;
+        let sample_rate = if self.resample { mp3::nearest_mp3_sample_rate(self.sample_rate) } else { self.sample_rate };
+        if self.sample_rate == 0 || sample_rate == 0 {
+            return 0;
+        }
+        let frames = frames * sample_rate as u64 / self.sample_rate as u64;
+
+        // MPEG-2/2.5（32kHz 以下）每帧 576 个采样帧，比特率上限为 160kbps
+        let mpeg1 = sample_rate >= 32000;
+        let (samples_per_frame, max_kbps) = if mpeg1 { (1152, 320) } else { (576, 160) };
+        let kbps = match &self.rate_mode {
+            Mp3RateMode::Cbr(bitrate) => bitrate.kbps(),
+            Mp3RateMode::Abr(kbps) => *kbps,
+            Mp3RateMode::Vbr(quality) => mp3::vbr_average_kbps(*quality),
+        }
+        .min(max_kbps);
+
+        // 编码器延迟 576 个采样帧，另加开头的 Xing/LAME 标签帧
+        let mp3_frames = (frames + 576).div_ceil(samples_per_frame) + 1;
+        mp3_frames * samples_per_frame * kbps as u64 * 1000 / 8 / sample_rate as u64
+    }
+}
+
+// ==================== 音频处理函数 ====================
+
+/// 读取原始 PCM 文件并按配置解码为交错的 f32 样本
+fn read_pcm_samples(input_path: &Path, config: &AudioConfig) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    let pcm_data = input::read_file(input_path)?;
+    if config.endianness == Endianness::Big {
+        return Ok(sample::decode_samples_f32(&sample::to_little_endian(&pcm_data, config.sample_format), config.sample_format));
+    }
+    Ok(sample::decode_samples_f32(&pcm_data, config.sample_format))
+}
+
+/// 将 f32 样本按配置的样本格式和字节序编码后写入原始 PCM 文件
+fn write_pcm_samples(output_path: &Path, samples: &[f32], config: &AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let mut pcm_data = sample::encode_samples_f32(samples, config.sample_format);
+    if config.endianness == Endianness::Big {
+        sample::swap_sample_bytes(&mut pcm_data, config.sample_format);
+    }
+    atomic::write_atomic(output_path, &pcm_data)?;
     Ok(())
 }
 
+/// 是否为 WAV 文件（按扩展名判断，不区分大小写）
+fn is_wav_file(file_path: &Path) -> bool {
+    file_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("wav"))
+}
+
+/// 读取 WAV 或原始 PCM 文件为交错的 f32 样本，PCM 文件的参数从文件名推断
+fn load_audio_file(input_path: &Path) -> Result<(AudioConfig, Vec<f32>), Box<dyn std::error::Error>> {
+    let filename = input_path.file_name().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy();
+    load_audio_file_with_config(input_path, infer_audio_config_from_filename(&filename))
+}
+
+/// 读取 WAV 或原始 PCM 文件为交错的 f32 样本，PCM 文件使用给定的参数，WAV 文件以文件头为准
+fn load_audio_file_with_config(input_path: &Path, config: AudioConfig) -> Result<(AudioConfig, Vec<f32>), Box<dyn std::error::Error>> {
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+    if is_wav_file(input_path) {
+        let audio = read_wav_file(input_path)?;
+        let samples = audio.samples_f32();
+        return Ok((audio.config, samples));
+    }
+    config.validate()?;
+    let samples = read_pcm_samples(input_path, &config)?;
+    Ok((config, samples))
+}
+
+/// 按输出文件扩展名将样本写为 WAV 或原始 PCM 文件
+fn save_audio_file(output_path: &Path, samples: &[f32], config: &AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    if !is_wav_file(output_path) {
+        return write_pcm_samples(output_path, samples, config);
+    }
+    let data = sample::encode_samples_f32(samples, config.sample_format);
+    let header = wav::WavHeader {
+        sample_rate: config.sample_rate,
+        channels: config.channels,
+        bits_per_sample: config.sample_format.bits_per_sample(),
+        data_size: data.len() as u64,
+        container: WavContainer::auto_for(data.len() as u64),
+        force_extensible: false,
+        sample_format: config.sample_format,
+        adpcm: None,
+        channel_mask: None,
+    };
+    let mut writer = BufWriter::new(AtomicFile::create(output_path)?);
+    wav::write_wav_header(&mut writer, &header)?;
+    writer.write_all(&data)?;
+    writer.write_all(&vec![0u8; header.padding() as usize])?;
+    writer.into_inner().map_err(|e| e.into_error())?.commit()?;
+    Ok(())
+}
+
+/// 调整 PCM 文件的增益
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出 PCM 文件路径（与输入使用相同的样本格式）
+/// * `gain_db` - 增益（dB），正值放大、负值衰减
+/// * `config` - 输入 PCM 的音频参数
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - 处理结果，超出范围的样本会被削波
+pub fn apply_gain_db(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, gain_db: f32, config: AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    config.validate()?;
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    let mut samples = read_pcm_samples(input_path, &config)?;
+    let clipped = dsp::apply_gain(&mut samples, gain_db);
+    if clipped > 0 {
+        log::warn!("Gain of {:+.1} dB clipped {} samples in {}", gain_db, clipped, input_path.display());
+    }
+    write_pcm_samples(output_path, &samples, &config)?;
+
+    log_conversion_done("Gain", input_path, output_path, (samples.len() * config.sample_format.bytes_per_sample()) as u64, started);
+    Ok(())
+}
+
+/// 倒放 PCM 文件：按帧反转顺序，每帧内的声道交错顺序保持不变
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出 PCM 文件路径（与输入使用相同的样本格式和字节序）
+/// * `config` - 输入 PCM 的音频参数
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - 处理结果，末尾不足一帧的字节会被丢弃
+pub fn reverse_audio(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, config: AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    config.validate()?;
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    let frame_size = config.sample_format.bytes_per_sample() * config.channels as usize;
+    // 直接按字节帧反转，无需解码，任意样本格式都能无损处理
+    let pcm_data = input::read_file(input_path)?;
+    let reversed: Vec<u8> = pcm_data.chunks_exact(frame_size).rev().flatten().copied().collect();
+    atomic::write_atomic(output_path, &reversed)?;
+
+    log_conversion_done("Reverse", input_path, output_path, pcm_data.len() as u64, started);
+    Ok(())
+}
+
+/// 变速不变调（如为有声书生成 0.75× / 1.25× 的播放速度版本）
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV，或从文件名推断参数的 PCM）
+/// * `output_path` - 输出文件路径，扩展名为 .wav 时写入 WAV，否则写入原始 PCM
+/// * `factor` - 速度倍率，大于 1 加快、小于 1 放慢，输出时长约为原时长 / `factor`；不能小于 1/16
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - 处理结果
+pub fn change_tempo(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, factor: f32) -> Result<(), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+    dsp::check_tempo_factor(factor)?;
+
+    let (config, samples) = load_audio_file(input_path)?;
+    let stretched = dsp::time_stretch(&samples, config.channels, config.sample_rate, factor)?;
+    log::debug!("Tempo x{}: {} -> {} samples", factor, samples.len(), stretched.len());
+    save_audio_file(output_path, &stretched, &config)?;
+
+    log_conversion_done("Tempo", input_path, output_path, (samples.len() * config.sample_format.bytes_per_sample()) as u64, started);
+    Ok(())
+}
+
+/// 去除音频文件首尾的静音
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV，或从文件名推断参数的 PCM）
+/// * `output_path` - 输出文件路径，扩展名为 .wav 时写入 WAV，否则写入原始 PCM
+/// * `threshold_dbfs` - 静音阈值（dBFS，如 -50.0），峰值低于该值视为静音
+/// * `min_duration_ms` - 首尾静音至少持续该时长才会被去除
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - 处理结果，全部为静音时输出空音频
+pub fn trim_silence(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, threshold_dbfs: f32, min_duration_ms: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+
+    let (config, samples) = load_audio_file(input_path)?;
+    let channels = config.channels.max(1) as usize;
+    let range = silence::find_trim_range(&samples, config.channels, config.sample_rate, threshold_dbfs, min_duration_ms);
+    let frames = samples.len() / channels;
+    log::info!(
+        "Trimming {} leading and {} trailing frames of silence from {}",
+        range.start, frames - range.end, input_path.display()
+    );
+    save_audio_file(output_path, &samples[range.start * channels..range.end * channels], &config)?;
+
+    log_conversion_done("Trim", input_path, output_path, (samples.len() * config.sample_format.bytes_per_sample()) as u64, started);
+    Ok(())
+}
+
+/// 检测音频文件中的静音区间
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV，或从文件名推断参数的 PCM）
+/// * `threshold_dbfs` - 静音阈值（dBFS，如 -50.0），峰值低于该值视为静音
+/// * `min_duration_ms` - 只返回至少持续该时长的静音区间
+/// # Returns
+/// * `Result<Vec<SilenceRegion>, Box<dyn std::error::Error>>` - 按时间顺序排列的静音区间
+pub fn detect_silence(input_path: impl AsRef<Path>, threshold_dbfs: f32, min_duration_ms: u32) -> Result<Vec<SilenceRegion>, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let (config, samples) = load_audio_file(input_path)?;
+    let regions = silence::find_silence_regions(&samples, config.channels, config.sample_rate, threshold_dbfs, min_duration_ms);
+    log::debug!("Detected {} silence regions in {}", regions.len(), input_path.display());
+    Ok(regions)
+}
+
+/// 测量音频文件每个声道的直流偏移
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV，或从文件名推断参数的 PCM）
+/// # Returns
+/// * `Result<Vec<f32>, Box<dyn std::error::Error>>` - 每个声道的偏移量（满量程为 1.0，如 0.01 约为 -40dBFS）
+pub fn detect_dc_offset(input_path: impl AsRef<Path>) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let (config, samples) = load_audio_file(input_path)?;
+    let offsets = dsp::measure_dc_offset(&samples, config.channels);
+    log::debug!("Measured DC offset {:?} in {}", offsets, input_path.display());
+    Ok(offsets)
+}
+
+/// 在静音处将长录音切分为多个文件
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV，或从文件名推断参数的 PCM）
+/// * `output_dir` - 输出目录，不存在时自动创建
+/// * `options` - 切分参数
+/// # Returns
+/// * `Result<Vec<PathBuf>, Box<dyn std::error::Error>>` - 按顺序生成的文件（`原文件名_001.扩展名`，格式与输入相同）
+pub fn split_at_silence(input_path: impl AsRef<Path>, output_dir: impl AsRef<Path>, options: &SplitOptions) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_dir = output_dir.as_ref();
+
+    let (config, samples) = load_audio_file(input_path)?;
+    let channels = config.channels.max(1) as usize;
+    let ranges = silence::find_split_ranges(&samples, config.channels, config.sample_rate, options);
+
+    std::fs::create_dir_all(output_dir)?;
+    let stem = input_path.file_stem().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy();
+    let extension = input_path.extension().map(|ext| ext.to_string_lossy()).unwrap_or_default();
+
+    let mut outputs = Vec::with_capacity(ranges.len());
+    for (index, range) in ranges.iter().enumerate() {
+        let output_path = output_dir.join(format!("{}_{:03}.{}", stem, index + 1, extension));
+        save_audio_file(&output_path, &samples[range.start * channels..range.end * channels], &config)?;
+        outputs.push(output_path);
+    }
+
+    log::info!(
+        "Split {} into {} segments in {:.3}s",
+        input_path.display(), outputs.len(), started.elapsed().as_secs_f64()
+    );
+    Ok(outputs)
+}
+
+/// 将多声道音频按声道拆分为单声道文件（如把立体声通话录音拆成坐席和客户两条音轨分别转写）
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV 或原始 PCM）
+/// * `config` - 原始 PCM 的音频参数，WAV 文件以文件头为准
+/// * `output_template` - 输出路径模板，`{channel}` 替换为从 1 开始的声道号（如 `call_ch{channel}.wav`），
+///   扩展名决定输出格式（WAV / MP3 / PCM），样本格式与输入相同
+/// # Returns
+/// * `Result<Vec<PathBuf>, Box<dyn std::error::Error>>` - 按声道顺序生成的文件
+pub fn split_channels(input_path: impl AsRef<Path>, config: AudioConfig, output_template: &str) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    if !output_template.contains("{channel}") {
+        return Err(AudioError::InvalidInput("Output template must contain {channel}".into()).into());
+    }
+
+    let (config, samples) = load_audio_file_with_config(input_path, config)?;
+    let channels = config.channels.max(1) as usize;
+    let output_format = convert::output_format(Path::new(output_template))?;
+    let mono_config = AudioConfig::new(config.sample_rate, 1, config.bits_per_sample).with_sample_format(config.sample_format).with_endianness(config.endianness);
+
+    let mut outputs = Vec::with_capacity(channels);
+    for channel in 0..channels {
+        let output_path = PathBuf::from(output_template.replace("{channel}", &(channel + 1).to_string()));
+        let track: Vec<f32> = samples.iter().skip(channel).step_by(channels).copied().collect();
+        if output_format == AudioFormat::Mp3 {
+            let mp3_config = audio_config_to_mp3_config(&mono_config, mp3::default_bitrate(config.sample_rate), AudioQuality::High);
+            let track: Vec<i16> = track.iter().map(|&s| sample::f32_to_i16(s)).collect();
+            let (mp3_data, _, _) = encode_samples_to_mp3(&track, mp3_config, &mut ConvertOptions::default())?;
+            atomic::write_atomic(&output_path, &mp3_data)?;
+        } else {
+            save_audio_file(&output_path, &track, &mono_config)?;
+        }
+        outputs.push(output_path);
+    }
+
+    log::info!(
+        "Split {} into {} channel tracks in {:.3}s",
+        input_path.display(), outputs.len(), started.elapsed().as_secs_f64()
+    );
+    Ok(outputs)
+}
+
+/// 将两个音频文件混音（如在旁白下叠加背景音乐）
+/// # Arguments
+/// * `input_a` - 音轨 A（WAV，或从文件名推断参数的 PCM），输出沿用其参数
+/// * `input_b` - 音轨 B，声道数和采样率与 A 不同时会自动转换
+/// * `output_path` - 输出文件路径，扩展名为 .wav 时写入 WAV，否则写入原始 PCM
+/// * `options` - 各音轨增益和对齐方式
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - 处理结果，超出范围的样本会被削波
+pub fn mix_files(input_a: impl AsRef<Path>, input_b: impl AsRef<Path>, output_path: impl AsRef<Path>, options: &MixOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let input_a = input_a.as_ref();
+    let input_b = input_b.as_ref();
+    let output_path = output_path.as_ref();
+
+    let (config, samples_a) = load_audio_file(input_a)?;
+    let (config_b, samples_b) = load_audio_file(input_b)?;
+
+    // 将音轨 B 转换为音轨 A 的声道数和采样率
+    let samples_b = dsp::convert_channels(&samples_b, config_b.channels, config.channels)?;
+    let samples_b = dsp::resample_f32(&samples_b, config.channels, config_b.sample_rate, config.sample_rate);
+
+    let mixed = dsp::mix_samples(&samples_a, &samples_b, config.channels, config.sample_rate, options);
+    save_audio_file(output_path, &mixed, &config)?;
+
+    log_conversion_done("Mix", input_a, output_path, (mixed.len() * config.sample_format.bytes_per_sample()) as u64, started);
+    Ok(())
+}
+
+/// 以交叉淡化方式拼接多个音频片段
+/// # Arguments
+/// * `inputs` - 按顺序拼接的文件（WAV，或从文件名推断参数的 PCM），输出沿用第一个文件的参数
+/// * `output_path` - 输出文件路径，扩展名为 .wav 时写入 WAV，否则写入原始 PCM
+/// * `crossfade_ms` - 相邻片段的重叠时长（毫秒），为 0 时直接拼接
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - 处理结果
+pub fn crossfade_concat<P: AsRef<Path>>(inputs: &[P], output_path: impl AsRef<Path>, crossfade_ms: u32) -> Result<(), Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let output_path = output_path.as_ref();
+    let (first, rest) = inputs.split_first().ok_or_else(|| AudioError::InvalidInput("No input files to concatenate".into()))?;
+
+    let (config, mut output) = load_audio_file(first.as_ref())?;
+    for input in rest {
+        let (clip_config, clip) = load_audio_file(input.as_ref())?;
+        let clip = dsp::convert_channels(&clip, clip_config.channels, config.channels)?;
+        let clip = dsp::resample_f32(&clip, config.channels, clip_config.sample_rate, config.sample_rate);
+        dsp::crossfade_append(&mut output, &clip, config.channels, config.sample_rate, crossfade_ms);
+    }
+    save_audio_file(output_path, &output, &config)?;
+
+    log_conversion_done("Crossfade concat", first.as_ref(), output_path, (output.len() * config.sample_format.bytes_per_sample()) as u64, started);
+    Ok(())
+}
+
+/// 按帧拼接多个 MP3 文件，不重新编码，用于合并分段录音
+///
+/// 保留第一个文件的 ID3 标签，去掉其余文件的 ID3 标签和各自的 Xing/Info 标签帧；第一个文件带有 Xing/LAME 标签时，
+/// 按合并结果重写帧数、字节数和 TOC，播放器能得到准确的时长和拖动位置。第一个文件没有标签、但其他文件带有标签或合并结果为 VBR 时，
+/// 按合并结果构造 Xing/Info 标签帧。各片段自身的编码器延迟和填充仍保留在中间
+/// # Arguments
+/// * `inputs` - 按顺序拼接的 MP3 文件
+/// * `output_path` - 输出 MP3 文件路径
+/// * `check_params` - 是否要求所有文件的采样率和声道数一致，不一致时报错
+/// # Returns
+/// * `Result<Mp3Info, Box<dyn std::error::Error>>` - 合并后的码流信息
+pub fn concat_mp3<P: AsRef<Path>>(inputs: &[P], output_path: impl AsRef<Path>, check_params: bool) -> Result<Mp3Info, Box<dyn std::error::Error>> {
+    let started = Instant::now();
+    let output_path = output_path.as_ref();
+    let first = inputs.first().ok_or_else(|| AudioError::InvalidInput("No input files to concatenate".into()))?;
+
+    let mut streams = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        let input = input.as_ref();
+        if !input.exists() {
+            return Err(input_not_found());
+        }
+        streams.push((input.display().to_string(), std::fs::read(input)?));
+    }
+    let merged = mp3::concat_streams(&streams, check_params)?;
+    atomic::write_atomic(output_path, &merged)?;
+
+    let info = parse_mp3_info(&merged)?;
+    log::debug!("Concatenated {} MP3 files: {} frames, {:?}", inputs.len(), info.frame_count, info.duration);
+    log_conversion_done("MP3 concat", first.as_ref(), output_path, streams.iter().map(|(_, data)| data.len() as u64).sum(), started);
+    Ok(info)
+}
+
+/// 截取原始 PCM 文件中的一段时间范围，按样本精确定位字节偏移，只读取该区间的数据
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出文件路径，扩展名为 .wav / .mp3 时分别写入 WAV / MP3（192kbps），否则写入原始 PCM
+/// * `start_ms` - 起始时间（毫秒）
+/// * `end_ms` - 结束时间（毫秒，不含），超出文件长度时截止到文件末尾
+/// * `config` - 输入 PCM 的音频配置，其中的声道转换、增益和淡入淡出设置会作用于截取的片段
+/// # Returns
+/// * `Result<(), Box<dyn std::error::Error>>` - 处理结果
+pub fn extract_range(input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, start_ms: u64, end_ms: u64, config: AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    use std::io::{Seek, SeekFrom};
+
+    config.validate()?;
+    let started = Instant::now();
+    let input_path = input_path.as_ref();
+    let output_path = output_path.as_ref();
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+    if end_ms <= start_ms {
+        return Err(AudioError::InvalidInput("End time must be greater than start time".into()).into());
+    }
+
+    // 按帧计算字节偏移，保证不会截断在样本或声道中间
+    let frame_size = (config.sample_format.bytes_per_sample() * config.channels as usize) as u64;
+    let mut input_file = File::open(input_path)?;
+    let total_frames = input_file.metadata()?.len() / frame_size;
+    // 时间过大导致乘法溢出时，起点必然超出文件，终点截止到文件末尾
+    let start_frame = start_ms
+        .checked_mul(config.sample_rate as u64)
+        .map(|n| n / 1000)
+        .ok_or_else(|| AudioError::InvalidInput("Requested range is outside the input file".into()))?;
+    let end_frame = end_ms.checked_mul(config.sample_rate as u64).map_or(total_frames, |n| (n / 1000).min(total_frames));
+    if start_frame >= end_frame {
+        return Err(AudioError::InvalidInput("Requested range is outside the input file".into()).into());
+    }
+    let data_size = (end_frame - start_frame) * frame_size;
+    log::debug!("Extracting frames {}..{} of {} from {}", start_frame, end_frame, total_frames, input_path.display());
+
+    input_file.seek(SeekFrom::Start(start_frame * frame_size))?;
+    let mut region = input_file.take(data_size);
+    let extension = output_path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase);
+    match extension.as_deref() {
+        Some("wav") => {
+            let mut writer = BufWriter::new(AtomicFile::create(output_path)?);
+            write_pcm_as_wav(&mut region, data_size, &audio_config_to_wav_config(&config), &mut writer, &mut ConvertOptions::default())?;
+            writer.into_inner().map_err(|e| e.into_error())?.commit()?;
+        }
+        Some("mp3") => {
+            let mut pcm_data = Vec::with_capacity(data_size as usize);
+            region.read_to_end(&mut pcm_data)?;
+            let mp3_config = audio_config_to_mp3_config(&config, mp3::default_bitrate(config.sample_rate), AudioQuality::High);
+            let (mp3_data, _, _) = encode_pcm_to_mp3(&pcm_data, mp3_config, &mut ConvertOptions::default())?;
+            atomic::write_atomic(output_path, &mp3_data)?;
+        }
+        _ => {
+            let processing = config.processing();
+            if processing.is_identity(config.channels) {
+                let mut writer = BufWriter::new(AtomicFile::create(output_path)?);
+                std::io::copy(&mut region, &mut writer)?;
+                writer.into_inner().map_err(|e| e.into_error())?.commit()?;
+            } else {
+                let mut pcm_data = Vec::with_capacity(data_size as usize);
+                region.read_to_end(&mut pcm_data)?;
+                if config.endianness == Endianness::Big {
+                    sample::swap_sample_bytes(&mut pcm_data, config.sample_format);
+                }
+                let samples = sample::decode_samples_f32(&pcm_data, config.sample_format);
+                let (samples, _) = processing.apply(samples, config.channels, config.sample_rate)?;
+                write_pcm_samples(output_path, &samples, &config)?;
+            }
+        }
+    }
+
+    log_conversion_done("Extract range", input_path, output_path, data_size, started);
+    Ok(())
+}
+
+// ==================== 音频分析函数 ====================
+
+/// 分析音频文件的峰值和均方根电平（可用于电平表显示或拒绝音量过低的上传）
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV 或原始 PCM）
+/// * `config` - 原始 PCM 的音频参数，WAV 文件以文件头为准
+/// # Returns
+/// * `Result<LevelReport, Box<dyn std::error::Error>>` - 整体及每个声道的电平（dBFS）
+pub fn analyze_levels(input_path: impl AsRef<Path>, config: AudioConfig) -> Result<LevelReport, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let (config, samples) = load_audio_file_with_config(input_path, config)?;
+    let report = analysis::measure_levels(&samples, config.channels);
+    log::debug!("Levels of {}: peak {:.1} dBFS, RMS {:.1} dBFS", input_path.display(), report.peak_dbfs, report.rms_dbfs);
+    Ok(report)
+}
+
+/// 计算音频文件的频谱图，便于直观检查转换结果（启用 `image` 特性后可通过 `Spectrogram::save_png` 导出图片）
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV 或原始 PCM）
+/// * `config` - 原始 PCM 的音频参数，WAV 文件以文件头为准
+/// * `options` - FFT 长度和帧移
+/// # Returns
+/// * `Result<Spectrogram, Box<dyn std::error::Error>>` - 按时间排列的各频点幅度
+pub fn compute_spectrogram(input_path: impl AsRef<Path>, config: AudioConfig, options: SpectrogramOptions) -> Result<Spectrogram, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let (config, samples) = load_audio_file_with_config(input_path, config)?;
+    let result = analysis::spectrogram(&samples, config.channels, config.sample_rate, &options)?;
+    log::debug!("Spectrogram of {}: {} frames x {} bins", input_path.display(), result.magnitudes.len(), options.fft_size / 2 + 1);
+    Ok(result)
+}
+
+/// 检测音频文件的速度（BPM），可用于为音乐库标记节拍速度
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV 或原始 PCM）
+/// * `config` - 原始 PCM 的音频参数，WAV 文件以文件头为准
+/// # Returns
+/// * `Result<f32, Box<dyn std::error::Error>>` - 60 - 200 之间的 BPM；音频过短或没有明显节拍时返回错误
+pub fn detect_bpm(input_path: impl AsRef<Path>, config: AudioConfig) -> Result<f32, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let (config, samples) = load_audio_file_with_config(input_path, config)?;
+    let bpm = analysis::estimate_bpm(&samples, config.channels, config.sample_rate)
+        .ok_or_else(|| AudioError::InvalidInput(format!("No tempo detected in {} (audio too short or without clear beats)", input_path.display())))?;
+    log::debug!("Tempo of {}: {:.1} BPM", input_path.display(), bpm);
+    Ok(bpm)
+}
+
+/// 计算音频文件的声学指纹，配合 `AudioFingerprint::similarity` 查找格式、码率或音量不同的重复录音
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV、MP3 或从文件名推断参数的 PCM；MP3 需要 `mp3-decode` 特性）
+/// # Returns
+/// * `Result<AudioFingerprint, Box<dyn std::error::Error>>` - 按时间排列的子指纹
+pub fn fingerprint(input_path: impl AsRef<Path>) -> Result<AudioFingerprint, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+    let format = probe::probe_file(input_path)?;
+    let (mut decoder, _) = convert::open_decoder(input_path, format, None)?;
+    let config = decoder.config();
+    let mut builder = fingerprint::FingerprintBuilder::new(config.channels, config.sample_rate);
+    let mut samples = Vec::new();
+    while decoder.decode(&mut samples)? > 0 {
+        builder.push(&samples.iter().map(|&s| s as f32 / 32768.0).collect::<Vec<_>>());
+        samples.clear();
+    }
+    let result = builder.finish();
+    log::debug!("Fingerprint of {}: {} values over {} ms", input_path.display(), result.values.len(), result.duration_ms);
+    Ok(result)
+}
+
+/// 解码两个音频文件并逐样本比较（可用于验证经过本库的往返转换是否无损）
+/// # Arguments
+/// * `path_a` - 文件 A（WAV，或从文件名推断参数的 PCM）
+/// * `path_b` - 文件 B，采样率和声道数必须与 A 相同
+/// # Returns
+/// * `Result<ComparisonReport, Box<dyn std::error::Error>>` - 是否完全一致、最大差值、PSNR 和长度差
+pub fn compare_audio(path_a: impl AsRef<Path>, path_b: impl AsRef<Path>) -> Result<ComparisonReport, Box<dyn std::error::Error>> {
+    let (config_a, samples_a) = load_audio_file(path_a.as_ref())?;
+    let (config_b, samples_b) = load_audio_file(path_b.as_ref())?;
+    if config_a.sample_rate != config_b.sample_rate || config_a.channels != config_b.channels {
+        return Err(AudioError::InvalidInput(format!(
+            "Cannot compare {}Hz/{}ch with {}Hz/{}ch audio",
+            config_a.sample_rate, config_a.channels, config_b.sample_rate, config_b.channels
+        )).into());
+    }
+
+    let report = analysis::compare_samples(&samples_a, &samples_b, config_a.channels);
+    log::debug!("Compared {} with {}: {:?}", path_a.as_ref().display(), path_b.as_ref().display(), report);
+    Ok(report)
+}
+
+/// 根据文件内容的特征字节识别音频格式（不依赖扩展名）
+/// # Arguments
+/// * `input_path` - 输入文件路径
+/// # Returns
+/// * `Result<DetectedFormat, Box<dyn std::error::Error>>` - 识别出的格式，没有可识别的文件头时为 `Unknown`
+pub fn probe_format(input_path: impl AsRef<Path>) -> Result<DetectedFormat, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+    let detected = probe::probe_file(input_path)?;
+    log::debug!("Probed {}: {:?}", input_path.display(), detected);
+    Ok(detected)
+}
+
+/// 逐帧遍历 MP3 文件，读取码率类型、帧数、编码器延迟和填充、Xing 标签以及精确时长
+/// # Arguments
+/// * `input_path` - MP3 文件路径
+/// # Returns
+/// * `Result<Mp3Info, Box<dyn std::error::Error>>` - 码流信息
+pub fn probe_mp3(input_path: impl AsRef<Path>) -> Result<Mp3Info, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+    let info = mp3::parse_mp3_info(&std::fs::read(input_path)?)?;
+    log::debug!("Probed MP3 {}: {:?}", input_path.display(), info);
+    Ok(info)
+}
+
+/// 读取音频文件信息：格式、音频参数、时长、数据大小和 WAV 块布局，原始 PCM 的参数从文件名推断
+/// # Arguments
+/// * `input_path` - 输入文件路径，格式按内容识别，没有可识别文件头的文件按原始 PCM 处理
+/// # Returns
+/// * `Result<AudioInfo, Box<dyn std::error::Error>>` - 文件信息
+pub fn probe_audio(input_path: impl AsRef<Path>) -> Result<AudioInfo, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let filename = input_path.file_name().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy();
+    probe_audio_with_config(input_path, &infer_audio_config_from_filename(&filename))
+}
+
+/// 读取音频文件信息，原始 PCM 使用给定的参数
+/// # Arguments
+/// * `input_path` - 输入文件路径
+/// * `config` - 原始 PCM 的音频参数，带文件头的格式忽略该参数
+/// # Returns
+/// * `Result<AudioInfo, Box<dyn std::error::Error>>` - 文件信息
+pub fn probe_audio_with_config(input_path: impl AsRef<Path>, config: &AudioConfig) -> Result<AudioInfo, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+    let info = probe::probe_audio_file(input_path, config)?;
+    log::debug!("Probed {}: {:?}", input_path.display(), info);
+    Ok(info)
+}
+
+/// 计算音频文件解码后样本的 MD5，原始 PCM 的参数从文件名推断
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV 或原始 PCM）
+/// # Returns
+/// * `Result<[u8; 16], Box<dyn std::error::Error>>` - 样本数据的 MD5，详见 `audio_md5_with_config`
+pub fn audio_md5(input_path: impl AsRef<Path>) -> Result<[u8; 16], Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let filename = input_path.file_name().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy();
+    audio_md5_with_config(input_path, &infer_audio_config_from_filename(&filename))
+}
+
+/// 计算音频文件解码后样本的 MD5，原始 PCM 使用给定的参数
+///
+/// 摘要按 FLAC 文件头中 MD5 的规则计算：交错样本依次以小端有符号整数参与计算（8 位为 1 字节，24 位为 3 字节），
+/// 与容器、文件头和字节序无关，同一段音频保存为 PCM 或 WAV 得到相同的结果，可用于归档时校验无损转换。
+/// 32 位浮点样本按小端 IEEE 字节计算，G.711 和 IMA ADPCM 按解码后的 16 位样本计算
+/// # Arguments
+/// * `input_path` - 输入文件路径（WAV 或原始 PCM）
+/// * `config` - 原始 PCM 的样本格式和字节序，WAV 文件以文件头为准
+/// # Returns
+/// * `Result<[u8; 16], Box<dyn std::error::Error>>` - 样本数据的 MD5
+pub fn audio_md5_with_config(input_path: impl AsRef<Path>, config: &AudioConfig) -> Result<[u8; 16], Box<dyn std::error::Error>> {
+    use md5::{Digest, Md5};
+
+    let input_path = input_path.as_ref();
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+    let digest: [u8; 16] = if is_wav_file(input_path) {
+        let audio = read_wav_file(input_path)?;
+        Md5::digest(sample::canonical_bytes(&audio.data, audio.config.sample_format)).into()
+    } else {
+        config.validate()?;
+        let data = input::read_file(input_path)?;
+        let data = if config.endianness == Endianness::Big {
+            Cow::Owned(sample::to_little_endian(&data, config.sample_format))
+        } else {
+            Cow::Borrowed(&data[..])
+        };
+        Md5::digest(sample::canonical_bytes(&data, config.sample_format)).into()
+    };
+    log::debug!("Audio MD5 of {}: {}", input_path.display(), digest.iter().map(|b| format!("{:02x}", b)).collect::<String>());
+    Ok(digest)
+}
+
+/// 计算音频文件的时长，原始 PCM 的参数从文件名推断
+/// # Arguments
+/// * `input_path` - 输入文件路径：WAV 从文件头读取，MP3 逐帧遍历，其余按原始 PCM 处理
+/// # Returns
+/// * `Result<Duration, Box<dyn std::error::Error>>` - 音频时长
+pub fn audio_duration(input_path: impl AsRef<Path>) -> Result<Duration, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let filename = input_path.file_name().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy();
+    audio_duration_with_config(input_path, &infer_audio_config_from_filename(&filename))
+}
+
+/// 计算音频文件的时长，原始 PCM 使用给定的参数
+/// # Arguments
+/// * `input_path` - 输入文件路径：WAV 从文件头读取，MP3 逐帧遍历，其余按原始 PCM 处理
+/// * `config` - 原始 PCM 的音频参数，WAV 和 MP3 文件忽略该参数
+/// # Returns
+/// * `Result<Duration, Box<dyn std::error::Error>>` - 音频时长
+pub fn audio_duration_with_config(input_path: impl AsRef<Path>, config: &AudioConfig) -> Result<Duration, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    if !input_path.exists() {
+        return Err(input_not_found());
+    }
+
+    let extension = input_path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase);
+    let (frames, sample_rate) = match extension.as_deref() {
+        Some("wav") => {
+            let mut file = File::open(input_path)?;
+            let len = file.metadata()?.len();
+            let info = wav::read_wav_info(&mut file, len, WavParseMode::Lenient)?;
+            (info.frames(), info.sample_rate())
+        }
+        Some("mp3") => return mp3::mp3_duration(&std::fs::read(input_path)?),
+        _ => {
+            let frame_size = (config.sample_format.bytes_per_sample() * config.channels as usize) as u64;
+            if frame_size == 0 {
+                return Err(AudioError::InvalidConfig("Invalid channel count".into()).into());
+            }
+            (std::fs::metadata(input_path)?.len() / frame_size, config.sample_rate)
+        }
+    };
+    if sample_rate == 0 {
+        return Err(AudioError::InvalidConfig("Invalid sample rate".into()).into());
+    }
+    Ok(Duration::from_secs_f64(frames as f64 / sample_rate as f64))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,282 @@
+// 实时麦克风 → MP3：采集到的数据立即分块编码，按完整帧交给回调，用于语音通话和直播推流
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use cpal::traits::StreamTrait;
+
+use crate::capture::InputDevice;
+use crate::{dsp, frames_duration, mp3, sample, AudioError, AudioQuality, Mp3Bitrate, Mp3Config, Mp3RateMode};
+
+/// 实时 MP3 编码选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveMp3Options {
+    /// 码率模式，默认 64kbps CBR（适合语音）
+    pub rate_mode: Mp3RateMode,
+    pub quality: AudioQuality,
+    /// 编码的声道数，为 None 时保持设备声道数（多于两个声道时混缩为单声道）
+    pub target_channels: Option<u8>,
+    /// 等待编码的音频最长时长，超出时丢弃新采集的数据而不是继续积压
+    pub max_latency: Duration,
+}
+
+impl Default for LiveMp3Options {
+    fn default() -> Self {
+        LiveMp3Options {
+            rate_mode: Mp3RateMode::Cbr(Mp3Bitrate::Kbps64),
+            quality: AudioQuality::Medium,
+            target_channels: None,
+            max_latency: Duration::from_millis(200),
+        }
+    }
+}
+
+impl LiveMp3Options {
+    /// 设置码率模式
+    pub fn with_rate_mode(mut self, rate_mode: Mp3RateMode) -> Self {
+        self.rate_mode = rate_mode;
+        self
+    }
+
+    /// 设置编码质量
+    pub fn with_quality(mut self, quality: AudioQuality) -> Self {
+        self.quality = quality;
+        self
+    }
+
+    /// 设置编码的声道数
+    pub fn with_target_channels(mut self, target_channels: u8) -> Self {
+        self.target_channels = Some(target_channels);
+        self
+    }
+
+    /// 设置等待编码的音频最长时长
+    pub fn with_max_latency(mut self, max_latency: Duration) -> Self {
+        self.max_latency = max_latency;
+        self
+    }
+}
+
+/// 实时编码结束后的统计
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct LiveMp3Stats {
+    /// 交给回调的 MP3 帧数
+    pub frames_delivered: u64,
+    /// 交给回调的字节数
+    pub bytes_delivered: u64,
+    /// 已编码的音频时长
+    pub duration: Duration,
+    /// 因超出延迟上限被丢弃的采样帧数
+    pub dropped_frames: u64,
+}
+
+/// 分块编码器：送入 f32 交错样本，每凑齐一个完整的 MP3 帧就交给回调
+pub(crate) struct LiveEncoder<F: FnMut(&[u8])> {
+    encoder: mp3::StreamEncoder,
+    config: Mp3Config,
+    input_channels: u8,
+    pending: Vec<u8>,
+    encoded_frames: u64,
+    stats: LiveMp3Stats,
+    on_frame: F,
+}
+
+impl<F: FnMut(&[u8])> LiveEncoder<F> {
+    /// 创建编码器，不写入 Xing/LAME 标签帧（实时输出无法回填码流开头）
+    pub fn new(sample_rate: u32, input_channels: u8, options: &LiveMp3Options, on_frame: F) -> Result<Self, Box<dyn std::error::Error>> {
+        if input_channels == 0 {
+            return Err(AudioError::UnsupportedFormat("Input device reports zero channels".into()).into());
+        }
+        if !mp3::is_mp3_sample_rate(sample_rate) {
+            return Err(AudioError::UnsupportedFormat(format!("Input device sample rate {}Hz is not supported by MP3", sample_rate)).into());
+        }
+        let channels = match options.target_channels {
+            Some(target) => target,
+            None if input_channels > 2 => 1,
+            None => input_channels,
+        };
+        let config = Mp3Config::new(sample_rate, channels, Mp3Bitrate::Kbps64, options.quality.clone())
+            .with_rate_mode(options.rate_mode.clone());
+        config.validate()?;
+
+        Ok(LiveEncoder {
+            encoder: mp3::StreamEncoder::new(&config, false)?,
+            config,
+            input_channels,
+            pending: Vec::new(),
+            encoded_frames: 0,
+            stats: LiveMp3Stats::default(),
+            on_frame,
+        })
+    }
+
+    /// 编码一块交错样本，交付其中已完成的帧
+    pub fn encode(&mut self, samples: &[f32]) -> Result<(), Box<dyn std::error::Error>> {
+        let converted = dsp::convert_channels(samples, self.input_channels, self.config.channels)?;
+        let converted: Vec<i16> = converted.into_iter().map(sample::f32_to_i16).collect();
+        self.encoded_frames += (converted.len() / self.config.channels as usize) as u64;
+        self.encoder.encode(&converted, &mut self.pending)?;
+        self.deliver_frames();
+        Ok(())
+    }
+
+    /// 输出编码器中剩余的数据并返回统计
+    pub fn finish(mut self) -> Result<LiveMp3Stats, Box<dyn std::error::Error>> {
+        self.encoder.flush(&mut self.pending)?;
+        self.deliver_frames();
+        // 剩余的不完整数据原样交付，不丢弃
+        if !self.pending.is_empty() {
+            self.deliver(self.pending.len());
+        }
+        self.stats.duration = frames_duration(self.encoded_frames, self.config.sample_rate);
+        Ok(self.stats)
+    }
+
+    /// 按帧头中的帧长切分缓冲区，逐帧交付
+    fn deliver_frames(&mut self) {
+        while let Some(header) = mp3::parse_frame_header(&self.pending) {
+            if header.frame_size == 0 || self.pending.len() < header.frame_size {
+                return;
+            }
+            self.deliver(header.frame_size);
+        }
+        // LAME 的输出总是从帧头开始，无法识别时整体交付，避免积压
+        if self.pending.len() >= 4 {
+            log::warn!("Unexpected data in MP3 stream, delivering {} bytes unsplit", self.pending.len());
+            self.deliver(self.pending.len());
+        }
+    }
+
+    fn deliver(&mut self, len: usize) {
+        (self.on_frame)(&self.pending[..len]);
+        self.stats.frames_delivered += 1;
+        self.stats.bytes_delivered += len as u64;
+        self.pending.drain(..len);
+    }
+}
+
+/// 正在进行的实时编码，调用 `stop` 结束
+pub struct LiveMp3Capture {
+    stream: cpal::Stream,
+    worker: JoinHandle<Result<LiveMp3Stats, Box<dyn std::error::Error + Send + Sync>>>,
+    dropped: Arc<AtomicU64>,
+    config: Mp3Config,
+}
+
+impl LiveMp3Capture {
+    /// 实际使用的编码参数（采样率和声道数来自输入设备）
+    pub fn config(&self) -> &Mp3Config {
+        &self.config
+    }
+
+    /// 到目前为止因超出延迟上限被丢弃的采样帧数
+    pub fn dropped_frames(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// 停止采集，编码剩余数据后返回统计
+    pub fn stop(self) -> Result<LiveMp3Stats, Box<dyn std::error::Error>> {
+        // 关闭输入流后发送端随回调一起释放，编码线程处理完剩余数据后结束
+        drop(self.stream);
+        let stats = self.worker.join().map_err(|_| AudioError::Encoder("Live encoding thread panicked".into()))?;
+        let mut stats = stats.map_err(|e| e as Box<dyn std::error::Error>)?;
+        stats.dropped_frames = self.dropped.load(Ordering::Relaxed);
+        Ok(stats)
+    }
+}
+
+/// 从默认输入设备采集并实时编码为 MP3
+///
+/// 采集回调只负责转发数据，编码在独立线程中进行；每得到一个完整的 MP3 帧就调用
+/// `on_frame`（在编码线程中调用，应尽快返回）。等待编码的数据超过 `max_latency`
+/// 时丢弃新采集的数据，延迟不会无限增长。
+///
+/// # Arguments
+/// * `options` - 编码选项
+/// * `on_frame` - 接收 MP3 帧的回调
+///
+/// # Returns
+/// 实时编码句柄，调用 `stop` 结束
+pub fn start_live_mp3(options: LiveMp3Options, on_frame: impl FnMut(&[u8]) + Send + 'static) -> Result<LiveMp3Capture, Box<dyn std::error::Error>> {
+    let input = InputDevice::open_default()?;
+    let mut encoder = LiveEncoder::new(input.sample_rate, input.channels, &options, on_frame)?;
+    let config = encoder.config.clone();
+    log::info!("Live MP3 encoding {}Hz {}ch from default input device", input.sample_rate, input.channels);
+
+    // 排队中的采样帧数：回调入队时增加，编码线程处理后减少
+    let max_queued = (options.max_latency.as_secs_f64() * input.sample_rate as f64) as u64;
+    let queued = Arc::new(AtomicU64::new(0));
+    let dropped = Arc::new(AtomicU64::new(0));
+    let input_channels = input.channels as usize;
+
+    let (sender, receiver) = mpsc::channel::<Vec<f32>>();
+    let stream = {
+        let queued = Arc::clone(&queued);
+        let dropped = Arc::clone(&dropped);
+        input.build_stream(move |samples| {
+            let frames = (samples.len() / input_channels) as u64;
+            // 队列为空时总是接收，单次回调的数据超过上限也不会一直被丢弃
+            let pending = queued.load(Ordering::Acquire);
+            if pending > 0 && pending + frames > max_queued {
+                dropped.fetch_add(frames, Ordering::Relaxed);
+                return;
+            }
+            queued.fetch_add(frames, Ordering::AcqRel);
+            let _ = sender.send(samples);
+        })?
+    };
+
+    let worker = std::thread::spawn(move || {
+        let result = (|| {
+            for samples in receiver {
+                encoder.encode(&samples)?;
+                queued.fetch_sub((samples.len() / input_channels) as u64, Ordering::AcqRel);
+            }
+            encoder.finish()
+        })();
+        result.map_err(crate::error::into_send)
+    });
+    stream.play()?;
+
+    Ok(LiveMp3Capture { stream, worker, dropped, config })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_live_encoder_delivers_whole_frames() {
+        let frames = Arc::new(std::sync::Mutex::new(Vec::<Vec<u8>>::new()));
+        let sink = Arc::clone(&frames);
+        let mut encoder = LiveEncoder::new(48000, 2, &LiveMp3Options::default().with_target_channels(1), move |frame: &[u8]| {
+            sink.lock().unwrap().push(frame.to_vec());
+        })
+        .unwrap();
+
+        // 每次送入 10ms，100ms 内就应有帧输出
+        let samples: Vec<f32> = (0..48000 * 2).map(|i| ((i / 2) as f32 * 0.05).sin() * 0.5).collect();
+        for (index, chunk) in samples.chunks(960).enumerate() {
+            encoder.encode(chunk).unwrap();
+            if index == 10 {
+                assert!(!frames.lock().unwrap().is_empty(), "100ms 后仍没有输出");
+            }
+        }
+        let stats = encoder.finish().unwrap();
+        assert_eq!(stats.duration, Duration::from_secs(1));
+
+        let frames = frames.lock().unwrap();
+        assert_eq!(stats.frames_delivered, frames.len() as u64);
+        for frame in frames.iter() {
+            let header = mp3::parse_frame_header(frame).expect("每块数据都应以帧头开始");
+            assert_eq!(header.frame_size, frame.len());
+            assert_eq!(header.channels, 1);
+        }
+        // 没有 Xing/LAME 标签帧，每帧都是音频
+        let total: usize = frames.iter().map(Vec::len).sum();
+        assert_eq!(stats.bytes_delivered, total as u64);
+    }
+}
@@ -0,0 +1,116 @@
+// 批量转换清单：以 JSON 记录每个文件的输入输出、音频参数、时长、大小和校验和，便于下游系统审计
+
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::atomic::AtomicFile;
+use crate::batch::run_parallel;
+use crate::{audio_duration, AudioConfig, BatchItem, BatchReport};
+
+/// 计算文件的 SHA-256（小写十六进制）
+pub(crate) fn sha256_file(path: &Path) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut BufReader::new(File::open(path)?), &mut hasher)?;
+    Ok(hasher.finalize().iter().fold(String::with_capacity(64), |mut hex, byte| {
+        let _ = write!(hex, "{:02x}", byte);
+        hex
+    }))
+}
+
+/// 转义为 JSON 字符串字面量
+pub(crate) fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// 音频参数的 JSON 对象
+pub(crate) fn config_json(config: &AudioConfig) -> String {
+    format!(
+        "{{\"sample_rate\": {}, \"channels\": {}, \"bits_per_sample\": {}, \"sample_format\": \"{:?}\", \"endianness\": \"{:?}\"}}",
+        config.sample_rate, config.channels, config.bits_per_sample, config.sample_format, config.endianness
+    )
+}
+
+/// 单个文件的清单条目，输出文件的时长、大小和校验和在生成清单时读取
+fn item_json(item: &BatchItem) -> String {
+    let mut fields = vec![
+        format!("\"input\": {}", json_string(&item.input.to_string_lossy())),
+        format!("\"output\": {}", json_string(&item.output.to_string_lossy())),
+    ];
+    if let Ok(metadata) = std::fs::metadata(&item.input) {
+        fields.push(format!("\"input_size\": {}", metadata.len()));
+    }
+    match &item.result {
+        Ok(config) => {
+            fields.push("\"status\": \"ok\"".to_string());
+            if item.skipped {
+                fields.push("\"skipped\": true".to_string());
+            }
+            fields.push(format!("\"config\": {}", config_json(config)));
+            if let Ok(duration) = audio_duration(&item.output) {
+                fields.push(format!("\"duration_secs\": {:.6}", duration.as_secs_f64()));
+            }
+            if let Ok(metadata) = std::fs::metadata(&item.output) {
+                fields.push(format!("\"output_size\": {}", metadata.len()));
+            }
+            if let Ok(hash) = sha256_file(&item.output) {
+                fields.push(format!("\"sha256\": \"{}\"", hash));
+            }
+        }
+        Err(e) => {
+            fields.push("\"status\": \"error\"".to_string());
+            fields.push(format!("\"error\": {}", json_string(e)));
+        }
+    }
+    format!("    {{{}}}", fields.join(", "))
+}
+
+/// 将批量转换结果写为 JSON 清单
+pub(crate) fn write_manifest(report: &BatchReport, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let workers = std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1).min(report.items.len()).max(1);
+    let items = run_parallel(&report.items, workers, item_json);
+
+    let mut writer = BufWriter::new(AtomicFile::create(path)?);
+    writeln!(writer, "{{")?;
+    writeln!(writer, "  \"version\": 1,")?;
+    writeln!(writer, "  \"succeeded\": {},", report.succeeded())?;
+    writeln!(writer, "  \"failed\": {},", report.items.len() - report.succeeded())?;
+    writeln!(writer, "  \"items\": [")?;
+    writeln!(writer, "{}", items.join(",\n"))?;
+    writeln!(writer, "  ]")?;
+    writeln!(writer, "}}")?;
+    writer.into_inner().map_err(|e| e.into_error())?.commit()?;
+
+    log::info!("Batch manifest written to {} ({} items)", path.display(), report.items.len());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_string_escaping() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+        assert_eq!(json_string("声道\u{1}"), "\"声道\\u0001\"");
+    }
+}
@@ -0,0 +1,777 @@
+// MP3 编码辅助：直接访问 LAME 底层接口完成 mp3lame-encoder 未封装的功能
+
+// LAME 是 C 库，无法编译到 wasm32-unknown-unknown，wasm32 上创建编码器会返回 `AudioError::Encoder`
+#[cfg(not(target_arch = "wasm32"))]
+use mp3lame_encoder::{Builder, DualPcm, Encoder, FlushGap, FlushNoGap, Mode, MonoPcm, VbrMode};
+#[cfg(not(target_arch = "wasm32"))]
+use mp3lame_sys::lame_global_flags;
+#[cfg(not(target_arch = "wasm32"))]
+use std::mem::MaybeUninit;
+#[cfg(not(target_arch = "wasm32"))]
+use std::os::raw::c_int;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{AudioQuality, Mp3ChannelMode, Mp3RateMode};
+use crate::{parallel, wav, AudioError, Mp3Bitrate, Mp3Config, VbrQuality};
+
+/// MPEG-1/2/2.5 Layer III 支持的采样率
+pub const MP3_SAMPLE_RATES: [u32; 9] = [8000, 11025, 12000, 16000, 22050, 24000, 32000, 44100, 48000];
+
+/// Xing/LAME 标签帧的最大长度（最大比特率下的一帧）
+#[cfg(not(target_arch = "wasm32"))]
+const MAX_LAME_TAG_FRAME: usize = 2880;
+
+/// 检查采样率是否为 MP3 标准采样率
+pub fn is_mp3_sample_rate(sample_rate: u32) -> bool {
+    MP3_SAMPLE_RATES.contains(&sample_rate)
+}
+
+/// 返回与给定采样率最接近的 MP3 标准采样率
+pub fn nearest_mp3_sample_rate(sample_rate: u32) -> u32 {
+    MP3_SAMPLE_RATES
+        .iter()
+        .copied()
+        .min_by_key(|&rate| rate.abs_diff(sample_rate))
+        .unwrap_or(44100)
+}
+
+/// 给定采样率可用的比特率范围（kbps）：MPEG-1 为 32-320，MPEG-2/2.5 为 8-160
+pub(crate) fn bitrate_range_kbps(sample_rate: u32) -> std::ops::RangeInclusive<u32> {
+    if sample_rate >= 32000 { 32..=320 } else { 8..=160 }
+}
+
+/// 未指定比特率时的默认值：MPEG-1 采样率使用 192kbps，更低的采样率使用 128kbps
+pub(crate) fn default_bitrate(sample_rate: u32) -> Mp3Bitrate {
+    if bitrate_range_kbps(nearest_mp3_sample_rate(sample_rate)).contains(&192) {
+        Mp3Bitrate::Kbps192
+    } else {
+        Mp3Bitrate::Kbps128
+    }
+}
+
+/// MP3 最多支持两个声道，多声道输入自动转换时的目标声道数：有默认扬声器布局的（四声道、5.1、7.1 等）
+/// 混缩为立体声，其余混缩为单声道；不超过两个声道时返回 None
+pub(crate) fn downmix_channels(channels: u8) -> Option<u8> {
+    match channels {
+        0..=2 => None,
+        _ if wav::default_channel_mask(channels) != 0 => Some(2),
+        _ => Some(1),
+    }
+}
+
+/// VBR 各质量等级的典型平均比特率（kbps，44.1kHz 立体声），用于估算输出大小
+pub(crate) fn vbr_average_kbps(quality: VbrQuality) -> u32 {
+    match quality {
+        VbrQuality::V0 => 245,
+        VbrQuality::V1 => 225,
+        VbrQuality::V2 => 190,
+        VbrQuality::V3 => 175,
+        VbrQuality::V4 => 165,
+        VbrQuality::V5 => 130,
+        VbrQuality::V6 => 115,
+        VbrQuality::V7 => 100,
+        VbrQuality::V8 => 85,
+        VbrQuality::V9 => 65,
+    }
+}
+
+/// 将 CBR 比特率枚举转换为 LAME 比特率
+#[cfg(not(target_arch = "wasm32"))]
+fn lame_bitrate(bitrate: &Mp3Bitrate) -> mp3lame_encoder::Bitrate {
+    match bitrate {
+        Mp3Bitrate::Kbps64 => mp3lame_encoder::Bitrate::Kbps64,
+        Mp3Bitrate::Kbps128 => mp3lame_encoder::Bitrate::Kbps128,
+        Mp3Bitrate::Kbps192 => mp3lame_encoder::Bitrate::Kbps192,
+        Mp3Bitrate::Kbps256 => mp3lame_encoder::Bitrate::Kbps256,
+        Mp3Bitrate::Kbps320 => mp3lame_encoder::Bitrate::Kbps320,
+    }
+}
+
+/// 将 VBR 质量等级转换为 LAME 质量
+#[cfg(not(target_arch = "wasm32"))]
+fn lame_vbr_quality(quality: VbrQuality) -> mp3lame_encoder::Quality {
+    match quality {
+        VbrQuality::V0 => mp3lame_encoder::Quality::Best,
+        VbrQuality::V1 => mp3lame_encoder::Quality::SecondBest,
+        VbrQuality::V2 => mp3lame_encoder::Quality::NearBest,
+        VbrQuality::V3 => mp3lame_encoder::Quality::VeryNice,
+        VbrQuality::V4 => mp3lame_encoder::Quality::Nice,
+        VbrQuality::V5 => mp3lame_encoder::Quality::Good,
+        VbrQuality::V6 => mp3lame_encoder::Quality::Decent,
+        VbrQuality::V7 => mp3lame_encoder::Quality::Ok,
+        VbrQuality::V8 => mp3lame_encoder::Quality::SecondWorst,
+        VbrQuality::V9 => mp3lame_encoder::Quality::Worst,
+    }
+}
+
+/// 按码率模式配置 LAME 编码器
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn configure_rate_mode(builder: &mut Builder, rate_mode: &Mp3RateMode) -> Result<(), AudioError> {
+    match rate_mode {
+        Mp3RateMode::Cbr(bitrate) => {
+            builder.set_vbr_mode(VbrMode::Off)
+                .map_err(|e| AudioError::Encoder(format!("Failed to set VBR mode: {:?}", e)))?;
+            builder.set_brate(lame_bitrate(bitrate))
+                .map_err(|e| AudioError::Encoder(format!("Failed to set bitrate: {:?}", e)))?;
+        }
+        Mp3RateMode::Abr(kbps) => {
+            if !(8..=320).contains(kbps) {
+                return Err(AudioError::InvalidConfig(format!("Unsupported ABR bitrate: {}kbps (expected 8-320)", kbps)));
+            }
+            builder.set_vbr_mode(VbrMode::Abr)
+                .map_err(|e| AudioError::Encoder(format!("Failed to set VBR mode: {:?}", e)))?;
+            // mp3lame-encoder 未封装平均码率设置，直接调用 LAME
+            let res = unsafe {
+                mp3lame_sys::lame_set_VBR_mean_bitrate_kbps(builder.as_ptr(), *kbps as c_int)
+            };
+            if res != 0 {
+                return Err(AudioError::Encoder(format!("Failed to set ABR bitrate: {}", res)));
+            }
+        }
+        Mp3RateMode::Vbr(quality) => {
+            builder.set_vbr_mode(VbrMode::Mtrh)
+                .map_err(|e| AudioError::Encoder(format!("Failed to set VBR mode: {:?}", e)))?;
+            builder.set_vbr_quality(lame_vbr_quality(*quality))
+                .map_err(|e| AudioError::Encoder(format!("Failed to set VBR quality: {:?}", e)))?;
+        }
+    }
+    Ok(())
+}
+
+/// 配置 LAME 声道模式，立体声模式要求输入为双声道
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn configure_channel_mode(builder: &mut Builder, channel_mode: Mp3ChannelMode, input_channels: u8) -> Result<(), AudioError> {
+    let mode = match channel_mode {
+        Mp3ChannelMode::JointStereo => Mode::JointStereo,
+        Mp3ChannelMode::SimpleStereo => Mode::Stereo,
+        Mp3ChannelMode::DualChannel => Mode::DaulChannel,
+        Mp3ChannelMode::Mono => Mode::Mono,
+    };
+
+    if channel_mode != Mp3ChannelMode::Mono && input_channels != 2 {
+        return Err(AudioError::InvalidConfig(format!("Channel mode {:?} requires stereo input, got {} channel(s)", channel_mode, input_channels)));
+    }
+
+    builder.set_mode(mode)
+        .map_err(|e| AudioError::Encoder(format!("Failed to set channel mode: {:?}", e)))?;
+    Ok(())
+}
+
+/// 分块编码的 MP3 编码器：按到达顺序逐块送入 i16 交错样本，编码结果追加到调用方的缓冲区
+///
+/// 文件转换和录音共用，后者在采集到数据时即时编码，不需要先缓存整段 PCM。
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) struct StreamEncoder {
+    encoder: Encoder,
+    /// 与 `encoder` 共用的 LAME 句柄，用于读取 Xing/LAME 标签帧
+    lame: *mut lame_global_flags,
+    channels: usize,
+    /// 结束时完整输出内部缓冲的 PCM 并补零到整帧，LAME 标签记录真实的末尾填充
+    gapless: bool,
+    output: Vec<MaybeUninit<u8>>,
+    /// 立体声拆分后的左右声道，跨块复用
+    left: Vec<i16>,
+    right: Vec<i16>,
+}
+
+// LAME 句柄由 `encoder` 独占持有，随编码器一起在线程间移动
+#[cfg(not(target_arch = "wasm32"))]
+unsafe impl Send for StreamEncoder {}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl StreamEncoder {
+    /// 按配置创建编码器（采样率必须是 MP3 标准采样率，声道数为 1 或 2）
+    ///
+    /// `write_vbr_tag` 为 true 时在码流开头预留 Xing/LAME 标签帧，需要在结束后回填，
+    /// 实时推流等无法回写开头的场景应关闭
+    pub fn new(mp3_config: &Mp3Config, write_vbr_tag: bool) -> Result<Self, AudioError> {
+        Self::build(mp3_config, write_vbr_tag, false)
+    }
+
+    /// 创建关闭比特池（bit reservoir）的编码器，每帧的主数据都在本帧内，输出可以在任意帧边界切开拼接
+    pub fn without_reservoir(mp3_config: &Mp3Config, write_vbr_tag: bool) -> Result<Self, AudioError> {
+        Self::build(mp3_config, write_vbr_tag, true)
+    }
+
+    fn build(mp3_config: &Mp3Config, write_vbr_tag: bool, disable_reservoir: bool) -> Result<Self, AudioError> {
+        let mut builder = Builder::new()
+            .ok_or_else(|| AudioError::Encoder("Failed to create MP3 encoder builder (mp3lame library not available)".into()))?;
+
+        builder.set_num_channels(mp3_config.channels)
+            .map_err(|e| AudioError::Encoder(format!("Failed to set channels: {:?}", e)))?;
+
+        builder.set_sample_rate(mp3_config.sample_rate)
+            .map_err(|e| AudioError::Encoder(format!("Failed to set sample rate: {:?}", e)))?;
+
+        // 设置声道模式
+        if let Some(channel_mode) = mp3_config.channel_mode {
+            configure_channel_mode(&mut builder, channel_mode, mp3_config.channels)?;
+        }
+
+        // 设置码率模式（CBR / ABR / VBR）
+        configure_rate_mode(&mut builder, &mp3_config.rate_mode)?;
+
+        let quality_value = match mp3_config.quality {
+            AudioQuality::Low => mp3lame_encoder::Quality::Worst,
+            AudioQuality::Medium => mp3lame_encoder::Quality::Good,
+            AudioQuality::High => mp3lame_encoder::Quality::Best,
+            AudioQuality::Best => mp3lame_encoder::Quality::Best, // 最高质量
+        };
+        builder.set_quality(quality_value)
+            .map_err(|e| AudioError::Encoder(format!("Failed to set quality: {:?}", e)))?;
+
+        // 预留 Xing/LAME 标签帧，编码完成后回填帧数和字节数
+        builder.set_to_write_vbr_tag(write_vbr_tag)
+            .map_err(|e| AudioError::Encoder(format!("Failed to enable VBR tag: {:?}", e)))?;
+
+        // 编码器构建后沿用同一个 LAME 句柄，用于读取标签帧
+        let lame = unsafe { builder.as_ptr() };
+        if disable_reservoir && unsafe { mp3lame_sys::lame_set_disable_reservoir(lame, 1) } != 0 {
+            return Err(AudioError::Encoder("Failed to disable bit reservoir".into()));
+        }
+
+        let encoder = builder.build()
+            .map_err(|e| AudioError::Encoder(format!("Failed to build encoder: {:?}", e)))?;
+
+        Ok(StreamEncoder {
+            encoder,
+            lame,
+            channels: mp3_config.channels.max(1) as usize,
+            gapless: mp3_config.gapless,
+            output: Vec::new(),
+            left: Vec::new(),
+            right: Vec::new(),
+        })
+    }
+
+    /// 编码一块交错样本，编码得到的数据追加到 `out`（LAME 内部有缓冲，可能暂时没有输出）
+    pub fn encode(&mut self, samples: &[i16], out: &mut Vec<u8>) -> Result<(), AudioError> {
+        // 输出缓冲区按 LAME 建议的最坏情况分配：1.25 倍每声道样本数 + 7200 字节
+        let required = samples.len() / self.channels * 5 / 4 + 7200;
+        if self.output.len() < required {
+            self.output.resize(required, MaybeUninit::uninit());
+        }
+
+        let bytes_written = if self.channels == 1 {
+            // 单声道（InterleavedPcm 会按左右声道成对读取，单声道必须使用 MonoPcm）
+            self.encoder.encode(MonoPcm(samples), &mut self.output)
+                .map_err(|e| AudioError::Encoder(format!("Failed to encode mono audio: {:?}", e)))?
+        } else {
+            // 双声道 - 需要分离左右声道
+            self.left.clear();
+            self.right.clear();
+            crate::simd::deinterleave_stereo(samples, &mut self.left, &mut self.right);
+            self.encoder.encode(DualPcm { left: &self.left, right: &self.right }, &mut self.output)
+                .map_err(|e| AudioError::Encoder(format!("Failed to encode stereo audio: {:?}", e)))?
+        };
+
+        out.extend(self.output[..bytes_written].iter().map(|byte| unsafe { byte.assume_init() }));
+        Ok(())
+    }
+
+    /// 输出编码器中剩余的数据，之后只能再读取标签帧
+    ///
+    /// 无缝模式下编码内部缓冲中剩余的 PCM（`lame_encode_flush`），否则只输出已编码的帧
+    /// （`lame_encode_flush_nogap`），末尾不足一帧的样本不会被编码
+    pub fn flush(&mut self, out: &mut Vec<u8>) -> Result<(), AudioError> {
+        if self.output.len() < 7200 {
+            self.output.resize(7200, MaybeUninit::uninit());
+        }
+        let flushed = if self.gapless {
+            self.encoder.flush::<FlushGap>(&mut self.output)
+        } else {
+            self.encoder.flush::<FlushNoGap>(&mut self.output)
+        };
+        let flush_bytes = flushed.map_err(|e| AudioError::Encoder(format!("Failed to flush encoder: {:?}", e)))?;
+        out.extend(self.output[..flush_bytes].iter().map(|byte| unsafe { byte.assume_init() }));
+        Ok(())
+    }
+
+    /// 取出 Xing/LAME 标签帧，用于覆盖码流开头预留的空白帧
+    ///
+    /// LAME 在开启 `bWriteVbrTag` 时会在码流开头预留一帧，编码结束后才能得到
+    /// 真实的帧数和字节数，因此需要在 flush 之后回填。
+    pub fn lame_tag(&self) -> Result<Option<Vec<u8>>, AudioError> {
+        let mut tag = vec![0u8; MAX_LAME_TAG_FRAME];
+        let size = unsafe { mp3lame_sys::lame_get_lametag_frame(self.lame, tag.as_mut_ptr(), tag.len()) };
+
+        if size == 0 {
+            // 未启用 VBR 标签，无需回填
+            return Ok(None);
+        }
+        if size > tag.len() {
+            return Err(AudioError::Encoder(format!("LAME tag frame size {} exceeds the maximum frame size", size)));
+        }
+        tag.truncate(size);
+        Ok(Some(tag))
+    }
+
+    /// 将标签帧写回到完整编码数据的开头
+    pub fn write_lame_tag(&self, mp3_data: &mut [u8]) -> Result<usize, AudioError> {
+        let Some(tag) = self.lame_tag()? else {
+            return Ok(0);
+        };
+        if tag.len() > mp3_data.len() {
+            return Err(AudioError::Encoder(format!("LAME tag frame size {} is larger than the encoded data", tag.len())));
+        }
+        mp3_data[..tag.len()].copy_from_slice(&tag);
+        Ok(tag.len())
+    }
+}
+
+/// wasm32 上的占位编码器：没有可用的 LAME，`new` 总是返回 `AudioError::Encoder`，其余方法不会被调用
+#[cfg(target_arch = "wasm32")]
+pub(crate) enum StreamEncoder {}
+
+#[cfg(target_arch = "wasm32")]
+impl StreamEncoder {
+    pub fn new(_mp3_config: &Mp3Config, _write_vbr_tag: bool) -> Result<Self, AudioError> {
+        Err(AudioError::Encoder("MP3 encoding is not available on wasm32: the LAME encoder cannot be built for this target".into()))
+    }
+
+    pub fn without_reservoir(mp3_config: &Mp3Config, write_vbr_tag: bool) -> Result<Self, AudioError> {
+        Self::new(mp3_config, write_vbr_tag)
+    }
+
+    pub fn encode(&mut self, _samples: &[i16], _out: &mut Vec<u8>) -> Result<(), AudioError> {
+        match *self {}
+    }
+
+    pub fn flush(&mut self, _out: &mut Vec<u8>) -> Result<(), AudioError> {
+        match *self {}
+    }
+
+    pub fn lame_tag(&self) -> Result<Option<Vec<u8>>, AudioError> {
+        match *self {}
+    }
+
+    pub fn write_lame_tag(&self, _mp3_data: &mut [u8]) -> Result<usize, AudioError> {
+        match *self {}
+    }
+}
+
+/// MPEG-1 Layer III 的比特率表（kbps），下标为帧头中的比特率索引
+const MPEG1_BITRATES: [u32; 15] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+
+/// MPEG-2/2.5 Layer III 的比特率表（kbps）
+const MPEG2_BITRATES: [u32; 15] = [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+/// MPEG-1 的采样率表，MPEG-2 和 MPEG-2.5 分别为其 1/2 和 1/4
+const MPEG1_SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+/// Layer III 帧头信息
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FrameHeader {
+    /// 是否为 MPEG-1（否则为 MPEG-2 或 MPEG-2.5）
+    pub mpeg1: bool,
+    pub sample_rate: u32,
+    pub bitrate_kbps: u32,
+    pub channels: u8,
+    /// 整帧字节数（含帧头）
+    pub frame_size: usize,
+}
+
+impl FrameHeader {
+    /// 每帧包含的采样帧数
+    pub fn samples_per_frame(&self) -> u64 {
+        if self.mpeg1 { 1152 } else { 576 }
+    }
+
+    /// 帧头之后边信息的长度，Xing/Info 标签紧随其后
+    pub(crate) fn side_info_size(&self) -> usize {
+        match (self.mpeg1, self.channels) {
+            (true, 1) => 17,
+            (true, _) => 32,
+            (false, 1) => 9,
+            (false, _) => 17,
+        }
+    }
+}
+
+/// 解析 4 字节的 Layer III 帧头，不是合法帧头时返回 None（不支持自由格式比特率）
+pub(crate) fn parse_frame_header(bytes: &[u8]) -> Option<FrameHeader> {
+    if bytes.len() < 4 || bytes[0] != 0xFF || bytes[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    let version = (bytes[1] >> 3) & 0x03;
+    let layer = (bytes[1] >> 1) & 0x03;
+    let bitrate_index = (bytes[2] >> 4) as usize;
+    let rate_index = ((bytes[2] >> 2) & 0x03) as usize;
+    // 版本 01 保留，层 01 为 Layer III
+    if version == 0x01 || layer != 0x01 || bitrate_index == 0 || bitrate_index == 15 || rate_index == 3 {
+        return None;
+    }
+
+    let mpeg1 = version == 0x03;
+    let sample_rate = MPEG1_SAMPLE_RATES[rate_index] >> match version { 0x03 => 0, 0x02 => 1, _ => 2 };
+    let bitrate_kbps = if mpeg1 { MPEG1_BITRATES[bitrate_index] } else { MPEG2_BITRATES[bitrate_index] };
+    let padding = ((bytes[2] >> 1) & 0x01) as usize;
+    let coefficient = if mpeg1 { 144 } else { 72 };
+    Some(FrameHeader {
+        mpeg1,
+        sample_rate,
+        bitrate_kbps,
+        channels: if bytes[3] >> 6 == 0x03 { 1 } else { 2 },
+        frame_size: (coefficient * bitrate_kbps * 1000 / sample_rate) as usize + padding,
+    })
+}
+
+/// 跳过开头的 ID3v2 标签，返回第一个音频帧可能的起始位置
+pub(crate) fn skip_id3v2(bytes: &[u8]) -> usize {
+    if bytes.len() < 10 || &bytes[0..3] != b"ID3" {
+        return 0;
+    }
+    // 标签大小为 4 个 7 位的同步安全整数，不含 10 字节的标签头；带页脚时再加 10 字节
+    let size = bytes[6..10].iter().fold(0usize, |acc, &b| (acc << 7) | (b & 0x7F) as usize);
+    let footer = if bytes[5] & 0x10 != 0 { 10 } else { 0 };
+    (10 + size + footer).min(bytes.len())
+}
+
+/// 读取 Xing/Info 帧中 LAME 标签记录的编码器延迟和末尾填充（采样帧数）
+fn lame_gapless_info(frame: &[u8], header: &FrameHeader) -> Option<(u64, u64)> {
+    let xing = 4 + header.side_info_size();
+    let tag = frame.get(xing..xing + 8)?;
+    if &tag[0..4] != b"Xing" && &tag[0..4] != b"Info" {
+        return None;
+    }
+    // 按标志位跳过帧数、字节数、TOC 和质量字段
+    let flags = u32::from_be_bytes(tag[4..8].try_into().unwrap());
+    let lame = xing + 8
+        + if flags & 0x1 != 0 { 4 } else { 0 }
+        + if flags & 0x2 != 0 { 4 } else { 0 }
+        + if flags & 0x4 != 0 { 100 } else { 0 }
+        + if flags & 0x8 != 0 { 4 } else { 0 };
+    let ext = frame.get(lame..lame + 24)?;
+    if &ext[0..4] != b"LAME" && &ext[0..4] != b"Lavc" {
+        return Some((0, 0));
+    }
+    let delay = ((ext[21] as u64) << 4) | (ext[22] as u64 >> 4);
+    let padding = (((ext[22] & 0x0F) as u64) << 8) | ext[23] as u64;
+    Some((delay, padding))
+}
+
+/// MP3 码率类型，按各音频帧的比特率判断
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mp3BitrateMode {
+    /// 所有音频帧的比特率相同
+    Cbr,
+    /// 帧间比特率变化（VBR 或 ABR）
+    Vbr,
+}
+
+/// 逐帧遍历得到的 MP3 码流信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mp3Info {
+    /// 采样率（Hz）
+    pub sample_rate: u32,
+    /// 声道数
+    pub channels: u8,
+    /// 码率类型
+    pub bitrate_mode: Mp3BitrateMode,
+    /// 平均比特率（kbps），按音频帧的字节数和时长计算
+    pub average_bitrate_kbps: u32,
+    /// 音频帧数，不含 Xing/Info 标签帧
+    pub frame_count: u64,
+    /// 解码得到的采样帧数，包含编码器延迟和末尾填充
+    pub total_samples: u64,
+    /// LAME 标签记录的编码器延迟（采样帧数），没有标签时为 0
+    pub encoder_delay: u64,
+    /// LAME 标签记录的末尾填充（采样帧数），没有标签时为 0
+    pub padding: u64,
+    /// 第一帧是否为 Xing/Info 标签帧
+    pub has_xing: bool,
+    /// 扣除编码器延迟和填充后的时长
+    pub duration: std::time::Duration,
+    /// 音频帧的总字节数，不含 ID3 标签和 Xing/Info 标签帧
+    pub audio_bytes: u64,
+}
+
+impl Mp3Info {
+    /// 扣除编码器延迟和填充后的采样帧数
+    pub fn samples(&self) -> u64 {
+        self.total_samples.saturating_sub(self.encoder_delay + self.padding)
+    }
+}
+
+/// 逐帧遍历 MP3 数据，统计帧数、码率类型和时长，读取 Xing/LAME 标签中的编码器延迟和填充
+/// # Arguments
+/// * `data` - MP3 文件内容，可以带 ID3v2 标签
+/// # Returns
+/// * `Result<Mp3Info, Box<dyn std::error::Error>>` - 码流信息，找不到 MPEG 音频帧时返回错误
+pub fn parse_mp3_info(data: &[u8]) -> Result<Mp3Info, Box<dyn std::error::Error>> {
+    let mut pos = skip_id3v2(data);
+    let mut first: Option<FrameHeader> = None;
+    let mut gapless = None;
+    let mut frame_count = 0u64;
+    let mut total_samples = 0u64;
+    let mut audio_bytes = 0u64;
+    let mut bitrates = None::<(u32, u32)>;
+
+    while pos + 4 <= data.len() {
+        let Some(header) = parse_frame_header(&data[pos..]) else {
+            // 不是帧头时逐字节重新同步
+            pos += 1;
+            continue;
+        };
+        let frame = &data[pos..(pos + header.frame_size).min(data.len())];
+        // 第一帧可能是不含音频的 Xing/Info 标签帧
+        if first.is_none() {
+            first = Some(header);
+            gapless = lame_gapless_info(frame, &header);
+            if gapless.is_some() {
+                pos += header.frame_size;
+                continue;
+            }
+        }
+        frame_count += 1;
+        total_samples += header.samples_per_frame();
+        audio_bytes += frame.len() as u64;
+        let (min, max) = bitrates.get_or_insert((header.bitrate_kbps, header.bitrate_kbps));
+        *min = (*min).min(header.bitrate_kbps);
+        *max = (*max).max(header.bitrate_kbps);
+        pos += header.frame_size;
+    }
+
+    let header = first.ok_or_else(|| AudioError::UnsupportedFormat("No MPEG audio frames found".into()))?;
+    let (encoder_delay, padding) = gapless.unwrap_or((0, 0));
+    let samples = total_samples.saturating_sub(encoder_delay + padding);
+    let average_bitrate_kbps = match total_samples {
+        0 => 0,
+        _ => (audio_bytes as f64 * 8.0 * header.sample_rate as f64 / total_samples as f64 / 1000.0).round() as u32,
+    };
+    Ok(Mp3Info {
+        sample_rate: header.sample_rate,
+        channels: header.channels,
+        bitrate_mode: match bitrates {
+            Some((min, max)) if min != max => Mp3BitrateMode::Vbr,
+            _ => Mp3BitrateMode::Cbr,
+        },
+        average_bitrate_kbps,
+        frame_count,
+        total_samples,
+        encoder_delay,
+        padding,
+        has_xing: gapless.is_some(),
+        duration: std::time::Duration::from_secs_f64(samples as f64 / header.sample_rate as f64),
+        audio_bytes,
+    })
+}
+
+/// 逐帧遍历 MP3 数据计算时长，扣除 LAME 标签记录的编码器延迟和填充
+pub(crate) fn mp3_duration(bytes: &[u8]) -> Result<std::time::Duration, Box<dyn std::error::Error>> {
+    parse_mp3_info(bytes).map(|info| info.duration)
+}
+
+/// 按帧切分的 MP3 文件
+struct Mp3Stream<'a> {
+    data: &'a [u8],
+    /// 开头的 ID3v2 标签
+    id3v2: std::ops::Range<usize>,
+    /// 末尾的 ID3v1 标签
+    id3v1: Option<std::ops::Range<usize>>,
+    /// Xing/Info 标签帧
+    tag_frame: Option<std::ops::Range<usize>>,
+    /// 标签帧之后的音频帧
+    frames: Vec<std::ops::Range<usize>>,
+    /// 第一帧的帧头
+    header: FrameHeader,
+    /// LAME 标签记录的编码器延迟和末尾填充
+    gapless: (u64, u64),
+}
+
+impl<'a> Mp3Stream<'a> {
+    /// 跳过 ID3 标签和无法识别的字节，按帧头切分；末尾被截断的帧被丢弃
+    fn split(data: &'a [u8]) -> Result<Self, AudioError> {
+        let start = skip_id3v2(data);
+        let end = match data.len().checked_sub(128) {
+            Some(at) if at >= start && &data[at..at + 3] == b"TAG" => at,
+            _ => data.len(),
+        };
+        let mut first = None;
+        let mut tag_frame = None;
+        let mut gapless = (0, 0);
+        let mut frames = Vec::new();
+        let mut pos = start;
+        while pos + 4 <= end {
+            let Some(header) = parse_frame_header(&data[pos..end]) else {
+                pos += 1;
+                continue;
+            };
+            let frame = pos..pos + header.frame_size;
+            if frame.end > end {
+                log::warn!("Dropping truncated MP3 frame at offset {}", pos);
+                break;
+            }
+            if first.is_none() {
+                first = Some(header);
+                if let Some(info) = lame_gapless_info(&data[frame.clone()], &header) {
+                    gapless = info;
+                    tag_frame = Some(frame);
+                    pos += header.frame_size;
+                    continue;
+                }
+            }
+            frames.push(frame);
+            pos += header.frame_size;
+        }
+        Ok(Mp3Stream {
+            data,
+            id3v2: 0..start,
+            id3v1: (end < data.len()).then_some(end..data.len()),
+            tag_frame,
+            frames,
+            header: first.ok_or_else(|| AudioError::UnsupportedFormat("No MPEG audio frames found".into()))?,
+            gapless,
+        })
+    }
+}
+
+/// 构造不含 LAME 扩展的空 Xing/Info 标签帧，帧数、字节数和 TOC 由 `parallel::rewrite_info_tag` 填写
+///
+/// 帧头参数取自 `first_frame`（去掉 CRC 和填充位），比特率取能容纳标签字段和 LAME 扩展位置的最小值
+fn empty_info_tag(first_frame: &[u8], vbr: bool) -> Option<Vec<u8>> {
+    let first_frame = first_frame.get(..4)?;
+    let mut header = [first_frame[0], first_frame[1] | 0x01, 0, first_frame[3]];
+    let tag_header = (1..15u8).find_map(|index| {
+        header[2] = (index << 4) | (first_frame[2] & 0x0D);
+        // Xing 标识、标志位、帧数、字节数、100 字节 TOC 和质量字段，之后留出 36 字节的 LAME 扩展位置（全 0）
+        parse_frame_header(&header).filter(|parsed| parsed.frame_size >= 4 + parsed.side_info_size() + 120 + 36)
+    })?;
+    let xing = 4 + tag_header.side_info_size();
+    let mut frame = vec![0u8; tag_header.frame_size];
+    frame[..4].copy_from_slice(&header);
+    frame[xing..xing + 4].copy_from_slice(if vbr { b"Xing" } else { b"Info" });
+    frame[xing + 4..xing + 8].copy_from_slice(&0x0Fu32.to_be_bytes());
+    Some(frame)
+}
+
+/// 按帧拼接多个 MP3 码流，不重新编码
+///
+/// 保留第一个输入的 ID3v2 / ID3v1 标签，丢弃其余输入的标签和各自的 Xing/Info 标签帧；
+/// 第一个输入带有 Xing/LAME 标签时，按拼接结果重写帧数、字节数、TOC 和校验和，编码器延迟取自第一个输入，末尾填充取自最后一个。
+/// 第一个输入没有标签帧、但其他输入带有标签或拼接结果为 VBR 时，按拼接结果构造不含 LAME 扩展的 Xing/Info 标签帧
+/// # Arguments
+/// * `inputs` - 各输入的名称（用于错误信息）和完整内容
+/// * `check_params` - 是否要求所有输入的 MPEG 版本、采样率和声道数一致
+pub(crate) fn concat_streams(inputs: &[(String, Vec<u8>)], check_params: bool) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let streams = inputs
+        .iter()
+        .map(|(name, data)| Mp3Stream::split(data).map_err(|e| AudioError::UnsupportedFormat(format!("{}: {}", name, e))))
+        .collect::<Result<Vec<_>, _>>()?;
+    let first = streams.first().ok_or_else(|| AudioError::InvalidInput("No input files".into()))?;
+    if check_params {
+        for ((name, _), stream) in inputs.iter().zip(&streams).skip(1) {
+            let (expected, actual) = (&first.header, &stream.header);
+            if (actual.mpeg1, actual.sample_rate, actual.channels) != (expected.mpeg1, expected.sample_rate, expected.channels) {
+                return Err(AudioError::InvalidInput(format!(
+                    "{} has {}Hz, {} channels, which does not match the first input ({}Hz, {} channels)",
+                    name, actual.sample_rate, actual.channels, expected.sample_rate, expected.channels
+                )).into());
+            }
+        }
+    }
+
+    let mut audio = Vec::new();
+    let mut frame_offsets = Vec::new();
+    let mut bitrates = std::collections::BTreeSet::new();
+    for stream in &streams {
+        for frame in &stream.frames {
+            frame_offsets.push(audio.len());
+            bitrates.insert(stream.data[frame.start + 2] >> 4);
+            audio.extend_from_slice(&stream.data[frame.clone()]);
+        }
+    }
+
+    // 比特率不同的 CBR 文件拼接后成为 VBR 码流
+    let vbr = bitrates.len() > 1;
+    let mut tag = match &first.tag_frame {
+        Some(frame) => Some(first.data[frame.clone()].to_vec()),
+        None if vbr || streams.iter().any(|stream| stream.tag_frame.is_some()) => empty_info_tag(&audio, vbr),
+        None => None,
+    };
+    if let Some(frame) = &mut tag {
+        let xing = 4 + first.header.side_info_size();
+        if vbr {
+            frame[xing..xing + 4].copy_from_slice(b"Xing");
+        }
+        let total_samples = frame_offsets.len() as u64 * first.header.samples_per_frame();
+        let padding = streams.last().map_or(0, |stream| stream.gapless.1);
+        let input_frames = total_samples.saturating_sub(first.gapless.0 + padding);
+        if let Err(e) = parallel::rewrite_info_tag(frame, &audio, &frame_offsets, Some(input_frames)) {
+            log::warn!("Dropping the Xing/Info tag of the merged stream: {}", e);
+            tag = None;
+        }
+    }
+
+    let mut output = Vec::with_capacity(first.id3v2.len() + tag.as_ref().map_or(0, Vec::len) + audio.len() + 128);
+    output.extend_from_slice(&first.data[first.id3v2.clone()]);
+    output.extend_from_slice(tag.as_deref().unwrap_or_default());
+    output.extend_from_slice(&audio);
+    if let Some(id3v1) = &first.id3v1 {
+        output.extend_from_slice(&first.data[id3v1.clone()]);
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_frame_header() {
+        // MPEG-1 Layer III，128kbps，44.1kHz，无填充，立体声
+        let header = parse_frame_header(&[0xFF, 0xFB, 0x90, 0x00]).unwrap();
+        assert!(header.mpeg1);
+        assert_eq!((header.sample_rate, header.bitrate_kbps, header.channels), (44100, 128, 2));
+        assert_eq!(header.frame_size, 417);
+
+        // MPEG-2 Layer III，64kbps，22.05kHz，有填充，单声道
+        let header = parse_frame_header(&[0xFF, 0xF3, 0x82, 0xC0]).unwrap();
+        assert!(!header.mpeg1);
+        assert_eq!((header.sample_rate, header.bitrate_kbps, header.channels), (22050, 64, 1));
+        assert_eq!(header.frame_size, 209);
+
+        assert!(parse_frame_header(&[0xFF, 0xFD, 0x90, 0x00]).is_none(), "Layer II 不支持");
+        assert!(parse_frame_header(b"ID3\x04").is_none());
+    }
+
+    #[test]
+    fn test_skip_id3v2() {
+        let mut bytes = b"ID3\x04\x00\x00\x00\x00\x01\x00".to_vec();
+        bytes.extend_from_slice(&[0; 128]);
+        assert_eq!(skip_id3v2(&bytes), 138);
+        assert_eq!(skip_id3v2(&[0xFF, 0xFB, 0x90, 0x00]), 0);
+    }
+
+    /// 构造一帧：4 字节帧头，其余填 0
+    fn frame(header: [u8; 4]) -> Vec<u8> {
+        let mut frame = vec![0u8; parse_frame_header(&header).unwrap().frame_size];
+        frame[..4].copy_from_slice(&header);
+        frame
+    }
+
+    #[test]
+    fn test_parse_mp3_info() {
+        // Info 标签帧：边信息之后是 "Info" 和标志位，再之后是 LAME 扩展，延迟 576、填充 1000
+        let mut tag = frame([0xFF, 0xFB, 0x90, 0x00]);
+        tag[36..40].copy_from_slice(b"Info");
+        tag[44..48].copy_from_slice(b"LAME");
+        let gapless = (576u32 << 12) | 1000;
+        tag[65..68].copy_from_slice(&gapless.to_be_bytes()[1..]);
+
+        let cbr: Vec<u8> = [tag.clone(), frame([0xFF, 0xFB, 0x90, 0x00]), frame([0xFF, 0xFB, 0x90, 0x00])].concat();
+        let info = parse_mp3_info(&cbr).unwrap();
+        assert!(info.has_xing);
+        assert_eq!((info.frame_count, info.total_samples, info.audio_bytes), (2, 2304, 834));
+        assert_eq!((info.encoder_delay, info.padding, info.samples()), (576, 1000, 728));
+        assert_eq!(info.bitrate_mode, Mp3BitrateMode::Cbr);
+        assert_eq!(info.average_bitrate_kbps, 128);
+
+        // 没有标签帧时第一帧计为音频；160kbps 和 128kbps 混合为 VBR
+        let vbr: Vec<u8> = [frame([0xFF, 0xFB, 0xA0, 0x00]), frame([0xFF, 0xFB, 0x90, 0x00])].concat();
+        let info = parse_mp3_info(&vbr).unwrap();
+        assert!(!info.has_xing);
+        assert_eq!((info.frame_count, info.encoder_delay), (2, 0));
+        assert_eq!(info.bitrate_mode, Mp3BitrateMode::Vbr);
+        assert_eq!(info.average_bitrate_kbps, 144);
+    }
+}
@@ -0,0 +1,265 @@
+// 多线程 MP3 编码：长音频按 MP3 帧边界分段，各段在工作线程中编码后按帧拼接
+//
+// 各段编码器关闭比特池（bit reservoir），每帧的主数据都在本帧内，可以在任意帧边界切开；
+// 每段前后多编码几帧作为预热和收尾，丢弃后保留的帧与单个编码器输出的帧一一对应。
+// Xing/LAME 标签取自第一段的编码器，帧数、字节数、TOC 和校验和按拼接结果重新计算，
+// 无缝编码时末尾填充也按整段输入重新计算。
+
+use std::sync::mpsc;
+use std::thread;
+
+use crate::mp3::{self, StreamEncoder};
+use crate::progress::CancelToken;
+use crate::{AudioError, Mp3Config};
+
+/// 每段前后额外编码的帧数，使心理声学模型和 MDCT 的重叠部分在保留的帧之前进入稳定状态
+const OVERLAP_FRAMES: usize = 4;
+
+/// 每段至少包含的 MP3 帧数（44.1kHz 下约 13 秒），更短的音频分段的开销大于并行的收益
+const MIN_SEGMENT_FRAMES: usize = 512;
+
+/// 工作线程每次送入编码器的 MP3 帧数，用于报告进度和响应取消
+const CHUNK_FRAMES: usize = 16;
+
+/// 每个 MP3 帧包含的采样帧数：MPEG-1 为 1152，MPEG-2/2.5 为 576
+fn samples_per_frame(sample_rate: u32) -> usize {
+    if sample_rate >= 32000 { 1152 } else { 576 }
+}
+
+/// 实际使用的编码线程数：0 表示使用全部可用核心
+pub(crate) fn resolve_threads(threads: usize) -> usize {
+    match threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    }
+}
+
+/// 按音频长度和线程数决定分段数，返回 1 时应使用单个编码器
+/// # Arguments
+/// * `frames` - 采样帧数
+/// * `sample_rate` - 编码采样率
+/// * `threads` - `Mp3Config::threads`
+pub(crate) fn segment_count(frames: u64, sample_rate: u32, threads: usize) -> usize {
+    let mp3_frames = frames / samples_per_frame(sample_rate) as u64;
+    let max_segments = (mp3_frames / MIN_SEGMENT_FRAMES as u64).min(usize::MAX as u64) as usize;
+    resolve_threads(threads).min(max_segments).max(1)
+}
+
+/// 一段的编码范围（单位为 MP3 帧）
+struct Segment {
+    /// 送入编码器的第一帧
+    input_start: usize,
+    /// 送入编码器的帧数上限（最后一段为 None，编码到音频末尾）
+    input_end: Option<usize>,
+    /// 编码器输出中需要跳过的帧数（预热帧，第一段还包括标签帧）
+    skip: usize,
+    /// 保留的帧数（最后一段为 None，保留到末尾）
+    keep: Option<usize>,
+}
+
+/// 将音频分为 `count` 段
+fn plan_segments(mp3_frames: usize, count: usize) -> Vec<Segment> {
+    (0..count)
+        .map(|k| {
+            let start = k * mp3_frames / count;
+            let end = (k + 1) * mp3_frames / count;
+            let input_start = start.saturating_sub(OVERLAP_FRAMES);
+            let last = k + 1 == count;
+            Segment {
+                input_start,
+                input_end: (!last).then_some(end + OVERLAP_FRAMES),
+                skip: start - input_start + usize::from(k == 0),
+                keep: (!last).then_some(end - start),
+            }
+        })
+        .collect()
+}
+
+/// 多线程编码交错的 i16 样本，返回带有 Xing/LAME 标签的完整 MP3 数据
+/// # Arguments
+/// * `samples` - 交错样本，采样率和声道数与 `mp3_config` 一致
+/// * `mp3_config` - 编码配置（采样率必须是 MP3 标准采样率）
+/// * `segments` - 分段数，由 `segment_count` 得到
+/// * `cancel` - 取消令牌，各工作线程每编码一块检查一次
+/// * `progress` - 在调用线程中报告已送入编码器的样本数和样本总数（含各段重叠部分）
+pub(crate) fn encode(
+    samples: &[i16],
+    mp3_config: &Mp3Config,
+    segments: usize,
+    cancel: Option<&CancelToken>,
+    mut progress: impl FnMut(u64, u64),
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let channels = mp3_config.channels.max(1) as usize;
+    let frame_samples = samples_per_frame(mp3_config.sample_rate) * channels;
+    let plan = plan_segments(samples.len() / frame_samples, segments);
+    let inputs: Vec<&[i16]> = plan
+        .iter()
+        .map(|segment| {
+            let start = segment.input_start * frame_samples;
+            let end = segment.input_end.map_or(samples.len(), |end| (end * frame_samples).min(samples.len()));
+            &samples[start..end]
+        })
+        .collect();
+    // 编码器在调用线程中创建，配置错误按原类型返回
+    let mut encoders = Vec::with_capacity(plan.len());
+    for k in 0..plan.len() {
+        encoders.push(StreamEncoder::without_reservoir(mp3_config, k == 0)?);
+    }
+
+    let total: u64 = inputs.iter().map(|input| input.len() as u64).sum();
+    let (sender, receiver) = mpsc::channel();
+    let results = thread::scope(|scope| {
+        let handles: Vec<_> = encoders
+            .into_iter()
+            .zip(&inputs)
+            .map(|(mut encoder, input)| {
+                let sender = sender.clone();
+                scope.spawn(move || -> Result<(StreamEncoder, Vec<u8>), AudioError> {
+                    let mut output = Vec::new();
+                    for chunk in input.chunks(CHUNK_FRAMES * frame_samples) {
+                        if cancel.is_some_and(CancelToken::is_cancelled) {
+                            break;
+                        }
+                        encoder.encode(chunk, &mut output)?;
+                        let _ = sender.send(chunk.len() as u64);
+                    }
+                    encoder.flush(&mut output)?;
+                    Ok((encoder, output))
+                })
+            })
+            .collect();
+        drop(sender);
+
+        let mut encoded = 0;
+        for count in receiver {
+            encoded += count;
+            progress(encoded, total);
+        }
+        handles.into_iter().map(|handle| handle.join().unwrap_or_else(|_| Err(AudioError::Encoder("Encoder thread panicked".into())))).collect::<Vec<_>>()
+    });
+    if cancel.is_some_and(CancelToken::is_cancelled) {
+        return Err(AudioError::Cancelled.into());
+    }
+
+    // 按计划截取各段的帧并拼接，第一段的标签帧占位在最前面
+    let mut tag_encoder = None;
+    let mut stream = Vec::new();
+    let mut audio_start = 0;
+    let mut frame_offsets = Vec::new();
+    for (k, (result, segment)) in results.into_iter().zip(&plan).enumerate() {
+        let (encoder, output) = result?;
+        let frames = split_frames(&output)?;
+        if k == 0 {
+            let tag = frames.first().ok_or_else(|| AudioError::Encoder("Encoder produced no frames".into()))?;
+            stream.extend_from_slice(&output[tag.clone()]);
+            audio_start = stream.len();
+            tag_encoder = Some(encoder);
+        }
+        let kept = frames.iter().skip(segment.skip).take(segment.keep.unwrap_or(usize::MAX));
+        for frame in kept {
+            frame_offsets.push(stream.len() - audio_start);
+            stream.extend_from_slice(&output[frame.clone()]);
+        }
+    }
+
+    if let Some(mut tag) = tag_encoder.map(|encoder| encoder.lame_tag()).transpose()?.flatten() {
+        let input_frames = mp3_config.gapless.then_some((samples.len() / channels) as u64);
+        rewrite_info_tag(&mut tag, &stream[audio_start..], &frame_offsets, input_frames)?;
+        if tag.len() != audio_start {
+            return Err(AudioError::Encoder(format!("LAME tag frame size {} does not match the reserved frame size {}", tag.len(), audio_start)).into());
+        }
+        stream[..audio_start].copy_from_slice(&tag);
+    }
+    Ok(stream)
+}
+
+/// 按帧头切分 LAME 的输出，返回每帧的字节范围
+fn split_frames(data: &[u8]) -> Result<Vec<std::ops::Range<usize>>, AudioError> {
+    let mut frames = Vec::new();
+    let mut pos = 0;
+    while pos < data.len() {
+        let header = mp3::parse_frame_header(&data[pos..]).ok_or_else(|| AudioError::Encoder(format!("Invalid MP3 frame header at offset {}", pos)))?;
+        let end = (pos + header.frame_size).min(data.len());
+        frames.push(pos..end);
+        pos = end;
+    }
+    Ok(frames)
+}
+
+/// 按拼接后的码流重写 Xing/Info 标签中的帧数、字节数、TOC，以及 LAME 扩展中的音乐长度和校验和
+/// # Arguments
+/// * `tag` - LAME 生成的标签帧
+/// * `audio` - 标签帧之后的全部音频帧
+/// * `frame_offsets` - 每个音频帧在 `audio` 中的偏移
+/// * `input_frames` - 无缝编码时的输入采样帧数，用于重新计算末尾填充
+pub(crate) fn rewrite_info_tag(tag: &mut [u8], audio: &[u8], frame_offsets: &[usize], input_frames: Option<u64>) -> Result<(), AudioError> {
+    let header = mp3::parse_frame_header(tag).ok_or_else(|| AudioError::Encoder("Invalid LAME tag frame".into()))?;
+    let xing = 4 + header.side_info_size();
+    let flags = match tag.get(xing..xing + 8) {
+        Some(id) if &id[0..4] == b"Xing" || &id[0..4] == b"Info" => u32::from_be_bytes(id[4..8].try_into().unwrap()),
+        _ => return Err(AudioError::Encoder("LAME tag frame has no Xing/Info header".into())),
+    };
+    // LAME 总是写入帧数、字节数、TOC 和质量四个字段
+    if flags & 0x0F != 0x0F {
+        return Err(AudioError::Encoder(format!("Unexpected Xing flags: {:#x}", flags)));
+    }
+    let total_bytes = (tag.len() + audio.len()) as u64;
+    let frames = frame_offsets.len();
+    let tag_len = tag.len();
+    tag[xing + 8..xing + 12].copy_from_slice(&(frames as u32).to_be_bytes());
+    tag[xing + 12..xing + 16].copy_from_slice(&(total_bytes as u32).to_be_bytes());
+    for (i, entry) in tag[xing + 16..xing + 116].iter_mut().enumerate() {
+        let offset = frame_offsets.get(i * frames / 100).map_or(0, |&offset| tag_len + offset);
+        *entry = (offset as u64 * 256 / total_bytes.max(1)).min(255) as u8;
+    }
+
+    let lame = xing + 120;
+    if tag.get(lame..lame + 4) != Some(b"LAME") || tag.len() < lame + 36 {
+        return Ok(());
+    }
+    if let Some(input_frames) = input_frames {
+        // 延迟和填充各占 12 位：第一段的填充只对应它自己的输入
+        let delay = ((tag[lame + 21] as u64) << 4) | (tag[lame + 22] as u64 >> 4);
+        let padding = (frames as u64 * header.samples_per_frame()).saturating_sub(delay + input_frames).min(0xFFF);
+        tag[lame + 22] = (tag[lame + 22] & 0xF0) | (padding >> 8) as u8;
+        tag[lame + 23] = padding as u8;
+    }
+    tag[lame + 28..lame + 32].copy_from_slice(&(total_bytes as u32).to_be_bytes());
+    tag[lame + 32..lame + 34].copy_from_slice(&crc16(audio, 0).to_be_bytes());
+    let tag_crc = crc16(&tag[..lame + 34], 0);
+    tag[lame + 34..lame + 36].copy_from_slice(&tag_crc.to_be_bytes());
+    Ok(())
+}
+
+/// LAME 标签使用的 CRC-16（多项式 0x8005，反射输入输出）
+fn crc16(data: &[u8], mut crc: u16) -> u16 {
+    for &byte in data {
+        crc ^= byte as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xA001 } else { crc >> 1 };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_segments_cover_every_frame_once() {
+        let plan = plan_segments(2050, 3);
+        // 保留的帧首尾相接：第一段跳过标签帧，其余各段跳过预热帧
+        assert_eq!(plan.iter().map(|s| s.skip).collect::<Vec<_>>(), vec![1, OVERLAP_FRAMES, OVERLAP_FRAMES]);
+        assert_eq!(plan.iter().map(|s| s.input_start + s.skip).collect::<Vec<_>>(), vec![1, 683, 1366]);
+        assert_eq!(plan[0].keep, Some(683));
+        assert_eq!(plan[1].keep, Some(683));
+        assert_eq!((plan[2].input_end, plan[2].keep), (None, None));
+    }
+
+    #[test]
+    fn test_crc16_matches_lame() {
+        // CRC-16/ARC 的标准校验值
+        assert_eq!(crc16(b"123456789", 0), 0xBB3D);
+    }
+}
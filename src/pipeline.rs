@@ -0,0 +1,559 @@
+// 处理流水线：解码 → 依次执行处理步骤 → 编码，一次流式完成，不产生临时文件
+//
+// 峰值归一化和淡出需要知道整段信号的峰值或长度，流水线会先遍历一次输入测量（只解码和处理，不编码），
+// 编码仍然只进行一次，内存占用与输入长度无关。
+
+use std::fmt;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use crate::atomic::{self, AtomicFile};
+use crate::convert::open_decoder;
+use crate::dsp::{self, ChannelMap, DcFilter, LimiterConfig, NoiseGate, NoiseGateConfig, StreamLimiter, StreamResampler};
+use crate::filters::{FilterChain, FilterProcessor};
+use crate::{
+    frames_duration, mp3, probe, sample, AudioConfig, AudioDecoder, AudioError, AudioEncoder, AudioFormat, AudioQuality,
+    ConversionReport, ConvertOptions, ConvertStage, FadeConfig, Mp3Config, Mp3StreamEncoder, PcmStreamEncoder,
+    PcmToWavConfig, SampleFormat, WavStreamEncoder,
+};
+
+/// 流水线的输出编码
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineOutput {
+    /// WAV，使用配置中的位深、样本格式和容器
+    Wav(PcmToWavConfig),
+    /// MP3，使用配置中的比特率、码率模式和质量；采样率与流水线输出不同时自动追加重采样
+    Mp3(Mp3Config),
+    /// 16 位小端原始 PCM
+    Pcm,
+}
+
+impl From<PcmToWavConfig> for PipelineOutput {
+    fn from(config: PcmToWavConfig) -> Self {
+        PipelineOutput::Wav(config)
+    }
+}
+
+impl From<Mp3Config> for PipelineOutput {
+    fn from(config: Mp3Config) -> Self {
+        PipelineOutput::Mp3(config)
+    }
+}
+
+/// 自定义处理步骤，在解码和编码之间就地处理样本，例如降噪、均衡
+///
+/// 样本为交错的 f32（满量程为 ±1.0），每次调用收到的是连续的一段信号，帧数不固定；
+/// 处理不能改变样本数。流水线在每次遍历输入之前调用 `reset`，有状态的效果器应在其中清除状态
+pub trait AudioEffect {
+    /// 处理一块交错样本
+    /// # Arguments
+    /// * `frames` - 交错样本，长度为声道数的整数倍
+    /// * `config` - 当前信号的采样率和声道数（样本格式为 F32）
+    fn process(&mut self, frames: &mut [f32], config: &AudioConfig);
+
+    /// 清除内部状态，准备处理新的信号
+    fn reset(&mut self) {}
+}
+
+impl<F: FnMut(&mut [f32], &AudioConfig)> AudioEffect for F {
+    fn process(&mut self, frames: &mut [f32], config: &AudioConfig) {
+        self(frames, config)
+    }
+}
+
+/// 处理步骤
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Resample(u32),
+    Channels(u8),
+    ChannelMap(ChannelMap),
+    Gain(f32),
+    Normalize(f32),
+    Fade(FadeConfig),
+    RemoveDc,
+    Filter(FilterChain),
+    NoiseGate(NoiseGateConfig),
+    Limit(LimiterConfig),
+    /// `AudioPipeline::effects` 中的序号
+    Effect(usize),
+}
+
+/// 音频处理流水线
+///
+/// ```no_run
+/// use audio_helper::{AudioPipeline, AudioQuality, Mp3Bitrate, Mp3Config};
+///
+/// let report = AudioPipeline::from("input.wav")
+///     .resample(16000)
+///     .downmix_mono()
+///     .normalize(-1.0)
+///     .encode(Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::High))
+///     .run("output.mp3")?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct AudioPipeline {
+    input: PathBuf,
+    pcm_config: Option<AudioConfig>,
+    steps: Vec<Step>,
+    effects: Vec<Box<dyn AudioEffect>>,
+    output: Option<PipelineOutput>,
+    options: ConvertOptions,
+}
+
+impl fmt::Debug for AudioPipeline {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AudioPipeline")
+            .field("input", &self.input)
+            .field("pcm_config", &self.pcm_config)
+            .field("steps", &self.steps)
+            .field("effects", &self.effects.len())
+            .field("output", &self.output)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<P: AsRef<Path>> From<P> for AudioPipeline {
+    fn from(input: P) -> Self {
+        AudioPipeline::new(input)
+    }
+}
+
+impl AudioPipeline {
+    /// 创建以 `input` 为输入的流水线，输入格式（PCM / WAV / MP3）按文件内容识别
+    pub fn new(input: impl AsRef<Path>) -> Self {
+        AudioPipeline {
+            input: input.as_ref().to_path_buf(),
+            pcm_config: None,
+            steps: Vec::new(),
+            effects: Vec::new(),
+            output: None,
+            options: ConvertOptions::default(),
+        }
+    }
+
+    /// 设置原始 PCM 输入的参数，未设置时从文件名推断；输入为 WAV / MP3 时忽略
+    pub fn with_pcm_config(mut self, config: AudioConfig) -> Self {
+        self.pcm_config = Some(config);
+        self
+    }
+
+    /// 设置转换选项（进度回调、取消令牌、覆盖策略）
+    pub fn with_options(mut self, options: ConvertOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 重采样到指定采样率
+    pub fn resample(mut self, sample_rate: u32) -> Self {
+        self.steps.push(Step::Resample(sample_rate));
+        self
+    }
+
+    /// 混缩为单声道
+    pub fn downmix_mono(self) -> Self {
+        self.channels(1)
+    }
+
+    /// 转换声道数（混缩为单声道或单声道复制为立体声）
+    pub fn channels(mut self, channels: u8) -> Self {
+        self.steps.push(Step::Channels(channels));
+        self
+    }
+
+    /// 交换、选取或重排声道（如修正左右声道接反的录音）
+    pub fn channel_map(mut self, map: ChannelMap) -> Self {
+        self.steps.push(Step::ChannelMap(map));
+        self
+    }
+
+    /// 调整增益（dB），超出满量程的样本被削波
+    pub fn gain(mut self, gain_db: f32) -> Self {
+        self.steps.push(Step::Gain(gain_db));
+        self
+    }
+
+    /// 按峰值归一化到目标电平（dBFS）
+    pub fn normalize(mut self, target_db: f32) -> Self {
+        self.steps.push(Step::Normalize(target_db));
+        self
+    }
+
+    /// 淡入淡出
+    pub fn fade(mut self, fade: FadeConfig) -> Self {
+        self.steps.push(Step::Fade(fade));
+        self
+    }
+
+    /// 去除直流偏移
+    pub fn remove_dc(mut self) -> Self {
+        self.steps.push(Step::RemoveDc);
+        self
+    }
+
+    /// 双二阶滤波器链（如语音编码前用 80Hz 高通去除隆隆声），可传入单个 `BiquadFilter`
+    pub fn filter(mut self, filters: impl Into<FilterChain>) -> Self {
+        self.steps.push(Step::Filter(filters.into()));
+        self
+    }
+
+    /// 噪声门，静音电平低于阈值的部分（如口述录音中语句之间的底噪）
+    pub fn noise_gate(mut self, gate: NoiseGateConfig) -> Self {
+        self.steps.push(Step::NoiseGate(gate));
+        self
+    }
+
+    /// 前瞻峰值限制器，使真峰值不超过上限（dBTP）；通常放在归一化之后作为最后一个步骤
+    pub fn limit(mut self, limiter: LimiterConfig) -> Self {
+        self.steps.push(Step::Limit(limiter));
+        self
+    }
+
+    /// 插入自定义处理步骤，按添加顺序与内置步骤一起执行
+    pub fn effect(mut self, effect: impl AudioEffect + 'static) -> Self {
+        self.steps.push(Step::Effect(self.effects.len()));
+        self.effects.push(Box::new(effect));
+        self
+    }
+
+    /// 设置输出编码，采样率和声道数由流水线决定（配置中的值被忽略）；未设置时按输出文件扩展名选择
+    pub fn encode(mut self, output: impl Into<PipelineOutput>) -> Self {
+        self.output = Some(output.into());
+        self
+    }
+
+    /// 执行流水线，输出原子写入
+    /// # Arguments
+    /// * `output_path` - 输出文件路径；未调用 `encode` 时扩展名须为 `wav`、`mp3`、`pcm` 或 `raw`
+    /// # Returns
+    /// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计，`config_used` 为输出音频的参数
+    pub fn run(self, output_path: impl AsRef<Path>) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        let started = Instant::now();
+        let AudioPipeline { input, pcm_config, mut steps, mut effects, output, mut options } = self;
+        let output_path = output_path.as_ref();
+        if !input.exists() {
+            return Err(crate::input_not_found());
+        }
+        let format = probe::probe_file(&input)?;
+        let input_bytes = std::fs::metadata(&input)?.len();
+        let open = || open_decoder(&input, format, pcm_config.as_ref());
+        let input_config = open()?.0.config();
+
+        let output = match output {
+            Some(output) => output,
+            None => match crate::convert::output_format(output_path)? {
+                AudioFormat::Wav => PipelineOutput::Wav(PcmToWavConfig::default()),
+                AudioFormat::Mp3 => {
+                    let (sample_rate, channels) = output_shape(&steps, &input_config);
+                    let bitrate = mp3::default_bitrate(mp3::nearest_mp3_sample_rate(sample_rate));
+                    PipelineOutput::Mp3(Mp3Config::new(sample_rate, channels, bitrate, AudioQuality::High))
+                }
+                AudioFormat::Pcm => PipelineOutput::Pcm,
+            },
+        };
+
+        // MP3 只支持标准采样率和最多两个声道，按编码配置补充声道转换和重采样
+        if let PipelineOutput::Mp3(config) = &output {
+            if let Some(map) = &config.channel_map {
+                steps.push(Step::ChannelMap(map.clone()));
+            }
+            let (sample_rate, channels) = output_shape(&steps, &input_config);
+            match config.target_channels {
+                Some(target) if target != channels => steps.push(Step::Channels(target)),
+                None => steps.extend(mp3::downmix_channels(channels).map(Step::Channels)),
+                _ => {}
+            }
+            let target_rate = config.target_sample_rate.unwrap_or_else(|| mp3::nearest_mp3_sample_rate(sample_rate));
+            if target_rate != sample_rate {
+                steps.push(Step::Resample(target_rate));
+            }
+        }
+
+        let Some(resolved_path) = atomic::resolve_output(output_path, options.overwrite)? else {
+            return crate::skipped_report(&input, output_path, output_path, output_format(&output), started);
+        };
+        let output_path = resolved_path.as_path();
+
+        // 归一化和淡出先测量整段信号，之后按测得的增益和长度流式处理
+        let mut resolved = vec![None; steps.len()];
+        while let Some(index) = (0..steps.len()).find(|&i| resolved[i].is_none() && needs_measure(&steps[i])) {
+            let (mut decoder, position) = open()?;
+            let mut chain = Chain::new(&steps[..index], &resolved, &input_config, &mut effects)?;
+            let mut measure = Measure::default();
+            let mut buffer = Vec::new();
+            let mut samples = Vec::new();
+            options.report(ConvertStage::Processing, 0, input_bytes);
+            loop {
+                options.check_cancelled()?;
+                let more = pull(decoder.as_mut(), &mut chain, &mut samples, &mut buffer)?;
+                measure.add(&buffer, chain.channels);
+                options.report(ConvertStage::Processing, position.load(Ordering::Relaxed).min(input_bytes), input_bytes);
+                if !more {
+                    break;
+                }
+            }
+            options.report(ConvertStage::Processing, input_bytes, input_bytes);
+            resolved[index] = Some(measure);
+        }
+
+        let (mut decoder, position) = open()?;
+        let mut chain = Chain::new(&steps, &resolved, &input_config, &mut effects)?;
+        let (sample_rate, channels) = (chain.sample_rate, chain.channels);
+        let writer = BufWriter::new(AtomicFile::create(output_path)?);
+        let mut run = |encoder: &mut dyn AudioEncoder| -> Result<(u64, u64), Box<dyn std::error::Error>> {
+            let mut buffer = Vec::new();
+            let mut samples = Vec::new();
+            let mut frames = 0u64;
+            let mut encode = |buffer: &[f32]| {
+                let converted: Vec<i16> = buffer.iter().copied().map(sample::f32_to_i16).collect();
+                frames += (converted.len() / channels as usize) as u64;
+                encoder.encode(&converted)
+            };
+            options.report(ConvertStage::Encoding, 0, input_bytes);
+            loop {
+                options.check_cancelled()?;
+                let more = pull(decoder.as_mut(), &mut chain, &mut samples, &mut buffer)?;
+                encode(&buffer)?;
+                options.report(ConvertStage::Encoding, position.load(Ordering::Relaxed).min(input_bytes), input_bytes);
+                if !more {
+                    break;
+                }
+            }
+            options.report(ConvertStage::Encoding, input_bytes, input_bytes);
+            options.report(ConvertStage::Writing, 0, 0);
+            let output_bytes = encoder.finish()?;
+            options.report(ConvertStage::Writing, output_bytes, output_bytes);
+            Ok((output_bytes, frames))
+        };
+        let (output_bytes, frames, config_used) = match &output {
+            PipelineOutput::Wav(config) => {
+                let mut config = config.clone();
+                config.sample_rate = Some(sample_rate);
+                config.channels = Some(channels);
+                let mut encoder = WavStreamEncoder::new(writer, &config)?;
+                let (output_bytes, frames) = run(&mut encoder)?;
+                encoder.into_inner().into_inner().map_err(|e| e.into_error())?.commit()?;
+                let sample_format = config.input_sample_format()?;
+                (output_bytes, frames, AudioConfig::new(sample_rate, channels, sample_format.bits_per_sample()).with_sample_format(sample_format))
+            }
+            PipelineOutput::Mp3(config) => {
+                let mut config = config.clone();
+                config.sample_rate = sample_rate;
+                config.channels = channels;
+                config.target_sample_rate = None;
+                config.channel_map = None;
+                config.target_channels = None;
+                let mut encoder = Mp3StreamEncoder::new(writer, &config)?;
+                let (output_bytes, frames) = run(&mut encoder)?;
+                encoder.into_inner().into_inner().map_err(|e| e.into_error())?.commit()?;
+                (output_bytes, frames, AudioConfig::mp3_output(sample_rate, channels))
+            }
+            PipelineOutput::Pcm => {
+                let mut encoder = PcmStreamEncoder::new(writer);
+                let (output_bytes, frames) = run(&mut encoder)?;
+                encoder.into_inner().into_inner().map_err(|e| e.into_error())?.commit()?;
+                (output_bytes, frames, AudioConfig::new(sample_rate, channels, 16))
+            }
+        };
+
+        crate::log_conversion_done("Pipeline", &input, output_path, input_bytes, started);
+        Ok(ConversionReport {
+            input_bytes,
+            output_bytes,
+            duration: frames_duration(frames, sample_rate),
+            elapsed: started.elapsed(),
+            config_used,
+            format: output_format(&output),
+            output_path: output_path.to_path_buf(),
+            skipped: false,
+        })
+    }
+}
+
+fn output_format(output: &PipelineOutput) -> AudioFormat {
+    match output {
+        PipelineOutput::Wav(_) => AudioFormat::Wav,
+        PipelineOutput::Mp3(_) => AudioFormat::Mp3,
+        PipelineOutput::Pcm => AudioFormat::Pcm,
+    }
+}
+
+/// 执行全部步骤之后的采样率和声道数
+fn output_shape(steps: &[Step], input: &AudioConfig) -> (u32, u8) {
+    steps.iter().fold((input.sample_rate, input.channels), |(sample_rate, channels), step| match step {
+        Step::Resample(rate) => (*rate, channels),
+        Step::Channels(target) => (sample_rate, *target),
+        Step::ChannelMap(map) => (sample_rate, map.output_channels()),
+        _ => (sample_rate, channels),
+    })
+}
+
+/// 执行前需要先测量整段信号的步骤
+fn needs_measure(step: &Step) -> bool {
+    match step {
+        Step::Normalize(_) => true,
+        Step::Fade(fade) => fade.fade_out_ms > 0,
+        _ => false,
+    }
+}
+
+/// 测量阶段得到的整段信号峰值和帧数
+#[derive(Debug, Clone, Copy, Default)]
+struct Measure {
+    peak: f32,
+    frames: u64,
+}
+
+impl Measure {
+    fn add(&mut self, samples: &[f32], channels: u8) {
+        self.peak = samples.iter().fold(self.peak, |peak, s| peak.max(s.abs()));
+        self.frames += (samples.len() / channels.max(1) as usize) as u64;
+    }
+}
+
+/// 带状态的处理步骤
+enum Stage {
+    Resample(StreamResampler),
+    Channels { from: u8, to: u8 },
+    ChannelMap { map: ChannelMap, from: u8 },
+    Gain(f32),
+    Fade { fade: FadeConfig, fade_in: usize, fade_out: usize, frames: usize, position: usize, channels: usize },
+    RemoveDc(DcFilter),
+    Filter(FilterProcessor),
+    NoiseGate(NoiseGate),
+    Limit(StreamLimiter),
+    Effect { index: usize, config: AudioConfig },
+}
+
+/// 按顺序连接的处理步骤
+struct Chain<'a> {
+    stages: Vec<Stage>,
+    effects: &'a mut [Box<dyn AudioEffect>],
+    /// 输出的采样率和声道数
+    sample_rate: u32,
+    channels: u8,
+}
+
+impl<'a> Chain<'a> {
+    fn new(steps: &[Step], resolved: &[Option<Measure>], input: &AudioConfig, effects: &'a mut [Box<dyn AudioEffect>]) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut sample_rate = input.sample_rate;
+        let mut channels = input.channels;
+        let mut stages = Vec::new();
+        for (step, measure) in steps.iter().zip(resolved) {
+            match step {
+                Step::Resample(rate) => {
+                    if *rate == 0 {
+                        return Err(AudioError::InvalidConfig("Invalid sample_rate: must be greater than zero".into()).into());
+                    }
+                    if *rate != sample_rate {
+                        stages.push(Stage::Resample(StreamResampler::new(channels, sample_rate, *rate)));
+                        sample_rate = *rate;
+                    }
+                }
+                Step::Channels(target) => {
+                    // 提前检查声道转换是否支持
+                    dsp::convert_channels::<f32>(&[], channels, *target)?;
+                    if *target != channels {
+                        stages.push(Stage::Channels { from: channels, to: *target });
+                        channels = *target;
+                    }
+                }
+                Step::ChannelMap(map) => {
+                    map.validate(channels)?;
+                    if !map.is_identity(channels) {
+                        stages.push(Stage::ChannelMap { map: map.clone(), from: channels });
+                        channels = map.output_channels();
+                    }
+                }
+                Step::Gain(gain_db) => stages.push(Stage::Gain(*gain_db)),
+                Step::Normalize(target_db) => {
+                    let peak = measure.map_or(0.0, |measure| measure.peak);
+                    // 与 normalize_peak 一样，全静音的数据保持不变
+                    if peak > 0.0 {
+                        stages.push(Stage::Gain(dsp::linear_to_db(dsp::db_to_linear(*target_db) / peak)));
+                    }
+                }
+                Step::Fade(fade) => {
+                    let frames = measure.map_or(usize::MAX, |measure| measure.frames as usize);
+                    let to_frames = |ms: u32| ((ms as u64 * sample_rate as u64 / 1000) as usize).min(frames);
+                    stages.push(Stage::Fade {
+                        fade: fade.clone(),
+                        fade_in: to_frames(fade.fade_in_ms),
+                        fade_out: if fade.fade_out_ms > 0 { to_frames(fade.fade_out_ms) } else { 0 },
+                        frames,
+                        position: 0,
+                        channels: channels.max(1) as usize,
+                    });
+                }
+                Step::RemoveDc => stages.push(Stage::RemoveDc(DcFilter::new(channels, sample_rate))),
+                Step::Filter(filters) => stages.push(Stage::Filter(FilterProcessor::new(filters, channels, sample_rate)?)),
+                Step::NoiseGate(gate) => stages.push(Stage::NoiseGate(NoiseGate::new(channels, sample_rate, gate))),
+                Step::Limit(limiter) => stages.push(Stage::Limit(StreamLimiter::new(channels, sample_rate, limiter))),
+                Step::Effect(index) => {
+                    effects[*index].reset();
+                    let config = AudioConfig::new(sample_rate, channels, 32).with_sample_format(SampleFormat::F32);
+                    stages.push(Stage::Effect { index: *index, config });
+                }
+            }
+        }
+        Ok(Chain { stages, effects, sample_rate, channels })
+    }
+
+    /// 依次执行各步骤；`finish` 为 true 时输出重采样器和限制器中剩余的样本
+    fn process(&mut self, mut samples: Vec<f32>, finish: bool) -> Result<Vec<f32>, Box<dyn std::error::Error>> {
+        for stage in &mut self.stages {
+            match stage {
+                Stage::Resample(resampler) => {
+                    let mut output = Vec::with_capacity(samples.len());
+                    resampler.process(&samples, &mut output);
+                    if finish {
+                        resampler.finish(&mut output);
+                    }
+                    samples = output;
+                }
+                Stage::Channels { from, to } => samples = dsp::convert_channels(&samples, *from, *to)?,
+                Stage::ChannelMap { map, from } => samples = map.apply(&samples, *from)?,
+                Stage::Gain(gain_db) => {
+                    dsp::apply_gain(&mut samples, *gain_db);
+                }
+                Stage::Fade { fade, fade_in, fade_out, frames, position, channels } => {
+                    for frame in samples.chunks_exact_mut(*channels) {
+                        let gain = dsp::fade_gain(&fade.curve, *position, *frames, *fade_in, *fade_out);
+                        if gain < 1.0 {
+                            frame.iter_mut().for_each(|s| *s *= gain);
+                        }
+                        *position += 1;
+                    }
+                }
+                Stage::RemoveDc(filter) => filter.process(&mut samples),
+                Stage::Filter(filters) => filters.process(&mut samples),
+                Stage::NoiseGate(gate) => gate.process(&mut samples),
+                Stage::Limit(limiter) => {
+                    let mut output = Vec::with_capacity(samples.len());
+                    limiter.process(&samples, &mut output);
+                    if finish {
+                        limiter.finish(&mut output);
+                    }
+                    samples = output;
+                }
+                Stage::Effect { index, config } => {
+                    if !samples.is_empty() {
+                        self.effects[*index].process(&mut samples, config);
+                    }
+                }
+            }
+        }
+        Ok(samples)
+    }
+}
+
+/// 解码下一块样本并送入处理链，结果放入 `output`；返回 false 表示输入已经结束（`output` 为处理链中剩余的样本）
+fn pull(decoder: &mut dyn AudioDecoder, chain: &mut Chain, samples: &mut Vec<i16>, output: &mut Vec<f32>) -> Result<bool, Box<dyn std::error::Error>> {
+    samples.clear();
+    let more = decoder.decode(samples)? > 0;
+    let input: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+    *output = chain.process(input, !more)?;
+    Ok(more)
+}
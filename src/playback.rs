@@ -0,0 +1,119 @@
+// 音频回放：批量转换前用给定参数试听 PCM 文件，确认采样率、声道数和位深度是否正确
+
+use std::fs::File;
+use std::io::BufReader;
+use std::num::NonZero;
+use std::path::Path;
+
+use rodio::buffer::SamplesBuffer;
+use rodio::{Decoder, DeviceSinkBuilder, Player, Source};
+
+use crate::{probe, read_wav_file, sample, AudioConfig, AudioError, DetectedFormat, Endianness};
+
+/// 将原始 PCM 数据按配置解码为交错的 f32 样本，并应用配置中的声道转换、增益等处理
+///
+/// # Returns
+/// 处理后的样本和声道数
+fn decode_pcm_for_playback(data: &[u8], config: &AudioConfig) -> Result<(Vec<f32>, u8), Box<dyn std::error::Error>> {
+    if config.sample_rate == 0 || config.channels == 0 {
+        return Err(AudioError::InvalidConfig(format!("Invalid audio config: {} Hz, {} channels", config.sample_rate, config.channels)).into());
+    }
+    let samples = if config.endianness == Endianness::Big {
+        let mut data = data.to_vec();
+        sample::swap_sample_bytes(&mut data, config.sample_format);
+        sample::decode_samples_f32(&data, config.sample_format)
+    } else {
+        sample::decode_samples_f32(data, config.sample_format)
+    };
+    config.processing().apply(samples, config.channels, config.sample_rate)
+}
+
+/// 由交错样本构造可播放的音源
+fn samples_source(samples: Vec<f32>, channels: u8, sample_rate: u32) -> Result<SamplesBuffer, Box<dyn std::error::Error>> {
+    let channels = NonZero::new(u16::from(channels)).ok_or_else(|| AudioError::InvalidConfig("Invalid channel count: 0".into()))?;
+    let sample_rate = NonZero::new(sample_rate).ok_or_else(|| AudioError::InvalidConfig("Invalid sample rate: 0".into()))?;
+    Ok(SamplesBuffer::new(channels, sample_rate, samples))
+}
+
+/// 在默认输出设备上播放音源，阻塞到播放结束
+fn play_source(source: impl Source + Send + 'static) -> Result<(), Box<dyn std::error::Error>> {
+    let mut device = DeviceSinkBuilder::open_default_sink()?;
+    device.log_on_drop(false);
+    let player = Player::connect_new(device.mixer());
+    player.append(source);
+    player.sleep_until_end();
+    Ok(())
+}
+
+/// 按给定参数播放原始 PCM 数据，阻塞到播放结束
+///
+/// # Arguments
+/// * `data` - 原始 PCM 数据
+/// * `config` - 音频参数（采样率、声道数、样本格式、字节序及转换时的处理选项）
+pub fn play_pcm(data: &[u8], config: AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let (samples, channels) = decode_pcm_for_playback(data, &config)?;
+    play_source(samples_source(samples, channels, config.sample_rate)?)
+}
+
+/// 播放 WAV、MP3 或原始 PCM 文件，阻塞到播放结束
+///
+/// 格式由文件头判断，原始 PCM 的参数从文件名推断
+///
+/// # Arguments
+/// * `path` - 音频文件路径
+pub fn play_file(path: impl AsRef<Path>) -> Result<(), Box<dyn std::error::Error>> {
+    let path = path.as_ref();
+    let filename = path.file_name().ok_or_else(|| AudioError::InvalidInput("无效的文件路径".into()))?.to_string_lossy();
+    play_file_with_config(path, &crate::infer_audio_config_from_filename(&filename))
+}
+
+/// 播放音频文件，原始 PCM 使用给定的参数（WAV 和 MP3 以文件头为准）
+///
+/// # Arguments
+/// * `path` - 音频文件路径
+/// * `pcm_config` - 原始 PCM 的音频参数
+pub fn play_file_with_config(path: impl AsRef<Path>, pcm_config: &AudioConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let path = path.as_ref();
+    match probe::probe_file(path)? {
+        DetectedFormat::Wav(_) => {
+            let audio = read_wav_file(path)?;
+            play_source(samples_source(audio.samples_f32(), audio.config.channels, audio.config.sample_rate)?)
+        }
+        DetectedFormat::Mp3 => play_source(Decoder::new(BufReader::new(File::open(path)?))?),
+        DetectedFormat::Flac => Err(AudioError::UnsupportedFormat("Playing FLAC files is not supported".into()).into()),
+        DetectedFormat::Ogg => Err(AudioError::UnsupportedFormat("Playing Ogg files is not supported".into()).into()),
+        format @ DetectedFormat::Registered(_) => {
+            let (mut decoder, _) = crate::convert::open_decoder(path, format, None)?;
+            let config = decoder.config();
+            let samples = decoder.decode_to_end()?.iter().map(|&s| s as f32 / 32768.0).collect();
+            play_source(samples_source(samples, config.channels, config.sample_rate)?)
+        }
+        DetectedFormat::Unknown => play_pcm(&std::fs::read(path)?, pcm_config.clone()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_pcm_for_playback() {
+        let data = [0x00, 0x40, 0x00, 0xC0];
+        let (samples, channels) = decode_pcm_for_playback(&data, &AudioConfig::new(8000, 1, 16)).unwrap();
+        assert_eq!(channels, 1);
+        assert_eq!(samples, vec![0.5, -0.5]);
+
+        // 大端数据与交换字节后的小端数据解码结果一致
+        let big = AudioConfig::new(8000, 1, 16).with_endianness(Endianness::Big);
+        let (samples, _) = decode_pcm_for_playback(&[0x40, 0x00, 0xC0, 0x00], &big).unwrap();
+        assert_eq!(samples, vec![0.5, -0.5]);
+
+        // 配置中的声道转换在播放前生效
+        let stereo = AudioConfig::new(8000, 1, 16).with_target_channels(2);
+        let (samples, channels) = decode_pcm_for_playback(&data, &stereo).unwrap();
+        assert_eq!(channels, 2);
+        assert_eq!(samples.len(), 4);
+
+        assert!(decode_pcm_for_playback(&data, &AudioConfig::new(0, 1, 16)).is_err());
+    }
+}
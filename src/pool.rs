@@ -0,0 +1,204 @@
+// 编码器复用：批量转换大量短音频时按配置保留 MP3 编码器，省去每个文件的配置校验和编码器初始化
+//
+// LAME 3.100 没有清除心理声学模型和 MDCT 状态的接口（flush 之后内部仍保留上一段末尾的 PCM），
+// 编码过的句柄不能直接用于下一个文件，否则上一个文件的结尾会出现在下一个文件开头。
+// 因此 `reset` 为下一次转换初始化一个新的句柄备用，初始化发生在两次转换之间，转换开始时直接使用。
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::mp3::StreamEncoder;
+use crate::{atomic, input, probe, AudioConfig, AudioError, AudioFormat, ConversionReport, ConvertOptions, Mp3Config};
+
+/// 可重复使用的 MP3 编码器：每次 `encode` 输出一个完整的 MP3 文件（含 Xing/LAME 标签）
+pub struct Mp3Encoder {
+    config: Mp3Config,
+    /// 备用的编码器，以及创建它时的编码参数（声道转换和重采样之后）
+    ready: Option<(Mp3Config, StreamEncoder)>,
+    /// 最近一次编码使用的编码参数，`reset` 按它准备下一个编码器
+    last_output: Option<Mp3Config>,
+}
+
+impl Mp3Encoder {
+    /// 按配置创建编码器，配置无效时返回 `AudioError::InvalidConfig`
+    ///
+    /// 不需要声道转换和重采样的配置会立即初始化 LAME 句柄，其余配置在第一次编码时初始化
+    pub fn new(config: Mp3Config) -> Result<Self, Box<dyn std::error::Error>> {
+        config.validate()?;
+        let direct = config.processing().is_identity(config.channels) && config.output_sample_rate() == config.sample_rate;
+        let mut encoder = Mp3Encoder { last_output: direct.then(|| config.clone()), config, ready: None };
+        encoder.reset()?;
+        Ok(encoder)
+    }
+
+    /// 编码配置
+    pub fn config(&self) -> &Mp3Config {
+        &self.config
+    }
+
+    /// 是否已有初始化好的 LAME 句柄，下一次 `encode` 可以直接使用
+    pub fn is_ready(&self) -> bool {
+        self.ready.is_some()
+    }
+
+    /// 将内存中的 PCM 数据编码为一个完整的 MP3 文件
+    /// # Arguments
+    /// * `pcm_data` - 原始 PCM 数据，格式与配置一致
+    /// # Returns
+    /// * `Result<Vec<u8>, Box<dyn std::error::Error>>` - MP3 数据，结果与 `trans_pcm_bytes_to_mp3` 相同
+    pub fn encode(&mut self, pcm_data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let detected = probe::detect_format(pcm_data);
+        if detected.is_container() {
+            return Err(AudioError::InvalidInput(format!("Input data is a {:?} file, not raw PCM", detected)).into());
+        }
+        let (mp3_data, _, _) = self.encode_with_options(pcm_data, &mut ConvertOptions::default())?;
+        Ok(mp3_data)
+    }
+
+    /// 转换 PCM 文件为 MP3 文件，输出原子写入，已存在的输出文件会被覆盖
+    /// # Arguments
+    /// * `input_path` - 输入 PCM 文件路径
+    /// * `output_path` - 输出 MP3 文件路径
+    /// # Returns
+    /// * `Result<ConversionReport, Box<dyn std::error::Error>>` - 转换统计
+    pub fn convert_file(&mut self, input_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        let input_path = input_path.as_ref();
+        let output_path = output_path.as_ref();
+        let started = Instant::now();
+        let detected = probe::probe_file(input_path)?;
+        if detected.is_container() {
+            return Err(AudioError::InvalidInput(format!("Input file is a {:?} file, not raw PCM", detected)).into());
+        }
+        let pcm_data = input::read_file(input_path)?;
+        let (mp3_data, mp3_config, duration) = self.encode_with_options(&pcm_data, &mut ConvertOptions::default())?;
+        atomic::write_atomic(output_path, &mp3_data)?;
+        crate::log_conversion_done("MP3", input_path, output_path, pcm_data.len() as u64, started);
+
+        Ok(ConversionReport {
+            input_bytes: pcm_data.len() as u64,
+            output_bytes: mp3_data.len() as u64,
+            duration,
+            elapsed: started.elapsed(),
+            config_used: AudioConfig::mp3_output(mp3_config.sample_rate, mp3_config.channels),
+            format: AudioFormat::Mp3,
+            output_path: output_path.to_path_buf(),
+            skipped: false,
+        })
+    }
+
+    /// 为下一次编码准备一个新的 LAME 句柄；编码之后不调用时，下一次 `encode` 开始时再初始化
+    ///
+    /// 编码出错或被取消后也可以调用，丢弃状态不确定的句柄
+    pub fn reset(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        self.ready = None;
+        if let Some(output) = &self.last_output {
+            self.ready = Some((output.clone(), StreamEncoder::new(output, true)?));
+        }
+        Ok(())
+    }
+
+    fn encode_with_options(&mut self, pcm_data: &[u8], options: &mut ConvertOptions) -> Result<(Vec<u8>, Mp3Config, std::time::Duration), Box<dyn std::error::Error>> {
+        let ready = self.ready.take();
+        let result = crate::encode_pcm_to_mp3_with(pcm_data, self.config.clone(), options, |output| match ready {
+            Some((config, encoder)) if config == *output => Ok(encoder),
+            _ => StreamEncoder::new(output, true),
+        });
+        if let Ok((_, output, _)) = &result {
+            self.last_output = Some(output.clone());
+        }
+        result
+    }
+}
+
+/// 按配置缓存 `Mp3Encoder` 的编码器池，可以在多个线程间共享
+///
+/// 用 `get` 取出与配置相同的空闲编码器（没有时新建），`PooledEncoder` 释放时自动 `reset` 并放回池中
+pub struct EncoderPool {
+    idle: Mutex<Vec<Mp3Encoder>>,
+    max_idle: usize,
+}
+
+impl Default for EncoderPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EncoderPool {
+    /// 创建编码器池，默认最多保留 `available_parallelism` 个空闲编码器
+    pub fn new() -> Self {
+        let max_idle = std::thread::available_parallelism().map_or(1, |n| n.get());
+        EncoderPool { idle: Mutex::new(Vec::new()), max_idle }
+    }
+
+    /// 设置最多保留的空闲编码器数量，超出的编码器释放时直接销毁
+    pub fn with_max_idle(mut self, max_idle: usize) -> Self {
+        self.max_idle = max_idle;
+        self
+    }
+
+    /// 当前空闲的编码器数量
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().unwrap_or_else(|e| e.into_inner()).len()
+    }
+
+    /// 取出一个配置相同的编码器，没有空闲编码器时新建
+    pub fn get(&self, config: &Mp3Config) -> Result<PooledEncoder<'_>, Box<dyn std::error::Error>> {
+        let reused = {
+            let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+            idle.iter().position(|encoder| encoder.config == *config).map(|index| idle.swap_remove(index))
+        };
+        let encoder = match reused {
+            Some(encoder) => encoder,
+            None => Mp3Encoder::new(config.clone())?,
+        };
+        Ok(PooledEncoder { pool: self, encoder: Some(encoder) })
+    }
+
+    /// 使用池中的编码器将内存中的 PCM 数据编码为 MP3
+    pub fn encode(&self, config: &Mp3Config, pcm_data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        self.get(config)?.encode(pcm_data)
+    }
+
+    /// 使用池中的编码器转换 PCM 文件为 MP3 文件
+    pub fn convert_file(&self, input_path: impl AsRef<Path>, output_path: impl AsRef<Path>, config: &Mp3Config) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        self.get(config)?.convert_file(input_path, output_path)
+    }
+}
+
+/// 从编码器池中取出的编码器，释放时放回池中
+pub struct PooledEncoder<'a> {
+    pool: &'a EncoderPool,
+    encoder: Option<Mp3Encoder>,
+}
+
+impl std::ops::Deref for PooledEncoder<'_> {
+    type Target = Mp3Encoder;
+
+    fn deref(&self) -> &Mp3Encoder {
+        self.encoder.as_ref().expect("encoder is present until drop")
+    }
+}
+
+impl std::ops::DerefMut for PooledEncoder<'_> {
+    fn deref_mut(&mut self) -> &mut Mp3Encoder {
+        self.encoder.as_mut().expect("encoder is present until drop")
+    }
+}
+
+impl Drop for PooledEncoder<'_> {
+    fn drop(&mut self) {
+        let Some(mut encoder) = self.encoder.take() else {
+            return;
+        };
+        // 无法初始化新句柄的编码器不再放回池中
+        if encoder.reset().is_err() {
+            return;
+        }
+        let mut idle = self.pool.idle.lock().unwrap_or_else(|e| e.into_inner());
+        if idle.len() < self.pool.max_idle {
+            idle.push(encoder);
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// 命名质量预设：按用途给出完整的输出采样率、码率和处理选项，调用方不必逐项调参
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+use crate::{
+    audio_config_to_mp3_config, infer_audio_config_from_filename, trans_pcm_file_to_mp3, AudioConfig, AudioError, AudioQuality,
+    ConversionReport, LimiterConfig, Mp3Bitrate, Mp3Config, Mp3RateMode, VbrQuality,
+};
+
+/// 常用场景的编码预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Preset {
+    /// 语音（录音、客服、语音识别）：16kHz 单声道 64kbps，去除直流偏移并归一化到 -1 dBFS
+    Voice,
+    /// 音乐：44.1kHz VBR V2（约 190kbps），保留原始动态
+    Music,
+    /// 播客：44.1kHz 单声道 96kbps 平均码率，去除直流偏移、归一化到 -1 dBFS 并限制真峰值不超过 -1 dBTP
+    Podcast,
+    /// 归档：保持输入采样率，320kbps 最佳质量，不做任何处理
+    Archive,
+}
+
+impl Preset {
+    /// 全部内置预设
+    pub const ALL: [Preset; 4] = [Preset::Voice, Preset::Music, Preset::Podcast, Preset::Archive];
+
+    /// 预设名称（小写，与命令行参数一致）
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Voice => "voice",
+            Preset::Music => "music",
+            Preset::Podcast => "podcast",
+            Preset::Archive => "archive",
+        }
+    }
+
+    /// 按名称查找预设（不区分大小写）
+    pub fn from_name(name: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|preset| preset.name().eq_ignore_ascii_case(name))
+    }
+
+    /// 将预设的声道转换和处理选项应用到输入 PCM 的参数上
+    ///
+    /// 返回的配置保留输入的采样率、声道数和样本格式，可用于 WAV 输出
+    pub fn audio_config(&self, input: &AudioConfig) -> AudioConfig {
+        let mut config = input.clone();
+        config.target_channels = self.target_channels(input.channels);
+        match self {
+            Preset::Voice => {
+                config.normalize_db = Some(-1.0);
+                config.remove_dc = true;
+            }
+            Preset::Podcast => {
+                config.normalize_db = Some(-1.0);
+                config.remove_dc = true;
+                // 播客平台要求解码后的真峰值不超过 -1 dBTP
+                config.limiter = Some(LimiterConfig::new(-1.0));
+            }
+            Preset::Music | Preset::Archive => {}
+        }
+        config
+    }
+
+    /// 输入 PCM 按预设编码为 MP3 时使用的完整配置
+    ///
+    /// # Arguments
+    /// * `input` - 输入 PCM 的参数（采样率、声道数、样本格式）
+    pub fn mp3_config(&self, input: &AudioConfig) -> Mp3Config {
+        self.encode_config(&self.audio_config(input))
+    }
+
+    /// 按预设的输出采样率、码率和质量创建 MP3 配置，处理选项取自 `audio_config`
+    pub(crate) fn encode_config(&self, audio_config: &AudioConfig) -> Mp3Config {
+        let (target_sample_rate, rate_mode, quality) = match self {
+            Preset::Voice => (Some(16000), Mp3RateMode::Cbr(Mp3Bitrate::Kbps64), AudioQuality::Medium),
+            Preset::Music => (Some(44100), Mp3RateMode::Vbr(VbrQuality::V2), AudioQuality::High),
+            Preset::Podcast => (Some(44100), Mp3RateMode::Abr(96), AudioQuality::High),
+            Preset::Archive => (None, Mp3RateMode::Cbr(Mp3Bitrate::Kbps320), AudioQuality::Best),
+        };
+        let mut config = audio_config_to_mp3_config(audio_config, Mp3Bitrate::Kbps192, quality).with_rate_mode(rate_mode);
+        config.target_sample_rate = target_sample_rate;
+        // 低于 32kHz 的输入以 MPEG-2 编码，320kbps 超出范围
+        if *self == Preset::Archive && config.output_sample_rate() < 32000 {
+            config.rate_mode = Mp3RateMode::Cbr(Mp3Bitrate::Kbps128);
+        }
+        config
+    }
+
+    /// 预设输出的声道数：语音和播客混缩为单声道，其余保持输入声道（多声道混缩为立体声）
+    fn target_channels(&self, input_channels: u8) -> Option<u8> {
+        match self {
+            Preset::Voice | Preset::Podcast => Some(1),
+            Preset::Music | Preset::Archive if input_channels > 2 => Some(2),
+            Preset::Music | Preset::Archive => None,
+        }
+    }
+}
+
+/// 按预设将 PCM 文件转换为 MP3，输入参数从文件名推断
+///
+/// # Arguments
+/// * `input_path` - 输入 PCM 文件路径
+/// * `output_path` - 输出 MP3 文件路径
+/// * `preset` - 编码预设
+///
+/// # Returns
+/// * 转换报告
+pub fn trans_pcm_file_to_mp3_preset(
+    input_path: impl AsRef<Path>,
+    output_path: impl AsRef<Path>,
+    preset: Preset,
+) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+    let input_path = input_path.as_ref();
+    let filename = input_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let input = infer_audio_config_from_filename(&filename);
+    log::info!("Converting {} with preset {}", input_path.display(), preset.name());
+    trans_pcm_file_to_mp3(input_path, output_path, Some(preset.mp3_config(&input)))
+}
+
+/// 运行时注册的自定义预设，名称统一转为小写
+static CUSTOM_PRESETS: RwLock<BTreeMap<String, Mp3Config>> = RwLock::new(BTreeMap::new());
+
+/// 预设注册表：按名称解析内置预设和运行时注册的自定义预设（名称不区分大小写）
+///
+/// 在程序启动时注册一次，批量转换和 FFI 调用中按名称引用，整个组织的编码参数只需维护一处。
+///
+/// ```
+/// use audio_helper::{AudioConfig, AudioQuality, Mp3Bitrate, Mp3Config, Presets};
+///
+/// let template = Mp3Config::new(8000, 1, Mp3Bitrate::Kbps64, AudioQuality::Medium).with_target_sample_rate(8000);
+/// Presets::register("callcenter", template).unwrap();
+/// let config = Presets::mp3_config("callcenter", &AudioConfig::new(16000, 1, 16)).unwrap();
+/// assert_eq!((config.sample_rate, config.target_sample_rate), (16000, Some(8000)));
+/// ```
+pub struct Presets;
+
+impl Presets {
+    /// 注册自定义预设，同名的自定义预设会被替换；内置预设的名称不能使用
+    ///
+    /// # Arguments
+    /// * `name` - 预设名称
+    /// * `config` - 预设模板：提供码率、质量、输出采样率和处理选项；其中的采样率、声道数、
+    ///   样本格式和字节序在使用时替换为输入文件的参数
+    pub fn register(name: &str, config: Mp3Config) -> Result<(), Box<dyn std::error::Error>> {
+        let key = name.trim().to_lowercase();
+        if key.is_empty() {
+            return Err(AudioError::InvalidInput("Preset name must not be empty".into()).into());
+        }
+        if Preset::from_name(&key).is_some() {
+            return Err(AudioError::InvalidInput(format!("Preset name {} is reserved for a built-in preset", key)).into());
+        }
+        config.validate().map_err(|e| AudioError::InvalidConfig(format!("Invalid preset {}: {}", key, e)))?;
+        log::debug!("Registering preset {}: {:?}", key, config);
+        CUSTOM_PRESETS.write().unwrap_or_else(|e| e.into_inner()).insert(key, config);
+        Ok(())
+    }
+
+    /// 移除自定义预设，返回是否存在
+    pub fn unregister(name: &str) -> bool {
+        CUSTOM_PRESETS.write().unwrap_or_else(|e| e.into_inner()).remove(&name.trim().to_lowercase()).is_some()
+    }
+
+    /// 名称是否对应内置或已注册的预设
+    pub fn contains(name: &str) -> bool {
+        Preset::from_name(name.trim()).is_some()
+            || CUSTOM_PRESETS.read().unwrap_or_else(|e| e.into_inner()).contains_key(&name.trim().to_lowercase())
+    }
+
+    /// 全部可用的预设名称：内置预设在前，自定义预设按名称排序
+    pub fn names() -> Vec<String> {
+        let custom = CUSTOM_PRESETS.read().unwrap_or_else(|e| e.into_inner());
+        Preset::ALL.iter().map(|preset| preset.name().to_string()).chain(custom.keys().cloned()).collect()
+    }
+
+    /// 按名称解析预设，得到输入 PCM 编码为 MP3 时使用的完整配置
+    ///
+    /// # Arguments
+    /// * `name` - 内置或已注册的预设名称
+    /// * `input` - 输入 PCM 的参数
+    pub fn mp3_config(name: &str, input: &AudioConfig) -> Result<Mp3Config, Box<dyn std::error::Error>> {
+        if let Some(preset) = Preset::from_name(name.trim()) {
+            return Ok(preset.mp3_config(input));
+        }
+        let custom = CUSTOM_PRESETS.read().unwrap_or_else(|e| e.into_inner());
+        let template = custom.get(&name.trim().to_lowercase()).ok_or_else(|| AudioError::InvalidInput(format!("Unknown preset: {}", name)))?;
+        let mut config = template.clone();
+        config.sample_rate = input.sample_rate;
+        config.channels = input.channels;
+        config.sample_format = input.sample_format;
+        config.endianness = input.endianness;
+        Ok(config)
+    }
+}
+
+/// 按名称解析预设并将 PCM 文件转换为 MP3，输入参数从文件名推断
+///
+/// # Returns
+/// * 从文件名推断的输入参数
+pub(crate) fn convert_with_named_preset(input_path: &Path, output_path: &Path, name: &str) -> Result<AudioConfig, Box<dyn std::error::Error>> {
+    let filename = input_path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let input = infer_audio_config_from_filename(&filename);
+    let config = Presets::mp3_config(name, &input)?;
+    log::info!("Converting {} with preset {}", input_path.display(), name);
+    trans_pcm_file_to_mp3(input_path, output_path, Some(config))?;
+    Ok(input)
+}
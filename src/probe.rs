@@ -0,0 +1,206 @@
+// 格式探测：根据文件开头的特征字节识别音频格式，而不是信任扩展名
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::manifest::{config_json, json_string};
+use crate::registry;
+use crate::wav::{self, WavChunk, WavContainer, WavParseMode};
+use crate::{frames_duration, mp3, AudioConfig, Mp3Info};
+
+/// 探测时读取的文件头字节数（足够容纳两个最大的 MP3 帧头）
+const PROBE_BYTES: usize = 4096;
+
+/// 按文件内容识别出的格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DetectedFormat {
+    /// WAV 文件（RIFF / RF64 / Wave64）
+    Wav(WavContainer),
+    /// MP3 文件（ID3v2 标签或连续的 MPEG Layer III 帧）
+    Mp3,
+    /// FLAC 文件
+    Flac,
+    /// Ogg 容器（Vorbis / Opus 等）
+    Ogg,
+    /// 由 `FormatRegistry` 中注册的处理器识别（值为注册的扩展名）
+    Registered(&'static str),
+    /// 没有可识别的文件头，可能是原始 PCM
+    Unknown,
+}
+
+impl DetectedFormat {
+    /// 是否为带文件头的已知容器（不能按原始 PCM 处理）
+    pub fn is_container(&self) -> bool {
+        *self != DetectedFormat::Unknown
+    }
+}
+
+/// 根据开头的字节识别格式：内置的 WAV 和 MP3 优先，其余交给已注册的格式处理器
+pub fn detect_format(header: &[u8]) -> DetectedFormat {
+    match detect_builtin(header) {
+        format @ (DetectedFormat::Wav(_) | DetectedFormat::Mp3) => format,
+        format => registry::detect(header).map_or(format, DetectedFormat::Registered),
+    }
+}
+
+fn detect_builtin(header: &[u8]) -> DetectedFormat {
+    if let Some(container) = wav::detect_container(header) {
+        return DetectedFormat::Wav(container);
+    }
+    if header.starts_with(b"fLaC") {
+        return DetectedFormat::Flac;
+    }
+    if header.starts_with(b"OggS") {
+        return DetectedFormat::Ogg;
+    }
+    if header.starts_with(b"ID3") {
+        return DetectedFormat::Mp3;
+    }
+    // 单个同步字在原始 PCM 中也可能出现，要求紧接着还有一个合法的帧头
+    if let Some(frame) = mp3::parse_frame_header(header)
+        && header.get(frame.frame_size..).and_then(mp3::parse_frame_header).is_some()
+    {
+        return DetectedFormat::Mp3;
+    }
+    DetectedFormat::Unknown
+}
+
+/// 读取文件开头的字节识别格式；没有可识别文件头时按扩展名查找已注册的格式
+pub(crate) fn probe_file(path: &Path) -> std::io::Result<DetectedFormat> {
+    let mut header = Vec::with_capacity(PROBE_BYTES);
+    File::open(path)?.take(PROBE_BYTES as u64).read_to_end(&mut header)?;
+    let format = detect_format(&header);
+    if format == DetectedFormat::Unknown
+        && let Some(name) = path.extension().and_then(|ext| ext.to_str()).and_then(registry::by_extension)
+    {
+        return Ok(DetectedFormat::Registered(name));
+    }
+    Ok(format)
+}
+
+/// 音频文件信息
+#[derive(Debug, Clone, PartialEq)]
+pub struct AudioInfo {
+    /// 按内容识别出的格式
+    pub format: DetectedFormat,
+    /// 文件字节数
+    pub file_size: u64,
+    /// 音频参数：WAV 从文件头读取，原始 PCM 为给定的参数，已注册的格式为解码器的输出参数，FLAC / Ogg / MP3 为 None
+    pub config: Option<AudioConfig>,
+    /// 音频时长，无法计算时为 None
+    pub duration: Option<Duration>,
+    /// 音频数据字节数：WAV 为 data 块大小，其余格式为文件大小
+    pub data_size: u64,
+    /// WAV 文件的顶层块布局，其余格式为空
+    pub chunks: Vec<WavChunk>,
+    /// MP3 文件的逐帧统计，其余格式为 None
+    pub mp3: Option<Mp3Info>,
+}
+
+impl AudioInfo {
+    /// 转换为 JSON 对象，便于脚本处理
+    pub fn to_json(&self) -> String {
+        let (format, container) = match self.format {
+            DetectedFormat::Wav(container) => ("wav", Some(container)),
+            DetectedFormat::Mp3 => ("mp3", None),
+            DetectedFormat::Flac => ("flac", None),
+            DetectedFormat::Ogg => ("ogg", None),
+            DetectedFormat::Registered(name) => (name, None),
+            DetectedFormat::Unknown => ("pcm", None),
+        };
+        let mut fields = vec![format!("\"format\": \"{}\"", format)];
+        if let Some(container) = container {
+            fields.push(format!("\"container\": \"{}\"", format!("{:?}", container).to_ascii_lowercase()));
+        }
+        fields.push(format!("\"file_size\": {}", self.file_size));
+        fields.push(format!("\"data_size\": {}", self.data_size));
+        fields.push(format!("\"config\": {}", self.config.as_ref().map_or("null".to_string(), config_json)));
+        fields.push(format!("\"duration_secs\": {}", self.duration.map_or("null".to_string(), |d| format!("{:.6}", d.as_secs_f64()))));
+        let chunks: Vec<String> = self
+            .chunks
+            .iter()
+            .map(|chunk| format!("{{\"id\": {}, \"offset\": {}, \"size\": {}}}", json_string(&chunk.id), chunk.offset, chunk.size))
+            .collect();
+        fields.push(format!("\"chunks\": [{}]", chunks.join(", ")));
+        let mp3 = self.mp3.as_ref().map_or("null".to_string(), |mp3| {
+            format!(
+                "{{\"bitrate_mode\": \"{}\", \"average_bitrate_kbps\": {}, \"frame_count\": {}, \"encoder_delay\": {}, \"padding\": {}, \"has_xing\": {}}}",
+                format!("{:?}", mp3.bitrate_mode).to_ascii_lowercase(),
+                mp3.average_bitrate_kbps,
+                mp3.frame_count,
+                mp3.encoder_delay,
+                mp3.padding,
+                mp3.has_xing
+            )
+        });
+        fields.push(format!("\"mp3\": {}", mp3));
+        format!("{{{}}}", fields.join(", "))
+    }
+}
+
+/// 读取文件的格式、音频参数、时长和块布局，不读取整个样本数据（MP3 需要逐帧遍历）
+pub(crate) fn probe_audio_file(path: &Path, pcm_config: &AudioConfig) -> Result<AudioInfo, Box<dyn std::error::Error>> {
+    let format = probe_file(path)?;
+    let file_size = std::fs::metadata(path)?.len();
+    let mut info = AudioInfo { format, file_size, config: None, duration: None, data_size: file_size, chunks: Vec::new(), mp3: None };
+    match format {
+        DetectedFormat::Wav(container) => {
+            let mut file = File::open(path)?;
+            info.chunks = wav::read_chunks(&mut file, file_size)?;
+            if container == WavContainer::Wave64 {
+                // Wave64 只支持写入，参数无法读取
+                if let Some(data) = info.chunks.iter().find(|chunk| chunk.id == "data") {
+                    info.data_size = data.size;
+                }
+            } else {
+                file.seek(SeekFrom::Start(0))?;
+                let wav_info = wav::read_wav_info(&mut file, file_size, WavParseMode::Lenient)?;
+                info.data_size = wav_info.data_size;
+                info.config = wav_info.config().ok();
+                info.duration = Some(frames_duration(wav_info.frames(), wav_info.sample_rate()));
+            }
+        }
+        DetectedFormat::Mp3 => {
+            info.mp3 = mp3::parse_mp3_info(&std::fs::read(path)?).ok();
+            info.duration = info.mp3.as_ref().map(|mp3| mp3.duration);
+        }
+        DetectedFormat::Unknown => {
+            let frame_size = (pcm_config.sample_format.bytes_per_sample() * pcm_config.channels as usize) as u64;
+            info.duration = file_size.checked_div(frame_size).map(|frames| frames_duration(frames, pcm_config.sample_rate));
+            info.config = Some(pcm_config.clone());
+        }
+        DetectedFormat::Registered(name) => {
+            let decoder = registry::open_decoder(name, Box::new(BufReader::new(File::open(path)?)))?;
+            info.config = Some(decoder.config());
+        }
+        DetectedFormat::Flac | DetectedFormat::Ogg => {}
+    }
+    Ok(info)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_signatures() {
+        assert_eq!(detect_format(b"RIFF\x24\x00\x00\x00WAVEfmt "), DetectedFormat::Wav(WavContainer::Riff));
+        assert_eq!(detect_format(b"RF64\xFF\xFF\xFF\xFFWAVEds64"), DetectedFormat::Wav(WavContainer::Rf64));
+        assert_eq!(detect_format(b"fLaC\x00\x00\x00\x22"), DetectedFormat::Flac);
+        assert_eq!(detect_format(b"OggS\x00\x02"), DetectedFormat::Ogg);
+        assert_eq!(detect_format(b"ID3\x04\x00\x00\x00\x00\x00\x00"), DetectedFormat::Mp3);
+        assert_eq!(detect_format(b"RIFF\x24\x00\x00\x00AVI "), DetectedFormat::Unknown);
+    }
+
+    #[test]
+    fn test_mp3_sync_requires_two_frames() {
+        // 128kbps / 44.1kHz 的帧长为 417 字节
+        let mut frames = vec![0u8; 417 + 4];
+        frames[..4].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        assert_eq!(detect_format(&frames), DetectedFormat::Unknown);
+        frames[417..].copy_from_slice(&[0xFF, 0xFB, 0x90, 0x00]);
+        assert_eq!(detect_format(&frames), DetectedFormat::Mp3);
+    }
+}
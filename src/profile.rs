@@ -0,0 +1,251 @@
+// 转换配置文件：用 TOML / JSON 文件描述输入输出格式和全部编码、处理选项，便于团队共享统一的转码参数
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    audio_config_to_mp3_config, audio_config_to_wav_config, infer_audio_config_from_filename, mp3, trans_pcm_file_to_mp3_with_options,
+    trans_pcm_file_to_wav_with_options, trans_wav_file_to_pcm_with_options, AudioConfig, AudioError, AudioFormat, AudioQuality,
+    ConversionReport, ConvertOptions, Endianness, FadeConfig, LimiterConfig, Mp3ChannelMode, Mp3Config, Mp3RateMode, PcmToWavConfig, Preset,
+    SampleFormat, WavContainer,
+};
+
+/// 配置文件中的 MP3 编码选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mp3ProfileOptions {
+    /// 码率模式（CBR / ABR / VBR），为 None 时按采样率选择默认的固定码率
+    pub rate_mode: Option<Mp3RateMode>,
+    pub quality: AudioQuality,
+    /// 声道模式，为 None 时由编码器自动选择
+    pub channel_mode: Option<Mp3ChannelMode>,
+    /// 采样率不被 MP3 支持时是否自动重采样
+    pub resample: bool,
+    /// 编码线程数，0 为使用全部可用核心
+    pub threads: usize,
+    /// 无缝编码，在 LAME 标签中记录编码器延迟和末尾填充
+    pub gapless: bool,
+}
+
+impl Default for Mp3ProfileOptions {
+    fn default() -> Self {
+        Mp3ProfileOptions {
+            rate_mode: None,
+            quality: AudioQuality::High,
+            channel_mode: None,
+            resample: true,
+            threads: 1,
+            gapless: false,
+        }
+    }
+}
+
+/// 配置文件中的 WAV 编码选项
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WavProfileOptions {
+    /// 容器格式，为 None 时自动选择
+    pub container: Option<WavContainer>,
+    /// 强制写入 WAVE_FORMAT_EXTENSIBLE 格式的 fmt 块
+    pub force_extensible: bool,
+    /// 将数据编码为 IMA ADPCM
+    pub ima_adpcm: bool,
+}
+
+/// 转换配置：输入输出格式、原始 PCM 参数、处理选项和编码选项
+///
+/// 原始 PCM 参数中未指定的项从输入文件名推断。所有字段都可省略，例如：
+///
+/// ```toml
+/// name = "voice"
+/// output_format = "Mp3"
+/// sample_rate = 16000
+/// channels = 1
+/// gain_db = -3.0
+///
+/// [mp3]
+/// rate_mode = { Abr = 96 }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConversionProfile {
+    /// 配置名称
+    pub name: Option<String>,
+    /// 输入格式（`Pcm` 或 `Wav`）
+    pub input_format: AudioFormat,
+    /// 输出格式
+    pub output_format: AudioFormat,
+    /// 原始 PCM 的采样率
+    pub sample_rate: Option<u32>,
+    /// 原始 PCM 的声道数
+    pub channels: Option<u8>,
+    /// 原始 PCM 的位深度
+    pub bits_per_sample: Option<u16>,
+    /// 原始 PCM 的样本格式，为 None 时根据位深度推断
+    pub sample_format: Option<SampleFormat>,
+    /// 原始 PCM 的字节序
+    pub endianness: Option<Endianness>,
+    /// 输出的声道数
+    pub target_channels: Option<u8>,
+    /// 增益（dB）
+    pub gain_db: Option<f32>,
+    /// 峰值归一化的目标电平（dBFS）
+    pub normalize_db: Option<f32>,
+    /// 淡入淡出
+    pub fade: Option<FadeConfig>,
+    /// 是否去除直流偏移
+    pub remove_dc: bool,
+    /// 峰值限制器
+    pub limiter: Option<LimiterConfig>,
+    /// 编码预设，提供输出采样率、码率、质量和处理选项；配置中显式指定的项优先
+    pub preset: Option<Preset>,
+    /// MP3 编码选项，设置了预设时只有 `rate_mode`、`channel_mode` 和 `resample` 生效
+    pub mp3: Mp3ProfileOptions,
+    /// WAV 编码选项
+    pub wav: WavProfileOptions,
+}
+
+impl Default for ConversionProfile {
+    fn default() -> Self {
+        ConversionProfile {
+            name: None,
+            input_format: AudioFormat::Pcm,
+            output_format: AudioFormat::Wav,
+            sample_rate: None,
+            channels: None,
+            bits_per_sample: None,
+            sample_format: None,
+            endianness: None,
+            target_channels: None,
+            gain_db: None,
+            normalize_db: None,
+            fade: None,
+            remove_dc: false,
+            limiter: None,
+            preset: None,
+            mp3: Mp3ProfileOptions::default(),
+            wav: WavProfileOptions::default(),
+        }
+    }
+}
+
+impl ConversionProfile {
+    /// 从文件加载配置，按扩展名解析为 TOML（`.toml`）或 JSON（`.json`）
+    ///
+    /// # Arguments
+    /// * `path` - 配置文件路径
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)?;
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default();
+        let profile = match extension.as_str() {
+            "toml" => Self::from_toml(&text),
+            "json" => Self::from_json(&text),
+            _ => return Err(AudioError::UnsupportedFormat(format!("Unsupported profile file (expected .toml or .json): {}", path.display())).into()),
+        };
+        profile.map_err(|e| AudioError::InvalidConfig(format!("Invalid profile {}: {}", path.display(), e)).into())
+    }
+
+    /// 解析 TOML 格式的配置
+    pub fn from_toml(text: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(toml::from_str(text)?)
+    }
+
+    /// 解析 JSON 格式的配置
+    pub fn from_json(text: &str) -> Result<Self, Box<dyn std::error::Error>> {
+        Ok(serde_json::from_str(text)?)
+    }
+
+    /// 序列化为 TOML，可写入文件后由 `from_file` 加载
+    pub fn to_toml(&self) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// 输入文件使用的音频配置：以从文件名推断的参数为基础，应用配置中指定的参数和处理选项
+    pub fn audio_config_for(&self, input_path: impl AsRef<Path>) -> Result<AudioConfig, Box<dyn std::error::Error>> {
+        let filename = input_path.as_ref().file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+        let mut config = infer_audio_config_from_filename(&filename);
+        if let Some(sample_rate) = self.sample_rate {
+            config.sample_rate = sample_rate;
+        }
+        if let Some(channels) = self.channels {
+            config.channels = channels;
+        }
+        if let Some(bits) = self.bits_per_sample {
+            let sample_format = SampleFormat::from_bits(bits).ok_or_else(|| AudioError::InvalidConfig(format!("Unsupported bit depth: {}", bits)))?;
+            config = config.with_sample_format(sample_format);
+        }
+        if let Some(sample_format) = self.sample_format {
+            config = config.with_sample_format(sample_format);
+        }
+        if let Some(endianness) = self.endianness {
+            config.endianness = endianness;
+        }
+        if let Some(preset) = self.preset {
+            config = preset.audio_config(&config);
+        }
+        config.target_channels = self.target_channels.or(config.target_channels);
+        config.gain_db = self.gain_db.or(config.gain_db);
+        config.normalize_db = self.normalize_db.or(config.normalize_db);
+        config.fade = self.fade.clone().or(config.fade);
+        config.remove_dc |= self.remove_dc;
+        config.limiter = self.limiter.clone().or(config.limiter);
+        Ok(config)
+    }
+
+    /// 输入文件转换为 WAV 时使用的配置
+    pub fn wav_config_for(&self, input_path: impl AsRef<Path>) -> Result<PcmToWavConfig, Box<dyn std::error::Error>> {
+        let mut config = audio_config_to_wav_config(&self.audio_config_for(input_path)?)
+            .with_force_extensible(self.wav.force_extensible)
+            .with_ima_adpcm(self.wav.ima_adpcm);
+        config.container = self.wav.container;
+        Ok(config)
+    }
+
+    /// 输入文件转换为 MP3 时使用的配置
+    pub fn mp3_config_for(&self, input_path: impl AsRef<Path>) -> Result<Mp3Config, Box<dyn std::error::Error>> {
+        let audio_config = self.audio_config_for(input_path)?;
+        let mut config = match self.preset {
+            Some(preset) => preset.encode_config(&audio_config),
+            None => audio_config_to_mp3_config(&audio_config, mp3::default_bitrate(audio_config.sample_rate), self.mp3.quality.clone()),
+        }
+        .with_resample(self.mp3.resample);
+        if let Some(rate_mode) = &self.mp3.rate_mode {
+            config = config.with_rate_mode(rate_mode.clone());
+        }
+        config.channel_mode = self.mp3.channel_mode;
+        config.threads = self.mp3.threads;
+        config.gapless = self.mp3.gapless;
+        Ok(config)
+    }
+
+    /// 按配置转换单个文件
+    ///
+    /// # Arguments
+    /// * `input_path` - 输入文件路径
+    /// * `output_path` - 输出文件路径
+    pub fn convert(&self, input_path: impl AsRef<Path>, output_path: impl AsRef<Path>) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        self.convert_with_options(input_path, output_path, ConvertOptions::new())
+    }
+
+    /// 按配置转换单个文件，支持进度回调、取消和覆盖策略
+    pub fn convert_with_options(
+        &self,
+        input_path: impl AsRef<Path>,
+        output_path: impl AsRef<Path>,
+        options: ConvertOptions,
+    ) -> Result<ConversionReport, Box<dyn std::error::Error>> {
+        let input_path = input_path.as_ref();
+        match (&self.input_format, &self.output_format) {
+            (AudioFormat::Pcm, AudioFormat::Wav) => {
+                trans_pcm_file_to_wav_with_options(input_path, output_path, Some(self.wav_config_for(input_path)?), options)
+            }
+            (AudioFormat::Pcm, AudioFormat::Mp3) => {
+                trans_pcm_file_to_mp3_with_options(input_path, output_path, Some(self.mp3_config_for(input_path)?), options)
+            }
+            (AudioFormat::Wav, AudioFormat::Pcm) => trans_wav_file_to_pcm_with_options(input_path, output_path, options),
+            (from, to) => Err(AudioError::UnsupportedFormat(format!("Unsupported conversion in profile: {:?} -> {:?}", from, to)).into()),
+        }
+    }
+}
@@ -0,0 +1,169 @@
+// 转换选项、进度与取消：向调用方报告当前阶段和已处理的字节数，并允许从其他线程中止转换
+
+use std::fmt;
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{AudioError, OverwritePolicy};
+
+/// 转换所处的阶段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertStage {
+    /// 读取输入文件
+    Reading,
+    /// 样本处理（声道转换、增益、淡入淡出、重采样等）
+    Processing,
+    /// 编码为输出格式
+    Encoding,
+    /// 写入输出文件
+    Writing,
+}
+
+/// 进度事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgressEvent {
+    /// 当前阶段
+    pub stage: ConvertStage,
+    /// 当前阶段已处理的字节数
+    pub bytes_processed: u64,
+    /// 当前阶段需要处理的总字节数
+    pub total_bytes: u64,
+}
+
+impl ProgressEvent {
+    /// 当前阶段的完成比例（0.0 ~ 1.0）
+    pub fn fraction(&self) -> f32 {
+        if self.total_bytes == 0 {
+            1.0
+        } else {
+            (self.bytes_processed as f64 / self.total_bytes as f64).min(1.0) as f32
+        }
+    }
+}
+
+/// 进度回调
+pub type ProgressCallback = Box<dyn FnMut(ProgressEvent) + Send>;
+
+/// 取消令牌，克隆后可在其他线程中取消转换
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// 创建未取消的令牌
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 请求取消，转换会在处理下一个数据块前停止
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// 是否已请求取消
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// 转换选项
+#[derive(Default)]
+pub struct ConvertOptions {
+    /// 进度回调，在转换线程中调用
+    pub progress: Option<ProgressCallback>,
+    /// 取消令牌，取消后转换返回 `AudioError::Cancelled` 错误并删除未写完的输出
+    pub cancel: Option<CancelToken>,
+    /// 输出文件已存在时的处理方式，默认覆盖
+    pub overwrite: OverwritePolicy,
+}
+
+impl fmt::Debug for ConvertOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConvertOptions")
+            .field("progress", &self.progress.as_ref().map(|_| "FnMut(ProgressEvent)"))
+            .field("cancel", &self.cancel)
+            .field("overwrite", &self.overwrite)
+            .finish()
+    }
+}
+
+impl ConvertOptions {
+    /// 创建默认选项
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 设置进度回调
+    pub fn with_progress(mut self, progress: impl FnMut(ProgressEvent) + Send + 'static) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    /// 设置取消令牌
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 设置输出文件已存在时的处理方式
+    pub fn with_overwrite(mut self, overwrite: OverwritePolicy) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    /// 是否已请求取消
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancel.as_ref().is_some_and(CancelToken::is_cancelled)
+    }
+
+    /// 已请求取消时返回 `AudioError::Cancelled`
+    pub(crate) fn check_cancelled(&self) -> Result<(), AudioError> {
+        if self.is_cancelled() { Err(AudioError::Cancelled) } else { Ok(()) }
+    }
+
+    /// 已请求取消时将读写错误替换为 `AudioError::Cancelled`（取消在 Reader 中表现为 IO 错误）
+    pub(crate) fn cancellation_aware(&self, error: io::Error) -> Box<dyn std::error::Error> {
+        if self.is_cancelled() { Box::new(AudioError::Cancelled) } else { error.into() }
+    }
+
+    /// 报告进度
+    pub(crate) fn report(&mut self, stage: ConvertStage, bytes_processed: u64, total_bytes: u64) {
+        if let Some(progress) = self.progress.as_mut() {
+            progress(ProgressEvent { stage, bytes_processed, total_bytes });
+        }
+    }
+}
+
+/// 两次进度报告之间至少间隔的字节数，避免回调过于频繁
+const REPORT_INTERVAL: u64 = 256 * 1024;
+
+/// 读取时报告进度、并在每次读取前检查取消的 Reader
+pub(crate) struct ProgressReader<'a, R> {
+    inner: R,
+    options: &'a mut ConvertOptions,
+    stage: ConvertStage,
+    processed: u64,
+    total: u64,
+    reported: u64,
+}
+
+impl<'a, R: Read> ProgressReader<'a, R> {
+    pub(crate) fn new(inner: R, options: &'a mut ConvertOptions, stage: ConvertStage, total: u64) -> Self {
+        options.report(stage, 0, total);
+        ProgressReader { inner, options, stage, processed: 0, total, reported: 0 }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.options.check_cancelled().map_err(io::Error::other)?;
+        let n = self.inner.read(buf)?;
+        self.processed += n as u64;
+        let finished = n == 0 || self.processed >= self.total;
+        if self.processed > self.reported && (finished || self.processed - self.reported >= REPORT_INTERVAL) {
+            self.reported = self.processed;
+            self.options.report(self.stage, self.processed, self.total);
+        }
+        Ok(n)
+    }
+}
@@ -0,0 +1,127 @@
+// 格式注册表：外部 crate 在运行时注册新的输入格式，`convert()`、`probe_format()` 和处理流水线按注册的处理器识别和解码
+
+use std::collections::BTreeMap;
+use std::io::{Read, Seek};
+use std::sync::{Arc, RwLock};
+
+use crate::{AudioDecoder, AudioError};
+
+/// 内置格式占用的扩展名，不能注册
+const RESERVED_EXTENSIONS: [&str; 4] = ["wav", "mp3", "pcm", "raw"];
+
+/// 交给格式处理器的输入流
+pub trait FormatReader: Read + Seek + Send + Sync {}
+
+impl<T: Read + Seek + Send + Sync> FormatReader for T {}
+
+/// 外部格式的处理器：识别文件头并打开解码器
+pub trait FormatHandler: Send + Sync {
+    /// 根据文件开头的字节（最多 4096 字节）判断是否为该格式
+    ///
+    /// 默认不识别文件头，只按扩展名匹配没有可识别文件头的文件
+    fn detect(&self, header: &[u8]) -> bool {
+        let _ = header;
+        false
+    }
+
+    /// 打开解码器
+    /// # Arguments
+    /// * `reader` - 位于文件开头的输入流
+    fn decoder(&self, reader: Box<dyn FormatReader>) -> Result<Box<dyn AudioDecoder>, Box<dyn std::error::Error>>;
+}
+
+/// 已注册的处理器，键为小写扩展名；名称注册后不再释放（替换时复用），以便 `DetectedFormat` 保持 `Copy`
+static HANDLERS: RwLock<BTreeMap<&'static str, Arc<dyn FormatHandler>>> = RwLock::new(BTreeMap::new());
+
+/// 格式注册表：按扩展名注册外部格式的处理器（扩展名不区分大小写）
+///
+/// 内置的 WAV 和 MP3 识别优先；FLAC、Ogg 和没有可识别文件头的文件会先交给已注册的处理器，
+/// 因此可以为这些格式补充解码支持。识别出的格式为 `DetectedFormat::Registered(扩展名)`，
+/// 解码后可以转换为任意内置输出格式。
+///
+/// ```
+/// use audio_helper::{AudioDecoder, FormatHandler, FormatReader, FormatRegistry, PcmDecoder, AudioConfig};
+/// use std::io::{Seek, SeekFrom};
+///
+/// /// 带 8 字节魔数的 8kHz 单声道 PCM
+/// struct Tagged;
+///
+/// impl FormatHandler for Tagged {
+///     fn detect(&self, header: &[u8]) -> bool {
+///         header.starts_with(b"TAGGED00")
+///     }
+///
+///     fn decoder(&self, mut reader: Box<dyn FormatReader>) -> Result<Box<dyn AudioDecoder>, Box<dyn std::error::Error>> {
+///         reader.seek(SeekFrom::Start(8))?;
+///         Ok(Box::new(PcmDecoder::new(reader, AudioConfig::new(8000, 1, 16))?))
+///     }
+/// }
+///
+/// FormatRegistry::register("tagged", Box::new(Tagged)).unwrap();
+/// assert!(FormatRegistry::contains("TAGGED"));
+/// ```
+pub struct FormatRegistry;
+
+impl FormatRegistry {
+    /// 注册格式处理器，同名的处理器会被替换；内置格式的扩展名（wav、mp3、pcm、raw）不能使用
+    ///
+    /// # Arguments
+    /// * `extension` - 文件扩展名，可以带前导的 `.`
+    /// * `handler` - 格式处理器
+    pub fn register(extension: &str, handler: Box<dyn FormatHandler>) -> Result<(), Box<dyn std::error::Error>> {
+        let key = normalize(extension);
+        if key.is_empty() {
+            return Err(AudioError::InvalidInput("Format extension must not be empty".into()).into());
+        }
+        if RESERVED_EXTENSIONS.contains(&key.as_str()) {
+            return Err(AudioError::InvalidInput(format!("Format extension {} is reserved for a built-in format", key)).into());
+        }
+        log::debug!("Registering format handler for .{}", key);
+        let mut handlers = HANDLERS.write().unwrap_or_else(|e| e.into_inner());
+        let name = match handlers.keys().find(|name| **name == key) {
+            Some(name) => *name,
+            None => Box::leak(key.into_boxed_str()),
+        };
+        handlers.insert(name, Arc::from(handler));
+        Ok(())
+    }
+
+    /// 移除格式处理器，返回是否存在
+    pub fn unregister(extension: &str) -> bool {
+        HANDLERS.write().unwrap_or_else(|e| e.into_inner()).remove(normalize(extension).as_str()).is_some()
+    }
+
+    /// 扩展名是否已注册
+    pub fn contains(extension: &str) -> bool {
+        HANDLERS.read().unwrap_or_else(|e| e.into_inner()).contains_key(normalize(extension).as_str())
+    }
+
+    /// 已注册的扩展名，按名称排序
+    pub fn extensions() -> Vec<String> {
+        HANDLERS.read().unwrap_or_else(|e| e.into_inner()).keys().map(|name| name.to_string()).collect()
+    }
+}
+
+fn normalize(extension: &str) -> String {
+    extension.trim().trim_start_matches('.').to_ascii_lowercase()
+}
+
+/// 按文件头查找处理器，多个处理器都能识别时取扩展名排序最前的一个
+pub(crate) fn detect(header: &[u8]) -> Option<&'static str> {
+    let handlers = HANDLERS.read().unwrap_or_else(|e| e.into_inner());
+    handlers.iter().find(|(_, handler)| handler.detect(header)).map(|(name, _)| *name)
+}
+
+/// 按扩展名查找已注册的格式名称
+pub(crate) fn by_extension(extension: &str) -> Option<&'static str> {
+    let handlers = HANDLERS.read().unwrap_or_else(|e| e.into_inner());
+    handlers.get_key_value(normalize(extension).as_str()).map(|(name, _)| *name)
+}
+
+/// 用已注册的处理器打开解码器
+pub(crate) fn open_decoder(name: &str, reader: Box<dyn FormatReader>) -> Result<Box<dyn AudioDecoder>, Box<dyn std::error::Error>> {
+    // 解码期间不持有锁，处理器内部可以再访问注册表
+    let handler = HANDLERS.read().unwrap_or_else(|e| e.into_inner()).get(name).cloned();
+    let handler = handler.ok_or_else(|| AudioError::UnsupportedFormat(format!("Format handler for .{} is not registered", name)))?;
+    handler.decoder(reader)
+}
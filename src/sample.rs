@@ -0,0 +1,441 @@
+// 原始 PCM 样本格式、通用的样本类型及其相互转换
+
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+
+use crate::{g711, simd};
+
+/// 原始 PCM 样本格式（小端）
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SampleFormat {
+    /// 8 位无符号整数（静音为 128）
+    U8,
+    /// 16 位有符号整数
+    S16,
+    /// 24 位有符号整数（3 字节紧凑存储）
+    S24,
+    /// 32 位有符号整数
+    S32,
+    /// 32 位 IEEE 浮点数，范围 [-1.0, 1.0]
+    F32,
+    /// 8 位 G.711 µ-law
+    Ulaw,
+    /// 8 位 G.711 A-law
+    Alaw,
+}
+
+impl SampleFormat {
+    /// 根据位深度推断整数样本格式
+    pub fn from_bits(bits_per_sample: u16) -> Option<Self> {
+        match bits_per_sample {
+            8 => Some(SampleFormat::U8),
+            16 => Some(SampleFormat::S16),
+            24 => Some(SampleFormat::S24),
+            32 => Some(SampleFormat::S32),
+            _ => None,
+        }
+    }
+
+    /// 每个样本的位数
+    pub fn bits_per_sample(&self) -> u16 {
+        match self {
+            SampleFormat::U8 | SampleFormat::Ulaw | SampleFormat::Alaw => 8,
+            SampleFormat::S16 => 16,
+            SampleFormat::S24 => 24,
+            SampleFormat::S32 | SampleFormat::F32 => 32,
+        }
+    }
+
+    /// 每个样本的字节数
+    pub fn bytes_per_sample(&self) -> usize {
+        self.bits_per_sample() as usize / 8
+    }
+
+    /// 是否为浮点格式
+    pub fn is_float(&self) -> bool {
+        matches!(self, SampleFormat::F32)
+    }
+
+    /// 是否为 G.711 压扩格式
+    pub fn is_g711(&self) -> bool {
+        matches!(self, SampleFormat::Ulaw | SampleFormat::Alaw)
+    }
+}
+
+/// 原始 PCM 数据的字节序
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    /// 小端（WAV 及大多数平台的默认字节序）
+    #[default]
+    Little,
+    /// 大端（常见于电话录音和部分嵌入式设备）
+    Big,
+}
+
+/// 内存中的样本类型（i16 / i32 / f32），按满幅归一化到 [-1.0, 1.0] 的 f64 相互转换
+///
+/// f64 可以精确表示所有 i32 样本，浮点流水线和 32 位整数数据不必在每个环节都经过 i16 而损失精度；
+/// 整数类型转换时四舍五入并削波，f32 不削波
+pub trait Sample: Copy + PartialEq + Default + std::fmt::Debug + Send + Sync + 'static {
+    /// 对应的原始 PCM 样本格式
+    const FORMAT: SampleFormat;
+
+    /// 转换为归一化的 f64
+    fn to_f64(self) -> f64;
+
+    /// 从归一化的 f64 转换
+    fn from_f64(value: f64) -> Self;
+
+    /// 转换为归一化的 f32
+    fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+
+    /// 从归一化的 f32 转换
+    fn from_f32(value: f32) -> Self {
+        Self::from_f64(value as f64)
+    }
+
+    /// 转换为另一种样本类型
+    fn convert<T: Sample>(self) -> T {
+        T::from_f64(self.to_f64())
+    }
+}
+
+impl Sample for i16 {
+    const FORMAT: SampleFormat = SampleFormat::S16;
+
+    fn to_f64(self) -> f64 {
+        self as f64 / 32768.0
+    }
+
+    fn from_f64(value: f64) -> Self {
+        (value * 32768.0).round().clamp(i16::MIN as f64, i16::MAX as f64) as i16
+    }
+}
+
+impl Sample for i32 {
+    const FORMAT: SampleFormat = SampleFormat::S32;
+
+    fn to_f64(self) -> f64 {
+        self as f64 / 2_147_483_648.0
+    }
+
+    fn from_f64(value: f64) -> Self {
+        // f64 转 i32 时超出范围会饱和
+        (value * 2_147_483_648.0).round() as i32
+    }
+}
+
+impl Sample for f32 {
+    const FORMAT: SampleFormat = SampleFormat::F32;
+
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value as f32
+    }
+
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+/// 在样本类型之间转换，例如 `convert_samples::<i32, f32>(&samples)`
+pub fn convert_samples<S: Sample, T: Sample>(samples: &[S]) -> Vec<T> {
+    samples.iter().map(|&s| s.convert()).collect()
+}
+
+/// 原地翻转每个样本的字节序，末尾不足一个样本的字节保持不变
+pub fn swap_sample_bytes(data: &mut [u8], format: SampleFormat) {
+    let size = format.bytes_per_sample();
+    if size > 1 {
+        data.chunks_exact_mut(size).for_each(|sample| sample.reverse());
+    }
+}
+
+/// 计算音频 MD5 时使用的规范样本字节（与 FLAC 相同）：小端有符号整数，8 位无符号样本转换为有符号，
+/// G.711 解码为 16 位，浮点保持小端 IEEE 字节；末尾不完整的样本被丢弃
+pub(crate) fn canonical_bytes(data: &[u8], format: SampleFormat) -> Cow<'_, [u8]> {
+    let data = &data[..data.len() - data.len() % format.bytes_per_sample()];
+    match format {
+        SampleFormat::U8 => Cow::Owned(data.iter().map(|b| b ^ 0x80).collect()),
+        SampleFormat::Ulaw | SampleFormat::Alaw => Cow::Owned(decode_samples::<i16>(data, format).iter().flat_map(|s| s.to_le_bytes()).collect()),
+        _ => Cow::Borrowed(data),
+    }
+}
+
+/// 复制一份数据并翻转每个样本的字节序，用于不能原地修改的输入（如内存映射的文件）
+pub(crate) fn to_little_endian(data: &[u8], format: SampleFormat) -> Vec<u8> {
+    let mut swapped = data.to_vec();
+    swap_sample_bytes(&mut swapped, format);
+    swapped
+}
+
+/// 将 i16 样本视为 16 位小端字节，小端平台上直接借用样本的内存
+pub(crate) fn i16_as_le_bytes(samples: &[i16]) -> Cow<'_, [u8]> {
+    if cfg!(target_endian = "little") {
+        Cow::Borrowed(unsafe { std::slice::from_raw_parts(samples.as_ptr() as *const u8, samples.len() * 2) })
+    } else {
+        Cow::Owned(samples.iter().flat_map(|s| s.to_le_bytes()).collect())
+    }
+}
+
+/// 按流拷贝 PCM 数据，源数据为大端时在拷贝过程中转换为小端
+pub(crate) fn copy_as_little_endian<R: Read, W: Write>(
+    reader: &mut R,
+    writer: &mut W,
+    format: SampleFormat,
+    endianness: Endianness,
+) -> io::Result<u64> {
+    if endianness == Endianness::Little {
+        return io::copy(reader, writer);
+    }
+
+    // 缓冲区大小为 1/2/3/4 字节样本的公倍数，保证样本不会跨越两次读取
+    let mut buffer = vec![0u8; 12 * 8192];
+    let mut total = 0u64;
+    loop {
+        let filled = read_full(reader, &mut buffer)?;
+        if filled == 0 {
+            return Ok(total);
+        }
+        swap_sample_bytes(&mut buffer[..filled], format);
+        writer.write_all(&buffer[..filled])?;
+        total += filled as u64;
+    }
+}
+
+/// 读取直到填满缓冲区或到达数据流末尾，返回读取的字节数
+pub(crate) fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// 将原始字节解码为 f32 样本（范围 [-1.0, 1.0]），末尾不足一个样本的字节会被忽略
+pub fn decode_samples_f32(data: &[u8], format: SampleFormat) -> Vec<f32> {
+    // 最常见的 16 位格式单独成循环，编译器可以自动向量化
+    if format == SampleFormat::S16 {
+        return data.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0).collect();
+    }
+    let size = format.bytes_per_sample();
+    data.chunks_exact(size)
+        .map(|b| match format {
+            SampleFormat::U8 => (b[0] as f32 - 128.0) / 128.0,
+            SampleFormat::S16 => i16::from_le_bytes([b[0], b[1]]) as f32 / 32768.0,
+            SampleFormat::S24 => read_i24(b) as f32 / 8_388_608.0,
+            SampleFormat::S32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32 / 2_147_483_648.0,
+            SampleFormat::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            SampleFormat::Ulaw => g711::ulaw_to_linear(b[0]) as f32 / 32768.0,
+            SampleFormat::Alaw => g711::alaw_to_linear(b[0]) as f32 / 32768.0,
+        })
+        .collect()
+}
+
+/// 将原始字节解码为 i16 样本（MP3 编码器的输入格式）
+pub fn decode_samples_i16(data: &[u8], format: SampleFormat) -> Vec<i16> {
+    if format == SampleFormat::S16 {
+        let mut samples = Vec::new();
+        simd::s16le_to_i16(data, &mut samples);
+        return samples;
+    }
+    let size = format.bytes_per_sample();
+    data.chunks_exact(size)
+        .map(|b| match format {
+            SampleFormat::U8 => ((b[0] as i16) - 128) << 8,
+            SampleFormat::S16 => i16::from_le_bytes([b[0], b[1]]),
+            SampleFormat::S24 => (read_i24(b) >> 8) as i16,
+            SampleFormat::S32 => (i32::from_le_bytes([b[0], b[1], b[2], b[3]]) >> 16) as i16,
+            SampleFormat::F32 => f32_to_i16(f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+            SampleFormat::Ulaw => g711::ulaw_to_linear(b[0]),
+            SampleFormat::Alaw => g711::alaw_to_linear(b[0]),
+        })
+        .collect()
+}
+
+/// 将交错的立体声样本拆分为左右声道，末尾不完整的帧被忽略
+pub fn deinterleave_stereo(samples: &[i16]) -> (Vec<i16>, Vec<i16>) {
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    simd::deinterleave_stereo(samples, &mut left, &mut right);
+    (left, right)
+}
+
+/// 将 f32 样本编码为指定格式的原始字节，超出范围的样本会被削波
+///
+/// 整数格式与解码使用相同的缩放系数，8/16/24 位样本经过 f32 往返后保持不变
+pub fn encode_samples_f32(samples: &[f32], format: SampleFormat) -> Vec<u8> {
+    let mut out = Vec::with_capacity(samples.len() * format.bytes_per_sample());
+    for &s in samples {
+        let s = s.clamp(-1.0, 1.0);
+        match format {
+            SampleFormat::U8 => out.push((s * 128.0 + 128.0).round().min(255.0) as u8),
+            SampleFormat::S16 => out.extend_from_slice(&f32_to_i16(s).to_le_bytes()),
+            SampleFormat::S24 => {
+                let v = (s * 8_388_608.0).round().min(8_388_607.0) as i32;
+                out.extend_from_slice(&v.to_le_bytes()[..3]);
+            }
+            SampleFormat::S32 => {
+                // f64 转 i32 时超出范围会饱和到 i32::MAX
+                let v = (s as f64 * 2_147_483_648.0).round() as i32;
+                out.extend_from_slice(&v.to_le_bytes());
+            }
+            SampleFormat::F32 => out.extend_from_slice(&s.to_le_bytes()),
+            SampleFormat::Ulaw => out.push(g711::linear_to_ulaw(f32_to_i16(s))),
+            SampleFormat::Alaw => out.push(g711::linear_to_alaw(f32_to_i16(s))),
+        }
+    }
+    out
+}
+
+/// 将原始字节解码为任意样本类型，末尾不足一个样本的字节会被忽略
+///
+/// 与 [`decode_samples_i16`] 不同，高位深数据转换为 i16 时四舍五入而不是截断
+pub fn decode_samples<S: Sample>(data: &[u8], format: SampleFormat) -> Vec<S> {
+    data.chunks_exact(format.bytes_per_sample())
+        .map(|b| {
+            let value = match format {
+                SampleFormat::U8 => (b[0] as f64 - 128.0) / 128.0,
+                SampleFormat::S16 => i16::from_le_bytes([b[0], b[1]]).to_f64(),
+                SampleFormat::S24 => read_i24(b) as f64 / 8_388_608.0,
+                SampleFormat::S32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]).to_f64(),
+                SampleFormat::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+                SampleFormat::Ulaw => g711::ulaw_to_linear(b[0]).to_f64(),
+                SampleFormat::Alaw => g711::alaw_to_linear(b[0]).to_f64(),
+            };
+            S::from_f64(value)
+        })
+        .collect()
+}
+
+/// 将任意类型的样本编码为指定格式的原始字节，超出范围的样本会被削波
+///
+/// 样本类型与输出格式相同时原样写入，i32 样本写入 32 位整数格式不损失精度
+pub fn encode_samples<S: Sample>(samples: &[S], format: SampleFormat) -> Vec<u8> {
+    let mut out = Vec::with_capacity(samples.len() * format.bytes_per_sample());
+    for &s in samples {
+        let value = s.to_f64().clamp(-1.0, 1.0);
+        match format {
+            SampleFormat::U8 => out.push((value * 128.0 + 128.0).round().min(255.0) as u8),
+            SampleFormat::S16 => out.extend_from_slice(&i16::from_f64(value).to_le_bytes()),
+            SampleFormat::S24 => {
+                let v = (value * 8_388_608.0).round().min(8_388_607.0) as i32;
+                out.extend_from_slice(&v.to_le_bytes()[..3]);
+            }
+            SampleFormat::S32 => out.extend_from_slice(&i32::from_f64(value).to_le_bytes()),
+            SampleFormat::F32 => out.extend_from_slice(&(value as f32).to_le_bytes()),
+            SampleFormat::Ulaw => out.push(g711::linear_to_ulaw(i16::from_f64(value))),
+            SampleFormat::Alaw => out.push(g711::linear_to_alaw(i16::from_f64(value))),
+        }
+    }
+    out
+}
+
+/// f32 样本转换为 i16（带削波）
+pub fn f32_to_i16(sample: f32) -> i16 {
+    (sample * 32768.0).round().clamp(i16::MIN as f32, i16::MAX as f32) as i16
+}
+
+/// 读取 3 字节小端有符号整数
+fn read_i24(b: &[u8]) -> i32 {
+    // 放到高 24 位再算术右移完成符号扩展
+    i32::from_le_bytes([0, b[0], b[1], b[2]]) >> 8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_i24_sign_extension() {
+        assert_eq!(read_i24(&[0xFF, 0xFF, 0xFF]), -1);
+        assert_eq!(read_i24(&[0x00, 0x00, 0x80]), -8_388_608);
+        assert_eq!(read_i24(&[0xFF, 0xFF, 0x7F]), 8_388_607);
+    }
+
+    #[test]
+    fn test_decode_to_i16_for_all_formats() {
+        assert_eq!(decode_samples_i16(&[0, 128, 255], SampleFormat::U8), vec![-32768, 0, 127 << 8]);
+        assert_eq!(decode_samples_i16(&[0x00, 0x00, 0x40], SampleFormat::S24), vec![0x4000]);
+        assert_eq!(decode_samples_i16(&0x4000_0000i32.to_le_bytes(), SampleFormat::S32), vec![0x4000]);
+        assert_eq!(decode_samples_i16(&0.5f32.to_le_bytes(), SampleFormat::F32), vec![16384]);
+        assert_eq!(decode_samples_i16(&2.0f32.to_le_bytes(), SampleFormat::F32), vec![i16::MAX]);
+    }
+
+    #[test]
+    fn test_big_endian_copy() {
+        let big_endian = [0x12u8, 0x34, 0x56, 0x78, 0x9A, 0xBC, 0xDE];
+        let mut output = Vec::new();
+        let copied = copy_as_little_endian(&mut &big_endian[..], &mut output, SampleFormat::S24, Endianness::Big).unwrap();
+        assert_eq!(copied, 7);
+        assert_eq!(output, vec![0x56, 0x34, 0x12, 0xBC, 0x9A, 0x78, 0xDE]);
+    }
+
+    #[test]
+    fn test_canonical_bytes() {
+        // 8 位无符号转换为有符号，G.711 解码为 16 位小端，其余格式不变
+        assert_eq!(&*canonical_bytes(&[0x80, 0xFF, 0x00], SampleFormat::U8), &[0x00, 0x7F, 0x80]);
+        assert_eq!(&*canonical_bytes(&[0xFF], SampleFormat::Ulaw), &0i16.to_le_bytes());
+        assert!(matches!(canonical_bytes(&[1, 2, 3, 4], SampleFormat::S16), Cow::Borrowed(&[1, 2, 3, 4])));
+        // 末尾不完整的样本被丢弃
+        assert_eq!(&*canonical_bytes(&[1, 2, 3, 4, 5], SampleFormat::S24), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_integer_round_trip_is_exact() {
+        let bytes: Vec<u8> = [i16::MIN, -1, 0, 1, 12345, i16::MAX].iter().flat_map(|s| s.to_le_bytes()).collect();
+        for format in [SampleFormat::U8, SampleFormat::S16, SampleFormat::S24] {
+            let data = &bytes[..bytes.len() / format.bytes_per_sample() * format.bytes_per_sample()];
+            assert_eq!(encode_samples_f32(&decode_samples_f32(data, format), format), data, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn test_f32_round_trip() {
+        let samples = [0.0f32, 0.5, -0.5, 0.999];
+        for format in [SampleFormat::S16, SampleFormat::S24, SampleFormat::S32, SampleFormat::F32] {
+            let decoded = decode_samples_f32(&encode_samples_f32(&samples, format), format);
+            for (a, b) in samples.iter().zip(&decoded) {
+                assert!((a - b).abs() < 1e-4, "{:?}: {} != {}", format, a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_generic_sample_conversion() {
+        assert_eq!(i16::MIN.convert::<i32>(), i32::MIN);
+        assert_eq!(0x1234_5678i32.convert::<i16>(), 0x1234);
+        assert_eq!(0x1234_8000i32.convert::<i16>(), 0x1235, "转换为 i16 时四舍五入");
+        assert_eq!(2.0f32.convert::<i32>(), i32::MAX);
+        assert_eq!((-0.5f32).convert::<i16>(), -16384);
+
+        // i32 经过 S32 字节往返不损失精度
+        let samples = [i32::MIN, -1, 0, 1, 0x1234_5679, i32::MAX];
+        let bytes = encode_samples(&samples, SampleFormat::S32);
+        assert_eq!(decode_samples::<i32>(&bytes, SampleFormat::S32), samples);
+        assert_eq!(bytes, samples.iter().flat_map(|s| s.to_le_bytes()).collect::<Vec<u8>>());
+
+        // 与 f32 专用函数的结果一致
+        let floats = [0.0f32, 0.25, -0.999, 1.5];
+        for format in [SampleFormat::U8, SampleFormat::S16, SampleFormat::S24, SampleFormat::S32, SampleFormat::F32, SampleFormat::Ulaw] {
+            assert_eq!(encode_samples(&floats, format), encode_samples_f32(&floats, format), "{:?}", format);
+            let bytes = encode_samples_f32(&floats, format);
+            assert_eq!(decode_samples::<f32>(&bytes, format), decode_samples_f32(&bytes, format), "{:?}", format);
+        }
+    }
+}
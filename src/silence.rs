@@ -0,0 +1,259 @@
+// 静音检测：按短时窗口的峰值电平判断静音
+
+use std::ops::Range;
+
+use crate::dsp;
+
+/// 静音分析窗口时长（毫秒）
+const ANALYSIS_WINDOW_MS: u32 = 10;
+
+/// 每个分析窗口包含的帧数
+fn window_frames(sample_rate: u32) -> usize {
+    ((sample_rate * ANALYSIS_WINDOW_MS / 1000) as usize).max(1)
+}
+
+/// 按窗口计算每个窗口是否为静音（所有声道的峰值都低于阈值）
+fn silent_windows(samples: &[f32], channels: u8, sample_rate: u32, threshold_dbfs: f32) -> Vec<bool> {
+    let threshold = dsp::db_to_linear(threshold_dbfs);
+    let window = window_frames(sample_rate) * channels.max(1) as usize;
+    samples
+        .chunks(window)
+        .map(|chunk| chunk.iter().all(|s| s.abs() < threshold))
+        .collect()
+}
+
+/// 静音区间（毫秒）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SilenceRegion {
+    /// 开始时间（毫秒）
+    pub start_ms: u64,
+    /// 结束时间（毫秒，不包含）
+    pub end_ms: u64,
+}
+
+impl SilenceRegion {
+    /// 静音时长（毫秒）
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms - self.start_ms
+    }
+}
+
+/// 查找静音区间对应的帧范围
+pub(crate) fn find_silent_frame_ranges(samples: &[f32], channels: u8, sample_rate: u32, threshold_dbfs: f32, min_duration_ms: u32) -> Vec<Range<usize>> {
+    let frames = samples.len() / channels.max(1) as usize;
+    let window = window_frames(sample_rate);
+    let min_frames = (min_duration_ms as u64 * sample_rate as u64 / 1000) as usize;
+
+    let mut ranges = Vec::new();
+    let mut run_start = None;
+    let silent = silent_windows(samples, channels, sample_rate, threshold_dbfs);
+    for (i, &is_silent) in silent.iter().chain(std::iter::once(&false)).enumerate() {
+        match (is_silent, run_start) {
+            (true, None) => run_start = Some(i),
+            (false, Some(start)) => {
+                let range = (start * window).min(frames)..(i * window).min(frames);
+                if range.len() >= min_frames.max(1) {
+                    ranges.push(range);
+                }
+                run_start = None;
+            }
+            _ => {}
+        }
+    }
+    ranges
+}
+
+/// 查找静音区间
+/// # Arguments
+/// * `samples` - 交错的样本
+/// * `channels` - 声道数
+/// * `sample_rate` - 采样率
+/// * `threshold_dbfs` - 静音阈值（dBFS），峰值低于该值视为静音
+/// * `min_duration_ms` - 只返回至少持续该时长的静音区间
+/// # Returns
+/// * 按时间顺序排列的静音区间（以 10ms 为分析粒度）
+pub fn find_silence_regions(samples: &[f32], channels: u8, sample_rate: u32, threshold_dbfs: f32, min_duration_ms: u32) -> Vec<SilenceRegion> {
+    let to_ms = |frame: usize| frame as u64 * 1000 / sample_rate.max(1) as u64;
+    find_silent_frame_ranges(samples, channels, sample_rate, threshold_dbfs, min_duration_ms)
+        .into_iter()
+        .map(|range| SilenceRegion { start_ms: to_ms(range.start), end_ms: to_ms(range.end) })
+        .collect()
+}
+
+/// 按静音切分音频的参数
+#[derive(Debug, Clone, PartialEq)]
+pub struct SplitOptions {
+    /// 静音阈值（dBFS），峰值低于该值视为静音
+    pub threshold_dbfs: f32,
+    /// 至少持续该时长的静音才作为切分点（毫秒）
+    pub min_silence_ms: u32,
+    /// 短于该时长的片段会被丢弃（毫秒）
+    pub min_segment_ms: u32,
+    /// 每个片段前后保留的静音时长（毫秒），避免切掉音节的起止部分
+    pub padding_ms: u32,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        SplitOptions {
+            threshold_dbfs: -45.0,
+            min_silence_ms: 500,
+            min_segment_ms: 200,
+            padding_ms: 100,
+        }
+    }
+}
+
+impl SplitOptions {
+    /// 设置静音阈值（dBFS）
+    pub fn with_threshold_dbfs(mut self, threshold_dbfs: f32) -> Self {
+        self.threshold_dbfs = threshold_dbfs;
+        self
+    }
+
+    /// 设置作为切分点的最短静音时长
+    pub fn with_min_silence_ms(mut self, min_silence_ms: u32) -> Self {
+        self.min_silence_ms = min_silence_ms;
+        self
+    }
+
+    /// 设置片段的最短时长
+    pub fn with_min_segment_ms(mut self, min_segment_ms: u32) -> Self {
+        self.min_segment_ms = min_segment_ms;
+        self
+    }
+
+    /// 设置片段前后保留的静音时长
+    pub fn with_padding_ms(mut self, padding_ms: u32) -> Self {
+        self.padding_ms = padding_ms;
+        self
+    }
+}
+
+/// 按静音切分，返回每个片段的帧范围
+/// # Arguments
+/// * `samples` - 交错的样本
+/// * `channels` - 声道数
+/// * `sample_rate` - 采样率
+/// * `options` - 切分参数
+/// # Returns
+/// * 按时间顺序排列的片段帧范围，前后保留的静音不会超过相邻静音区间的中点
+pub fn find_split_ranges(samples: &[f32], channels: u8, sample_rate: u32, options: &SplitOptions) -> Vec<Range<usize>> {
+    let frames = samples.len() / channels.max(1) as usize;
+    let to_frames = |ms: u32| (ms as u64 * sample_rate as u64 / 1000) as usize;
+    let padding = to_frames(options.padding_ms);
+    let min_segment = to_frames(options.min_segment_ms).max(1);
+
+    let silences = find_silent_frame_ranges(samples, channels, sample_rate, options.threshold_dbfs, options.min_silence_ms);
+
+    // 静音区间之间（以及首尾）的部分为有声片段
+    let boundaries: Vec<Range<usize>> = std::iter::once(0..0)
+        .chain(silences)
+        .chain(std::iter::once(frames..frames))
+        .collect();
+
+    boundaries
+        .windows(2)
+        .filter_map(|pair| {
+            let (before, after) = (&pair[0], &pair[1]);
+            let segment = before.end..after.start;
+            if segment.len() < min_segment {
+                return None;
+            }
+            let start = segment.start.saturating_sub(padding).max(before.start + before.len() / 2);
+            let end = (segment.end + padding).min(after.end - after.len() / 2);
+            Some(start..end)
+        })
+        .collect()
+}
+
+/// 计算去除首尾静音后保留的帧范围
+/// # Arguments
+/// * `samples` - 交错的样本
+/// * `channels` - 声道数
+/// * `sample_rate` - 采样率
+/// * `threshold_dbfs` - 静音阈值（dBFS，如 -50.0）
+/// * `min_duration_ms` - 首尾静音至少持续该时长才会被去除
+/// # Returns
+/// * 保留的帧范围，全部为静音时返回空范围
+pub fn find_trim_range(samples: &[f32], channels: u8, sample_rate: u32, threshold_dbfs: f32, min_duration_ms: u32) -> Range<usize> {
+    let frames = samples.len() / channels.max(1) as usize;
+    let window = window_frames(sample_rate);
+    let silent = silent_windows(samples, channels, sample_rate, threshold_dbfs);
+    let min_frames = (min_duration_ms as u64 * sample_rate as u64 / 1000) as usize;
+
+    let leading = silent.iter().take_while(|&&s| s).count();
+    if leading == silent.len() {
+        return 0..0;
+    }
+    let trailing = silent.iter().rev().take_while(|&&s| s).count();
+
+    let start = (leading * window).min(frames);
+    // 最后一个窗口可能不完整，按实际帧数计算尾部静音
+    let end = if trailing == 0 { frames } else { ((silent.len() - trailing) * window).min(frames) };
+
+    let start = if start >= min_frames { start } else { 0 };
+    let end = if frames - end >= min_frames { end } else { frames };
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim_range() {
+        // 1kHz 采样率，窗口为 10 帧：30 帧静音 + 50 帧信号 + 20 帧静音
+        let mut samples = vec![0.0f32; 100];
+        samples[30..80].iter_mut().for_each(|s| *s = 0.5);
+        assert_eq!(find_trim_range(&samples, 1, 1000, -40.0, 0), 30..80);
+        // 尾部静音短于最短时长时保留
+        assert_eq!(find_trim_range(&samples, 1, 1000, -40.0, 25), 30..100);
+    }
+
+    #[test]
+    fn test_silence_regions() {
+        // 1kHz 采样率：20 帧静音 + 30 帧信号 + 40 帧静音 + 10 帧信号 + 5 帧静音
+        let mut samples = vec![0.0f32; 105];
+        samples[20..50].iter_mut().for_each(|s| *s = 0.5);
+        samples[90..100].iter_mut().for_each(|s| *s = -0.5);
+
+        let regions = find_silence_regions(&samples, 1, 1000, -40.0, 0);
+        assert_eq!(regions, vec![
+            SilenceRegion { start_ms: 0, end_ms: 20 },
+            SilenceRegion { start_ms: 50, end_ms: 90 },
+            SilenceRegion { start_ms: 100, end_ms: 105 },
+        ]);
+        assert_eq!(regions[1].duration_ms(), 40);
+
+        let long = find_silence_regions(&samples, 1, 1000, -40.0, 30);
+        assert_eq!(long, vec![SilenceRegion { start_ms: 50, end_ms: 90 }]);
+    }
+
+    #[test]
+    fn test_split_ranges() {
+        // 1kHz 采样率：20 帧静音 + 30 帧信号 + 40 帧静音 + 10 帧信号 + 20 帧静音 + 5 帧信号
+        let mut samples = vec![0.0f32; 125];
+        samples[20..50].iter_mut().for_each(|s| *s = 0.5);
+        samples[90..100].iter_mut().for_each(|s| *s = 0.5);
+        samples[120..125].iter_mut().for_each(|s| *s = 0.5);
+
+        let options = SplitOptions::default()
+            .with_threshold_dbfs(-40.0)
+            .with_min_silence_ms(30)
+            .with_min_segment_ms(8)
+            .with_padding_ms(5);
+        // 20 帧的静音短于最短静音，不作为切分点
+        assert_eq!(find_split_ranges(&samples, 1, 1000, &options), vec![0..55, 85..125]);
+
+        // 前后保留的静音不超过静音区间的中点
+        let options = options.with_padding_ms(100);
+        assert_eq!(find_split_ranges(&samples, 1, 1000, &options), vec![0..70, 70..125]);
+    }
+
+    #[test]
+    fn test_trim_all_silent_or_no_silence() {
+        assert_eq!(find_trim_range(&[0.0; 100], 1, 1000, -40.0, 0), 0..0);
+        assert_eq!(find_trim_range(&[0.5; 105], 1, 1000, -40.0, 0), 0..105);
+    }
+}
@@ -0,0 +1,110 @@
+// 样本转换热点的 SIMD 实现：x86_64 使用 SSE2，小端 aarch64 使用 NEON（均为目标架构的基线指令集，
+// 不需要运行时检测），其余平台和不足一个向量的尾部使用标量实现
+//
+// 16 位整数转 f32 的标量循环会被编译器自动向量化，手写的 SSE2 版本没有更快，因此不在这里实现
+
+/// 16 位小端字节解码为 i16，追加到 `out`
+pub(crate) fn s16le_to_i16(data: &[u8], out: &mut Vec<i16>) {
+    let count = data.len() / 2;
+    out.reserve(count);
+    if cfg!(target_endian = "little") {
+        // 小端平台上字节布局与 i16 相同，直接整块复制
+        let start = out.len();
+        unsafe {
+            std::ptr::copy_nonoverlapping(data.as_ptr(), out.as_mut_ptr().add(start) as *mut u8, count * 2);
+            out.set_len(start + count);
+        }
+    } else {
+        out.extend(data.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])));
+    }
+}
+
+/// 将交错的立体声样本拆分为左右声道，追加到 `left` 和 `right`，末尾不完整的帧被忽略
+pub(crate) fn deinterleave_stereo(samples: &[i16], left: &mut Vec<i16>, right: &mut Vec<i16>) {
+    left.reserve(samples.len() / 2);
+    right.reserve(samples.len() / 2);
+    let done = deinterleave_stereo_simd(samples, left, right);
+    for frame in samples[done..].chunks_exact(2) {
+        left.push(frame[0]);
+        right.push(frame[1]);
+    }
+}
+
+/// SIMD 处理完整的向量，返回已处理的样本数
+#[cfg(target_arch = "x86_64")]
+fn deinterleave_stereo_simd(samples: &[i16], left: &mut Vec<i16>, right: &mut Vec<i16>) -> usize {
+    use std::arch::x86_64::*;
+
+    // 每次处理 8 帧（16 个样本）
+    let blocks = samples.len() / 16;
+    let (left_start, right_start) = (left.len(), right.len());
+    unsafe {
+        let (left_dst, right_dst) = (left.as_mut_ptr().add(left_start), right.as_mut_ptr().add(right_start));
+        for i in 0..blocks {
+            let a = _mm_loadu_si128(samples.as_ptr().add(i * 16) as *const __m128i);
+            let b = _mm_loadu_si128(samples.as_ptr().add(i * 16 + 8) as *const __m128i);
+            // 每个 32 位通道为一帧：低 16 位是左声道，高 16 位是右声道；符号扩展后打包，数值不会饱和
+            let l = _mm_packs_epi32(_mm_srai_epi32(_mm_slli_epi32(a, 16), 16), _mm_srai_epi32(_mm_slli_epi32(b, 16), 16));
+            let r = _mm_packs_epi32(_mm_srai_epi32(a, 16), _mm_srai_epi32(b, 16));
+            _mm_storeu_si128(left_dst.add(i * 8) as *mut __m128i, l);
+            _mm_storeu_si128(right_dst.add(i * 8) as *mut __m128i, r);
+        }
+        left.set_len(left_start + blocks * 8);
+        right.set_len(right_start + blocks * 8);
+    }
+    blocks * 16
+}
+
+#[cfg(all(target_arch = "aarch64", target_endian = "little"))]
+fn deinterleave_stereo_simd(samples: &[i16], left: &mut Vec<i16>, right: &mut Vec<i16>) -> usize {
+    use std::arch::aarch64::*;
+
+    let blocks = samples.len() / 16;
+    let (left_start, right_start) = (left.len(), right.len());
+    unsafe {
+        let (left_dst, right_dst) = (left.as_mut_ptr().add(left_start), right.as_mut_ptr().add(right_start));
+        for i in 0..blocks {
+            // vld2q 按偶数/奇数位置拆分为两个向量
+            let frames = vld2q_s16(samples.as_ptr().add(i * 16));
+            vst1q_s16(left_dst.add(i * 8), frames.0);
+            vst1q_s16(right_dst.add(i * 8), frames.1);
+        }
+        left.set_len(left_start + blocks * 8);
+        right.set_len(right_start + blocks * 8);
+    }
+    blocks * 16
+}
+
+#[cfg(not(any(target_arch = "x86_64", all(target_arch = "aarch64", target_endian = "little"))))]
+fn deinterleave_stereo_simd(_samples: &[i16], _left: &mut Vec<i16>, _right: &mut Vec<i16>) -> usize {
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 覆盖完整向量和尾部的测试数据
+    fn test_samples() -> Vec<i16> {
+        (0..37).map(|i: i32| (i * 1777 - 32768).clamp(i16::MIN as i32, i16::MAX as i32) as i16).chain([i16::MIN, i16::MAX, -1, 0]).collect()
+    }
+
+    #[test]
+    fn test_s16_conversion_matches_scalar() {
+        let samples = test_samples();
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        let mut decoded = Vec::new();
+        s16le_to_i16(&bytes, &mut decoded);
+        assert_eq!(decoded, samples);
+    }
+
+    #[test]
+    fn test_deinterleave_matches_scalar() {
+        let samples = test_samples();
+        let (mut left, mut right) = (Vec::new(), Vec::new());
+        deinterleave_stereo(&samples, &mut left, &mut right);
+        let frames: Vec<_> = samples.chunks_exact(2).collect();
+        assert_eq!(left, frames.iter().map(|f| f[0]).collect::<Vec<_>>());
+        assert_eq!(right, frames.iter().map(|f| f[1]).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,224 @@
+// UniFFI 绑定：由 uniffi-bindgen 生成 Kotlin / Swift / Python 接口，带有枚举、记录和错误类型，
+// 移动端不需要再直接使用基于裸指针的 C 接口
+
+use crate::ffi::{self, CErrorCode, CMp3Config};
+use crate::{AudioFormat, DetectedFormat, PcmToWavConfig};
+
+/// 转换失败的原因，分类与 C 接口的 `CErrorCode` 相同
+#[derive(Debug, Clone, PartialEq, uniffi::Error)]
+pub enum AudioHelperError {
+    /// 参数取值无效
+    InvalidArgument { message: String },
+    /// 路径无效
+    InvalidPath { message: String },
+    /// 输入文件或输出目录不存在
+    FileNotFound { message: String },
+    /// 没有读写权限
+    PermissionDenied { message: String },
+    /// 其他读写错误
+    Io { message: String },
+    /// 音频参数不受支持或组合无效
+    UnsupportedConfig { message: String },
+    /// 无法创建或配置 MP3 编码器
+    EncoderUnavailable { message: String },
+    /// 转换被取消
+    Cancelled { message: String },
+    /// 未归类的错误
+    Unknown { message: String },
+}
+
+impl std::fmt::Display for AudioHelperError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AudioHelperError::InvalidArgument { message }
+            | AudioHelperError::InvalidPath { message }
+            | AudioHelperError::FileNotFound { message }
+            | AudioHelperError::PermissionDenied { message }
+            | AudioHelperError::Io { message }
+            | AudioHelperError::UnsupportedConfig { message }
+            | AudioHelperError::EncoderUnavailable { message }
+            | AudioHelperError::Cancelled { message }
+            | AudioHelperError::Unknown { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for AudioHelperError {}
+
+impl From<Box<dyn std::error::Error>> for AudioHelperError {
+    fn from(error: Box<dyn std::error::Error>) -> Self {
+        let message = error.to_string();
+        match ffi::error_code(error.as_ref()) {
+            CErrorCode::InvalidArgument => AudioHelperError::InvalidArgument { message },
+            CErrorCode::InvalidPath => AudioHelperError::InvalidPath { message },
+            CErrorCode::FileNotFound => AudioHelperError::FileNotFound { message },
+            CErrorCode::PermissionDenied => AudioHelperError::PermissionDenied { message },
+            CErrorCode::IoError => AudioHelperError::Io { message },
+            CErrorCode::UnsupportedConfig => AudioHelperError::UnsupportedConfig { message },
+            CErrorCode::EncoderUnavailable => AudioHelperError::EncoderUnavailable { message },
+            CErrorCode::Cancelled => AudioHelperError::Cancelled { message },
+            CErrorCode::Ok | CErrorCode::Unknown => AudioHelperError::Unknown { message },
+        }
+    }
+}
+
+/// PCM 数据的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct PcmFormat {
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub bits_per_sample: u16,
+}
+
+/// MP3 编码质量
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Mp3Quality {
+    Low,
+    Medium,
+    High,
+    Best,
+}
+
+/// MP3 编码参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct Mp3Settings {
+    pub sample_rate: u32,
+    pub channels: u8,
+    /// 64、128、192、256 或 320
+    pub bitrate_kbps: u32,
+    pub quality: Mp3Quality,
+}
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum OutputFormat {
+    Wav,
+    Mp3,
+}
+
+/// 按内容识别出的文件格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum FileFormat {
+    /// 没有可识别的文件头，按原始 PCM 处理
+    Pcm,
+    Wav,
+    Mp3,
+    Flac,
+    Ogg,
+    /// 由 `FormatRegistry` 中注册的处理器识别
+    Registered,
+}
+
+/// 音频文件信息
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct AudioFileInfo {
+    pub format: FileFormat,
+    /// 音频参数：WAV 从文件头读取，原始 PCM 从文件名推断，MP3 / FLAC / Ogg 为 None
+    pub pcm_format: Option<PcmFormat>,
+    /// 时长（毫秒），无法计算时为 None
+    pub duration_ms: Option<u64>,
+    /// 音频数据字节数：WAV 为 data 块大小，其余格式为文件大小
+    pub data_size: u64,
+    pub file_size: u64,
+}
+
+impl From<PcmFormat> for PcmToWavConfig {
+    fn from(format: PcmFormat) -> Self {
+        PcmToWavConfig::new(format.sample_rate, format.channels, format.bits_per_sample)
+    }
+}
+
+impl TryFrom<Mp3Settings> for crate::Mp3Config {
+    type Error = Box<dyn std::error::Error>;
+
+    fn try_from(settings: Mp3Settings) -> Result<Self, Self::Error> {
+        ffi::c_mp3_config_to_rust(CMp3Config {
+            sample_rate: settings.sample_rate,
+            channels: settings.channels,
+            bitrate: settings.bitrate_kbps,
+            quality: match settings.quality {
+                Mp3Quality::Low => 0,
+                Mp3Quality::Medium => 1,
+                Mp3Quality::High => 2,
+                Mp3Quality::Best => 3,
+            },
+        })
+    }
+}
+
+/// PCM 文件转 WAV，`format` 为 None 时使用默认配置
+#[uniffi::export]
+pub fn pcm_to_wav(input_path: String, output_path: String, format: Option<PcmFormat>) -> Result<(), AudioHelperError> {
+    crate::trans_pcm_file_to_wav(input_path, output_path, format.map(PcmToWavConfig::from))?;
+    Ok(())
+}
+
+/// PCM 文件转 MP3，`settings` 为 None 时使用默认配置
+#[uniffi::export]
+pub fn pcm_to_mp3(input_path: String, output_path: String, settings: Option<Mp3Settings>) -> Result<(), AudioHelperError> {
+    let config = settings.map(crate::Mp3Config::try_from).transpose()?;
+    crate::trans_pcm_file_to_mp3(input_path, output_path, config)?;
+    Ok(())
+}
+
+/// 从文件名推断参数并转换，返回推断出的 PCM 格式
+#[uniffi::export]
+pub fn auto_convert(input_path: String, output_path: String, format: OutputFormat) -> Result<PcmFormat, AudioHelperError> {
+    let format = match format {
+        OutputFormat::Wav => AudioFormat::Wav,
+        OutputFormat::Mp3 => AudioFormat::Mp3,
+    };
+    let config = crate::auto_convert_pcm(input_path, output_path, format)?;
+    Ok(PcmFormat { sample_rate: config.sample_rate, channels: config.channels, bits_per_sample: config.bits_per_sample })
+}
+
+/// 将内存中的 PCM 数据转换为 WAV
+#[uniffi::export]
+pub fn pcm_bytes_to_wav(pcm: Vec<u8>, format: Option<PcmFormat>) -> Result<Vec<u8>, AudioHelperError> {
+    Ok(crate::trans_pcm_bytes_to_wav(&pcm, format.map(PcmToWavConfig::from))?)
+}
+
+/// 将内存中的 PCM 数据编码为 MP3
+#[uniffi::export]
+pub fn pcm_bytes_to_mp3(pcm: Vec<u8>, settings: Option<Mp3Settings>) -> Result<Vec<u8>, AudioHelperError> {
+    let config = settings.map(crate::Mp3Config::try_from).transpose()?;
+    Ok(crate::trans_pcm_bytes_to_mp3(&pcm, config)?)
+}
+
+/// 读取音频文件信息
+#[uniffi::export]
+pub fn probe(path: String) -> Result<AudioFileInfo, AudioHelperError> {
+    let info = crate::probe_audio(path)?;
+    let format = match info.format {
+        DetectedFormat::Wav(_) => FileFormat::Wav,
+        DetectedFormat::Mp3 => FileFormat::Mp3,
+        DetectedFormat::Flac => FileFormat::Flac,
+        DetectedFormat::Ogg => FileFormat::Ogg,
+        DetectedFormat::Registered(_) => FileFormat::Registered,
+        DetectedFormat::Unknown => FileFormat::Pcm,
+    };
+    Ok(AudioFileInfo {
+        format,
+        pcm_format: info.config.map(|config| PcmFormat {
+            sample_rate: config.sample_rate,
+            channels: config.channels,
+            bits_per_sample: config.bits_per_sample,
+        }),
+        duration_ms: info.duration.map(|duration| duration.as_millis() as u64),
+        data_size: info.data_size,
+        file_size: info.file_size,
+    })
+}
+
+/// 从文件名推断 PCM 格式
+#[uniffi::export]
+pub fn infer_format_from_filename(filename: String) -> PcmFormat {
+    let config = crate::infer_audio_config_from_filename(&filename);
+    PcmFormat { sample_rate: config.sample_rate, channels: config.channels, bits_per_sample: config.bits_per_sample }
+}
+
+/// 库版本
+#[uniffi::export]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
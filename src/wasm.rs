@@ -0,0 +1,45 @@
+// WebAssembly 绑定：在浏览器中把录制的 PCM 数据转换为 WAV，不依赖文件系统
+//
+// MP3 编码依赖的 LAME 是 C 库，无法编译到 wasm32-unknown-unknown，因此这里只导出 WAV 转换
+
+use wasm_bindgen::prelude::*;
+
+use crate::{PcmToWavConfig, SampleFormat};
+
+/// 将原始 PCM 数据转换为 WAV：`pcmToWav(pcm: Uint8Array, sampleRate, channels, bitsPerSample): Uint8Array`
+#[wasm_bindgen(js_name = pcmToWav)]
+pub fn pcm_to_wav(pcm: &[u8], sample_rate: u32, channels: u8, bits_per_sample: u16) -> Result<Vec<u8>, JsError> {
+    let config = PcmToWavConfig::new(sample_rate, channels, bits_per_sample);
+    crate::trans_pcm_bytes_to_wav(pcm, Some(config)).map_err(|e| JsError::new(&e.to_string()))
+}
+
+/// 将 Web Audio 的浮点样本（交错，-1.0 ~ 1.0）转换为 16 位 WAV：
+/// `floatToWav(samples: Float32Array, sampleRate, channels): Uint8Array`
+#[wasm_bindgen(js_name = floatToWav)]
+pub fn float_to_wav(samples: &[f32], sample_rate: u32, channels: u8) -> Result<Vec<u8>, JsError> {
+    let pcm = crate::encode_samples_f32(samples, SampleFormat::S16);
+    pcm_to_wav(&pcm, sample_rate, channels, 16)
+}
+
+/// 从文件名推断的 PCM 参数：`inferConfig(filename): { sampleRate, channels, bitsPerSample }`
+#[wasm_bindgen(js_name = InferredPcmConfig)]
+pub struct InferredPcmConfig {
+    #[wasm_bindgen(js_name = sampleRate)]
+    pub sample_rate: u32,
+    pub channels: u8,
+    #[wasm_bindgen(js_name = bitsPerSample)]
+    pub bits_per_sample: u16,
+}
+
+/// 从文件名推断 PCM 参数
+#[wasm_bindgen(js_name = inferConfig)]
+pub fn infer_config(filename: &str) -> InferredPcmConfig {
+    let config = crate::infer_audio_config_from_filename(filename);
+    InferredPcmConfig { sample_rate: config.sample_rate, channels: config.channels, bits_per_sample: config.bits_per_sample }
+}
+
+/// 库版本
+#[wasm_bindgen]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
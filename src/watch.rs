@@ -0,0 +1,189 @@
+// 监视目录：新放入的 PCM 文件写入完成后自动转换（热文件夹）
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::batch::{convert_item, output_extension};
+use crate::{AudioFormat, BatchItem, OverwritePolicy};
+
+/// 监视目录选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchOptions {
+    /// 输出格式
+    pub format: AudioFormat,
+    /// 输出目录，None 表示输出到输入文件所在目录
+    pub output_dir: Option<PathBuf>,
+    /// 是否监视子目录（输出保持相同的目录结构）
+    pub recursive: bool,
+    /// 文件大小保持不变多长时间后视为写入完成
+    pub settle_delay: Duration,
+    /// 输出文件已存在时的处理方式，默认覆盖
+    pub overwrite: OverwritePolicy,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        WatchOptions {
+            format: AudioFormat::Wav,
+            output_dir: None,
+            recursive: false,
+            settle_delay: Duration::from_millis(500),
+            overwrite: OverwritePolicy::Overwrite,
+        }
+    }
+}
+
+impl WatchOptions {
+    /// 创建指定输出格式的监视选项
+    pub fn new(format: AudioFormat) -> Self {
+        WatchOptions {
+            format,
+            ..Default::default()
+        }
+    }
+
+    /// 设置输出目录
+    pub fn with_output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
+    /// 设置是否监视子目录
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// 设置判断文件写入完成的等待时间
+    pub fn with_settle_delay(mut self, settle_delay: Duration) -> Self {
+        self.settle_delay = settle_delay;
+        self
+    }
+
+    /// 设置输出文件已存在时的处理方式
+    pub fn with_overwrite(mut self, overwrite: OverwritePolicy) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+}
+
+/// 正在监视的目录，调用 `stop` 或离开作用域时停止监视
+pub struct DirectoryWatcher {
+    watcher: Option<RecommendedWatcher>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl DirectoryWatcher {
+    /// 停止监视并等待后台线程退出，尚未写入完成的文件不会被转换
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        // 销毁 watcher 会关闭事件通道，后台线程随之退出
+        self.watcher.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for DirectoryWatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// 等待写入完成的文件
+struct PendingFile {
+    size: u64,
+    changed: Instant,
+}
+
+/// 监视目录，自动转换新放入的 PCM 文件
+///
+/// 只处理开始监视后新建或修改的 .pcm 文件，音频参数从文件名推断
+/// # Arguments
+/// * `dir` - 监视的目录
+/// * `options` - 输出格式、输出目录、是否递归、写入完成的判断时间和覆盖策略
+/// * `callback` - 每个文件转换结束（成功或失败）后在后台线程中调用
+/// # Returns
+/// * `Result<DirectoryWatcher, Box<dyn std::error::Error>>` - 监视句柄
+pub fn watch_directory(
+    dir: impl AsRef<Path>,
+    options: WatchOptions,
+    mut callback: impl FnMut(BatchItem) + Send + 'static,
+) -> Result<DirectoryWatcher, Box<dyn std::error::Error>> {
+    let dir = dir.as_ref();
+    if !dir.is_dir() {
+        return Err(std::io::Error::new(std::io::ErrorKind::NotFound, "Watch directory does not exist").into());
+    }
+    let dir = dir.canonicalize()?;
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    let mode = if options.recursive { RecursiveMode::Recursive } else { RecursiveMode::NonRecursive };
+    watcher.watch(&dir, mode)?;
+    log::info!("Watching {} for new PCM files", dir.display());
+
+    let worker = std::thread::spawn(move || {
+        let mut pending: HashMap<PathBuf, PendingFile> = HashMap::new();
+        let poll_interval = (options.settle_delay / 4).max(Duration::from_millis(10));
+        loop {
+            match receiver.recv_timeout(poll_interval) {
+                Ok(Ok(event)) => {
+                    for path in event.paths {
+                        if !path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("pcm")) {
+                            continue;
+                        }
+                        match event.kind {
+                            EventKind::Create(_) | EventKind::Modify(_) => {
+                                let size = std::fs::metadata(&path).map_or(0, |m| m.len());
+                                pending.insert(path, PendingFile { size, changed: Instant::now() });
+                            }
+                            EventKind::Remove(_) => {
+                                pending.remove(&path);
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Ok(Err(e)) => log::warn!("Watch error on {}: {}", dir.display(), e),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            // 文件大小在等待时间内不再变化才开始转换，避免读到写了一半的文件
+            let mut ready = Vec::new();
+            pending.retain(|path, file| {
+                let Ok(metadata) = std::fs::metadata(path) else { return false };
+                if metadata.len() != file.size {
+                    file.size = metadata.len();
+                    file.changed = Instant::now();
+                    true
+                } else if file.changed.elapsed() >= options.settle_delay {
+                    ready.push(path.clone());
+                    false
+                } else {
+                    true
+                }
+            });
+
+            for input in ready {
+                let relative = input.strip_prefix(&dir).unwrap_or(&input);
+                let output = options.output_dir.as_deref().unwrap_or(&dir).join(relative).with_extension(output_extension(&options.format));
+                callback(convert_item(&input, &output, &options.format, options.overwrite, None));
+            }
+        }
+    });
+
+    Ok(DirectoryWatcher {
+        watcher: Some(watcher),
+        worker: Some(worker),
+    })
+}
@@ -0,0 +1,1133 @@
+// WAV 容器相关：文件头写入（RIFF / RF64 / Wave64）与读取（RIFF / RF64）
+
+use std::io::{Read, Seek, SeekFrom, Write};
+use byteorder::{LittleEndian, WriteBytesExt};
+
+use crate::{AudioConfig, AudioError};
+use crate::adpcm::{self, AdpcmFormat};
+use crate::sample::{self, SampleFormat};
+
+/// 标准 RIFF 头中 32 位大小字段能表示的最大值
+const RIFF_MAX_SIZE: u64 = u32::MAX as u64;
+
+/// fmt 块大小（PCM 格式）
+const FMT_CHUNK_SIZE: u32 = 16;
+
+/// fmt 块大小（非 PCM 格式，带 cbSize 字段）
+const NON_PCM_FMT_CHUNK_SIZE: u32 = 18;
+
+/// fmt 块大小（IMA ADPCM 格式，带 samplesPerBlock 字段）
+const ADPCM_FMT_CHUNK_SIZE: u32 = 20;
+
+/// fmt 块大小（WAVE_FORMAT_EXTENSIBLE 格式）
+const EXTENSIBLE_FMT_CHUNK_SIZE: u32 = 40;
+
+/// ds64 块内容大小（不含表项）
+const DS64_CHUNK_SIZE: u32 = 28;
+
+/// fact 块内容大小（RIFF / RF64）
+const FACT_CHUNK_SIZE: u32 = 4;
+
+/// WAVE_FORMAT_PCM
+const WAVE_FORMAT_PCM: u16 = 0x0001;
+
+/// WAVE_FORMAT_EXTENSIBLE
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// WAVE_FORMAT_IEEE_FLOAT
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 0x0003;
+
+/// WAVE_FORMAT_ALAW
+const WAVE_FORMAT_ALAW: u16 = 0x0006;
+
+/// WAVE_FORMAT_MULAW
+const WAVE_FORMAT_MULAW: u16 = 0x0007;
+
+/// WAVE_FORMAT_IMA_ADPCM
+const WAVE_FORMAT_IMA_ADPCM: u16 = 0x0011;
+
+/// KSDATAFORMAT_SUBTYPE_* GUID 的公共部分，前两个字节为格式标签
+const SUBTYPE_GUID_TAIL: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+    0x80, 0x00, 0x00, 0xAA, 0x00, 0x38, 0x9B, 0x71,
+];
+
+/// Wave64 各块的 GUID
+const W64_RIFF_GUID: [u8; 16] = [
+    0x72, 0x69, 0x66, 0x66, 0x2E, 0x91, 0xCF, 0x11,
+    0xA5, 0xD6, 0x28, 0xDB, 0x04, 0xC1, 0x00, 0x00,
+];
+const W64_WAVE_GUID: [u8; 16] = [
+    0x77, 0x61, 0x76, 0x65, 0xF3, 0xAC, 0xD3, 0x11,
+    0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+const W64_FMT_GUID: [u8; 16] = [
+    0x66, 0x6D, 0x74, 0x20, 0xF3, 0xAC, 0xD3, 0x11,
+    0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+const W64_DATA_GUID: [u8; 16] = [
+    0x64, 0x61, 0x74, 0x61, 0xF3, 0xAC, 0xD3, 0x11,
+    0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+const W64_FACT_GUID: [u8; 16] = [
+    0x66, 0x61, 0x63, 0x74, 0xF3, 0xAC, 0xD3, 0x11,
+    0x8C, 0xD1, 0x00, 0xC0, 0x4F, 0x8E, 0xDB, 0x8A,
+];
+
+/// WAV 容器格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WavContainer {
+    /// 标准 RIFF/WAVE，数据不能超过 4 GB
+    Riff,
+    /// EBU RF64，通过 ds64 块记录 64 位大小
+    Rf64,
+    /// Sony Wave64，所有块大小均为 64 位
+    Wave64,
+}
+
+impl WavContainer {
+    /// 根据数据大小自动选择容器：超过 4 GB 时升级为 RF64（按最大的 fmt 块计算）
+    pub fn auto_for(data_size: u64) -> Self {
+        if riff_size(EXTENSIBLE_FMT_CHUNK_SIZE, true, data_size) > RIFF_MAX_SIZE {
+            WavContainer::Rf64
+        } else {
+            WavContainer::Riff
+        }
+    }
+}
+
+/// WAV 文件头参数
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WavHeader {
+    pub sample_rate: u32,
+    pub channels: u8,
+    pub bits_per_sample: u16,
+    pub data_size: u64,
+    pub container: WavContainer,
+    pub force_extensible: bool,
+    pub sample_format: SampleFormat,
+    /// 数据为 IMA ADPCM 编码时的块布局
+    pub adpcm: Option<AdpcmFormat>,
+    /// 扬声器位置掩码，为 None 时按声道数使用默认布局
+    pub channel_mask: Option<u32>,
+}
+
+impl WavHeader {
+    fn byte_rate(&self) -> u32 {
+        match self.adpcm {
+            Some(adpcm) => {
+                let per_block = adpcm.samples_per_block(self.channels) as u64;
+                (self.sample_rate as u64 * adpcm.block_align as u64 / per_block) as u32
+            }
+            None => self.sample_rate * self.channels as u32 * (self.bits_per_sample / 8) as u32,
+        }
+    }
+
+    fn block_align(&self) -> u16 {
+        match self.adpcm {
+            Some(adpcm) => adpcm.block_align,
+            None => self.channels as u16 * (self.bits_per_sample / 8),
+        }
+    }
+
+    /// 多声道、高位深或显式要求时使用 WAVE_FORMAT_EXTENSIBLE（ADPCM 不使用）
+    pub fn is_extensible(&self) -> bool {
+        self.adpcm.is_none() && (self.force_extensible || self.channel_mask.is_some() || self.channels > 2 || self.bits_per_sample > 16)
+    }
+
+    /// 样本编码对应的格式标签（不考虑 EXTENSIBLE 包装）
+    fn format_code(&self) -> u16 {
+        if self.adpcm.is_some() {
+            return WAVE_FORMAT_IMA_ADPCM;
+        }
+        match self.sample_format {
+            SampleFormat::F32 => WAVE_FORMAT_IEEE_FLOAT,
+            SampleFormat::Alaw => WAVE_FORMAT_ALAW,
+            SampleFormat::Ulaw => WAVE_FORMAT_MULAW,
+            _ => WAVE_FORMAT_PCM,
+        }
+    }
+
+    /// 非整数 PCM 格式需要 fact 块记录采样帧数
+    fn has_fact(&self) -> bool {
+        self.format_code() != WAVE_FORMAT_PCM
+    }
+
+    /// 采样帧数
+    pub fn frames(&self) -> u64 {
+        match self.adpcm {
+            Some(adpcm) => adpcm.frames,
+            None => self.data_size / self.block_align().max(1) as u64,
+        }
+    }
+
+    fn fmt_chunk_size(&self) -> u32 {
+        if self.adpcm.is_some() {
+            ADPCM_FMT_CHUNK_SIZE
+        } else if self.is_extensible() {
+            EXTENSIBLE_FMT_CHUNK_SIZE
+        } else if self.has_fact() {
+            NON_PCM_FMT_CHUNK_SIZE
+        } else {
+            FMT_CHUNK_SIZE
+        }
+    }
+
+    /// data 块之后需要补齐的字节数（RIFF 块按 2 字节对齐，Wave64 要求块按 8 字节对齐）
+    pub fn padding(&self) -> u64 {
+        match self.container {
+            WavContainer::Riff | WavContainer::Rf64 => self.data_size & 1,
+            WavContainer::Wave64 => w64_align(self.data_size),
+        }
+    }
+}
+
+/// RIFF 大小字段的值（文件大小 - 8），包含奇数长度 data 块之后的填充字节
+fn riff_size(fmt_chunk_size: u32, has_fact: bool, data_size: u64) -> u64 {
+    let fact = if has_fact { 8 + FACT_CHUNK_SIZE as u64 } else { 0 };
+    4 + (8 + fmt_chunk_size as u64) + fact + 8 + data_size + (data_size & 1)
+}
+
+/// Wave64 块按 8 字节对齐所需的填充字节数
+fn w64_align(size: u64) -> u64 {
+    (8 - size % 8) % 8
+}
+
+/// 多声道 WAV 的扬声器布局，决定 WAVE_FORMAT_EXTENSIBLE 中的声道掩码和交错样本中各声道的顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChannelLayout {
+    /// 单声道（FC）
+    Mono,
+    /// 立体声（FL FR）
+    Stereo,
+    /// 四声道（FL FR BL BR）
+    Quad,
+    /// 5.1 环绕声（FL FR FC LFE BL BR）
+    Surround51,
+    /// 7.1 环绕声（FL FR FC LFE BL BR SL SR）
+    Surround71,
+}
+
+impl ChannelLayout {
+    /// 声道数
+    pub fn channels(&self) -> u8 {
+        match self {
+            ChannelLayout::Mono => 1,
+            ChannelLayout::Stereo => 2,
+            ChannelLayout::Quad => 4,
+            ChannelLayout::Surround51 => 6,
+            ChannelLayout::Surround71 => 8,
+        }
+    }
+
+    /// WAVE_FORMAT_EXTENSIBLE 的扬声器位置掩码（dwChannelMask）
+    pub fn channel_mask(&self) -> u32 {
+        default_channel_mask(self.channels())
+    }
+
+    /// 按声道数选择默认布局
+    pub fn from_channels(channels: u8) -> Option<Self> {
+        match channels {
+            1 => Some(ChannelLayout::Mono),
+            2 => Some(ChannelLayout::Stereo),
+            4 => Some(ChannelLayout::Quad),
+            6 => Some(ChannelLayout::Surround51),
+            8 => Some(ChannelLayout::Surround71),
+            _ => None,
+        }
+    }
+}
+
+impl std::str::FromStr for ChannelLayout {
+    type Err = AudioError;
+
+    /// 解析布局名称：mono、stereo、quad、5.1、7.1
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "mono" => Ok(ChannelLayout::Mono),
+            "stereo" => Ok(ChannelLayout::Stereo),
+            "quad" => Ok(ChannelLayout::Quad),
+            "5.1" => Ok(ChannelLayout::Surround51),
+            "7.1" => Ok(ChannelLayout::Surround71),
+            other => Err(AudioError::InvalidConfig(format!("Unknown channel layout: {} (expected mono, stereo, quad, 5.1 or 7.1)", other))),
+        }
+    }
+}
+
+/// 按声道数返回默认的扬声器位置掩码
+pub(crate) fn default_channel_mask(channels: u8) -> u32 {
+    match channels {
+        1 => 0x4,   // FC
+        2 => 0x3,   // FL FR
+        3 => 0x7,   // FL FR FC
+        4 => 0x33,  // FL FR BL BR
+        5 => 0x37,  // FL FR FC BL BR
+        6 => 0x3F,  // 5.1
+        7 => 0x13F, // 6.1
+        8 => 0x63F, // 7.1
+        _ => 0,
+    }
+}
+
+/// 根据文件开头的字节识别 WAV 容器格式
+pub(crate) fn detect_container(header: &[u8]) -> Option<WavContainer> {
+    if header.len() >= 40 && header[0..16] == W64_RIFF_GUID && header[24..40] == W64_WAVE_GUID {
+        return Some(WavContainer::Wave64);
+    }
+    if header.len() < 12 || &header[8..12] != b"WAVE" {
+        return None;
+    }
+    match &header[0..4] {
+        b"RIFF" => Some(WavContainer::Riff),
+        b"RF64" => Some(WavContainer::Rf64),
+        _ => None,
+    }
+}
+
+/// 写入 WAV 文件头
+pub(crate) fn write_wav_header<W: Write>(
+    writer: &mut W,
+    header: &WavHeader,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match header.container {
+        WavContainer::Riff => {
+            let size = riff_size(header.fmt_chunk_size(), header.has_fact(), header.data_size);
+            if size > RIFF_MAX_SIZE {
+                return Err(AudioError::InvalidConfig(format!(
+                    "Data size {} bytes exceeds the 4 GB RIFF limit, use RF64 or Wave64",
+                    header.data_size
+                )).into());
+            }
+
+            // RIFF 头
+            writer.write_all(b"RIFF")?;
+            writer.write_u32::<LittleEndian>(size as u32)?; // 文件大小 - 8
+            writer.write_all(b"WAVE")?;
+            write_riff_chunks(writer, header)?;
+        }
+        WavContainer::Rf64 => {
+            let size = riff_size(header.fmt_chunk_size(), header.has_fact(), header.data_size) + 8 + DS64_CHUNK_SIZE as u64;
+
+            // RF64 头，32 位大小字段固定为 0xFFFFFFFF
+            writer.write_all(b"RF64")?;
+            writer.write_u32::<LittleEndian>(u32::MAX)?;
+            writer.write_all(b"WAVE")?;
+
+            // ds64 块
+            writer.write_all(b"ds64")?;
+            writer.write_u32::<LittleEndian>(DS64_CHUNK_SIZE)?;
+            writer.write_u64::<LittleEndian>(size)?;
+            writer.write_u64::<LittleEndian>(header.data_size)?;
+            writer.write_u64::<LittleEndian>(header.frames())?; // 采样帧数
+            writer.write_u32::<LittleEndian>(0)?; // 表项数
+
+            write_fmt_body(writer, header, true)?;
+            // 帧数记录在 ds64 中，fact 块中的 32 位字段固定为 0xFFFFFFFF
+            write_fact_chunk(writer, header, u32::MAX)?;
+
+            writer.write_all(b"data")?;
+            writer.write_u32::<LittleEndian>(u32::MAX)?;
+        }
+        WavContainer::Wave64 => {
+            // Wave64 块大小包含 24 字节块头
+            let fmt_chunk = 24 + header.fmt_chunk_size() as u64;
+            let fmt_padding = w64_align(fmt_chunk);
+            let fact_chunk = if header.has_fact() { 24 + 8 } else { 0 };
+            let data_chunk = 24 + header.data_size;
+            let total = 16 + 8 + 16 + fmt_chunk + fmt_padding + fact_chunk + data_chunk + header.padding();
+
+            writer.write_all(&W64_RIFF_GUID)?;
+            writer.write_u64::<LittleEndian>(total)?;
+            writer.write_all(&W64_WAVE_GUID)?;
+
+            writer.write_all(&W64_FMT_GUID)?;
+            writer.write_u64::<LittleEndian>(fmt_chunk)?;
+            write_fmt_body(writer, header, false)?;
+            writer.write_all(&vec![0u8; fmt_padding as usize])?;
+
+            if header.has_fact() {
+                writer.write_all(&W64_FACT_GUID)?;
+                writer.write_u64::<LittleEndian>(fact_chunk)?;
+                writer.write_u64::<LittleEndian>(header.frames())?;
+            }
+
+            writer.write_all(&W64_DATA_GUID)?;
+            writer.write_u64::<LittleEndian>(data_chunk)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 写入流式输出的文件头：数据不超过 4 GB 时写入 RIFF 头，并在 ds64 的位置放置等长的 JUNK 块；
+/// 超过时改写为 RF64 头。两种文件头长度相同，可以在写完数据后原地回填
+pub(crate) fn write_promotable_header<W: Write>(
+    writer: &mut W,
+    header: &WavHeader,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let size = riff_size(header.fmt_chunk_size(), header.has_fact(), header.data_size) + 8 + DS64_CHUNK_SIZE as u64;
+    if size > RIFF_MAX_SIZE {
+        return write_wav_header(writer, &WavHeader { container: WavContainer::Rf64, ..header.clone() });
+    }
+
+    writer.write_all(b"RIFF")?;
+    writer.write_u32::<LittleEndian>(size as u32)?; // 文件大小 - 8
+    writer.write_all(b"WAVE")?;
+
+    // 为 ds64 块预留的空间
+    writer.write_all(b"JUNK")?;
+    writer.write_u32::<LittleEndian>(DS64_CHUNK_SIZE)?;
+    writer.write_all(&[0u8; DS64_CHUNK_SIZE as usize])?;
+
+    write_riff_chunks(writer, header)
+}
+
+/// 写入 RIFF 头之后的 fmt、fact 和 data 块头
+fn write_riff_chunks<W: Write>(
+    writer: &mut W,
+    header: &WavHeader,
+) -> Result<(), Box<dyn std::error::Error>> {
+    write_fmt_body(writer, header, true)?;
+    write_fact_chunk(writer, header, header.frames() as u32)?;
+
+    // data 块
+    writer.write_all(b"data")?;
+    writer.write_u32::<LittleEndian>(header.data_size as u32)?;
+    Ok(())
+}
+
+/// 写入 fmt 块内容，`with_chunk_header` 为 true 时同时写入 RIFF 风格的块头
+fn write_fmt_body<W: Write>(
+    writer: &mut W,
+    header: &WavHeader,
+    with_chunk_header: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let extensible = header.is_extensible();
+
+    if with_chunk_header {
+        writer.write_all(b"fmt ")?;
+        writer.write_u32::<LittleEndian>(header.fmt_chunk_size())?; // fmt 块大小
+    }
+    let format_tag = if extensible { WAVE_FORMAT_EXTENSIBLE } else { header.format_code() };
+    writer.write_u16::<LittleEndian>(format_tag)?;
+    writer.write_u16::<LittleEndian>(header.channels as u16)?;
+    writer.write_u32::<LittleEndian>(header.sample_rate)?;
+    writer.write_u32::<LittleEndian>(header.byte_rate())?;
+    writer.write_u16::<LittleEndian>(header.block_align())?;
+    writer.write_u16::<LittleEndian>(header.bits_per_sample)?;
+
+    if extensible {
+        writer.write_u16::<LittleEndian>(22)?; // cbSize
+        writer.write_u16::<LittleEndian>(header.bits_per_sample)?; // 有效位数
+        writer.write_u32::<LittleEndian>(header.channel_mask.unwrap_or_else(|| default_channel_mask(header.channels)))?;
+        writer.write_all(&header.format_code().to_le_bytes())?; // 子格式 GUID
+        writer.write_all(&SUBTYPE_GUID_TAIL)?;
+    } else if let Some(adpcm) = header.adpcm {
+        writer.write_u16::<LittleEndian>(2)?; // cbSize
+        writer.write_u16::<LittleEndian>(adpcm.samples_per_block(header.channels) as u16)?;
+    } else if header.has_fact() {
+        writer.write_u16::<LittleEndian>(0)?; // cbSize
+    }
+    Ok(())
+}
+
+/// 非 PCM 格式在 fmt 块之后写入 fact 块（RIFF / RF64）
+fn write_fact_chunk<W: Write>(
+    writer: &mut W,
+    header: &WavHeader,
+    frames: u32,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if header.has_fact() {
+        writer.write_all(b"fact")?;
+        writer.write_u32::<LittleEndian>(FACT_CHUNK_SIZE)?;
+        writer.write_u32::<LittleEndian>(frames)?;
+    }
+    Ok(())
+}
+
+/// 从 WAV 文件中读取的音频数据
+#[derive(Debug, Clone, PartialEq)]
+pub struct WavAudio {
+    /// 音频参数，`sample_format` 描述 `data` 的编码（ADPCM 会被解码为 16 位 PCM）
+    pub config: AudioConfig,
+    /// 小端样本数据
+    pub data: Vec<u8>,
+}
+
+impl WavAudio {
+    /// 采样帧数
+    pub fn frames(&self) -> usize {
+        let frame_size = self.config.sample_format.bytes_per_sample() * self.config.channels.max(1) as usize;
+        self.data.len() / frame_size
+    }
+
+    /// 解码为交错的 i16 样本
+    pub fn samples_i16(&self) -> Vec<i16> {
+        sample::decode_samples_i16(&self.data, self.config.sample_format)
+    }
+
+    /// 解码为交错的 f32 样本
+    pub fn samples_f32(&self) -> Vec<f32> {
+        sample::decode_samples_f32(&self.data, self.config.sample_format)
+    }
+}
+
+/// fmt 块中读取的格式信息
+struct FmtChunk {
+    format_code: u16,
+    channels: u16,
+    sample_rate: u32,
+    block_align: u16,
+    bits_per_sample: u16,
+}
+
+/// 只读取 WAV 文件头得到的信息（不读取样本数据）
+pub(crate) struct WavInfo {
+    fmt: FmtChunk,
+    fact_frames: Option<u64>,
+    /// data 块内容在文件中的偏移
+    pub data_offset: u64,
+    /// data 块的字节数
+    pub data_size: u64,
+}
+
+impl WavInfo {
+    /// 采样率
+    pub fn sample_rate(&self) -> u32 {
+        self.fmt.sample_rate
+    }
+
+    /// 采样帧数：ADPCM 优先使用 fact 块记录的帧数，其余格式按块对齐计算
+    pub fn frames(&self) -> u64 {
+        let block_align = self.fmt.block_align.max(1) as u64;
+        if self.fmt.format_code != WAVE_FORMAT_IMA_ADPCM {
+            return self.data_size / block_align;
+        }
+        if let Some(frames) = self.fact_frames {
+            return frames;
+        }
+        let channels = self.fmt.channels.clamp(1, u8::MAX as u16) as u8;
+        let per_block = adpcm::samples_per_block(self.fmt.block_align, channels) as u64;
+        // 最后一个不完整的块：块头含 1 帧，其余每字节 2 个样本
+        let remainder = self.data_size % block_align;
+        let header = 4 * channels as u64;
+        let partial = if remainder >= header { 1 + (remainder - header) * 2 / channels as u64 } else { 0 };
+        self.data_size / block_align * per_block + partial
+    }
+
+    /// 数据是否为 IMA ADPCM 编码
+    pub fn is_adpcm(&self) -> bool {
+        self.fmt.format_code == WAVE_FORMAT_IMA_ADPCM
+    }
+
+    /// 块对齐字节数（PCM 为每帧字节数，ADPCM 为每块字节数）
+    pub fn block_align(&self) -> u16 {
+        self.fmt.block_align
+    }
+
+    /// 音频参数，IMA ADPCM 为解码后的 16 位 PCM 参数
+    pub fn config(&self) -> Result<AudioConfig, Box<dyn std::error::Error>> {
+        let fmt = &self.fmt;
+        if fmt.channels == 0 || fmt.channels > u8::MAX as u16 {
+            return Err(AudioError::InvalidConfig(format!("Invalid channel count {}", fmt.channels)).into());
+        }
+        let sample_format = match fmt.format_code {
+            WAVE_FORMAT_PCM => SampleFormat::from_bits(fmt.bits_per_sample)
+                .ok_or_else(|| AudioError::UnsupportedFormat(format!("Unsupported PCM bit depth {}", fmt.bits_per_sample)))?,
+            WAVE_FORMAT_IEEE_FLOAT if fmt.bits_per_sample == 32 => SampleFormat::F32,
+            WAVE_FORMAT_ALAW => SampleFormat::Alaw,
+            WAVE_FORMAT_MULAW => SampleFormat::Ulaw,
+            WAVE_FORMAT_IMA_ADPCM => SampleFormat::S16,
+            code => return Err(AudioError::UnsupportedFormat(format!("Unsupported WAV format tag 0x{:04X}", code)).into()),
+        };
+        Ok(AudioConfig::new(fmt.sample_rate, fmt.channels as u8, sample_format.bits_per_sample()).with_sample_format(sample_format))
+    }
+}
+
+/// WAV 文件中的一个块
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WavChunk {
+    /// 块标识（Wave64 取 GUID 的前 4 个字节，如 `fmt `、`data`）
+    pub id: String,
+    /// 块头在文件中的偏移
+    pub offset: u64,
+    /// 块内容的字节数（不含块头）
+    pub size: u64,
+}
+
+/// 列出 WAV 文件顶层的所有块（RIFF / RF64 / Wave64），遇到截断的块时停止
+pub(crate) fn read_chunks<R: Read + Seek>(reader: &mut R, len: u64) -> Result<Vec<WavChunk>, Box<dyn std::error::Error>> {
+    let mut header = Vec::with_capacity(40);
+    reader.by_ref().take(40).read_to_end(&mut header)?;
+    let container = detect_container(&header).ok_or_else(|| AudioError::UnsupportedFormat("Not a WAV file".into()))?;
+    // Wave64 块头为 16 字节 GUID + 8 字节大小（含块头），按 8 字节对齐
+    let (header_len, align, mut pos) = match container {
+        WavContainer::Wave64 => (24u64, 8u64, 40u64),
+        _ => (8, 2, 12),
+    };
+
+    let mut chunks = Vec::new();
+    let mut ds64_data_size = None;
+    while pos + header_len <= len {
+        let mut chunk_header = [0u8; 24];
+        reader.seek(SeekFrom::Start(pos))?;
+        reader.read_exact(&mut chunk_header[..header_len as usize])?;
+        let id = String::from_utf8_lossy(&chunk_header[0..4]).into_owned();
+        let size = if container == WavContainer::Wave64 {
+            u64::from_le_bytes(chunk_header[16..24].try_into().unwrap()).saturating_sub(header_len)
+        } else {
+            let declared = u32::from_le_bytes(chunk_header[4..8].try_into().unwrap()) as u64;
+            match ds64_data_size {
+                Some(size) if id == "data" && declared == u32::MAX as u64 => size,
+                _ => declared,
+            }
+        };
+        if id == "ds64" && size >= 16 {
+            let mut ds64 = [0u8; 16];
+            reader.read_exact(&mut ds64)?;
+            ds64_data_size = Some(u64::from_le_bytes(ds64[8..16].try_into().unwrap()));
+        }
+
+        let truncated = pos + header_len + size > len;
+        chunks.push(WavChunk { id, offset: pos, size: size.min(len - pos - header_len) });
+        if truncated {
+            log::warn!("Chunk at offset {} is truncated", pos);
+            break;
+        }
+        pos = (pos + header_len + size).next_multiple_of(align);
+    }
+    Ok(chunks)
+}
+
+/// WAV 解析模式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum WavParseMode {
+    /// 宽松模式：跳过未知块，容忍被截断的 data 块、缺失的填充字节和缺少 ds64 的 RF64 文件
+    #[default]
+    Lenient,
+    /// 严格模式：遇到未知块或任何不符合规范的结构都报错
+    Strict,
+}
+
+/// 严格模式下允许出现的块（格式相关块和常见的元数据块）
+const KNOWN_CHUNKS: [&[u8; 4]; 14] = [
+    b"fmt ", b"fact", b"data", b"ds64", b"LIST", b"JUNK", b"junk", b"PAD ",
+    b"bext", b"cue ", b"smpl", b"inst", b"iXML", b"id3 ",
+];
+
+/// 块 ID 是否由可打印 ASCII 字符组成
+fn is_plausible_chunk_id(id: &[u8]) -> bool {
+    id.len() == 4 && id.iter().all(|b| (0x20..=0x7E).contains(b))
+}
+
+/// 遍历 RIFF / RF64 文件的块，读取格式信息并定位 data 块
+/// # Arguments
+/// * `reader` - 位于文件开头的读取器
+/// * `len` - 文件总字节数，用于检查块是否被截断
+/// * `mode` - 遇到不规范结构时报错还是尽量恢复
+pub(crate) fn read_wav_info<R: Read + Seek>(reader: &mut R, len: u64, mode: WavParseMode) -> Result<WavInfo, Box<dyn std::error::Error>> {
+    let strict = mode == WavParseMode::Strict;
+    let mut riff = [0u8; 12];
+    if len < 12 || reader.read_exact(&mut riff).is_err() || &riff[8..12] != b"WAVE" {
+        return Err(AudioError::UnsupportedFormat("Not a WAV file".into()).into());
+    }
+    let rf64 = match &riff[0..4] {
+        b"RIFF" => false,
+        b"RF64" => true,
+        _ => return Err(AudioError::UnsupportedFormat("Unsupported WAV container".into()).into()),
+    };
+
+    let u16_at = |bytes: &[u8], at: usize| u16::from_le_bytes([bytes[at], bytes[at + 1]]);
+    let u32_at = |bytes: &[u8], at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+    let read_id_at = |reader: &mut R, at: u64| -> std::io::Result<[u8; 4]> {
+        let mut id = [0u8; 4];
+        reader.seek(SeekFrom::Start(at))?;
+        reader.read_exact(&mut id)?;
+        Ok(id)
+    };
+
+    let mut fmt = None;
+    let mut fact_frames = None;
+    let mut ds64_data_size = None;
+
+    let mut pos = 12u64;
+    while pos + 8 <= len {
+        let mut chunk_header = [0u8; 8];
+        reader.seek(SeekFrom::Start(pos))?;
+        reader.read_exact(&mut chunk_header)?;
+        let id = &chunk_header[0..4];
+        let chunk_name = String::from_utf8_lossy(id).into_owned();
+        if strict && !KNOWN_CHUNKS.iter().any(|known| known.as_slice() == id) {
+            return Err(AudioError::UnsupportedFormat(format!("Unknown chunk '{}'", chunk_name)).into());
+        }
+
+        let declared = u32_at(&chunk_header, 4) as u64;
+        let body = pos + 8;
+        let size = if id == b"data" && rf64 && declared == u32::MAX as u64 {
+            match ds64_data_size {
+                Some(size) => size,
+                None if strict => return Err(AudioError::InvalidInput("RF64 file is missing the ds64 chunk".into()).into()),
+                // 缺少 ds64 时 data 块延伸到文件末尾
+                None => len - body,
+            }
+        } else {
+            declared
+        };
+
+        let mut end = body + size;
+        if end > len {
+            if strict || id != b"data" {
+                if !strict && fmt.is_some() {
+                    log::warn!("Chunk '{}' is truncated, stopping at offset {}", chunk_name, pos);
+                    break;
+                }
+                return Err(AudioError::InvalidInput(format!("Chunk '{}' is truncated", chunk_name)).into());
+            }
+            // 录音中断的文件：data 块只保留实际存在的字节
+            log::warn!("Data chunk declares {} bytes but only {} are present", size, len - body);
+            end = len;
+        }
+        let size = end - body;
+
+        // 只读取需要解析的小块内容
+        let mut read_body = |max: u64| -> std::io::Result<Vec<u8>> {
+            let mut buf = vec![0u8; size.min(max) as usize];
+            reader.read_exact(&mut buf)?;
+            Ok(buf)
+        };
+        match id {
+            b"ds64" if size >= 16 => {
+                let bytes = read_body(16)?;
+                ds64_data_size = Some(u64::from_le_bytes(bytes[8..16].try_into().unwrap()));
+            }
+            b"fmt " if size >= 16 => {
+                // fmt 块可能比 16 字节长（cbSize 扩展、WAVE_FORMAT_EXTENSIBLE），多余部分忽略
+                let bytes = read_body(40)?;
+                let mut format_code = u16_at(&bytes, 0);
+                // WAVE_FORMAT_EXTENSIBLE 的实际格式记录在子格式 GUID 的前两个字节
+                if format_code == WAVE_FORMAT_EXTENSIBLE && size >= 40 {
+                    format_code = u16_at(&bytes, 24);
+                }
+                fmt = Some(FmtChunk {
+                    format_code,
+                    channels: u16_at(&bytes, 2),
+                    sample_rate: u32_at(&bytes, 4),
+                    block_align: u16_at(&bytes, 12),
+                    bits_per_sample: u16_at(&bytes, 14),
+                });
+            }
+            b"fmt " if strict => return Err(AudioError::InvalidInput(format!("fmt chunk is too small ({} bytes)", size)).into()),
+            b"fact" if size >= 4 => fact_frames = Some(u32_at(&read_body(4)?, 0) as u64),
+            b"data" => {
+                let fmt = fmt.ok_or_else(|| AudioError::InvalidInput("WAV file is missing the fmt chunk".into()))?;
+                return Ok(WavInfo { fmt, fact_frames, data_offset: body, data_size: size });
+            }
+            _ => {}
+        }
+
+        // RIFF 块按 2 字节对齐；部分写入器会省略奇数长度块后的填充字节
+        pos = end + (end & 1);
+        if end & 1 == 1 && !strict && pos + 4 <= len {
+            let padded = read_id_at(reader, pos)?;
+            if !is_plausible_chunk_id(&padded) && is_plausible_chunk_id(&read_id_at(reader, end)?) {
+                log::warn!("Chunk '{}' is missing its pad byte", chunk_name);
+                pos = end;
+            }
+        }
+    }
+
+    fmt.ok_or_else(|| AudioError::InvalidInput("WAV file is missing the fmt chunk".into()))?;
+    Err(AudioError::InvalidInput("WAV file is missing the data chunk".into()).into())
+}
+
+/// 在 RIFF / RF64 文件的 data 块末尾追加样本数据，并更新文件头中的大小字段
+///
+/// 先写入数据再更新文件头：中途出错时文件头仍描述追加前的数据，多出的字节在宽松模式下被忽略
+/// # Arguments
+/// * `file` - 以读写方式打开的 WAV 文件
+/// * `len` - 文件当前的字节数
+/// * `info` - `read_wav_info` 读取的文件头信息
+/// * `data` - 小端样本数据，格式与文件一致（由调用方检查）
+/// # Returns
+/// * 追加后的采样帧数
+pub(crate) fn append_data(file: &mut std::fs::File, len: u64, info: &WavInfo, data: &[u8]) -> Result<u64, Box<dyn std::error::Error>> {
+    file.seek(SeekFrom::Start(0))?;
+    let chunks = read_chunks(file, len)?;
+    let rf64 = match chunks.iter().position(|chunk| chunk.offset + 8 == info.data_offset) {
+        Some(index) if index + 1 < chunks.len() => {
+            return Err(AudioError::UnsupportedFormat(format!("Cannot append: the data chunk is followed by a '{}' chunk", chunks[index + 1].id)).into());
+        }
+        Some(_) => {
+            let mut magic = [0u8; 4];
+            file.seek(SeekFrom::Start(0))?;
+            file.read_exact(&mut magic)?;
+            &magic == b"RF64"
+        }
+        None => return Err(AudioError::InvalidInput("Cannot append: data chunk not found".into()).into()),
+    };
+
+    // 录音中断时 data 块末尾可能残留不完整的帧，追加前将其丢弃
+    let block_align = info.block_align().max(1) as u64;
+    let existing = info.data_size / block_align * block_align;
+    if existing != info.data_size {
+        log::warn!("Dropping {} trailing bytes of an incomplete frame", info.data_size - existing);
+    }
+    let data_size = existing + data.len() as u64;
+    let pad = data_size & 1;
+    let end = info.data_offset + data_size + pad;
+    let frames = data_size / block_align;
+    if !rf64 && end - 8 > RIFF_MAX_SIZE {
+        return Err(AudioError::InvalidInput(format!("Data size {} bytes exceeds the 4 GB RIFF limit, convert the file to RF64 first", data_size)).into());
+    }
+
+    let chunk_offset = |id: &str, min_size: u64| chunks.iter().find(|chunk| chunk.id == id && chunk.size >= min_size).map(|chunk| chunk.offset);
+    let ds64 = if rf64 { Some(chunk_offset("ds64", 24).ok_or_else(|| AudioError::InvalidInput("RF64 file is missing the ds64 chunk".into()))?) } else { None };
+
+    file.seek(SeekFrom::Start(info.data_offset + existing))?;
+    file.write_all(data)?;
+    file.write_all(&vec![0u8; pad as usize])?;
+    file.set_len(end)?;
+
+    if let Some(ds64) = ds64 {
+        // RF64 的大小和帧数记录在 ds64 块中，32 位字段固定为 0xFFFFFFFF
+        file.seek(SeekFrom::Start(ds64 + 8))?;
+        file.write_u64::<LittleEndian>(end - 8)?;
+        file.write_u64::<LittleEndian>(data_size)?;
+        file.write_u64::<LittleEndian>(frames)?;
+        file.seek(SeekFrom::Start(info.data_offset - 4))?;
+        file.write_u32::<LittleEndian>(u32::MAX)?;
+    } else {
+        file.seek(SeekFrom::Start(4))?;
+        file.write_u32::<LittleEndian>((end - 8) as u32)?;
+        file.seek(SeekFrom::Start(info.data_offset - 4))?;
+        file.write_u32::<LittleEndian>(data_size as u32)?;
+        if let Some(fact) = chunk_offset("fact", 4) {
+            file.seek(SeekFrom::Start(fact + 8))?;
+            file.write_u32::<LittleEndian>(frames as u32)?;
+        }
+    }
+    file.flush()?;
+    Ok(frames)
+}
+
+/// 解析 RIFF / RF64 格式的 WAV 文件内容
+pub(crate) fn parse_wav(bytes: &[u8], mode: WavParseMode) -> Result<WavAudio, Box<dyn std::error::Error>> {
+    let info = read_wav_info(&mut std::io::Cursor::new(bytes), bytes.len() as u64, mode)?;
+    let data = &bytes[info.data_offset as usize..(info.data_offset + info.data_size) as usize];
+    let config = info.config()?;
+    let WavInfo { fmt, fact_frames, .. } = info;
+
+    let data = if fmt.format_code == WAVE_FORMAT_IMA_ADPCM {
+        let mut samples = adpcm::decode_ima_adpcm(data, config.channels, fmt.block_align)?;
+        // 最后一块可能被补齐，按 fact 块记录的帧数截断
+        if let Some(frames) = fact_frames {
+            samples.truncate((frames as usize).saturating_mul(config.channels as usize));
+        }
+        samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+    } else {
+        data.to_vec()
+    };
+    Ok(WavAudio { config, data })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(data_size: u64, container: WavContainer) -> WavHeader {
+        WavHeader {
+            sample_rate: 48000,
+            channels: 2,
+            bits_per_sample: 16,
+            data_size,
+            container,
+            force_extensible: false,
+            sample_format: SampleFormat::S16,
+            adpcm: None,
+            channel_mask: None,
+        }
+    }
+
+    #[test]
+    fn test_read_chunks_layout() {
+        for container in [WavContainer::Riff, WavContainer::Rf64, WavContainer::Wave64] {
+            let mut buf = Vec::new();
+            write_wav_header(&mut buf, &header(1001, container)).unwrap();
+            let header_len = buf.len() as u64;
+            buf.extend(std::iter::repeat_n(0u8, 1001));
+            let chunks = read_chunks(&mut std::io::Cursor::new(&buf), buf.len() as u64).unwrap();
+            let data = chunks.last().unwrap();
+            assert_eq!(data.id, "data", "{:?}", container);
+            assert_eq!(data.size, 1001);
+            assert_eq!(data.offset + if container == WavContainer::Wave64 { 24 } else { 8 }, header_len);
+            assert!(chunks.iter().any(|chunk| chunk.id == "fmt "));
+        }
+    }
+
+    #[test]
+    fn test_auto_container_promotes_to_rf64() {
+        assert_eq!(WavContainer::auto_for(1000), WavContainer::Riff);
+        assert_eq!(WavContainer::auto_for(u32::MAX as u64), WavContainer::Rf64);
+        assert_eq!(WavContainer::auto_for(5 * 1024 * 1024 * 1024), WavContainer::Rf64);
+    }
+
+    #[test]
+    fn test_promotable_header_switches_to_rf64_in_place() {
+        let mut small = Vec::new();
+        write_promotable_header(&mut small, &header(1000, WavContainer::Riff)).unwrap();
+        assert_eq!(&small[0..4], b"RIFF");
+        assert_eq!(&small[12..16], b"JUNK");
+        let chunks = read_chunks(&mut std::io::Cursor::new(&small), small.len() as u64 + 1000).unwrap();
+        assert_eq!(chunks.last().unwrap().offset + 8, small.len() as u64);
+
+        let mut large = Vec::new();
+        write_promotable_header(&mut large, &header(5 * 1024 * 1024 * 1024, WavContainer::Riff)).unwrap();
+        assert_eq!(&large[0..4], b"RF64");
+        assert_eq!(&large[12..16], b"ds64");
+        assert_eq!(large.len(), small.len(), "两种文件头长度应该相同");
+    }
+
+    #[test]
+    fn test_riff_rejects_oversized_data() {
+        let mut buf = Vec::new();
+        let result = write_wav_header(&mut buf, &header(5 * 1024 * 1024 * 1024, WavContainer::Riff));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_rf64_header_records_64bit_sizes() {
+        let data_size = 5 * 1024 * 1024 * 1024u64;
+        let mut buf = Vec::new();
+        write_wav_header(&mut buf, &header(data_size, WavContainer::Rf64)).unwrap();
+
+        assert_eq!(buf.len(), 80);
+        assert_eq!(&buf[0..4], b"RF64");
+        assert_eq!(&buf[4..8], &[0xFF; 4]);
+        assert_eq!(&buf[12..16], b"ds64");
+        assert_eq!(u64::from_le_bytes(buf[28..36].try_into().unwrap()), data_size);
+        assert_eq!(&buf[72..76], b"data");
+    }
+
+    #[test]
+    fn test_wave64_header_layout() {
+        let mut buf = Vec::new();
+        write_wav_header(&mut buf, &header(1001, WavContainer::Wave64)).unwrap();
+
+        assert_eq!(buf.len(), 104);
+        assert_eq!(&buf[0..16], &W64_RIFF_GUID);
+        assert_eq!(u64::from_le_bytes(buf[16..24].try_into().unwrap()), 104 + 1001 + 7);
+        assert_eq!(&buf[80..96], &W64_DATA_GUID);
+        assert_eq!(u64::from_le_bytes(buf[96..104].try_into().unwrap()), 24 + 1001);
+    }
+
+    #[test]
+    fn test_extensible_fmt_chunk_for_multichannel() {
+        let mut h = header(1200, WavContainer::Riff);
+        h.channels = 6;
+        h.bits_per_sample = 24;
+        let mut buf = Vec::new();
+        write_wav_header(&mut buf, &h).unwrap();
+
+        assert_eq!(buf.len(), 68);
+        assert_eq!(u32::from_le_bytes(buf[16..20].try_into().unwrap()), 40);
+        assert_eq!(u16::from_le_bytes(buf[20..22].try_into().unwrap()), WAVE_FORMAT_EXTENSIBLE);
+        assert_eq!(u16::from_le_bytes(buf[32..34].try_into().unwrap()), 18); // block align
+        assert_eq!(u32::from_le_bytes(buf[40..44].try_into().unwrap()), 0x3F);
+        assert_eq!(&buf[44..46], &WAVE_FORMAT_PCM.to_le_bytes());
+        assert_eq!(&buf[46..60], &SUBTYPE_GUID_TAIL);
+        assert_eq!(&buf[60..64], b"data");
+    }
+
+    #[test]
+    fn test_g711_fmt_and_fact_chunks() {
+        let mut h = header(8000, WavContainer::Riff);
+        h.channels = 1;
+        h.bits_per_sample = 8;
+        h.sample_format = SampleFormat::Ulaw;
+        let mut buf = Vec::new();
+        write_wav_header(&mut buf, &h).unwrap();
+
+        assert_eq!(buf.len(), 58);
+        assert_eq!(u32::from_le_bytes(buf[4..8].try_into().unwrap()), 50 + 8000);
+        assert_eq!(u32::from_le_bytes(buf[16..20].try_into().unwrap()), 18);
+        assert_eq!(u16::from_le_bytes(buf[20..22].try_into().unwrap()), WAVE_FORMAT_MULAW);
+        assert_eq!(&buf[38..42], b"fact");
+        assert_eq!(u32::from_le_bytes(buf[46..50].try_into().unwrap()), 8000);
+        assert_eq!(&buf[50..54], b"data");
+
+        // Wave64 下 fmt 块需要补齐到 8 字节边界
+        h.container = WavContainer::Wave64;
+        h.sample_format = SampleFormat::Alaw;
+        let mut buf = Vec::new();
+        write_wav_header(&mut buf, &h).unwrap();
+        assert_eq!(buf.len(), 40 + 48 + 32 + 24);
+        assert_eq!(&buf[88..104], &W64_FACT_GUID);
+        assert_eq!(&buf[120..136], &W64_DATA_GUID);
+    }
+
+    #[test]
+    fn test_adpcm_fmt_chunk() {
+        let mut h = header(512, WavContainer::Riff);
+        h.channels = 1;
+        h.bits_per_sample = 4;
+        h.sample_rate = 8000;
+        h.adpcm = Some(AdpcmFormat { block_align: 256, frames: 1000 });
+        let mut buf = Vec::new();
+        write_wav_header(&mut buf, &h).unwrap();
+
+        assert_eq!(buf.len(), 60);
+        assert_eq!(u32::from_le_bytes(buf[16..20].try_into().unwrap()), 20);
+        assert_eq!(u16::from_le_bytes(buf[20..22].try_into().unwrap()), WAVE_FORMAT_IMA_ADPCM);
+        assert_eq!(u32::from_le_bytes(buf[28..32].try_into().unwrap()), 8000 * 256 / 505);
+        assert_eq!(u16::from_le_bytes(buf[32..34].try_into().unwrap()), 256);
+        assert_eq!(u16::from_le_bytes(buf[38..40].try_into().unwrap()), 505);
+        assert_eq!(&buf[40..44], b"fact");
+        assert_eq!(u32::from_le_bytes(buf[48..52].try_into().unwrap()), 1000);
+    }
+
+    #[test]
+    fn test_parse_written_header() {
+        let mut h = header(8, WavContainer::Rf64);
+        h.channels = 1;
+        h.sample_format = SampleFormat::Alaw;
+        h.bits_per_sample = 8;
+        let mut buf = Vec::new();
+        write_wav_header(&mut buf, &h).unwrap();
+        buf.extend_from_slice(&[0xD5; 8]);
+
+        let audio = parse_wav(&buf, WavParseMode::Strict).unwrap();
+        assert_eq!(audio.config.sample_format, SampleFormat::Alaw);
+        assert_eq!(audio.config.sample_rate, 48000);
+        assert_eq!(audio.frames(), 8);
+        assert_eq!(audio.samples_i16(), vec![8; 8]);
+    }
+
+    #[test]
+    fn test_parse_rejects_truncated_data() {
+        let mut buf = Vec::new();
+        write_wav_header(&mut buf, &header(100, WavContainer::Riff)).unwrap();
+        buf.extend_from_slice(&[0; 50]);
+        assert!(parse_wav(&buf, WavParseMode::Strict).is_err());
+    }
+
+    /// 手工拼装 WAV 文件：`chunks` 中的块按原样写入（大小字段取声明值），不自动补齐填充字节
+    fn build_wav(chunks: &[(&[u8; 4], u32, &[u8])]) -> Vec<u8> {
+        let mut buf = b"RIFF\0\0\0\0WAVE".to_vec();
+        for (id, declared, body) in chunks {
+            buf.extend_from_slice(*id);
+            buf.extend_from_slice(&declared.to_le_bytes());
+            buf.extend_from_slice(body);
+        }
+        let riff_size = (buf.len() - 8) as u32;
+        buf[4..8].copy_from_slice(&riff_size.to_le_bytes());
+        buf
+    }
+
+    /// 8kHz 单声道 16 位 PCM 的 fmt 块内容，附加 `extra` 个扩展字节
+    fn fmt_body(extra: usize) -> Vec<u8> {
+        let mut body = Vec::new();
+        for v in [1u16, 1] {
+            body.extend_from_slice(&v.to_le_bytes());
+        }
+        body.extend_from_slice(&8000u32.to_le_bytes());
+        body.extend_from_slice(&16000u32.to_le_bytes());
+        body.extend_from_slice(&2u16.to_le_bytes());
+        body.extend_from_slice(&16u16.to_le_bytes());
+        body.extend(std::iter::repeat_n(0xAB, extra));
+        body
+    }
+
+    const SAMPLES: [u8; 8] = [1, 0, 2, 0, 3, 0, 4, 0];
+
+    #[test]
+    fn test_large_fmt_junk_and_padded_chunks() {
+        // 22 字节的 fmt 块、JUNK 块、带填充字节的奇数长度 LIST 块
+        let fmt = fmt_body(6);
+        let wav = build_wav(&[
+            (b"JUNK", 4, &[0; 4]),
+            (b"fmt ", fmt.len() as u32, &fmt),
+            (b"LIST", 3, &[b'a', b'b', b'c', 0]),
+            (b"data", 8, &SAMPLES),
+        ]);
+        for mode in [WavParseMode::Lenient, WavParseMode::Strict] {
+            let audio = parse_wav(&wav, mode).unwrap();
+            assert_eq!(audio.config.sample_rate, 8000);
+            assert_eq!(audio.data, SAMPLES);
+        }
+    }
+
+    #[test]
+    fn test_unknown_chunks() {
+        let fmt = fmt_body(0);
+        let wav = build_wav(&[(b"fmt ", 16, &fmt), (b"zzzz", 2, &[9, 9]), (b"data", 8, &SAMPLES)]);
+        assert_eq!(parse_wav(&wav, WavParseMode::Lenient).unwrap().data, SAMPLES);
+        let err = parse_wav(&wav, WavParseMode::Strict).unwrap_err();
+        assert!(err.to_string().contains("Unknown chunk 'zzzz'"), "{}", err);
+    }
+
+    #[test]
+    fn test_missing_pad_byte() {
+        // 奇数长度块后没有填充字节，data 块紧随其后
+        let fmt = fmt_body(0);
+        let wav = build_wav(&[(b"fmt ", 16, &fmt), (b"LIST", 3, b"abc"), (b"data", 8, &SAMPLES)]);
+        assert_eq!(parse_wav(&wav, WavParseMode::Lenient).unwrap().data, SAMPLES);
+        assert!(parse_wav(&wav, WavParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_truncated_data_is_recovered_in_lenient_mode() {
+        // 录音中断：data 块声明 100 字节，实际只有 8 字节
+        let fmt = fmt_body(0);
+        let wav = build_wav(&[(b"fmt ", 16, &fmt), (b"data", 100, &SAMPLES)]);
+        assert_eq!(parse_wav(&wav, WavParseMode::Lenient).unwrap().data, SAMPLES);
+        assert!(parse_wav(&wav, WavParseMode::Strict).is_err());
+    }
+
+    #[test]
+    fn test_malformed_fmt_and_missing_chunks() {
+        let short_fmt = fmt_body(0);
+        let wav = build_wav(&[(b"fmt ", 12, &short_fmt[..12]), (b"data", 8, &SAMPLES)]);
+        assert!(parse_wav(&wav, WavParseMode::Lenient).is_err(), "缺少有效 fmt 块");
+        assert!(parse_wav(&wav, WavParseMode::Strict).unwrap_err().to_string().contains("too small"));
+
+        let fmt = fmt_body(0);
+        let wav = build_wav(&[(b"fmt ", 16, &fmt)]);
+        assert!(parse_wav(&wav, WavParseMode::Lenient).unwrap_err().to_string().contains("missing the data chunk"));
+        assert!(parse_wav(b"RIFF\x04\0\0\0WAV", WavParseMode::Lenient).is_err());
+        // 截断在 fmt 块中间
+        let wav = build_wav(&[(b"fmt ", 16, &fmt[..10])]);
+        assert!(parse_wav(&wav, WavParseMode::Lenient).is_err());
+    }
+
+    #[test]
+    fn test_random_bytes_never_panic() {
+        // 简单的确定性伪随机输入，覆盖各种截断和损坏的块头
+        let fmt = fmt_body(0);
+        let valid = build_wav(&[(b"fmt ", 16, &fmt), (b"LIST", 3, &[1, 2, 3, 0]), (b"data", 8, &SAMPLES)]);
+        let mut state = 0x2545_F491u32;
+        for _ in 0..2000 {
+            let mut bytes = valid.clone();
+            for _ in 0..4 {
+                state ^= state << 13;
+                state ^= state >> 17;
+                state ^= state << 5;
+                let at = state as usize % bytes.len();
+                bytes[at] = (state >> 24) as u8;
+            }
+            bytes.truncate(12 + state as usize % (bytes.len() - 11));
+            for mode in [WavParseMode::Lenient, WavParseMode::Strict] {
+                let _ = parse_wav(&bytes, mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_force_extensible_for_stereo_16bit() {
+        let mut h = header(100, WavContainer::Riff);
+        assert!(!h.is_extensible());
+        h.force_extensible = true;
+        assert!(h.is_extensible());
+    }
+}
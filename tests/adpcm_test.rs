@@ -0,0 +1,87 @@
+use audio_helper::{trans_pcm_file_to_wav, trans_wav_file_to_pcm, read_wav_file, decode_samples_i16, generate_sine, AudioConfig, PcmToWavConfig, SampleFormat};
+use std::fs;
+use std::time::Duration;
+
+/// 生成 440Hz 正弦波 PCM 数据（16 位小端）
+fn sine_samples(sample_rate: u32, channels: u8, seconds: f32) -> Vec<i16> {
+    let pcm = generate_sine(440.0, Duration::from_secs_f32(seconds), &AudioConfig::new(sample_rate, channels, 16));
+    decode_samples_i16(&pcm, SampleFormat::S16)
+}
+
+fn to_bytes(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+/// 验证 PCM -> IMA ADPCM WAV -> 解码的完整流程
+#[test]
+fn test_ima_adpcm_round_trip() {
+    for channels in [1u8, 2] {
+        let input_path = format!("adpcm_test_{}ch.pcm", channels);
+        let output_path = format!("adpcm_test_{}ch.wav", channels);
+        let samples = sine_samples(16000, channels, 1.3);
+        fs::write(&input_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+
+        let config = PcmToWavConfig::new(16000, channels, 16).with_ima_adpcm(true);
+        let result = trans_pcm_file_to_wav(&input_path, &output_path, Some(config));
+        assert!(result.is_ok(), "ADPCM 编码应该成功: {:?}", result.err());
+
+        // ADPCM 每个样本 4 位，文件约为 16 位 PCM 的 1/4
+        let pcm_size = fs::metadata(&input_path).unwrap().len();
+        let wav_size = fs::metadata(&output_path).unwrap().len();
+        println!("{} 声道: PCM {} 字节 -> ADPCM WAV {} 字节", channels, pcm_size, wav_size);
+        assert!(wav_size < pcm_size / 3, "ADPCM 文件应该显著小于 PCM");
+
+        let audio = read_wav_file(&output_path).expect("ADPCM WAV 应该可以读取");
+        assert_eq!(audio.config.sample_rate, 16000);
+        assert_eq!(audio.config.channels, channels);
+        assert_eq!(audio.config.sample_format, SampleFormat::S16);
+        assert_eq!(audio.frames(), samples.len() / channels as usize, "解码帧数应该与 fact 块一致");
+
+        let decoded = audio.samples_i16();
+        // 起始阶段步长需要几个样本自适应，跳过后再比较
+        let warmup = 64 * channels as usize;
+        let max_error = samples.iter().zip(&decoded).skip(warmup).map(|(a, b)| (*a as i32 - *b as i32).abs()).max().unwrap();
+        println!("  最大量化误差: {}", max_error);
+        assert!(max_error < 1000, "ADPCM 量化误差过大: {}", max_error);
+
+        let _ = fs::remove_file(&input_path);
+        let _ = fs::remove_file(&output_path);
+    }
+}
+
+/// 验证普通 PCM WAV 解码回原始 PCM 数据不变
+#[test]
+fn test_wav_to_pcm_is_lossless() {
+    let input_path = "wav_to_pcm_input.pcm";
+    let wav_path = "wav_to_pcm.wav";
+    let output_path = "wav_to_pcm_output.pcm";
+    let data = to_bytes(&sine_samples(8000, 2, 0.5));
+    fs::write(input_path, &data).expect("Failed to write test PCM file");
+
+    trans_pcm_file_to_wav(input_path, wav_path, Some(PcmToWavConfig::new(8000, 2, 16))).expect("WAV 转换应该成功");
+    let config = trans_wav_file_to_pcm(wav_path, output_path).expect("WAV 解码应该成功").config_used;
+
+    assert_eq!(config.sample_rate, 8000);
+    assert_eq!(config.channels, 2);
+    assert_eq!(config.bits_per_sample, 16);
+    assert_eq!(fs::read(output_path).unwrap(), data, "解码后的 PCM 数据应该与原始数据一致");
+    println!("✓ WAV -> PCM 解码无损");
+
+    for path in [input_path, wav_path, output_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证 ADPCM 不支持多于两个声道
+#[test]
+fn test_ima_adpcm_rejects_multichannel() {
+    let input_path = "adpcm_multichannel.pcm";
+    let output_path = "adpcm_multichannel.wav";
+    fs::write(input_path, vec![0u8; 1200]).expect("Failed to write test PCM file");
+
+    let config = PcmToWavConfig::new(16000, 6, 16).with_ima_adpcm(true);
+    assert!(trans_pcm_file_to_wav(input_path, output_path, Some(config)).is_err());
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
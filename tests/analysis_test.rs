@@ -0,0 +1,285 @@
+use audio_helper::{analyze_levels, audio_md5, audio_md5_with_config, compute_spectrogram, compare_audio, decode_samples_i16, detect_bpm, fingerprint, generate_sine, resample_i16, audio_duration, audio_duration_with_config, trans_pcm_file_to_wav, trans_pcm_file_to_mp3, trans_wav_file_to_pcm, AudioConfig, PcmToWavConfig, Mp3Config, Mp3Bitrate, AudioQuality, SampleFormat, SpectrogramOptions};
+use std::fs;
+use std::time::Duration;
+
+fn to_bytes(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+/// 验证 PCM 和 WAV 输入的电平分析
+#[test]
+fn test_analyze_levels() {
+    let pcm_path = "analysis_levels.pcm";
+    let wav_path = "analysis_levels.wav";
+    // 立体声：左声道为 -6dBFS 的方波，右声道为 -20dBFS 的方波
+    let samples: Vec<i16> = (0..8000).flat_map(|i| if i % 2 == 0 { [16384i16, 3277] } else { [-16384, -3277] }).collect();
+    fs::write(pcm_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+
+    let report = analyze_levels(pcm_path, AudioConfig::new(8000, 2, 16)).expect("电平分析应该成功");
+    println!("📊 电平: {:?}", report);
+    assert_eq!(report.per_channel.len(), 2);
+    assert!((report.peak_dbfs + 6.02).abs() < 0.01);
+    assert!((report.per_channel[0].rms_dbfs + 6.02).abs() < 0.01);
+    assert!((report.per_channel[1].peak_dbfs + 20.0).abs() < 0.01);
+
+    // WAV 文件以文件头为准，忽略传入的参数
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(8000, 2, 16))).expect("WAV 转换应该成功");
+    let wav_report = analyze_levels(wav_path, AudioConfig::new(44100, 1, 8)).expect("电平分析应该成功");
+    assert_eq!(wav_report, report);
+
+    assert!(analyze_levels("analysis_missing.pcm", AudioConfig::default()).is_err());
+
+    let _ = fs::remove_file(pcm_path);
+    let _ = fs::remove_file(wav_path);
+}
+
+/// 验证静音文件的电平为负无穷
+#[test]
+fn test_analyze_levels_of_silence() {
+    let pcm_path = "analysis_silence.pcm";
+    fs::write(pcm_path, vec![0u8; 1600]).expect("Failed to write test PCM file");
+    let report = analyze_levels(pcm_path, AudioConfig::new(8000, 1, 16)).expect("电平分析应该成功");
+    assert_eq!(report.peak_dbfs, f32::NEG_INFINITY);
+    assert_eq!(report.rms_dbfs, f32::NEG_INFINITY);
+    let _ = fs::remove_file(pcm_path);
+}
+
+/// 验证文件频谱图：主频点与输入正弦波频率一致
+#[test]
+fn test_compute_spectrogram() {
+    let pcm_path = "analysis_spectrogram.pcm";
+    // 0.5 秒 16kHz 单声道 2kHz 正弦波
+    let samples: Vec<i16> = (0..8000)
+        .map(|i| ((2.0 * std::f32::consts::PI * 2000.0 * i as f32 / 16000.0).sin() * 16000.0) as i16)
+        .collect();
+    fs::write(pcm_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+
+    let options = SpectrogramOptions::default().with_fft_size(512).with_hop_size(256);
+    let result = compute_spectrogram(pcm_path, AudioConfig::new(16000, 1, 16), options).expect("频谱图计算应该成功");
+    println!("📈 频谱图: {} 帧 x {} 频点", result.magnitudes.len(), result.magnitudes[0].len());
+    assert_eq!(result.magnitudes[0].len(), 257);
+
+    let frame = &result.magnitudes[result.magnitudes.len() / 2];
+    let peak_bin = (0..frame.len()).max_by(|&a, &b| frame[a].total_cmp(&frame[b])).unwrap();
+    assert_eq!(result.bin_frequency(peak_bin), 2000.0);
+
+    #[cfg(feature = "image")]
+    {
+        let png_path = "analysis_spectrogram.png";
+        result.save_png(png_path).expect("PNG 导出应该成功");
+        assert_eq!(&fs::read(png_path).expect("Failed to read PNG")[1..4], b"PNG");
+        let _ = fs::remove_file(png_path);
+    }
+
+    let _ = fs::remove_file(pcm_path);
+}
+
+/// 验证往返转换的比较：PCM → WAV → PCM 应该完全一致
+#[test]
+fn test_compare_audio_round_trip() {
+    let pcm_path = "analysis_compare_8k16bit单声道.pcm";
+    let wav_path = "analysis_compare.wav";
+    let round_trip_path = "analysis_compare_round_trip_8k16bit单声道.pcm";
+    let samples: Vec<i16> = (0..4000).map(|i| ((i * 37) % 20000 - 10000) as i16).collect();
+    fs::write(pcm_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(8000, 1, 16))).expect("WAV 转换应该成功");
+    trans_wav_file_to_pcm(wav_path, round_trip_path).expect("PCM 转换应该成功");
+
+    let report = compare_audio(pcm_path, round_trip_path).expect("比较应该成功");
+    println!("🔍 往返比较: {:?}", report);
+    assert!(report.bit_exact);
+    assert_eq!(report.length_diff, 0);
+
+    // 截短后长度差为负，重叠部分仍然一致
+    fs::write(round_trip_path, to_bytes(&samples[..3000])).expect("Failed to write test PCM file");
+    let report = compare_audio(pcm_path, round_trip_path).expect("比较应该成功");
+    assert!(!report.bit_exact);
+    assert_eq!(report.max_diff, 0.0);
+    assert_eq!(report.length_diff, -1000);
+
+    // 采样率不同时无法比较
+    assert!(compare_audio(pcm_path, wav_path).is_ok());
+    fs::rename(round_trip_path, "analysis_compare_16k16bit单声道.pcm").expect("Failed to rename");
+    assert!(compare_audio(pcm_path, "analysis_compare_16k16bit单声道.pcm").is_err());
+
+    for path in [pcm_path, wav_path, "analysis_compare_16k16bit单声道.pcm"] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证 PCM、WAV、ADPCM WAV 和 MP3 的时长计算
+#[test]
+fn test_audio_duration() {
+    let pcm_path = "analysis_duration_16k16bit单声道.pcm";
+    let wav_path = "analysis_duration.wav";
+    let adpcm_path = "analysis_duration_adpcm.wav";
+    let mp3_path = "analysis_duration.mp3";
+    // 1.5 秒 16kHz 单声道
+    let samples: Vec<i16> = (0..24000).map(|i| ((i % 200) * 100 - 10000) as i16).collect();
+    fs::write(pcm_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+
+    let duration = audio_duration(pcm_path).expect("PCM 时长计算应该成功");
+    println!("⏱️ PCM: {:?}", duration);
+    assert_eq!(duration.as_millis(), 1500);
+    // 指定参数时按 8kHz 立体声计算
+    let duration = audio_duration_with_config(pcm_path, &AudioConfig::new(8000, 2, 16)).expect("PCM 时长计算应该成功");
+    assert_eq!(duration.as_millis(), 1500);
+
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(16000, 1, 16))).expect("WAV 转换应该成功");
+    assert_eq!(audio_duration(wav_path).expect("WAV 时长计算应该成功").as_millis(), 1500);
+
+    let config = PcmToWavConfig::new(16000, 1, 16).with_ima_adpcm(true);
+    trans_pcm_file_to_wav(pcm_path, adpcm_path, Some(config)).expect("ADPCM 转换应该成功");
+    assert_eq!(audio_duration(adpcm_path).expect("ADPCM 时长计算应该成功").as_millis(), 1500);
+
+    let config = Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::High);
+    trans_pcm_file_to_mp3(pcm_path, mp3_path, Some(config)).expect("MP3 转换应该成功");
+    let duration = audio_duration(mp3_path).expect("MP3 时长计算应该成功");
+    println!("⏱️ MP3: {:?}", duration);
+    // 已扣除编码器延迟，误差不超过一帧（16kHz 下 576 个采样帧）加一次延迟
+    assert!((duration.as_secs_f64() - 1.5).abs() < 0.075);
+
+    assert!(audio_duration("analysis_duration_missing.wav").is_err());
+
+    for path in [pcm_path, wav_path, adpcm_path, mp3_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证从鼓点录音中检测速度
+#[test]
+fn test_detect_bpm() {
+    let pcm_path = "analysis_bpm.pcm";
+    let wav_path = "analysis_bpm.wav";
+    // 16kHz 立体声，8 秒 124 BPM 的底鼓，右声道电平减半
+    let beat = 60.0 / 124.0 * 16000.0;
+    let samples: Vec<i16> = (0..16000 * 8)
+        .flat_map(|i| {
+            let t = (i as f32 % beat) / 16000.0;
+            let kick = ((2.0 * std::f32::consts::PI * 55.0 * t).sin() * (-t * 25.0).exp() * 20000.0) as i16;
+            [kick, kick / 2]
+        })
+        .collect();
+    fs::write(pcm_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(16000, 2, 16))).expect("WAV 转换应该成功");
+
+    let bpm = detect_bpm(wav_path, AudioConfig::default()).expect("速度检测应该成功");
+    println!("🥁 检测到 {:.2} BPM", bpm);
+    assert!((bpm - 124.0).abs() < 1.0);
+    assert_eq!(detect_bpm(pcm_path, AudioConfig::new(16000, 2, 16)).unwrap(), bpm);
+
+    // 静音没有节拍
+    fs::write(pcm_path, vec![0u8; 16000 * 2 * 4]).unwrap();
+    assert!(detect_bpm(pcm_path, AudioConfig::new(16000, 2, 16)).is_err());
+
+    let _ = fs::remove_file(pcm_path);
+    let _ = fs::remove_file(wav_path);
+}
+
+/// 每 150ms 换一个音高的伪随机旋律，叠加少量噪声（16 位单声道）
+fn melody(seed: u32, sample_rate: u32, seconds: u32) -> Vec<i16> {
+    let mut state = seed;
+    let mut next = move || {
+        state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+        state as f32 / u32::MAX as f32
+    };
+    let mut phase = 0.0f32;
+    let mut frequency = 0.0;
+    (0..sample_rate * seconds)
+        .map(|i| {
+            if i % (sample_rate * 3 / 20) == 0 {
+                frequency = 300.0 + next() * 1500.0;
+            }
+            phase += 2.0 * std::f32::consts::PI * frequency / sample_rate as f32;
+            (phase.sin() * 12000.0 + (next() - 0.5) * 600.0) as i16
+        })
+        .collect()
+}
+
+/// 验证同一录音经过重采样、转码后指纹相似，不同录音不相似
+#[test]
+fn test_fingerprint_similarity() {
+    let original_path = "analysis_fingerprint_original.wav";
+    let resampled_path = "analysis_fingerprint_resampled.wav";
+    let other_path = "analysis_fingerprint_other.wav";
+    let pcm_path = "analysis_fingerprint.pcm";
+
+    let original = melody(11, 44100, 6);
+    fs::write(pcm_path, to_bytes(&original)).expect("Failed to write test PCM file");
+    trans_pcm_file_to_wav(pcm_path, original_path, Some(PcmToWavConfig::new(44100, 1, 16))).expect("WAV 转换应该成功");
+    fs::write(pcm_path, to_bytes(&resample_i16(&original, 1, 44100, 16000))).unwrap();
+    trans_pcm_file_to_wav(pcm_path, resampled_path, Some(PcmToWavConfig::new(16000, 1, 16))).unwrap();
+    fs::write(pcm_path, to_bytes(&melody(12, 44100, 6))).unwrap();
+    trans_pcm_file_to_wav(pcm_path, other_path, Some(PcmToWavConfig::new(44100, 1, 16))).unwrap();
+
+    let reference = fingerprint(original_path).expect("计算指纹应该成功");
+    assert_eq!(reference.duration_ms, 6000);
+    let resampled = reference.similarity(&fingerprint(resampled_path).unwrap());
+    let other = reference.similarity(&fingerprint(other_path).unwrap());
+    println!("🔎 重采样到 16kHz 的相似度 {:.3}，不同录音 {:.3}", resampled, other);
+    assert!(resampled > 0.8);
+    assert!(other < 0.2);
+
+    #[cfg(feature = "mp3-decode")]
+    {
+        let mp3_path = "analysis_fingerprint.mp3";
+        fs::write(pcm_path, to_bytes(&original)).unwrap();
+        trans_pcm_file_to_mp3(pcm_path, mp3_path, Some(Mp3Config::new(44100, 1, Mp3Bitrate::Kbps64, AudioQuality::Medium))).unwrap();
+        let transcoded = reference.similarity(&fingerprint(mp3_path).unwrap());
+        println!("🔎 64kbps MP3 的相似度 {:.3}", transcoded);
+        assert!(transcoded > 0.7);
+        let _ = fs::remove_file(mp3_path);
+    }
+
+    assert!(fingerprint("analysis_fingerprint_missing.wav").is_err());
+    for path in [original_path, resampled_path, other_path, pcm_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证样本 MD5 与容器和字节序无关，样本改变时摘要随之改变
+#[test]
+fn test_audio_md5_across_containers() {
+    let pcm_path = "analysis_md5.pcm";
+    let wav_path = "analysis_md5.wav";
+    let config = AudioConfig::new(44100, 2, 16);
+    let samples = decode_samples_i16(&generate_sine(440.0, Duration::from_millis(50), &config), SampleFormat::S16);
+    fs::write(pcm_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+
+    let pcm_md5 = audio_md5_with_config(pcm_path, &config).expect("计算 MD5 应该成功");
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(44100, 2, 16))).expect("WAV 转换应该成功");
+    let wav_md5 = audio_md5(wav_path).expect("计算 MD5 应该成功");
+    println!("🔐 PCM {:02x?}", pcm_md5);
+    assert_eq!(wav_md5, pcm_md5, "PCM 和 WAV 的样本 MD5 应该相同");
+
+    // WAV 解码回 PCM 仍然相同
+    trans_wav_file_to_pcm(wav_path, pcm_path).unwrap();
+    assert_eq!(audio_md5_with_config(pcm_path, &config).unwrap(), pcm_md5);
+
+    // 大端 PCM 按样本值计算，与小端相同
+    let big_endian: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+    fs::write(pcm_path, big_endian).unwrap();
+    assert_eq!(audio_md5_with_config(pcm_path, &config.clone().with_endianness(audio_helper::Endianness::Big)).unwrap(), pcm_md5);
+
+    // 改变一个样本
+    let mut changed = samples.clone();
+    changed[1000] ^= 1;
+    fs::write(pcm_path, to_bytes(&changed)).unwrap();
+    assert_ne!(audio_md5_with_config(pcm_path, &config).unwrap(), pcm_md5);
+
+    // 空数据的 MD5
+    fs::write(pcm_path, []).unwrap();
+    let empty = audio_md5_with_config(pcm_path, &config).unwrap();
+    assert_eq!(empty, [0xd4, 0x1d, 0x8c, 0xd9, 0x8f, 0x00, 0xb2, 0x04, 0xe9, 0x80, 0x09, 0x98, 0xec, 0xf8, 0x42, 0x7e]);
+
+    // 8 位 WAV 与同样数值的有符号字节一致
+    fs::write(pcm_path, [0x80u8, 0x81, 0x7F, 0x00]).unwrap();
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(8000, 1, 8))).unwrap();
+    fs::write(pcm_path, [0x00u8, 0x01, 0xFF, 0x80]).unwrap();
+    assert_eq!(audio_md5(wav_path).unwrap(), audio_md5_with_config(pcm_path, &AudioConfig::new(8000, 1, 16)).unwrap());
+
+    assert!(audio_md5("analysis_md5_missing.wav").is_err());
+    let _ = fs::remove_file(pcm_path);
+    let _ = fs::remove_file(wav_path);
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "async")]
+
+use audio_helper::{
+    auto_convert_pcm_async, trans_pcm_file_to_mp3, trans_pcm_file_to_mp3_async, trans_pcm_file_to_wav,
+    trans_pcm_file_to_wav_async, trans_wav_file_to_pcm_async, AudioFormat, AudioQuality, Mp3Bitrate, Mp3Config, PcmToWavConfig, generate_sine, AudioConfig,
+};
+use std::fs;
+use std::time::Duration;
+
+/// 创建测试用的 PCM 文件（0.2 秒正弦波）
+fn write_pcm(path: &str, sample_rate: u32) -> Vec<u8> {
+    let bytes = generate_sine(440.0, Duration::from_millis(200), &AudioConfig::new(sample_rate, 1, 16));
+    fs::write(path, &bytes).expect("Failed to write test PCM file");
+    bytes
+}
+
+/// 验证异步转换与同步转换的输出一致
+#[tokio::test]
+async fn test_async_matches_sync_conversion() {
+    let pcm = write_pcm("async_test_16k16bit单声道.pcm", 16000);
+    let wav_config = PcmToWavConfig::new(16000, 1, 16);
+
+    trans_pcm_file_to_wav_async("async_test_16k16bit单声道.pcm", "async_test_async.wav", Some(wav_config.clone())).await.expect("异步 WAV 转换应该成功");
+    trans_pcm_file_to_wav("async_test_16k16bit单声道.pcm", "async_test_sync.wav", Some(wav_config)).expect("同步 WAV 转换应该成功");
+    assert_eq!(fs::read("async_test_async.wav").unwrap(), fs::read("async_test_sync.wav").unwrap());
+    println!("✅ 异步 WAV 输出与同步一致");
+
+    let mp3_config = Mp3Config::new(16000, 1, Mp3Bitrate::Kbps128, AudioQuality::Medium);
+    let used = trans_pcm_file_to_mp3_async("async_test_16k16bit单声道.pcm", "async_test_async.mp3", Some(mp3_config.clone())).await.expect("异步 MP3 转换应该成功");
+    trans_pcm_file_to_mp3("async_test_16k16bit单声道.pcm", "async_test_sync.mp3", Some(mp3_config)).expect("同步 MP3 转换应该成功");
+    assert_eq!(used.config_used.sample_rate, 16000);
+    assert_eq!(fs::read("async_test_async.mp3").unwrap(), fs::read("async_test_sync.mp3").unwrap());
+    println!("✅ 异步 MP3 输出与同步一致");
+
+    // WAV 转回 PCM 得到原始数据
+    let config = trans_wav_file_to_pcm_async("async_test_async.wav", "async_test_roundtrip.pcm").await.expect("异步 WAV 解码应该成功");
+    assert_eq!(config.config_used.sample_rate, 16000);
+    assert_eq!(fs::read("async_test_roundtrip.pcm").unwrap(), pcm);
+
+    let inferred = auto_convert_pcm_async("async_test_16k16bit单声道.pcm", "async_test_auto.wav", AudioFormat::Wav).await.expect("异步自动转换应该成功");
+    assert_eq!(inferred.sample_rate, 16000);
+
+    for file in ["async_test_16k16bit单声道.pcm", "async_test_async.wav", "async_test_sync.wav", "async_test_async.mp3", "async_test_sync.mp3", "async_test_roundtrip.pcm", "async_test_auto.wav"] {
+        let _ = fs::remove_file(file);
+    }
+}
+
+/// 验证异步接口保留同步接口的输入检查
+#[tokio::test]
+async fn test_async_input_validation() {
+    let err = trans_pcm_file_to_wav_async("async_missing.pcm", "async_missing.wav", None).await.unwrap_err();
+    assert_eq!(err.to_string(), "Input file does not exist");
+
+    let err = trans_pcm_file_to_mp3_async("async_test.txt", "async_test.mp3", None).await.unwrap_err();
+    assert_eq!(err.to_string(), "Input file is not a PCM file");
+
+    fs::write("async_test_disguised.pcm", b"RIFF\x24\x00\x00\x00WAVEfmt ").unwrap();
+    let err = trans_pcm_file_to_wav_async("async_test_disguised.pcm", "async_test_disguised.wav", None).await.unwrap_err();
+    assert!(err.to_string().contains("not raw PCM"), "{}", err);
+    let _ = fs::remove_file("async_test_disguised.pcm");
+    println!("✅ 异步输入检查正常");
+}
@@ -0,0 +1,268 @@
+use audio_helper::{batch_convert_directory, batch_convert_files, generate_sine, read_wav_file, AudioConfig, AudioFormat, BatchOptions, ConvertPolicy, OverwritePolicy};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// 在目录中创建测试用的 PCM 文件（0.1 秒正弦波）
+fn write_pcm(path: &Path, sample_rate: u32) {
+    let bytes = generate_sine(440.0, Duration::from_millis(100), &AudioConfig::new(sample_rate, 1, 16));
+    fs::write(path, bytes).expect("Failed to write test PCM file");
+}
+
+/// 验证并行批量转换：递归处理子目录、单个文件失败不影响其他文件
+#[test]
+fn test_parallel_batch_conversion() {
+    let input_dir = Path::new("batch_test_input");
+    let output_dir = Path::new("batch_test_output");
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(input_dir.join("nested")).expect("Failed to create test directory");
+
+    for i in 0..12 {
+        write_pcm(&input_dir.join(format!("clip{:02}_16k16bit单声道.pcm", i)), 16000);
+    }
+    write_pcm(&input_dir.join("nested").join("voice_8k16bit单声道.pcm"), 8000);
+    fs::write(input_dir.join("notes.txt"), "not audio").expect("Failed to write test file");
+    // 扩展名为 .pcm 的 WAV 文件会转换失败
+    fs::write(input_dir.join("broken.pcm"), b"RIFF\x24\x00\x00\x00WAVEfmt ").expect("Failed to write test file");
+
+    let options = BatchOptions::new(AudioFormat::Wav).with_parallelism(4).with_recursive(true);
+    let report = batch_convert_directory(input_dir, output_dir, &options).expect("批量转换应该成功");
+    println!("📦 批量转换: {} 个文件，成功 {} 个", report.items.len(), report.succeeded());
+
+    assert_eq!(report.items.len(), 14);
+    assert_eq!(report.succeeded(), 13);
+    let failures: Vec<_> = report.failures().collect();
+    assert_eq!(failures.len(), 1);
+    assert!(failures[0].input.ends_with("broken.pcm"));
+
+    // 结果按输入路径排序，输出保持目录结构
+    assert!(report.items.windows(2).all(|w| w[0].input < w[1].input));
+    let nested = output_dir.join("nested").join("voice_8k16bit单声道.wav");
+    assert_eq!(read_wav_file(&nested).expect("Failed to read nested output").config.sample_rate, 8000);
+    assert_eq!(read_wav_file(output_dir.join("clip03_16k16bit单声道.wav")).expect("Failed to read output").config.sample_rate, 16000);
+
+    // 单线程与多线程的结果一致
+    let sequential = batch_convert_directory(input_dir, output_dir, &options.clone().with_parallelism(1)).expect("批量转换应该成功");
+    assert_eq!(sequential, report);
+
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+}
+
+/// 验证不递归时忽略子目录，以及输入目录不存在时报错
+#[test]
+fn test_batch_non_recursive_and_missing_dir() {
+    let input_dir = Path::new("batch_test_flat_input");
+    let output_dir = Path::new("batch_test_flat_output");
+    let _ = fs::remove_dir_all(input_dir);
+    fs::create_dir_all(input_dir.join("nested")).expect("Failed to create test directory");
+    write_pcm(&input_dir.join("top_8k16bit单声道.pcm"), 8000);
+    write_pcm(&input_dir.join("nested").join("inner_8k16bit单声道.pcm"), 8000);
+
+    let report = batch_convert_directory(input_dir, output_dir, &BatchOptions::new(AudioFormat::Mp3)).expect("批量转换应该成功");
+    assert_eq!(report.items.len(), 1);
+    assert!(report.items[0].output.ends_with("top_8k16bit单声道.mp3"));
+    assert!(report.items[0].result.is_ok());
+
+    assert!(batch_convert_directory("batch_test_missing", output_dir, &BatchOptions::default()).is_err());
+
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+}
+
+/// 验证批量转换清单：成功条目包含参数、时长、大小和校验和，失败条目包含错误信息
+#[test]
+fn test_batch_manifest() {
+    let input_dir = Path::new("batch_test_manifest_input");
+    let output_dir = Path::new("batch_test_manifest_output");
+    let manifest_path = Path::new("batch_test_manifest.json");
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(input_dir).expect("Failed to create test directory");
+    write_pcm(&input_dir.join("voice \"a\"_16k16bit单声道.pcm"), 16000);
+    fs::write(input_dir.join("broken.pcm"), b"RIFF\x24\x00\x00\x00WAVEfmt ").expect("Failed to write test file");
+
+    let options = BatchOptions::new(AudioFormat::Wav).with_manifest(manifest_path);
+    let report = batch_convert_directory(input_dir, output_dir, &options).expect("批量转换应该成功");
+    assert_eq!(report.succeeded(), 1);
+
+    let manifest: serde_json::Value = serde_json::from_str(&fs::read_to_string(manifest_path).expect("清单应该已写入")).expect("清单应该是合法的 JSON");
+    println!("🧾 清单: {}", manifest);
+    assert_eq!(manifest["succeeded"], 1);
+    assert_eq!(manifest["failed"], 1);
+
+    let items = manifest["items"].as_array().expect("items 应该是数组");
+    assert_eq!(items.len(), 2);
+    let failed = &items[0];
+    assert_eq!(failed["status"], "error");
+    assert!(failed["error"].as_str().unwrap().contains("not raw PCM"));
+
+    let ok = &items[1];
+    assert_eq!(ok["status"], "ok");
+    assert_eq!(ok["input_size"], 3200);
+    assert_eq!(ok["config"]["sample_rate"], 16000);
+    assert_eq!(ok["config"]["channels"], 1);
+    assert!((ok["duration_secs"].as_f64().unwrap() - 0.1).abs() < 1e-6);
+    let output = fs::read(output_dir.join("voice \"a\"_16k16bit单声道.wav")).expect("Failed to read output");
+    assert_eq!(ok["output_size"], output.len());
+    let expected: String = Sha256::digest(&output).iter().map(|b| format!("{:02x}", b)).collect();
+    assert_eq!(ok["sha256"], expected);
+
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    let _ = fs::remove_file(manifest_path);
+}
+
+/// 验证断点续传：输出未变化的文件被跳过，缺失或被改动的输出重新转换
+#[test]
+fn test_batch_resume() {
+    let input_dir = Path::new("batch_test_resume_input");
+    let output_dir = Path::new("batch_test_resume_output");
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(input_dir).expect("Failed to create test directory");
+    for name in ["a", "b", "c"] {
+        write_pcm(&input_dir.join(format!("{}_16k16bit单声道.pcm", name)), 16000);
+    }
+
+    let options = BatchOptions::new(AudioFormat::Wav).with_resume(true);
+    let first = batch_convert_directory(input_dir, output_dir, &options).expect("批量转换应该成功");
+    assert_eq!(first.succeeded(), 3);
+    assert_eq!(first.skipped(), 0);
+
+    // 模拟中断：一个输出丢失，一个输出只写了一半
+    let output_a = output_dir.join("a_16k16bit单声道.wav");
+    let output_b = output_dir.join("b_16k16bit单声道.wav");
+    let expected_b = fs::read(&output_b).expect("Failed to read output");
+    fs::remove_file(&output_a).expect("Failed to remove output");
+    fs::write(&output_b, &expected_b[..100]).expect("Failed to truncate output");
+
+    let resumed = batch_convert_directory(input_dir, output_dir, &options).expect("续传应该成功");
+    println!("⏯️ 续传: 成功 {} 个，跳过 {} 个", resumed.succeeded(), resumed.skipped());
+    assert_eq!(resumed.succeeded(), 3);
+    assert_eq!(resumed.skipped(), 1);
+    assert!(resumed.items[2].skipped);
+    assert_eq!(resumed.items[2].result.as_ref().unwrap().sample_rate, 16000);
+    assert!(output_a.exists());
+    assert_eq!(fs::read(&output_b).unwrap(), expected_b);
+
+    // 不开启续传时总是重新转换
+    let full = batch_convert_directory(input_dir, output_dir, &BatchOptions::new(AudioFormat::Wav)).expect("批量转换应该成功");
+    assert_eq!(full.skipped(), 0);
+
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+}
+
+/// 验证增量转换：只转换新增或内容变化的文件，仅修改时间变化的文件被跳过
+#[test]
+fn test_batch_if_changed() {
+    let input_dir = Path::new("batch_test_incremental_input");
+    let output_dir = Path::new("batch_test_incremental_output");
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(input_dir).expect("Failed to create test directory");
+    let input_a = input_dir.join("a_16k16bit单声道.pcm");
+    let input_b = input_dir.join("b_16k16bit单声道.pcm");
+    write_pcm(&input_a, 16000);
+    write_pcm(&input_b, 16000);
+
+    let options = BatchOptions::new(AudioFormat::Wav).with_policy(ConvertPolicy::IfChanged);
+    let first = batch_convert_directory(input_dir, output_dir, &options).expect("批量转换应该成功");
+    assert_eq!(first.skipped(), 0);
+
+    let second = batch_convert_directory(input_dir, output_dir, &options).expect("批量转换应该成功");
+    assert_eq!(second.skipped(), 2);
+
+    // a 的内容变化（大小不变），b 只更新修改时间，并新增 c
+    let later = std::time::SystemTime::now() + std::time::Duration::from_secs(60);
+    fs::write(&input_a, vec![0u8; 3200]).expect("Failed to rewrite test file");
+    fs::File::options().write(true).open(&input_a).and_then(|f| f.set_modified(later)).expect("Failed to set mtime");
+    fs::File::options().write(true).open(&input_b).and_then(|f| f.set_modified(later)).expect("Failed to set mtime");
+    write_pcm(&input_dir.join("c_16k16bit单声道.pcm"), 16000);
+
+    let third = batch_convert_directory(input_dir, output_dir, &options).expect("批量转换应该成功");
+    println!("🔁 增量转换: 共 {} 个，跳过 {} 个", third.items.len(), third.skipped());
+    let skipped: Vec<bool> = third.items.iter().map(|item| item.skipped).collect();
+    assert_eq!(skipped, vec![false, true, false]);
+    let audio = read_wav_file(output_dir.join("a_16k16bit单声道.wav")).expect("Failed to read output");
+    assert!(audio.data.iter().all(|&b| b == 0));
+
+    // 记录已更新为新的修改时间，再次运行全部跳过
+    let fourth = batch_convert_directory(input_dir, output_dir, &options).expect("批量转换应该成功");
+    assert_eq!(fourth.skipped(), 3);
+
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+}
+
+/// 验证批量转换的覆盖策略
+#[test]
+fn test_batch_overwrite_policy() {
+    let input_dir = Path::new("batch_test_overwrite_input");
+    let output_dir = Path::new("batch_test_overwrite_output");
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(input_dir).expect("Failed to create test directory");
+    fs::create_dir_all(output_dir).expect("Failed to create test directory");
+    write_pcm(&input_dir.join("a_16k16bit单声道.pcm"), 16000);
+    write_pcm(&input_dir.join("b_16k16bit单声道.pcm"), 16000);
+    let existing = output_dir.join("a_16k16bit单声道.wav");
+    fs::write(&existing, b"keep me").unwrap();
+
+    let options = BatchOptions::new(AudioFormat::Wav).with_overwrite(OverwritePolicy::Error);
+    let report = batch_convert_directory(input_dir, output_dir, &options).expect("批量转换应该成功");
+    assert_eq!(report.succeeded(), 1);
+    assert!(report.failures().next().unwrap().result.as_ref().unwrap_err().contains("already exists"));
+    assert_eq!(fs::read(&existing).unwrap(), b"keep me");
+
+    let report = batch_convert_directory(input_dir, output_dir, &options.clone().with_overwrite(OverwritePolicy::Skip)).expect("批量转换应该成功");
+    assert_eq!((report.succeeded(), report.skipped()), (2, 2));
+    assert_eq!(fs::read(&existing).unwrap(), b"keep me");
+
+    let report = batch_convert_directory(input_dir, output_dir, &options.clone().with_overwrite(OverwritePolicy::RenameWithSuffix)).expect("批量转换应该成功");
+    println!("📦 重命名输出: {:?}", report.items.iter().map(|item| &item.output).collect::<Vec<_>>());
+    assert_eq!(report.succeeded(), 2);
+    assert_eq!(report.items[0].output, output_dir.join("a_16k16bit单声道_1.wav"));
+    assert_eq!(read_wav_file(&report.items[0].output).expect("Failed to read output").config.sample_rate, 16000);
+    assert_eq!(fs::read(&existing).unwrap(), b"keep me");
+
+    // 默认覆盖已有文件
+    let report = batch_convert_directory(input_dir, output_dir, &BatchOptions::new(AudioFormat::Wav)).expect("批量转换应该成功");
+    assert_eq!(report.succeeded(), 2);
+    assert!(read_wav_file(&existing).is_ok());
+
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+}
+
+/// 验证按文件列表批量转换：结果顺序与输入一致，重复的输出路径和断点续传报错
+#[test]
+fn test_batch_convert_files() {
+    let dir = Path::new("batch_test_files");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    write_pcm(&dir.join("b_16k16bit单声道.pcm"), 16000);
+    write_pcm(&dir.join("a_8k16bit单声道.pcm"), 8000);
+
+    let jobs = vec![
+        (dir.join("b_16k16bit单声道.pcm"), dir.join("out").join("first.mp3")),
+        (dir.join("a_8k16bit单声道.pcm"), dir.join("out").join("nested").join("second.mp3")),
+        (dir.join("missing.pcm"), dir.join("out").join("third.mp3")),
+    ];
+    let options = BatchOptions::new(AudioFormat::Mp3).with_parallelism(2);
+    let report = batch_convert_files(&jobs, &options).expect("批量转换应该成功");
+    println!("📦 文件列表转换: 成功 {} 个", report.succeeded());
+    assert_eq!(report.items.iter().map(|item| item.input.clone()).collect::<Vec<_>>(), jobs.iter().map(|(input, _)| input.clone()).collect::<Vec<_>>());
+    assert_eq!(report.succeeded(), 2);
+    assert_eq!(report.items[1].result.as_ref().unwrap().sample_rate, 8000);
+    assert!(dir.join("out").join("nested").join("second.mp3").exists());
+
+    let duplicate = vec![jobs[0].clone(), (jobs[1].0.clone(), jobs[0].1.clone())];
+    assert!(batch_convert_files(&duplicate, &options).is_err());
+    assert!(batch_convert_files(&jobs, &options.clone().with_resume(true)).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+}
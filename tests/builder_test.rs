@@ -0,0 +1,53 @@
+use audio_helper::{AudioConfig, AudioError, AudioQuality, ErrorKind, Mp3Bitrate, Mp3ChannelMode, Mp3Config, Mp3RateMode, SampleFormat, VbrQuality};
+
+/// 验证构建器生成的配置与直接构造一致
+#[test]
+fn test_builders_match_constructors() {
+    let config = Mp3Config::builder()
+        .sample_rate(48000)
+        .channels(1)
+        .bitrate(Mp3Bitrate::Kbps128)
+        .quality(AudioQuality::Medium)
+        .build()
+        .expect("合法配置应该构建成功");
+    assert_eq!(config, Mp3Config::new(48000, 1, Mp3Bitrate::Kbps128, AudioQuality::Medium));
+
+    let config = Mp3Config::builder().vbr(4).bits(24).target_channels(2).channels(6).build().unwrap();
+    assert_eq!(config.rate_mode, Mp3RateMode::Vbr(VbrQuality::V4));
+    assert_eq!(config.sample_format, SampleFormat::S24);
+
+    let config = AudioConfig::builder().sample_rate(16000).channels(1).bits(24).gain_db(-3.0).build().unwrap();
+    assert_eq!(config, AudioConfig::new(16000, 1, 24).with_gain_db(-3.0));
+    println!("✅ 构建器结果与构造函数一致");
+}
+
+/// 验证构建时的参数校验，错误信息包含字段名
+#[test]
+fn test_builders_reject_invalid_settings() {
+    let cases = [
+        (Mp3Config::builder().vbr(12).build(), "vbr"),
+        (Mp3Config::builder().channels(0).build(), "channels"),
+        (Mp3Config::builder().channels(6).build(), "channels"),
+        (Mp3Config::builder().sample_rate(0).build(), "sample_rate"),
+        (Mp3Config::builder().sample_rate(17000).resample(false).build(), "sample_rate"),
+        (Mp3Config::builder().abr(400).build(), "abr"),
+        (Mp3Config::builder().bits(12).build(), "bits"),
+        (Mp3Config::builder().channels(1).channel_mode(Mp3ChannelMode::JointStereo).build(), "channel_mode"),
+    ];
+    for (result, field) in cases {
+        let error = result.expect_err("非法配置应该失败");
+        assert_eq!(AudioError::kind(error.as_ref()), Some(ErrorKind::InvalidConfig), "{}", error);
+        let error = error.to_string();
+        println!("❌ {}", error);
+        assert!(error.contains(field), "错误信息应包含字段名 {}: {}", field, error);
+    }
+
+    let error = AudioConfig::builder().bits(20).build().unwrap_err();
+    assert_eq!(AudioError::kind(error.as_ref()), Some(ErrorKind::InvalidConfig));
+    assert!(error.to_string().contains("bits"));
+    assert!(AudioConfig::builder().channels(0).build().unwrap_err().to_string().contains("channels"));
+    assert!(AudioConfig::builder().gain_db(f32::NAN).build().unwrap_err().to_string().contains("gain_db"));
+    // 重采样开启时非标准采样率可以构建
+    assert!(Mp3Config::builder().sample_rate(17000).build().is_ok());
+    println!("✅ 构建器校验测试通过");
+}
@@ -0,0 +1,146 @@
+use audio_helper::{
+    convert, parse_mp3_info, probe_audio, read_wav_file, split_channels, trans_pcm_bytes_to_mp3, trans_pcm_bytes_to_wav, AudioConfig, AudioPipeline, AudioQuality, ChannelLayout, ChannelMap,
+    AudioError, DetectedFormat, Mp3Bitrate, Mp3Config, PcmToWavConfig,
+};
+use std::fs;
+use std::path::Path;
+
+/// 生成左右声道不同的立体声 PCM：左声道为递增序列，右声道为其相反数
+fn stereo_pcm(frames: i16) -> Vec<u8> {
+    (0..frames).flat_map(|i| [i * 4, -i * 4]).flat_map(|s| s.to_le_bytes()).collect()
+}
+
+/// 验证立体声录音按声道拆分为单声道文件
+#[test]
+fn test_split_channels() {
+    let dir = Path::new("channel_split_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let pcm = stereo_pcm(8000);
+    let input = dir.join("call.wav");
+    fs::write(&input, trans_pcm_bytes_to_wav(&pcm, Some(PcmToWavConfig::new(8000, 2, 16))).unwrap()).unwrap();
+
+    // WAV 输入以文件头为准
+    let template = dir.join("call_ch{channel}.wav");
+    let outputs = split_channels(&input, AudioConfig::default(), template.to_str().unwrap()).expect("拆分声道应该成功");
+    assert_eq!(outputs, vec![dir.join("call_ch1.wav"), dir.join("call_ch2.wav")]);
+    let agent = read_wav_file(&outputs[0]).unwrap();
+    let customer = read_wav_file(&outputs[1]).unwrap();
+    assert_eq!(agent.config.channels, 1);
+    assert_eq!(agent.config.sample_rate, 8000);
+    assert_eq!(agent.samples_i16(), (0..8000).map(|i| i * 4).collect::<Vec<i16>>());
+    assert_eq!(customer.samples_i16(), (0..8000).map(|i| -i * 4).collect::<Vec<i16>>());
+    println!("📞 通话录音拆分为 {} 条音轨", outputs.len());
+
+    // 原始 PCM 输入使用传入的参数，按扩展名输出 PCM 和 MP3
+    let raw = dir.join("call.pcm");
+    fs::write(&raw, &pcm).unwrap();
+    let outputs = split_channels(&raw, AudioConfig::new(8000, 2, 16), dir.join("raw_{channel}.pcm").to_str().unwrap()).unwrap();
+    assert_eq!(fs::read(&outputs[1]).unwrap(), (0..8000i16).flat_map(|i| (-i * 4).to_le_bytes()).collect::<Vec<u8>>());
+    let outputs = split_channels(&raw, AudioConfig::new(8000, 2, 16), dir.join("track_{channel}.mp3").to_str().unwrap()).unwrap();
+    assert_eq!(probe_audio(&outputs[0]).unwrap().format, DetectedFormat::Mp3);
+
+    // 模板必须包含声道号
+    assert!(split_channels(&input, AudioConfig::default(), dir.join("same.wav").to_str().unwrap()).is_err());
+    assert!(split_channels(dir.join("missing.wav"), AudioConfig::default(), template.to_str().unwrap()).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 验证转换时交换、选取和重排声道
+#[test]
+fn test_channel_map_during_conversion() {
+    let dir = Path::new("channel_map_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let pcm = stereo_pcm(4000);
+    let swapped = trans_pcm_bytes_to_wav(&pcm, Some(PcmToWavConfig::new(8000, 2, 16).with_channel_map(ChannelMap::swap_stereo()))).expect("交换声道应该成功");
+    let path = dir.join("swapped.wav");
+    fs::write(&path, &swapped).unwrap();
+    let audio = read_wav_file(&path).unwrap();
+    assert_eq!(&audio.samples_i16()[..6], &[0, 0, -4, 4, -8, 8], "左右声道应该互换");
+
+    // 选取单个声道后再复制为立体声
+    let config = PcmToWavConfig::new(8000, 2, 16).with_channel_map(ChannelMap::select(1)).with_target_channels(2);
+    fs::write(&path, trans_pcm_bytes_to_wav(&pcm, Some(config)).unwrap()).unwrap();
+    assert_eq!(&read_wav_file(&path).unwrap().samples_i16()[..4], &[0, 0, -4, -4]);
+
+    // 映射后的声道数决定 MP3 是否需要混缩
+    let mp3_config = Mp3Config::new(8000, 2, Mp3Bitrate::Kbps64, AudioQuality::Medium).with_channel_map("0".parse().unwrap());
+    let mp3 = trans_pcm_bytes_to_mp3(&pcm, Some(mp3_config)).expect("选取声道后编码 MP3 应该成功");
+    assert_eq!(parse_mp3_info(&mp3).unwrap().channels, 1);
+
+    // 流水线中的声道映射
+    let input = dir.join("input.wav");
+    fs::write(&input, trans_pcm_bytes_to_wav(&pcm, Some(PcmToWavConfig::new(8000, 2, 16))).unwrap()).unwrap();
+    AudioPipeline::from(&input).channel_map(ChannelMap::new([1, 0, 0])).run(dir.join("three.pcm")).expect("流水线声道映射应该成功");
+    let output: Vec<i16> = fs::read(dir.join("three.pcm")).unwrap().chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect();
+    assert_eq!(&output[3..6], &[-4, 4, 4]);
+    println!("🔀 声道映射: 交换、选取和重排均正确");
+
+    // 映射引用了不存在的声道
+    let error = trans_pcm_bytes_to_wav(&pcm, Some(PcmToWavConfig::new(8000, 2, 16).with_channel_map(ChannelMap::select(2)))).unwrap_err();
+    assert!(matches!(error.downcast_ref::<AudioError>(), Some(AudioError::InvalidConfig(_))) && error.to_string().contains("channel_map"), "{}", error);
+    assert!(AudioPipeline::from(&input).channel_map(ChannelMap::new([])).run(dir.join("empty.pcm")).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// WAVE_FORMAT_EXTENSIBLE 文件头中的声道掩码（RIFF 容器，fmt 块紧跟在 RIFF 头之后）
+fn channel_mask(wav: &[u8]) -> u32 {
+    assert_eq!(u16::from_le_bytes([wav[20], wav[21]]), 0xFFFE, "应该使用 WAVE_FORMAT_EXTENSIBLE");
+    u32::from_le_bytes(wav[40..44].try_into().unwrap())
+}
+
+/// 验证多声道 PCM 转 WAV 时按布局写入声道掩码，以及 MP3 输出的混缩和拒绝
+#[test]
+fn test_multichannel_layouts() {
+    let dir = Path::new("channel_layout_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    // 5.1：只有前置左声道和 LFE 有信号
+    let frames = 4800;
+    let surround: Vec<i16> = (0..frames).flat_map(|i| [((i % 100) * 100) as i16, 0, 0, 20000, 0, 0]).collect();
+    let pcm: Vec<u8> = surround.iter().flat_map(|s| s.to_le_bytes()).collect();
+
+    for layout in [ChannelLayout::Quad, ChannelLayout::Surround51, ChannelLayout::Surround71] {
+        assert_eq!(ChannelLayout::from_channels(layout.channels()), Some(layout));
+    }
+    assert_eq!("5.1".parse::<ChannelLayout>().unwrap(), ChannelLayout::Surround51);
+    assert!("9.1".parse::<ChannelLayout>().is_err());
+
+    let wav = trans_pcm_bytes_to_wav(&pcm, Some(PcmToWavConfig::new(48000, 6, 16).with_channel_layout(ChannelLayout::Surround51))).expect("5.1 转 WAV 应该成功");
+    assert_eq!(channel_mask(&wav), 0x3F);
+    let quad = trans_pcm_bytes_to_wav(&pcm[..4 * 2 * 100], Some(PcmToWavConfig::new(48000, 4, 16))).unwrap();
+    assert_eq!(channel_mask(&quad), ChannelLayout::Quad.channel_mask(), "四声道默认使用 quad 布局");
+    let stereo = trans_pcm_bytes_to_wav(&pcm[..400], Some(PcmToWavConfig::new(48000, 2, 16).with_channel_layout(ChannelLayout::Stereo))).unwrap();
+    assert_eq!(channel_mask(&stereo), 0x3, "指定布局时立体声也写入声道掩码");
+    println!("🔊 5.1 WAV 声道掩码 0x{:X}", channel_mask(&wav));
+
+    // 布局的声道数必须与输出一致
+    let error = PcmToWavConfig::new(48000, 6, 16).with_channel_layout(ChannelLayout::Surround71).validate().unwrap_err();
+    assert!(error.to_string().contains("channel_layout"), "{}", error);
+    assert!(PcmToWavConfig::new(48000, 6, 16).with_target_channels(2).with_channel_layout(ChannelLayout::Stereo).validate().is_ok());
+
+    // MP3：未指定目标声道数时拒绝，指定后按布局混缩为立体声
+    let mp3_config = Mp3Config::new(48000, 6, Mp3Bitrate::Kbps192, AudioQuality::Medium);
+    assert!(trans_pcm_bytes_to_mp3(&pcm, Some(mp3_config.clone())).is_err());
+    let mp3 = trans_pcm_bytes_to_mp3(&pcm, Some(mp3_config.with_target_channels(2))).expect("5.1 混缩为立体声后编码应该成功");
+    assert_eq!(parse_mp3_info(&mp3).unwrap().channels, 2);
+
+    // 自动转换时多声道 WAV 按布局混缩为立体声，LFE 被舍弃
+    let input = dir.join("surround.wav");
+    fs::write(&input, &wav).unwrap();
+    let report = convert(&input, dir.join("surround.mp3"), None).expect("5.1 WAV 转 MP3 应该成功");
+    assert_eq!(report.config_used.channels, 2);
+    let report = AudioPipeline::from(&input).channels(2).run(dir.join("stereo.wav")).unwrap();
+    assert_eq!(report.config_used.channels, 2);
+    let downmixed = read_wav_file(dir.join("stereo.wav")).unwrap().samples_i16();
+    assert!(downmixed.chunks_exact(2).all(|frame| frame[1] == 0), "只有左前声道有信号时右声道应该为静音");
+    assert!(downmixed.iter().step_by(2).any(|&s| s > 0));
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,165 @@
+#![cfg(feature = "cli")]
+
+use audio_helper::read_wav_file;
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// 运行命令行工具
+fn run_cli(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_audio-helper")).args(args).output().expect("Failed to run audio-helper")
+}
+
+/// 验证 convert / probe / infer 子命令
+#[test]
+fn test_cli_convert_probe_infer() {
+    let dir = Path::new("cli_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm_path = dir.join("clip.pcm");
+    let bytes: Vec<u8> = (0..3200i16).flat_map(|i| (i * 10).to_le_bytes()).collect();
+    fs::write(&pcm_path, &bytes).expect("Failed to write test PCM file");
+    let wav_path = dir.join("clip.wav");
+
+    // 命令行参数覆盖文件名推断
+    let output = run_cli(&["convert", pcm_path.to_str().unwrap(), wav_path.to_str().unwrap(), "--sample-rate", "16000", "--channels", "1", "--bits", "16"]);
+    println!("🖥️ convert: {}", String::from_utf8_lossy(&output.stdout));
+    assert!(output.status.success());
+    let audio = read_wav_file(&wav_path).expect("Failed to read output");
+    assert_eq!((audio.config.sample_rate, audio.config.channels), (16000, 1));
+    assert_eq!(audio.data, bytes);
+
+    let output = run_cli(&["convert", pcm_path.to_str().unwrap(), dir.join("clip.mp3").to_str().unwrap(), "--sample-rate", "16000", "--channels", "1", "--bitrate", "128"]);
+    assert!(output.status.success());
+
+    // 已存在的输出按覆盖策略报错
+    let output = run_cli(&["convert", pcm_path.to_str().unwrap(), wav_path.to_str().unwrap(), "--overwrite", "error"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already exists"));
+
+    let output = run_cli(&["probe", wav_path.to_str().unwrap()]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("🖥️ probe: {}", stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("16000Hz"));
+    assert!(stdout.contains("0.200s"));
+
+    // JSON 输出可被脚本解析
+    let output = run_cli(&["probe", wav_path.to_str().unwrap(), "--json"]);
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("probe --json 应输出合法 JSON");
+    assert_eq!(json["format"], "wav");
+    assert_eq!(json["config"]["sample_rate"], 16000);
+    assert_eq!(json["data_size"], bytes.len() as u64);
+    assert_eq!(json["chunks"].as_array().unwrap().last().unwrap()["id"], "data");
+
+    let output = run_cli(&["infer", "voice_48k24bit双声道.pcm"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("48000Hz, 2 声道, 24 位"));
+
+    // 不支持的转换
+    assert!(!run_cli(&["convert", wav_path.to_str().unwrap(), dir.join("x.mp3").to_str().unwrap()]).status.success());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 验证 batch 子命令，有文件失败时返回非零退出码
+#[test]
+fn test_cli_batch() {
+    let input_dir = Path::new("cli_test_batch_input");
+    let output_dir = Path::new("cli_test_batch_output");
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(input_dir).expect("Failed to create test directory");
+    let bytes: Vec<u8> = (0..1600i16).flat_map(|i| (i * 10).to_le_bytes()).collect();
+    fs::write(input_dir.join("a_16k16bit单声道.pcm"), &bytes).expect("Failed to write test PCM file");
+    fs::write(input_dir.join("b_8k16bit单声道.pcm"), &bytes).expect("Failed to write test PCM file");
+
+    let output = run_cli(&["batch", input_dir.to_str().unwrap(), output_dir.to_str().unwrap(), "--to", "wav", "--jobs", "2"]);
+    println!("🖥️ batch: {}", String::from_utf8_lossy(&output.stdout));
+    assert!(output.status.success());
+    assert_eq!(read_wav_file(output_dir.join("b_8k16bit单声道.wav")).expect("Failed to read output").config.sample_rate, 8000);
+
+    fs::write(input_dir.join("broken.pcm"), b"RIFF\x24\x00\x00\x00WAVEfmt ").expect("Failed to write test file");
+    let output = run_cli(&["batch", input_dir.to_str().unwrap(), output_dir.to_str().unwrap(), "--overwrite", "skip"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("跳过 2 个"));
+
+    // glob 模式与输出模板，{dir} 保留相对于模式固定前缀的目录结构
+    fs::create_dir_all(input_dir.join("sub")).expect("Failed to create test directory");
+    fs::write(input_dir.join("sub").join("c_16k16bit单声道.pcm"), &bytes).expect("Failed to write test PCM file");
+    let glob_output = Path::new("cli_test_glob_output");
+    let _ = fs::remove_dir_all(glob_output);
+    let pattern = format!("{}/**/*_16k16bit单声道.pcm", input_dir.display());
+    let template = format!("{}/{{dir}}/{{stem}}-converted.{{ext}}", glob_output.display());
+    let output = run_cli(&["batch", &pattern, "--to", "mp3", "--out", &template, "-j", "2"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("🖥️ batch glob:\n{}", stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("成功 2 个"));
+    assert!(glob_output.join("a_16k16bit单声道-converted.mp3").exists());
+    assert!(glob_output.join("sub").join("c_16k16bit单声道-converted.mp3").exists());
+
+    // 多个输入写入同一输出时报错
+    let output = run_cli(&["batch", &pattern, "--out", &format!("{}/same.wav", glob_output.display())]);
+    assert!(!output.status.success());
+
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    let _ = fs::remove_dir_all(glob_output);
+}
+
+/// 验证 convert --profile 使用配置文件中的参数，命令行参数优先
+#[test]
+fn test_cli_convert_with_profile() {
+    let dir = Path::new("cli_profile_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm_path = dir.join("clip.pcm");
+    let bytes: Vec<u8> = (0..3200i16).flat_map(|i| (i * 10).to_le_bytes()).collect();
+    fs::write(&pcm_path, &bytes).expect("Failed to write test PCM file");
+    let profile_path = dir.join("voice.toml");
+    fs::write(&profile_path, "name = \"voice\"\nsample_rate = 16000\nchannels = 1\ntarget_channels = 2\n").unwrap();
+
+    let wav_path = dir.join("clip.wav");
+    let output = run_cli(&["convert", pcm_path.to_str().unwrap(), wav_path.to_str().unwrap(), "--profile", profile_path.to_str().unwrap()]);
+    println!("🖥️ convert --profile: {}", String::from_utf8_lossy(&output.stdout));
+    assert!(output.status.success());
+    let audio = read_wav_file(&wav_path).expect("Failed to read output");
+    assert_eq!((audio.config.sample_rate, audio.config.channels), (16000, 2));
+
+    // 命令行参数覆盖配置文件
+    let output = run_cli(&["convert", pcm_path.to_str().unwrap(), wav_path.to_str().unwrap(), "--profile", profile_path.to_str().unwrap(), "--sample-rate", "8000"]);
+    assert!(output.status.success());
+    assert_eq!(read_wav_file(&wav_path).unwrap().config.sample_rate, 8000);
+
+    let output = run_cli(&["convert", pcm_path.to_str().unwrap(), wav_path.to_str().unwrap(), "--profile", dir.join("missing.toml").to_str().unwrap()]);
+    assert!(!output.status.success());
+
+    let _ = fs::remove_dir_all(dir);
+    println!("✅ convert --profile 测试通过");
+}
+
+/// 验证 convert --preset 使用预设的输出采样率和声道
+#[test]
+fn test_cli_convert_with_preset() {
+    let dir = Path::new("cli_preset_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm_path = dir.join("clip_48k16bit双声道.pcm");
+    let bytes: Vec<u8> = (0..9600i16).flat_map(|i| (i * 3).to_le_bytes()).collect();
+    fs::write(&pcm_path, &bytes).expect("Failed to write test PCM file");
+
+    let mp3_path = dir.join("clip.mp3");
+    let output = run_cli(&["convert", pcm_path.to_str().unwrap(), mp3_path.to_str().unwrap(), "--preset", "voice"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("🖥️ convert --preset: {}", stdout);
+    assert!(output.status.success());
+    assert!(stdout.contains("16000Hz, 1 声道"), "{}", stdout);
+
+    let output = run_cli(&["convert", pcm_path.to_str().unwrap(), mp3_path.to_str().unwrap(), "--preset", "karaoke"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("voice"));
+
+    let _ = fs::remove_dir_all(dir);
+    println!("✅ convert --preset 测试通过");
+}
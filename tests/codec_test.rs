@@ -0,0 +1,293 @@
+use audio_helper::{
+    decode_samples_i16, generate_sine, trans_pcm_bytes_to_mp3, trans_pcm_bytes_to_wav, AudioConfig, AudioDecoder, AudioEncoder, AudioQuality, Endianness,
+    Mp3Bitrate, Mp3Config, Mp3StreamEncoder, PcmDecoder, PcmToWavConfig, SampleFormat, WavContainer, WavDecoder,
+    WavStreamEncoder, WavWriter,
+};
+use std::io::Cursor;
+use std::time::Duration;
+
+/// 生成交错的正弦波样本
+fn sine_samples(sample_rate: u32, channels: u8, seconds: f32, frequency: f32) -> Vec<i16> {
+    let pcm = generate_sine(frequency, Duration::from_secs_f32(seconds), &AudioConfig::new(sample_rate, channels, 16));
+    decode_samples_i16(&pcm, SampleFormat::S16)
+}
+
+fn to_le_bytes(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+/// 统计样本数的编码器，验证下游可以实现自己的编码器
+#[derive(Default)]
+struct CountingEncoder {
+    samples: usize,
+}
+
+impl AudioEncoder for CountingEncoder {
+    fn encode(&mut self, samples: &[i16]) -> Result<(), Box<dyn std::error::Error>> {
+        self.samples += samples.len();
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<u64, Box<dyn std::error::Error>> {
+        Ok(self.samples as u64 * 2)
+    }
+}
+
+/// 将解码器的输出全部送入编码器
+fn transcode(decoder: &mut dyn AudioDecoder, encoder: &mut dyn AudioEncoder) -> Result<u64, Box<dyn std::error::Error>> {
+    let mut samples = Vec::new();
+    loop {
+        samples.clear();
+        if decoder.decode(&mut samples)? == 0 {
+            break;
+        }
+        encoder.encode(&samples)?;
+    }
+    encoder.finish()
+}
+
+/// 验证分块写入的 WAV 与整体转换的结果相同，并能被 WavDecoder 原样读回
+#[test]
+fn test_wav_encoder_roundtrip() {
+    let samples = sine_samples(22050, 2, 1.5, 440.0);
+    let config = PcmToWavConfig::new(22050, 2, 16).with_container(WavContainer::Riff);
+
+    let mut encoder = WavStreamEncoder::new(Cursor::new(Vec::new()), &config).expect("创建编码器应该成功");
+    for chunk in samples.chunks(1000) {
+        encoder.encode(chunk).expect("编码应该成功");
+    }
+    let total = encoder.finish().expect("完成编码应该成功");
+    assert_eq!(encoder.finish().unwrap(), total, "重复调用 finish 应该直接返回");
+    assert!(encoder.encode(&samples[..2]).is_err(), "finish 之后不能继续编码");
+    let wav = encoder.into_inner().into_inner();
+    assert_eq!(total, wav.len() as u64);
+    assert_eq!(wav, trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(config)).unwrap());
+
+    let mut decoder = WavDecoder::new(Cursor::new(wav)).expect("解析 WAV 应该成功");
+    assert_eq!(decoder.config().sample_rate, 22050);
+    assert_eq!(decoder.config().channels, 2);
+    assert_eq!(decoder.decode_to_end().unwrap(), samples);
+    println!("🔁 WAV 编码 {} 个样本后解码结果一致", samples.len());
+
+    // 未指定容器时在 RIFF 头中预留 ds64 的位置
+    let mut encoder = WavStreamEncoder::new(Cursor::new(Vec::new()), &PcmToWavConfig::new(22050, 2, 16)).unwrap();
+    encoder.encode(&samples).unwrap();
+    encoder.finish().unwrap();
+    let wav = encoder.into_inner().into_inner();
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert_eq!(&wav[12..16], b"JUNK");
+    assert_eq!(WavDecoder::new(Cursor::new(wav)).unwrap().decode_to_end().unwrap(), samples);
+
+    // RF64 容器和 24 位样本
+    let config = PcmToWavConfig::new(16000, 1, 24).with_container(WavContainer::Rf64);
+    let mono = sine_samples(16000, 1, 0.5, 1000.0);
+    let mut encoder = WavStreamEncoder::new(Cursor::new(Vec::new()), &config).unwrap();
+    encoder.encode(&mono).unwrap();
+    encoder.finish().unwrap();
+    let decoded = WavDecoder::new(Cursor::new(encoder.into_inner().into_inner())).unwrap().decode_to_end().unwrap();
+    assert_eq!(decoded, mono, "16 位样本写成 24 位后应该能无损读回");
+
+    // IMA ADPCM 按块解码，按 fact 块记录的帧数去掉最后一块的补齐
+    let adpcm = trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(PcmToWavConfig::new(22050, 2, 16).with_ima_adpcm(true))).unwrap();
+    let decoded = WavDecoder::new(Cursor::new(adpcm)).unwrap().decode_to_end().unwrap();
+    assert_eq!(decoded.len(), samples.len());
+
+    // 不支持的配置在创建时报错
+    assert!(WavStreamEncoder::new(Cursor::new(Vec::new()), &PcmToWavConfig::new(16000, 1, 16).with_ima_adpcm(true)).is_err());
+}
+
+/// 验证 PcmDecoder 按字节序和样本格式读取，忽略末尾不完整的帧
+#[test]
+fn test_pcm_decoder() {
+    let samples = sine_samples(8000, 2, 1.0, 300.0);
+    let mut data: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+    data.extend_from_slice(&[1, 2, 3]);
+
+    let config = AudioConfig::new(8000, 2, 16).with_endianness(Endianness::Big);
+    let mut decoder = PcmDecoder::new(Cursor::new(data), config).expect("创建解码器应该成功");
+    assert_eq!(decoder.decode_to_end().unwrap(), samples);
+
+    let bytes: Vec<u8> = samples.iter().map(|&s| ((s >> 8) as i8 as u8) ^ 0x80).collect();
+    let config = AudioConfig::new(8000, 2, 8).with_sample_format(SampleFormat::U8);
+    let decoded = PcmDecoder::new(Cursor::new(bytes), config).unwrap().decode_to_end().unwrap();
+    assert_eq!(decoded.len(), samples.len());
+
+    let mut counter = CountingEncoder::default();
+    let mut decoder = PcmDecoder::new(Cursor::new(to_le_bytes(&samples)), AudioConfig::new(8000, 2, 16)).unwrap();
+    assert_eq!(transcode(&mut decoder, &mut counter).unwrap(), samples.len() as u64 * 2);
+    println!("🧮 自定义编码器收到 {} 个样本", counter.samples);
+}
+
+/// 验证分块送入 Mp3StreamEncoder 的结果与整体转换相同
+#[test]
+fn test_mp3_encoder_matches_bytes_conversion() {
+    let samples = sine_samples(44100, 2, 2.0, 440.0);
+    let config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium);
+
+    let mut encoder = Mp3StreamEncoder::new(Cursor::new(Vec::new()), &config).expect("创建编码器应该成功");
+    let mut decoder = PcmDecoder::new(Cursor::new(to_le_bytes(&samples)), AudioConfig::new(44100, 2, 16)).unwrap();
+    let total = transcode(&mut decoder, &mut encoder).expect("编码应该成功");
+    let mp3 = encoder.into_inner().into_inner();
+    assert_eq!(total, mp3.len() as u64);
+    assert_eq!(mp3, trans_pcm_bytes_to_mp3(&to_le_bytes(&samples), Some(config)).unwrap());
+    println!("🎵 流式 MP3 编码输出 {} 字节，与整体转换一致", mp3.len());
+
+    // 需要重采样的配置应该在创建时报错
+    let resampled = Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::Medium).with_target_sample_rate(22050);
+    assert!(Mp3StreamEncoder::new(Cursor::new(Vec::new()), &resampled).is_err());
+}
+
+/// 验证 MP3 解码去除编码器延迟后与原始音频对齐
+#[cfg(feature = "mp3-decode")]
+#[test]
+fn test_mp3_decoder_roundtrip() {
+    use audio_helper::Mp3Decoder;
+
+    let samples = sine_samples(44100, 2, 2.0, 440.0);
+    let config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps192, AudioQuality::Medium);
+    let mp3 = trans_pcm_bytes_to_mp3(&to_le_bytes(&samples), Some(config)).unwrap();
+
+    let mut decoder = Mp3Decoder::new(Cursor::new(mp3)).expect("解析 MP3 应该成功");
+    assert_eq!(decoder.config().sample_rate, 44100);
+    assert_eq!(decoder.config().channels, 2);
+    let decoded = decoder.decode_to_end().expect("解码应该成功");
+    // 去除编码器延迟后开头与原始音频对齐；编码器以 FlushNoGap 结束，末尾缓冲中的样本不会输出
+    assert!(decoded.len() <= samples.len());
+    assert!(samples.len() - decoded.len() <= 2 * 1152 * 2, "解码样本数 {} 与原始样本数 {} 相差过多", decoded.len(), samples.len());
+
+    // 有损编码后波形大致相同
+    let error: f64 = decoded.iter().zip(&samples).map(|(&a, &b)| (a as f64 - b as f64).abs()).sum::<f64>() / samples.len() as f64;
+    assert!(error < 500.0, "平均误差过大: {}", error);
+    println!("🎧 MP3 解码 {} 个样本，平均误差 {:.1}", decoded.len(), error);
+}
+
+/// 验证无缝编码：同一段录音切成两段分别编码，解码后长度与各段输入完全相同，拼接后与原始音频对齐
+#[cfg(feature = "mp3-decode")]
+#[test]
+fn test_mp3_gapless_clips() {
+    use audio_helper::Mp3Decoder;
+
+    let samples = sine_samples(44100, 2, 3.0, 440.0);
+    // 切分点不在 MP3 帧边界上
+    let (first, second) = samples.split_at(44100 * 2 + 2 * 777);
+    let config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps192, AudioQuality::Medium).with_gapless(true);
+
+    let mut decoded = Vec::new();
+    for clip in [first, second] {
+        let mut encoder = Mp3StreamEncoder::new(Cursor::new(Vec::new()), &config).unwrap();
+        encoder.encode(clip).unwrap();
+        encoder.finish().unwrap();
+        let mut decoder = Mp3Decoder::new(Cursor::new(encoder.into_inner().into_inner())).unwrap();
+        let clip_decoded = decoder.decode_to_end().expect("解码应该成功");
+        assert_eq!(clip_decoded.len(), clip.len(), "无缝编码的片段解码后长度应该与输入相同");
+        decoded.extend(clip_decoded);
+    }
+
+    // 拼接处附近的波形与原始音频一致，没有插入静音或丢失样本
+    let boundary = first.len();
+    let window = &decoded[boundary - 2048..boundary + 2048];
+    let error: f64 = window.iter().zip(&samples[boundary - 2048..]).map(|(&a, &b)| (a as f64 - b as f64).abs()).sum::<f64>() / window.len() as f64;
+    assert!(error < 500.0, "拼接处平均误差过大: {}", error);
+    println!("🔗 两段无缝编码拼接后 {} 个样本，拼接处平均误差 {:.1}", decoded.len(), error);
+}
+
+/// 验证从惰性生成的采样帧迭代器编码，结果与整体编码相同
+#[test]
+fn test_encode_from_iterator() {
+    let samples = sine_samples(44100, 2, 1.0, 440.0);
+
+    // 逐帧送入，编码器不会一次得到完整的信号
+    let frames = samples.chunks_exact(2).map(|frame| [frame[0], frame[1]]);
+    let config = PcmToWavConfig::new(44100, 2, 16).with_container(WavContainer::Riff);
+    let mut encoder = WavStreamEncoder::new(Cursor::new(Vec::new()), &config).unwrap();
+    let total = encoder.encode_iter(frames).expect("按帧编码应该成功");
+    let wav = encoder.into_inner().into_inner();
+    assert_eq!(total, wav.len() as u64);
+    assert_eq!(wav, trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(config)).unwrap());
+
+    // 大小不一的样本块
+    let mp3_config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium);
+    let mut encoder = Mp3StreamEncoder::new(Cursor::new(Vec::new()), &mp3_config).unwrap();
+    let chunks = samples.chunks(2 * 1000).chain(std::iter::once(&[][..]));
+    let total = encoder.encode_iter(chunks).expect("按块编码应该成功");
+    let mp3 = encoder.into_inner().into_inner();
+    assert_eq!(total, mp3.len() as u64);
+    let mut whole = Mp3StreamEncoder::new(Cursor::new(Vec::new()), &mp3_config).unwrap();
+    whole.encode(&samples).unwrap();
+    whole.finish().unwrap();
+    assert_eq!(mp3, whole.into_inner().into_inner());
+
+    // trait 对象同样可用
+    let mut counting: Box<dyn AudioEncoder> = Box::new(CountingEncoder::default());
+    assert_eq!(counting.encode_iter(vec![vec![1i16, 2], vec![3, 4, 5, 6]]).unwrap(), 12);
+    println!("🧵 迭代器编码: WAV {} 字节，MP3 {} 字节", wav.len(), mp3.len());
+}
+
+/// 验证 32 位整数和浮点样本写入高位深 WAV 时不经过 i16 损失精度
+#[test]
+fn test_encode_generic_samples() {
+    use audio_helper::{convert_samples, decode_samples, Sample};
+
+    let tone = AudioConfig::new(48000, 1, 32).with_sample_format(SampleFormat::F32);
+    let floats: Vec<f32> = decode_samples(&generate_sine(440.0, Duration::from_millis(100), &tone), SampleFormat::F32);
+    let config = PcmToWavConfig::new(48000, 1, 32).with_sample_format(SampleFormat::F32);
+    let mut encoder = WavStreamEncoder::new(Cursor::new(Vec::new()), &config).unwrap();
+    encoder.encode_as(&floats).expect("写入 f32 样本应该成功");
+    encoder.finish().unwrap();
+    let wav = encoder.into_inner().into_inner();
+    assert!(wav.ends_with(&floats.iter().flat_map(|s| s.to_le_bytes()).collect::<Vec<u8>>()), "浮点样本应该原样写入");
+
+    let ints: Vec<i32> = convert_samples(&floats);
+    let mut encoder = WavStreamEncoder::new(Cursor::new(Vec::new()), &PcmToWavConfig::new(48000, 1, 32)).unwrap();
+    encoder.encode_as(&ints).unwrap();
+    encoder.finish().unwrap();
+    let wav = encoder.into_inner().into_inner();
+    assert_eq!(decode_samples::<i32>(&wav[wav.len() - ints.len() * 4..], SampleFormat::S32), ints);
+    assert!(ints.iter().any(|s| s & 0xFFFF != 0), "低 16 位应该被保留");
+
+    // 只支持 i16 的编码器默认转换为 i16
+    let mut counting = CountingEncoder::default();
+    counting.encode_as(&ints).unwrap();
+    assert_eq!(counting.samples, ints.len());
+    assert_eq!(ints[100].convert::<i16>(), floats[100].convert::<i16>());
+    println!("🎚️ 通用样本类型: {} 个 f32 / i32 样本无损写入", floats.len());
+}
+
+/// 验证 WavWriter 分块写入后 finalize 回填大小，未调用 finalize 时在 Drop 中回填
+#[test]
+fn test_wav_writer() {
+    let output_path = "codec_wav_writer.wav";
+    let samples = sine_samples(16000, 2, 1.0, 440.0);
+    let config = PcmToWavConfig::new(16000, 2, 16).with_container(WavContainer::Riff);
+
+    let mut writer = WavWriter::create(output_path, &config).expect("创建 WAV 文件应该成功");
+    for chunk in samples.chunks(2 * 333) {
+        writer.write_frames(chunk).expect("写入应该成功");
+    }
+    assert!(writer.write_frames(&samples[..3]).is_err(), "不完整的帧应该报错");
+    assert_eq!(writer.frames(), 16000);
+    let size = writer.finalize().expect("finalize 应该成功");
+    let wav = std::fs::read(output_path).unwrap();
+    assert_eq!(size, wav.len() as u64);
+    assert_eq!(wav, trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(config.clone())).unwrap(), "应该与整体转换的结果相同");
+    println!("📝 WavWriter 写入 {} 字节", size);
+
+    // 没有调用 finalize 就被丢弃
+    {
+        let mut writer = WavWriter::create(output_path, &config).unwrap();
+        writer.write_frames(&samples[..2000]).unwrap();
+    }
+    let decoded = WavDecoder::new(std::fs::File::open(output_path).unwrap()).unwrap().decode_to_end().unwrap();
+    assert_eq!(decoded, samples[..2000], "Drop 时应该回填文件头");
+    let _ = std::fs::remove_file(output_path);
+
+    // 无效的配置不留下文件
+    assert!(WavWriter::create(output_path, &PcmToWavConfig::new(16000, 2, 16).with_ima_adpcm(true)).is_err());
+    assert!(!std::path::Path::new(output_path).exists());
+
+    // 无效的配置不覆盖已有的文件
+    std::fs::write(output_path, b"existing").unwrap();
+    assert!(WavWriter::create(output_path, &PcmToWavConfig::new(16000, 2, 16).with_ima_adpcm(true)).is_err());
+    assert_eq!(std::fs::read(output_path).unwrap(), b"existing", "已有的文件应该保持不变");
+    let _ = std::fs::remove_file(output_path);
+}
@@ -0,0 +1,96 @@
+use audio_helper::{encode_samples_f32, guess_config_from_content, swap_sample_bytes, AudioConfig, Endianness, SampleFormat};
+use std::fs;
+use std::path::Path;
+
+/// 生成交错的多音正弦波，每个声道使用不同的频率组合，幅度为满幅的一半
+fn tones(sample_rate: u32, channels: u8, seconds: f32, frequencies: &[f32]) -> Vec<f32> {
+    let frames = (sample_rate as f32 * seconds) as usize;
+    (0..frames)
+        .flat_map(|i| {
+            let t = i as f32 / sample_rate as f32;
+            (0..channels).map(move |ch| {
+                let sum: f32 = frequencies.iter().map(|f| (2.0 * std::f32::consts::PI * f * (ch as f32 + 1.0) * t).sin()).sum();
+                0.5 * sum / frequencies.len() as f32
+            })
+        })
+        .collect()
+}
+
+/// 将样本按指定格式写入测试文件，返回猜测结果
+fn guess(dir: &Path, name: &str, samples: &[f32], format: SampleFormat, endianness: Endianness) -> Vec<(AudioConfig, f32)> {
+    let mut data = encode_samples_f32(samples, format);
+    if endianness == Endianness::Big {
+        swap_sample_bytes(&mut data, format);
+    }
+    let path = dir.join(name);
+    fs::write(&path, data).expect("Failed to write test PCM file");
+    let candidates = guess_config_from_content(&path).expect("内容推断失败");
+    for (config, confidence) in candidates.iter().take(3) {
+        println!("🔍 {} -> {}Hz {}ch {:?} {:?} ({:.2})", name, config.sample_rate, config.channels, config.sample_format, config.endianness, confidence);
+    }
+    candidates
+}
+
+/// 验证样本格式、字节序和声道数的推断
+#[test]
+fn test_guess_format_and_channels() {
+    let dir = Path::new("content_guess_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let cases = [
+        ("mono_s16le.raw", 1, SampleFormat::S16, Endianness::Little),
+        ("stereo_s16le.raw", 2, SampleFormat::S16, Endianness::Little),
+        ("mono_s16be.raw", 1, SampleFormat::S16, Endianness::Big),
+        ("stereo_s24le.raw", 2, SampleFormat::S24, Endianness::Little),
+        ("mono_f32le.raw", 1, SampleFormat::F32, Endianness::Little),
+        ("mono_u8.raw", 1, SampleFormat::U8, Endianness::Little),
+    ];
+    for (name, channels, format, endianness) in cases {
+        let samples = tones(16000, channels, 1.0, &[220.0, 330.0]);
+        let candidates = guess(dir, name, &samples, format, endianness);
+        let (best, confidence) = &candidates[0];
+        assert_eq!(best.sample_format, format, "样本格式推断错误: {}", name);
+        assert_eq!(best.endianness, endianness, "字节序推断错误: {}", name);
+        assert_eq!(best.channels, channels, "声道数推断错误: {}", name);
+        assert_eq!(best.bits_per_sample, format.bits_per_sample());
+        assert!(*confidence > 0.0 && *confidence <= 1.0);
+        assert!(candidates.windows(2).all(|pair| pair[0].1 >= pair[1].1), "候选未按置信度排序: {}", name);
+    }
+
+    // 单声道复制成的双声道
+    let dual_mono: Vec<f32> = tones(16000, 1, 1.0, &[220.0]).into_iter().flat_map(|s| [s, s]).collect();
+    let candidates = guess(dir, "dual_mono.raw", &dual_mono, SampleFormat::S16, Endianness::Little);
+    assert_eq!(candidates[0].0.channels, 2);
+
+    // 空文件和静音没有候选
+    assert!(guess(dir, "empty.raw", &[], SampleFormat::S16, Endianness::Little).is_empty());
+    assert!(guess(dir, "silence.raw", &[0.0; 16000], SampleFormat::S16, Endianness::Little).is_empty());
+    assert!(guess_config_from_content(dir.join("missing.raw")).is_err());
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ 样本格式和声道数推断测试通过");
+}
+
+/// 验证根据频谱截止位置估计采样率：电话带宽的内容在 8kHz 下充满频带，在 48kHz 下只占低频
+#[test]
+fn test_guess_sample_rate_from_bandwidth() {
+    let dir = Path::new("content_guess_rate_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    // 300Hz 到 3400Hz 的谐波，模拟电话语音
+    let voice: Vec<f32> = (1..=11).map(|k| 300.0 * k as f32 + 50.0).collect();
+    let wideband = tones(48000, 1, 1.0, &voice);
+    let candidates = guess(dir, "voice_48k.raw", &wideband, SampleFormat::S16, Endianness::Little);
+    assert!(candidates[0].0.sample_rate >= 44100, "宽带采样率推断错误: {}", candidates[0].0.sample_rate);
+
+    let narrowband = tones(8000, 1, 1.0, &voice);
+    let candidates = guess(dir, "voice_8k.raw", &narrowband, SampleFormat::S16, Endianness::Little);
+    // 充满频带的 8kHz 电话语音与 16kHz 宽带语音无法区分，只要求排在前面的都是低采样率
+    assert!(candidates[0].0.sample_rate <= 16000, "窄带采样率推断错误: {}", candidates[0].0.sample_rate);
+    assert!(candidates.iter().any(|(config, _)| config.sample_rate == 8000 && config.channels == 1));
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ 采样率推断测试通过");
+}
@@ -0,0 +1,123 @@
+use audio_helper::{
+    convert, generate_sine, read_wav_file, trans_pcm_bytes_to_wav, AudioConfig, AudioFormat, ConvertOptions, ConvertStage, OverwritePolicy,
+    PcmToWavConfig,
+};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 生成正弦波 PCM 数据（16 位小端）
+fn sine_pcm(sample_rate: u32, channels: u8, seconds: f32, frequency: f32) -> Vec<u8> {
+    generate_sine(frequency, Duration::from_secs_f32(seconds), &AudioConfig::new(sample_rate, channels, 16))
+}
+
+/// 验证按输入内容和输出扩展名选择转换路径
+#[test]
+fn test_convert_routes_by_format() {
+    let dir = Path::new("convert_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    // PCM 输入的参数从文件名推断
+    let pcm_path = dir.join("voice_16000_mono.pcm");
+    let pcm = sine_pcm(16000, 1, 1.0, 440.0);
+    fs::write(&pcm_path, &pcm).unwrap();
+    let wav_path = dir.join("voice.wav");
+    let report = convert(&pcm_path, &wav_path, None).expect("PCM 转 WAV 应该成功");
+    assert_eq!(report.format, AudioFormat::Wav);
+    assert_eq!(report.config_used.sample_rate, 16000);
+    assert_eq!(report.config_used.channels, 1);
+    assert_eq!(read_wav_file(&wav_path).unwrap().data, pcm);
+    println!("📄 PCM -> WAV: {} 字节", report.output_bytes);
+
+    let report = convert(&pcm_path, dir.join("voice.mp3"), None).expect("PCM 转 MP3 应该成功");
+    assert_eq!(report.format, AudioFormat::Mp3);
+    assert_eq!(report.duration.as_millis(), 1000);
+
+    // WAV 输入按文件头读取参数，扩展名不影响识别
+    let disguised = dir.join("voice_wav_8000.pcm");
+    fs::copy(&wav_path, &disguised).unwrap();
+    let report = convert(&disguised, dir.join("from_wav.pcm"), None).expect("WAV 转 PCM 应该成功");
+    assert_eq!(report.config_used.sample_rate, 16000);
+    assert_eq!(fs::read(dir.join("from_wav.pcm")).unwrap(), pcm);
+
+    let report = convert(&wav_path, dir.join("from_wav.mp3"), None).expect("WAV 转 MP3 应该成功");
+    assert_eq!(report.output_bytes, fs::metadata(dir.join("from_wav.mp3")).unwrap().len());
+    assert_eq!(report.duration.as_millis(), 1000);
+    println!("🎵 WAV -> MP3: {} 字节", report.output_bytes);
+
+    // IMA ADPCM 的 WAV 解码为 16 位 PCM 的 WAV
+    let adpcm_path = dir.join("adpcm.wav");
+    fs::write(&adpcm_path, trans_pcm_bytes_to_wav(&pcm, Some(PcmToWavConfig::new(16000, 1, 16).with_ima_adpcm(true))).unwrap()).unwrap();
+    let report = convert(&adpcm_path, dir.join("decoded.wav"), None).expect("ADPCM 转 WAV 应该成功");
+    let decoded = read_wav_file(dir.join("decoded.wav")).unwrap();
+    assert_eq!(decoded.config.bits_per_sample, 16);
+    assert_eq!(decoded.data.len(), pcm.len());
+    assert_eq!(report.duration.as_millis(), 1000);
+
+    // 非 MP3 采样率的 WAV 自动重采样
+    let hires = dir.join("hires.wav");
+    fs::write(&hires, trans_pcm_bytes_to_wav(&sine_pcm(96000, 2, 0.5, 440.0), Some(PcmToWavConfig::new(96000, 2, 16))).unwrap()).unwrap();
+    let report = convert(&hires, dir.join("hires.mp3"), None).expect("重采样转换应该成功");
+    assert_eq!(report.config_used.sample_rate, 48000);
+    assert_eq!(report.config_used.channels, 2);
+    println!("🔄 96kHz WAV -> {}Hz MP3", report.config_used.sample_rate);
+
+    // 不支持的输出扩展名和不存在的输入
+    assert!(convert(&wav_path, dir.join("voice.flac"), None).is_err());
+    assert!(convert(dir.join("missing.wav"), dir.join("out.wav"), None).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 验证转换选项（进度、覆盖策略）对解码路径同样生效
+#[test]
+fn test_convert_options() {
+    let dir = Path::new("convert_options_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let wav_path = dir.join("input.wav");
+    fs::write(&wav_path, trans_pcm_bytes_to_wav(&sine_pcm(44100, 2, 2.0, 440.0), Some(PcmToWavConfig::new(44100, 2, 16))).unwrap()).unwrap();
+    let output = dir.join("output.mp3");
+
+    let stages = Arc::new(Mutex::new(Vec::new()));
+    let recorded = stages.clone();
+    let options = ConvertOptions::new().with_progress(move |event| recorded.lock().unwrap().push((event.stage, event.bytes_processed, event.total_bytes)));
+    convert(&wav_path, &output, Some(options)).expect("转换应该成功");
+    let stages = stages.lock().unwrap();
+    assert!(stages.iter().any(|&(stage, done, total)| stage == ConvertStage::Encoding && done == total && total > 0));
+    assert!(stages.iter().any(|&(stage, done, _)| stage == ConvertStage::Writing && done > 0));
+    println!("📊 收到 {} 个进度事件", stages.len());
+
+    let before = fs::read(&output).unwrap();
+    let report = convert(&wav_path, &output, Some(ConvertOptions::new().with_overwrite(OverwritePolicy::Skip))).unwrap();
+    assert!(report.skipped);
+    assert_eq!(fs::read(&output).unwrap(), before);
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 未启用 mp3-decode 时 MP3 输入应该报错，启用后可以解码
+#[test]
+fn test_convert_mp3_input() {
+    let dir = Path::new("convert_mp3_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let mp3_path = dir.join("input.mp3");
+    let mp3 = audio_helper::trans_pcm_bytes_to_mp3(&sine_pcm(44100, 2, 1.0, 440.0), None).unwrap();
+    fs::write(&mp3_path, mp3).unwrap();
+    let result = convert(&mp3_path, dir.join("output.wav"), None);
+    if cfg!(feature = "mp3-decode") {
+        let report = result.expect("MP3 转 WAV 应该成功");
+        assert_eq!(report.config_used.sample_rate, 44100);
+        assert!(report.duration.as_millis() > 900);
+        println!("🎧 MP3 -> WAV: {:?}", report.duration);
+    } else {
+        assert!(result.is_err());
+    }
+
+    let _ = fs::remove_dir_all(dir);
+}
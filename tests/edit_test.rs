@@ -0,0 +1,200 @@
+use audio_helper::{trans_pcm_file_to_wav, mix_files, crossfade_concat, extract_range, reverse_audio, change_tempo, read_wav_file, PcmToWavConfig, AudioConfig, AudioError, MixOptions, MixAlign};
+use std::fs;
+
+fn to_bytes(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+fn read_pcm(path: &str) -> Vec<i16> {
+    fs::read(path).expect("Failed to read PCM output").chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect()
+}
+
+/// 验证混音：旁白（PCM）叠加不同采样率和声道数的背景音乐（WAV）
+#[test]
+fn test_mix_files() {
+    let narration_path = "edit_mix_narration_16k16bit单声道.pcm";
+    let music_pcm_path = "edit_mix_music.pcm";
+    let music_path = "edit_mix_music.wav";
+    let output_path = "edit_mix_output.wav";
+
+    // 旁白：1 秒 16kHz 单声道；背景音乐：0.5 秒 8kHz 立体声
+    fs::write(narration_path, to_bytes(&vec![8000i16; 16000])).expect("Failed to write test PCM file");
+    fs::write(music_pcm_path, to_bytes(&vec![4000i16; 8000])).expect("Failed to write test PCM file");
+    trans_pcm_file_to_wav(music_pcm_path, music_path, Some(PcmToWavConfig::new(8000, 2, 16))).expect("WAV 转换应该成功");
+
+    let options = MixOptions::default().with_gain_b(-6.0206).with_align(MixAlign::End);
+    mix_files(narration_path, music_path, output_path, &options).expect("混音应该成功");
+
+    let audio = read_wav_file(output_path).expect("Failed to read WAV output");
+    assert_eq!(audio.config.sample_rate, 16000, "输出应该沿用音轨 A 的采样率");
+    assert_eq!(audio.config.channels, 1, "输出应该沿用音轨 A 的声道数");
+    assert_eq!(audio.frames(), 16000);
+
+    let samples = audio.samples_i16();
+    println!("混音前半段: {}, 后半段: {}", samples[4000], samples[12000]);
+    assert_eq!(samples[4000], 8000, "背景音乐结尾对齐，前半段只有旁白");
+    assert!((samples[12000] - 10000).abs() <= 2, "后半段应该叠加衰减 6dB 的背景音乐");
+
+    for path in [narration_path, music_pcm_path, music_path, output_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证混音的削波保护
+#[test]
+fn test_mix_clipping_protection() {
+    let a_path = "edit_mix_clip_a_8k16bit单声道.pcm";
+    let b_path = "edit_mix_clip_b_8k16bit单声道.pcm";
+    let output_path = "edit_mix_clip_8k16bit单声道_out.pcm";
+    fs::write(a_path, to_bytes(&[30000, -30000, 100])).expect("Failed to write test PCM file");
+    fs::write(b_path, to_bytes(&[30000, -30000, 100])).expect("Failed to write test PCM file");
+
+    mix_files(a_path, b_path, output_path, &MixOptions::default()).expect("混音应该成功");
+    let output = read_pcm(output_path);
+    println!("削波保护结果: {:?}", output);
+    assert_eq!(output, vec![i16::MAX, i16::MIN, 200]);
+
+    for path in [a_path, b_path, output_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证交叉淡化拼接
+#[test]
+fn test_crossfade_concat() {
+    let paths = ["edit_concat_a_8k16bit单声道.pcm", "edit_concat_b_8k16bit单声道.pcm", "edit_concat_c_8k16bit单声道.pcm"];
+    let output_path = "edit_concat_output.wav";
+    // 三段各 0.5 秒，电平分别为 6000 / -6000 / 6000
+    for (i, path) in paths.iter().enumerate() {
+        let level = if i % 2 == 0 { 6000i16 } else { -6000 };
+        fs::write(path, to_bytes(&vec![level; 4000])).expect("Failed to write test PCM file");
+    }
+
+    crossfade_concat(&paths, output_path, 100).expect("交叉淡化拼接应该成功");
+    let audio = read_wav_file(output_path).expect("Failed to read WAV output");
+    let samples = audio.samples_i16();
+    println!("拼接后帧数: {}", audio.frames());
+    // 3 × 4000 帧，两处各重叠 800 帧
+    assert_eq!(audio.frames(), 3 * 4000 - 2 * 800);
+
+    // 重叠区间内电平平滑过渡，不出现突变
+    let max_step = samples.windows(2).map(|w| (w[0] as i32 - w[1] as i32).abs()).max().unwrap();
+    println!("相邻样本最大跳变: {}", max_step);
+    assert!(max_step < 100, "交叉淡化后不应该出现突变，实际最大跳变 {}", max_step);
+
+    // 不重叠时直接拼接
+    crossfade_concat(&paths, output_path, 0).expect("直接拼接应该成功");
+    assert_eq!(read_wav_file(output_path).unwrap().frames(), 3 * 4000);
+
+    assert!(crossfade_concat::<&str>(&[], output_path, 100).is_err(), "没有输入时应该返回错误");
+
+    for path in paths.iter().chain([&output_path]) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证按时间范围截取：输出的样本应与源文件对应区间逐字节一致
+#[test]
+fn test_extract_range() {
+    let input_path = "edit_extract_input.pcm";
+    let pcm_output = "edit_extract_output.pcm";
+    let wav_output = "edit_extract_output.wav";
+    let mp3_output = "edit_extract_output.mp3";
+
+    // 2 秒 8kHz 立体声，样本值递增便于校验偏移
+    let samples: Vec<i16> = (0..32000).map(|i| (i % 30000) as i16).collect();
+    fs::write(input_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+    let config = AudioConfig::new(8000, 2, 16);
+
+    extract_range(input_path, pcm_output, 500, 1250, config.clone()).expect("截取 PCM 应该成功");
+    let output = read_pcm(pcm_output);
+    println!("截取 PCM: {} 个样本", output.len());
+    assert_eq!(output, samples[8000..20000], "应该精确截取 500ms..1250ms 的帧");
+
+    extract_range(input_path, wav_output, 500, 1250, config.clone()).expect("截取 WAV 应该成功");
+    let audio = read_wav_file(wav_output).expect("Failed to read WAV output");
+    assert_eq!(audio.config.sample_rate, 8000);
+    assert_eq!(audio.config.channels, 2);
+    assert_eq!(audio.samples_i16(), samples[8000..20000]);
+
+    // 结束时间超出文件长度时截止到文件末尾
+    extract_range(input_path, mp3_output, 1500, 10_000, config.clone()).expect("截取 MP3 应该成功");
+    let mp3_data = fs::read(mp3_output).expect("Failed to read MP3 output");
+    println!("截取 MP3: {} 字节", mp3_data.len());
+    assert!(!mp3_data.is_empty());
+
+    assert!(extract_range(input_path, pcm_output, 1000, 1000, config.clone()).is_err(), "空区间应该报错");
+    assert!(extract_range(input_path, pcm_output, 3000, 4000, config.clone()).is_err(), "超出文件的区间应该报错");
+
+    // 换算为帧时乘法溢出的时间不会 panic：终点截止到文件末尾，起点超出文件
+    extract_range(input_path, pcm_output, 1500, u64::MAX, config.clone()).expect("截取到文件末尾应该成功");
+    assert_eq!(read_pcm(pcm_output), samples[24000..]);
+    assert!(extract_range(input_path, pcm_output, u64::MAX - 1, u64::MAX, config).is_err(), "超出文件的区间应该报错");
+
+    let error = extract_range(input_path, pcm_output, 0, 1000, AudioConfig::new(0, 2, 16)).expect_err("无效配置应该报错");
+    assert!(matches!(error.downcast_ref::<AudioError>(), Some(AudioError::InvalidConfig(_))), "{}", error);
+
+    for path in [input_path, pcm_output, wav_output, mp3_output] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证倒放：帧顺序反转，帧内的左右声道保持原位
+#[test]
+fn test_reverse_audio() {
+    let input_path = "edit_reverse_input.pcm";
+    let output_path = "edit_reverse_output.pcm";
+    // 立体声：左声道为正、右声道为负，末尾多出的半帧应被丢弃
+    fs::write(input_path, to_bytes(&[1, -1, 2, -2, 3, -3, 99])).expect("Failed to write test PCM file");
+
+    reverse_audio(input_path, output_path, AudioConfig::new(8000, 2, 16)).expect("倒放应该成功");
+    let output = read_pcm(output_path);
+    println!("倒放结果: {:?}", output);
+    assert_eq!(output, vec![3, -3, 2, -2, 1, -1]);
+
+    // 24 位样本按 3 字节整体移动
+    fs::write(input_path, [1u8, 2, 3, 4, 5, 6]).expect("Failed to write test PCM file");
+    reverse_audio(input_path, output_path, AudioConfig::new(8000, 1, 24)).expect("倒放应该成功");
+    assert_eq!(fs::read(output_path).expect("Failed to read PCM output"), vec![4, 5, 6, 1, 2, 3]);
+
+    let error = reverse_audio(input_path, output_path, AudioConfig::new(8000, 0, 16)).expect_err("0 声道应该报错");
+    assert!(matches!(error.downcast_ref::<AudioError>(), Some(AudioError::InvalidConfig(_))), "{}", error);
+
+    for path in [input_path, output_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证变速不变调：输出时长按倍率变化，声道数和采样率保持不变
+#[test]
+fn test_change_tempo() {
+    let pcm_path = "edit_tempo_input.pcm";
+    let input_path = "edit_tempo_input.wav";
+    let output_path = "edit_tempo_output.wav";
+
+    // 1 秒 16kHz 立体声正弦波
+    let samples: Vec<i16> = (0..16000)
+        .flat_map(|i| {
+            let s = ((2.0 * std::f32::consts::PI * 300.0 * i as f32 / 16000.0).sin() * 10000.0) as i16;
+            [s, s]
+        })
+        .collect();
+    fs::write(pcm_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+    trans_pcm_file_to_wav(pcm_path, input_path, Some(PcmToWavConfig::new(16000, 2, 16))).expect("WAV 转换应该成功");
+
+    for (factor, expected_frames) in [(1.25f32, 12800usize), (0.75, 21333)] {
+        change_tempo(input_path, output_path, factor).expect("变速应该成功");
+        let audio = read_wav_file(output_path).expect("Failed to read WAV output");
+        println!("倍率 {}: {} 帧", factor, audio.frames());
+        assert_eq!(audio.config.sample_rate, 16000);
+        assert_eq!(audio.config.channels, 2);
+        assert_eq!(audio.frames(), expected_frames);
+    }
+
+    assert!(change_tempo(input_path, output_path, 0.0).is_err(), "倍率必须为正数");
+    assert!(change_tempo(input_path, output_path, 0.001).is_err(), "倍率过小时输出过长，应该报错");
+
+    for path in [pcm_path, input_path, output_path] {
+        let _ = fs::remove_file(path);
+    }
+}
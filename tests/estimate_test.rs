@@ -0,0 +1,65 @@
+use audio_helper::{estimate_output_size, trans_pcm_file_to_wav, trans_pcm_file_to_mp3, PcmToWavConfig, Mp3Config, Mp3Bitrate, Mp3RateMode, AudioQuality, SampleFormat, VbrQuality};
+use std::fs;
+
+fn write_test_pcm(path: &str, frames: usize, channels: usize) -> u64 {
+    let bytes: Vec<u8> = (0..frames * channels)
+        .flat_map(|i| (((i * 97) % 20000) as i16 - 10000).to_le_bytes())
+        .collect();
+    fs::write(path, &bytes).expect("Failed to write test PCM file");
+    bytes.len() as u64
+}
+
+/// 验证 WAV 输出大小的估算与实际文件大小完全一致
+#[test]
+fn test_estimate_wav_size_is_exact() {
+    let input_path = "estimate_wav_input.pcm";
+    let output_path = "estimate_wav_output.wav";
+    let input_len = write_test_pcm(input_path, 12345, 2);
+
+    let configs = [
+        PcmToWavConfig::new(44100, 2, 16),
+        PcmToWavConfig::new(44100, 2, 16).with_force_extensible(true),
+        PcmToWavConfig::new(44100, 2, 16).with_target_channels(1),
+        PcmToWavConfig::new(22050, 2, 16).with_ima_adpcm(true),
+        PcmToWavConfig::new(8000, 2, 8).with_sample_format(SampleFormat::Ulaw),
+        PcmToWavConfig::new(48000, 1, 24),
+    ];
+    for config in configs {
+        let estimated = estimate_output_size(input_len, &config);
+        trans_pcm_file_to_wav(input_path, output_path, Some(config.clone())).expect("WAV 转换应该成功");
+        let actual = fs::metadata(output_path).expect("Failed to stat WAV output").len();
+        println!("📏 {:?}: 估算 {} / 实际 {}", config.sample_format, estimated, actual);
+        assert_eq!(estimated, actual);
+    }
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证 MP3 输出大小的估算：CBR 误差在几帧以内，VBR 给出合理的量级
+#[test]
+fn test_estimate_mp3_size() {
+    let input_path = "estimate_mp3_input.pcm";
+    let output_path = "estimate_mp3_output.mp3";
+    let input_len = write_test_pcm(input_path, 44100 * 2, 2);
+
+    for bitrate in [Mp3Bitrate::Kbps128, Mp3Bitrate::Kbps320] {
+        let config = Mp3Config::new(44100, 2, bitrate, AudioQuality::Medium);
+        let estimated = estimate_output_size(input_len, &config);
+        trans_pcm_file_to_mp3(input_path, output_path, Some(config)).expect("MP3 转换应该成功");
+        let actual = fs::metadata(output_path).expect("Failed to stat MP3 output").len();
+        println!("📏 MP3 CBR: 估算 {} / 实际 {}", estimated, actual);
+        assert!(estimated.abs_diff(actual) < 3 * 1045, "CBR 估算误差应在三帧以内");
+    }
+
+    // 16kHz 时使用 MPEG-2，192kbps 会被限制到 160kbps
+    let low_rate = Mp3Config::new(16000, 2, Mp3Bitrate::Kbps192, AudioQuality::Medium);
+    let low_rate_abr = low_rate.clone().with_rate_mode(Mp3RateMode::Abr(160));
+    let v5 = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium).with_rate_mode(Mp3RateMode::Vbr(VbrQuality::V5));
+    let v0 = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium).with_rate_mode(Mp3RateMode::Vbr(VbrQuality::V0));
+    assert_eq!(estimate_output_size(input_len, &low_rate), estimate_output_size(input_len, &low_rate_abr));
+    assert!(estimate_output_size(input_len, &v5) < estimate_output_size(input_len, &v0));
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
@@ -0,0 +1,450 @@
+use audio_helper::ffi::{
+    ah_encoder_close, ah_encoder_feed, ah_encoder_feed_samples, ah_encoder_finish, ah_encoder_open, audio_helper_free_buffer, auto_convert_audio_w, clear_last_error,
+    free_string, get_last_error, get_last_error_code, infer_config_from_filename, pcm_buffer_to_mp3_buffer, pcm_buffer_to_wav_buffer, pcm_samples_to_mp3_buffer,
+    pcm_samples_to_wav_buffer, pcm_to_mp3,
+    pcm_to_mp3_w, pcm_to_mp3_with_progress, pcm_to_wav, pcm_to_wav_w, pcm_to_wav_with_progress, probe_audio, CAudioFormat, CAudioInfo,
+    CConvertStage, CDetectedFormat, CErrorCode, CMp3Config, CPcmConfig, WChar,
+};
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::os::raw::{c_int, c_void};
+use std::path::Path;
+use std::ptr;
+
+/// 读取并释放当前线程的错误信息
+fn last_error() -> Option<String> {
+    let error = get_last_error();
+    if error.is_null() {
+        return None;
+    }
+    let message = unsafe { CStr::from_ptr(error) }.to_string_lossy().into_owned();
+    free_string(error);
+    Some(message)
+}
+
+/// 验证失败的 FFI 调用会记录按线程保存的错误信息
+#[test]
+fn test_last_error_per_thread() {
+    clear_last_error();
+    assert_eq!(last_error(), None);
+
+    let missing = CString::new("ffi_test_missing_16k16bit单声道.pcm").unwrap();
+    let output = CString::new("ffi_test_missing.wav").unwrap();
+    assert_eq!(pcm_to_wav(missing.as_ptr(), output.as_ptr(), ptr::null()), CErrorCode::FileNotFound);
+    let message = last_error().expect("失败后应有错误信息");
+    println!("❌ {}", message);
+    assert!(message.contains("does not exist"), "{}", message);
+    // 读取不会清除错误信息
+    assert_eq!(last_error(), Some(message));
+
+    // 其他线程看不到本线程的错误
+    std::thread::spawn(|| assert_eq!(last_error(), None)).join().unwrap();
+
+    // 成功的调用保留上一次的错误，新的失败覆盖它
+    let filename = CString::new("voice_16k16bit单声道.pcm").unwrap();
+    let mut config = CPcmConfig { sample_rate: 0, channels: 0, bits_per_sample: 0 };
+    assert_eq!(infer_config_from_filename(filename.as_ptr(), &mut config), CErrorCode::Ok);
+    assert_eq!((config.sample_rate, config.channels, config.bits_per_sample), (16000, 1, 16));
+    assert!(last_error().is_some());
+    assert_eq!(infer_config_from_filename(filename.as_ptr(), ptr::null_mut()), CErrorCode::InvalidArgument);
+    assert!(last_error().unwrap().contains("Null config pointer"));
+    assert_eq!(pcm_to_wav(ptr::null(), output.as_ptr(), ptr::null()), CErrorCode::InvalidArgument);
+    assert!(last_error().unwrap().contains("Null pointer"));
+
+    clear_last_error();
+    assert_eq!(last_error(), None);
+    println!("✅ FFI 错误信息测试通过");
+}
+
+/// 验证不同的失败原因返回不同的错误码
+#[test]
+fn test_error_codes() {
+    let dir = Path::new("ffi_error_code_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let input_path = dir.join("clip_16k16bit单声道.pcm");
+    let samples: Vec<u8> = (0..16000i32).flat_map(|i| (((i % 100) * 300 - 15000) as i16).to_le_bytes()).collect();
+    fs::write(&input_path, samples).expect("Failed to write test PCM file");
+    let c_path = |path: &Path| CString::new(path.to_str().unwrap()).unwrap();
+    let input = c_path(&input_path);
+
+    let mp3 = c_path(&dir.join("out.mp3"));
+    let good = CMp3Config { sample_rate: 16000, channels: 1, bitrate: 64, quality: 1 };
+    assert_eq!(pcm_to_mp3(input.as_ptr(), mp3.as_ptr(), &good), CErrorCode::Ok);
+
+    // C 结构体中无法表示的码率
+    let bad_bitrate = CMp3Config { bitrate: 100, ..good };
+    assert_eq!(pcm_to_mp3(input.as_ptr(), mp3.as_ptr(), &bad_bitrate), CErrorCode::UnsupportedConfig);
+    // 校验失败：16kHz 以 MPEG-2 编码，不支持 320kbps
+    let too_high = CMp3Config { bitrate: 320, ..good };
+    assert_eq!(pcm_to_mp3(input.as_ptr(), mp3.as_ptr(), &too_high), CErrorCode::UnsupportedConfig);
+    assert_eq!(get_last_error_code(), CErrorCode::UnsupportedConfig);
+    let wav = c_path(&dir.join("out.wav"));
+    let zero_channels = CPcmConfig { sample_rate: 16000, channels: 0, bits_per_sample: 16 };
+    assert_eq!(pcm_to_wav(input.as_ptr(), wav.as_ptr(), &zero_channels), CErrorCode::UnsupportedConfig);
+
+    // 输出目录不存在
+    let orphan = c_path(&dir.join("missing_dir").join("out.wav"));
+    assert_eq!(pcm_to_wav(input.as_ptr(), orphan.as_ptr(), ptr::null()), CErrorCode::FileNotFound);
+
+    // 路径不是有效的 UTF-8
+    let invalid_utf8 = CString::new(vec![0xff, 0xfe, b'.', b'p', b'c', b'm']).unwrap();
+    assert_eq!(pcm_to_wav(invalid_utf8.as_ptr(), wav.as_ptr(), ptr::null()), CErrorCode::InvalidPath);
+    println!("❌ {:?}: {:?}", get_last_error_code(), last_error());
+
+    // 扩展名不是 .pcm、内容是 WAV 文件：均为无效输入
+    let not_pcm_path = dir.join("clip.dat");
+    fs::copy(&input_path, &not_pcm_path).unwrap();
+    assert_eq!(pcm_to_wav(c_path(&not_pcm_path).as_ptr(), wav.as_ptr(), ptr::null()), CErrorCode::InvalidArgument);
+    assert_eq!(pcm_to_wav(input.as_ptr(), wav.as_ptr(), ptr::null()), CErrorCode::Ok);
+    let wav_as_pcm = dir.join("wav_16k16bit单声道.pcm");
+    fs::copy(dir.join("out.wav"), &wav_as_pcm).unwrap();
+    assert_eq!(pcm_to_wav(c_path(&wav_as_pcm).as_ptr(), wav.as_ptr(), ptr::null()), CErrorCode::InvalidArgument);
+    println!("❌ {:?}: {:?}", get_last_error_code(), last_error());
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ FFI 错误码测试通过");
+}
+
+/// 验证内存缓冲区转换与文件转换的结果一致
+#[test]
+fn test_buffer_conversion() {
+    let pcm: Vec<u8> = (0..16000i32).flat_map(|i| (((i % 80) * 400 - 16000) as i16).to_le_bytes()).collect();
+    let config = CPcmConfig { sample_rate: 16000, channels: 1, bits_per_sample: 16 };
+
+    let mut out: *mut u8 = ptr::null_mut();
+    let mut out_len = 0usize;
+    assert_eq!(pcm_buffer_to_wav_buffer(pcm.as_ptr(), pcm.len(), &config, &mut out, &mut out_len), CErrorCode::Ok);
+    let wav = unsafe { std::slice::from_raw_parts(out, out_len) }.to_vec();
+    audio_helper_free_buffer(out, out_len);
+    assert_eq!(&wav[..4], b"RIFF");
+    assert_eq!(wav.len(), pcm.len() + 44);
+    assert_eq!(&wav[44..], &pcm[..]);
+    let expected = audio_helper::trans_pcm_bytes_to_wav(&pcm, Some(audio_helper::PcmToWavConfig::new(16000, 1, 16))).unwrap();
+    assert_eq!(wav, expected);
+
+    let mp3_config = CMp3Config { sample_rate: 16000, channels: 1, bitrate: 64, quality: 1 };
+    assert_eq!(pcm_buffer_to_mp3_buffer(pcm.as_ptr(), pcm.len(), &mp3_config, &mut out, &mut out_len), CErrorCode::Ok);
+    assert!(out_len > 0);
+    let mp3 = unsafe { std::slice::from_raw_parts(out, out_len) }.to_vec();
+    audio_helper_free_buffer(out, out_len);
+    assert_eq!(mp3[0], 0xFF, "应以 MP3 帧同步字开头");
+    println!("🎵 WAV {} 字节, MP3 {} 字节", wav.len(), mp3.len());
+
+    // 失败时输出被重置为 NULL 和 0
+    let bad = CMp3Config { bitrate: 100, ..mp3_config };
+    assert_eq!(pcm_buffer_to_mp3_buffer(pcm.as_ptr(), pcm.len(), &bad, &mut out, &mut out_len), CErrorCode::UnsupportedConfig);
+    assert!(out.is_null());
+    assert_eq!(out_len, 0);
+    assert_eq!(pcm_buffer_to_wav_buffer(ptr::null(), 16, &config, &mut out, &mut out_len), CErrorCode::InvalidArgument);
+    assert_eq!(pcm_buffer_to_wav_buffer(pcm.as_ptr(), pcm.len(), &config, ptr::null_mut(), &mut out_len), CErrorCode::InvalidArgument);
+    // WAV 数据不能当作 PCM 输入
+    assert_ne!(pcm_buffer_to_wav_buffer(wav.as_ptr(), wav.len(), &config, &mut out, &mut out_len), CErrorCode::Ok);
+    assert!(out.is_null());
+
+    // 空缓冲区得到只有文件头的 WAV
+    assert_eq!(pcm_buffer_to_wav_buffer(ptr::null(), 0, &config, &mut out, &mut out_len), CErrorCode::Ok);
+    assert_eq!(out_len, 44);
+    audio_helper_free_buffer(out, out_len);
+    audio_helper_free_buffer(ptr::null_mut(), 0);
+    println!("✅ FFI 缓冲区转换测试通过");
+}
+
+/// 取出缓冲区转换的结果并释放
+fn take_buffer(out: *mut u8, out_len: usize) -> Vec<u8> {
+    let data = unsafe { std::slice::from_raw_parts(out, out_len) }.to_vec();
+    audio_helper_free_buffer(out, out_len);
+    data
+}
+
+/// 验证直接传入 int16_t 样本数组的结果与传入字节缓冲区相同
+#[test]
+fn test_sample_buffer_conversion() {
+    let samples: Vec<i16> = (0..32000i32).map(|i| ((i % 90) * 350 - 15750) as i16).collect();
+    let pcm: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    let (mut out, mut out_len) = (ptr::null_mut(), 0usize);
+
+    // 位深度字段被忽略，样本总是 16 位
+    let config = CPcmConfig { sample_rate: 16000, channels: 2, bits_per_sample: 24 };
+    assert_eq!(pcm_samples_to_wav_buffer(samples.as_ptr(), samples.len(), &config, &mut out, &mut out_len), CErrorCode::Ok);
+    let wav = take_buffer(out, out_len);
+    let config = CPcmConfig { bits_per_sample: 16, ..config };
+    assert_eq!(pcm_buffer_to_wav_buffer(pcm.as_ptr(), pcm.len(), &config, &mut out, &mut out_len), CErrorCode::Ok);
+    assert_eq!(wav, take_buffer(out, out_len));
+
+    let mp3_config = CMp3Config { sample_rate: 16000, channels: 2, bitrate: 64, quality: 1 };
+    assert_eq!(pcm_samples_to_mp3_buffer(samples.as_ptr(), samples.len(), &mp3_config, &mut out, &mut out_len), CErrorCode::Ok);
+    let mp3 = take_buffer(out, out_len);
+    assert_eq!(pcm_buffer_to_mp3_buffer(pcm.as_ptr(), pcm.len(), &mp3_config, &mut out, &mut out_len), CErrorCode::Ok);
+    assert_eq!(mp3, take_buffer(out, out_len));
+    println!("🎚️ 样本数组转换: WAV {} 字节, MP3 {} 字节，与字节缓冲区一致", wav.len(), mp3.len());
+
+    // 空指针、未对齐的指针和无效配置
+    assert_eq!(pcm_samples_to_mp3_buffer(ptr::null(), 16, &mp3_config, &mut out, &mut out_len), CErrorCode::InvalidArgument);
+    let misaligned = unsafe { pcm.as_ptr().add(1) } as *const i16;
+    assert_eq!(pcm_samples_to_wav_buffer(misaligned, 16, &config, &mut out, &mut out_len), CErrorCode::InvalidArgument);
+    assert!(last_error().unwrap().contains("aligned"));
+    let bad = CMp3Config { bitrate: 100, ..mp3_config };
+    assert_eq!(pcm_samples_to_mp3_buffer(samples.as_ptr(), samples.len(), &bad, &mut out, &mut out_len), CErrorCode::UnsupportedConfig);
+    assert!(out.is_null());
+    assert_eq!(pcm_samples_to_wav_buffer(ptr::null(), 0, &config, &mut out, &mut out_len), CErrorCode::Ok);
+    assert_eq!(take_buffer(out, out_len).len(), 44);
+
+    // 流式编码：交替送入样本和字节，块长度不是帧的整数倍，结果与只送入字节相同
+    let encode_stream = |mixed: bool| {
+        let handle = ah_encoder_open(&mp3_config);
+        assert!(!handle.is_null());
+        let mut stream = Vec::new();
+        let mut out = vec![0u8; 64 * 1024];
+        let mut start = 0;
+        for (i, len) in [1001usize, 3, 4096, 7, 2000].iter().cycle().enumerate() {
+            if start >= samples.len() {
+                break;
+            }
+            let end = (start + len).min(samples.len());
+            let written = if mixed && i % 2 == 0 {
+                ah_encoder_feed_samples(handle, samples[start..end].as_ptr(), end - start, out.as_mut_ptr(), out.len())
+            } else {
+                ah_encoder_feed(handle, pcm[start * 2..end * 2].as_ptr(), (end - start) * 2, out.as_mut_ptr(), out.len())
+            };
+            assert!(written >= 0, "第 {} 块编码失败: {:?}", i, last_error());
+            stream.extend_from_slice(&out[..written as usize]);
+            start = end;
+        }
+        let written = ah_encoder_finish(handle, out.as_mut_ptr(), out.len());
+        assert!(written >= 0);
+        stream.extend_from_slice(&out[..written as usize]);
+        ah_encoder_close(handle);
+        stream
+    };
+    let from_bytes = encode_stream(false);
+    let mixed = encode_stream(true);
+    assert!(!from_bytes.is_empty());
+    assert_eq!(mixed, from_bytes, "样本与字节交替送入的结果应该一致");
+
+    // 字节送入停在半个样本上时不能再送入样本
+    let handle = ah_encoder_open(&mp3_config);
+    let mut out = [0u8; 8192];
+    assert!(ah_encoder_feed(handle, pcm.as_ptr(), 3, out.as_mut_ptr(), out.len()) >= 0);
+    assert_eq!(ah_encoder_feed_samples(handle, samples.as_ptr(), 2, out.as_mut_ptr(), out.len()), CErrorCode::InvalidArgument as isize);
+    ah_encoder_close(handle);
+    assert_eq!(ah_encoder_feed_samples(ptr::null_mut(), ptr::null(), 0, ptr::null_mut(), 0), CErrorCode::InvalidArgument as isize);
+    println!("✅ FFI 样本数组接口测试通过");
+}
+
+/// 验证流式编码句柄逐块编码的结果与整体编码的帧数一致
+#[test]
+fn test_encoder_handle() {
+    let config = CMp3Config { sample_rate: 16000, channels: 2, bitrate: 64, quality: 1 };
+    let pcm: Vec<u8> = (0..32000i32).flat_map(|i| (((i % 90) * 350 - 15750) as i16).to_le_bytes()).collect();
+
+    let handle = ah_encoder_open(&config);
+    assert!(!handle.is_null());
+    let mut mp3 = Vec::new();
+    // 故意使用奇数块长度和很小的输出缓冲区，验证拼接和分次取出
+    let mut out = [0u8; 512];
+    for chunk in pcm.chunks(1001) {
+        let written = ah_encoder_feed(handle, chunk.as_ptr(), chunk.len(), out.as_mut_ptr(), out.len());
+        assert!(written >= 0, "编码失败: {:?}", last_error());
+        mp3.extend_from_slice(&out[..written as usize]);
+    }
+    loop {
+        let written = ah_encoder_feed(handle, ptr::null(), 0, out.as_mut_ptr(), out.len());
+        assert!(written >= 0);
+        if written == 0 {
+            break;
+        }
+        mp3.extend_from_slice(&out[..written as usize]);
+    }
+    loop {
+        let written = ah_encoder_finish(handle, out.as_mut_ptr(), out.len());
+        assert!(written >= 0);
+        if written == 0 {
+            break;
+        }
+        mp3.extend_from_slice(&out[..written as usize]);
+    }
+    // 结束后不能再送入数据
+    let written = ah_encoder_feed(handle, pcm.as_ptr(), 4, out.as_mut_ptr(), out.len());
+    assert_eq!(written, CErrorCode::InvalidArgument as isize);
+    ah_encoder_close(handle);
+    ah_encoder_close(ptr::null_mut());
+
+    assert_eq!(mp3[0], 0xFF, "应以 MP3 帧同步字开头");
+    let dir = Path::new("ffi_encoder_handle_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).unwrap();
+    let path = dir.join("stream.mp3");
+    fs::write(&path, &mp3).unwrap();
+    let duration = audio_helper::audio_duration(&path).unwrap();
+    println!("🎵 流式编码 {} 字节, 时长 {:?}", mp3.len(), duration);
+    assert!((duration.as_secs_f64() - 1.0).abs() < 0.1, "{:?}", duration);
+    fs::remove_dir_all(dir).unwrap();
+
+    // 非 MP3 标准采样率不做重采样，直接拒绝
+    let odd_rate = CMp3Config { sample_rate: 17000, ..config };
+    assert!(ah_encoder_open(&odd_rate).is_null());
+    assert_eq!(get_last_error_code(), CErrorCode::UnsupportedConfig);
+    assert_eq!(ah_encoder_feed(ptr::null_mut(), ptr::null(), 0, ptr::null_mut(), 0), CErrorCode::InvalidArgument as isize);
+    println!("✅ FFI 流式编码句柄测试通过");
+}
+
+/// 进度回调的调用记录，通过 user_data 传给回调
+#[derive(Default)]
+struct ProgressLog {
+    events: Vec<(CConvertStage, u64, u64)>,
+    cancel_after: Option<usize>,
+}
+
+extern "C" fn record_progress(stage: CConvertStage, bytes_processed: u64, total_bytes: u64, user_data: *mut c_void) -> c_int {
+    let log = unsafe { &mut *(user_data as *mut ProgressLog) };
+    log.events.push((stage, bytes_processed, total_bytes));
+    log.cancel_after.is_some_and(|limit| log.events.len() >= limit) as c_int
+}
+
+/// 验证 C 进度回调收到各阶段的进度，并能通过返回值取消转换
+#[test]
+fn test_progress_callback() {
+    let dir = Path::new("ffi_progress_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let input_path = dir.join("clip_16k16bit单声道.pcm");
+    let samples: Vec<u8> = (0..64000i32).flat_map(|i| (((i % 100) * 300 - 15000) as i16).to_le_bytes()).collect();
+    fs::write(&input_path, &samples).expect("Failed to write test PCM file");
+    let input = CString::new(input_path.to_str().unwrap()).unwrap();
+    let config = CMp3Config { sample_rate: 16000, channels: 1, bitrate: 64, quality: 1 };
+
+    let mp3_path = dir.join("out.mp3");
+    let mp3 = CString::new(mp3_path.to_str().unwrap()).unwrap();
+    let mut log = ProgressLog::default();
+    let user_data = &mut log as *mut ProgressLog as *mut c_void;
+    assert_eq!(pcm_to_mp3_with_progress(input.as_ptr(), mp3.as_ptr(), &config, Some(record_progress), user_data), CErrorCode::Ok);
+    println!("📈 MP3 进度事件 {} 个", log.events.len());
+    for stage in [CConvertStage::Encoding, CConvertStage::Writing] {
+        assert!(log.events.iter().any(|event| event.0 == stage), "缺少阶段 {:?}", stage);
+    }
+    let last_encoding = log.events.iter().rfind(|event| event.0 == CConvertStage::Encoding).unwrap();
+    assert_eq!(last_encoding.1, last_encoding.2);
+    assert_eq!(last_encoding.2, samples.len() as u64);
+
+    // 回调返回非 0 时取消，已有的输出文件保持不变
+    let before = fs::read(&mp3_path).unwrap();
+    let mut log = ProgressLog { cancel_after: Some(1), ..Default::default() };
+    let user_data = &mut log as *mut ProgressLog as *mut c_void;
+    let high = CMp3Config { bitrate: 128, ..config };
+    assert_eq!(pcm_to_mp3_with_progress(input.as_ptr(), mp3.as_ptr(), &high, Some(record_progress), user_data), CErrorCode::Cancelled);
+    assert_eq!(fs::read(&mp3_path).unwrap(), before);
+
+    // 回调为 NULL 时与 pcm_to_wav 相同
+    let wav = CString::new(dir.join("out.wav").to_str().unwrap()).unwrap();
+    let pcm_config = CPcmConfig { sample_rate: 16000, channels: 1, bits_per_sample: 16 };
+    assert_eq!(pcm_to_wav_with_progress(input.as_ptr(), wav.as_ptr(), &pcm_config, None, ptr::null_mut()), CErrorCode::Ok);
+    assert_eq!(fs::metadata(dir.join("out.wav")).unwrap().len(), samples.len() as u64 + 44);
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ FFI 进度回调测试通过");
+}
+
+/// 将路径编码为以 0 结尾的平台宽字符串
+fn wide(path: &Path) -> Vec<WChar> {
+    #[cfg(windows)]
+    let mut units: Vec<WChar> = std::os::windows::ffi::OsStrExt::encode_wide(path.as_os_str()).collect();
+    #[cfg(not(windows))]
+    let mut units: Vec<WChar> = path.to_str().unwrap().chars().map(|c| c as WChar).collect();
+    units.push(0);
+    units
+}
+
+/// 验证宽字符路径版本能处理非 ASCII 目录
+#[test]
+fn test_wide_path_variants() {
+    let dir = Path::new("ffi_wide_test_用户目录");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let input_path = dir.join("录音_16k16bit单声道.pcm");
+    let samples: Vec<u8> = (0..16000i32).flat_map(|i| (((i % 100) * 300 - 15000) as i16).to_le_bytes()).collect();
+    fs::write(&input_path, &samples).expect("Failed to write test PCM file");
+    let input = wide(&input_path);
+
+    let wav_path = dir.join("输出.wav");
+    let config = CPcmConfig { sample_rate: 16000, channels: 1, bits_per_sample: 16 };
+    assert_eq!(pcm_to_wav_w(input.as_ptr(), wide(&wav_path).as_ptr(), &config), CErrorCode::Ok);
+    assert_eq!(fs::metadata(&wav_path).unwrap().len(), samples.len() as u64 + 44);
+
+    let mp3_path = dir.join("输出.mp3");
+    assert_eq!(pcm_to_mp3_w(input.as_ptr(), wide(&mp3_path).as_ptr(), ptr::null()), CErrorCode::Ok);
+    assert!(mp3_path.exists());
+
+    // 从宽字符文件名推断 16kHz 单声道
+    let auto_path = dir.join("自动.wav");
+    assert_eq!(auto_convert_audio_w(input.as_ptr(), wide(&auto_path).as_ptr(), CAudioFormat::Wav), CErrorCode::Ok);
+    let audio = audio_helper::read_wav_file(&auto_path).unwrap();
+    assert_eq!((audio.config.sample_rate, audio.config.channels), (16000, 1));
+
+    assert_eq!(pcm_to_wav_w(ptr::null(), wide(&wav_path).as_ptr(), &config), CErrorCode::InvalidArgument);
+    #[cfg(not(windows))]
+    {
+        // 不是有效码点的宽字符
+        let invalid: Vec<WChar> = vec![0xD800, 0];
+        assert_eq!(pcm_to_wav_w(invalid.as_ptr(), wide(&wav_path).as_ptr(), &config), CErrorCode::InvalidPath);
+    }
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ FFI 宽字符路径测试通过");
+}
+
+/// 验证 probe_audio 填写 PCM、WAV 和 MP3 文件的信息
+#[test]
+fn test_probe_audio() {
+    let dir = Path::new("ffi_probe_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm_path = dir.join("clip_16k16bit单声道.pcm");
+    let samples: Vec<u8> = (0..16000i32).flat_map(|i| (((i % 100) * 300 - 15000) as i16).to_le_bytes()).collect();
+    fs::write(&pcm_path, &samples).expect("Failed to write test PCM file");
+    let c_path = |path: &Path| CString::new(path.to_str().unwrap()).unwrap();
+    let probe = |path: &Path| {
+        let mut info = CAudioInfo {
+            format: CDetectedFormat::Ogg,
+            sample_rate: 1,
+            channels: 1,
+            bits_per_sample: 1,
+            duration_ms: 1,
+            data_size: 1,
+            file_size: 1,
+        };
+        assert_eq!(probe_audio(c_path(path).as_ptr(), &mut info), CErrorCode::Ok, "{:?}", last_error());
+        println!("📋 {}: {:?}", path.display(), info);
+        info
+    };
+
+    let info = probe(&pcm_path);
+    assert_eq!(info.format, CDetectedFormat::Pcm);
+    assert_eq!((info.sample_rate, info.channels, info.bits_per_sample), (16000, 1, 16));
+    assert_eq!(info.duration_ms, 1000);
+    assert_eq!((info.data_size, info.file_size), (32000, 32000));
+
+    let wav_path = dir.join("clip.wav");
+    let config = CPcmConfig { sample_rate: 16000, channels: 1, bits_per_sample: 16 };
+    assert_eq!(pcm_to_wav(c_path(&pcm_path).as_ptr(), c_path(&wav_path).as_ptr(), &config), CErrorCode::Ok);
+    let info = probe(&wav_path);
+    assert_eq!(info.format, CDetectedFormat::Wav);
+    assert_eq!((info.sample_rate, info.channels, info.bits_per_sample), (16000, 1, 16));
+    assert_eq!(info.duration_ms, 1000);
+    assert_eq!((info.data_size, info.file_size), (32000, 32044));
+
+    let mp3_path = dir.join("clip.mp3");
+    let mp3_config = CMp3Config { sample_rate: 16000, channels: 1, bitrate: 64, quality: 1 };
+    assert_eq!(pcm_to_mp3(c_path(&pcm_path).as_ptr(), c_path(&mp3_path).as_ptr(), &mp3_config), CErrorCode::Ok);
+    let info = probe(&mp3_path);
+    assert_eq!(info.format, CDetectedFormat::Mp3);
+    assert_eq!((info.sample_rate, info.channels, info.bits_per_sample), (0, 0, 0));
+    assert!(info.duration_ms.abs_diff(1000) < 100, "{}", info.duration_ms);
+
+    let mut info = probe(&pcm_path);
+    assert_eq!(probe_audio(c_path(&dir.join("missing.wav")).as_ptr(), &mut info), CErrorCode::FileNotFound);
+    assert_eq!(probe_audio(c_path(&pcm_path).as_ptr(), ptr::null_mut()), CErrorCode::InvalidArgument);
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ FFI 文件信息测试通过");
+}
@@ -0,0 +1,163 @@
+use audio_helper::{
+    infer_audio_config_from_filename, infer_audio_config_with_confidence, ConfigInferencer, Endianness, InferenceRule, SampleFormat,
+};
+
+/// 验证常见设备和工具的命名方式都能推断出正确的参数
+#[test]
+fn test_device_naming_schemes() {
+    let cases = [
+        // ffmpeg -f s16le / f32le 导出
+        ("capture_f32le_48000_2ch.pcm", 48000, 2, SampleFormat::F32, Endianness::Little),
+        ("pcm_s16le_16000_mono.raw", 16000, 1, SampleFormat::S16, Endianness::Little),
+        ("dump_s32be_22050hz.pcm", 22050, 2, SampleFormat::S32, Endianness::Big),
+        // arecord -f S16_LE / S24_3LE
+        ("arecord_S16_LE_8000_1ch.raw", 8000, 1, SampleFormat::S16, Endianness::Little),
+        ("arecord-S24_3LE-44100Hz-ch2.pcm", 44100, 2, SampleFormat::S24, Endianness::Little),
+        // Android AudioRecord / iOS AVAudioRecorder
+        ("record_44100hz_mono_16bit.pcm", 44100, 1, SampleFormat::S16, Endianness::Little),
+        ("voice_sr16000_ch1.pcm", 16000, 1, SampleFormat::S16, Endianness::Little),
+        ("VoiceMemo_SR-24000_CH-1_S16.pcm", 24000, 1, SampleFormat::S16, Endianness::Little),
+        // 麦克风阵列和环绕声
+        ("mic_array_6ch_s24le_48k.pcm", 48000, 6, SampleFormat::S24, Endianness::Little),
+        ("surround_5.1_48kHz_s32le.pcm", 48000, 6, SampleFormat::S32, Endianness::Little),
+        ("atmos_bed_7.1_96k_24-bit.pcm", 96000, 8, SampleFormat::S24, Endianness::Little),
+        // 电话录音
+        ("call_8000_mulaw.raw", 8000, 2, SampleFormat::Ulaw, Endianness::Little),
+        ("pbx-alaw-8k-1ch.pcm", 8000, 1, SampleFormat::Alaw, Endianness::Little),
+        // 只有数字的采样率、u8 和浮点
+        ("asr_corpus_utt0001_16000.pcm", 16000, 2, SampleFormat::S16, Endianness::Little),
+        ("game_sfx_u8_11025.pcm", 11025, 2, SampleFormat::U8, Endianness::Little),
+        ("render_float_44.1k_stereo_big-endian.pcm", 44100, 2, SampleFormat::F32, Endianness::Big),
+        // 原有的写法
+        ("浪花一朵朵片段8k16bit单声道.pcm", 8000, 1, SampleFormat::S16, Endianness::Little),
+        ("audio_96k_2ch_24bit.pcm", 96000, 2, SampleFormat::S24, Endianness::Little),
+    ];
+
+    for (filename, sample_rate, channels, sample_format, endianness) in cases {
+        let config = infer_audio_config_from_filename(filename);
+        println!("📁 {} -> {}Hz {}ch {:?} {:?}", filename, config.sample_rate, config.channels, config.sample_format, config.endianness);
+        assert_eq!(config.sample_rate, sample_rate, "采样率推断错误: {}", filename);
+        assert_eq!(config.channels, channels, "声道数推断错误: {}", filename);
+        assert_eq!(config.sample_format, sample_format, "样本格式推断错误: {}", filename);
+        assert_eq!(config.bits_per_sample, sample_format.bits_per_sample(), "位深度与样本格式不一致: {}", filename);
+        assert_eq!(config.endianness, endianness, "字节序推断错误: {}", filename);
+    }
+    println!("✅ 设备命名方式推断测试通过");
+}
+
+/// 验证不会把码率、日期和编号误认为采样率
+#[test]
+fn test_numbers_that_are_not_sample_rates() {
+    for filename in ["podcast_128kbps.pcm", "meeting_20240315_1030.pcm", "take_0042.pcm", "track2048.pcm"] {
+        let config = infer_audio_config_from_filename(filename);
+        println!("📁 {} -> {}Hz", filename, config.sample_rate);
+        assert_eq!(config.sample_rate, 44100, "不应识别出采样率: {}", filename);
+    }
+    println!("✅ 非采样率数字测试通过");
+}
+
+/// 验证置信度、识别依据和备选配置
+#[test]
+fn test_inference_confidence() {
+    let complete = infer_audio_config_with_confidence("arecord-S24_3LE-44100Hz-ch2.pcm");
+    println!("📁 {:?}", complete);
+    assert_eq!(complete.config, infer_audio_config_from_filename("arecord-S24_3LE-44100Hz-ch2.pcm"));
+    assert_eq!(complete.confidence, 1.0);
+    assert_eq!(complete.matched_tokens, ["44100hz", "ch2", "s24_3le"]);
+    assert!(complete.alternatives.is_empty());
+
+    // 位深度没有写明字节序、采样率没有单位，置信度降低
+    let partial = infer_audio_config_with_confidence("utt_16000_mono_16bit.raw");
+    assert_eq!(partial.matched_tokens, ["16000", "mono", "16bit"]);
+    assert!(partial.confidence > 0.5 && partial.confidence < 1.0);
+
+    // 没有任何线索时使用默认值，并给出其他常见采样率和声道数
+    let unknown = infer_audio_config_with_confidence("recording.pcm");
+    println!("📁 recording.pcm -> 置信度 {:.2}，备选 {} 个", unknown.confidence, unknown.alternatives.len());
+    assert_eq!((unknown.config.sample_rate, unknown.config.channels), (44100, 2));
+    assert!(unknown.confidence < 0.5);
+    assert!(unknown.matched_tokens.is_empty());
+    assert!(unknown.alternatives.iter().any(|(config, _)| config.sample_rate == 48000));
+    assert!(unknown.alternatives.iter().any(|(config, _)| config.channels == 1));
+    assert!(unknown.alternatives.iter().all(|(_, confidence)| *confidence <= unknown.confidence));
+    assert!(unknown.alternatives.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+
+    // 只缺声道数时，备选只替换声道数
+    let no_channels = infer_audio_config_with_confidence("clip_48k_s16le.pcm");
+    assert_eq!(no_channels.alternatives.len(), 1);
+    assert_eq!((no_channels.alternatives[0].0.sample_rate, no_channels.alternatives[0].0.channels), (48000, 1));
+    println!("✅ 置信度推断测试通过");
+}
+
+/// 验证自定义推断规则及其与内置规则的优先级
+#[test]
+fn test_custom_inference_rules() {
+    let deva = InferenceRule::prefix("DEVA_").with_sample_rate(8000).with_channels(1).with_bits(16).unwrap();
+    let inferencer = ConfigInferencer::new().with_rule(deva.clone());
+
+    let inferred = inferencer.infer_with_confidence("DEVA_20240315_0001.pcm");
+    println!("📁 DEVA_20240315_0001.pcm -> {:?}", inferred);
+    assert_eq!((inferred.config.sample_rate, inferred.config.channels, inferred.config.sample_format), (8000, 1, SampleFormat::S16));
+    assert_eq!(inferred.matched_tokens, ["deva_"]);
+    assert!(inferred.alternatives.is_empty());
+
+    // 优先规则覆盖文件名中的内置写法，规则没有给出的项仍由内置规则识别
+    let config = inferencer.infer("deva_48k_s16be.pcm");
+    assert_eq!((config.sample_rate, config.channels, config.endianness), (8000, 1, Endianness::Big));
+    // 不匹配的文件名与内置推断相同
+    assert_eq!(inferencer.infer("music_48k_stereo.pcm"), infer_audio_config_from_filename("music_48k_stereo.pcm"));
+
+    // 后备规则只填补内置规则没有识别的项
+    let fallback = ConfigInferencer::new().with_fallback_rule(deva.clone());
+    let config = fallback.infer("DEVA_48k.pcm");
+    assert_eq!((config.sample_rate, config.channels), (48000, 1));
+
+    // 按添加顺序取第一个给出该项的规则
+    let field_recorder = InferenceRule::new(r"_field\d+").unwrap().with_sample_rate(96000).with_sample_format(SampleFormat::S24);
+    let config = ConfigInferencer::new().with_rule(field_recorder).with_rule(deva.clone()).infer("DEVA_field01.pcm");
+    assert_eq!((config.sample_rate, config.channels, config.sample_format), (96000, 1, SampleFormat::S24));
+
+    // 关闭内置规则后只使用自定义规则
+    let config = ConfigInferencer::new().without_builtin_rules().with_rule(deva).infer("other_48k_mono.pcm");
+    assert_eq!((config.sample_rate, config.channels), (44100, 2));
+
+    assert!(InferenceRule::new("(unclosed").is_err());
+    assert!(InferenceRule::contains("x").with_bits(12).is_err());
+    println!("✅ 自定义推断规则测试通过");
+}
+
+/// 验证日文、韩文、繁体中文的文件名和自定义声道关键词
+#[test]
+fn test_multilingual_and_custom_keywords() {
+    let cases = [
+        ("会議録音_48kHz_16ビット_モノラル.pcm", 48000, 1, SampleFormat::S16),
+        ("ライブ_44.1k_24ビット_ステレオ.pcm", 44100, 2, SampleFormat::S24),
+        ("회의_16k_16비트_모노.pcm", 16000, 1, SampleFormat::S16),
+        ("음악_48k_24비트_스테레오.pcm", 48000, 2, SampleFormat::S24),
+        ("訪談_16k_16位_單聲道.pcm", 16000, 1, SampleFormat::S16),
+        ("演唱會_48k_24位_立體聲.pcm", 48000, 2, SampleFormat::S24),
+    ];
+    for (filename, sample_rate, channels, sample_format) in cases {
+        let inferred = infer_audio_config_with_confidence(filename);
+        println!("📁 {} -> {:?}", filename, inferred.matched_tokens);
+        assert_eq!(inferred.config.sample_rate, sample_rate, "采样率推断错误: {}", filename);
+        assert_eq!(inferred.config.channels, channels, "声道数推断错误: {}", filename);
+        assert_eq!(inferred.config.sample_format, sample_format, "样本格式推断错误: {}", filename);
+    }
+
+    // 自定义同义词优先于内置关键词，不区分大小写
+    let inferencer = ConfigInferencer::new().with_channel_keyword("Zweikanal", 2).unwrap().with_channel_keyword("mono_mix", 2).unwrap();
+    assert_eq!(inferencer.infer("aufnahme_ZWEIKANAL_48k.pcm").channels, 2);
+    assert_eq!(inferencer.infer("drums_mono_mix_48k.pcm").channels, 2);
+    assert_eq!(inferencer.infer("voice_mono_48k.pcm").channels, 1);
+    // 内置规则不认识的词仍使用默认的立体声
+    assert_eq!(infer_audio_config_from_filename("einkanal.pcm").channels, 2);
+    let einkanal = ConfigInferencer::new().without_builtin_rules().with_channel_keyword("einkanal", 1).unwrap();
+    let inferred = einkanal.infer_with_confidence("EINKANAL_48k.pcm");
+    assert_eq!((inferred.config.channels, inferred.config.sample_rate), (1, 44100));
+    assert_eq!(inferred.matched_tokens, ["einkanal"]);
+
+    assert!(ConfigInferencer::new().with_channel_keyword(" ", 1).is_err());
+    assert!(ConfigInferencer::new().with_channel_keyword("leer", 0).is_err());
+    println!("✅ 多语言和自定义关键词测试通过");
+}
@@ -0,0 +1,35 @@
+use audio_helper::{generate_sine, generate_sine_file, probe_audio, read_wav_file, AudioConfig, DetectedFormat};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// 验证生成的正弦波按扩展名写入 WAV / MP3 / PCM 文件
+#[test]
+fn test_generate_sine_file() {
+    let dir = Path::new("generate_sine_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let config = AudioConfig::new(44100, 2, 16);
+    let duration = Duration::from_millis(500);
+    let pcm = generate_sine(1000.0, duration, &config);
+    assert_eq!(pcm.len(), 22050 * 2 * 2);
+
+    generate_sine_file(dir.join("tone.wav"), 1000.0, duration, &config).expect("写入 WAV 应该成功");
+    let wav = read_wav_file(dir.join("tone.wav")).unwrap();
+    assert_eq!(wav.config.sample_rate, 44100);
+    assert_eq!(wav.data, pcm);
+
+    generate_sine_file(dir.join("tone.pcm"), 1000.0, duration, &config).unwrap();
+    assert_eq!(fs::read(dir.join("tone.pcm")).unwrap(), pcm);
+
+    generate_sine_file(dir.join("tone.mp3"), 1000.0, duration, &config).expect("写入 MP3 应该成功");
+    assert_eq!(probe_audio(dir.join("tone.mp3")).unwrap().format, DetectedFormat::Mp3);
+    println!("🎼 1kHz 校准音: {} 字节 PCM", pcm.len());
+
+    // 超过奈奎斯特频率和不支持的扩展名
+    assert!(generate_sine_file(dir.join("alias.wav"), 30000.0, duration, &config).is_err());
+    assert!(generate_sine_file(dir.join("tone.flac"), 1000.0, duration, &config).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+}
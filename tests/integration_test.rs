@@ -1,4 +1,4 @@
-use audio_helper::{trans_pcm_file_to_wav, PcmToWavConfig};
+use audio_helper::{trans_pcm_file_to_wav, trans_pcm_file_to_mp3, trans_wav_file_to_pcm, trans_pcm_file_to_wav_with_options, trans_pcm_file_to_mp3_with_options, trans_wav_file_to_pcm_with_options, ConvertOptions, OverwritePolicy, auto_convert_pcm, probe_format, probe_audio, AudioFormat, DetectedFormat, WavContainer, Mp3Config, Mp3Bitrate, AudioQuality, PcmToWavConfig};
 use std::fs;
 use std::path::Path;
 
@@ -90,4 +90,186 @@ fn integration_test_different_configurations() {
     let _ = fs::remove_file(input_path);
     
     println!("Integration test passed: Different configurations work correctly");
-}
\ No newline at end of file
+}
+
+#[test]
+fn integration_test_path_types() {
+    use std::path::PathBuf;
+
+    let input_path = PathBuf::from("path_types_input.pcm");
+    let pcm_data: Vec<u8> = (0..400i16).flat_map(|i| (i * 50).to_le_bytes()).collect();
+    fs::write(&input_path, &pcm_data).expect("Failed to write test PCM file");
+
+    // PathBuf、&Path、String 都可以直接传入
+    let output_path = PathBuf::from("path_types_output.wav");
+    assert!(trans_pcm_file_to_wav(&input_path, &output_path, None).is_ok());
+    assert!(trans_pcm_file_to_wav(input_path.as_path(), String::from("path_types_output.wav"), None).is_ok());
+    let _ = fs::remove_file(&output_path);
+
+    // 非 UTF-8 文件名（仅 Unix 可构造）
+    #[cfg(unix)]
+    {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let odd_output = PathBuf::from(OsStr::from_bytes(b"path_types_\xff\xfe.wav"));
+        assert!(trans_pcm_file_to_wav(&input_path, &odd_output, None).is_ok());
+        assert!(odd_output.exists(), "非 UTF-8 路径的输出文件应该存在");
+        let _ = fs::remove_file(&odd_output);
+    }
+
+    let _ = fs::remove_file(&input_path);
+    println!("Integration test passed: AsRef<Path> parameters work correctly");
+}
+
+#[test]
+fn integration_test_probe_format_by_content() {
+    let pcm_path = "integration_probe.pcm";
+    let wav_path = "integration_probe.wav";
+    let mp3_path = "integration_probe.mp3";
+    let disguised_path = "integration_probe_disguised.pcm";
+    let samples: Vec<u8> = (0..8820i16).flat_map(|i| ((i % 100) * 200).to_le_bytes()).collect();
+    fs::write(pcm_path, &samples).expect("Failed to write test PCM file");
+
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(44100, 2, 16))).expect("WAV conversion should succeed");
+    trans_pcm_file_to_mp3(pcm_path, mp3_path, Some(Mp3Config::default())).expect("MP3 conversion should succeed");
+
+    assert_eq!(probe_format(pcm_path).unwrap(), DetectedFormat::Unknown);
+    assert_eq!(probe_format(wav_path).unwrap(), DetectedFormat::Wav(WavContainer::Riff));
+    assert_eq!(probe_format(mp3_path).unwrap(), DetectedFormat::Mp3);
+
+    // 扩展名为 .pcm 的 WAV 文件不能再被当作原始 PCM 转换
+    fs::copy(wav_path, disguised_path).expect("Failed to copy WAV file");
+    let result = trans_pcm_file_to_wav(disguised_path, "integration_probe_out.wav", None);
+    assert!(result.unwrap_err().to_string().contains("not raw PCM"));
+    assert!(trans_pcm_file_to_mp3(disguised_path, "integration_probe_out.mp3", None).is_err());
+
+    for path in [pcm_path, wav_path, mp3_path, disguised_path] {
+        let _ = fs::remove_file(path);
+    }
+    println!("Integration test passed: Format probing works correctly");
+}
+
+#[test]
+fn integration_test_conversion_report() {
+    let pcm_path = "integration_report_16k16bit单声道.pcm";
+    let wav_path = "integration_report.wav";
+    let mp3_path = "integration_report.mp3";
+    let round_trip_path = "integration_report_round_trip.pcm";
+    // 0.5 秒 16kHz 单声道
+    let samples: Vec<u8> = (0..8000i16).flat_map(|i| ((i % 100) * 200).to_le_bytes()).collect();
+    fs::write(pcm_path, &samples).expect("Failed to write test PCM file");
+
+    let wav = trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(16000, 1, 16))).expect("WAV conversion should succeed");
+    assert_eq!(wav.format, AudioFormat::Wav);
+    assert_eq!(wav.input_bytes, samples.len() as u64);
+    assert_eq!(wav.output_bytes, fs::metadata(wav_path).unwrap().len());
+    assert_eq!(wav.duration.as_millis(), 500);
+    assert_eq!((wav.config_used.sample_rate, wav.config_used.channels, wav.config_used.bits_per_sample), (16000, 1, 16));
+
+    let mp3 = trans_pcm_file_to_mp3(pcm_path, mp3_path, Some(Mp3Config::new(16000, 1, Mp3Bitrate::Kbps128, AudioQuality::Medium).with_target_channels(2))).expect("MP3 conversion should succeed");
+    assert_eq!(mp3.format, AudioFormat::Mp3);
+    assert_eq!(mp3.output_bytes, fs::metadata(mp3_path).unwrap().len());
+    assert_eq!(mp3.duration.as_millis(), 500);
+    assert_eq!(mp3.config_used.channels, 2, "实际编码参数应反映声道转换");
+
+    let pcm = trans_wav_file_to_pcm(wav_path, round_trip_path).expect("PCM conversion should succeed");
+    assert_eq!(pcm.format, AudioFormat::Pcm);
+    assert_eq!(pcm.output_bytes, samples.len() as u64);
+    assert_eq!(pcm.duration, wav.duration);
+    println!("Conversion reports: {:?} / {:?} / {:?}", wav, mp3, pcm);
+
+    // 输出为 PCM 时直接复制，输出与输入相同时报错
+    auto_convert_pcm(pcm_path, round_trip_path, AudioFormat::Pcm).expect("PCM copy should succeed");
+    assert_eq!(fs::read(round_trip_path).unwrap(), samples);
+    assert!(auto_convert_pcm(pcm_path, pcm_path, AudioFormat::Pcm).is_err());
+    assert_eq!(fs::read(pcm_path).unwrap(), samples);
+
+    for path in [pcm_path, wav_path, mp3_path, round_trip_path] {
+        let _ = fs::remove_file(path);
+    }
+    println!("Integration test passed: Conversion reports are accurate");
+}
+
+#[test]
+fn integration_test_overwrite_policy() {
+    let dir = Path::new("integration_overwrite");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm_path = dir.join("clip_16k16bit单声道.pcm");
+    let samples: Vec<u8> = (0..1600i16).flat_map(|i| (i * 10).to_le_bytes()).collect();
+    fs::write(&pcm_path, &samples).expect("Failed to write test PCM file");
+    let wav_path = dir.join("clip.wav");
+    let mp3_path = dir.join("clip.mp3");
+    fs::write(&wav_path, b"existing wav").unwrap();
+    fs::write(&mp3_path, b"existing mp3").unwrap();
+    let config = || Some(PcmToWavConfig::new(16000, 1, 16));
+
+    // Error：已有文件时报错且不修改
+    let err = trans_pcm_file_to_wav_with_options(&pcm_path, &wav_path, config(), ConvertOptions::new().with_overwrite(OverwritePolicy::Error)).unwrap_err();
+    assert!(err.to_string().contains("already exists"));
+    assert_eq!(fs::read(&wav_path).unwrap(), b"existing wav");
+
+    // Skip：跳过转换
+    let report = trans_pcm_file_to_mp3_with_options(&pcm_path, &mp3_path, None, ConvertOptions::new().with_overwrite(OverwritePolicy::Skip)).expect("跳过应该成功");
+    assert!(report.skipped);
+    assert_eq!(report.output_bytes, 12);
+    assert_eq!(fs::read(&mp3_path).unwrap(), b"existing mp3");
+
+    // RenameWithSuffix：写入第一个不存在的文件名
+    let options = || ConvertOptions::new().with_overwrite(OverwritePolicy::RenameWithSuffix);
+    let first = trans_pcm_file_to_wav_with_options(&pcm_path, &wav_path, config(), options()).expect("WAV 转换应该成功");
+    let second = trans_pcm_file_to_wav_with_options(&pcm_path, &wav_path, config(), options()).expect("WAV 转换应该成功");
+    println!("Renamed outputs: {} / {}", first.output_path.display(), second.output_path.display());
+    assert_eq!(first.output_path, dir.join("clip_1.wav"));
+    assert_eq!(second.output_path, dir.join("clip_2.wav"));
+    assert!(!first.skipped);
+    assert_eq!(fs::read(&wav_path).unwrap(), b"existing wav");
+
+    // 输出不存在时所有策略都正常转换，默认覆盖
+    let pcm_out = dir.join("round_trip.pcm");
+    let report = trans_wav_file_to_pcm_with_options(&first.output_path, &pcm_out, ConvertOptions::new().with_overwrite(OverwritePolicy::Error)).expect("PCM 转换应该成功");
+    assert_eq!(report.output_path, pcm_out);
+    let report = trans_pcm_file_to_wav(&pcm_path, &wav_path, config()).expect("WAV 转换应该成功");
+    assert_eq!(report.output_path, wav_path);
+    assert_eq!(fs::metadata(&wav_path).unwrap().len(), report.output_bytes);
+
+    let _ = fs::remove_dir_all(dir);
+    println!("Integration test passed: Overwrite policies work as expected");
+}
+
+#[test]
+fn integration_test_probe_audio() {
+    let pcm_path = "integration_probe_audio_8k16bit双声道.pcm";
+    let wav_path = "integration_probe_audio.wav";
+    let samples: Vec<u8> = (0..16000i16).flat_map(|i| (i % 300).to_le_bytes()).collect();
+    fs::write(pcm_path, &samples).expect("Failed to write test PCM file");
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(8000, 2, 16).with_container(WavContainer::Rf64))).expect("WAV conversion should succeed");
+
+    let info = probe_audio(wav_path).expect("Probe should succeed");
+    println!("Probed WAV: {:?}", info);
+    assert_eq!(info.format, DetectedFormat::Wav(WavContainer::Rf64));
+    assert_eq!(info.data_size, samples.len() as u64);
+    assert_eq!(info.file_size, fs::metadata(wav_path).unwrap().len());
+    let config = info.config.as_ref().expect("WAV should have a config");
+    assert_eq!((config.sample_rate, config.channels, config.bits_per_sample), (8000, 2, 16));
+    assert_eq!(info.duration.unwrap().as_millis(), 1000);
+    let ids: Vec<&str> = info.chunks.iter().map(|chunk| chunk.id.as_str()).collect();
+    assert_eq!(ids, ["ds64", "fmt ", "data"]);
+    let json: serde_json::Value = serde_json::from_str(&info.to_json()).expect("to_json should produce valid JSON");
+    assert_eq!(json["container"], "rf64");
+    assert_eq!(json["chunks"][2]["size"], samples.len() as u64);
+
+    // 原始 PCM 的参数从文件名推断
+    let info = probe_audio(pcm_path).expect("Probe should succeed");
+    assert_eq!(info.format, DetectedFormat::Unknown);
+    assert_eq!(info.config.unwrap().sample_rate, 8000);
+    assert_eq!(info.duration.unwrap().as_millis(), 1000);
+    assert!(info.chunks.is_empty());
+
+    assert!(probe_audio("integration_probe_missing.wav").is_err());
+
+    let _ = fs::remove_file(pcm_path);
+    let _ = fs::remove_file(wav_path);
+    println!("Integration test passed: probe_audio reports format, config and chunk layout");
+}
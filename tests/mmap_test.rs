@@ -0,0 +1,52 @@
+#![cfg(feature = "mmap")]
+
+use audio_helper::{
+    read_wav_file, trans_pcm_file_to_mp3_with_options, trans_pcm_file_to_wav, trans_wav_file_to_pcm, ConvertOptions, ConvertStage, Endianness,
+    Mp3Config, PcmToWavConfig, SampleFormat,
+};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// 验证映射输入文件时 PCM → WAV → PCM 往返无损，大端数据在映射上复制后翻转
+#[test]
+fn test_mapped_input_round_trip() {
+    let dir = Path::new("mmap_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm: Vec<u8> = (0..48000 * 2).flat_map(|i| (((i as f32 * 0.02).sin() * 9000.0) as i16).to_le_bytes()).collect();
+    let input = dir.join("input.pcm");
+    fs::write(&input, &pcm).expect("Failed to write test PCM file");
+
+    let wav_path = dir.join("output.wav");
+    let pcm_path = dir.join("output.pcm");
+    trans_pcm_file_to_wav(&input, &wav_path, Some(PcmToWavConfig::new(48000, 2, 16))).expect("WAV 转换应该成功");
+    let audio = read_wav_file(&wav_path).expect("读取 WAV 应该成功");
+    assert_eq!(audio.data, pcm);
+    trans_wav_file_to_pcm(&wav_path, &pcm_path).expect("WAV 解码应该成功");
+    assert_eq!(fs::read(&pcm_path).unwrap(), pcm);
+    println!("🗺️ 映射输入往返无损: {} 字节", pcm.len());
+
+    // 大端输入：映射是只读的，翻转字节序时不能修改原文件
+    let big_endian: Vec<u8> = pcm.chunks_exact(2).flat_map(|c| [c[1], c[0]]).collect();
+    fs::write(&input, &big_endian).unwrap();
+    let config = PcmToWavConfig::new(48000, 2, 16).with_endianness(Endianness::Big);
+    trans_pcm_file_to_wav(&input, &wav_path, Some(config)).expect("大端 WAV 转换应该成功");
+    assert_eq!(read_wav_file(&wav_path).unwrap().data, pcm);
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let options = ConvertOptions::new().with_progress(move |event| sink.lock().unwrap().push(event));
+    let config = Mp3Config::default().with_sample_format(SampleFormat::S16).with_endianness(Endianness::Big).with_normalize_db(-1.0);
+    trans_pcm_file_to_mp3_with_options(&input, dir.join("output.mp3"), Some(config), options).expect("大端 MP3 转换应该成功");
+    assert_eq!(fs::read(&input).unwrap(), big_endian, "输入文件不应被修改");
+    let reading: Vec<_> = events.lock().unwrap().iter().filter(|e| e.stage == ConvertStage::Reading).copied().collect();
+    assert_eq!(reading.last().map(|e| e.bytes_processed), Some(big_endian.len() as u64));
+
+    // 空文件也能映射
+    fs::write(&input, []).unwrap();
+    trans_pcm_file_to_wav(&input, &wav_path, Some(PcmToWavConfig::new(48000, 2, 16))).expect("空文件转换应该成功");
+    assert!(read_wav_file(&wav_path).unwrap().data.is_empty());
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,197 @@
+use audio_helper::{
+    concat_mp3, generate_sine, generate_white_noise, parse_mp3_info, probe_audio, probe_mp3, trans_pcm_bytes_to_mp3, AudioConfig, AudioQuality,
+    Mp3Bitrate, Mp3BitrateMode, Mp3Config, Mp3RateMode, VbrQuality,
+};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// 验证本库编码的 CBR / VBR 输出的帧统计和 LAME 标签
+#[test]
+fn test_mp3_info_of_encoded_output() {
+    let config = AudioConfig::new(44100, 2, 16);
+    let pcm = generate_sine(440.0, Duration::from_secs(2), &config);
+    let frames = (pcm.len() / 4) as u64;
+
+    let cbr = trans_pcm_bytes_to_mp3(&pcm, Some(Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::High))).unwrap();
+    let info = parse_mp3_info(&cbr).expect("应该能解析 CBR 输出");
+    println!("📏 CBR: {:?}", info);
+    assert_eq!((info.sample_rate, info.channels), (44100, 2));
+    assert_eq!(info.bitrate_mode, Mp3BitrateMode::Cbr);
+    assert_eq!(info.average_bitrate_kbps, 128);
+    assert!(info.has_xing, "编码器应该写入 Info 标签帧");
+    assert!(info.encoder_delay > 0);
+    assert_eq!(info.total_samples, info.frame_count * 1152);
+    assert!(info.audio_bytes < cbr.len() as u64 && info.audio_bytes + 418 >= cbr.len() as u64, "只有 Info 标签帧不计入音频字节");
+    // 扣除延迟和填充后不超过输入的帧数
+    assert!(info.samples() <= frames && frames - info.samples() < 2 * 1152, "解码 {} 帧，输入 {} 帧", info.samples(), frames);
+
+    let vbr_config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::High).with_rate_mode(Mp3RateMode::Vbr(VbrQuality::V2));
+    let noisy = [generate_silence_like(&config), generate_white_noise(Duration::from_secs(1), &config)].concat();
+    let vbr = trans_pcm_bytes_to_mp3(&noisy, Some(vbr_config)).unwrap();
+    let info = parse_mp3_info(&vbr).unwrap();
+    println!("📏 VBR: {} 帧，平均 {}kbps", info.frame_count, info.average_bitrate_kbps);
+    assert_eq!(info.bitrate_mode, Mp3BitrateMode::Vbr);
+    assert!(info.has_xing);
+
+    assert!(parse_mp3_info(&[0u8; 1024]).is_err());
+}
+
+/// 一秒接近静音的信号，VBR 编码时使用较低的比特率
+fn generate_silence_like(config: &AudioConfig) -> Vec<u8> {
+    generate_sine(100.0, Duration::from_secs(1), config).chunks_exact(2).flat_map(|b| (i16::from_le_bytes([b[0], b[1]]) / 256).to_le_bytes()).collect()
+}
+
+/// 验证文件接口和 probe_audio 中的 MP3 信息
+#[test]
+fn test_probe_mp3_file() {
+    let dir = Path::new("mp3_info_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let config = AudioConfig::new(16000, 1, 16);
+    let path = dir.join("tone.mp3");
+    let mp3 = trans_pcm_bytes_to_mp3(&generate_sine(440.0, Duration::from_secs(1), &config), Some(Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::Medium))).unwrap();
+    // 带 ID3v2 标签的文件
+    let mut tagged = b"ID3\x04\x00\x00\x00\x00\x00\x10".to_vec();
+    tagged.extend_from_slice(&[0; 16]);
+    tagged.extend_from_slice(&mp3);
+    fs::write(&path, &tagged).unwrap();
+
+    let info = probe_mp3(&path).expect("probe_mp3 应该成功");
+    assert_eq!(info, parse_mp3_info(&mp3).unwrap());
+    assert_eq!(info.total_samples, info.frame_count * 576, "MPEG-2 每帧 576 个采样");
+    assert_eq!(info.average_bitrate_kbps, 64);
+
+    let probed = probe_audio(&path).unwrap();
+    assert_eq!(probed.mp3.as_ref(), Some(&info));
+    assert_eq!(probed.duration, Some(info.duration));
+    assert!(probed.to_json().contains("\"bitrate_mode\": \"cbr\""));
+
+    assert!(probe_mp3(dir.join("missing.mp3")).is_err());
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 验证无缝编码在 LAME 标签中记录的延迟和填充与输入长度一致（单线程和多线程）
+#[test]
+fn test_gapless_padding() {
+    let config = AudioConfig::new(44100, 2, 16);
+    let pcm = generate_sine(440.0, Duration::from_millis(30_700), &config);
+    let frames = (pcm.len() / 4) as u64;
+    for threads in [1, 4] {
+        let mp3_config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium).with_threads(threads);
+        let info = parse_mp3_info(&trans_pcm_bytes_to_mp3(&pcm, Some(mp3_config.clone())).unwrap()).unwrap();
+        assert_eq!(info.padding, 0, "默认不记录末尾填充");
+        assert!(info.samples() < frames);
+
+        let info = parse_mp3_info(&trans_pcm_bytes_to_mp3(&pcm, Some(mp3_config.with_gapless(true))).unwrap()).unwrap();
+        assert_eq!(info.samples(), frames, "{} 线程：延迟 {}，填充 {}", threads, info.encoder_delay, info.padding);
+        assert_eq!(info.encoder_delay + frames + info.padding, info.total_samples);
+        println!("🎚️ {} 线程无缝编码：延迟 {}，填充 {}", threads, info.encoder_delay, info.padding);
+    }
+}
+
+/// 验证分段 MP3 按帧合并后的帧数、标签和时长
+#[test]
+fn test_concat_mp3() {
+    let paths = ["concat_part1.mp3", "concat_part2.mp3", "concat_part3.mp3", "concat_other_rate.mp3"];
+    let output_path = "concat_merged.mp3";
+    let config = AudioConfig::new(44100, 2, 16);
+    let encode = |seconds: u64, bitrate: Mp3Bitrate| {
+        let pcm = generate_sine(440.0, Duration::from_secs(seconds), &config);
+        trans_pcm_bytes_to_mp3(&pcm, Some(Mp3Config::new(44100, 2, bitrate, AudioQuality::High))).unwrap()
+    };
+
+    // 第一段带 ID3v2 和 ID3v1 标签，第二段带 ID3v2 标签
+    let mut id3v2 = b"ID3\x03\x00\x00\x00\x00\x00\x14".to_vec();
+    id3v2.extend_from_slice(&[0u8; 20]);
+    let mut id3v1 = b"TAG".to_vec();
+    id3v1.resize(128, b' ');
+    let parts = [
+        [&id3v2[..], &encode(2, Mp3Bitrate::Kbps128), &id3v1].concat(),
+        [&id3v2[..], &encode(1, Mp3Bitrate::Kbps128)].concat(),
+        encode(1, Mp3Bitrate::Kbps192),
+    ];
+    for (path, data) in paths.iter().zip(&parts) {
+        fs::write(path, data).unwrap();
+    }
+    let infos: Vec<_> = parts.iter().map(|data| parse_mp3_info(data).unwrap()).collect();
+
+    let info = concat_mp3(&paths[..3], output_path, true).expect("合并应该成功");
+    println!("🔗 合并后 {} 帧，时长 {:?}", info.frame_count, info.duration);
+    assert_eq!(info.frame_count, infos.iter().map(|i| i.frame_count).sum::<u64>(), "所有音频帧都应该保留");
+    assert!(info.has_xing, "应该保留第一段的标签帧");
+    assert_eq!(info.bitrate_mode, Mp3BitrateMode::Vbr);
+    assert_eq!((info.encoder_delay, info.padding), (infos[0].encoder_delay, infos[2].padding));
+
+    let merged = fs::read(output_path).unwrap();
+    assert!(merged.starts_with(&id3v2), "第一段的 ID3v2 标签应该保留在开头");
+    assert!(merged.ends_with(&id3v1), "第一段的 ID3v1 标签应该移到末尾");
+    assert_eq!(merged.windows(3).filter(|w| w == b"ID3").count(), 1, "其余片段的 ID3 标签应该被去掉");
+    // 标签帧中的帧数和字节数按合并结果重写，比特率不同时标记为 Xing
+    let tag = &merged[id3v2.len()..];
+    assert_eq!(&tag[36..40], b"Xing");
+    assert_eq!(u32::from_be_bytes(tag[44..48].try_into().unwrap()) as u64, info.frame_count);
+    assert_eq!(u32::from_be_bytes(tag[48..52].try_into().unwrap()) as usize, merged.len() - id3v2.len() - id3v1.len());
+
+    // 采样率不同
+    let pcm = generate_sine(440.0, Duration::from_secs(1), &AudioConfig::new(22050, 2, 16));
+    fs::write(paths[3], trans_pcm_bytes_to_mp3(&pcm, Some(Mp3Config::new(22050, 2, Mp3Bitrate::Kbps64, AudioQuality::High))).unwrap()).unwrap();
+    let result = concat_mp3(&[paths[0], paths[3]], output_path, true);
+    assert!(result.is_err(), "采样率不同时应该报错");
+    println!("✓ 预期的错误: {}", result.unwrap_err());
+    assert!(concat_mp3(&[paths[0], paths[3]], output_path, false).is_ok(), "不检查参数时应该直接拼接");
+
+    assert!(concat_mp3::<&str>(&[], output_path, true).is_err());
+    assert!(concat_mp3(&["concat_missing.mp3"], output_path, true).is_err());
+    for path in paths.iter().chain([&output_path]) {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 去掉 LAME 写在开头的 Info 标签帧（44.1kHz 立体声 MPEG-1 Layer III），得到不带标签的码流
+fn strip_tag_frame(mp3: &[u8]) -> Vec<u8> {
+    const BITRATES: [usize; 15] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+    assert_eq!(&mp3[36..40], b"Info", "第一帧应该是 Info 标签帧");
+    let size = 144_000 * BITRATES[(mp3[2] >> 4) as usize] / 44100 + ((mp3[2] >> 1) & 1) as usize;
+    mp3[size..].to_vec()
+}
+
+/// 验证第一段没有标签帧时，只要后续片段带有标签，仍按合并结果构造 Xing/Info 标签
+#[test]
+fn test_concat_mp3_tagless_then_tagged() {
+    let paths = ["concat_tagless.mp3", "concat_tagged.mp3", "concat_tagged_vbr.mp3"];
+    let output_path = "concat_tagless_merged.mp3";
+    let config = AudioConfig::new(44100, 2, 16);
+    let pcm = generate_sine(440.0, Duration::from_secs(1), &config);
+    let cbr = trans_pcm_bytes_to_mp3(&pcm, Some(Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::High))).unwrap();
+    let vbr_config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::High).with_rate_mode(Mp3RateMode::Vbr(VbrQuality::V2));
+    let vbr = trans_pcm_bytes_to_mp3(&[generate_silence_like(&config), pcm.clone()].concat(), Some(vbr_config)).unwrap();
+    let tagless = strip_tag_frame(&cbr);
+    assert!(!parse_mp3_info(&tagless).unwrap().has_xing);
+    fs::write(paths[0], &tagless).unwrap();
+    fs::write(paths[1], &cbr).unwrap();
+    fs::write(paths[2], &vbr).unwrap();
+
+    for (second, expected_id) in [(paths[1], b"Info"), (paths[2], b"Xing")] {
+        let info = concat_mp3(&[paths[0], second], output_path, true).expect("合并应该成功");
+        let expected_frames = parse_mp3_info(&tagless).unwrap().frame_count + parse_mp3_info(&fs::read(second).unwrap()).unwrap().frame_count;
+        println!("🔗 无标签 + {}: {} 帧，{:?}", second, info.frame_count, info.bitrate_mode);
+        assert!(info.has_xing, "合并结果应该带有标签帧");
+        assert_eq!(info.frame_count, expected_frames, "所有音频帧都应该保留");
+
+        let merged = fs::read(output_path).unwrap();
+        assert_eq!(&merged[36..40], expected_id);
+        assert_eq!(u32::from_be_bytes(merged[44..48].try_into().unwrap()) as u64, info.frame_count);
+        assert_eq!(u32::from_be_bytes(merged[48..52].try_into().unwrap()) as usize, merged.len());
+    }
+
+    // 两段都没有标签且比特率相同时原样拼接
+    let info = concat_mp3(&[paths[0], paths[0]], output_path, true).unwrap();
+    assert!(!info.has_xing);
+    assert_eq!(fs::read(output_path).unwrap(), [&tagless[..], &tagless].concat());
+
+    for path in paths.iter().chain([&output_path]) {
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,242 @@
+use audio_helper::{trans_pcm_file_to_mp3, trans_pcm_bytes_to_mp3, resample_i16, is_mp3_sample_rate, nearest_mp3_sample_rate, Mp3Config, Mp3Bitrate, Mp3RateMode, Mp3ChannelMode, VbrQuality, AudioQuality, SampleFormat, Endianness, audio_duration, generate_sine, AudioConfig};
+use std::fs;
+use std::time::Duration;
+
+/// 生成 440Hz 正弦波 PCM 数据（16 位小端）
+fn sine_pcm(sample_rate: u32, channels: u8, seconds: f32) -> Vec<u8> {
+    generate_sine(440.0, Duration::from_secs_f32(seconds), &AudioConfig::new(sample_rate, channels, 16))
+}
+
+/// 在 MP3 第一帧中查找 Xing/Info 标签，返回 (帧数, 字节数)
+fn read_xing_tag(mp3: &[u8]) -> Option<(u32, u32)> {
+    let pos = mp3.windows(4).take(200).position(|w| w == b"Xing" || w == b"Info")?;
+    let be = |at: usize| u32::from_be_bytes(mp3[at..at + 4].try_into().unwrap());
+    let flags = be(pos + 4);
+    if flags & 0x3 != 0x3 {
+        return None;
+    }
+    Some((be(pos + 8), be(pos + 12)))
+}
+
+/// 验证生成的 MP3 带有正确的 Xing/LAME 头
+#[test]
+fn test_mp3_has_xing_header() {
+    let input_path = "mp3_xing_test_input.pcm";
+    let output_path = "mp3_xing_test_output.mp3";
+    fs::write(input_path, sine_pcm(44100, 2, 2.0)).expect("Failed to write test PCM file");
+
+    let config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium);
+    let result = trans_pcm_file_to_mp3(input_path, output_path, Some(config));
+    assert!(result.is_ok(), "PCM 到 MP3 转换应该成功: {:?}", result.err());
+
+    let mp3 = fs::read(output_path).expect("Failed to read MP3 output");
+    let (frames, bytes) = read_xing_tag(&mp3).expect("MP3 应该包含 Xing/Info 标签");
+    println!("Xing 标签: {} 帧, {} 字节", frames, bytes);
+
+    // 2 秒 44.1kHz 音频约 77 帧（每帧 1152 个采样）
+    assert!((70..=90).contains(&frames), "帧数不合理: {}", frames);
+    assert_eq!(bytes as usize, mp3.len(), "标签中的字节数应该等于文件大小");
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证 VBR / ABR 码率模式
+#[test]
+fn test_mp3_rate_modes() {
+    let input_path = "mp3_rate_mode_input.pcm";
+    fs::write(input_path, sine_pcm(44100, 1, 3.0)).expect("Failed to write test PCM file");
+
+    let base = Mp3Config::new(44100, 1, Mp3Bitrate::Kbps320, AudioQuality::Medium);
+    let cases = vec![
+        ("cbr", base.clone()),
+        ("abr", base.clone().with_rate_mode(Mp3RateMode::Abr(96))),
+        ("vbr", base.clone().with_rate_mode(Mp3RateMode::Vbr(VbrQuality::V9))),
+    ];
+
+    let mut sizes = Vec::new();
+    for (name, config) in cases {
+        let output_path = format!("mp3_rate_mode_{}.mp3", name);
+        println!("🎚️  {}: {}", name, config.rate_mode);
+        trans_pcm_file_to_mp3(input_path, &output_path, Some(config)).expect("转换应该成功");
+
+        let mp3 = fs::read(&output_path).expect("Failed to read MP3 output");
+        assert!(read_xing_tag(&mp3).is_some(), "{} 输出应该包含 Xing/Info 标签", name);
+        sizes.push(mp3.len());
+        let _ = fs::remove_file(&output_path);
+    }
+
+    assert!(sizes[1] < sizes[0], "ABR 96kbps 应该比 CBR 320kbps 小");
+    assert!(sizes[2] < sizes[0], "VBR V9 应该比 CBR 320kbps 小");
+
+    // 超出范围的 ABR 比特率应该报错
+    let bad = base.with_rate_mode(Mp3RateMode::Abr(1000));
+    assert!(trans_pcm_file_to_mp3(input_path, "mp3_rate_mode_bad.mp3", Some(bad)).is_err());
+    let _ = fs::remove_file("mp3_rate_mode_bad.mp3");
+
+    assert_eq!(VbrQuality::from_level(4), Some(VbrQuality::V4));
+    assert_eq!(VbrQuality::from_level(10), None);
+
+    let _ = fs::remove_file(input_path);
+}
+
+/// 读取第二个 MPEG-1 Layer III 帧（第一帧为 Xing/Info 标签帧）帧头中的声道模式
+/// （0=立体声, 1=联合立体声, 2=双声道, 3=单声道）
+fn audio_frame_channel_mode(mp3: &[u8]) -> Option<u8> {
+    const BITRATES: [u32; 15] = [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+    const SAMPLE_RATES: [u32; 3] = [44100, 48000, 32000];
+
+    let header = mp3.get(0..4)?;
+    let bitrate = BITRATES[(header[2] >> 4) as usize];
+    let sample_rate = SAMPLE_RATES[((header[2] >> 2) & 0x3) as usize];
+    let padding = ((header[2] >> 1) & 0x1) as u32;
+    let frame_len = (144_000 * bitrate / sample_rate + padding) as usize;
+
+    let next = mp3.get(frame_len..frame_len + 4)?;
+    assert!(next[0] == 0xFF && next[1] & 0xE0 == 0xE0, "第二帧帧头应该是有效的同步字");
+    Some(next[3] >> 6)
+}
+
+/// 验证 MP3 声道模式配置
+#[test]
+fn test_mp3_channel_modes() {
+    let input_path = "mp3_channel_mode_input.pcm";
+    fs::write(input_path, sine_pcm(44100, 2, 1.0)).expect("Failed to write test PCM file");
+
+    let base = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps192, AudioQuality::Medium);
+    let cases = vec![
+        (Mp3ChannelMode::SimpleStereo, 0),
+        (Mp3ChannelMode::Mono, 3),
+    ];
+
+    for (mode, expected) in cases {
+        let output_path = format!("mp3_channel_mode_{:?}.mp3", mode);
+        let config = base.clone().with_channel_mode(mode);
+        trans_pcm_file_to_mp3(input_path, &output_path, Some(config)).expect("转换应该成功");
+
+        let mp3 = fs::read(&output_path).expect("Failed to read MP3 output");
+        assert_eq!(audio_frame_channel_mode(&mp3), Some(expected), "{:?} 帧头声道模式不正确", mode);
+        let _ = fs::remove_file(&output_path);
+    }
+
+    // 单声道输入不能使用立体声模式
+    let mono = Mp3Config::new(44100, 1, Mp3Bitrate::Kbps128, AudioQuality::Medium)
+        .with_channel_mode(Mp3ChannelMode::JointStereo);
+    assert!(trans_pcm_file_to_mp3(input_path, "mp3_channel_mode_bad.mp3", Some(mono)).is_err());
+    let _ = fs::remove_file("mp3_channel_mode_bad.mp3");
+
+    let _ = fs::remove_file(input_path);
+}
+
+/// 验证不支持的采样率会被自动重采样
+#[test]
+fn test_mp3_automatic_resampling() {
+    assert!(is_mp3_sample_rate(44100));
+    assert!(!is_mp3_sample_rate(96000));
+    assert_eq!(nearest_mp3_sample_rate(96000), 48000);
+    assert_eq!(nearest_mp3_sample_rate(7000), 8000);
+    assert_eq!(nearest_mp3_sample_rate(44000), 44100);
+
+    let input_path = "mp3_resample_input.pcm";
+    let output_path = "mp3_resample_output.mp3";
+    fs::write(input_path, sine_pcm(96000, 2, 1.0)).expect("Failed to write test PCM file");
+
+    let config = Mp3Config::new(96000, 2, Mp3Bitrate::Kbps192, AudioQuality::Medium);
+    let used = trans_pcm_file_to_mp3(input_path, output_path, Some(config.clone())).expect("重采样后转换应该成功");
+    println!("🔁 实际使用采样率: {}Hz", used.config_used.sample_rate);
+    assert_eq!(used.config_used.sample_rate, 48000);
+
+    // 关闭重采样时应该报错
+    let disabled = config.with_resample(false);
+    assert!(trans_pcm_file_to_mp3(input_path, output_path, Some(disabled)).is_err());
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证重采样保持时长和幅度
+#[test]
+fn test_resample_preserves_signal() {
+    let samples: Vec<i16> = sine_pcm(96000, 1, 0.5)
+        .chunks_exact(2)
+        .map(|c| i16::from_le_bytes([c[0], c[1]]))
+        .collect();
+
+    let resampled = resample_i16(&samples, 1, 96000, 48000);
+    assert_eq!(resampled.len(), samples.len() / 2);
+
+    // 440Hz 正弦波幅度（满幅的一半）应该基本保持不变（忽略边缘）
+    let peak = resampled[100..resampled.len() - 100].iter().map(|s| s.unsigned_abs()).max().unwrap();
+    assert!((15800..=16900).contains(&peak), "重采样后峰值异常: {}", peak);
+}
+
+/// 验证分块流式编码的结果与整体编码一致（含大端 24 位和末尾不完整的帧）
+#[test]
+fn test_streaming_matches_in_memory_encoding() {
+    let input_path = "mp3_stream_test_input.pcm";
+    let output_path = "mp3_stream_test_output.mp3";
+
+    // 5 秒立体声，远大于单次读取的块大小
+    let pcm = sine_pcm(44100, 2, 5.0);
+    fs::write(input_path, &pcm).expect("Failed to write test PCM file");
+    let config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium);
+    let report = trans_pcm_file_to_mp3(input_path, output_path, Some(config.clone())).expect("流式编码应该成功");
+    let streamed = fs::read(output_path).unwrap();
+    println!("🌊 流式编码: {} 字节, 时长 {:?}", streamed.len(), report.duration);
+    assert_eq!(streamed, trans_pcm_bytes_to_mp3(&pcm, Some(config)).unwrap());
+    assert_eq!(report.output_bytes, streamed.len() as u64);
+    assert_eq!(report.duration.as_millis(), 5000);
+
+    // 大端 24 位，末尾多出半帧
+    let mut pcm: Vec<u8> = pcm.chunks_exact(2).flat_map(|c| {
+        let sample = i16::from_le_bytes([c[0], c[1]]) as i32 * 256;
+        sample.to_be_bytes()[1..].to_vec()
+    }).collect();
+    pcm.extend_from_slice(&[0x12, 0x34, 0x56]);
+    fs::write(input_path, &pcm).expect("Failed to write test PCM file");
+    let config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium)
+        .with_sample_format(SampleFormat::S24)
+        .with_endianness(Endianness::Big);
+    trans_pcm_file_to_mp3(input_path, output_path, Some(config.clone())).expect("大端 24 位流式编码应该成功");
+    assert_eq!(fs::read(output_path).unwrap(), trans_pcm_bytes_to_mp3(&pcm, Some(config)).unwrap());
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证多线程分段编码：帧数、Xing 标签和时长与单线程编码一致
+#[test]
+fn test_multithreaded_encoding() {
+    // 30 秒单声道，足够分为两段
+    let pcm = sine_pcm(44100, 1, 30.0);
+    let config = Mp3Config::new(44100, 1, Mp3Bitrate::Kbps128, AudioQuality::Medium);
+    let single = trans_pcm_bytes_to_mp3(&pcm, Some(config.clone())).expect("单线程编码应该成功");
+    let threaded = trans_pcm_bytes_to_mp3(&pcm, Some(config.with_threads(2))).expect("多线程编码应该成功");
+    println!("🧵 单线程 {} 字节, 多线程 {} 字节", single.len(), threaded.len());
+
+    // 固定码率下帧数相同，文件大小也相同
+    assert_eq!(threaded.len(), single.len());
+    let (frames, bytes) = read_xing_tag(&threaded).expect("多线程编码应带有 Xing/Info 标签");
+    assert_eq!(Some((frames, bytes)), read_xing_tag(&single));
+    assert_eq!(bytes as usize, threaded.len());
+
+    let input_path = "mp3_threads_test_input.pcm";
+    let output_path = "mp3_threads_test_output.mp3";
+    fs::write(input_path, &pcm).expect("Failed to write test PCM file");
+    let config = Mp3Config::new(44100, 1, Mp3Bitrate::Kbps128, AudioQuality::Medium).with_threads(0);
+    let report = trans_pcm_file_to_mp3(input_path, output_path, Some(config)).expect("多线程文件编码应该成功");
+    assert_eq!(report.duration.as_secs(), 30);
+    assert_eq!(audio_duration(output_path).unwrap(), audio_duration_of(&single));
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 写入临时文件后计算 MP3 数据的时长
+fn audio_duration_of(mp3: &[u8]) -> std::time::Duration {
+    let path = "mp3_threads_test_reference.mp3";
+    fs::write(path, mp3).unwrap();
+    let duration = audio_duration(path).unwrap();
+    let _ = fs::remove_file(path);
+    duration
+}
@@ -0,0 +1,253 @@
+use audio_helper::{
+    decode_samples, decode_samples_i16, downmix_to_mono, generate_sine, linear_to_db, normalize_peak, read_wav_file, resample_f32,
+    trans_pcm_bytes_to_wav, true_peak, AudioConfig, AudioEffect, AudioFormat, AudioPipeline, AudioQuality, BiquadFilter, ConvertOptions,
+    FadeConfig, LimiterConfig, NoiseGateConfig, Mp3Bitrate, Mp3Config, OverwritePolicy, PcmToWavConfig, PipelineOutput, SampleFormat,
+};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 生成交错的正弦波样本，左右声道频率不同
+fn sine_samples(sample_rate: u32, channels: u8, seconds: f32) -> Vec<i16> {
+    let mono = AudioConfig::new(sample_rate, 1, 16);
+    let tones: Vec<Vec<i16>> = (1..=channels)
+        .map(|c| decode_samples_i16(&generate_sine(440.0 * c as f32, Duration::from_secs_f32(seconds), &mono), SampleFormat::S16))
+        .collect();
+    (0..tones[0].len()).flat_map(|i| tones.iter().map(move |tone| tone[i])).collect()
+}
+
+fn to_le_bytes(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+fn from_le_bytes(data: &[u8]) -> Vec<i16> {
+    data.chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect()
+}
+
+/// 验证流式执行的结果与对完整信号依次调用处理函数相同
+#[test]
+fn test_pipeline_matches_batch_processing() {
+    let dir = Path::new("pipeline_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let samples = sine_samples(48000, 2, 1.5);
+    let input = dir.join("input.wav");
+    fs::write(&input, trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(PcmToWavConfig::new(48000, 2, 16))).unwrap()).unwrap();
+
+    let output = dir.join("output.wav");
+    let report = AudioPipeline::from(&input).resample(16000).downmix_mono().normalize(-1.0).run(&output).expect("流水线应该执行成功");
+    assert_eq!(report.format, AudioFormat::Wav);
+    assert_eq!(report.config_used, AudioConfig::new(16000, 1, 16));
+    assert_eq!(report.duration.as_millis(), 1500);
+
+    let floats: Vec<f32> = samples.iter().map(|&s| s as f32 / 32768.0).collect();
+    let mut expected = downmix_to_mono(&resample_f32(&floats, 2, 48000, 16000), 2);
+    normalize_peak(&mut expected, -1.0);
+    let actual = from_le_bytes(&read_wav_file(&output).unwrap().data);
+    assert_eq!(actual.len(), expected.len());
+    let max_diff = actual.iter().zip(&expected).map(|(&a, &e)| (a as i32 - (e * 32768.0).round() as i32).abs()).max().unwrap();
+    assert!(max_diff <= 1, "与逐步处理的结果相差 {}", max_diff);
+    println!("🔗 48kHz 立体声 -> 16kHz 单声道并归一化，最大误差 {}", max_diff);
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 验证 MP3 输出自动补充重采样和混缩，未指定编码时按扩展名选择
+#[test]
+fn test_pipeline_encoding() {
+    let dir = Path::new("pipeline_encoding_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    // 原始 PCM 输入，参数由调用方指定
+    let input = dir.join("capture.pcm");
+    fs::write(&input, to_le_bytes(&sine_samples(96000, 2, 1.0))).unwrap();
+    let pcm_config = AudioConfig::new(96000, 2, 16);
+
+    let report = AudioPipeline::new(&input).with_pcm_config(pcm_config.clone()).gain(-6.0).run(dir.join("auto.mp3")).expect("MP3 输出应该成功");
+    assert_eq!(report.format, AudioFormat::Mp3);
+    assert_eq!(report.config_used.sample_rate, 48000, "96kHz 应该自动重采样到 48kHz");
+    assert_eq!(report.output_bytes, fs::metadata(dir.join("auto.mp3")).unwrap().len());
+
+    let mp3_config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps64, AudioQuality::Medium).with_target_sample_rate(22050).with_target_channels(1);
+    let report = AudioPipeline::new(&input).with_pcm_config(pcm_config.clone()).encode(mp3_config).run(dir.join("custom.bin")).unwrap();
+    assert_eq!(report.config_used, AudioConfig::new(22050, 1, 16));
+    println!("🎵 96kHz PCM -> {}Hz {} 声道 MP3", report.config_used.sample_rate, report.config_used.channels);
+
+    let report = AudioPipeline::new(&input).with_pcm_config(pcm_config.clone()).encode(PipelineOutput::Pcm).run(dir.join("copy.raw")).unwrap();
+    assert_eq!(report.output_bytes, fs::metadata(&input).unwrap().len());
+
+    // config_used 记录 WAV 输出实际的位深度
+    let report = AudioPipeline::new(&input).with_pcm_config(pcm_config.clone()).encode(PcmToWavConfig::new(96000, 2, 24)).run(dir.join("hires.wav")).unwrap();
+    assert_eq!(report.config_used, AudioConfig::new(96000, 2, 24));
+    assert_eq!(read_wav_file(dir.join("hires.wav")).unwrap().config.bits_per_sample, 24);
+
+    // 淡出需要整段长度：最后一帧应该接近静音
+    let report = AudioPipeline::new(&input).with_pcm_config(pcm_config.clone()).fade(FadeConfig::new(0, 200)).run(dir.join("fade.wav")).unwrap();
+    let faded = from_le_bytes(&read_wav_file(dir.join("fade.wav")).unwrap().data);
+    assert_eq!(report.duration.as_millis(), 1000);
+    assert!(faded[faded.len() - 2..].iter().all(|s| s.abs() <= 1));
+    assert!(faded[..9600].iter().any(|s| s.abs() > 5000), "淡出之前的样本不应该被衰减");
+
+    // 覆盖策略和错误处理
+    let skipped = AudioPipeline::new(&input)
+        .with_pcm_config(pcm_config)
+        .with_options(ConvertOptions::new().with_overwrite(OverwritePolicy::Skip))
+        .run(dir.join("fade.wav"))
+        .unwrap();
+    assert!(skipped.skipped);
+    assert!(AudioPipeline::new(&input).run(dir.join("output.flac")).is_err());
+    assert!(AudioPipeline::new(dir.join("missing.wav")).run(dir.join("output.wav")).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 反相效果器，记录收到的参数和重置次数
+struct Invert {
+    log: Arc<Mutex<(Vec<AudioConfig>, usize, usize)>>,
+}
+
+impl AudioEffect for Invert {
+    fn process(&mut self, frames: &mut [f32], config: &AudioConfig) {
+        let mut log = self.log.lock().unwrap();
+        if !log.0.contains(config) {
+            log.0.push(config.clone());
+        }
+        log.2 += frames.len();
+        frames.iter_mut().for_each(|s| *s = -*s);
+    }
+
+    fn reset(&mut self) {
+        self.log.lock().unwrap().1 += 1;
+    }
+}
+
+/// 验证自定义效果器按顺序插入流水线，并在每次遍历前重置
+#[test]
+fn test_pipeline_custom_effect() {
+    let dir = Path::new("pipeline_effect_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let samples = sine_samples(48000, 2, 0.5);
+    let input = dir.join("input.wav");
+    fs::write(&input, trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(PcmToWavConfig::new(48000, 2, 16))).unwrap()).unwrap();
+
+    // 效果器位于混缩之后，收到的是单声道信号
+    let log = Arc::new(Mutex::new((Vec::new(), 0, 0)));
+    AudioPipeline::from(&input).downmix_mono().effect(Invert { log: log.clone() }).run(dir.join("inverted.wav")).expect("流水线应该执行成功");
+    let plain = from_le_bytes(&read_wav_file(dir.join("inverted.wav")).unwrap().data);
+    {
+        let log = log.lock().unwrap();
+        assert_eq!(log.0.len(), 1);
+        assert_eq!((log.0[0].sample_rate, log.0[0].channels), (48000, 1));
+        assert_eq!(log.1, 1);
+        assert_eq!(log.2, 24000);
+    }
+    let expected = downmix_to_mono(&samples.iter().map(|&s| s as f32 / 32768.0).collect::<Vec<_>>(), 2);
+    assert!(plain.iter().zip(&expected).all(|(&a, &e)| (a as i32 + (e * 32768.0).round() as i32).abs() <= 1), "样本应该被反相");
+
+    // 归一化位于效果器之后时需要先测量一遍，效果器会被重置两次
+    let log = Arc::new(Mutex::new((Vec::new(), 0, 0)));
+    AudioPipeline::from(&input).effect(Invert { log: log.clone() }).normalize(-3.0).run(dir.join("normalized.wav")).unwrap();
+    assert_eq!(log.lock().unwrap().1, 2);
+    println!("🎛️ 自定义效果器处理 {} 个样本", log.lock().unwrap().2);
+
+    // 闭包也可以作为效果器
+    let report = AudioPipeline::from(&input)
+        .effect(|frames: &mut [f32], _: &AudioConfig| frames.iter_mut().for_each(|s| *s *= 0.0))
+        .run(dir.join("silent.wav"))
+        .unwrap();
+    assert_eq!(report.duration.as_millis(), 500);
+    assert!(from_le_bytes(&read_wav_file(dir.join("silent.wav")).unwrap().data).iter().all(|&s| s == 0));
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 验证流水线中的限制器在归一化之后限制真峰值，输出长度不变
+#[test]
+fn test_pipeline_limiter() {
+    let dir = Path::new("pipeline_limiter_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let samples = sine_samples(44100, 2, 1.0);
+    let input = dir.join("input.wav");
+    fs::write(&input, trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(PcmToWavConfig::new(44100, 2, 16))).unwrap()).unwrap();
+
+    let output = dir.join("limited.wav");
+    let report = AudioPipeline::from(&input).normalize(0.0).limit(LimiterConfig::new(-2.0)).run(&output).expect("流水线应该执行成功");
+    assert_eq!(report.duration.as_millis(), 1000);
+
+    let limited: Vec<f32> = from_le_bytes(&read_wav_file(&output).unwrap().data).iter().map(|&s| s as f32 / 32768.0).collect();
+    assert_eq!(limited.len(), samples.len());
+    let peak_db = linear_to_db(true_peak(&limited, 2));
+    assert!(peak_db <= -1.95, "真峰值 {:.2} dBTP 超过上限", peak_db);
+    println!("🧱 归一化到 0 dBFS 后限制到 {:+.2} dBTP", peak_db);
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 验证流水线中的噪声门：低于阈值的输入被静音，高于阈值的输入保持不变
+#[test]
+fn test_pipeline_noise_gate() {
+    let dir = Path::new("pipeline_noise_gate_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    // 前 0.5 秒为约 -6 dBFS 的正弦，后 0.5 秒为 ±8 的微弱噪声
+    let mut samples = sine_samples(16000, 1, 0.5);
+    samples.extend((0..8000).map(|i| if i % 2 == 0 { 8i16 } else { -8 }));
+    let input = dir.join("input.wav");
+    fs::write(&input, trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(PcmToWavConfig::new(16000, 1, 16))).unwrap()).unwrap();
+
+    let output = dir.join("gated.wav");
+    AudioPipeline::from(&input).noise_gate(NoiseGateConfig::new(-50.0)).run(&output).expect("流水线应该执行成功");
+    let gated = from_le_bytes(&read_wav_file(&output).unwrap().data);
+    assert_eq!(gated.len(), samples.len());
+    assert_eq!(&gated[100..8000], &samples[100..8000], "高于阈值的部分不应该受影响");
+    assert!(gated[8000 + 4800..].iter().all(|&s| s == 0), "保持和释放之后的底噪应该被静音");
+    println!("🚪 噪声门静音了 {} 个样本", gated.iter().filter(|&&s| s == 0).count());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+/// 验证流水线中的高通滤波去除低频隆隆声，保留语音频段
+#[test]
+fn test_pipeline_high_pass_filter() {
+    let dir = Path::new("pipeline_filter_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    // 1kHz 的“语音”叠加同样电平的 20Hz 隆隆声，幅度均为 6000
+    let tone_config = AudioConfig::new(16000, 1, 32).with_sample_format(SampleFormat::F32);
+    let tone = |frequency: f32| -> Vec<f32> { decode_samples(&generate_sine(frequency, Duration::from_secs(1), &tone_config), SampleFormat::F32) };
+    let samples: Vec<i16> = tone(1000.0).iter().zip(tone(20.0)).map(|(voice, rumble)| ((voice + rumble) * 12000.0) as i16).collect();
+    let input = dir.join("input.wav");
+    fs::write(&input, trans_pcm_bytes_to_wav(&to_le_bytes(&samples), Some(PcmToWavConfig::new(16000, 1, 16))).unwrap()).unwrap();
+
+    let output = dir.join("filtered.wav");
+    AudioPipeline::from(&input).filter(BiquadFilter::high_pass(80.0)).run(&output).expect("流水线应该执行成功");
+    let filtered = from_le_bytes(&read_wav_file(&output).unwrap().data);
+    assert_eq!(filtered.len(), samples.len());
+
+    // 后半秒（整数个周期）中各频率成分的幅度
+    let amplitude = |frequency: f32| {
+        let (re, im) = filtered[8000..].iter().enumerate().fold((0.0f32, 0.0f32), |(re, im), (i, &s)| {
+            let phase = 2.0 * std::f32::consts::PI * frequency * i as f32 / 16000.0;
+            (re + s as f32 * phase.cos(), im + s as f32 * phase.sin())
+        });
+        2.0 * (re * re + im * im).sqrt() / 8000.0
+    };
+    let (rumble, voice) = (amplitude(20.0), amplitude(1000.0));
+    println!("🎚️ 80Hz 高通后 20Hz 幅度 {:.0}，1kHz 幅度 {:.0}（原始均为 6000）", rumble, voice);
+    assert!(rumble < 600.0, "20Hz 成分应该被大幅衰减");
+    assert!(voice > 5900.0, "1kHz 成分应该基本不变");
+
+    // 截止频率超过奈奎斯特频率时报错
+    assert!(AudioPipeline::from(&input).filter(BiquadFilter::low_pass(9000.0)).run(dir.join("invalid.wav")).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+}
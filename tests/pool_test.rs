@@ -0,0 +1,84 @@
+use audio_helper::{generate_sine, trans_pcm_bytes_to_mp3, AudioConfig, AudioQuality, EncoderPool, Mp3Bitrate, Mp3Config, Mp3Encoder};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// 生成正弦波 PCM 数据（16 位小端）
+fn sine_pcm(sample_rate: u32, channels: u8, seconds: f32, frequency: f32) -> Vec<u8> {
+    generate_sine(frequency, Duration::from_secs_f32(seconds), &AudioConfig::new(sample_rate, channels, 16))
+}
+
+/// 验证复用的编码器与每次新建编码器的输出完全相同，上一个片段不会影响下一个片段
+#[test]
+fn test_reused_encoder_matches_fresh_encoder() {
+    let config = Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::Medium);
+    let clips: Vec<Vec<u8>> = [440.0, 880.0, 220.0].iter().map(|&f| sine_pcm(16000, 1, 2.0, f)).collect();
+
+    let mut encoder = Mp3Encoder::new(config.clone()).expect("创建编码器应该成功");
+    assert!(encoder.is_ready(), "不需要处理的配置应该立即初始化编码器");
+    for (i, clip) in clips.iter().enumerate() {
+        let reused = encoder.encode(clip).expect("编码应该成功");
+        assert!(!encoder.is_ready());
+        encoder.reset().expect("重置应该成功");
+        assert!(encoder.is_ready());
+        let fresh = trans_pcm_bytes_to_mp3(clip, Some(config.clone())).expect("编码应该成功");
+        assert_eq!(reused, fresh, "第 {} 个片段的输出应该与新建编码器相同", i + 1);
+    }
+    println!("♻️ 复用编码器连续编码 {} 个片段，输出与新建编码器一致", clips.len());
+
+    // 不调用 reset 时下一次编码自动初始化
+    let again = encoder.encode(&clips[0]).expect("编码应该成功");
+    assert_eq!(again, encoder.encode(&clips[0]).expect("编码应该成功"));
+
+    // 需要重采样的配置在第一次编码时才初始化
+    let resampled = Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::Medium).with_target_sample_rate(22050);
+    let mut encoder = Mp3Encoder::new(resampled.clone()).expect("创建编码器应该成功");
+    assert!(!encoder.is_ready());
+    let first = encoder.encode(&clips[1]).expect("编码应该成功");
+    encoder.reset().expect("重置应该成功");
+    assert!(encoder.is_ready());
+    assert_eq!(encoder.encode(&clips[1]).expect("编码应该成功"), first);
+    assert_eq!(first, trans_pcm_bytes_to_mp3(&clips[1], Some(resampled)).unwrap());
+}
+
+/// 验证编码器池按配置复用编码器，并限制空闲编码器数量
+#[test]
+fn test_encoder_pool_reuses_by_config() {
+    let dir = Path::new("pool_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    let pool = EncoderPool::new().with_max_idle(1);
+    let mono = Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::Medium);
+    let stereo = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium);
+    assert_eq!(pool.idle_count(), 0);
+
+    for i in 0..5 {
+        let input = dir.join(format!("clip_{}.pcm", i));
+        let output = dir.join(format!("clip_{}.mp3", i));
+        fs::write(&input, sine_pcm(16000, 1, 2.0, 300.0 + i as f32 * 100.0)).unwrap();
+        let report = pool.convert_file(&input, &output, &mono).expect("转换应该成功");
+        assert_eq!(report.output_bytes, fs::metadata(&output).unwrap().len());
+        assert_eq!(report.duration.as_millis(), 2000);
+        assert_eq!(pool.idle_count(), 1, "转换结束后编码器应该回到池中");
+    }
+    println!("🏊 编码器池连续转换 5 个文件，只保留 1 个空闲编码器");
+
+    // 不同配置不会取到同一个编码器；超过上限的编码器释放时直接销毁
+    {
+        let first = pool.get(&mono).expect("应该取到空闲编码器");
+        assert_eq!(pool.idle_count(), 0);
+        let second = pool.get(&stereo).expect("应该新建编码器");
+        assert_eq!(first.config(), &mono);
+        assert_eq!(second.config(), &stereo);
+    }
+    assert_eq!(pool.idle_count(), 1);
+
+    let pcm = sine_pcm(44100, 2, 1.0, 440.0);
+    assert_eq!(pool.encode(&stereo, &pcm).unwrap(), trans_pcm_bytes_to_mp3(&pcm, Some(stereo.clone())).unwrap());
+
+    // 无效配置在取出时报错
+    assert!(pool.get(&Mp3Config::new(16000, 0, Mp3Bitrate::Kbps64, AudioQuality::Medium)).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+}
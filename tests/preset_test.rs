@@ -0,0 +1,150 @@
+use audio_helper::{
+    batch_convert_files, generate_sine, normalize_peak, trans_pcm_file_to_mp3_preset, AudioConfig, AudioFormat, AudioQuality, BatchOptions,
+    Mp3Bitrate, Mp3Config, Mp3RateMode, Preset, Presets, SampleFormat, VbrQuality,
+};
+use audio_helper::ffi::CErrorCode;
+use std::ffi::CString;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// 生成交错的正弦波 PCM（16 位小端），幅度为满幅的一半
+fn sine_pcm(sample_rate: u32, channels: u8, seconds: f32) -> Vec<u8> {
+    generate_sine(440.0, Duration::from_secs_f32(seconds), &AudioConfig::new(sample_rate, channels, 16))
+}
+
+/// 验证各预设展开的配置对常见输入都是合法的
+#[test]
+fn test_presets_expand_to_valid_configs() {
+    let inputs = [
+        AudioConfig::new(8000, 1, 16),
+        AudioConfig::new(16000, 1, 16),
+        AudioConfig::new(44100, 2, 16),
+        AudioConfig::new(96000, 6, 24),
+        AudioConfig::new(48000, 2, 32).with_sample_format(SampleFormat::F32),
+    ];
+    for preset in Preset::ALL {
+        assert_eq!(Preset::from_name(preset.name()), Some(preset));
+        for input in &inputs {
+            let config = preset.mp3_config(input);
+            config.validate().unwrap_or_else(|e| panic!("{:?} 对 {:?} 生成了非法配置: {}", preset, input, e));
+            assert_eq!(config.sample_format, input.sample_format);
+        }
+    }
+    assert_eq!(Preset::from_name("VOICE"), Some(Preset::Voice));
+    assert_eq!(Preset::from_name("karaoke"), None);
+
+    let voice = Preset::Voice.mp3_config(&AudioConfig::new(48000, 2, 16));
+    assert_eq!(voice.target_sample_rate, Some(16000));
+    assert_eq!(voice.target_channels, Some(1));
+    assert_eq!(voice.normalize_db, Some(-1.0));
+    assert!(voice.remove_dc);
+
+    let music = Preset::Music.mp3_config(&AudioConfig::new(48000, 2, 16));
+    assert_eq!(music.rate_mode, Mp3RateMode::Vbr(VbrQuality::V2));
+    assert_eq!(music.normalize_db, None);
+    println!("✅ 预设展开测试通过");
+}
+
+/// 验证按预设转换：语音预设输出 16kHz 单声道并归一化
+#[test]
+fn test_trans_pcm_file_to_mp3_preset() {
+    let dir = Path::new("preset_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let input_path = dir.join("meeting_48k16bit双声道.pcm");
+    fs::write(&input_path, sine_pcm(48000, 2, 1.0)).expect("Failed to write test PCM file");
+
+    let report = trans_pcm_file_to_mp3_preset(&input_path, dir.join("voice.mp3"), Preset::Voice).expect("语音预设转换失败");
+    println!("🎙️ voice: {:?}", report.config_used);
+    assert_eq!((report.config_used.sample_rate, report.config_used.channels), (16000, 1));
+
+    let report = trans_pcm_file_to_mp3_preset(&input_path, dir.join("archive.mp3"), Preset::Archive).expect("归档预设转换失败");
+    assert_eq!((report.config_used.sample_rate, report.config_used.channels), (48000, 2));
+    assert!(report.output_bytes > 0);
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ 预设转换测试通过");
+}
+
+/// 验证峰值归一化
+#[test]
+fn test_normalize_peak() {
+    let mut samples = vec![0.25, -0.5, 0.1];
+    let gain_db = normalize_peak(&mut samples, 0.0);
+    assert!((gain_db - 6.02).abs() < 0.01);
+    assert!((samples[1] + 1.0).abs() < 1e-6);
+
+    let mut silence = vec![0.0; 4];
+    assert_eq!(normalize_peak(&mut silence, -1.0), 0.0);
+    assert!(silence.iter().all(|&s| s == 0.0));
+    println!("✅ 峰值归一化测试通过");
+}
+
+/// 验证自定义预设的注册、解析和移除
+#[test]
+fn test_preset_registry() {
+    let template = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps64, AudioQuality::Medium)
+        .with_target_sample_rate(8000)
+        .with_target_channels(1)
+        .with_normalize_db(-2.0);
+    Presets::register("CallCenter", template).expect("注册自定义预设失败");
+    assert!(Presets::contains("callcenter"));
+    assert!(Presets::names().contains(&"callcenter".to_string()));
+    assert_eq!(Presets::names()[0], "voice");
+
+    // 输入参数来自输入文件，其余来自模板
+    let config = Presets::mp3_config("callcenter", &AudioConfig::new(48000, 2, 24)).unwrap();
+    assert_eq!((config.sample_rate, config.channels, config.sample_format), (48000, 2, SampleFormat::S24));
+    assert_eq!((config.target_sample_rate, config.target_channels), (Some(8000), Some(1)));
+    assert_eq!(config.normalize_db, Some(-2.0));
+    assert!(config.validate().is_ok());
+
+    // 内置预设同样可以按名称解析
+    assert_eq!(Presets::mp3_config("Podcast", &AudioConfig::default()).unwrap().rate_mode, Mp3RateMode::Abr(96));
+
+    // 内置名称不可覆盖，非法模板和未知名称报错
+    assert!(Presets::register("voice", Mp3Config::default()).is_err());
+    assert!(Presets::register("  ", Mp3Config::default()).is_err());
+    let invalid = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps320, AudioQuality::High).with_target_sample_rate(8000);
+    let error = Presets::register("tiny", invalid).unwrap_err().to_string();
+    println!("❌ {}", error);
+    assert!(error.contains("rate_mode"));
+    assert!(Presets::mp3_config("missing", &AudioConfig::default()).is_err());
+
+    assert!(Presets::unregister("callcenter"));
+    assert!(!Presets::contains("callcenter"));
+    println!("✅ 预设注册表测试通过");
+}
+
+/// 验证批量转换和 FFI 按名称使用自定义预设
+#[test]
+fn test_named_preset_in_batch_and_ffi() {
+    let dir = Path::new("preset_batch_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let input_path = dir.join("call_16k16bit单声道.pcm");
+    fs::write(&input_path, sine_pcm(16000, 1, 0.5)).expect("Failed to write test PCM file");
+    Presets::register("archive-lowrate", Mp3Config::default().with_rate_mode(Mp3RateMode::Cbr(Mp3Bitrate::Kbps64))).unwrap();
+
+    let jobs = vec![(input_path.clone(), dir.join("call.mp3"))];
+    let report = batch_convert_files(&jobs, &BatchOptions::new(AudioFormat::Mp3).with_preset("archive-lowrate")).unwrap();
+    assert_eq!(report.succeeded(), 1);
+    assert!(dir.join("call.mp3").exists());
+
+    // 未知预设和非 MP3 输出在开始前报错
+    assert!(batch_convert_files(&jobs, &BatchOptions::new(AudioFormat::Mp3).with_preset("nope")).is_err());
+    assert!(batch_convert_files(&jobs, &BatchOptions::new(AudioFormat::Wav).with_preset("voice")).is_err());
+
+    let c_path = |path: PathBuf| CString::new(path.to_str().unwrap()).unwrap();
+    let (input, output) = (c_path(input_path), c_path(dir.join("ffi.mp3")));
+    let preset = CString::new("archive-lowrate").unwrap();
+    assert_eq!(audio_helper::ffi::pcm_to_mp3_preset(input.as_ptr(), output.as_ptr(), preset.as_ptr()), CErrorCode::Ok);
+    assert!(dir.join("ffi.mp3").exists());
+    let unknown = CString::new("unknown").unwrap();
+    assert_eq!(audio_helper::ffi::pcm_to_mp3_preset(input.as_ptr(), output.as_ptr(), unknown.as_ptr()), CErrorCode::InvalidArgument);
+
+    Presets::unregister("archive-lowrate");
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ 批量转换和 FFI 预设测试通过");
+}
@@ -0,0 +1,243 @@
+use audio_helper::{trans_pcm_file_to_wav, trans_pcm_file_to_mp3, read_wav_file, apply_gain_db, detect_dc_offset, audio_config_to_wav_config, apply_noise_gate, decode_samples, generate_sine, linear_to_db, true_peak, AudioConfig, PcmToWavConfig, Mp3Config, Mp3Bitrate, AudioQuality, FadeConfig, FadeCurve, LimiterConfig, NoiseGateConfig, SampleFormat};
+use std::fs;
+use std::time::Duration;
+
+/// 将 i16 样本写为 16 位小端 PCM 文件
+fn write_pcm(path: &str, samples: &[i16]) {
+    let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+    fs::write(path, bytes).expect("Failed to write test PCM file");
+}
+
+/// 验证立体声混缩为单声道
+#[test]
+fn test_stereo_to_mono_wav() {
+    let input_path = "processing_stereo_to_mono.pcm";
+    let output_path = "processing_stereo_to_mono.wav";
+    // 左声道 16000，右声道 0，混缩后为 8000
+    let samples: Vec<i16> = (0..1000).flat_map(|_| [16000i16, 0]).collect();
+    write_pcm(input_path, &samples);
+
+    let config = PcmToWavConfig::new(16000, 2, 16).with_target_channels(1);
+    trans_pcm_file_to_wav(input_path, output_path, Some(config)).expect("混缩转换应该成功");
+
+    let audio = read_wav_file(output_path).expect("Failed to read WAV output");
+    assert_eq!(audio.config.channels, 1);
+    assert_eq!(audio.frames(), 1000);
+    assert!(audio.samples_i16().iter().all(|&s| s == 8000), "混缩后的样本应该为左右声道平均值");
+    println!("✓ 立体声 -> 单声道混缩正确");
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证单声道复制为立体声（WAV 与 MP3）
+#[test]
+fn test_mono_to_stereo() {
+    let input_path = "processing_mono_to_stereo.pcm";
+    let wav_path = "processing_mono_to_stereo.wav";
+    let mp3_path = "processing_mono_to_stereo.mp3";
+    let samples: Vec<i16> = (0..44100).map(|i| ((i as f32 * 0.06).sin() * 10000.0) as i16).collect();
+    write_pcm(input_path, &samples);
+
+    // 通过通用配置设置目标声道数
+    let audio_config = AudioConfig::new(44100, 1, 16).with_target_channels(2);
+    trans_pcm_file_to_wav(input_path, wav_path, Some(audio_config_to_wav_config(&audio_config))).expect("WAV 转换应该成功");
+    let audio = read_wav_file(wav_path).expect("Failed to read WAV output");
+    assert_eq!(audio.config.channels, 2);
+    let stereo = audio.samples_i16();
+    assert!(stereo.chunks(2).zip(&samples).all(|(frame, &s)| frame[0] == s && frame[1] == s), "左右声道应该与原始单声道一致");
+    println!("✓ 单声道 -> 立体声复制正确");
+
+    let config = Mp3Config::new(44100, 1, Mp3Bitrate::Kbps128, AudioQuality::Medium).with_target_channels(2);
+    let used = trans_pcm_file_to_mp3(input_path, mp3_path, Some(config)).expect("MP3 转换应该成功");
+    assert_eq!(used.config_used.channels, 2, "实际使用的配置应该为立体声");
+    println!("✓ MP3 编码使用 {} 声道", used.config_used.channels);
+
+    for path in [input_path, wav_path, mp3_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证不支持的声道转换会返回错误
+#[test]
+fn test_unsupported_channel_conversion() {
+    let input_path = "processing_unsupported_channels.pcm";
+    let output_path = "processing_unsupported_channels.wav";
+    write_pcm(input_path, &[0; 400]);
+
+    let config = PcmToWavConfig::new(16000, 2, 16).with_target_channels(6);
+    assert!(trans_pcm_file_to_wav(input_path, output_path, Some(config)).is_err());
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证独立的增益调整函数（含削波保护）
+#[test]
+fn test_apply_gain_db_file() {
+    let input_path = "processing_gain_input.pcm";
+    let output_path = "processing_gain_output.pcm";
+    write_pcm(input_path, &[1000, -1000, 20000, -20000]);
+
+    // +6.02 dB 约为 2 倍增益
+    apply_gain_db(input_path, output_path, 6.0206, AudioConfig::new(16000, 1, 16)).expect("增益调整应该成功");
+    let output: Vec<i16> = fs::read(output_path).unwrap().chunks_exact(2).map(|b| i16::from_le_bytes([b[0], b[1]])).collect();
+    println!("增益调整结果: {:?}", output);
+    assert_eq!(&output[..2], &[2000, -2000]);
+    assert_eq!(output[2], i16::MAX, "超出范围的样本应该被削波而不是溢出");
+    assert_eq!(output[3], i16::MIN);
+    assert!(apply_gain_db(input_path, output_path, 6.0, AudioConfig::new(16000, 0, 16)).is_err(), "0 声道应该报错");
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证在 WAV 编码前应用增益
+#[test]
+fn test_gain_before_wav_encoding() {
+    let input_path = "processing_gain_chain.pcm";
+    let output_path = "processing_gain_chain.wav";
+    write_pcm(input_path, &[4000; 200]);
+
+    let config = PcmToWavConfig::new(16000, 1, 16).with_gain_db(-6.0206);
+    trans_pcm_file_to_wav(input_path, output_path, Some(config)).expect("WAV 转换应该成功");
+    let audio = read_wav_file(output_path).expect("Failed to read WAV output");
+    assert!(audio.samples_i16().iter().all(|&s| s == 2000), "衰减 6dB 后样本应该减半");
+    println!("✓ 编码前增益调整正确");
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证转换时应用淡入淡出，首尾不再突变
+#[test]
+fn test_fade_during_conversion() {
+    let input_path = "processing_fade.pcm";
+    let wav_path = "processing_fade.wav";
+    let mp3_path = "processing_fade.mp3";
+    // 1 秒恒定电平的立体声，首尾会产生明显的爆音
+    write_pcm(input_path, &[10000i16; 16000 * 2]);
+
+    let fade = FadeConfig::new(100, 200).with_curve(FadeCurve::SCurve);
+    let config = PcmToWavConfig::new(16000, 2, 16).with_fade(fade.clone());
+    trans_pcm_file_to_wav(input_path, wav_path, Some(config)).expect("WAV 转换应该成功");
+
+    let samples = read_wav_file(wav_path).expect("Failed to read WAV output").samples_i16();
+    let frames = samples.len() / 2;
+    assert_eq!(samples[0], 0, "第一帧应该静音");
+    assert_eq!(samples[samples.len() - 1], 0, "最后一帧应该静音");
+    assert!(samples[800 * 2] < 10000, "淡入区间内的样本应该被衰减");
+    assert_eq!(samples[frames / 2 * 2], 10000, "中间部分不应该受影响");
+    assert!(samples[(frames - 1600) * 2] < 10000, "淡出区间内的样本应该被衰减");
+    println!("✓ WAV 淡入淡出正确");
+
+    let config = Mp3Config::new(16000, 2, Mp3Bitrate::Kbps64, AudioQuality::Medium).with_fade(fade);
+    assert!(trans_pcm_file_to_mp3(input_path, mp3_path, Some(config)).is_ok(), "MP3 转换应该成功");
+
+    for path in [input_path, wav_path, mp3_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证直流偏移的测量和转换时去除
+#[test]
+fn test_dc_offset_detection_and_removal() {
+    let input_path = "processing_dc_offset.pcm";
+    let output_path = "processing_dc_offset.wav";
+    // 1 秒 8kHz 单声道，正弦波叠加 +3000 的直流偏移
+    let samples: Vec<i16> = (0..8000)
+        .map(|i| ((2.0 * std::f32::consts::PI * 200.0 * i as f32 / 8000.0).sin() * 8000.0) as i16 + 3000)
+        .collect();
+    write_pcm(input_path, &samples);
+
+    trans_pcm_file_to_wav(input_path, output_path, Some(PcmToWavConfig::new(8000, 1, 16))).expect("WAV 转换应该成功");
+    let offsets = detect_dc_offset(output_path).expect("测量直流偏移应该成功");
+    println!("去除前偏移: {:?}", offsets);
+    assert_eq!(offsets.len(), 1);
+    assert!((offsets[0] - 3000.0 / 32768.0).abs() < 1e-3);
+
+    let config = PcmToWavConfig::new(8000, 1, 16).with_dc_removal(true);
+    trans_pcm_file_to_wav(input_path, output_path, Some(config)).expect("去除直流偏移的转换应该成功");
+    let offsets = detect_dc_offset(output_path).expect("测量直流偏移应该成功");
+    println!("去除后偏移: {:?}", offsets);
+    assert!(offsets[0].abs() < 2e-3);
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证归一化到 0 dBFS 后限制器使真峰值不超过 -1 dBTP
+#[test]
+fn test_true_peak_limiter_after_normalization() {
+    let input_path = "processing_limiter.pcm";
+    let output_path = "processing_limiter.wav";
+    // 前半秒为低电平的 1kHz 正弦，后半秒为 fs/4 的正弦，以 45° 相位采样，样本之间的峰值比样本峰值高约 3dB
+    let samples: Vec<i16> = (0..44100)
+        .map(|i| {
+            let value = if i < 22050 {
+                (2.0 * std::f32::consts::PI * 1000.0 * i as f32 / 44100.0).sin() * 0.3
+            } else {
+                (std::f32::consts::FRAC_PI_2 * i as f32 + std::f32::consts::FRAC_PI_4).sin() * 0.5
+            };
+            (value * 32767.0) as i16
+        })
+        .collect();
+    write_pcm(input_path, &samples);
+    let read_floats = |path: &str| -> Vec<f32> { read_wav_file(path).expect("Failed to read WAV output").samples_i16().iter().map(|&s| s as f32 / 32768.0).collect() };
+
+    let config = PcmToWavConfig::new(44100, 1, 16).with_normalize_db(0.0);
+    trans_pcm_file_to_wav(input_path, output_path, Some(config.clone())).expect("WAV 转换应该成功");
+    let normalized = read_floats(output_path);
+    let unlimited_db = linear_to_db(true_peak(&normalized, 1));
+    println!("仅归一化: 真峰值 {:+.2} dBTP", unlimited_db);
+    assert!(unlimited_db > 2.0, "样本之间的峰值应该超过满量程");
+
+    trans_pcm_file_to_wav(input_path, output_path, Some(config.with_limiter(LimiterConfig::new(-1.0)))).expect("WAV 转换应该成功");
+    let limited = read_floats(output_path);
+    let limited_db = linear_to_db(true_peak(&limited, 1));
+    println!("归一化 + 限制器: 真峰值 {:+.2} dBTP", limited_db);
+    assert_eq!(limited.len(), normalized.len(), "限制器不应该改变长度");
+    assert!(limited_db <= -0.95, "真峰值应该不超过 -1 dBTP");
+    assert_eq!(&limited[..20000], &normalized[..20000], "低于上限的部分不应该受影响");
+    println!("✓ 真峰值限制正确");
+
+    let invalid = PcmToWavConfig::new(44100, 1, 16).with_limiter(LimiterConfig::new(1.0));
+    assert!(trans_pcm_file_to_wav(input_path, output_path, Some(invalid)).is_err(), "高于 0 dBTP 的上限应该报错");
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证噪声门静音口述录音中语句之间的底噪，语句本身保持不变
+#[test]
+fn test_noise_gate_mutes_pauses() {
+    let sample_rate = 16000;
+    // 交替 0.5 秒“语句”（-12 dBFS 正弦）和 0.5 秒底噪（约 -60 dBFS 的伪随机噪声）
+    let tone_config = AudioConfig::new(sample_rate, 1, 32).with_sample_format(SampleFormat::F32);
+    let tone: Vec<f32> = decode_samples(&generate_sine(220.0, Duration::from_secs(3), &tone_config), SampleFormat::F32);
+    let mut seed = 12345u32;
+    let samples: Vec<f32> = (0..sample_rate as usize * 3)
+        .map(|i| {
+            seed = seed.wrapping_mul(1664525).wrapping_add(1013904223);
+            if (i / 8000) % 2 == 0 {
+                tone[i] * 0.5
+            } else {
+                (seed as f32 / u32::MAX as f32 - 0.5) * 0.002
+            }
+        })
+        .collect();
+
+    let gate = NoiseGateConfig::new(-40.0).with_hold_ms(50.0).with_release_ms(100.0);
+    let mut gated = samples.clone();
+    let muted = apply_noise_gate(&mut gated, 1, sample_rate, &gate);
+    println!("静音 {} 帧（{:.2} 秒）", muted, muted as f32 / sample_rate as f32);
+
+    // 停顿中保持和释放（共 150ms）之后完全静音
+    let pause = 8000 + 2400..16000;
+    assert!(gated[pause.clone()].iter().all(|&s| s == 0.0), "停顿中的底噪应该被静音");
+    assert!(samples[pause].iter().any(|&s| s != 0.0));
+    // 语句在开头 1ms 的打开过程之后保持不变
+    assert_eq!(&gated[16000 + 16..24000], &samples[16000 + 16..24000], "语句不应该受影响");
+    assert!(muted >= 2 * (8000 - 2400), "两段停顿都应该被静音");
+    println!("✓ 噪声门静音停顿中的底噪");
+}
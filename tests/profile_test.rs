@@ -0,0 +1,83 @@
+#![cfg(feature = "profile")]
+
+use audio_helper::{read_wav_file, AudioFormat, AudioQuality, ConversionProfile, Mp3RateMode, WavContainer};
+use std::fs;
+use std::path::Path;
+
+/// 验证 TOML / JSON 配置文件的加载和转换
+#[test]
+fn test_profile_from_file_and_convert() {
+    let dir = Path::new("profile_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm_path = dir.join("clip_16k16bit单声道.pcm");
+    let bytes: Vec<u8> = (0..3200i16).flat_map(|i| (i * 10).to_le_bytes()).collect();
+    fs::write(&pcm_path, &bytes).expect("Failed to write test PCM file");
+
+    let toml_path = dir.join("broadcast.toml");
+    fs::write(
+        &toml_path,
+        r#"
+name = "broadcast"
+output_format = "Wav"
+target_channels = 2
+gain_db = -6.0
+
+[fade]
+fade_in_ms = 10
+fade_out_ms = 10
+
+[wav]
+container = "Rf64"
+"#,
+    )
+    .unwrap();
+    let profile = ConversionProfile::from_file(&toml_path).expect("TOML 配置应该可以加载");
+    assert_eq!(profile.name.as_deref(), Some("broadcast"));
+    assert_eq!(profile.input_format, AudioFormat::Pcm);
+    assert_eq!(profile.wav.container, Some(WavContainer::Rf64));
+
+    // 未指定的 PCM 参数从文件名推断
+    let config = profile.audio_config_for(&pcm_path).unwrap();
+    assert_eq!((config.sample_rate, config.channels, config.target_channels), (16000, 1, Some(2)));
+
+    let report = profile.convert(&pcm_path, dir.join("out.wav")).expect("按配置转换应该成功");
+    assert_eq!(report.config_used.channels, 2);
+    assert_eq!(&fs::read(dir.join("out.wav")).unwrap()[..4], b"RF64");
+    assert_eq!(read_wav_file(dir.join("out.wav")).unwrap().config.channels, 2);
+    println!("✅ TOML 配置: {:?}", report.duration);
+
+    // JSON 配置，MP3 码率模式
+    let json_path = dir.join("voice.json");
+    fs::write(&json_path, r#"{"output_format": "Mp3", "sample_rate": 16000, "mp3": {"rate_mode": {"Abr": 96}, "quality": "Medium"}}"#).unwrap();
+    let profile = ConversionProfile::from_file(&json_path).expect("JSON 配置应该可以加载");
+    let mp3_config = profile.mp3_config_for(&pcm_path).unwrap();
+    assert_eq!(mp3_config.rate_mode, Mp3RateMode::Abr(96));
+    assert_eq!(mp3_config.quality, AudioQuality::Medium);
+    profile.convert(&pcm_path, dir.join("out.mp3")).expect("MP3 转换应该成功");
+
+    // 预设提供默认值，配置中显式指定的项优先
+    let profile = ConversionProfile::from_toml("preset = \"Voice\"\nnormalize_db = -3.0\n").unwrap();
+    let mp3_config = profile.mp3_config_for(&pcm_path).unwrap();
+    assert_eq!(mp3_config.target_sample_rate, Some(16000));
+    assert_eq!(mp3_config.normalize_db, Some(-3.0));
+    assert!(mp3_config.remove_dc);
+
+    // TOML 往返
+    let reloaded = ConversionProfile::from_toml(&profile.to_toml().unwrap()).unwrap();
+    assert_eq!(reloaded, profile);
+
+    // 错误情况
+    fs::write(dir.join("bad.yaml"), "name: x").unwrap();
+    assert!(ConversionProfile::from_file(dir.join("bad.yaml")).is_err());
+    fs::write(dir.join("bad.toml"), "sample_rate = \"fast\"").unwrap();
+    let error = ConversionProfile::from_file(dir.join("bad.toml")).unwrap_err().to_string();
+    assert!(error.contains("bad.toml"), "{}", error);
+    let profile = ConversionProfile { bits_per_sample: Some(12), ..Default::default() };
+    assert!(profile.audio_config_for(&pcm_path).is_err());
+    let profile = ConversionProfile { output_format: AudioFormat::Pcm, ..Default::default() };
+    assert!(profile.convert(&pcm_path, dir.join("out.pcm")).is_err());
+
+    let _ = fs::remove_dir_all(dir);
+    println!("✅ 配置文件测试通过");
+}
@@ -0,0 +1,165 @@
+use audio_helper::{
+    trans_pcm_file_to_mp3_with_options, trans_pcm_file_to_wav_with_options, trans_wav_file_to_pcm_with_options,
+    AudioError, CancelToken, ConvertOptions, ConvertStage, Mp3Config, PcmToWavConfig, ProgressEvent,
+};
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// 创建收集进度事件的转换选项
+fn collecting_options() -> (ConvertOptions, Arc<Mutex<Vec<ProgressEvent>>>) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let sink = Arc::clone(&events);
+    let options = ConvertOptions::new().with_progress(move |event| sink.lock().unwrap().push(event));
+    (options, events)
+}
+
+/// 检查每个阶段内的进度单调递增，并以完成事件结束
+fn assert_stages_complete(events: &[ProgressEvent], stages: &[ConvertStage]) {
+    for &stage in stages {
+        let stage_events: Vec<_> = events.iter().filter(|e| e.stage == stage).collect();
+        assert!(!stage_events.is_empty(), "缺少 {:?} 阶段", stage);
+        assert!(stage_events.windows(2).all(|w| w[0].bytes_processed <= w[1].bytes_processed), "{:?} 阶段进度应单调递增", stage);
+        let last = stage_events.last().unwrap();
+        assert_eq!(last.bytes_processed, last.total_bytes, "{:?} 阶段应报告完成", stage);
+        assert_eq!(last.fraction(), 1.0);
+    }
+}
+
+/// 按出现顺序列出进度事件经过的阶段
+fn stage_order(events: &[ProgressEvent]) -> Vec<ConvertStage> {
+    events.iter().map(|e| e.stage).fold(Vec::new(), |mut order, stage| {
+        if order.last() != Some(&stage) {
+            order.push(stage);
+        }
+        order
+    })
+}
+
+/// 验证 PCM 转 WAV / MP3 及 WAV 转 PCM 的进度报告
+#[test]
+fn test_conversion_progress_events() {
+    // 3 秒 48kHz 立体声，MP3 会分多块编码
+    let pcm: Vec<u8> = (0..48000 * 3 * 2)
+        .flat_map(|i| (((i as f32 * 0.01).sin() * 8000.0) as i16).to_le_bytes())
+        .collect();
+    fs::write("progress_test.pcm", &pcm).expect("Failed to write test PCM file");
+
+    let (options, events) = collecting_options();
+    trans_pcm_file_to_wav_with_options("progress_test.pcm", "progress_test.wav", Some(PcmToWavConfig::new(48000, 2, 16)), options).expect("WAV 转换应该成功");
+    let wav_events = events.lock().unwrap().clone();
+    println!("📈 WAV 进度事件: {} 个", wav_events.len());
+    assert!(wav_events.len() > 2, "流式写入应多次报告进度");
+    assert_stages_complete(&wav_events, &[ConvertStage::Writing]);
+    assert_eq!(wav_events.last().unwrap().total_bytes, pcm.len() as u64);
+
+    // 不需要样本处理时边读边编码，只有编码和写入两个阶段
+    let (options, events) = collecting_options();
+    trans_pcm_file_to_mp3_with_options("progress_test.pcm", "progress_test.mp3", Some(Mp3Config::default()), options).expect("MP3 转换应该成功");
+    let mp3_events = events.lock().unwrap().clone();
+    println!("📈 MP3 进度事件: {} 个", mp3_events.len());
+    assert_stages_complete(&mp3_events, &[ConvertStage::Encoding, ConvertStage::Writing]);
+    let encoding_events = mp3_events.iter().filter(|e| e.stage == ConvertStage::Encoding).count();
+    assert!(encoding_events > 2, "分块编码应多次报告进度");
+    assert_eq!(stage_order(&mp3_events), vec![ConvertStage::Encoding, ConvertStage::Writing]);
+    assert_eq!(mp3_events.iter().rfind(|e| e.stage == ConvertStage::Encoding).unwrap().total_bytes, pcm.len() as u64);
+
+    // 归一化需要完整的信号，整体读入后依次经过四个阶段
+    let (options, events) = collecting_options();
+    trans_pcm_file_to_mp3_with_options("progress_test.pcm", "progress_test.mp3", Some(Mp3Config::default().with_normalize_db(-1.0)), options).expect("MP3 转换应该成功");
+    let mp3_events = events.lock().unwrap().clone();
+    assert_stages_complete(&mp3_events, &[ConvertStage::Reading, ConvertStage::Processing, ConvertStage::Encoding, ConvertStage::Writing]);
+    assert!(mp3_events.iter().filter(|e| e.stage == ConvertStage::Encoding).count() > 2, "分块编码应多次报告进度");
+    assert_eq!(stage_order(&mp3_events), vec![ConvertStage::Reading, ConvertStage::Processing, ConvertStage::Encoding, ConvertStage::Writing]);
+
+    let (options, events) = collecting_options();
+    trans_wav_file_to_pcm_with_options("progress_test.wav", "progress_test_out.pcm", options).expect("WAV 解码应该成功");
+    assert_stages_complete(&events.lock().unwrap(), &[ConvertStage::Reading, ConvertStage::Writing]);
+    assert_eq!(fs::read("progress_test_out.pcm").unwrap(), pcm);
+
+    for file in ["progress_test.pcm", "progress_test.wav", "progress_test.mp3", "progress_test_out.pcm"] {
+        let _ = fs::remove_file(file);
+    }
+}
+
+/// 创建在指定阶段开始处理数据后取消转换的选项
+fn cancel_during(stage: ConvertStage) -> ConvertOptions {
+    let token = CancelToken::new();
+    let trigger = token.clone();
+    ConvertOptions::new().with_cancel(token).with_progress(move |event| {
+        if event.stage == stage && event.bytes_processed > 0 {
+            trigger.cancel();
+        }
+    })
+}
+
+/// 验证取消：返回 AudioError::Cancelled 错误，且不留下写了一半的输出
+#[test]
+fn test_conversion_cancellation() {
+    let pcm: Vec<u8> = (0..48000 * 4 * 2)
+        .flat_map(|i| (((i as f32 * 0.01).sin() * 8000.0) as i16).to_le_bytes())
+        .collect();
+    fs::write("cancel_test.pcm", &pcm).expect("Failed to write test PCM file");
+
+    let err = trans_pcm_file_to_mp3_with_options("cancel_test.pcm", "cancel_test.mp3", Some(Mp3Config::default()), cancel_during(ConvertStage::Encoding)).unwrap_err();
+    println!("🛑 MP3 取消: {}", err);
+    assert!(matches!(err.downcast_ref::<AudioError>(), Some(AudioError::Cancelled)));
+    assert!(!Path::new("cancel_test.mp3").exists());
+
+    // 流式写入 WAV 时取消，已创建的输出文件被删除
+    let err = trans_pcm_file_to_wav_with_options("cancel_test.pcm", "cancel_test.wav", Some(PcmToWavConfig::new(48000, 2, 16)), cancel_during(ConvertStage::Writing)).unwrap_err();
+    assert!(matches!(err.downcast_ref::<AudioError>(), Some(AudioError::Cancelled)));
+    assert!(!Path::new("cancel_test.wav").exists());
+
+    // 需要样本处理的 WAV 在读取阶段取消
+    let config = PcmToWavConfig::new(48000, 2, 16).with_gain_db(-6.0);
+    let err = trans_pcm_file_to_wav_with_options("cancel_test.pcm", "cancel_test.wav", Some(config), cancel_during(ConvertStage::Reading)).unwrap_err();
+    assert!(matches!(err.downcast_ref::<AudioError>(), Some(AudioError::Cancelled)));
+    assert!(!Path::new("cancel_test.wav").exists());
+
+    // 转换开始前已取消
+    trans_pcm_file_to_wav_with_options("cancel_test.pcm", "cancel_test.wav", Some(PcmToWavConfig::new(48000, 2, 16)), ConvertOptions::new()).expect("WAV 转换应该成功");
+    let token = CancelToken::new();
+    token.cancel();
+    let err = trans_wav_file_to_pcm_with_options("cancel_test.wav", "cancel_test_out.pcm", ConvertOptions::new().with_cancel(token)).unwrap_err();
+    assert!(matches!(err.downcast_ref::<AudioError>(), Some(AudioError::Cancelled)));
+    assert!(!Path::new("cancel_test_out.pcm").exists());
+
+    let _ = fs::remove_file("cancel_test.pcm");
+    let _ = fs::remove_file("cancel_test.wav");
+}
+
+/// 验证原子写入：转换被取消或失败时已有的输出文件保持不变，也不留下临时文件
+#[test]
+fn test_failed_conversion_keeps_existing_output() {
+    let dir = Path::new("atomic_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm: Vec<u8> = (0..48000 * 4 * 2)
+        .flat_map(|i| (((i as f32 * 0.01).sin() * 8000.0) as i16).to_le_bytes())
+        .collect();
+    let input = dir.join("input.pcm");
+    fs::write(&input, &pcm).expect("Failed to write test PCM file");
+    let wav_output = dir.join("existing.wav");
+    let mp3_output = dir.join("existing.mp3");
+    fs::write(&wav_output, b"previous wav").unwrap();
+    fs::write(&mp3_output, b"previous mp3").unwrap();
+
+    let err = trans_pcm_file_to_wav_with_options(&input, &wav_output, Some(PcmToWavConfig::new(48000, 2, 16)), cancel_during(ConvertStage::Writing)).unwrap_err();
+    assert!(matches!(err.downcast_ref::<AudioError>(), Some(AudioError::Cancelled)));
+    assert_eq!(fs::read(&wav_output).unwrap(), b"previous wav");
+
+    let err = trans_pcm_file_to_mp3_with_options(&input, &mp3_output, Some(Mp3Config::default()), cancel_during(ConvertStage::Encoding)).unwrap_err();
+    assert!(matches!(err.downcast_ref::<AudioError>(), Some(AudioError::Cancelled)));
+    assert_eq!(fs::read(&mp3_output).unwrap(), b"previous mp3");
+
+    // 成功的转换替换已有的输出
+    let report = trans_pcm_file_to_wav_with_options(&input, &wav_output, Some(PcmToWavConfig::new(48000, 2, 16)), ConvertOptions::new()).expect("WAV 转换应该成功");
+    assert_eq!(fs::metadata(&wav_output).unwrap().len(), report.output_bytes);
+
+    let leftovers: Vec<_> = fs::read_dir(dir).unwrap().filter_map(Result::ok).map(|entry| entry.file_name()).collect();
+    println!("📁 目录内容: {:?}", leftovers);
+    assert_eq!(leftovers.len(), 3, "不应留下临时文件");
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,90 @@
+use audio_helper::{
+    convert, probe_audio, probe_format, read_wav_file, AudioConfig, AudioDecoder, AudioPipeline, DetectedFormat, FormatHandler,
+    FormatReader, FormatRegistry, PcmDecoder,
+};
+use std::fs;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// 测试用的容器：8 字节魔数加 8 字节采样率和声道数，之后是 16 位小端 PCM
+struct TaggedPcm;
+
+impl FormatHandler for TaggedPcm {
+    fn detect(&self, header: &[u8]) -> bool {
+        header.starts_with(b"TAGGED00")
+    }
+
+    fn decoder(&self, mut reader: Box<dyn FormatReader>) -> Result<Box<dyn AudioDecoder>, Box<dyn std::error::Error>> {
+        let mut header = [0u8; 16];
+        reader.read_exact(&mut header)?;
+        let sample_rate = u32::from_le_bytes(header[8..12].try_into()?);
+        let channels = header[12];
+        Ok(Box::new(PcmDecoder::new(reader, AudioConfig::new(sample_rate, channels, 16))?))
+    }
+}
+
+/// 没有文件头的格式，只能按扩展名识别：8kHz 单声道 16 位大端
+struct BigEndianPcm;
+
+impl FormatHandler for BigEndianPcm {
+    fn decoder(&self, mut reader: Box<dyn FormatReader>) -> Result<Box<dyn AudioDecoder>, Box<dyn std::error::Error>> {
+        reader.seek(SeekFrom::Start(0))?;
+        let config = AudioConfig::new(8000, 1, 16).with_endianness(audio_helper::Endianness::Big);
+        Ok(Box::new(PcmDecoder::new(reader, config)?))
+    }
+}
+
+fn tagged_file(samples: &[i16], sample_rate: u32, channels: u8) -> Vec<u8> {
+    let mut data = b"TAGGED00".to_vec();
+    data.extend_from_slice(&sample_rate.to_le_bytes());
+    data.extend_from_slice(&[channels, 0, 0, 0]);
+    data.extend(samples.iter().flat_map(|s| s.to_le_bytes()));
+    data
+}
+
+/// 验证注册的格式参与识别、信息读取、转换和流水线
+#[test]
+fn test_registered_format() {
+    let dir = Path::new("registry_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+
+    FormatRegistry::register(".Tagged", Box::new(TaggedPcm)).expect("注册应该成功");
+    FormatRegistry::register("bepcm", Box::new(BigEndianPcm)).unwrap();
+    assert!(FormatRegistry::contains("tagged"));
+    assert_eq!(FormatRegistry::extensions(), vec!["bepcm".to_string(), "tagged".to_string()]);
+
+    // 按文件头识别，与扩展名无关
+    let samples: Vec<i16> = (0..22050 * 2).map(|i| ((i as f32 * 0.05).sin() * 8000.0) as i16).collect();
+    let input = dir.join("recording.bin");
+    fs::write(&input, tagged_file(&samples, 22050, 2)).unwrap();
+    assert_eq!(probe_format(&input).unwrap(), DetectedFormat::Registered("tagged"));
+    let info = probe_audio(&input).unwrap();
+    assert_eq!(info.config, Some(AudioConfig::new(22050, 2, 16)));
+    assert!(info.to_json().contains("\"format\": \"tagged\""));
+
+    let report = convert(&input, dir.join("decoded.wav"), None).expect("注册格式转 WAV 应该成功");
+    assert_eq!(report.duration.as_millis(), 1000);
+    let decoded = read_wav_file(dir.join("decoded.wav")).unwrap();
+    assert_eq!(decoded.data, samples.iter().flat_map(|s| s.to_le_bytes()).collect::<Vec<u8>>());
+    println!("🧩 注册格式 -> WAV: {} 字节", report.output_bytes);
+
+    let report = AudioPipeline::from(&input).downmix_mono().run(dir.join("mono.mp3")).expect("流水线应该支持注册格式");
+    assert_eq!(report.config_used, AudioConfig::new(22050, 1, 16));
+
+    // 没有文件头的格式按扩展名识别
+    let big_endian = dir.join("voice.BEPCM");
+    fs::write(&big_endian, [0x12u8, 0x34, 0xFF, 0xFE]).unwrap();
+    assert_eq!(probe_format(&big_endian).unwrap(), DetectedFormat::Registered("bepcm"));
+    convert(&big_endian, dir.join("voice.pcm"), None).unwrap();
+    assert_eq!(fs::read(dir.join("voice.pcm")).unwrap(), vec![0x34, 0x12, 0xFE, 0xFF]);
+
+    // 内置格式的扩展名不能注册，注销后恢复为未知格式
+    assert!(FormatRegistry::register("wav", Box::new(TaggedPcm)).is_err());
+    assert!(FormatRegistry::register(" ", Box::new(TaggedPcm)).is_err());
+    assert!(FormatRegistry::unregister("bepcm"));
+    assert!(!FormatRegistry::unregister("bepcm"));
+    assert_eq!(probe_format(&big_endian).unwrap(), DetectedFormat::Unknown);
+
+    let _ = fs::remove_dir_all(dir);
+}
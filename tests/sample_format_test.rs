@@ -0,0 +1,146 @@
+use audio_helper::{trans_pcm_file_to_wav, trans_pcm_file_to_mp3, trans_pcm_bytes_to_wav, read_wav_file, encode_samples_f32, decode_samples_f32, generate_sine, swap_sample_bytes, AudioConfig, PcmToWavConfig, Mp3Config, Mp3Bitrate, AudioQuality, SampleFormat, Endianness};
+use std::fs;
+use std::time::Duration;
+
+/// 生成 440Hz 正弦波 f32 样本
+fn sine_f32(sample_rate: u32, channels: u8, seconds: f32) -> Vec<f32> {
+    let config = AudioConfig::new(sample_rate, channels, 32).with_sample_format(SampleFormat::F32);
+    decode_samples_f32(&generate_sine(440.0, Duration::from_secs_f32(seconds), &config), SampleFormat::F32)
+}
+
+/// 读取 WAV fmt 块中的格式标签、位深度和（扩展格式时的）子格式标签
+fn read_fmt(wav: &[u8]) -> (u16, u16, Option<u16>) {
+    let u16_at = |at: usize| u16::from_le_bytes([wav[at], wav[at + 1]]);
+    let format_tag = u16_at(20);
+    let bits = u16_at(34);
+    let sub_format = if format_tag == 0xFFFE { Some(u16_at(44)) } else { None };
+    (format_tag, bits, sub_format)
+}
+
+/// 验证各种样本格式写入 WAV 时的 fmt 块
+#[test]
+fn test_sample_formats_to_wav() {
+    let samples = sine_f32(16000, 1, 0.5);
+    let cases = vec![
+        (SampleFormat::U8, 1u16, 8u16, None),
+        (SampleFormat::S16, 1, 16, None),
+        (SampleFormat::S24, 0xFFFE, 24, Some(1u16)),
+        (SampleFormat::S32, 0xFFFE, 32, Some(1)),
+        (SampleFormat::F32, 0xFFFE, 32, Some(3)),
+        (SampleFormat::Alaw, 6, 8, None),
+        (SampleFormat::Ulaw, 7, 8, None),
+    ];
+
+    for (format, expected_tag, expected_bits, expected_sub) in cases {
+        let input_path = format!("sample_format_{:?}.pcm", format);
+        let output_path = format!("sample_format_{:?}.wav", format);
+        let data = encode_samples_f32(&samples, format);
+        fs::write(&input_path, &data).expect("Failed to write test PCM file");
+
+        let config = PcmToWavConfig::new(16000, 1, 16).with_sample_format(format);
+        let result = trans_pcm_file_to_wav(&input_path, &output_path, Some(config));
+        assert!(result.is_ok(), "{:?} 转换应该成功: {:?}", format, result.err());
+
+        let wav = fs::read(&output_path).expect("Failed to read WAV output");
+        let (tag, bits, sub) = read_fmt(&wav);
+        println!("{:?}: 格式标签 0x{:04X}, {} 位, 子格式 {:?}", format, tag, bits, sub);
+        assert_eq!(tag, expected_tag, "{:?} 格式标签不正确", format);
+        assert_eq!(bits, expected_bits, "{:?} 位深度不正确", format);
+        assert_eq!(sub, expected_sub, "{:?} 子格式不正确", format);
+        assert!(wav.ends_with(&data), "{:?} 数据块应该原样保留", format);
+
+        let _ = fs::remove_file(&input_path);
+        let _ = fs::remove_file(&output_path);
+    }
+}
+
+/// 验证奇数长度的 8 位数据块之后补齐填充字节，RIFF 大小包含填充字节
+#[test]
+fn test_odd_length_u8_wav_padding() {
+    let input_path = "sample_format_odd_u8.pcm";
+    let output_path = "sample_format_odd_u8.wav";
+    let data = encode_samples_f32(&sine_f32(8000, 1, 0.5), SampleFormat::U8);
+    let data = &data[..data.len() - 1];
+    assert_eq!(data.len() % 2, 1);
+    fs::write(input_path, data).expect("Failed to write test PCM file");
+
+    let config = PcmToWavConfig::new(8000, 1, 8).with_sample_format(SampleFormat::U8);
+    trans_pcm_file_to_wav(input_path, output_path, Some(config.clone())).expect("WAV 转换应该成功");
+    let wav = fs::read(output_path).expect("Failed to read WAV output");
+    let u32_at = |at: usize| u32::from_le_bytes([wav[at], wav[at + 1], wav[at + 2], wav[at + 3]]);
+    assert_eq!(wav.len(), 44 + data.len() + 1, "数据块之后应该有一个填充字节");
+    assert_eq!(wav.last(), Some(&0));
+    assert_eq!(u32_at(4) as usize, wav.len() - 8, "RIFF 大小应该包含填充字节");
+    assert_eq!(u32_at(40) as usize, data.len(), "data 块大小不包含填充字节");
+    assert_eq!(read_wav_file(output_path).unwrap().data, data);
+    assert_eq!(trans_pcm_bytes_to_wav(data, Some(config)).unwrap(), wav, "内存转换应该得到相同的文件");
+    println!("✓ {} 字节的 8 位数据块补齐到偶数长度", data.len());
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证各种样本格式都能正确编码为 MP3，且与 16 位输入结果一致
+#[test]
+fn test_sample_formats_to_mp3() {
+    let samples = sine_f32(44100, 2, 1.0);
+    let mut sizes = Vec::new();
+
+    for format in [SampleFormat::S16, SampleFormat::S24, SampleFormat::S32, SampleFormat::F32, SampleFormat::U8, SampleFormat::Ulaw, SampleFormat::Alaw] {
+        let input_path = format!("sample_format_mp3_{:?}.pcm", format);
+        let output_path = format!("sample_format_mp3_{:?}.mp3", format);
+        fs::write(&input_path, encode_samples_f32(&samples, format)).expect("Failed to write test PCM file");
+
+        let config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium)
+            .with_sample_format(format);
+        let result = trans_pcm_file_to_mp3(&input_path, &output_path, Some(config));
+        assert!(result.is_ok(), "{:?} 转换应该成功: {:?}", format, result.err());
+
+        let size = fs::metadata(&output_path).expect("Failed to read MP3 output").len();
+        println!("{:?}: MP3 大小 {} 字节", format, size);
+        sizes.push(size);
+
+        let _ = fs::remove_file(&input_path);
+        let _ = fs::remove_file(&output_path);
+    }
+
+    // CBR 下相同时长的音频应该得到相同大小的 MP3
+    assert!(sizes.iter().all(|&s| s == sizes[0]), "不同样本格式的 MP3 大小应该一致: {:?}", sizes);
+}
+
+/// 验证大端 PCM 输入会被转换为小端后写入 WAV，并能正确编码为 MP3
+#[test]
+fn test_big_endian_input() {
+    let samples = sine_f32(16000, 1, 0.5);
+    let little = encode_samples_f32(&samples, SampleFormat::S16);
+    let mut big = little.clone();
+    swap_sample_bytes(&mut big, SampleFormat::S16);
+
+    let input_path = "sample_format_big_endian.pcm";
+    let wav_path = "sample_format_big_endian.wav";
+    fs::write(input_path, &big).expect("Failed to write test PCM file");
+
+    let config = PcmToWavConfig::new(16000, 1, 16).with_endianness(Endianness::Big);
+    let result = trans_pcm_file_to_wav(input_path, wav_path, Some(config));
+    assert!(result.is_ok(), "大端 PCM 转 WAV 应该成功: {:?}", result.err());
+    let wav = fs::read(wav_path).expect("Failed to read WAV output");
+    assert!(wav.ends_with(&little), "WAV 数据块应该是小端样本");
+    println!("✓ 大端 PCM -> WAV 字节序转换正确");
+
+    // 大端与小端输入编码得到的 MP3 应该完全一致
+    let little_path = "sample_format_little_endian.pcm";
+    fs::write(little_path, &little).expect("Failed to write test PCM file");
+    let base = Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::Medium);
+    trans_pcm_file_to_mp3(input_path, "sample_format_big_endian.mp3", Some(base.clone().with_endianness(Endianness::Big)))
+        .expect("大端 PCM 转 MP3 应该成功");
+    trans_pcm_file_to_mp3(little_path, "sample_format_little_endian.mp3", Some(base))
+        .expect("小端 PCM 转 MP3 应该成功");
+    let big_mp3 = fs::read("sample_format_big_endian.mp3").expect("Failed to read MP3 output");
+    let little_mp3 = fs::read("sample_format_little_endian.mp3").expect("Failed to read MP3 output");
+    assert_eq!(big_mp3, little_mp3, "大端与小端输入应该得到相同的 MP3");
+    println!("✓ 大端 PCM -> MP3 编码结果与小端一致");
+
+    for path in [input_path, wav_path, little_path, "sample_format_big_endian.mp3", "sample_format_little_endian.mp3"] {
+        let _ = fs::remove_file(path);
+    }
+}
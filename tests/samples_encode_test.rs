@@ -0,0 +1,26 @@
+use audio_helper::{
+    decode_samples_i16, generate_sine, trans_pcm_bytes_to_mp3, trans_pcm_bytes_to_wav, trans_pcm_samples_to_mp3, trans_pcm_samples_to_wav,
+    AudioConfig, AudioQuality, Mp3Bitrate, Mp3Config, PcmToWavConfig, SampleFormat,
+};
+use std::time::Duration;
+
+/// 验证直接从 i16 样本编码的结果与字节接口一致
+#[test]
+fn test_encode_from_samples() {
+    let bytes = generate_sine(440.0, Duration::from_secs(1), &AudioConfig::new(44100, 2, 16));
+    let samples = decode_samples_i16(&bytes, SampleFormat::S16);
+
+    let wav_config = PcmToWavConfig::new(44100, 2, 16);
+    let mut wav = Vec::new();
+    let written = trans_pcm_samples_to_wav(&samples, &wav_config, &mut wav).expect("样本转 WAV 应该成功");
+    assert_eq!(written, wav.len() as u64);
+    assert_eq!(wav, trans_pcm_bytes_to_wav(&bytes, Some(wav_config)).unwrap());
+    println!("🎵 样本 -> WAV: {} 字节", written);
+
+    let mp3_config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps128, AudioQuality::Medium);
+    let mut mp3 = Vec::new();
+    let written = trans_pcm_samples_to_mp3(&samples, &mp3_config, &mut mp3).expect("样本转 MP3 应该成功");
+    assert_eq!(written, mp3.len() as u64);
+    assert_eq!(mp3, trans_pcm_bytes_to_mp3(&bytes, Some(mp3_config)).unwrap());
+    println!("🎵 样本 -> MP3: {} 字节", written);
+}
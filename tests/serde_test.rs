@@ -0,0 +1,51 @@
+#![cfg(feature = "serde")]
+
+use audio_helper::{
+    infer_audio_config_from_filename, AudioConfig, AudioFormat, AudioQuality, Endianness, FadeConfig, Mp3Bitrate, Mp3Config,
+    Mp3RateMode, PcmToWavConfig, SampleFormat, VbrQuality,
+};
+
+/// 验证配置结构体经 JSON 往返后保持不变
+#[test]
+fn test_config_json_round_trip() {
+    let config = infer_audio_config_from_filename("recording_48k24bit双声道.pcm")
+        .with_endianness(Endianness::Big)
+        .with_fade(FadeConfig::new(10, 20))
+        .with_gain_db(-3.0);
+    let json = serde_json::to_string(&config).unwrap();
+    println!("📄 AudioConfig: {}", json);
+    assert_eq!(serde_json::from_str::<AudioConfig>(&json).unwrap(), config);
+
+    let mp3_config = Mp3Config::new(44100, 2, Mp3Bitrate::Kbps192, AudioQuality::High).with_rate_mode(Mp3RateMode::Vbr(VbrQuality::V2));
+    let json = serde_json::to_string(&mp3_config).unwrap();
+    assert_eq!(serde_json::from_str::<Mp3Config>(&json).unwrap(), mp3_config);
+
+    let wav_config = PcmToWavConfig::new(16000, 1, 16);
+    let json = serde_json::to_string(&wav_config).unwrap();
+    assert_eq!(serde_json::from_str::<PcmToWavConfig>(&json).unwrap(), wav_config);
+
+    for format in [AudioFormat::Wav, AudioFormat::Mp3, AudioFormat::Pcm] {
+        let json = serde_json::to_string(&format).unwrap();
+        assert_eq!(serde_json::from_str::<AudioFormat>(&json).unwrap(), format);
+    }
+    println!("✅ 配置 JSON 往返一致");
+}
+
+/// 验证只包含部分字段的 JSON 使用默认值补齐
+#[test]
+fn test_partial_config_json_uses_defaults() {
+    let config: AudioConfig = serde_json::from_str(r#"{"sample_rate": 16000, "channels": 1}"#).unwrap();
+    assert_eq!(config.sample_rate, 16000);
+    assert_eq!(config.channels, 1);
+    assert_eq!(config.bits_per_sample, 16);
+    assert_eq!(config.sample_format, SampleFormat::S16);
+    assert_eq!(config.endianness, Endianness::Little);
+
+    let mp3_config: Mp3Config = serde_json::from_str(r#"{"rate_mode": {"Cbr": "Kbps320"}, "quality": "Best"}"#).unwrap();
+    assert_eq!(mp3_config.rate_mode, Mp3RateMode::Cbr(Mp3Bitrate::Kbps320));
+    assert_eq!(mp3_config.quality, AudioQuality::Best);
+    assert_eq!(mp3_config.sample_rate, Mp3Config::default().sample_rate);
+
+    assert!(serde_json::from_str::<AudioConfig>(r#"{"sample_format": "S12"}"#).is_err());
+    println!("✅ 缺省字段使用默认值");
+}
@@ -0,0 +1,112 @@
+use audio_helper::{trans_pcm_file_to_wav, trim_silence, detect_silence, split_at_silence, read_wav_file, PcmToWavConfig, SplitOptions};
+use std::fs;
+
+/// 生成 静音 + 正弦波 + 静音 的 16 位单声道样本
+fn speech_like(sample_rate: u32, lead_ms: u32, tone_ms: u32, tail_ms: u32) -> Vec<i16> {
+    let frames = |ms: u32| (sample_rate * ms / 1000) as usize;
+    let mut samples = vec![0i16; frames(lead_ms)];
+    samples.extend((0..frames(tone_ms)).map(|i| ((i as f32 * 0.2).sin() * 8000.0) as i16));
+    samples.extend(vec![0i16; frames(tail_ms)]);
+    samples
+}
+
+fn to_bytes(samples: &[i16]) -> Vec<u8> {
+    samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+}
+
+/// 验证去除 WAV 文件首尾静音
+#[test]
+fn test_trim_silence_wav() {
+    let pcm_path = "silence_trim_input.pcm";
+    let wav_path = "silence_trim_input.wav";
+    let output_path = "silence_trim_output.wav";
+    fs::write(pcm_path, to_bytes(&speech_like(16000, 500, 1000, 300))).expect("Failed to write test PCM file");
+    trans_pcm_file_to_wav(pcm_path, wav_path, Some(PcmToWavConfig::new(16000, 1, 16))).expect("WAV 转换应该成功");
+
+    trim_silence(wav_path, output_path, -50.0, 100).expect("去除静音应该成功");
+    let audio = read_wav_file(output_path).expect("Failed to read WAV output");
+    let duration_ms = audio.frames() as u32 * 1000 / audio.config.sample_rate;
+    println!("去除静音后时长: {}ms", duration_ms);
+    assert!((990..=1020).contains(&duration_ms), "去除静音后应该只剩约 1 秒信号，实际 {}ms", duration_ms);
+
+    // 静音短于最短时长时不去除
+    trim_silence(wav_path, output_path, -50.0, 400).expect("去除静音应该成功");
+    let duration_ms = read_wav_file(output_path).unwrap().frames() as u32 * 1000 / 16000;
+    println!("最短 400ms 时的时长: {}ms", duration_ms);
+    assert!((1290..=1320).contains(&duration_ms), "只应去除 500ms 的开头静音，实际 {}ms", duration_ms);
+
+    for path in [pcm_path, wav_path, output_path] {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 验证 PCM 输入从文件名推断参数并输出 PCM
+#[test]
+fn test_trim_silence_pcm() {
+    let input_path = "silence_trim_8k16bit单声道.pcm";
+    let output_path = "silence_trim_8k16bit单声道_trimmed.pcm";
+    fs::write(input_path, to_bytes(&speech_like(8000, 200, 500, 200))).expect("Failed to write test PCM file");
+
+    trim_silence(input_path, output_path, -50.0, 0).expect("去除静音应该成功");
+    let frames = fs::metadata(output_path).unwrap().len() / 2;
+    println!("PCM 去除静音后帧数: {}", frames);
+    assert!((3990..=4080).contains(&frames), "应该只保留约 500ms 的信号，实际 {} 帧", frames);
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(output_path);
+}
+
+/// 验证静音区间检测
+#[test]
+fn test_detect_silence_regions() {
+    let input_path = "silence_regions_16k16bit单声道.pcm";
+    // 300ms 静音 + 500ms 信号 + 400ms 静音 + 500ms 信号 + 50ms 静音
+    let mut samples = speech_like(16000, 300, 500, 400);
+    samples.extend(speech_like(16000, 0, 500, 50));
+    fs::write(input_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+
+    let regions = detect_silence(input_path, -50.0, 100).expect("静音检测应该成功");
+    println!("静音区间: {:?}", regions);
+    assert_eq!(regions.len(), 2, "50ms 的结尾静音短于最短时长，不应返回");
+    assert_eq!(regions[0].start_ms, 0);
+    assert!((290..=310).contains(&regions[0].end_ms));
+    assert!((800..=810).contains(&regions[1].start_ms));
+    assert!((390..=410).contains(&regions[1].duration_ms()));
+
+    let _ = fs::remove_file(input_path);
+}
+
+/// 验证在静音处切分长录音
+#[test]
+fn test_split_at_silence() {
+    let input_path = "silence_split_16k16bit单声道.pcm";
+    let wav_path = "silence_split.wav";
+    let output_dir = "silence_split_output";
+    // 500ms 开头静音，三段 600ms 的语音，中间间隔 700ms 和 150ms 的静音
+    let mut samples = speech_like(16000, 500, 600, 700);
+    samples.extend(speech_like(16000, 0, 600, 150));
+    samples.extend(speech_like(16000, 0, 600, 300));
+    fs::write(input_path, to_bytes(&samples)).expect("Failed to write test PCM file");
+
+    let options = SplitOptions::default().with_min_silence_ms(400).with_padding_ms(50);
+    let outputs = split_at_silence(input_path, output_dir, &options).expect("切分应该成功");
+    println!("PCM 切分结果: {:?}", outputs);
+    assert_eq!(outputs.len(), 2, "150ms 的停顿不应该切分");
+    assert!(outputs[0].to_string_lossy().ends_with("silence_split_16k16bit单声道_001.pcm"));
+    let first_ms = fs::metadata(&outputs[0]).unwrap().len() / 2 * 1000 / 16000;
+    assert!((690..=720).contains(&first_ms), "第一段应该约为 600ms 加前后各 50ms 留白，实际 {}ms", first_ms);
+
+    // WAV 输入输出 WAV 片段
+    trans_pcm_file_to_wav(input_path, wav_path, Some(PcmToWavConfig::new(16000, 1, 16))).expect("WAV 转换应该成功");
+    let outputs = split_at_silence(wav_path, output_dir, &options).expect("切分应该成功");
+    assert_eq!(outputs.len(), 2);
+    let second = read_wav_file(&outputs[1]).expect("切分后的 WAV 应该可以读取");
+    let second_ms = second.frames() as u32 * 1000 / 16000;
+    println!("第二段 WAV 时长: {}ms", second_ms);
+    // 50ms 留白 + 两段语音和短停顿 + 300ms 结尾静音（短于最短静音，不会被切掉）
+    assert!((1690..=1710).contains(&second_ms), "第二段应该包含两段语音和短停顿，实际 {}ms", second_ms);
+
+    let _ = fs::remove_file(input_path);
+    let _ = fs::remove_file(wav_path);
+    let _ = fs::remove_dir_all(output_dir);
+}
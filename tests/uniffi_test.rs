@@ -0,0 +1,50 @@
+#![cfg(feature = "uniffi")]
+
+use audio_helper::uniffi_api::{
+    auto_convert, infer_format_from_filename, pcm_bytes_to_mp3, pcm_bytes_to_wav, pcm_to_wav, probe, AudioHelperError, FileFormat, Mp3Quality,
+    Mp3Settings, OutputFormat, PcmFormat,
+};
+use std::fs;
+use std::path::Path;
+
+/// 验证 UniFFI 导出的函数返回记录类型和分类的错误
+#[test]
+fn test_uniffi_api() {
+    let dir = Path::new("uniffi_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm: Vec<u8> = (0..16000i32).flat_map(|i| (((i % 100) * 300 - 15000) as i16).to_le_bytes()).collect();
+    let path = |name: &str| dir.join(name).to_str().unwrap().to_string();
+
+    let format = PcmFormat { sample_rate: 16000, channels: 1, bits_per_sample: 16 };
+    let wav = pcm_bytes_to_wav(pcm.clone(), Some(format)).unwrap();
+    assert_eq!(wav.len(), pcm.len() + 44);
+    let settings = Mp3Settings { sample_rate: 16000, channels: 1, bitrate_kbps: 64, quality: Mp3Quality::Medium };
+    let mp3 = pcm_bytes_to_mp3(pcm.clone(), Some(settings)).unwrap();
+    assert_eq!(mp3[0], 0xFF);
+    println!("🎵 WAV {} 字节, MP3 {} 字节", wav.len(), mp3.len());
+
+    fs::write(path("clip_16k16bit单声道.pcm"), &pcm).unwrap();
+    assert_eq!(auto_convert(path("clip_16k16bit单声道.pcm"), path("auto.wav"), OutputFormat::Wav).unwrap(), format);
+    let info = probe(path("auto.wav")).unwrap();
+    println!("📋 {:?}", info);
+    assert_eq!(info.format, FileFormat::Wav);
+    assert_eq!(info.pcm_format, Some(format));
+    assert_eq!(info.duration_ms, Some(1000));
+    assert_eq!((info.data_size, info.file_size), (32000, 32044));
+
+    let inferred = infer_format_from_filename("song_48k24bit双声道.pcm".to_string());
+    assert_eq!(inferred, PcmFormat { sample_rate: 48000, channels: 2, bits_per_sample: 24 });
+
+    // 错误按类别区分
+    let missing = pcm_to_wav(path("missing.pcm"), path("missing.wav"), None).unwrap_err();
+    assert!(matches!(missing, AudioHelperError::FileNotFound { .. }), "{:?}", missing);
+    let bad_bitrate = pcm_bytes_to_mp3(pcm.clone(), Some(Mp3Settings { bitrate_kbps: 100, ..settings })).unwrap_err();
+    assert!(matches!(bad_bitrate, AudioHelperError::UnsupportedConfig { .. }), "{:?}", bad_bitrate);
+    let zero_channels = pcm_bytes_to_wav(pcm, Some(PcmFormat { channels: 0, ..format })).unwrap_err();
+    assert!(matches!(zero_channels, AudioHelperError::UnsupportedConfig { .. }), "{:?}", zero_channels);
+    println!("❌ {}", zero_channels);
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ UniFFI 接口测试通过");
+}
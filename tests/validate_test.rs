@@ -0,0 +1,109 @@
+use audio_helper::{
+    auto_convert_pcm, trans_pcm_file_to_mp3, trans_pcm_file_to_wav, AudioConfig, AudioFormat, AudioQuality, Mp3Bitrate, Mp3Config,
+    AudioError, ChannelLayout, ErrorKind, Mp3RateMode, PcmToWavConfig, Presets, SampleFormat,
+};
+use std::fs;
+use std::path::Path;
+
+/// 验证 validate 拒绝无法编码的参数组合，错误信息包含字段名
+#[test]
+fn test_validate_rejects_impossible_configs() {
+    let mp3_cases = [
+        (Mp3Config::new(44100, 0, Mp3Bitrate::Kbps192, AudioQuality::High), "channels"),
+        (Mp3Config::new(44100, 6, Mp3Bitrate::Kbps192, AudioQuality::High), "channels"),
+        (Mp3Config::new(0, 2, Mp3Bitrate::Kbps192, AudioQuality::High), "sample_rate"),
+        (Mp3Config::new(17000, 2, Mp3Bitrate::Kbps128, AudioQuality::High).with_resample(false), "sample_rate"),
+        // MPEG-2 采样率最高 160kbps，MPEG-1 采样率最低 32kbps
+        (Mp3Config::new(16000, 1, Mp3Bitrate::Kbps192, AudioQuality::High), "rate_mode"),
+        (Mp3Config::new(48000, 1, Mp3Bitrate::Kbps128, AudioQuality::High).with_rate_mode(Mp3RateMode::Abr(16)), "rate_mode"),
+        (Mp3Config::new(22050, 1, Mp3Bitrate::Kbps128, AudioQuality::High).with_rate_mode(Mp3RateMode::Abr(256)), "rate_mode"),
+        (Mp3Config::new(44100, 2, Mp3Bitrate::Kbps192, AudioQuality::High).with_target_channels(0), "target_channels"),
+    ];
+    for (config, field) in mp3_cases {
+        let error = config.validate().expect_err("非法 MP3 配置应该失败").to_string();
+        println!("❌ {}", error);
+        assert!(error.contains(field), "错误信息应包含字段名 {}: {}", field, error);
+    }
+
+    let mut audio_config = AudioConfig::new(16000, 1, 16);
+    audio_config.bits_per_sample = 12;
+    assert!(audio_config.validate().unwrap_err().to_string().contains("bits_per_sample"));
+    audio_config.bits_per_sample = 24;
+    assert!(audio_config.validate().unwrap_err().to_string().contains("bits_per_sample"));
+    assert!(AudioConfig::new(16000, 0, 16).validate().unwrap_err().to_string().contains("channels"));
+    assert!(PcmToWavConfig::new(16000, 1, 20).validate().unwrap_err().to_string().contains("bits_per_sample"));
+    assert!(PcmToWavConfig::new(0, 1, 16).validate().unwrap_err().to_string().contains("sample_rate"));
+    assert!(PcmToWavConfig::new(16000, 4, 16).with_ima_adpcm(true).validate().unwrap_err().to_string().contains("ima_adpcm"));
+    // 校验错误可按类型区分
+    assert!(matches!(PcmToWavConfig::new(0, 1, 16).validate(), Err(AudioError::InvalidConfig(_))));
+
+    // 合法组合：多声道混缩为立体声、低采样率配低码率、非标准采样率重采样
+    assert!(Mp3Config::new(48000, 6, Mp3Bitrate::Kbps192, AudioQuality::High).with_target_channels(2).validate().is_ok());
+    assert!(Mp3Config::new(16000, 1, Mp3Bitrate::Kbps64, AudioQuality::High).validate().is_ok());
+    assert!(Mp3Config::new(17000, 1, Mp3Bitrate::Kbps128, AudioQuality::High).validate().is_ok());
+    assert!(AudioConfig::new(48000, 2, 24).with_sample_format(SampleFormat::F32).validate().is_ok());
+    println!("✅ 配置校验测试通过");
+}
+
+/// 验证转换函数在编码前校验配置，不产生输出文件
+#[test]
+fn test_conversions_validate_config() {
+    let dir = Path::new("validate_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let pcm_path = dir.join("clip.pcm");
+    let bytes: Vec<u8> = (0..3200i16).flat_map(|i| (i * 10).to_le_bytes()).collect();
+    fs::write(&pcm_path, &bytes).expect("Failed to write test PCM file");
+
+    let mp3_path = dir.join("clip.mp3");
+    let config = Mp3Config::new(16000, 1, Mp3Bitrate::Kbps320, AudioQuality::High);
+    let error = trans_pcm_file_to_mp3(&pcm_path, &mp3_path, Some(config)).expect_err("码率与采样率不匹配应该失败");
+    println!("❌ {}", error);
+    assert!(error.to_string().contains("rate_mode"));
+    assert!(!mp3_path.exists());
+
+    let wav_path = dir.join("clip.wav");
+    let error = trans_pcm_file_to_wav(&pcm_path, &wav_path, Some(PcmToWavConfig::new(16000, 0, 16))).expect_err("0 声道应该失败");
+    assert!(error.to_string().contains("channels"));
+    assert!(!wav_path.exists());
+
+    // 从文件名推断的 16kHz 配置使用适合该采样率的默认码率
+    let inferred_path = dir.join("voice_16k16bit单声道.pcm");
+    fs::write(&inferred_path, &bytes).unwrap();
+    auto_convert_pcm(&inferred_path, dir.join("voice.mp3"), AudioFormat::Mp3).expect("推断配置应该可以编码");
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ 转换前配置校验测试通过");
+}
+
+/// 验证错误类型：可区分无效输入、IO 错误和参数错误
+#[test]
+fn test_errors_are_typed() {
+    let dir = Path::new("typed_error_test_dir");
+    let _ = fs::remove_dir_all(dir);
+    fs::create_dir_all(dir).expect("Failed to create test directory");
+    let bytes: Vec<u8> = (0..3200i16).flat_map(|i| (i * 10).to_le_bytes()).collect();
+
+    let not_pcm = dir.join("clip.dat");
+    fs::write(&not_pcm, &bytes).unwrap();
+    let error = trans_pcm_file_to_wav(&not_pcm, dir.join("clip.wav"), None).expect_err("非 .pcm 文件应该失败");
+    println!("❌ {}", error);
+    assert!(matches!(error.downcast_ref::<AudioError>(), Some(AudioError::InvalidInput(_))));
+
+    let missing = dir.join("missing.pcm");
+    let error = trans_pcm_file_to_wav(&missing, dir.join("clip.wav"), None).expect_err("不存在的文件应该失败");
+    assert_eq!(AudioError::kind(error.as_ref()), Some(ErrorKind::Io(std::io::ErrorKind::NotFound)));
+
+    let pcm_path = dir.join("clip.pcm");
+    fs::write(&pcm_path, &bytes).unwrap();
+    let error = trans_pcm_file_to_wav(&pcm_path, dir.join("clip.wav"), Some(PcmToWavConfig::new(16000, 0, 16))).expect_err("0 声道应该失败");
+    assert_eq!(AudioError::kind(error.as_ref()), Some(ErrorKind::InvalidConfig));
+
+    let error = Presets::mp3_config("no-such-preset", &AudioConfig::new(16000, 1, 16)).expect_err("未注册的预设应该失败");
+    assert_eq!(AudioError::kind(error.as_ref()), Some(ErrorKind::InvalidInput));
+    let error = "7.2".parse::<ChannelLayout>().expect_err("未知的声道布局应该失败");
+    assert!(matches!(error, AudioError::InvalidConfig(_)));
+
+    fs::remove_dir_all(dir).unwrap();
+    println!("✅ 错误类型测试通过");
+}
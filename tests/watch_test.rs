@@ -0,0 +1,51 @@
+#![cfg(feature = "watch")]
+
+use audio_helper::{read_wav_file, watch_directory, AudioFormat, WatchOptions};
+use std::fs;
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// 验证监视目录：新放入的 PCM 文件转换为 WAV，非 PCM 文件被忽略
+#[test]
+fn test_watch_directory_converts_new_files() {
+    let input_dir = Path::new("watch_test_input");
+    let output_dir = Path::new("watch_test_output");
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+    fs::create_dir_all(input_dir).expect("Failed to create test directory");
+
+    let (sender, receiver) = mpsc::channel();
+    let options = WatchOptions::new(AudioFormat::Wav)
+        .with_output_dir(output_dir)
+        .with_settle_delay(Duration::from_millis(100));
+    let watcher = watch_directory(input_dir, options, move |item| {
+        let _ = sender.send(item);
+    })
+    .expect("监视目录应该成功");
+
+    fs::write(input_dir.join("notes.txt"), "not audio").expect("Failed to write test file");
+    let bytes: Vec<u8> = (0..1600i16).flat_map(|i| (i * 10).to_le_bytes()).collect();
+    fs::write(input_dir.join("drop_16k16bit单声道.pcm"), &bytes).expect("Failed to write test PCM file");
+
+    let item = receiver.recv_timeout(Duration::from_secs(10)).expect("应该在超时前收到转换结果");
+    println!("📂 监视转换: {} -> {} ({:?})", item.input.display(), item.output.display(), item.result);
+    assert!(item.input.ends_with("drop_16k16bit单声道.pcm"));
+    assert_eq!(item.result.expect("转换应该成功").sample_rate, 16000);
+    let audio = read_wav_file(output_dir.join("drop_16k16bit单声道.wav")).expect("Failed to read output");
+    assert_eq!(audio.data, bytes);
+
+    watcher.stop();
+    // 停止后不再有其他文件被转换
+    assert!(receiver.recv_timeout(Duration::from_millis(200)).is_err());
+
+    let _ = fs::remove_dir_all(input_dir);
+    let _ = fs::remove_dir_all(output_dir);
+}
+
+/// 验证监视不存在的目录时报错
+#[test]
+fn test_watch_missing_directory() {
+    let result = watch_directory("watch_test_missing", WatchOptions::default(), |_| {});
+    assert!(result.is_err());
+}
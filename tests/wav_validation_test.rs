@@ -151,4 +151,99 @@ fn test_performance_large_files() {
         // 断言处理速度应该足够快（至少 10 MB/秒）
         assert!(throughput > 10.0, "处理速度太慢: {:.2} MB/秒", throughput);
     }
-}
\ No newline at end of file
+}
+
+/// 验证强制使用 RF64 / Wave64 容器时的输出
+#[test]
+fn test_forced_container_formats() {
+    use audio_helper::WavContainer;
+
+    let input_path = "container_test_input.pcm";
+    let pcm_data: Vec<u8> = (0..1000i16).flat_map(|i| (i * 30).to_le_bytes()).collect();
+    std::fs::write(input_path, &pcm_data).expect("Failed to write test PCM file");
+
+    let cases = vec![
+        (WavContainer::Rf64, "container_test_rf64.wav", b"RF64".to_vec(), 80 + pcm_data.len() as u64),
+        (WavContainer::Wave64, "container_test_w64.wav", b"riff".to_vec(), 104 + pcm_data.len() as u64),
+    ];
+
+    for (container, output_path, magic, expected_size) in cases {
+        let config = PcmToWavConfig::new(44100, 1, 16).with_container(container);
+        let result = trans_pcm_file_to_wav(input_path, output_path, Some(config));
+        assert!(result.is_ok(), "{:?} 转换应该成功", container);
+
+        let bytes = std::fs::read(output_path).expect("Failed to read output");
+        assert_eq!(&bytes[..4], magic.as_slice(), "{:?} 文件头不正确", container);
+        assert_eq!(bytes.len() as u64, expected_size, "{:?} 文件大小不正确", container);
+        assert_eq!(&bytes[bytes.len() - pcm_data.len()..], pcm_data.as_slice(), "PCM 数据应该原样保留");
+
+        let _ = std::fs::remove_file(output_path);
+    }
+
+    let _ = std::fs::remove_file(input_path);
+}
+
+/// 验证向已有 WAV 文件追加 PCM 数据后文件头大小正确
+#[test]
+fn test_append_pcm_to_wav() {
+    use audio_helper::{append_pcm_to_wav, read_wav_file_with_mode, trans_pcm_bytes_to_wav, AudioConfig, Endianness, SampleFormat, WavContainer, WavParseMode};
+
+    let output_path = "append_test.wav";
+    let first: Vec<u8> = (0..400i16).flat_map(|i| (i * 50).to_le_bytes()).collect();
+    let second: Vec<u8> = (0..600i16).flat_map(|i| (-i * 20).to_le_bytes()).collect();
+    let config = AudioConfig::new(16000, 2, 16);
+
+    for container in [WavContainer::Riff, WavContainer::Rf64] {
+        let wav = trans_pcm_bytes_to_wav(&first, Some(PcmToWavConfig::new(16000, 2, 16).with_container(container))).unwrap();
+        std::fs::write(output_path, wav).unwrap();
+
+        assert_eq!(append_pcm_to_wav(output_path, &second, &config).expect("追加应该成功"), 500);
+        // 大端数据转换为小端后追加
+        let big_endian: Vec<u8> = second.chunks(2).flat_map(|b| [b[1], b[0]]).collect();
+        let frames = append_pcm_to_wav(output_path, &big_endian, &config.clone().with_endianness(Endianness::Big)).unwrap();
+        assert_eq!(frames, 800);
+
+        // 严格模式检查各大小字段与文件长度一致
+        let audio = read_wav_file_with_mode(output_path, WavParseMode::Strict).expect("追加后应该是有效的 WAV");
+        assert_eq!(audio.data, [&first[..], &second[..], &second[..]].concat(), "{:?} 数据应该依次拼接", container);
+        println!("✓ {:?} 追加后共 {} 帧", container, audio.frames());
+    }
+
+    // 奇数长度的 8 位数据需要补齐填充字节
+    let wav = trans_pcm_bytes_to_wav(&[0x80, 0x90, 0xA0], Some(PcmToWavConfig::new(8000, 1, 8))).unwrap();
+    std::fs::write(output_path, wav).unwrap();
+    let u8_config = AudioConfig::new(8000, 1, 8);
+    append_pcm_to_wav(output_path, &[0xB0, 0xC0], &u8_config).unwrap();
+    append_pcm_to_wav(output_path, &[0xD0], &u8_config).unwrap();
+    let audio = read_wav_file_with_mode(output_path, WavParseMode::Strict).unwrap();
+    assert_eq!(audio.data, vec![0x80, 0x90, 0xA0, 0xB0, 0xC0, 0xD0]);
+    assert_eq!(std::fs::metadata(output_path).unwrap().len(), 44 + 6);
+
+    // 浮点格式同时更新 fact 块的帧数
+    let float_config = AudioConfig::new(8000, 1, 32).with_sample_format(SampleFormat::F32);
+    let wav = trans_pcm_bytes_to_wav(&0.5f32.to_le_bytes(), Some(PcmToWavConfig::new(8000, 1, 32).with_sample_format(SampleFormat::F32))).unwrap();
+    std::fs::write(output_path, wav).unwrap();
+    assert_eq!(append_pcm_to_wav(output_path, &[0.25f32.to_le_bytes(), (-0.25f32).to_le_bytes()].concat(), &float_config).unwrap(), 3);
+    let bytes = std::fs::read(output_path).unwrap();
+    let fact = bytes.windows(4).position(|w| w == b"fact").expect("应该有 fact 块");
+    assert_eq!(u32::from_le_bytes(bytes[fact + 8..fact + 12].try_into().unwrap()), 3);
+
+    // 格式不一致、不完整的帧和 data 块之后还有其他块时报错，文件保持不变
+    let wav = trans_pcm_bytes_to_wav(&first, Some(PcmToWavConfig::new(16000, 2, 16))).unwrap();
+    std::fs::write(output_path, &wav).unwrap();
+    assert!(append_pcm_to_wav(output_path, &second, &AudioConfig::new(44100, 2, 16)).is_err(), "采样率不同应该报错");
+    assert!(append_pcm_to_wav(output_path, &second, &AudioConfig::new(16000, 1, 16)).is_err(), "声道数不同应该报错");
+    assert!(append_pcm_to_wav(output_path, &second[..6], &config).is_err(), "不完整的帧应该报错");
+    let mut with_list = wav.clone();
+    with_list.extend_from_slice(b"LIST\x04\x00\x00\x00INFO");
+    let riff_size = (with_list.len() - 8) as u32;
+    with_list[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    std::fs::write(output_path, &with_list).unwrap();
+    let result = append_pcm_to_wav(output_path, &second, &config);
+    assert!(result.is_err(), "data 块之后有 LIST 块时应该报错");
+    println!("✓ 预期的错误: {}", result.unwrap_err());
+    assert_eq!(std::fs::read(output_path).unwrap(), with_list, "出错时文件应该保持不变");
+
+    assert!(append_pcm_to_wav("append_test_missing.wav", &second, &config).is_err());
+    let _ = std::fs::remove_file(output_path);
+}